@@ -0,0 +1,195 @@
+use crate::{
+    common::{random_password, strip_off_newlines},
+    SECRETS_DIR_FLAG, VALIDATOR_DIR_FLAG,
+};
+use clap::{App, Arg, ArgMatches};
+use eth2_keystore::{
+    default_kdf,
+    json_keystore::{Kdf, Pbkdf2, Prf},
+    DKLEN, SALT_SIZE,
+};
+use eth2_wallet::PlainText;
+use rand::Rng;
+use std::fs;
+use std::path::PathBuf;
+use validator_dir::{KeystoreKind, Manager as ValidatorManager};
+
+pub const CMD: &str = "change-password";
+pub const VALIDATOR_FLAG: &str = "validator";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const NEW_PASSWORD_FLAG: &str = "new-password";
+pub const KDF_FLAG: &str = "kdf";
+pub const KDF_COST_FLAG: &str = "kdf-cost";
+
+/// The iteration count used for PBKDF2 when `KDF_COST_FLAG` is not supplied, as per the EIP-2335
+/// test vectors.
+const DEFAULT_PBKDF2_C: u32 = 262_144;
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Decrypts the keystore of an existing validator and re-encrypts it with a new \
+            password, replacing the password file in --secrets-dir. The new keystore and \
+            password are written to temporary files before replacing the originals.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to the validator client data directory. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path where the validator keystore passwords are stored. \
+                    Defaults to ~/.lighthouse/secrets",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_FLAG)
+                .long(VALIDATOR_FLAG)
+                .value_name("VALIDATOR_NAME")
+                .help(
+                    "The name of the directory in --validator-dir for which to change the \
+                    password. Set to 'all' to change the password of all validators.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE")
+                .help("The keystore to re-encrypt.")
+                .takes_value(true)
+                .possible_values(&["voting", "withdrawal"])
+                .default_value("voting"),
+        )
+        .arg(
+            Arg::with_name(NEW_PASSWORD_FLAG)
+                .long(NEW_PASSWORD_FLAG)
+                .value_name("PASSWORD_PATH")
+                .help(
+                    "A path to a file containing the new password. If not supplied, a new \
+                    random password is generated for each keystore.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help("The key derivation function with which to re-encrypt the keystore.")
+                .takes_value(true)
+                .possible_values(&["scrypt", "pbkdf2"])
+                .default_value("scrypt"),
+        )
+        .arg(
+            Arg::with_name(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("COST")
+                .help(
+                    "The work factor of the key derivation function: `n` for scrypt (which must \
+                    be a power of two) or `c` for pbkdf2. Defaults to 262144 for both.",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let secrets_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRETS_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("secrets"),
+    )?;
+    let validator: String = clap_utils::parse_required(matches, VALIDATOR_FLAG)?;
+    let kind = match matches.value_of(KEYSTORE_FLAG) {
+        Some("withdrawal") => KeystoreKind::Withdrawal,
+        _ => KeystoreKind::Voting,
+    };
+    let new_password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, NEW_PASSWORD_FLAG)?;
+    let kdf_name: String = clap_utils::parse_required(matches, KDF_FLAG)?;
+    let kdf_cost: Option<u32> = clap_utils::parse_optional(matches, KDF_COST_FLAG)?;
+
+    let new_password = new_password_path
+        .map(|path| {
+            fs::read(&path)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))
+                .map(|bytes| PlainText::from(strip_off_newlines(bytes)))
+        })
+        .transpose()?;
+
+    let manager = ValidatorManager::open(&validator_dir)
+        .map_err(|e| format!("Unable to read --{}: {:?}", VALIDATOR_DIR_FLAG, e))?;
+
+    let validators = match validator.as_ref() {
+        "all" => manager
+            .open_all_validators()
+            .map_err(|e| format!("Unable to read all validators: {:?}", e)),
+        name => {
+            let path = manager
+                .directory_names()
+                .map_err(|e| {
+                    format!(
+                        "Unable to read --{} directory names: {:?}",
+                        VALIDATOR_DIR_FLAG, e
+                    )
+                })?
+                .get(name)
+                .ok_or_else(|| format!("Unknown validator:  {}", name))?
+                .clone();
+
+            manager
+                .open_validator(&path)
+                .map_err(|e| format!("Unable to open {}: {:?}", name, e))
+                .map(|v| vec![v])
+        }
+    }?;
+
+    for mut dir in validators {
+        let password = new_password.clone().unwrap_or_else(random_password);
+        let kdf = new_kdf(&kdf_name, kdf_cost)?;
+
+        dir.change_password(kind, &secrets_dir, password.as_bytes(), Some(kdf))
+            .map_err(|e| format!("Unable to change password for {:?}: {:?}", dir.dir(), e))?;
+
+        println!("Changed password for {:?}", dir.dir());
+    }
+
+    Ok(())
+}
+
+/// Returns the `Kdf` identified by `name`, with a new random salt and the given `cost`.
+fn new_kdf(name: &str, cost: Option<u32>) -> Result<Kdf, String> {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+
+    match name {
+        "scrypt" => {
+            let mut kdf = default_kdf(salt);
+            if let (Kdf::Scrypt(params), Some(n)) = (&mut kdf, cost) {
+                params.n = n;
+            }
+            Ok(kdf)
+        }
+        "pbkdf2" => Ok(Kdf::Pbkdf2(Pbkdf2 {
+            dklen: DKLEN,
+            c: cost.unwrap_or(DEFAULT_PBKDF2_C),
+            prf: Prf::HmacSha256,
+            salt: salt.into(),
+        })),
+        other => Err(format!("Unknown --{}: {}", KDF_FLAG, other)),
+    }
+}
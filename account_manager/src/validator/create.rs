@@ -9,13 +9,14 @@ use eth2_wallet_manager::WalletManager;
 use std::fs;
 use std::path::{Path, PathBuf};
 use types::EthSpec;
-use validator_dir::Builder as ValidatorDirBuilder;
+use validator_dir::{AuditLog, Builder as ValidatorDirBuilder, Interface, KeyEvent};
 
 pub const CMD: &str = "create";
 pub const BASE_DIR_FLAG: &str = "base-dir";
 pub const WALLET_NAME_FLAG: &str = "wallet-name";
 pub const WALLET_PASSPHRASE_FLAG: &str = "wallet-passphrase";
 pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
+pub const DEPOSIT_FORK_VERSION_FLAG: &str = "deposit-fork-version";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
@@ -72,6 +73,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(DEPOSIT_FORK_VERSION_FLAG)
+                .long(DEPOSIT_FORK_VERSION_FLAG)
+                .value_name("FORK_VERSION")
+                .help(
+                    "Sign the deposits for the network with this 0x-prefixed, 4 byte genesis fork \
+                    version, instead of that of --testnet-dir. DANGEROUS: a deposit signed with \
+                    the wrong fork version is ignored by the beacon chain and its ether is lost.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(STORE_WITHDRAW_FLAG)
                 .long(STORE_WITHDRAW_FLAG)
@@ -124,6 +136,8 @@ pub fn cli_run<T: EthSpec>(
     )?;
     let deposit_gwei = clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
         .unwrap_or_else(|| spec.max_effective_balance);
+    let deposit_fork_version: Option<[u8; 4]> =
+        clap_utils::parse_ssz_optional(matches, DEPOSIT_FORK_VERSION_FLAG)?;
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
 
@@ -153,6 +167,20 @@ pub fn cli_run<T: EthSpec>(
         return Ok(());
     }
 
+    if let Some(fork_version) = deposit_fork_version {
+        if fork_version != spec.genesis_fork_version {
+            eprintln!(
+                "WARNING: signing deposits with genesis fork version 0x{}, which differs from \
+                the 0x{} of the configured network. These deposits are only valid on the network \
+                with fork version 0x{}. Submitting them to any other network will lose the \
+                deposited ether.",
+                hex::encode(fork_version),
+                hex::encode(spec.genesis_fork_version),
+                hex::encode(fork_version),
+            );
+        }
+    }
+
     let wallet_password = fs::read(&wallet_password_path)
         .map_err(|e| format!("Unable to read {:?}: {:?}", wallet_password_path, e))
         .map(|bytes| PlainText::from(strip_off_newlines(bytes)))?;
@@ -164,6 +192,8 @@ pub fn cli_run<T: EthSpec>(
         .wallet_by_name(&name)
         .map_err(|e| format!("Unable to open wallet: {:?}", e))?;
 
+    let audit_log = AuditLog::new(&validator_dir);
+
     for i in 0..n {
         let voting_password = random_password();
         let withdrawal_password = random_password();
@@ -178,14 +208,31 @@ pub fn cli_run<T: EthSpec>(
 
         let voting_pubkey = keystores.voting.pubkey().to_string();
 
-        ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
+        let builder = ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
             .create_eth1_tx_data(deposit_gwei, &spec)
-            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG))
+            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG));
+
+        let builder = if let Some(fork_version) = deposit_fork_version {
+            builder.deposit_fork_version(fork_version)
+        } else {
+            builder
+        };
+
+        builder
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
+        audit_log
+            .record(
+                KeyEvent::Create,
+                Interface::Cli,
+                format!("0x{}", voting_pubkey),
+                Some(format!("wallet: {}", name)),
+            )
+            .map_err(|e| format!("Unable to write {:?}: {:?}", audit_log.path(), e))?;
+
         println!("{}/{}\t0x{}", i + 1, n, voting_pubkey);
     }
 
@@ -1,17 +1,20 @@
 use crate::VALIDATOR_DIR_FLAG;
 use clap::{App, Arg, ArgMatches};
 use clap_utils;
-use deposit_contract::DEPOSIT_GAS;
+use deposit_contract::{batch_deposit_gas, encode_batch_eth1_tx_data, DEPOSIT_GAS};
 use environment::Environment;
 use futures::compat::Future01CompatExt;
+use serde::Serialize;
 use slog::{info, Logger};
+use std::fs;
 use std::path::PathBuf;
+use tiny_keccak::{Hasher, Keccak};
 use tokio::time::{delay_until, Duration, Instant};
 use types::EthSpec;
 use validator_dir::Manager as ValidatorManager;
 use web3::{
     transports::Ipc,
-    types::{Address, SyncInfo, SyncState, TransactionRequest, U256},
+    types::{Address, SyncInfo, SyncState, TransactionRequest, H256, U256},
     Transport, Web3,
 };
 
@@ -19,6 +22,18 @@ pub const CMD: &str = "deposit";
 pub const VALIDATOR_FLAG: &str = "validator";
 pub const ETH1_IPC_FLAG: &str = "eth1-ipc";
 pub const FROM_ADDRESS_FLAG: &str = "from-address";
+pub const UNSIGNED_TX_FLAG: &str = "unsigned-tx";
+pub const CHAIN_ID_FLAG: &str = "chain-id";
+pub const BATCH_CONTRACT_FLAG: &str = "batch-contract";
+pub const BATCH_CONTRACT_CODE_HASH_FLAG: &str = "batch-contract-code-hash";
+
+/// The file in each validator directory where the unsigned deposit transaction is saved when
+/// using `UNSIGNED_TX_FLAG`.
+pub const ETH1_DEPOSIT_TX_JSON_FILE: &str = "eth1-deposit-tx.json";
+
+/// The file in the --validator-dir where the unsigned batch deposit transaction is saved when
+/// using `UNSIGNED_TX_FLAG` with `BATCH_CONTRACT_FLAG`.
+pub const ETH1_BATCH_DEPOSIT_TX_JSON_FILE: &str = "eth1-batch-deposit-tx.json";
 
 const GWEI: u64 = 1_000_000_000;
 
@@ -35,7 +50,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             The application does not wait for confirmations so there is not guarantee that \
             the transaction is included in the Eth1 chain; use a block explorer and the \
             transaction hash to check for confirmations. The deposit contract address will \
-            be determined by the --testnet-dir flag on the primary Lighthouse binary.",
+            be determined by the --network or --testnet-dir flag on the primary Lighthouse \
+            binary.",
         )
         .arg(
             Arg::with_name(VALIDATOR_DIR_FLAG)
@@ -62,9 +78,12 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name(ETH1_IPC_FLAG)
                 .long(ETH1_IPC_FLAG)
                 .value_name("ETH1_IPC_PATH")
-                .help("Path to an Eth1 JSON-RPC IPC endpoint")
+                .help(
+                    "Path to an Eth1 JSON-RPC IPC endpoint. With --unsigned-tx, it is only \
+                    used to read the chain id and to verify the --batch-contract.",
+                )
                 .takes_value(true)
-                .required(true),
+                .required_unless(UNSIGNED_TX_FLAG),
         )
         .arg(
             Arg::with_name(FROM_ADDRESS_FLAG)
@@ -75,10 +94,103 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     Must be unlocked on the node at --eth1-ipc.",
                 )
                 .takes_value(true)
-                .required(true),
+                .required_unless(UNSIGNED_TX_FLAG),
+        )
+        .arg(
+            Arg::with_name(UNSIGNED_TX_FLAG)
+                .long(UNSIGNED_TX_FLAG)
+                .help(
+                    "Instead of submitting the deposits via --eth1-ipc, save an unsigned \
+                    transaction for each deposit to eth1-deposit-tx.json in its validator \
+                    directory. The transaction contains the to, data, value, gas and chainId \
+                    fields and may be signed and sent with a wallet such as MetaMask or a \
+                    hardware wallet.",
+                )
+                .conflicts_with(FROM_ADDRESS_FLAG),
+        )
+        .arg(
+            Arg::with_name(CHAIN_ID_FLAG)
+                .long(CHAIN_ID_FLAG)
+                .value_name("CHAIN_ID")
+                .help(
+                    "The chain id of the Eth1 network, included in the transactions saved with \
+                    --unsigned-tx. Defaults to the chain id of the node at --eth1-ipc and is \
+                    required without it.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BATCH_CONTRACT_FLAG)
+                .long(BATCH_CONTRACT_FLAG)
+                .value_name("BATCH_CONTRACT_ADDRESS")
+                .help(
+                    "Submit all of the deposits in a single transaction to the batch deposit \
+                    contract at this address, instead of one transaction per deposit to the \
+                    deposit contract. With --unsigned-tx, the transaction is saved to \
+                    eth1-batch-deposit-tx.json in --validator-dir. All deposits must be of the \
+                    same amount.",
+                )
+                .takes_value(true)
+                .requires_all(&[BATCH_CONTRACT_CODE_HASH_FLAG, ETH1_IPC_FLAG]),
+        )
+        .arg(
+            Arg::with_name(BATCH_CONTRACT_CODE_HASH_FLAG)
+                .long(BATCH_CONTRACT_CODE_HASH_FLAG)
+                .value_name("KECCAK256_HASH")
+                .help(
+                    "The keccak256 hash of the deployed bytecode of the --batch-contract. Since \
+                    the batch contract receives the value of every deposit, the code at its \
+                    address is fetched via --eth1-ipc and must match this hash before any \
+                    transaction is sent or saved.",
+                )
+                .takes_value(true)
+                .requires(BATCH_CONTRACT_FLAG),
         )
 }
 
+/// An Eth1 deposit transaction which has not been signed, in the JSON format accepted by common
+/// wallets.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedDepositTransaction {
+    /// The deposit contract address.
+    pub to: String,
+    /// The `0x`-prefixed deposit contract call data.
+    pub data: String,
+    /// The `0x`-prefixed hex value of the deposit, in wei.
+    pub value: String,
+    /// The `0x`-prefixed hex gas limit of the transaction.
+    pub gas: String,
+    /// The chain id of the Eth1 network.
+    pub chain_id: u64,
+}
+
+impl UnsignedDepositTransaction {
+    pub fn new(deposit_contract: Address, data: &[u8], amount_gwei: u64, chain_id: u64) -> Self {
+        Self {
+            to: format!("{:?}", deposit_contract),
+            data: format!("0x{}", hex::encode(data)),
+            value: format!("{:#x}", from_gwei(amount_gwei)),
+            gas: format!("{:#x}", DEPOSIT_GAS),
+            chain_id,
+        }
+    }
+
+    /// Returns a transaction which submits `deposit_count` deposits to a batch deposit contract.
+    pub fn new_batch(
+        batch_contract: Address,
+        data: &[u8],
+        total_gwei: u64,
+        deposit_count: usize,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            gas: format!("{:#x}", batch_deposit_gas(deposit_count)),
+            ..Self::new(batch_contract, data, total_gwei, chain_id)
+        }
+    }
+}
+
 pub fn cli_run<T: EthSpec>(
     matches: &ArgMatches<'_>,
     mut env: Environment<T>,
@@ -91,8 +203,6 @@ pub fn cli_run<T: EthSpec>(
         PathBuf::new().join(".lighthouse").join("validators"),
     )?;
     let validator: String = clap_utils::parse_required(matches, VALIDATOR_FLAG)?;
-    let eth1_ipc_path: PathBuf = clap_utils::parse_required(matches, ETH1_IPC_FLAG)?;
-    let from_address: Address = clap_utils::parse_required(matches, FROM_ADDRESS_FLAG)?;
 
     let manager = ValidatorManager::open(&data_dir)
         .map_err(|e| format!("Unable to read --{}: {:?}", VALIDATOR_DIR_FLAG, e))?;
@@ -167,13 +277,150 @@ pub fn cli_run<T: EthSpec>(
         return Err("Refusing to deposit to the zero address. Check testnet configuration.".into());
     }
 
-    let (_event_loop_handle, transport) =
-        Ipc::new(eth1_ipc_path).map_err(|e| format!("Unable to connect to eth1 IPC: {:?}", e))?;
-    let web3 = Web3::new(transport);
+    let eth1_ipc_path: Option<PathBuf> = clap_utils::parse_optional(matches, ETH1_IPC_FLAG)?;
+    let eth1 = eth1_ipc_path
+        .map(|path| {
+            Ipc::new(path)
+                .map(|(event_loop_handle, transport)| (event_loop_handle, Web3::new(transport)))
+                .map_err(|e| format!("Unable to connect to eth1 IPC: {:?}", e))
+        })
+        .transpose()?;
+
+    let batch_contract: Option<Address> = clap_utils::parse_optional(matches, BATCH_CONTRACT_FLAG)?;
+    let batch = if let Some(batch_contract) = batch_contract {
+        let code_hash: H256 = clap_utils::parse_required(matches, BATCH_CONTRACT_CODE_HASH_FLAG)?;
+        let web3 = eth1
+            .as_ref()
+            .map(|(_, web3)| web3.clone())
+            .ok_or_else(|| format!("--{} requires --{}", BATCH_CONTRACT_FLAG, ETH1_IPC_FLAG))?;
+        env.runtime()
+            .block_on(verify_batch_contract(web3, batch_contract, code_hash))?;
+        info!(
+            log,
+            "Verified batch deposit contract";
+            "address" => format!("{:?}", batch_contract),
+            "code_hash" => format!("{:?}", code_hash),
+        );
+
+        let amount = eth1_deposit_datas[0].1.deposit_data.amount;
+        if eth1_deposit_datas
+            .iter()
+            .any(|(_, d)| d.deposit_data.amount != amount)
+        {
+            return Err(format!(
+                "All deposits must be of the same amount to use --{}",
+                BATCH_CONTRACT_FLAG
+            ));
+        }
+
+        let deposit_datas = eth1_deposit_datas
+            .iter()
+            .map(|(_, d)| d.deposit_data.clone())
+            .collect::<Vec<_>>();
+        let data = encode_batch_eth1_tx_data(&deposit_datas)
+            .map_err(|e| format!("Unable to encode batch deposit: {:?}", e))?;
+
+        Some((batch_contract, data))
+    } else {
+        None
+    };
+
+    if matches.is_present(UNSIGNED_TX_FLAG) {
+        let chain_id_override: Option<u64> = clap_utils::parse_optional(matches, CHAIN_ID_FLAG)?;
+        let chain_id = match (chain_id_override, &eth1) {
+            (Some(chain_id), _) => chain_id,
+            (None, Some((_, web3))) => env.runtime().block_on(eth1_chain_id(web3.clone()))?,
+            (None, None) => {
+                return Err(format!(
+                    "--{} requires either --{} or --{}",
+                    UNSIGNED_TX_FLAG, CHAIN_ID_FLAG, ETH1_IPC_FLAG
+                ))
+            }
+        };
+
+        if let Some((batch_contract, data)) = batch {
+            let tx = UnsignedDepositTransaction::new_batch(
+                batch_contract,
+                &data,
+                total_gwei,
+                eth1_deposit_datas.len(),
+                chain_id,
+            );
+            let json = serde_json::to_string_pretty(&tx)
+                .map_err(|e| format!("Unable to encode transaction as JSON: {:?}", e))?;
+
+            let path = data_dir.join(ETH1_BATCH_DEPOSIT_TX_JSON_FILE);
+            fs::write(&path, json)
+                .map_err(|e| format!("Unable to write transaction to {:?}: {:?}", path, e))?;
+
+            info!(
+                log,
+                "Saved unsigned batch deposit transaction";
+                "path" => format!("{:?}", path),
+            );
+
+            return Ok(());
+        }
+
+        for (validator_dir, eth1_deposit_data) in eth1_deposit_datas {
+            let tx = UnsignedDepositTransaction::new(
+                deposit_contract,
+                &eth1_deposit_data.rlp,
+                eth1_deposit_data.deposit_data.amount,
+                chain_id,
+            );
+            let json = serde_json::to_string_pretty(&tx)
+                .map_err(|e| format!("Unable to encode transaction as JSON: {:?}", e))?;
+
+            let path = validator_dir.dir().join(ETH1_DEPOSIT_TX_JSON_FILE);
+            fs::write(&path, json)
+                .map_err(|e| format!("Unable to write transaction to {:?}: {:?}", path, e))?;
+
+            info!(
+                log,
+                "Saved unsigned deposit transaction";
+                "path" => format!("{:?}", path),
+            );
+        }
+
+        return Ok(());
+    }
+
+    let from_address: Address = clap_utils::parse_required(matches, FROM_ADDRESS_FLAG)?;
+    let (_event_loop_handle, web3) =
+        eth1.ok_or_else(|| format!("--{} is required", ETH1_IPC_FLAG))?;
 
     let deposits_fut = async {
         poll_until_synced(web3.clone(), log.clone()).await?;
 
+        if let Some((batch_contract, data)) = batch {
+            let tx_hash = web3
+                .eth()
+                .send_transaction(TransactionRequest {
+                    from: from_address,
+                    to: Some(batch_contract),
+                    gas: Some(batch_deposit_gas(eth1_deposit_datas.len()).into()),
+                    gas_price: None,
+                    value: Some(from_gwei(total_gwei)),
+                    data: Some(data.into()),
+                    nonce: None,
+                    condition: None,
+                })
+                .compat()
+                .await
+                .map_err(|e| format!("Failed to send transaction: {:?}", e))?;
+
+            for (mut validator_dir, _) in eth1_deposit_datas {
+                validator_dir
+                    .save_eth1_deposit_tx_hash(&format!("{:?}", tx_hash))
+                    .map_err(|e| {
+                        format!("Failed to save tx hash {:?} to disk: {:?}", tx_hash, e)
+                    })?;
+            }
+
+            return Ok(());
+        }
+
         for (mut validator_dir, eth1_deposit_data) in eth1_deposit_datas {
             let tx_hash = web3
                 .eth()
@@ -204,6 +451,70 @@ pub fn cli_run<T: EthSpec>(
     Ok(())
 }
 
+/// Returns an error unless the code deployed at `batch_contract` has the keccak256 `code_hash`.
+async fn verify_batch_contract<T>(
+    web3: Web3<T>,
+    batch_contract: Address,
+    code_hash: H256,
+) -> Result<(), String>
+where
+    T: Transport + Send + 'static,
+    <T as Transport>::Out: Send,
+{
+    let code = web3
+        .eth()
+        .code(batch_contract, None)
+        .compat()
+        .await
+        .map_err(|e| format!("Unable to read batch contract code from eth1 node: {:?}", e))?;
+
+    if code.0.is_empty() {
+        return Err(format!(
+            "Refusing to deposit: there is no contract at --{} {:?}",
+            BATCH_CONTRACT_FLAG, batch_contract
+        ));
+    }
+
+    let actual_hash = keccak256(&code.0);
+    if actual_hash != code_hash {
+        return Err(format!(
+            "Refusing to deposit: the code at --{} {:?} has hash {:?}, not the expected {:?}",
+            BATCH_CONTRACT_FLAG, batch_contract, actual_hash, code_hash
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the chain id reported by the `eth_chainId` endpoint.
+async fn eth1_chain_id<T>(web3: Web3<T>) -> Result<u64, String>
+where
+    T: Transport + Send + 'static,
+    <T as Transport>::Out: Send,
+{
+    let response = web3
+        .transport()
+        .execute("eth_chainId", vec![])
+        .compat()
+        .await
+        .map_err(|e| format!("Unable to read chain id from eth1 node: {:?}", e))?;
+    let hex = response
+        .as_str()
+        .ok_or_else(|| format!("Invalid eth_chainId response: {:?}", response))?;
+
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Unable to parse eth_chainId response {}: {:?}", hex, e))
+}
+
+/// Returns the keccak256 hash of `bytes`.
+fn keccak256(bytes: &[u8]) -> H256 {
+    let mut hasher = Keccak::v256();
+    let mut output = [0; 32];
+    hasher.update(bytes);
+    hasher.finalize(&mut output);
+    H256::from(output)
+}
+
 /// Converts gwei to wei.
 fn from_gwei(gwei: u64) -> U256 {
     U256::from(gwei) * U256::exp10(9)
@@ -269,3 +580,37 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unsigned_deposit_transaction() {
+        let tx = UnsignedDepositTransaction::new(
+            Address::repeat_byte(0x42),
+            &[0xde, 0xad],
+            32_000_000_000,
+            5,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&tx).unwrap(),
+            serde_json::json!({
+                "to": "0x4242424242424242424242424242424242424242",
+                "data": "0xdead",
+                "value": "0x1bc16d674ec800000",
+                "gas": "0x61a80",
+                "chainId": 5,
+            })
+        );
+    }
+
+    #[test]
+    fn keccak256_empty_code() {
+        assert_eq!(
+            format!("{:?}", keccak256(&[])),
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+}
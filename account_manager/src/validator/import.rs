@@ -0,0 +1,319 @@
+//! Imports EIP-2335 voting keystores from the directory layouts used by other clients, converting
+//! each into a Lighthouse validator directory (see `validator_dir`).
+//!
+//! The supported layouts are:
+//!
+//! - `nimbus`: `<source-dir>/0x<pubkey>/keystore.json`, with the password in
+//!   `<source-secrets-dir>/0x<pubkey>`.
+//! - `teku`: `<source-dir>/<name>.json`, with the password in
+//!   `<source-secrets-dir>/<name>.txt`.
+//! - `prysm`: `<source-dir>/keystore-*.json`, as produced by the `eth2.0-deposit-cli` and imported
+//!   by Prysm, all unlocked with the single password in `--password-file`. Prysm's own
+//!   `all-accounts.keystore.json` is not an EIP-2335 keystore and cannot be imported.
+//!
+//! Slashing protection history is **not** imported, since there is no common format in which it
+//! may be exported. Before starting the validator client, ensure the other client has stopped and
+//! wait until its last signed epoch has passed.
+
+use crate::{
+    common::{ensure_dir_exists, strip_off_newlines},
+    SECRETS_DIR_FLAG, VALIDATOR_DIR_FLAG,
+};
+use clap::{App, Arg, ArgMatches};
+use eth2_keystore::{Keystore, PlainText};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use validator_dir::{AuditLog, Builder as ValidatorDirBuilder, Interface, KeyEvent};
+
+pub const CMD: &str = "import";
+pub const LAYOUT_FLAG: &str = "layout";
+pub const SOURCE_DIR_FLAG: &str = "source-dir";
+pub const SOURCE_SECRETS_DIR_FLAG: &str = "source-secrets-dir";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+
+/// The name of the keystore file in each validator directory of the `nimbus` layout.
+const NIMBUS_KEYSTORE_FILE: &str = "keystore.json";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Imports EIP-2335 voting keystores from the directory layout of another client, \
+            creating a validator directory for each. Slashing protection history is not \
+            imported; ensure the other client has stopped and its last signed epoch has \
+            passed before starting the validator client.",
+        )
+        .arg(
+            Arg::with_name(LAYOUT_FLAG)
+                .long(LAYOUT_FLAG)
+                .value_name("LAYOUT")
+                .help("The directory layout of the keystores to import.")
+                .takes_value(true)
+                .possible_values(&["nimbus", "teku", "prysm"])
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(SOURCE_DIR_FLAG)
+                .long(SOURCE_DIR_FLAG)
+                .value_name("SOURCE_DIRECTORY")
+                .help("The directory containing the keystores to import.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(SOURCE_SECRETS_DIR_FLAG)
+                .long(SOURCE_SECRETS_DIR_FLAG)
+                .value_name("SOURCE_SECRETS_DIRECTORY")
+                .help(
+                    "The directory containing the keystore passwords. Required for the nimbus \
+                    and teku layouts.",
+                )
+                .takes_value(true)
+                .required_ifs(&[(LAYOUT_FLAG, "nimbus"), (LAYOUT_FLAG, "teku")]),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_PATH")
+                .help(
+                    "A path to a file containing the password to all of the keystores. \
+                    Required for the prysm layout.",
+                )
+                .takes_value(true)
+                .required_if(LAYOUT_FLAG, "prysm"),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path where the validator directories will be created. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path where the validator keystore passwords will be stored. \
+                    Defaults to ~/.lighthouse/secrets",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let layout: String = clap_utils::parse_required(matches, LAYOUT_FLAG)?;
+    let source_dir: PathBuf = clap_utils::parse_required(matches, SOURCE_DIR_FLAG)?;
+    let source_secrets_dir: Option<PathBuf> =
+        clap_utils::parse_optional(matches, SOURCE_SECRETS_DIR_FLAG)?;
+    let password_file: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let secrets_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRETS_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("secrets"),
+    )?;
+
+    let required = |opt: Option<PathBuf>, flag: &str| {
+        opt.ok_or_else(|| format!("--{} is required for the {} layout", flag, layout))
+    };
+
+    let keystores = match layout.as_str() {
+        "nimbus" => nimbus_keystores(
+            &source_dir,
+            &required(source_secrets_dir, SOURCE_SECRETS_DIR_FLAG)?,
+        ),
+        "teku" => teku_keystores(
+            &source_dir,
+            &required(source_secrets_dir, SOURCE_SECRETS_DIR_FLAG)?,
+        ),
+        "prysm" => prysm_keystores(
+            &source_dir,
+            &read_password(&required(password_file, PASSWORD_FILE_FLAG)?)?,
+        ),
+        other => Err(format!("Unknown --{}: {}", LAYOUT_FLAG, other)),
+    }?;
+
+    if keystores.is_empty() {
+        return Err(format!("No keystores found in {:?}", source_dir));
+    }
+
+    // Check every password before creating any validator directories, so a mistake does not leave
+    // a partial import.
+    let keystores = keystores
+        .into_iter()
+        .map(|(path, password)| {
+            let keystore = File::open(&path)
+                .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+                .and_then(|file| {
+                    Keystore::from_json_reader(file)
+                        .map_err(|e| format!("Unable to parse keystore {:?}: {:?}", path, e))
+                })?;
+            keystore
+                .decrypt_keypair(password.as_bytes())
+                .map_err(|e| format!("Unable to decrypt keystore {:?}: {:?}", path, e))?;
+            Ok((keystore, password))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    ensure_dir_exists(&validator_dir)?;
+    ensure_dir_exists(&secrets_dir)?;
+
+    let audit_log = AuditLog::new(&validator_dir);
+
+    let n = keystores.len();
+    for (i, (keystore, password)) in keystores.into_iter().enumerate() {
+        let voting_pubkey = keystore.pubkey().to_string();
+
+        ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
+            .voting_keystore(keystore, password.as_bytes())
+            .store_withdrawal_keystore(false)
+            .build()
+            .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
+
+        audit_log
+            .record(
+                KeyEvent::Import,
+                Interface::Cli,
+                format!("0x{}", voting_pubkey),
+                Some(format!("{} layout from {:?}", layout, source_dir)),
+            )
+            .map_err(|e| format!("Unable to write {:?}: {:?}", audit_log.path(), e))?;
+
+        println!("{}/{}\t0x{}", i + 1, n, voting_pubkey);
+    }
+
+    Ok(())
+}
+
+/// Finds keystores in the `nimbus` layout, returning each keystore path with its password.
+fn nimbus_keystores(
+    source_dir: &Path,
+    secrets_dir: &Path,
+) -> Result<Vec<(PathBuf, PlainText)>, String> {
+    read_dir_sorted(source_dir)?
+        .into_iter()
+        .filter(|path| path.join(NIMBUS_KEYSTORE_FILE).is_file())
+        .map(|path| {
+            let name = file_name(&path)?;
+            Ok((
+                path.join(NIMBUS_KEYSTORE_FILE),
+                read_password(&secrets_dir.join(name))?,
+            ))
+        })
+        .collect()
+}
+
+/// Finds keystores in the `teku` layout, returning each keystore path with its password.
+fn teku_keystores(
+    source_dir: &Path,
+    passwords_dir: &Path,
+) -> Result<Vec<(PathBuf, PlainText)>, String> {
+    read_dir_sorted(source_dir)?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "json"))
+        .map(|path| {
+            let password_path = passwords_dir.join(
+                path.with_extension("txt")
+                    .file_name()
+                    .ok_or_else(|| format!("Invalid keystore path {:?}", path))?,
+            );
+            Ok((path, read_password(&password_path)?))
+        })
+        .collect()
+}
+
+/// Finds keystores in the `prysm` layout, returning each keystore path with `password`.
+fn prysm_keystores(
+    source_dir: &Path,
+    password: &PlainText,
+) -> Result<Vec<(PathBuf, PlainText)>, String> {
+    read_dir_sorted(source_dir)?
+        .into_iter()
+        .filter(|path| {
+            path.is_file()
+                && file_name(path).map_or(false, |name| {
+                    name.starts_with("keystore-") && name.ends_with(".json")
+                })
+        })
+        .map(|path| Ok((path, password.clone())))
+        .collect()
+}
+
+/// Returns the paths in `dir`, sorted so that imports happen in a predictable order.
+fn read_dir_sorted(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn file_name(path: &Path) -> Result<&str, String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid file name {:?}", path))
+}
+
+fn read_password(path: &Path) -> Result<PlainText, String> {
+    fs::read(path)
+        .map_err(|e| format!("Unable to read password {:?}: {:?}", path, e))
+        .map(|bytes| PlainText::from(strip_off_newlines(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn finds_keystores_in_each_layout() {
+        let dir = TempDir::new("import").unwrap();
+        let keys = dir.path().join("keys");
+        let secrets = dir.path().join("secrets");
+        fs::create_dir_all(keys.join("0xaa")).unwrap();
+        fs::create_dir_all(keys.join("0xbb")).unwrap();
+        fs::create_dir_all(&secrets).unwrap();
+
+        // Nimbus: only directories containing a keystore are considered.
+        fs::write(keys.join("0xaa").join(NIMBUS_KEYSTORE_FILE), "{}").unwrap();
+        fs::write(secrets.join("0xaa"), "nimbus\n").unwrap();
+        let found = nimbus_keystores(&keys, &secrets).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, keys.join("0xaa").join(NIMBUS_KEYSTORE_FILE));
+        assert_eq!(found[0].1.as_bytes(), b"nimbus");
+
+        // Teku: each JSON file has a password file of the same name.
+        fs::write(keys.join("keystore-0.json"), "{}").unwrap();
+        fs::write(secrets.join("keystore-0.txt"), "teku").unwrap();
+        let found = teku_keystores(&keys, &secrets).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.as_bytes(), b"teku");
+
+        // A missing password is an error.
+        fs::write(keys.join("keystore-1.json"), "{}").unwrap();
+        assert!(teku_keystores(&keys, &secrets).is_err());
+
+        // Prysm: all `keystore-*.json` files share a password.
+        fs::write(keys.join("deposit_data.json"), "[]").unwrap();
+        let found = prysm_keystores(&keys, &b"prysm".to_vec().into()).unwrap();
+        assert_eq!(
+            found
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            vec![keys.join("keystore-0.json"), keys.join("keystore-1.json")]
+        );
+    }
+}
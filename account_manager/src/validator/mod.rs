@@ -1,5 +1,8 @@
+pub mod change_password;
 pub mod create;
 pub mod deposit;
+pub mod import;
+pub mod recover;
 
 use crate::common::base_wallet_dir;
 use clap::{App, Arg, ArgMatches};
@@ -20,6 +23,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         )
         .subcommand(create::cli_app())
         .subcommand(deposit::cli_app())
+        .subcommand(change_password::cli_app())
+        .subcommand(import::cli_app())
+        .subcommand(recover::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -28,6 +34,9 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
     match matches.subcommand() {
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, base_wallet_dir),
         (deposit::CMD, Some(matches)) => deposit::cli_run::<T>(matches, env),
+        (change_password::CMD, Some(matches)) => change_password::cli_run(matches),
+        (import::CMD, Some(matches)) => import::cli_run(matches),
+        (recover::CMD, Some(matches)) => recover::cli_run::<T>(matches, env),
         (unknown, _) => {
             return Err(format!(
                 "{} does not have a {} command. See --help",
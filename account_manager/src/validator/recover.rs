@@ -0,0 +1,342 @@
+//! Re-derives the keys of existing validators from the BIP-39 mnemonic of the wallet which created
+//! them, rebuilding a lost or damaged validators directory without submitting new deposits.
+//!
+//! Validators whose directory already exists are skipped, so the command may be run repeatedly
+//! (e.g., after an interruption) without creating duplicates or overwriting existing keystores.
+
+use crate::{
+    common::{ensure_dir_exists, random_password},
+    SECRETS_DIR_FLAG, VALIDATOR_DIR_FLAG,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_keystore::{keypair_from_secret, KeystoreBuilder};
+use eth2_wallet::{
+    bip39::{Language, Mnemonic, Seed as Bip39Seed},
+    recover_validator_secret_from_seed, KeyType,
+};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use types::{ChainSpec, EthSpec, Keypair};
+use validator_dir::{AuditLog, Builder as ValidatorDirBuilder, Interface, KeyEvent};
+
+pub const CMD: &str = "recover";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const COUNT_FLAG: &str = "count";
+pub const DEPOSIT_DATA_FLAG: &str = "create-deposit-data";
+pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
+pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Recovers validators from the BIP-39 mnemonic of the wallet which created them, \
+            using the EIP-2333 HD key derivation scheme. Validators already present in \
+            --validator-dir are skipped.",
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("A path to a file containing the BIP-39 mnemonic of the wallet.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The wallet index of the first validator to recover.")
+                .takes_value(true)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("COUNT")
+                .help(
+                    "The number of validators to recover, i.e., those with wallet indices from \
+                    --first-index up to but excluding --first-index + --count.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path where the validator directories will be created. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path where the validator keystore passwords will be stored. \
+                    Defaults to ~/.lighthouse/secrets",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_DATA_FLAG)
+                .long(DEPOSIT_DATA_FLAG)
+                .help(
+                    "If present, the eth1 deposit data of each validator is also re-created. \
+                    Only needed if a deposit has not yet been submitted, since re-submitting \
+                    the deposit of an existing validator only adds to its balance.",
+                ),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_GWEI_FLAG)
+                .long(DEPOSIT_GWEI_FLAG)
+                .value_name("DEPOSIT_GWEI")
+                .help(
+                    "The GWEI value of the re-created deposits. Defaults to the minimum amount \
+                    required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                )
+                .takes_value(true)
+                .requires(DEPOSIT_DATA_FLAG),
+        )
+        .arg(
+            Arg::with_name(STORE_WITHDRAW_FLAG)
+                .long(STORE_WITHDRAW_FLAG)
+                .help(
+                    "If present, the withdrawal keystore will be stored alongside the voting \
+                    keypair. It is generally recommended to *not* store the withdrawal key and \
+                    instead generate them from the wallet seed when required.",
+                ),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
+
+    let mnemonic_path: PathBuf = clap_utils::parse_required(matches, MNEMONIC_FLAG)?;
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let secrets_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRETS_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("secrets"),
+    )?;
+    let store_withdrawal_keystore = matches.is_present(STORE_WITHDRAW_FLAG);
+    let deposit_gwei = if matches.is_present(DEPOSIT_DATA_FLAG) {
+        Some(
+            clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
+                .unwrap_or_else(|| spec.max_effective_balance),
+        )
+    } else {
+        None
+    };
+
+    let end_index = first_index.checked_add(count).ok_or_else(|| {
+        format!(
+            "--{} + --{} must not exceed {}",
+            FIRST_INDEX_FLAG,
+            COUNT_FLAG,
+            u32::max_value()
+        )
+    })?;
+
+    let phrase = fs::read_to_string(&mnemonic_path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", mnemonic_path, e))?;
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|e| format!("Invalid mnemonic in {:?}: {:?}", mnemonic_path, e))?;
+    // The mnemonic of a Lighthouse wallet does not have a passphrase.
+    let seed = Bip39Seed::new(&mnemonic, "");
+
+    ensure_dir_exists(&validator_dir)?;
+    ensure_dir_exists(&secrets_dir)?;
+
+    recover_validators(
+        seed.as_bytes(),
+        first_index..end_index,
+        &validator_dir,
+        &secrets_dir,
+        deposit_gwei.map(|deposit_gwei| (deposit_gwei, &spec)),
+        store_withdrawal_keystore,
+    )
+}
+
+/// Creates a validator directory for each of the wallet `indices` derived from `seed`, unless one
+/// already exists, with eth1 deposit data for `deposit` (an amount in GWEI) if it is `Some`.
+fn recover_validators(
+    seed: &[u8],
+    indices: Range<u32>,
+    validator_dir: &Path,
+    secrets_dir: &Path,
+    deposit: Option<(u64, &ChainSpec)>,
+    store_withdrawal_keystore: bool,
+) -> Result<(), String> {
+    let audit_log = AuditLog::new(validator_dir);
+
+    for index in indices {
+        let derive = |key_type: KeyType| {
+            let (secret, path) = recover_validator_secret_from_seed(seed, index, key_type)
+                .map_err(|e| format!("Unable to derive validator {} keys: {:?}", index, e))?;
+            let keypair = keypair_from_secret(secret.as_bytes())
+                .map_err(|e| format!("Unable to derive validator {} keys: {:?}", index, e))?;
+            Ok::<_, String>((keypair, format!("{}", path)))
+        };
+
+        let (voting_keypair, voting_path) = derive(KeyType::Voting)?;
+        let voting_pubkey = voting_keypair.pk.as_hex_string();
+
+        // Check for an existing directory before encrypting any keystores, since encryption is
+        // deliberately slow.
+        if validator_dir.join(&voting_pubkey).exists() {
+            println!("{}\t{}\talready exists, skipping", index, voting_pubkey);
+            continue;
+        }
+
+        let (withdrawal_keypair, withdrawal_path) = derive(KeyType::Withdrawal)?;
+
+        let voting_password = random_password();
+        let withdrawal_password = random_password();
+        let keystore = |keypair: &Keypair, password: &[u8], path: String| {
+            KeystoreBuilder::new(keypair, password, path)
+                .and_then(|builder| builder.build())
+                .map_err(|e| format!("Unable to create keystore for validator {}: {:?}", index, e))
+        };
+
+        let builder = ValidatorDirBuilder::new(validator_dir.into(), secrets_dir.into())
+            .voting_keystore(
+                keystore(&voting_keypair, voting_password.as_bytes(), voting_path)?,
+                voting_password.as_bytes(),
+            )
+            .withdrawal_keystore(
+                keystore(
+                    &withdrawal_keypair,
+                    withdrawal_password.as_bytes(),
+                    withdrawal_path,
+                )?,
+                withdrawal_password.as_bytes(),
+            )
+            .store_withdrawal_keystore(store_withdrawal_keystore);
+
+        let builder = if let Some((deposit_gwei, spec)) = deposit {
+            builder.create_eth1_tx_data(deposit_gwei, spec)
+        } else {
+            builder
+        };
+
+        builder
+            .build()
+            .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
+
+        audit_log
+            .record(
+                KeyEvent::Recover,
+                Interface::Cli,
+                voting_pubkey.clone(),
+                Some(format!("mnemonic index: {}", index)),
+            )
+            .map_err(|e| format!("Unable to write {:?}: {:?}", audit_log.path(), e))?;
+
+        println!("{}\t{}\trecovered", index, voting_pubkey);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+// These tests are very slow in debug, only test in release.
+#[cfg(not(debug_assertions))]
+mod tests {
+    use super::*;
+    use eth2_wallet::WalletBuilder;
+    use tempdir::TempDir;
+    use validator_dir::VOTING_KEYSTORE_FILE;
+
+    const MNEMONIC: &str =
+        "enemy fog enlist laundry nurse hungry discover turkey holiday resemble glad discover";
+    const WALLET_PASSWORD: &[u8] = &[43; 43];
+    const KEYSTORE_PASSWORD: &[u8] = &[44; 44];
+
+    /// Returns the name and voting keystore of each validator directory in `validator_dir`.
+    fn validator_dirs(validator_dir: &Path) -> Vec<(String, String)> {
+        let mut dirs = fs::read_dir(validator_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                let keystore = fs::read_to_string(path.join(VOTING_KEYSTORE_FILE)).unwrap();
+                (name, keystore)
+            })
+            .collect::<Vec<_>>();
+        dirs.sort();
+        dirs
+    }
+
+    #[test]
+    fn recovers_wallet_validators_once() {
+        let dir = TempDir::new("recover").unwrap();
+        let validator_dir = dir.path().join("validators");
+        let secrets_dir = dir.path().join("secrets");
+        fs::create_dir_all(&validator_dir).unwrap();
+        fs::create_dir_all(&secrets_dir).unwrap();
+
+        let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+        let seed = Bip39Seed::new(&mnemonic, "");
+
+        // The validators created by a wallet with the same mnemonic.
+        let mut wallet = WalletBuilder::from_mnemonic(&mnemonic, WALLET_PASSWORD, "wallet".into())
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut expected = (0..3)
+            .map(|_| {
+                let keystores = wallet
+                    .next_validator(WALLET_PASSWORD, KEYSTORE_PASSWORD, KEYSTORE_PASSWORD)
+                    .unwrap();
+                format!("0x{}", keystores.voting.pubkey())
+            })
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        recover_validators(
+            seed.as_bytes(),
+            0..3,
+            &validator_dir,
+            &secrets_dir,
+            None,
+            false,
+        )
+        .unwrap();
+
+        let recovered = validator_dirs(&validator_dir);
+        assert_eq!(
+            recovered
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>(),
+            expected,
+            "should recover the validators of the wallet"
+        );
+
+        // Recovering the same range again neither duplicates nor overwrites any validator.
+        recover_validators(
+            seed.as_bytes(),
+            0..3,
+            &validator_dir,
+            &secrets_dir,
+            None,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(validator_dirs(&validator_dir), recovered);
+    }
+}
@@ -0,0 +1,210 @@
+//! Compares the attestation data produced by this node against the aggregates seen on gossip for
+//! the same slots, so that an operator whose node consistently disagrees with the network (e.g.,
+//! because it is on a minority fork or receives blocks late) is warned before it costs their
+//! validators rewards.
+//!
+//! The first attestation data produced for each slot (i.e., requested by a validator client) is
+//! remembered. Each aggregate subsequently verified for that slot is compared with it, and the
+//! comparison counted in the `beacon_attestation_self_check_*` metrics. A warning is logged, at
+//! most once per epoch, whilst the fraction of recent comparisons which diverged exceeds
+//! `DIVERGENCE_WARNING_THRESHOLD`.
+//!
+//! Nothing is compared for slots in which no attestation data was produced.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use slog::{debug, warn, Logger};
+use std::collections::{HashMap, VecDeque};
+use types::{AttestationData, Epoch, EthSpec, Slot};
+
+/// The number of slots for which produced attestation data is remembered. Aggregates older than
+/// this are not propagated on gossip, so cannot be compared.
+pub const SELF_CHECK_RETAINED_SLOTS: u64 = 64;
+
+/// The number of most recent comparisons from which the divergence rate is calculated.
+pub const SELF_CHECK_WINDOW: usize = 256;
+
+/// The minimum number of comparisons in the window before a warning may be logged.
+pub const MIN_COMPARISONS_FOR_WARNING: usize = 32;
+
+/// The divergence rate above which the operator is warned.
+pub const DIVERGENCE_WARNING_THRESHOLD: f64 = 0.5;
+
+/// The parts of an aggregate's attestation data which differed from that produced by this node.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Divergence {
+    pub head: bool,
+    pub target: bool,
+    pub source: bool,
+}
+
+impl Divergence {
+    /// Returns `true` if any part differed.
+    pub fn is_divergent(&self) -> bool {
+        self.head || self.target || self.source
+    }
+}
+
+struct Inner {
+    produced: HashMap<Slot, AttestationData>,
+    /// Whether each of the most recent comparisons diverged, oldest first.
+    window: VecDeque<bool>,
+    last_warning_epoch: Option<Epoch>,
+}
+
+/// Remembers produced attestation data and compares it with aggregates from gossip.
+pub struct AttestationSelfCheck {
+    inner: Mutex<Inner>,
+    log: Logger,
+}
+
+impl AttestationSelfCheck {
+    pub fn new(log: Logger) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                produced: HashMap::new(),
+                window: VecDeque::with_capacity(SELF_CHECK_WINDOW),
+                last_warning_epoch: None,
+            }),
+            log,
+        }
+    }
+
+    /// Remembers `data`, if it is the first attestation data produced for its slot.
+    pub fn record_produced(&self, data: &AttestationData) {
+        let mut inner = self.inner.lock();
+
+        inner
+            .produced
+            .entry(data.slot)
+            .or_insert_with(|| data.clone());
+
+        let oldest = data.slot.as_u64().saturating_sub(SELF_CHECK_RETAINED_SLOTS);
+        inner.produced.retain(|slot, _| slot.as_u64() >= oldest);
+    }
+
+    /// Compares the data of an aggregate from gossip with the data produced for the same slot,
+    /// returning `None` if no data was produced for that slot.
+    pub fn compare<E: EthSpec>(&self, aggregate: &AttestationData) -> Option<Divergence> {
+        let mut inner = self.inner.lock();
+        let produced = inner.produced.get(&aggregate.slot)?;
+
+        let divergence = Divergence {
+            head: produced.beacon_block_root != aggregate.beacon_block_root,
+            target: produced.target != aggregate.target,
+            source: produced.source != aggregate.source,
+        };
+
+        metrics::inc_counter(&metrics::ATTESTATION_SELF_CHECK_COMPARISONS);
+        for (diverged, field) in &[
+            (divergence.head, "head"),
+            (divergence.target, "target"),
+            (divergence.source, "source"),
+        ] {
+            if *diverged {
+                metrics::inc_counter_vec(&metrics::ATTESTATION_SELF_CHECK_DIVERGENCES, &[*field]);
+            }
+        }
+
+        if divergence.is_divergent() {
+            debug!(
+                self.log,
+                "Aggregate differs from produced attestation";
+                "slot" => aggregate.slot,
+                "head" => divergence.head,
+                "target" => divergence.target,
+                "source" => divergence.source
+            );
+        }
+
+        if inner.window.len() == SELF_CHECK_WINDOW {
+            inner.window.pop_front();
+        }
+        inner.window.push_back(divergence.is_divergent());
+
+        let comparisons = inner.window.len();
+        let divergences = inner.window.iter().filter(|diverged| **diverged).count();
+        let rate = divergences as f64 / comparisons as f64;
+        metrics::set_float_gauge(&metrics::ATTESTATION_SELF_CHECK_DIVERGENCE_RATE, rate);
+
+        let epoch = aggregate.slot.epoch(E::slots_per_epoch());
+        if comparisons >= MIN_COMPARISONS_FOR_WARNING
+            && rate > DIVERGENCE_WARNING_THRESHOLD
+            && inner.last_warning_epoch.map_or(true, |last| last < epoch)
+        {
+            inner.last_warning_epoch = Some(epoch);
+            warn!(
+                self.log,
+                "Attestations disagree with the network";
+                "msg" => "this node may be on a minority fork or receiving blocks late",
+                "divergence_rate" => format!("{:.2}", rate),
+                "comparisons" => comparisons,
+                "epoch" => epoch
+            );
+        }
+
+        Some(divergence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use types::{Checkpoint, Hash256, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn data(slot: u64, head: u8) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(slot),
+            index: 0,
+            beacon_block_root: Hash256::repeat_byte(head),
+            source: Checkpoint::default(),
+            target: Checkpoint::default(),
+        }
+    }
+
+    #[test]
+    fn compares_with_produced_data() {
+        let check = AttestationSelfCheck::new(NullLoggerBuilder.build().unwrap());
+
+        assert_eq!(check.compare::<E>(&data(1, 1)), None);
+
+        check.record_produced(&data(1, 1));
+        // Only the first data produced for a slot is remembered.
+        check.record_produced(&data(1, 2));
+
+        assert_eq!(check.compare::<E>(&data(1, 1)), Some(Divergence::default()));
+        assert_eq!(
+            check.compare::<E>(&data(1, 2)),
+            Some(Divergence {
+                head: true,
+                ..Divergence::default()
+            })
+        );
+
+        let mut other_index = data(1, 1);
+        other_index.index = 3;
+        assert_eq!(
+            check.compare::<E>(&other_index),
+            Some(Divergence::default())
+        );
+
+        // Old slots are forgotten.
+        check.record_produced(&data(1 + SELF_CHECK_RETAINED_SLOTS + 1, 1));
+        assert_eq!(check.compare::<E>(&data(1, 1)), None);
+    }
+
+    #[test]
+    fn warns_once_per_epoch() {
+        let check = AttestationSelfCheck::new(NullLoggerBuilder.build().unwrap());
+        check.record_produced(&data(1, 1));
+
+        for _ in 0..MIN_COMPARISONS_FOR_WARNING {
+            check.compare::<E>(&data(1, 2));
+        }
+        assert_eq!(check.inner.lock().last_warning_epoch, Some(Epoch::new(0)));
+        assert_eq!(check.inner.lock().window.len(), MIN_COMPARISONS_FOR_WARNING);
+    }
+}
@@ -95,6 +95,15 @@ pub enum Error {
     ValidatorIndexTooHigh(usize),
     /// The `attestation.data.beacon_block_root` block is unknown.
     UnknownHeadBlock { beacon_block_root: Hash256 },
+    /// The `attestation.data.slot` is more than the `max_skip_slots` of the chain after the slot of
+    /// the `attestation.data.beacon_block_root` block, so we refuse to compute its committees.
+    ///
+    /// The attestation may be valid, but is most likely from a peer on a fork which has not had
+    /// a block for a long time, or crafted to make us compute a costly state transition.
+    TooManySkippedSlots {
+        head_block_slot: Slot,
+        attestation_slot: Slot,
+    },
     /// The `attestation.data.slot` is not from the same epoch as `data.target.epoch` and therefore
     /// the attestation is invalid.
     BadTargetEpoch,
@@ -135,6 +144,36 @@ impl From<BeaconChainError> for Error {
     }
 }
 
+impl Error {
+    /// A short, static name for this error, used to label metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Error::FutureSlot { .. } => "future_slot",
+            Error::PastSlot { .. } => "past_slot",
+            Error::EmptyAggregationBitfield => "empty_aggregation_bitfield",
+            Error::InvalidSelectionProof { .. } => "invalid_selection_proof",
+            Error::AggregatorNotInCommittee { .. } => "aggregator_not_in_committee",
+            Error::AggregatorPubkeyUnknown(_) => "aggregator_pubkey_unknown",
+            Error::AttestationAlreadyKnown(_) => "attestation_already_known",
+            Error::AggregatorAlreadyKnown(_) => "aggregator_already_known",
+            Error::ValidatorIndexTooHigh(_) => "validator_index_too_high",
+            Error::UnknownHeadBlock { .. } => "unknown_head_block",
+            Error::TooManySkippedSlots { .. } => "too_many_skipped_slots",
+            Error::BadTargetEpoch => "bad_target_epoch",
+            Error::UnknownTargetRoot(_) => "unknown_target_root",
+            Error::InvalidSignature => "invalid_signature",
+            Error::NoCommitteeForSlotAndIndex { .. } => "no_committee_for_slot_and_index",
+            Error::NotExactlyOneAggregationBitSet(_) => "not_exactly_one_aggregation_bit_set",
+            Error::PriorAttestationKnown { .. } => "prior_attestation_known",
+            Error::FutureEpoch { .. } => "future_epoch",
+            Error::PastEpoch { .. } => "past_epoch",
+            Error::AttestsToFutureBlock { .. } => "attests_to_future_block",
+            Error::Invalid(_) => "invalid",
+            Error::BeaconChainError(_) => "beacon_chain_error",
+        }
+    }
+}
+
 /// Wraps a `SignedAggregateAndProof` that has been verified for propagation on the gossip network.
 pub struct VerifiedAggregatedAttestation<T: BeaconChainTypes> {
     signed_aggregate: SignedAggregateAndProof<T::EthSpec>,
@@ -466,6 +505,11 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
         &self.attestation
     }
 
+    /// Returns the `IndexedAttestation` of the wrapped `attestation`.
+    pub fn indexed_attestation(&self) -> &IndexedAttestation<T::EthSpec> {
+        &self.indexed_attestation
+    }
+
     /// Returns a mutable reference to the underlying attestation.
     ///
     /// Only use during testing since modifying the `IndexedAttestation` can cause the attestation
@@ -597,16 +641,25 @@ fn verify_head_block_is_known<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     attestation: &Attestation<T::EthSpec>,
 ) -> Result<(), Error> {
-    if chain
+    let (head_block_slot, _state_root) = chain
         .fork_choice
-        .contains_block(&attestation.data.beacon_block_root)
-    {
-        Ok(())
-    } else {
-        Err(Error::UnknownHeadBlock {
+        .block_slot_and_state_root(&attestation.data.beacon_block_root)
+        .ok_or_else(|| Error::UnknownHeadBlock {
             beacon_block_root: attestation.data.beacon_block_root,
-        })
+        })?;
+
+    // Reject any attestation which would require us to skip too many slots from its head block.
+    if let Some(max_skip_slots) = chain.max_skip_slots {
+        if attestation.data.slot > head_block_slot + max_skip_slots {
+            metrics::inc_counter_vec(&metrics::MAX_SKIP_SLOTS_REJECTIONS, &["attestation"]);
+            return Err(Error::TooManySkippedSlots {
+                head_block_slot,
+                attestation_slot: attestation.data.slot,
+            });
+        }
     }
+
+    Ok(())
 }
 
 /// Verify that the `attestation` is within the acceptable gossip propagation range, with reference
@@ -748,7 +801,7 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         .map_err(BeaconChainError::SignatureSetError)?,
     ];
 
-    Ok(verify_signature_sets(signature_sets))
+    Ok(verify_signature_sets(&signature_sets))
 }
 
 /// Returns the `indexed_attestation` for the `attestation` using the public keys cached in the
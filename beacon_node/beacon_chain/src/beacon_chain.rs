@@ -1,3 +1,4 @@
+use crate::attestation_self_check::AttestationSelfCheck;
 use crate::attestation_verification::{
     Error as AttestationError, ForkChoiceVerifiedAttestation, IntoForkChoiceVerifiedAttestation,
     VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
@@ -6,24 +7,30 @@ use crate::block_verification::{
     check_block_relevancy, get_block_root, signature_verify_chain_segment, BlockError,
     FullyVerifiedBlock, GossipVerifiedBlock, IntoFullyVerifiedBlock,
 };
+use crate::differential::DifferentialTester;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
+use crate::event_journal::EventJournal;
 use crate::events::{EventHandler, EventKind};
 use crate::fork_choice::{Error as ForkChoiceError, ForkChoice};
 use crate::head_tracker::HeadTracker;
+use crate::input_recorder::{InputRecorder, RecordedInput};
 use crate::metrics;
 use crate::migrate::Migrate;
 use crate::naive_aggregation_pool::{Error as NaiveAggregationError, NaiveAggregationPool};
 use crate::observed_attestations::{Error as AttestationObservationError, ObservedAttestations};
 use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
 use crate::observed_block_producers::ObservedBlockProducers;
+use crate::own_publications::OwnPublications;
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_observed_caches::PersistedObservedCaches;
+use crate::proposer_cache::{ProposerCache, ProposerShuffling};
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::SnapshotCache;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::BeaconSnapshot;
-use operation_pool::{OperationPool, PersistedOperationPool};
+use operation_pool::{AttestationInclusionStrategy, OperationPool, PersistedOperationPool};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::per_block_processing::errors::{
@@ -42,7 +49,7 @@ use store::iter::{
     BlockRootsIterator, ParentRootBlockIterator, ReverseBlockRootIterator,
     ReverseStateRootIterator, StateRootsIterator,
 };
-use store::{Error as DBError, Store};
+use store::{Error as DBError, HotStateSummary, Store, TemporaryState};
 use types::*;
 
 // Text included in blocks.
@@ -70,6 +77,7 @@ pub const BEACON_CHAIN_DB_KEY: [u8; 32] = [0; 32];
 pub const OP_POOL_DB_KEY: [u8; 32] = [0; 32];
 pub const ETH1_CACHE_DB_KEY: [u8; 32] = [0; 32];
 pub const FORK_CHOICE_DB_KEY: [u8; 32] = [0; 32];
+pub const OBSERVED_CACHES_DB_KEY: [u8; 32] = [0; 32];
 
 /// The result of a chain segment processing.
 #[derive(Debug)]
@@ -186,6 +194,9 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub observed_aggregators: ObservedAggregators<T::EthSpec>,
     /// Maintains a record of which validators have proposed blocks for each slot.
     pub observed_block_producers: ObservedBlockProducers<T::EthSpec>,
+    /// Maintains a record of the blocks and attestations published through the API, to refuse
+    /// those which would equivocate.
+    pub own_publications: OwnPublications<T::EthSpec>,
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec, T::Store>>,
     /// Stores a "snapshot" of the chain at the time the head-of-the-chain block was received.
@@ -205,10 +216,25 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
     /// Caches the shuffling for a given epoch and state root.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
+    /// Caches the block proposers for a given epoch and decision root.
+    pub(crate) proposer_cache: TimeoutRwLock<ProposerCache>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache>,
     /// A list of any hard-coded forks that have been disabled.
     pub disabled_forks: Vec<String>,
+    /// Determines whether attestations more than one epoch old are included in produced blocks.
+    pub attestation_inclusion_strategy: AttestationInclusionStrategy,
+    /// If present, blocks and attestations which skip more than this many slots from their
+    /// parent or head block are rejected rather than processed.
+    pub max_skip_slots: Option<u64>,
+    /// If present, records the consensus-relevant inputs to the chain so they may be replayed.
+    pub(crate) input_recorder: Option<InputRecorder<T::EthSpec>>,
+    /// If present, compares the state transition of each block against a reference implementation.
+    pub(crate) differential_tester: Option<DifferentialTester<T::EthSpec>>,
+    /// If present, retains recent head and finalization events so missed events may be recovered.
+    pub event_journal: Option<EventJournal<T::EthSpec>>,
+    /// If present, compares produced attestation data with the aggregates seen on gossip.
+    pub(crate) attestation_self_check: Option<AttestationSelfCheck>,
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
 }
@@ -292,6 +318,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persists the caches of observed attestations, attesters, aggregators and block producers to
+    /// disk.
+    pub fn persist_observed_caches(&self) -> Result<(), Error> {
+        let timer = metrics::start_timer(&metrics::PERSIST_OBSERVED_CACHES);
+
+        self.store.put(
+            &Hash256::from_slice(&OBSERVED_CACHES_DB_KEY),
+            &PersistedObservedCaches::from_observed_caches(
+                &self.observed_attestations,
+                &self.observed_attesters,
+                &self.observed_aggregators,
+                &self.observed_block_producers,
+            ),
+        )?;
+
+        metrics::stop_timer(timer);
+
+        Ok(())
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -350,6 +396,73 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         ))
     }
 
+    /// Calls `f` with the `(block_root, slot)` of each slot in `start_slot..end_slot` of the
+    /// canonical chain, in ascending slot order, returning the first error returned by `f`.
+    ///
+    /// The roots are read from the store as `f` consumes them, rather than collected first, so
+    /// this is suitable for ranges spanning much of the chain (e.g., for exports).
+    ///
+    /// ## Notes
+    ///
+    /// - Skipped slots contain the root of the closest prior non-skipped slot (identical to the
+    ///     way they are stored in `state.block_roots`).
+    /// - Slots after the head are not visited.
+    pub fn with_canonical_block_roots<F, E>(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        mut f: F,
+    ) -> Result<(), E>
+    where
+        F: FnMut(Hash256, Slot) -> Result<(), E>,
+        E: From<Error>,
+    {
+        for (block_root, slot) in self
+            .forwards_iter_block_roots(start_slot)?
+            .take_while(|(_, slot)| *slot < end_slot)
+        {
+            f(block_root, slot)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with the root and block of each block in the canonical chain with a slot in
+    /// `start_slot..end_slot`, in ascending slot order, returning the first error returned by `f`.
+    ///
+    /// Blocks are loaded one at a time, as in `Self::with_canonical_block_roots`.
+    pub fn with_canonical_blocks<F, E>(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        mut f: F,
+    ) -> Result<(), E>
+    where
+        F: FnMut(Hash256, SignedBeaconBlock<T::EthSpec>) -> Result<(), E>,
+        E: From<Error>,
+    {
+        let mut previous_block_root = None;
+
+        self.with_canonical_block_roots(start_slot, end_slot, |block_root, _slot| {
+            // Skipped slots repeat the root of the previous block.
+            if previous_block_root == Some(block_root) {
+                return Ok(());
+            }
+            previous_block_root = Some(block_root);
+
+            let block = self
+                .get_block(&block_root)?
+                .ok_or_else(|| Error::MissingBeaconBlock(block_root))?;
+
+            // The first root belongs to a block prior to `start_slot` if it is a skipped slot.
+            if block.slot() >= start_slot {
+                f(block_root, block)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
     /// Traverse backwards from `block_root` to find the block roots of its ancestors.
     ///
     /// ## Notes
@@ -657,33 +770,161 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Returns the block proposer for a given slot.
     ///
-    /// Information is read from the present `beacon_state` shuffling, only information from the
-    /// present epoch is available.
+    /// The proposer is determined by the canonical chain, see `Self::proposer_shuffling_for_epoch`.
     pub fn block_proposer(&self, slot: Slot) -> Result<usize, Error> {
-        let epoch = |slot: Slot| slot.epoch(T::EthSpec::slots_per_epoch());
-        let head_state = &self.head()?.beacon_state;
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+
+        self.proposer_shuffling_for_epoch(slot.epoch(slots_per_epoch))?
+            .proposer(slot, slots_per_epoch)
+            .ok_or_else(|| {
+                Error::InvariantViolated(format!("No proposer in shuffling for slot {}", slot))
+            })
+    }
+
+    /// Returns the block proposers for every slot of `epoch`, as determined by the canonical
+    /// chain.
+    ///
+    /// The `proposer_cache` is consulted first, keyed by the root of the block which decided the
+    /// shuffling for `epoch`. On a miss, the shuffling is computed from the head state (skipping
+    /// slots if `epoch` is later than the head) or from a state loaded from the database and then
+    /// added to the cache, so repeatedly asking "who proposes slot X" does not repeatedly load or
+    /// skip a state.
+    pub fn proposer_shuffling_for_epoch(&self, epoch: Epoch) -> Result<ProposerShuffling, Error> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let decision_root = self.proposer_shuffling_decision_root(epoch)?;
 
-        let mut state = if epoch(slot) == epoch(head_state.slot) {
+        if let Some(shuffling) = self
+            .proposer_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::ProposerCacheLockTimeout)?
+            .get(epoch, decision_root)
+        {
+            return Ok(shuffling.clone());
+        }
+
+        let head_epoch = self.head_info()?.slot.epoch(slots_per_epoch);
+        let mut state = if epoch == head_epoch {
             self.head()?.beacon_state
         } else {
             // The block proposer shuffling is not affected by the state roots, so we don't need to
             // calculate them.
-            self.state_at_slot(slot, StateSkipConfig::WithoutStateRoots)?
+            self.state_at_slot(
+                epoch.start_slot(slots_per_epoch),
+                StateSkipConfig::WithoutStateRoots,
+            )?
         };
 
-        state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
-
-        if epoch(state.slot) != epoch(slot) {
+        if state.current_epoch() != epoch {
             return Err(Error::InvariantViolated(format!(
-                "Epochs in consistent in proposer lookup: state: {}, requested: {}",
-                epoch(state.slot),
-                epoch(slot)
+                "Epochs inconsistent in proposer lookup: state: {}, requested: {}",
+                state.current_epoch(),
+                epoch
             )));
         }
 
-        state
-            .get_beacon_proposer_index(slot, &self.spec)
-            .map_err(Into::into)
+        state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
+
+        let proposers = epoch
+            .slot_iter(slots_per_epoch)
+            .map(|slot| state.get_beacon_proposer_index(slot, &self.spec))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shuffling = ProposerShuffling {
+            epoch,
+            decision_root,
+            proposers,
+        };
+
+        self.proposer_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::ProposerCacheLockTimeout)?
+            .insert(shuffling.clone());
+
+        Ok(shuffling)
+    }
+
+    /// Returns the root of the block which decides the proposer shuffling for `epoch` on the
+    /// canonical chain.
+    ///
+    /// This is the block at the last slot of the prior epoch (or the genesis block, for the
+    /// genesis epoch). If that slot is later than the head, it must be a skip slot so the head
+    /// block is the decision block.
+    pub fn proposer_shuffling_decision_root(&self, epoch: Epoch) -> Result<Hash256, Error> {
+        if epoch == self.spec.genesis_slot.epoch(T::EthSpec::slots_per_epoch()) {
+            return Ok(self.genesis_block_root);
+        }
+
+        let decision_slot = epoch.start_slot(T::EthSpec::slots_per_epoch()) - 1;
+
+        let from_head = {
+            let head = self
+                .canonical_head
+                .try_read_for(HEAD_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+
+            if decision_slot >= head.beacon_block.slot() {
+                Some(head.beacon_block_root)
+            } else {
+                // Avoid cloning the head state when the root is within its historical roots.
+                head.beacon_state
+                    .get_block_root(decision_slot)
+                    .ok()
+                    .copied()
+            }
+        };
+
+        match from_head {
+            Some(root) => Ok(root),
+            None => self
+                .root_at_slot(decision_slot)?
+                .ok_or_else(|| Error::NoStateForSlot(decision_slot)),
+        }
+    }
+
+    /// Returns the `CommitteeCache` for `epoch` as defined by `state`.
+    ///
+    /// The `shuffling_cache` is consulted first. On a miss, the committee cache is built from
+    /// `state` and, if its shuffling can be identified by a block root in `state`, added to the
+    /// `shuffling_cache`.
+    pub fn committee_cache_for_state(
+        &self,
+        state: &mut BeaconState<T::EthSpec>,
+        epoch: Epoch,
+    ) -> Result<CommitteeCache, Error> {
+        let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
+            .map_err(Error::IncorrectStateForAttestation)?;
+
+        // The shuffling cache is keyed by the block root at the first slot of the epoch, which is
+        // only known to `state` once it has advanced beyond that slot.
+        let epoch_start_slot = epoch.start_slot(T::EthSpec::slots_per_epoch());
+        let target_root = if epoch_start_slot < state.slot {
+            Some(*state.get_block_root(epoch_start_slot)?)
+        } else {
+            None
+        };
+
+        if let Some(target_root) = target_root {
+            if let Some(committee_cache) = self
+                .shuffling_cache
+                .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::AttestationCacheLockTimeout)?
+                .get(epoch, target_root)
+            {
+                return Ok(committee_cache.clone());
+            }
+        }
+
+        state.build_committee_cache(relative_epoch, &self.spec)?;
+        let committee_cache = state.committee_cache(relative_epoch)?;
+
+        if let Some(target_root) = target_root {
+            self.shuffling_cache
+                .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::AttestationCacheLockTimeout)?
+                .insert(epoch, target_root, committee_cache);
+        }
+
+        Ok(committee_cache.clone())
     }
 
     /// Returns the attestation slot and committee index for a given validator index.
@@ -838,7 +1079,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             *state.get_block_root(target_slot)?
         };
 
-        Ok(Attestation {
+        let attestation = Attestation {
             aggregation_bits: BitList::with_capacity(committee_len)?,
             data: AttestationData {
                 slot,
@@ -851,7 +1092,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 },
             },
             signature: AggregateSignature::empty_signature(),
-        })
+        };
+
+        if let Some(self_check) = &self.attestation_self_check {
+            self_check.record_produced(&attestation.data);
+        }
+
+        Ok(attestation)
     }
 
     /// Accepts some `Attestation` from the network and attempts to verify it, returning `Ok(_)` if
@@ -867,10 +1114,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let _timer =
             metrics::start_timer(&metrics::UNAGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES);
 
-        VerifiedUnaggregatedAttestation::verify(attestation, self).map(|v| {
-            metrics::inc_counter(&metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
-            v
-        })
+        self.record_input(|| RecordedInput::Attestation(Box::new(attestation.clone())));
+
+        VerifiedUnaggregatedAttestation::verify(attestation, self)
+            .map(|v| {
+                metrics::inc_counter(&metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+                v
+            })
+            .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::ATTESTATION_PROCESSING_REJECTIONS,
+                    &["unaggregated", e.as_str()],
+                );
+                e
+            })
     }
 
     /// Accepts some `SignedAggregateAndProof` from the network and attempts to verify it,
@@ -883,10 +1140,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let _timer =
             metrics::start_timer(&metrics::AGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES);
 
-        VerifiedAggregatedAttestation::verify(signed_aggregate, self).map(|v| {
-            metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
-            v
-        })
+        self.record_input(|| RecordedInput::AggregateAndProof(Box::new(signed_aggregate.clone())));
+
+        VerifiedAggregatedAttestation::verify(signed_aggregate, self)
+            .map(|v| {
+                metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+                if let Some(self_check) = &self.attestation_self_check {
+                    self_check.compare::<T::EthSpec>(&v.attestation().data);
+                }
+                v
+            })
+            .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::ATTESTATION_PROCESSING_REJECTIONS,
+                    &["aggregated", e.as_str()],
+                );
+                e
+            })
     }
 
     /// Accepts some attestation-type object and attempts to verify it in the context of fork
@@ -903,12 +1173,24 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<ForkChoiceVerifiedAttestation<'a, T>, AttestationError> {
         let _timer = metrics::start_timer(&metrics::ATTESTATION_PROCESSING_APPLY_TO_FORK_CHOICE);
 
-        let verified = unverified_attestation.into_fork_choice_verified_attestation(self)?;
-        let indexed_attestation = verified.indexed_attestation();
-        self.fork_choice
-            .process_indexed_attestation(indexed_attestation)
-            .map_err(|e| Error::from(e))?;
-        Ok(verified)
+        let result = unverified_attestation
+            .into_fork_choice_verified_attestation(self)
+            .and_then(|verified| {
+                let indexed_attestation = verified.indexed_attestation();
+                self.fork_choice
+                    .process_indexed_attestation(indexed_attestation)
+                    .map_err(|e| Error::from(e))?;
+                Ok(verified)
+            });
+
+        if let Err(e) = &result {
+            metrics::inc_counter_vec(
+                &metrics::ATTESTATION_PROCESSING_REJECTIONS,
+                &["fork_choice", e.as_str()],
+            );
+        }
+
+        result
     }
 
     /// Accepts an `VerifiedUnaggregatedAttestation` and attempts to apply it to the "naive
@@ -1073,7 +1355,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         match self.wall_clock_state() {
             Ok(state) => {
                 if self.eth1_chain.is_some() {
-                    self.op_pool.insert_voluntary_exit(exit, &state, &self.spec)
+                    self.op_pool
+                        .insert_voluntary_exit(exit.clone(), &state, &self.spec)?;
+                    let _ = self
+                        .event_handler
+                        .register(EventKind::BeaconVoluntaryExitImported {
+                            exit: Box::new(exit),
+                        });
+                    Ok(())
                 } else {
                     Ok(())
                 }
@@ -1349,6 +1638,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Clone the block so we can provide it to the event handler.
         let block = unverified_block.block().clone();
 
+        self.record_input(|| RecordedInput::Block(Box::new(block.clone())));
+
         // A small closure to group the verification and import errors.
         let import_block = |unverified_block: B| -> Result<Hash256, BlockError> {
             let fully_verified = unverified_block.into_fully_verified_block(self)?;
@@ -1509,6 +1800,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let db_write_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_WRITE);
 
+        // Mark the states which are new to the database as temporary until the block is stored, so
+        // that they are deleted when the database is next opened if the import is interrupted.
+        let mut temporary_state_roots = vec![];
+        for state_root in intermediate_states
+            .state_roots()
+            .chain(std::iter::once(block.state_root))
+        {
+            if !self.store.exists::<HotStateSummary>(&state_root)? {
+                self.store
+                    .put(&state_root, &TemporaryState { block_root })?;
+                temporary_state_roots.push(state_root);
+            }
+        }
+
         // Store all the states between the parent block state and this block's slot before storing
         // the final state.
         intermediate_states.commit(&*self.store)?;
@@ -1522,6 +1827,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.store.put_state(&block.state_root, &state)?;
         self.store.put_block(&block_root, signed_block.clone())?;
 
+        for state_root in &temporary_state_roots {
+            self.store.delete::<TemporaryState>(state_root)?;
+        }
+
         let parent_root = block.parent_root;
         let slot = block.slot;
 
@@ -1562,12 +1871,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         randao_reveal: Signature,
         slot: Slot,
+        validator_graffiti: Option<[u8; 32]>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
         let state = self
             .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
             .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
 
-        self.produce_block_on_state(state, slot, randao_reveal)
+        self.produce_block_on_state(state, slot, randao_reveal, validator_graffiti)
     }
 
     /// Produce a block for some `slot` upon the given `state`.
@@ -1578,11 +1888,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// The given state will be advanced to the given `produce_at_slot`, then a block will be
     /// produced at that slot height.
+    ///
+    /// The block contains `validator_graffiti`, if supplied, otherwise `GRAFFITI`.
     pub fn produce_block_on_state(
         &self,
         mut state: BeaconState<T::EthSpec>,
         produce_at_slot: Slot,
         randao_reveal: Signature,
+        validator_graffiti: Option<[u8; 32]>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
@@ -1610,8 +1923,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state.latest_block_header.canonical_root()
         };
 
-        let mut graffiti: [u8; 32] = [0; 32];
-        graffiti.copy_from_slice(GRAFFITI.as_bytes());
+        let graffiti = validator_graffiti.unwrap_or_else(|| {
+            let mut graffiti: [u8; 32] = [0; 32];
+            graffiti.copy_from_slice(GRAFFITI.as_bytes());
+            graffiti
+        });
 
         let (proposer_slashings, attester_slashings) =
             self.op_pool.get_slashings(&state, &self.spec);
@@ -1650,7 +1966,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     attester_slashings: attester_slashings.into(),
                     attestations: self
                         .op_pool
-                        .get_attestations(&state, attestation_filter, &self.spec)
+                        .get_attestations_with_strategy(
+                            &state,
+                            attestation_filter,
+                            self.attestation_inclusion_strategy,
+                            &self.spec,
+                        )
                         .map_err(BlockProductionError::OpPoolError)?
                         .into(),
                     deposits,
@@ -1689,6 +2010,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Execute the fork choice algorithm and enthrone the result as the canonical head.
     pub fn fork_choice(&self) -> Result<(), Error> {
+        self.record_input(|| RecordedInput::ForkChoice);
+
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
         let overall_timer = metrics::start_timer(&metrics::FORK_CHOICE_TIMES);
 
@@ -1699,6 +2022,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         metrics::stop_timer(overall_timer);
+        metrics::scrape_head_slot_distance(self);
 
         result
     }
@@ -1788,6 +2112,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         };
 
+        // Summarise the new head block for the event before the snapshot is moved into the
+        // canonical head.
+        let new_head_block = &new_head.beacon_block.message;
+        let head_changed_event = EventKind::BeaconHeadChanged {
+            reorg: is_reorg,
+            previous_head_beacon_block_root: current_head.block_root,
+            current_head_beacon_block_root: beacon_block_root,
+            proposer_index: new_head_block.proposer_index,
+            attestation_count: new_head_block.body.attestations.len(),
+            deposit_count: new_head_block.body.deposits.len(),
+            graffiti: new_head_block.body.graffiti,
+        };
+
         let old_finalized_epoch = current_head.finalized_checkpoint.epoch;
         let new_finalized_epoch = new_head.beacon_state.finalized_checkpoint.epoch;
         let finalized_root = new_head.beacon_state.finalized_checkpoint.root;
@@ -1843,11 +2180,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             )?;
         }
 
-        let _ = self.event_handler.register(EventKind::BeaconHeadChanged {
-            reorg: is_reorg,
-            previous_head_beacon_block_root: current_head.block_root,
-            current_head_beacon_block_root: beacon_block_root,
-        });
+        self.register_journaled_event(head_changed_event);
 
         Ok(())
     }
@@ -1857,9 +2190,68 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Performs slot-based pruning.
     pub fn per_slot_task(&self) {
         trace!(self.log, "Running beacon chain per slot tasks");
+        self.record_input(|| RecordedInput::Tick);
+
         if let Some(slot) = self.slot_clock.now() {
             self.naive_aggregation_pool.prune(slot);
         }
+
+        // Prepare the deposits for the next block, assuming that it is built upon the head.
+        if let Some(eth1_chain) = &self.eth1_chain {
+            let result = self
+                .canonical_head
+                .try_read_for(HEAD_LOCK_TIMEOUT)
+                .ok_or_else(|| "Timed out waiting for the head lock".to_string())
+                .and_then(|head| {
+                    eth1_chain
+                        .expect_deposits_for_next_block(&head.beacon_state)
+                        .map_err(|e| format!("{:?}", e))
+                });
+
+            if let Err(e) = result {
+                warn!(
+                    self.log,
+                    "Unable to prepare deposits";
+                    "error" => e
+                );
+            }
+        }
+    }
+
+    /// Appends `event` to `self.event_journal`, if any, before registering it with
+    /// `self.event_handler` along with its sequence number.
+    fn register_journaled_event(&self, event: EventKind<T::EthSpec>) {
+        let sequence = self.event_journal.as_ref().and_then(|journal| {
+            journal.append(&event).unwrap_or_else(|e| {
+                warn!(
+                    self.log,
+                    "Unable to journal event";
+                    "error" => e
+                );
+                None
+            })
+        });
+
+        let _ = match sequence {
+            Some(sequence) => self.event_handler.register_journaled(event, sequence),
+            None => self.event_handler.register(event),
+        };
+    }
+
+    /// Records the `input` produced by `f` with `self.input_recorder`, if any.
+    ///
+    /// The `input` is only produced if there is a recorder, to avoid needless cloning.
+    fn record_input<F: FnOnce() -> RecordedInput<T::EthSpec>>(&self, f: F) {
+        if let Some(recorder) = &self.input_recorder {
+            let time = self.slot_clock.now_duration().unwrap_or_default();
+            if let Err(e) = recorder.record(time, f()) {
+                warn!(
+                    self.log,
+                    "Unable to record chain input";
+                    "error" => e
+                );
+            }
+        }
     }
 
     /// Called after `self` has had a new block finalized.
@@ -1910,6 +2302,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             self.op_pool.prune_all(&finalized_state, &self.spec);
 
+            if let Some(eth1_chain) = self.eth1_chain.as_ref() {
+                if let Err(e) = eth1_chain.verify_deposit_root(&finalized_state.eth1_data) {
+                    metrics::inc_counter(&metrics::ETH1_DEPOSIT_ROOT_MISMATCHES);
+                    crit!(
+                        self.log,
+                        "Deposit cache does not match finalized state";
+                        "error" => format!("{:?}", e),
+                        "finalized_epoch" => new_finalized_epoch,
+                        "advice" => "blocks produced by this node may be invalid, \
+                                     delete the eth1 cache by restarting with --purge-db",
+                    );
+                }
+            }
+
             // TODO: configurable max finality distance
             let max_finality_distance = 0;
             self.store_migrator.process_finalization(
@@ -1921,7 +2327,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 finalized_block_root.into(),
             );
 
-            let _ = self.event_handler.register(EventKind::BeaconFinalization {
+            self.register_journaled_event(EventKind::BeaconFinalization {
                 epoch: new_finalized_epoch,
                 root: finalized_block_root,
             });
@@ -2102,6 +2508,7 @@ impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
         let drop = || -> Result<(), Error> {
             self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
+            self.persist_observed_caches()?;
             self.persist_eth1_cache()
         };
 
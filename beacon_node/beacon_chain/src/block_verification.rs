@@ -49,11 +49,13 @@ use crate::{
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BeaconSnapshot,
 };
 use parking_lot::RwLockReadGuard;
-use slog::{error, Logger};
+use slog::{error, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 use state_processing::{
-    block_signature_verifier::{BlockSignatureVerifier, Error as BlockSignatureVerifierError},
+    block_signature_verifier::{
+        BlockSignatureVerifier, Error as BlockSignatureVerifierError, InvalidSignatureSet,
+    },
     per_block_processing,
     per_epoch_processing::EpochProcessingSummary,
     per_slot_processing, BlockProcessingError, BlockSignatureStrategy, SlotProcessingError,
@@ -118,10 +120,18 @@ pub enum BlockError {
     ProposalSignatureInvalid,
     /// The `block.proposal_index` is not known.
     UnknownValidator(u64),
-    /// A signature in the block is invalid (exactly which is unknown).
-    InvalidSignature,
+    /// A signature in the block is invalid.
+    ///
+    /// Describes the first invalid signature, if it could be identified.
+    InvalidSignature(Option<InvalidSignatureSet>),
     /// The provided block is from an earlier slot than its parent.
     BlockIsNotLaterThanParent { block_slot: Slot, state_slot: Slot },
+    /// The block is more than the `max_skip_slots` of the chain after its parent, so we refuse to
+    /// compute the state transition to it.
+    ///
+    /// The block may be valid, but is most likely from a fork which has not had a block for a
+    /// long time, or crafted to make us compute a costly state transition.
+    TooManySkippedSlots { parent_slot: Slot, block_slot: Slot },
     /// At least one block in the chain segment did not have it's parent root set to the root of
     /// the prior block.
     NonLinearParentRoots,
@@ -134,6 +144,33 @@ pub enum BlockError {
     BeaconChainError(BeaconChainError),
 }
 
+impl BlockError {
+    /// Returns a short, stable identifier for the error, suitable for use in metrics or a
+    /// response to an API request.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockError::ParentUnknown(_) => "parent_unknown",
+            BlockError::FutureSlot { .. } => "future_slot",
+            BlockError::StateRootMismatch { .. } => "state_root_mismatch",
+            BlockError::GenesisBlock => "genesis_block",
+            BlockError::WouldRevertFinalizedSlot { .. } => "would_revert_finalized_slot",
+            BlockError::BlockIsAlreadyKnown => "block_is_already_known",
+            BlockError::RepeatProposal { .. } => "repeat_proposal",
+            BlockError::BlockSlotLimitReached => "block_slot_limit_reached",
+            BlockError::IncorrectBlockProposer { .. } => "incorrect_block_proposer",
+            BlockError::ProposalSignatureInvalid => "proposal_signature_invalid",
+            BlockError::UnknownValidator(_) => "unknown_validator",
+            BlockError::InvalidSignature(_) => "invalid_signature",
+            BlockError::BlockIsNotLaterThanParent { .. } => "block_is_not_later_than_parent",
+            BlockError::TooManySkippedSlots { .. } => "too_many_skipped_slots",
+            BlockError::NonLinearParentRoots => "non_linear_parent_roots",
+            BlockError::NonLinearSlots => "non_linear_slots",
+            BlockError::PerBlockProcessingError(_) => "per_block_processing_error",
+            BlockError::BeaconChainError(_) => "beacon_chain_error",
+        }
+    }
+}
+
 impl From<BlockSignatureVerifierError> for BlockError {
     fn from(e: BlockSignatureVerifierError) -> Self {
         match e {
@@ -178,8 +215,8 @@ impl From<DBError> for BlockError {
 /// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
 /// signatures are valid, the `chain_segment` is mapped to a `Vec<SignatureVerifiedBlock>` that can
 /// later be transformed into a `FullyVerifiedBlock` without re-checking the signatures. If any
-/// signature in the block is invalid, an `Err` is returned (it is not possible to known _which_
-/// signature was invalid).
+/// signature in the block is invalid, an `Err` is returned which identifies the first invalid
+/// signature.
 ///
 /// ## Errors
 ///
@@ -201,6 +238,14 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         .map(|(_, block)| block.slot())
         .unwrap_or_else(|| slot);
 
+    // Each block is checked against the block before it, so a long segment without gaps is not
+    // mistaken for one which skips many slots.
+    let mut previous_slot = parent.beacon_block.slot();
+    for (_, block) in &chain_segment {
+        check_block_skip_slots(chain, previous_slot, block.slot())?;
+        previous_slot = block.slot();
+    }
+
     let state = cheap_state_advance_to_obtain_committees(
         &mut parent.beacon_state,
         highest_slot,
@@ -214,9 +259,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         signature_verifier.include_all_signatures(block, Some(*block_root))?;
     }
 
-    if signature_verifier.verify().is_err() {
-        return Err(BlockError::InvalidSignature);
-    }
+    verify_signatures(signature_verifier, &chain.log)?;
 
     drop(pubkey_cache);
 
@@ -322,6 +365,8 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         let mut parent = load_parent(&block.message, chain)?;
         let block_root = get_block_root(&block);
 
+        check_block_skip_slots(chain, parent.beacon_block.slot(), block.slot())?;
+
         let state = cheap_state_advance_to_obtain_committees(
             &mut parent.beacon_state,
             block.slot(),
@@ -410,6 +455,8 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         let mut parent = load_parent(&block.message, chain)?;
         let block_root = get_block_root(&block);
 
+        check_block_skip_slots(chain, parent.beacon_block.slot(), block.slot())?;
+
         let state = cheap_state_advance_to_obtain_committees(
             &mut parent.beacon_state,
             block.slot(),
@@ -422,15 +469,13 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
 
         signature_verifier.include_all_signatures(&block, Some(block_root))?;
 
-        if signature_verifier.verify().is_ok() {
-            Ok(Self {
-                block,
-                block_root,
-                parent: Some(parent),
-            })
-        } else {
-            Err(BlockError::InvalidSignature)
-        }
+        verify_signatures(signature_verifier, &chain.log)?;
+
+        Ok(Self {
+            block,
+            block_root,
+            parent: Some(parent),
+        })
     }
 
     /// Finishes signature verification on the provided `GossipVerifedBlock`. Does not re-verify
@@ -454,15 +499,13 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
 
         signature_verifier.include_all_signatures_except_proposal(&block)?;
 
-        if signature_verifier.verify().is_ok() {
-            Ok(Self {
-                block,
-                block_root: from.block_root,
-                parent: Some(parent),
-            })
-        } else {
-            Err(BlockError::InvalidSignature)
-        }
+        verify_signatures(signature_verifier, &chain.log)?;
+
+        Ok(Self {
+            block,
+            block_root: from.block_root,
+            parent: Some(parent),
+        })
     }
 }
 
@@ -540,6 +583,8 @@ impl<T: BeaconChainTypes> FullyVerifiedBlock<T> {
 
         check_block_relevancy(&block, Some(block_root), chain)?;
 
+        check_block_skip_slots(chain, parent.beacon_block.slot(), block.slot())?;
+
         /*
          * Advance the given `parent.beacon_state` to the slot of the given `block`.
          */
@@ -560,6 +605,20 @@ impl<T: BeaconChainTypes> FullyVerifiedBlock<T> {
 
         let mut summaries = vec![];
 
+        // The reference implementation is given the parent state before any slot processing, so
+        // that it applies the full state transition. Only clone it when there is space in the
+        // queue for it.
+        let differential_test = chain
+            .differential_tester
+            .as_ref()
+            .and_then(|tester| tester.reserve(block_root))
+            .map(|reservation| {
+                (
+                    reservation,
+                    parent.beacon_state.clone_with_only_committee_caches(),
+                )
+            });
+
         // Transition the parent state to the block slot.
         let mut state = parent.beacon_state;
         let distance = block.slot().as_u64().saturating_sub(state.slot.as_u64());
@@ -575,7 +634,13 @@ impl<T: BeaconChainTypes> FullyVerifiedBlock<T> {
                 state_root
             };
 
-            per_slot_processing(&mut state, Some(state_root), &chain.spec)?
+            per_slot_processing(&mut state, Some(state_root), &chain.spec)
+                .map_err(|e| {
+                    if e.is_arith_error() {
+                        observe_arith_error(&e, block_root, block.slot(), &chain.log);
+                    }
+                    e
+                })?
                 .map(|summary| summaries.push(summary));
         }
 
@@ -616,6 +681,10 @@ impl<T: BeaconChainTypes> FullyVerifiedBlock<T> {
             BlockSignatureStrategy::NoVerification,
             &chain.spec,
         ) {
+            if err.is_arith_error() {
+                observe_arith_error(&err, block_root, block.slot(), &chain.log);
+            }
+
             match err {
                 // Capture `BeaconStateError` so that we can easily distinguish between a block
                 // that's invalid and one that caused an internal error.
@@ -653,6 +722,10 @@ impl<T: BeaconChainTypes> FullyVerifiedBlock<T> {
             });
         }
 
+        if let Some((reservation, pre_state)) = differential_test {
+            reservation.submit(block.clone(), pre_state);
+        }
+
         Ok(Self {
             block,
             block_root,
@@ -775,7 +848,9 @@ fn load_parent<T: BeaconChainTypes>(
     let result = chain
         .snapshot_cache
         .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
-        .and_then(|mut snapshot_cache| snapshot_cache.try_remove(block.parent_root))
+        .and_then(|mut snapshot_cache| {
+            snapshot_cache.try_remove_for_child(block.parent_root, block.slot)
+        })
         .map(|snapshot| Ok(Some(snapshot)))
         .unwrap_or_else(|| {
             // Load the blocks parent block from the database, returning invalid if that block is not
@@ -861,6 +936,47 @@ fn cheap_state_advance_to_obtain_committees<'a, E: EthSpec>(
     }
 }
 
+/// Returns an error if `block_slot` is more than the `max_skip_slots` of the chain after
+/// `parent_slot`.
+///
+/// This is not part of the specification, it prevents a block (e.g., from a long-dead fork or an
+/// attacker) from making us compute a state transition across an enormous number of slots.
+fn check_block_skip_slots<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    parent_slot: Slot,
+    block_slot: Slot,
+) -> Result<(), BlockError> {
+    if let Some(max_skip_slots) = chain.max_skip_slots {
+        if block_slot > parent_slot + max_skip_slots {
+            metrics::inc_counter_vec(&metrics::MAX_SKIP_SLOTS_REJECTIONS, &["block"]);
+            return Err(BlockError::TooManySkippedSlots {
+                parent_slot,
+                block_slot,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Records a failure of state processing due to an arithmetic overflow or division by zero,
+/// which is either a block crafted to trigger one or a bug which may cause consensus divergence.
+fn observe_arith_error<E: std::fmt::Debug>(
+    error: &E,
+    block_root: Hash256,
+    block_slot: Slot,
+    log: &Logger,
+) {
+    metrics::inc_counter(&metrics::BLOCK_PROCESSING_ARITH_ERRORS);
+    error!(
+        log,
+        "Arithmetic error during state processing";
+        "error" => format!("{:?}", error),
+        "block_root" => format!("{:?}", block_root),
+        "block_slot" => block_slot,
+    );
+}
+
 /// Obtains a read-locked `ValidatorPubkeyCache` from the `chain`.
 fn get_validator_pubkey_cache<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
@@ -872,6 +988,41 @@ fn get_validator_pubkey_cache<T: BeaconChainTypes>(
         .map_err(BlockError::BeaconChainError)
 }
 
+/// Verifies the signatures in `signature_verifier`, logging the first invalid signature (if any)
+/// so it may be compared with the output of other clients.
+fn verify_signatures<'a, E, F>(
+    signature_verifier: BlockSignatureVerifier<'a, E, F>,
+    log: &Logger,
+) -> Result<(), BlockError>
+where
+    E: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>> + Clone,
+{
+    match signature_verifier.verify() {
+        Ok(()) => Ok(()),
+        Err(BlockSignatureVerifierError::SignatureInvalid(invalid)) => {
+            if let Some(invalid) = &invalid {
+                warn!(
+                    log,
+                    "Invalid signature in block";
+                    "signature" => invalid.kind.to_string(),
+                    "object_root" => format!("{:?}", invalid.object_root),
+                    "block_root" => format!("{:?}", invalid.block_root),
+                    "block_slot" => invalid.block_slot,
+                );
+            } else {
+                warn!(
+                    log,
+                    "Invalid block signature batch";
+                    "info" => "all signatures were valid when verified individually",
+                );
+            }
+            Err(BlockError::InvalidSignature(invalid))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Produces an _empty_ `BlockSignatureVerifier`.
 ///
 /// The signature verifier is empty because it does not yet have any of this block's signatures
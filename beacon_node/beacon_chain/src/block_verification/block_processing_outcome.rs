@@ -49,6 +49,11 @@ pub enum BlockProcessingOutcome {
         block_slot: Slot,
         state_slot: Slot,
     },
+    /// The block is more than the `max_skip_slots` of the chain after its parent.
+    TooManySkippedSlots {
+        parent_slot: Slot,
+        block_slot: Slot,
+    },
     /// The `BeaconBlock` has a `proposer_index` that does not match the index we computed locally.
     ///
     /// The block is invalid.
@@ -102,7 +107,7 @@ impl BlockProcessingOutcome {
                 Ok(BlockProcessingOutcome::ProposalSignatureInvalid)
             }
             Err(BlockError::UnknownValidator(i)) => Ok(BlockProcessingOutcome::UnknownValidator(i)),
-            Err(BlockError::InvalidSignature) => Ok(BlockProcessingOutcome::InvalidSignature),
+            Err(BlockError::InvalidSignature(_)) => Ok(BlockProcessingOutcome::InvalidSignature),
             Err(BlockError::BlockIsNotLaterThanParent {
                 block_slot,
                 state_slot,
@@ -110,6 +115,13 @@ impl BlockProcessingOutcome {
                 block_slot,
                 state_slot,
             }),
+            Err(BlockError::TooManySkippedSlots {
+                parent_slot,
+                block_slot,
+            }) => Ok(BlockProcessingOutcome::TooManySkippedSlots {
+                parent_slot,
+                block_slot,
+            }),
             Err(BlockError::IncorrectBlockProposer {
                 block,
                 local_shuffling,
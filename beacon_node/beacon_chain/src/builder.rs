@@ -1,14 +1,23 @@
+use crate::attestation_self_check::{AttestationSelfCheck, DIVERGENCE_WARNING_THRESHOLD};
 use crate::beacon_chain::{
-    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OBSERVED_CACHES_DB_KEY,
+    OP_POOL_DB_KEY,
 };
+use crate::differential::DifferentialTester;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
-use crate::events::NullEventHandler;
+use crate::event_journal::{EventJournal, EVENT_JOURNAL_FILENAME};
+use crate::events::{EventHandlerList, NullEventHandler};
 use crate::fork_choice::SszForkChoice;
 use crate::head_tracker::HeadTracker;
+use crate::input_recorder::{InputRecorder, RecordedInput};
 use crate::migrate::Migrate;
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_observed_caches::{ObservedCaches, PersistedObservedCaches};
+use crate::proposer_cache::ProposerCache;
 use crate::shuffling_cache::ShufflingCache;
-use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
+use crate::snapshot_cache::{
+    SnapshotCache, DEFAULT_PINNED_EPOCH_BOUNDARIES, DEFAULT_SNAPSHOT_CACHE_SIZE,
+};
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
@@ -16,21 +25,25 @@ use crate::{
     ForkChoice,
 };
 use eth1::Config as Eth1Config;
-use operation_pool::{OperationPool, PersistedOperationPool};
+use operation_pool::{AttestationInclusionStrategy, OperationPool, PersistedOperationPool};
 use proto_array_fork_choice::ProtoArrayForkChoice;
-use slog::{info, Logger};
+use slog::{info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::Store;
 use types::{
-    BeaconBlock, BeaconState, ChainSpec, EthSpec, Hash256, Signature, SignedBeaconBlock, Slot,
+    BeaconBlock, BeaconState, ChainSpec, Epoch, EthSpec, Hash256, Signature, SignedBeaconBlock,
 };
 
 pub const PUBKEY_CACHE_FILENAME: &str = "pubkey_cache.ssz";
 
+/// The directory within the `data_dir` in which the inputs of differential test divergences are
+/// kept.
+pub const DIVERGENCE_DIR: &str = "divergences";
+
 /// An empty struct used to "witness" all the `BeaconChainTypes` traits. It has no user-facing
 /// functionality and only exists to satisfy the type system.
 pub struct Witness<TStore, TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler>(
@@ -79,6 +92,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     pub finalized_snapshot: Option<BeaconSnapshot<T::EthSpec>>,
     genesis_block_root: Option<Hash256>,
     op_pool: Option<OperationPool<T::EthSpec>>,
+    observed_caches: Option<ObservedCaches<T::EthSpec>>,
     fork_choice: Option<ForkChoice<T>>,
     eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec, T::Store>>,
     event_handler: Option<T::EventHandler>,
@@ -89,6 +103,12 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     validator_pubkey_cache: Option<ValidatorPubkeyCache>,
     spec: ChainSpec,
     disabled_forks: Vec<String>,
+    attestation_inclusion_strategy: AttestationInclusionStrategy,
+    input_recording_path: Option<PathBuf>,
+    differential_test_command: Option<PathBuf>,
+    event_journal_retention: Option<Duration>,
+    attestation_self_check: bool,
+    max_skip_slots: Option<u64>,
     log: Option<Logger>,
 }
 
@@ -116,6 +136,7 @@ where
             finalized_snapshot: None,
             genesis_block_root: None,
             op_pool: None,
+            observed_caches: None,
             fork_choice: None,
             eth1_chain: None,
             event_handler: None,
@@ -124,6 +145,12 @@ where
             pubkey_cache_path: None,
             data_dir: None,
             disabled_forks: Vec::new(),
+            attestation_inclusion_strategy: AttestationInclusionStrategy::default(),
+            input_recording_path: None,
+            differential_test_command: None,
+            event_journal_retention: None,
+            attestation_self_check: false,
+            max_skip_slots: None,
             validator_pubkey_cache: None,
             spec: TEthSpec::default_spec(),
             log: None,
@@ -176,6 +203,59 @@ where
         self
     }
 
+    /// Sets how attestations from the previous epoch are included in produced blocks.
+    pub fn attestation_inclusion_strategy(
+        mut self,
+        strategy: AttestationInclusionStrategy,
+    ) -> Self {
+        self.attestation_inclusion_strategy = strategy;
+        self
+    }
+
+    /// Record all of the consensus-relevant inputs to the chain by appending them to the file at
+    /// `path`, so they may be replayed later.
+    ///
+    /// See the `input_recorder` module for more information.
+    pub fn record_inputs(mut self, path: PathBuf) -> Self {
+        self.input_recording_path = Some(path);
+        self
+    }
+
+    /// Compare the state transition of each imported block against the reference implementation
+    /// run by `command`, keeping the inputs of any divergence in the `divergences` directory of
+    /// the `data_dir`.
+    ///
+    /// See the `differential` module for more information.
+    pub fn differential_test(mut self, command: PathBuf) -> Self {
+        self.differential_test_command = Some(command);
+        self
+    }
+
+    /// Retain the head and finalization events of the last `retention` in a journal in the
+    /// `data_dir`, so that consumers may recover the events they missed.
+    ///
+    /// See the `event_journal` module for more information.
+    pub fn event_journal(mut self, retention: Duration) -> Self {
+        self.event_journal_retention = Some(retention);
+        self
+    }
+
+    /// Compare the attestation data produced by the chain with the aggregates seen on gossip for
+    /// the same slots, warning if they consistently differ.
+    ///
+    /// See the `attestation_self_check` module for more information.
+    pub fn attestation_self_check(mut self) -> Self {
+        self.attestation_self_check = true;
+        self
+    }
+
+    /// Reject blocks and attestations which skip more than `max_skip_slots` slots from their
+    /// parent or head block, rather than computing the state transition across them.
+    pub fn max_skip_slots(mut self, max_skip_slots: u64) -> Self {
+        self.max_skip_slots = Some(max_skip_slots);
+        self
+    }
+
     /// Attempt to load an existing eth1 cache from the builder's `Store`.
     pub fn get_persisted_eth1_backend(&self) -> Result<Option<SszEth1>, String> {
         let store = self
@@ -259,6 +339,25 @@ where
                 .unwrap_or_else(|| OperationPool::new()),
         );
 
+        // The observed caches only protect against re-processing gossip, so the chain may start
+        // without them if they are missing or invalid.
+        self.observed_caches = store
+            .get::<PersistedObservedCaches>(&Hash256::from_slice(&OBSERVED_CACHES_DB_KEY))
+            .map_err(|e| format!("DB error whilst reading persisted observed caches: {:?}", e))
+            .and_then(|persisted| {
+                persisted
+                    .map(|persisted| persisted.to_observed_caches())
+                    .transpose()
+            })
+            .unwrap_or_else(|e| {
+                warn!(
+                    log,
+                    "Unable to restore observed caches";
+                    "error" => e
+                );
+                None
+            });
+
         let finalized_block_root = head_state.finalized_checkpoint.root;
         let finalized_block = store
             .get::<SignedBeaconBlock<TEthSpec>>(&finalized_block_root)
@@ -360,6 +459,30 @@ where
         self
     }
 
+    /// Sets the `BeaconChain` slot clock to a new `TSlotClock` which starts at the genesis time of
+    /// the chain, with slots of `slot_duration`.
+    ///
+    /// This allows any `SlotClock` implementation to be used (e.g., `SystemTimeSlotClock` for a
+    /// live chain or `ManualSlotClock` for tests) without the caller reading the genesis time.
+    ///
+    /// Requires the state to be initialized.
+    pub fn genesis_slot_clock(self, slot_duration: Duration) -> Result<Self, String> {
+        let genesis_time = self
+            .finalized_snapshot
+            .as_ref()
+            .ok_or_else(|| "genesis_slot_clock requires an initialized state")?
+            .beacon_state
+            .genesis_time;
+
+        let slot_clock = TSlotClock::new(
+            self.spec.genesis_slot,
+            Duration::from_secs(genesis_time),
+            slot_duration,
+        );
+
+        Ok(self.slot_clock(slot_clock))
+    }
+
     /// Creates a new, empty operation pool.
     fn empty_op_pool(mut self) -> Self {
         self.op_pool = Some(OperationPool::new());
@@ -385,6 +508,67 @@ where
             .log
             .ok_or_else(|| "Cannot build without a logger".to_string())?;
 
+        let input_recorder = self
+            .input_recording_path
+            .as_ref()
+            .map(|path| {
+                info!(
+                    log,
+                    "Recording chain inputs";
+                    "path" => format!("{:?}", path)
+                );
+                InputRecorder::open(path, log.clone())
+            })
+            .transpose()?;
+
+        let differential_tester = self
+            .differential_test_command
+            .clone()
+            .map(|command| {
+                let divergence_dir = self
+                    .data_dir
+                    .as_ref()
+                    .ok_or_else(|| "Differential testing requires a data_dir".to_string())?
+                    .join(DIVERGENCE_DIR);
+                info!(
+                    log,
+                    "Comparing blocks with reference implementation";
+                    "divergence_dir" => format!("{:?}", divergence_dir),
+                    "command" => format!("{:?}", command)
+                );
+                DifferentialTester::spawn(command, divergence_dir, log.clone())
+            })
+            .transpose()?;
+
+        let event_journal = self
+            .event_journal_retention
+            .map(|retention| {
+                let path = self
+                    .data_dir
+                    .as_ref()
+                    .ok_or_else(|| "The event journal requires a data_dir".to_string())?
+                    .join(EVENT_JOURNAL_FILENAME);
+                info!(
+                    log,
+                    "Journaling chain events";
+                    "retention_secs" => retention.as_secs(),
+                    "path" => format!("{:?}", path)
+                );
+                EventJournal::open(&path, retention)
+            })
+            .transpose()?;
+
+        let attestation_self_check = if self.attestation_self_check {
+            info!(
+                log,
+                "Comparing attestations with the network";
+                "warning_threshold" => DIVERGENCE_WARNING_THRESHOLD
+            );
+            Some(AttestationSelfCheck::new(log.clone()))
+        } else {
+            None
+        };
+
         // If this beacon chain is being loaded from disk, use the stored head. Otherwise, just use
         // the finalized checkpoint (which is probably genesis).
         let mut canonical_head = if let Some(head) = self.canonical_head {
@@ -403,6 +587,22 @@ where
             return Err("beacon_block.state_root != beacon_state".to_string());
         }
 
+        if let Some(recorder) = &input_recorder {
+            let time = self
+                .slot_clock
+                .as_ref()
+                .and_then(|slot_clock| slot_clock.now_duration())
+                .unwrap_or_default();
+            recorder.record(
+                time,
+                RecordedInput::Start {
+                    slot: canonical_head.beacon_block.slot(),
+                    block_root: canonical_head.beacon_block_root,
+                    state_root: canonical_head.beacon_state_root,
+                },
+            )?;
+        }
+
         let pubkey_cache_path = self
             .pubkey_cache_path
             .ok_or_else(|| "Cannot build without a pubkey cache path".to_string())?;
@@ -415,11 +615,48 @@ where
                     .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
             })?;
 
+        let store = self
+            .store
+            .ok_or_else(|| "Cannot build without store".to_string())?;
+
+        // Load the recent epoch boundary snapshots before any blocks or attestations arrive, so
+        // that they are not loaded from the store whilst processing the first of them.
+        let mut snapshot_cache = SnapshotCache::new(
+            DEFAULT_SNAPSHOT_CACHE_SIZE,
+            DEFAULT_PINNED_EPOCH_BOUNDARIES,
+            canonical_head.clone(),
+        );
+        let warm_up_start = Instant::now();
+        match epoch_boundary_snapshots(
+            &*store,
+            &canonical_head,
+            DEFAULT_PINNED_EPOCH_BOUNDARIES,
+            &self.spec,
+        ) {
+            Ok(snapshots) => {
+                let count = snapshots.len();
+                for snapshot in snapshots {
+                    snapshot_cache.insert(snapshot);
+                }
+                info!(
+                    log,
+                    "Warmed snapshot cache";
+                    "epoch_boundaries" => count,
+                    "duration_ms" => warm_up_start.elapsed().as_millis() as u64,
+                );
+            }
+            Err(e) => warn!(
+                log,
+                "Unable to warm snapshot cache";
+                "error" => e,
+            ),
+        }
+
+        let observed_caches = self.observed_caches.unwrap_or_default();
+
         let beacon_chain = BeaconChain {
             spec: self.spec,
-            store: self
-                .store
-                .ok_or_else(|| "Cannot build without store".to_string())?,
+            store,
             store_migrator: self
                 .store_migrator
                 .ok_or_else(|| "Cannot build without store migrator".to_string())?,
@@ -431,17 +668,14 @@ where
                 .ok_or_else(|| "Cannot build without op pool".to_string())?,
             // TODO: allow for persisting and loading the pool from disk.
             naive_aggregation_pool: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_attestations: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_attesters: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_aggregators: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_block_producers: <_>::default(),
+            observed_attestations: observed_caches.observed_attestations,
+            observed_attesters: observed_caches.observed_attesters,
+            observed_aggregators: observed_caches.observed_aggregators,
+            observed_block_producers: observed_caches.observed_block_producers,
+            own_publications: OwnPublications::new(log.clone()),
             eth1_chain: self.eth1_chain,
             genesis_validators_root: canonical_head.beacon_state.genesis_validators_root,
-            canonical_head: TimeoutRwLock::new(canonical_head.clone()),
+            canonical_head: TimeoutRwLock::new(canonical_head),
             genesis_block_root: self
                 .genesis_block_root
                 .ok_or_else(|| "Cannot build without a genesis block root".to_string())?,
@@ -452,13 +686,17 @@ where
                 .event_handler
                 .ok_or_else(|| "Cannot build without an event handler".to_string())?,
             head_tracker: Arc::new(self.head_tracker.unwrap_or_default()),
-            snapshot_cache: TimeoutRwLock::new(SnapshotCache::new(
-                DEFAULT_SNAPSHOT_CACHE_SIZE,
-                canonical_head,
-            )),
+            snapshot_cache: TimeoutRwLock::new(snapshot_cache),
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
+            proposer_cache: TimeoutRwLock::new(ProposerCache::new()),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             disabled_forks: self.disabled_forks,
+            attestation_inclusion_strategy: self.attestation_inclusion_strategy,
+            max_skip_slots: self.max_skip_slots,
+            input_recorder,
+            differential_tester,
+            event_journal,
+            attestation_self_check,
             log: log.clone(),
         };
 
@@ -601,20 +839,7 @@ where
     ///
     /// Requires the state to be initialized.
     pub fn testing_slot_clock(self, slot_duration: Duration) -> Result<Self, String> {
-        let genesis_time = self
-            .finalized_snapshot
-            .as_ref()
-            .ok_or_else(|| "testing_slot_clock requires an initialized state")?
-            .beacon_state
-            .genesis_time;
-
-        let slot_clock = TestingSlotClock::new(
-            Slot::new(0),
-            Duration::from_secs(genesis_time),
-            slot_duration,
-        );
-
-        Ok(self.slot_clock(slot_clock))
+        self.genesis_slot_clock(slot_duration)
     }
 }
 
@@ -643,6 +868,34 @@ where
     }
 }
 
+impl<TStore, TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec>
+    BeaconChainBuilder<
+        Witness<
+            TStore,
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            EventHandlerList<TEthSpec>,
+        >,
+    >
+where
+    TStore: Store<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TStore, TEthSpec> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec, TStore> + 'static,
+    TEthSpec: EthSpec + 'static,
+{
+    /// Adds `handler` to the handlers which receive every `BeaconChain` event, allowing any number
+    /// of handlers of differing types to be used at once.
+    pub fn add_event_handler<H: EventHandler<TEthSpec> + 'static>(mut self, handler: H) -> Self {
+        self.event_handler
+            .get_or_insert_with(EventHandlerList::new)
+            .push(handler);
+        self
+    }
+}
+
 fn genesis_block<T: EthSpec>(
     genesis_state: &mut BeaconState<T>,
     spec: &ChainSpec,
@@ -659,11 +912,79 @@ fn genesis_block<T: EthSpec>(
     Ok(genesis_block)
 }
 
+/// Loads the snapshot of the latest block in each of the `epochs` epochs prior to the epoch of
+/// `head` from `store`, with all state caches built.
+///
+/// Stops at the first epoch which is finalized or whose block or state is not in `store`, so
+/// fewer than `epochs` snapshots may be returned.
+fn epoch_boundary_snapshots<E: EthSpec, S: Store<E>>(
+    store: &S,
+    head: &BeaconSnapshot<E>,
+    epochs: usize,
+    spec: &ChainSpec,
+) -> Result<Vec<BeaconSnapshot<E>>, String> {
+    let slots_per_epoch = E::slots_per_epoch();
+    let head_state = &head.beacon_state;
+    let head_epoch = head.beacon_block.slot().epoch(slots_per_epoch);
+    let finalized_slot = head_state
+        .finalized_checkpoint
+        .epoch
+        .start_slot(slots_per_epoch);
+
+    let mut snapshots: Vec<BeaconSnapshot<E>> = vec![];
+    for epoch in (1..=epochs as u64).filter_map(|i| head_epoch.as_u64().checked_sub(i)) {
+        let beacon_block_root =
+            match head_state.get_block_root(Epoch::new(epoch).end_slot(slots_per_epoch)) {
+                Ok(root) => *root,
+                // The epoch is too old to be in the `block_roots` of the head state.
+                Err(_) => break,
+            };
+
+        // An epoch without blocks has the same latest block as the epoch before it.
+        if beacon_block_root == head.beacon_block_root
+            || snapshots
+                .iter()
+                .any(|snapshot| snapshot.beacon_block_root == beacon_block_root)
+        {
+            continue;
+        }
+
+        let beacon_block = match store
+            .get::<SignedBeaconBlock<E>>(&beacon_block_root)
+            .map_err(|e| format!("DB error when reading block: {:?}", e))?
+        {
+            Some(block) if block.slot() >= finalized_slot => block,
+            _ => break,
+        };
+        let beacon_state_root = beacon_block.state_root();
+        let mut beacon_state = match store
+            .get_state(&beacon_state_root, Some(beacon_block.slot()))
+            .map_err(|e| format!("DB error when reading state: {:?}", e))?
+        {
+            Some(state) => state,
+            None => break,
+        };
+        beacon_state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Failed to build state caches: {:?}", e))?;
+
+        snapshots.push(BeaconSnapshot {
+            beacon_block,
+            beacon_block_root,
+            beacon_state,
+            beacon_state_root,
+        });
+    }
+
+    Ok(snapshots)
+}
+
 #[cfg(not(debug_assertions))]
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::migrate::{MemoryStore, NullMigrator};
+    use crate::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
     use eth2_hashing::hash;
     use genesis::{generate_deterministic_keypairs, interop_genesis_state};
     use sloggers::{null::NullLoggerBuilder, Build};
@@ -803,4 +1124,42 @@ mod test {
             "validator count should be correct"
         );
     }
+
+    #[test]
+    fn epoch_boundary_snapshots_are_loaded() {
+        let slots_per_epoch = TestEthSpec::slots_per_epoch();
+        let harness = BeaconChainHarness::new(MinimalEthSpec, generate_deterministic_keypairs(8));
+
+        // Without attestations the chain does not finalize, so no snapshots are excluded.
+        harness.advance_slot();
+        harness.extend_chain(
+            slots_per_epoch as usize * 3,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(vec![]),
+        );
+
+        let head = harness.chain.head().expect("should get head");
+        assert_eq!(head.beacon_block.slot(), Slot::new(slots_per_epoch * 3));
+
+        let snapshots = epoch_boundary_snapshots(&*harness.chain.store, &head, 2, &harness.spec)
+            .expect("should load snapshots");
+        let slots: Vec<_> = snapshots
+            .iter()
+            .map(|snapshot| snapshot.beacon_block.slot())
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                Slot::new(slots_per_epoch * 3 - 1),
+                Slot::new(slots_per_epoch * 2 - 1)
+            ]
+        );
+        for snapshot in &snapshots {
+            assert_eq!(snapshot.beacon_state.slot, snapshot.beacon_block.slot());
+            assert_eq!(
+                snapshot.beacon_state.canonical_root(),
+                snapshot.beacon_state_root
+            );
+        }
+    }
 }
@@ -0,0 +1,357 @@
+//! Compares the state transition of each imported block against an external reference
+//! implementation (e.g., the Python executable spec), for use on canary nodes.
+//!
+//! After a block passes `per_block_processing` and its state root check, the pre-state and block
+//! are queued for a background thread which writes them to SSZ files and runs the reference
+//! command with their paths as arguments. The pre-state is the post-state of the parent block,
+//! before any slot processing, so the command must apply the full `state_transition` (i.e.,
+//! `process_slots` up to the block slot, then `process_block`):
+//!
+//! ```ignore
+//! <command> <pre_state.ssz> <block.ssz>
+//! ```
+//!
+//! The command must print the `0x`-prefixed root of the post-state to stdout and exit
+//! successfully. Any other outcome is a divergence: a different root, a non-zero exit (i.e., the
+//! reference rejected a block which Lighthouse accepted) or unparseable output. Each divergence is
+//! logged at `crit`, counted in the `beacon_differential_test_divergences_total` metric and its
+//! input files are kept in the divergence directory so it may be reproduced.
+//!
+//! Reference implementations are typically much slower than Lighthouse, so blocks are dropped
+//! (and counted as skipped) rather than delaying block import when the queue is full. A place in
+//! the queue is reserved before the pre-state is cloned, so a skipped block costs nothing.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use slog::{crit, debug, error, Logger};
+use ssz::Encode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use types::{BeaconState, EthSpec, Hash256, SignedBeaconBlock};
+
+/// The number of blocks which may wait for the reference implementation.
+pub const DIFFERENTIAL_TEST_QUEUE_SIZE: usize = 16;
+
+/// A block and the state to which it was applied.
+struct TestCase<E: EthSpec> {
+    block_root: Hash256,
+    block: SignedBeaconBlock<E>,
+    pre_state: BeaconState<E>,
+}
+
+/// The result of running the reference implementation on a `TestCase`.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// The reference implementation produced the same post-state root as Lighthouse.
+    Agreed,
+    /// The reference implementation did not produce the same post-state root as Lighthouse.
+    Diverged(String),
+}
+
+/// Queues blocks for comparison against the reference implementation.
+pub struct DifferentialTester<E: EthSpec> {
+    sender: Mutex<SyncSender<TestCase<E>>>,
+    /// The number of reserved, queued or running test cases.
+    pending: Arc<AtomicUsize>,
+    log: Logger,
+}
+
+impl<E: EthSpec> DifferentialTester<E> {
+    /// Spawns the thread which runs `command`, keeping the inputs of diverging blocks in
+    /// `divergence_dir`.
+    pub fn spawn(command: PathBuf, divergence_dir: PathBuf, log: Logger) -> Result<Self, String> {
+        fs::create_dir_all(&divergence_dir).map_err(|e| {
+            format!(
+                "Unable to create divergence directory {:?}: {:?}",
+                divergence_dir, e
+            )
+        })?;
+
+        let (sender, receiver) = sync_channel(DIFFERENTIAL_TEST_QUEUE_SIZE);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let thread_pending = pending.clone();
+        let thread_log = log.clone();
+        thread::Builder::new()
+            .name("differential_test".to_string())
+            .spawn(move || {
+                run_test_cases(
+                    receiver,
+                    &thread_pending,
+                    &command,
+                    &divergence_dir,
+                    &thread_log,
+                )
+            })
+            .map_err(|e| format!("Unable to spawn differential test thread: {:?}", e))?;
+
+        Ok(Self {
+            sender: Mutex::new(sender),
+            pending,
+            log,
+        })
+    }
+
+    /// Reserves a place in the queue for the block with `block_root`, so that its pre-state need
+    /// only be cloned if it will be compared. Returns `None`, skipping the block, if the queue is
+    /// full.
+    pub fn reserve(&self, block_root: Hash256) -> Option<Reservation<E>> {
+        if self.pending.fetch_add(1, Ordering::SeqCst) >= DIFFERENTIAL_TEST_QUEUE_SIZE {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            metrics::inc_counter(&metrics::DIFFERENTIAL_TEST_SKIPPED);
+            debug!(
+                self.log,
+                "Skipping differential test";
+                "reason" => "queue full",
+                "block_root" => format!("{}", block_root)
+            );
+            return None;
+        }
+
+        Some(Reservation {
+            tester: self,
+            block_root,
+            submitted: false,
+        })
+    }
+}
+
+/// A place in the queue of a `DifferentialTester`, which is released if it is dropped without
+/// being submitted (e.g., because the block was invalid).
+pub struct Reservation<'a, E: EthSpec> {
+    tester: &'a DifferentialTester<E>,
+    block_root: Hash256,
+    submitted: bool,
+}
+
+impl<'a, E: EthSpec> Reservation<'a, E> {
+    /// Queues `block`, which was applied to `pre_state`, for comparison.
+    pub fn submit(mut self, block: SignedBeaconBlock<E>, pre_state: BeaconState<E>) {
+        let test_case = TestCase {
+            block_root: self.block_root,
+            block,
+            pre_state,
+        };
+
+        // The reservation ensures there is space in the queue, so it can only fail to send if
+        // the thread has stopped.
+        match self.tester.sender.lock().try_send(test_case) {
+            Ok(()) => self.submitted = true,
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                error!(
+                    self.tester.log,
+                    "Differential test thread has stopped";
+                    "block_root" => format!("{}", self.block_root)
+                );
+            }
+        }
+    }
+}
+
+impl<'a, E: EthSpec> Drop for Reservation<'a, E> {
+    fn drop(&mut self) {
+        // Once submitted, the place is released by the test thread when the test case is done.
+        if !self.submitted {
+            self.tester.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Runs each `TestCase` from `receiver` until all senders are dropped, releasing its place in the
+/// queue from `pending` once it is done.
+fn run_test_cases<E: EthSpec>(
+    receiver: Receiver<TestCase<E>>,
+    pending: &AtomicUsize,
+    command: &Path,
+    divergence_dir: &Path,
+    log: &Logger,
+) {
+    for test_case in receiver {
+        let block_root = test_case.block_root;
+        let slot = test_case.block.slot();
+        let case_dir = divergence_dir.join(format!("{:?}", block_root));
+
+        let outcome =
+            write_test_case(&test_case, &case_dir).and_then(|(pre_state_path, block_path)| {
+                run_reference(
+                    command,
+                    &pre_state_path,
+                    &block_path,
+                    test_case.block.state_root(),
+                )
+            });
+
+        match outcome {
+            Ok(Outcome::Agreed) => {
+                metrics::inc_counter(&metrics::DIFFERENTIAL_TEST_AGREEMENTS);
+                debug!(
+                    log,
+                    "Reference implementation agrees";
+                    "slot" => slot,
+                    "block_root" => format!("{}", block_root)
+                );
+                let _ = fs::remove_dir_all(&case_dir);
+            }
+            Ok(Outcome::Diverged(reason)) => {
+                metrics::inc_counter(&metrics::DIFFERENTIAL_TEST_DIVERGENCES);
+                crit!(
+                    log,
+                    "Reference implementation diverged";
+                    "reason" => reason,
+                    "inputs" => format!("{:?}", case_dir),
+                    "slot" => slot,
+                    "block_root" => format!("{}", block_root)
+                );
+            }
+            Err(e) => {
+                error!(
+                    log,
+                    "Unable to run differential test";
+                    "error" => e,
+                    "block_root" => format!("{}", block_root)
+                );
+                let _ = fs::remove_dir_all(&case_dir);
+            }
+        }
+
+        drop(test_case);
+        pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Writes the pre-state and block of `test_case` to `dir`, returning their paths.
+fn write_test_case<E: EthSpec>(
+    test_case: &TestCase<E>,
+    dir: &Path,
+) -> Result<(PathBuf, PathBuf), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Unable to create {:?}: {:?}", dir, e))?;
+
+    let pre_state_path = dir.join("pre_state.ssz");
+    let block_path = dir.join("block.ssz");
+
+    fs::write(&pre_state_path, test_case.pre_state.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", pre_state_path, e))?;
+    fs::write(&block_path, test_case.block.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", block_path, e))?;
+
+    Ok((pre_state_path, block_path))
+}
+
+/// Runs `command` on the given inputs, comparing the post-state root it prints with
+/// `expected_state_root`.
+///
+/// Returns an error only if `command` could not be run at all.
+pub fn run_reference(
+    command: &Path,
+    pre_state_path: &Path,
+    block_path: &Path,
+    expected_state_root: Hash256,
+) -> Result<Outcome, String> {
+    let output = Command::new(command)
+        .arg(pre_state_path)
+        .arg(block_path)
+        .output()
+        .map_err(|e| format!("Unable to run {:?}: {:?}", command, e))?;
+
+    if !output.status.success() {
+        return Ok(Outcome::Diverged(format!(
+            "reference exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let reference_root = stdout.trim();
+
+    let root = if reference_root.starts_with("0x") {
+        reference_root[2..].parse::<Hash256>().ok()
+    } else {
+        None
+    };
+
+    Ok(match root {
+        Some(root) if root == expected_state_root => Outcome::Agreed,
+        Some(root) => Outcome::Diverged(format!(
+            "reference state root {:?} does not match {:?}",
+            root, expected_state_root
+        )),
+        None => Outcome::Diverged(format!("invalid reference output: {:?}", reference_root)),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use std::os::unix::fs::PermissionsExt;
+    use types::MinimalEthSpec;
+
+    /// Writes an executable shell script containing `body` to `dir`.
+    fn script(dir: &Path, body: &str) -> PathBuf {
+        let path = dir.join("reference.sh");
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn reservations_are_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = NullLoggerBuilder.build().expect("logger should build");
+        let tester = DifferentialTester::<MinimalEthSpec>::spawn(
+            script(dir.path(), "exit 0"),
+            dir.path().join("divergences"),
+            log,
+        )
+        .unwrap();
+        let root = Hash256::zero();
+
+        let reservations = (0..DIFFERENTIAL_TEST_QUEUE_SIZE)
+            .map(|_| tester.reserve(root).expect("should reserve"))
+            .collect::<Vec<_>>();
+        assert!(tester.reserve(root).is_none(), "queue should be full");
+
+        // Unsubmitted reservations release their place in the queue.
+        drop(reservations);
+        assert!(tester.reserve(root).is_some(), "queue should have space");
+    }
+
+    #[test]
+    fn compares_reference_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let pre_state = dir.path().join("pre_state.ssz");
+        let block = dir.path().join("block.ssz");
+        let root = Hash256::repeat_byte(0x42);
+
+        let agree = script(dir.path(), &format!("echo {:?}", root));
+        assert_eq!(
+            run_reference(&agree, &pre_state, &block, root),
+            Ok(Outcome::Agreed)
+        );
+
+        let other = script(dir.path(), &format!("echo {:?}", Hash256::zero()));
+        match run_reference(&other, &pre_state, &block, root) {
+            Ok(Outcome::Diverged(_)) => {}
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        let reject = script(dir.path(), "echo invalid block >&2; exit 1");
+        match run_reference(&reject, &pre_state, &block, root) {
+            Ok(Outcome::Diverged(reason)) => assert!(reason.contains("invalid block")),
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        let garbage = script(dir.path(), "echo hello");
+        match run_reference(&garbage, &pre_state, &block, root) {
+            Ok(Outcome::Diverged(_)) => {}
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        assert!(run_reference(&dir.path().join("missing"), &pre_state, &block, root).is_err());
+    }
+}
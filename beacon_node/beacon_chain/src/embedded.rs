@@ -0,0 +1,194 @@
+//! Provides `EmbeddedChain`, a minimal facade over `BeaconChain` for applications which embed this
+//! crate as a library (e.g., to replay a chain for research or indexing) rather than running a
+//! full beacon node.
+//!
+//! ## Stability
+//!
+//! The items in this module are intended to remain stable across minor releases of this crate,
+//! unlike the rest of its API which changes freely to suit the beacon node. The underlying
+//! `BeaconChain` is accessible via `EmbeddedChain::chain`, but it carries no such guarantee.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! let chain = EmbeddedChain::from_genesis(store, spec, genesis_state, log, data_dir)?;
+//!
+//! for block in blocks {
+//!     chain.process_block(block)?;
+//! }
+//!
+//! let head = chain.head()?;
+//! let finalized = chain.finalized_checkpoint()?;
+//! ```
+
+use crate::{
+    builder::{BeaconChainBuilder, Witness},
+    eth1_chain::CachingEth1Backend,
+    events::NullEventHandler,
+    migrate::{Migrate, NullMigrator},
+    AttestationError, BeaconChain, BeaconChainError, BlockError, HeadInfo,
+};
+use slog::Logger;
+use slot_clock::{SlotClock, TestingSlotClock};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use store::Store;
+use types::{Attestation, BeaconState, ChainSpec, Checkpoint, EthSpec, Hash256, SignedBeaconBlock};
+
+/// The `BeaconChainTypes` used by an `EmbeddedChain` backed by the store `S`.
+///
+/// The slot clock is advanced as blocks are processed, rather than following the wall clock, and
+/// there is no eth1 backend, so the chain cannot produce blocks. Since the store is never pruned,
+/// only stores supported by the `NullMigrator` (i.e., `MemoryStore` and `SimpleDiskStore`) may be
+/// used.
+pub type EmbeddedChainTypes<S, E> =
+    Witness<S, NullMigrator, TestingSlotClock, CachingEth1Backend<E, S>, E, NullEventHandler<E>>;
+
+/// A `BeaconChain` which can be fed blocks and attestations and queried for its head and finality,
+/// without any of the networking, HTTP or eth1 components of the beacon node.
+pub struct EmbeddedChain<S: Store<E> + 'static, E: EthSpec>
+where
+    NullMigrator: Migrate<S, E>,
+{
+    chain: BeaconChain<EmbeddedChainTypes<S, E>>,
+}
+
+impl<S: Store<E> + 'static, E: EthSpec> EmbeddedChain<S, E>
+where
+    NullMigrator: Migrate<S, E>,
+{
+    /// Starts a new chain from `genesis_state`, which is written to `store`.
+    ///
+    /// The `data_dir` is used to persist caches which are not kept in the `store`.
+    pub fn from_genesis(
+        store: Arc<S>,
+        spec: ChainSpec,
+        genesis_state: BeaconState<E>,
+        log: Logger,
+        data_dir: PathBuf,
+    ) -> Result<Self, String> {
+        let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+
+        BeaconChainBuilder::new(E::default())
+            .logger(log)
+            .custom_spec(spec)
+            .store(store)
+            .store_migrator(NullMigrator)
+            .data_dir(data_dir)
+            .genesis_state(genesis_state)?
+            .no_eth1_backend()
+            .null_event_handler()
+            .testing_slot_clock(slot_duration)?
+            .reduced_tree_fork_choice()?
+            .build()
+            .map(|chain| Self { chain })
+    }
+
+    /// Resumes a chain which was previously persisted to `store` (e.g., by a beacon node or by
+    /// `Self::persist`).
+    pub fn resume(
+        store: Arc<S>,
+        spec: ChainSpec,
+        log: Logger,
+        data_dir: PathBuf,
+    ) -> Result<Self, String> {
+        let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+
+        let chain = BeaconChainBuilder::new(E::default())
+            .logger(log)
+            .custom_spec(spec)
+            .store(store)
+            .store_migrator(NullMigrator)
+            .data_dir(data_dir)
+            .resume_from_db()?
+            .no_eth1_backend()
+            .null_event_handler()
+            .testing_slot_clock(slot_duration)?
+            .reduced_tree_fork_choice()?
+            .build()?;
+
+        // Blocks at or before the head have already been seen, so the clock may start there.
+        let head_slot = chain.head_info().map_err(|e| format!("{:?}", e))?.slot;
+        if head_slot > chain.slot().map_err(|e| format!("{:?}", e))? {
+            chain.slot_clock.set_slot(head_slot.as_u64());
+        }
+
+        Ok(Self { chain })
+    }
+
+    /// Verifies and imports `block`, then updates the head. Returns the root of the block.
+    ///
+    /// The chain's clock is advanced to the slot of the block if it is behind.
+    pub fn process_block(&self, block: SignedBeaconBlock<E>) -> Result<Hash256, BlockError> {
+        self.advance_clock_to(block.slot().as_u64());
+
+        let block_root = self.chain.process_block(block)?;
+        self.chain.fork_choice()?;
+
+        Ok(block_root)
+    }
+
+    /// Verifies `attestation` and applies it to fork choice, then updates the head.
+    ///
+    /// Only unaggregated attestations (i.e., with a single aggregation bit set) from the current
+    /// or previous slot are accepted, as per the gossip rules. There is no need to supply the
+    /// attestations included in blocks, since they are applied when the block is imported.
+    pub fn process_attestation(&self, attestation: Attestation<E>) -> Result<(), AttestationError> {
+        let verified = self
+            .chain
+            .verify_unaggregated_attestation_for_gossip(attestation)?;
+        self.chain.apply_attestation_to_fork_choice(&verified)?;
+        self.chain.fork_choice()?;
+
+        Ok(())
+    }
+
+    /// Returns a summary of the head block and state.
+    pub fn head(&self) -> Result<HeadInfo, BeaconChainError> {
+        self.chain.head_info()
+    }
+
+    /// Returns the finalized checkpoint of the head state.
+    pub fn finalized_checkpoint(&self) -> Result<Checkpoint, BeaconChainError> {
+        self.head().map(|head| head.finalized_checkpoint)
+    }
+
+    /// Returns the current justified checkpoint of the head state.
+    pub fn justified_checkpoint(&self) -> Result<Checkpoint, BeaconChainError> {
+        self.head().map(|head| head.current_justified_checkpoint)
+    }
+
+    /// Writes the head and fork choice to the store, so the chain may later be resumed with
+    /// `Self::resume`.
+    pub fn persist(&self) -> Result<(), BeaconChainError> {
+        self.chain.persist_head_and_fork_choice()
+    }
+
+    /// Sets the chain's clock to `since_unix_epoch`, which may be in the past.
+    ///
+    /// Useful when replaying inputs which must be processed at the time they were received.
+    pub fn set_time(&self, since_unix_epoch: Duration) {
+        self.chain.slot_clock.set_current_time(since_unix_epoch);
+    }
+
+    /// Returns the underlying `BeaconChain`.
+    ///
+    /// Unlike the rest of this module, the `BeaconChain` API is not guaranteed to be stable.
+    pub fn chain(&self) -> &BeaconChain<EmbeddedChainTypes<S, E>> {
+        &self.chain
+    }
+
+    /// Advances the slot clock to `slot`, if it is behind.
+    fn advance_clock_to(&self, slot: u64) {
+        let behind = self
+            .chain
+            .slot_clock
+            .now()
+            .map_or(true, |current_slot| current_slot.as_u64() < slot);
+
+        if behind {
+            self.chain.slot_clock.set_slot(slot);
+        }
+    }
+}
@@ -60,6 +60,7 @@ pub enum BeaconChainError {
     SszTypesError(SszTypesError),
     CanonicalHeadLockTimeout,
     AttestationCacheLockTimeout,
+    ProposerCacheLockTimeout,
     ValidatorPubkeyCacheLockTimeout,
     IncorrectStateForAttestation(RelativeEpochError),
     InvalidValidatorPubkeyBytes(DecodeError),
@@ -1,5 +1,5 @@
 use crate::metrics;
-use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
+use eth1::{Config as Eth1Config, Eth1Block, ExpectedDeposits, Service as HttpService};
 use eth2_hashing::hash;
 use slog::{debug, error, trace, Logger};
 use ssz::{Decode, Encode};
@@ -45,6 +45,14 @@ pub enum Error {
     UnknownPreviousEth1BlockHash,
     /// An arithmetic error occurred.
     ArithError(safe_arith::ArithError),
+    /// The root of the deposits in the local cache does not match the `Eth1Data` in a state.
+    ///
+    /// The deposit cache is likely corrupt.
+    DepositRootMismatch {
+        deposit_count: u64,
+        state_root: Hash256,
+        cache_root: Hash256,
+    },
 }
 
 impl From<safe_arith::ArithError> for Error {
@@ -138,6 +146,42 @@ where
         }
     }
 
+    /// Prepares the `Deposits` which `deposits_for_block_inclusion` is expected to return for the
+    /// next block built upon `state`, so that they need not be generated whilst the block is
+    /// produced.
+    ///
+    /// Assumes that the eth1 vote of the next block does not change `state.eth1_data`. If it does,
+    /// the prepared deposits are not used and `deposits_for_block_inclusion` generates them.
+    pub fn expect_deposits_for_next_block(&self, state: &BeaconState<E>) -> Result<(), Error> {
+        if self.use_dummy_backend {
+            return Ok(());
+        }
+
+        self.backend.expect_deposits(state)
+    }
+
+    /// Checks that the root of the first `eth1_data.deposit_count` deposits in the backend's cache
+    /// matches `eth1_data.deposit_root`.
+    ///
+    /// Passes if the backend is unable to compute the root (e.g., the cache does not yet contain
+    /// enough deposits, or the dummy backend is in use).
+    pub fn verify_deposit_root(&self, eth1_data: &Eth1Data) -> Result<(), Error> {
+        if self.use_dummy_backend {
+            return Ok(());
+        }
+
+        match self.backend.deposit_root(eth1_data.deposit_count) {
+            Some(cache_root) if cache_root != eth1_data.deposit_root => {
+                Err(Error::DepositRootMismatch {
+                    deposit_count: eth1_data.deposit_count,
+                    state_root: eth1_data.deposit_root,
+                    cache_root,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Instantiate `Eth1Chain` from a persisted `SszEth1`.
     ///
     /// The `Eth1Chain` will have the same caches as the persisted `SszEth1`.
@@ -190,6 +234,15 @@ pub trait Eth1ChainBackend<T: EthSpec, S: Store<T>>: Sized + Send + Sync {
         spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error>;
 
+    /// Informs the backend that `queued_deposits` is expected to be called for `beacon_state`
+    /// with an eth1 vote that does not change `beacon_state.eth1_data`, allowing it to prepare
+    /// those deposits in advance.
+    fn expect_deposits(&self, beacon_state: &BeaconState<T>) -> Result<(), Error>;
+
+    /// Returns the root of the deposit tree containing the first `deposit_count` deposits, if
+    /// known.
+    fn deposit_root(&self, deposit_count: u64) -> Option<Hash256>;
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -236,6 +289,16 @@ impl<T: EthSpec, S: Store<T>> Eth1ChainBackend<T, S> for DummyEth1ChainBackend<T
         Ok(vec![])
     }
 
+    /// The dummy back-end has no deposits to prepare.
+    fn expect_deposits(&self, _state: &BeaconState<T>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// The dummy back-end has no deposits.
+    fn deposit_root(&self, _deposit_count: u64) -> Option<Hash256> {
+        None
+    }
+
     /// Return empty Vec<u8> for dummy backend.
     fn as_bytes(&self) -> Vec<u8> {
         Vec::new()
@@ -376,29 +439,38 @@ impl<T: EthSpec, S: Store<T>> Eth1ChainBackend<T, S> for CachingEth1Backend<T, S
         eth1_data_vote: &Eth1Data,
         _spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error> {
-        let deposit_index = state.eth1_deposit_index;
-        let deposit_count = if let Some(new_eth1_data) = get_new_eth1_data(state, eth1_data_vote)? {
-            new_eth1_data.deposit_count
-        } else {
-            state.eth1_data.deposit_count
-        };
-
-        match deposit_index.cmp(&deposit_count) {
-            Ordering::Greater => Err(Error::DepositIndexTooHigh),
-            Ordering::Equal => Ok(vec![]),
-            Ordering::Less => {
-                let next = deposit_index;
-                let last = std::cmp::min(deposit_count, next + T::MaxDeposits::to_u64());
+        match deposit_range(state, eth1_data_vote)? {
+            None => Ok(vec![]),
+            Some(range) => self
+                .core
+                .deposits()
+                .read()
+                .cache
+                .get_deposits(
+                    range.start,
+                    range.end,
+                    range.deposit_count,
+                    DEPOSIT_TREE_DEPTH,
+                )
+                .map_err(|e| Error::BackendError(format!("Failed to get deposits: {:?}", e)))
+                .map(|(_deposit_root, deposits)| deposits),
+        }
+    }
 
-                self.core
-                    .deposits()
-                    .read()
-                    .cache
-                    .get_deposits(next, last, deposit_count, DEPOSIT_TREE_DEPTH)
-                    .map_err(|e| Error::BackendError(format!("Failed to get deposits: {:?}", e)))
-                    .map(|(_deposit_root, deposits)| deposits)
-            }
+    fn expect_deposits(&self, state: &BeaconState<T>) -> Result<(), Error> {
+        if let Some(range) = deposit_range(state, &state.eth1_data)? {
+            self.core.set_expected_deposits(range);
         }
+
+        Ok(())
+    }
+
+    fn deposit_root(&self, deposit_count: u64) -> Option<Hash256> {
+        self.core
+            .deposits()
+            .read()
+            .cache
+            .get_deposit_root_by_count(deposit_count)
     }
 
     /// Return encoded byte representation of the block and deposit caches.
@@ -482,6 +554,31 @@ fn find_winning_vote(valid_votes: Eth1DataVoteCount) -> Option<Eth1Data> {
         .map(|((eth1_data, _), _)| eth1_data.clone())
 }
 
+/// Returns the range of deposits which must be included in a block built upon `state` with
+/// `eth1_data_vote`, along with the deposit count they are proved against, or `None` if there are
+/// no such deposits.
+fn deposit_range<T: EthSpec>(
+    state: &BeaconState<T>,
+    eth1_data_vote: &Eth1Data,
+) -> Result<Option<ExpectedDeposits>, Error> {
+    let deposit_index = state.eth1_deposit_index;
+    let deposit_count = if let Some(new_eth1_data) = get_new_eth1_data(state, eth1_data_vote)? {
+        new_eth1_data.deposit_count
+    } else {
+        state.eth1_data.deposit_count
+    };
+
+    match deposit_index.cmp(&deposit_count) {
+        Ordering::Greater => Err(Error::DepositIndexTooHigh),
+        Ordering::Equal => Ok(None),
+        Ordering::Less => Ok(Some(ExpectedDeposits {
+            start: deposit_index,
+            end: std::cmp::min(deposit_count, deposit_index + T::MaxDeposits::to_u64()),
+            deposit_count,
+        })),
+    }
+}
+
 /// Returns `int` as little-endian bytes with a length of 32.
 fn int_to_bytes32(int: u64) -> Vec<u8> {
     let mut vec = int.to_le_bytes().to_vec();
@@ -715,6 +812,49 @@ mod test {
             })
         }
 
+        #[test]
+        fn verify_deposit_root() {
+            let spec = &E::default_spec();
+
+            let eth1_chain = get_eth1_chain();
+
+            for i in 0..4 {
+                eth1_chain
+                    .backend
+                    .core
+                    .deposits()
+                    .write()
+                    .cache
+                    .insert_log(get_deposit_log(i, spec))
+                    .expect("should insert log");
+            }
+
+            let cache_root = eth1_chain
+                .backend
+                .deposit_root(3)
+                .expect("cache should contain three deposits");
+
+            let mut eth1_data = Eth1Data {
+                deposit_root: cache_root,
+                deposit_count: 3,
+                block_hash: Hash256::zero(),
+            };
+            assert!(eth1_chain.verify_deposit_root(&eth1_data).is_ok());
+
+            eth1_data.deposit_root = Hash256::repeat_byte(42);
+            assert!(matches!(
+                eth1_chain.verify_deposit_root(&eth1_data),
+                Err(Error::DepositRootMismatch {
+                    deposit_count: 3,
+                    ..
+                })
+            ));
+
+            // The check passes if the cache has too few deposits to compute the root.
+            eth1_data.deposit_count = 5;
+            assert!(eth1_chain.verify_deposit_root(&eth1_data).is_ok());
+        }
+
         #[test]
         fn eth1_data_empty_cache() {
             let spec = &E::default_spec();
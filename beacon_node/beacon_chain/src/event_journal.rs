@@ -0,0 +1,349 @@
+//! Provides a bounded, on-disk journal of the head and finalization events of the `BeaconChain`,
+//! so that an event consumer which was disconnected (e.g., from the websocket server) can recover
+//! the events it missed.
+//!
+//! Each event is given a sequence number which increases by one with every event and persists
+//! across restarts. A consumer remembers the sequence number of the last event it received (its
+//! cursor) and, after reconnecting, requests all events after the cursor from the HTTP API.
+//!
+//! Events older than the retention period are discarded. If a consumer's cursor precedes the
+//! oldest retained event, the response says that some events were lost so the consumer may
+//! resynchronise by other means.
+//!
+//! The file contains one JSON-encoded `JournalEntry` per line.
+
+use crate::events::EventKind;
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::EthSpec;
+
+/// The name of the journal file within the beacon node data directory.
+pub const EVENT_JOURNAL_FILENAME: &str = "event_journal.jsonl";
+
+/// The number of discarded entries after which the file is rewritten without them.
+const COMPACTION_THRESHOLD: usize = 256;
+
+/// An event and the time at which it occurred.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct JournalEntry<E: EthSpec> {
+    pub sequence: u64,
+    /// Milliseconds since the UNIX epoch.
+    pub time_ms: u64,
+    pub event: EventKind<E>,
+}
+
+/// The entries after some cursor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct EventsSince<E: EthSpec> {
+    /// `true` if entries after the cursor have been discarded, so `entries` is incomplete.
+    pub events_missed: bool,
+    pub entries: Vec<JournalEntry<E>>,
+}
+
+struct Inner<E: EthSpec> {
+    file: File,
+    entries: VecDeque<JournalEntry<E>>,
+    next_sequence: u64,
+    discarded_since_compaction: usize,
+}
+
+/// Appends head and finalization events to a file, retaining those from the last `retention`.
+pub struct EventJournal<E: EthSpec> {
+    path: PathBuf,
+    retention: Duration,
+    inner: Mutex<Inner<E>>,
+}
+
+impl<E: EthSpec> EventJournal<E> {
+    /// Opens the journal at `path`, creating it if it does not exist.
+    ///
+    /// A partially-written final entry (e.g., if the node crashed) is ignored.
+    pub fn open(path: &Path, retention: Duration) -> Result<Self, String> {
+        let mut entries = VecDeque::new();
+        if path.exists() {
+            let file = File::open(path)
+                .map_err(|e| format!("Unable to open event journal {:?}: {:?}", path, e))?;
+            for line in BufReader::new(file).lines() {
+                let line =
+                    line.map_err(|e| format!("Unable to read event journal {:?}: {:?}", path, e))?;
+                match serde_json::from_str(&line) {
+                    Ok(entry) => entries.push_back(entry),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let next_sequence = entries.back().map_or(0, |entry| entry.sequence + 1);
+        let journal = Self {
+            path: path.to_path_buf(),
+            retention,
+            inner: Mutex::new(Inner {
+                file: Self::open_for_append(path)?,
+                entries,
+                next_sequence,
+                discarded_since_compaction: 0,
+            }),
+        };
+
+        // Remove any partial entry and any entries which expired whilst the node was stopped.
+        {
+            let mut inner = journal.inner.lock();
+            journal.discard_expired(&mut inner, unix_millis());
+            journal.compact(&mut inner)?;
+        }
+
+        Ok(journal)
+    }
+
+    /// Returns `true` if `event` is one of the kinds of event retained by the journal.
+    pub fn is_journaled(event: &EventKind<E>) -> bool {
+        match event {
+            EventKind::BeaconHeadChanged { .. } | EventKind::BeaconFinalization { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Appends `event`, if it is of a kind retained by the journal, returning its sequence number.
+    pub fn append(&self, event: &EventKind<E>) -> Result<Option<u64>, String> {
+        self.append_at(unix_millis(), event)
+    }
+
+    fn append_at(&self, time_ms: u64, event: &EventKind<E>) -> Result<Option<u64>, String> {
+        if !Self::is_journaled(event) {
+            return Ok(None);
+        }
+
+        let mut inner = self.inner.lock();
+        let entry = JournalEntry {
+            sequence: inner.next_sequence,
+            time_ms,
+            event: event.clone(),
+        };
+
+        let mut line = serde_json::to_vec(&entry)
+            .map_err(|e| format!("Unable to serialize event: {:?}", e))?;
+        line.push(b'\n');
+        inner
+            .file
+            .write_all(&line)
+            .map_err(|e| format!("Unable to write event journal: {:?}", e))?;
+
+        inner.next_sequence += 1;
+        inner.entries.push_back(entry);
+
+        self.discard_expired(&mut inner, time_ms);
+        if inner.discarded_since_compaction >= COMPACTION_THRESHOLD {
+            self.compact(&mut inner)?;
+        }
+
+        Ok(Some(inner.next_sequence - 1))
+    }
+
+    /// Returns the retained entries with a sequence number greater than `cursor`, or all retained
+    /// entries if `cursor` is `None`.
+    pub fn events_since(&self, cursor: Option<u64>) -> EventsSince<E> {
+        let inner = self.inner.lock();
+        let first_wanted = cursor.map_or(0, |cursor| cursor.saturating_add(1));
+        let oldest_retained = inner
+            .entries
+            .front()
+            .map_or(inner.next_sequence, |entry| entry.sequence);
+
+        EventsSince {
+            events_missed: cursor.is_some() && first_wanted < oldest_retained,
+            entries: inner
+                .entries
+                .iter()
+                .filter(|entry| entry.sequence >= first_wanted)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Removes the entries which occurred more than `self.retention` before `now_ms`, always
+    /// keeping the latest entry so that sequence numbers survive a restart.
+    fn discard_expired(&self, inner: &mut Inner<E>, now_ms: u64) {
+        let oldest_ms = now_ms.saturating_sub(self.retention.as_millis() as u64);
+        while inner.entries.len() > 1
+            && inner
+                .entries
+                .front()
+                .map_or(false, |entry| entry.time_ms < oldest_ms)
+        {
+            inner.entries.pop_front();
+            inner.discarded_since_compaction += 1;
+        }
+    }
+
+    /// Atomically replaces the file with one containing only the retained entries.
+    fn compact(&self, inner: &mut Inner<E>) -> Result<(), String> {
+        let temp_path = self.path.with_extension("tmp");
+
+        let mut contents = vec![];
+        for entry in &inner.entries {
+            contents.extend_from_slice(
+                &serde_json::to_vec(entry)
+                    .map_err(|e| format!("Unable to serialize event: {:?}", e))?,
+            );
+            contents.push(b'\n');
+        }
+
+        fs::write(&temp_path, contents)
+            .map_err(|e| format!("Unable to write event journal {:?}: {:?}", temp_path, e))?;
+        fs::rename(&temp_path, &self.path)
+            .map_err(|e| format!("Unable to replace event journal {:?}: {:?}", self.path, e))?;
+
+        inner.file = Self::open_for_append(&self.path)?;
+        inner.discarded_since_compaction = 0;
+
+        Ok(())
+    }
+
+    fn open_for_append(path: &Path) -> Result<File, String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Unable to open event journal {:?}: {:?}", path, e))
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{BeaconBlock, Epoch, Hash256, MinimalEthSpec, Signature, SignedBeaconBlock};
+
+    type E = MinimalEthSpec;
+
+    fn finalization(epoch: u64) -> EventKind<E> {
+        EventKind::BeaconFinalization {
+            epoch: Epoch::new(epoch),
+            root: Hash256::zero(),
+        }
+    }
+
+    fn epochs(since: &EventsSince<E>) -> Vec<u64> {
+        since
+            .entries
+            .iter()
+            .map(|entry| match entry.event {
+                EventKind::BeaconFinalization { epoch, .. } => epoch.as_u64(),
+                _ => panic!("unexpected event"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn returns_events_after_cursor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVENT_JOURNAL_FILENAME);
+        let now = unix_millis();
+
+        let journal = EventJournal::<E>::open(&path, Duration::from_secs(60)).unwrap();
+        assert_eq!(journal.append_at(now, &finalization(1)), Ok(Some(0)));
+        assert_eq!(journal.append_at(now, &finalization(2)), Ok(Some(1)));
+
+        let rejected = EventKind::BeaconBlockRejected {
+            reason: "invalid".to_string(),
+            block: Box::new(SignedBeaconBlock {
+                message: BeaconBlock::empty(&E::default_spec()),
+                signature: Signature::empty_signature(),
+            }),
+        };
+        assert_eq!(journal.append_at(now, &rejected), Ok(None));
+
+        assert_eq!(epochs(&journal.events_since(None)), vec![1, 2]);
+        assert_eq!(epochs(&journal.events_since(Some(0))), vec![2]);
+        assert!(journal.events_since(Some(1)).entries.is_empty());
+        assert!(!journal.events_since(Some(0)).events_missed);
+
+        // Sequence numbers and entries persist across a restart.
+        drop(journal);
+        let journal = EventJournal::<E>::open(&path, Duration::from_secs(60)).unwrap();
+        assert_eq!(journal.append_at(now, &finalization(3)), Ok(Some(2)));
+        assert_eq!(epochs(&journal.events_since(Some(0))), vec![2, 3]);
+    }
+
+    #[test]
+    fn discards_expired_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVENT_JOURNAL_FILENAME);
+        let now = unix_millis();
+
+        let journal = EventJournal::<E>::open(&path, Duration::from_secs(60)).unwrap();
+        journal.append_at(now - 120_000, &finalization(1)).unwrap();
+        journal.append_at(now - 90_000, &finalization(2)).unwrap();
+        journal.append_at(now, &finalization(3)).unwrap();
+
+        let since = journal.events_since(Some(0));
+        assert!(since.events_missed);
+        assert_eq!(epochs(&since), vec![3]);
+
+        // A partially-written entry is ignored and removed.
+        drop(journal);
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"sequence\":")
+            .unwrap();
+        let journal = EventJournal::<E>::open(&path, Duration::from_secs(60)).unwrap();
+        assert_eq!(journal.append_at(now, &finalization(4)), Ok(Some(3)));
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn reads_events_from_older_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EVENT_JOURNAL_FILENAME);
+        let now = unix_millis();
+
+        // A head event written before the block summary fields were added.
+        let root = serde_json::to_string(&Hash256::repeat_byte(1)).unwrap();
+        let line = format!(
+            "{{\"sequence\":0,\"time_ms\":{},\"event\":{{\"event\":\"beacon_head_changed\",\
+                \"data\":{{\"reorg\":false,\"current_head_beacon_block_root\":{},\
+                \"previous_head_beacon_block_root\":{}}}}}}}\n",
+            now, root, root
+        );
+        fs::write(&path, line).unwrap();
+
+        let journal = EventJournal::<E>::open(&path, Duration::from_secs(60)).unwrap();
+        let since = journal.events_since(None);
+        assert_eq!(since.entries.len(), 1, "should read the older entry");
+        match since.entries[0].event {
+            EventKind::BeaconHeadChanged {
+                current_head_beacon_block_root,
+                proposer_index,
+                attestation_count,
+                deposit_count,
+                graffiti,
+                ..
+            } => {
+                assert_eq!(current_head_beacon_block_root, Hash256::repeat_byte(1));
+                assert_eq!(proposer_index, 0);
+                assert_eq!(attestation_count, 0);
+                assert_eq!(deposit_count, 0);
+                assert_eq!(graffiti, [0; 32]);
+            }
+            _ => panic!("unexpected event"),
+        }
+
+        // The entry is kept, so the next sequence number follows it.
+        assert_eq!(journal.append_at(now, &finalization(1)), Ok(Some(1)));
+    }
+}
@@ -1,20 +1,113 @@
 use serde_derive::{Deserialize, Serialize};
 use std::marker::PhantomData;
-use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock};
-pub use websocket_server::WebSocketSender;
+use types::utils::{graffiti_from_hex_str, graffiti_to_hex_str};
+use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock, SignedVoluntaryExit};
+pub use websocket_server::{Topic, WebSocketSender};
 
-pub trait EventHandler<T: EthSpec>: Sized + Send + Sync {
+pub trait EventHandler<T: EthSpec>: Send + Sync {
     fn register(&self, kind: EventKind<T>) -> Result<(), String>;
+
+    /// Registers an event which was appended to the `EventJournal` with `sequence`, so that a
+    /// consumer may later recover the events after it. By default, the `sequence` is ignored.
+    fn register_journaled(&self, kind: EventKind<T>, _sequence: u64) -> Result<(), String> {
+        self.register(kind)
+    }
 }
 
 pub struct NullEventHandler<T: EthSpec>(PhantomData<T>);
 
+/// Forwards each event to all of a list of handlers (e.g., a websocket server as well as a test
+/// recorder), allowing any number of handlers of differing types to be used with a single
+/// `BeaconChain`.
+pub struct EventHandlerList<T: EthSpec> {
+    handlers: Vec<Box<dyn EventHandler<T>>>,
+}
+
+impl<T: EthSpec> EventHandlerList<T> {
+    /// Returns a list without any handlers.
+    pub fn new() -> Self {
+        Self { handlers: vec![] }
+    }
+
+    /// Adds `handler` to the end of the list.
+    pub fn push<H: EventHandler<T> + 'static>(&mut self, handler: H) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Returns the number of handlers in the list.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if there are no handlers in the list.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl<T: EthSpec> Default for EventHandlerList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registers the event with every handler, even if some of them fail. Returns an error describing
+/// all of the failures, if any.
+impl<T: EthSpec> EventHandler<T> for EventHandlerList<T> {
+    fn register(&self, kind: EventKind<T>) -> Result<(), String> {
+        self.register_with_each(|handler| handler.register(kind.clone()))
+    }
+
+    fn register_journaled(&self, kind: EventKind<T>, sequence: u64) -> Result<(), String> {
+        self.register_with_each(|handler| handler.register_journaled(kind.clone(), sequence))
+    }
+}
+
+impl<T: EthSpec> EventHandlerList<T> {
+    fn register_with_each<F>(&self, register: F) -> Result<(), String>
+    where
+        F: Fn(&dyn EventHandler<T>) -> Result<(), String>,
+    {
+        let errors = self
+            .handlers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, handler)| {
+                register(handler.as_ref())
+                    .err()
+                    .map(|e| format!("handler {}: {}", i, e))
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Event handlers failed: {}", errors.join(", ")))
+        }
+    }
+}
+
+/// Returns the JSON of `kind` sent to websocket clients, which also contains the `sequence` of
+/// the event in the `EventJournal`, if it was journaled.
+pub fn websocket_json<T: EthSpec>(
+    kind: &EventKind<T>,
+    sequence: Option<u64>,
+) -> Result<String, String> {
+    let mut json =
+        serde_json::to_value(kind).map_err(|e| format!("Unable to serialize event: {:?}", e))?;
+    if let (Some(sequence), Some(object)) = (sequence, json.as_object_mut()) {
+        object.insert("sequence".to_string(), sequence.into());
+    }
+    Ok(json.to_string())
+}
+
 impl<T: EthSpec> EventHandler<T> for WebSocketSender<T> {
     fn register(&self, kind: EventKind<T>) -> Result<(), String> {
-        self.send_string(
-            serde_json::to_string(&kind)
-                .map_err(|e| format!("Unable to serialize event: {:?}", e))?,
-        )
+        self.send_string(&kind.topics(), websocket_json(&kind, None)?)
+    }
+
+    fn register_journaled(&self, kind: EventKind<T>, sequence: u64) -> Result<(), String> {
+        self.send_string(&kind.topics(), websocket_json(&kind, Some(sequence))?)
     }
 }
 
@@ -30,7 +123,7 @@ impl<T: EthSpec> Default for NullEventHandler<T> {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(
     bound = "T: EthSpec",
     rename_all = "snake_case",
@@ -42,6 +135,24 @@ pub enum EventKind<T: EthSpec> {
         reorg: bool,
         current_head_beacon_block_root: Hash256,
         previous_head_beacon_block_root: Hash256,
+        // The following fields were added later, so they default to zero when reading events
+        // recorded by an older version (e.g., in the `EventJournal`).
+        /// The proposer of the new head block.
+        #[serde(default)]
+        proposer_index: u64,
+        /// The number of attestations in the new head block.
+        #[serde(default)]
+        attestation_count: usize,
+        /// The number of deposits in the new head block.
+        #[serde(default)]
+        deposit_count: usize,
+        /// The graffiti of the new head block.
+        #[serde(
+            default,
+            serialize_with = "graffiti_to_hex_str",
+            deserialize_with = "graffiti_from_hex_str"
+        )]
+        graffiti: [u8; 32],
     },
     BeaconFinalization {
         epoch: Epoch,
@@ -62,4 +173,124 @@ pub enum EventKind<T: EthSpec> {
         reason: String,
         attestation: Box<Attestation<T>>,
     },
+    BeaconVoluntaryExitImported {
+        exit: Box<SignedVoluntaryExit>,
+    },
+}
+
+impl<T: EthSpec> EventKind<T> {
+    /// Returns the topics of `self`, so that it is only sent to subscribers of those topics.
+    pub fn topics(&self) -> Vec<Topic> {
+        match self {
+            EventKind::BeaconHeadChanged { reorg: true, .. } => vec![Topic::Head, Topic::Reorg],
+            EventKind::BeaconHeadChanged { reorg: false, .. } => vec![Topic::Head],
+            EventKind::BeaconFinalization { .. } => vec![Topic::FinalizedCheckpoint],
+            EventKind::BeaconBlockImported { .. } | EventKind::BeaconBlockRejected { .. } => {
+                vec![Topic::Block]
+            }
+            EventKind::BeaconAttestationImported { .. }
+            | EventKind::BeaconAttestationRejected { .. } => vec![Topic::Attestation],
+            EventKind::BeaconVoluntaryExitImported { .. } => vec![Topic::Exit],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    /// Records the epoch of each finalization event it receives.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Epoch>>>);
+
+    impl EventHandler<E> for Recorder {
+        fn register(&self, kind: EventKind<E>) -> Result<(), String> {
+            if let EventKind::BeaconFinalization { epoch, .. } = kind {
+                self.0.lock().push(epoch);
+            }
+            Ok(())
+        }
+    }
+
+    struct Failing;
+
+    impl EventHandler<E> for Failing {
+        fn register(&self, _kind: EventKind<E>) -> Result<(), String> {
+            Err("failed".to_string())
+        }
+    }
+
+    fn finalization(epoch: u64) -> EventKind<E> {
+        EventKind::BeaconFinalization {
+            epoch: Epoch::new(epoch),
+            root: Hash256::zero(),
+        }
+    }
+
+    #[test]
+    fn fans_out_to_all_handlers() {
+        let first = Recorder::default();
+        let second = Recorder::default();
+
+        let mut handlers = EventHandlerList::new();
+        handlers.push(first.clone());
+        handlers.push(Failing);
+        handlers.push(NullEventHandler::default());
+        handlers.push(second.clone());
+        assert_eq!(handlers.len(), 4);
+
+        // The failing handler must not prevent the later handlers from receiving the event.
+        assert!(handlers.register(finalization(1)).is_err());
+        assert_eq!(*first.0.lock(), vec![Epoch::new(1)]);
+        assert_eq!(*second.0.lock(), vec![Epoch::new(1)]);
+
+        assert!(EventHandlerList::<E>::new()
+            .register(finalization(2))
+            .is_ok());
+    }
+
+    #[test]
+    fn head_changes_have_reorg_topic_only_if_reorg() {
+        let head_changed = |reorg| EventKind::<E>::BeaconHeadChanged {
+            reorg,
+            current_head_beacon_block_root: Hash256::zero(),
+            previous_head_beacon_block_root: Hash256::zero(),
+            proposer_index: 0,
+            attestation_count: 0,
+            deposit_count: 0,
+            graffiti: [0; 32],
+        };
+
+        assert_eq!(head_changed(false).topics(), vec![Topic::Head]);
+        assert_eq!(head_changed(true).topics(), vec![Topic::Head, Topic::Reorg]);
+        assert_eq!(finalization(1).topics(), vec![Topic::FinalizedCheckpoint]);
+
+        // Graffiti is hex-encoded, as in blocks.
+        let json = serde_json::to_string(&head_changed(false)).unwrap();
+        assert!(json.contains(&format!("\"graffiti\":\"0x{}\"", "00".repeat(32))));
+    }
+
+    #[test]
+    fn websocket_json_contains_sequence_if_journaled() {
+        let parse = |sequence| {
+            serde_json::from_str::<serde_json::Value>(
+                &websocket_json(&finalization(3), sequence).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let journaled = parse(Some(41));
+        assert_eq!(journaled["sequence"], 41);
+        assert_eq!(journaled["event"], "beacon_finalization");
+        assert_eq!(journaled["data"]["epoch"], 3);
+
+        let unjournaled = parse(None);
+        assert!(unjournaled.get("sequence").is_none());
+        assert_eq!(unjournaled["event"], "beacon_finalization");
+    }
 }
@@ -289,12 +289,23 @@ impl CheckpointManager {
         Ok(())
     }
 
+    /// Returns the balances of the state of the block with `block_root`.
+    ///
+    /// The balances of `self.current` and `self.best` are pinned, remaining available until those
+    /// checkpoints change. This is important because every block imported whilst a new justified
+    /// checkpoint is pending (see `Self::maybe_update`) requests the balances for that same
+    /// checkpoint, and `self.balances_cache` only serves each entry once.
     fn get_balances_for_block<T: BeaconChainTypes>(
         &mut self,
         block_root: Hash256,
         chain: &BeaconChain<T>,
     ) -> Result<Vec<u64>, Error> {
-        if let Some(balances) = self.balances_cache.get(block_root) {
+        if let Some(balances) = self.pinned_balances(block_root) {
+            metrics::inc_counter(&metrics::BALANCES_CACHE_HITS);
+            metrics::inc_counter(&metrics::BALANCES_CACHE_PINNED_HITS);
+
+            Ok(balances)
+        } else if let Some(balances) = self.balances_cache.get(block_root) {
             metrics::inc_counter(&metrics::BALANCES_CACHE_HITS);
 
             Ok(balances)
@@ -313,6 +324,15 @@ impl CheckpointManager {
         }
     }
 
+    /// Returns the balances of `self.current.justified` or `self.best.justified`, if either has
+    /// the given `block_root`.
+    fn pinned_balances(&self, block_root: Hash256) -> Option<Vec<u64>> {
+        [&self.current.justified, &self.best.justified]
+            .iter()
+            .find(|checkpoint| checkpoint.root == block_root)
+            .map(|checkpoint| checkpoint.balances.clone())
+    }
+
     /// Attempts to get the block root for the given `slot`.
     ///
     /// First, the `state` is used to see if the slot is within the distance of its historical
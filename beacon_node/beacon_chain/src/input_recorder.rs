@@ -0,0 +1,357 @@
+//! Provides a record/replay facility for the consensus-relevant inputs to a `BeaconChain`.
+//!
+//! When enabled, the `BeaconChain` appends each of the following to a file, along with the time
+//! at which it occurred:
+//!
+//! - Every block supplied for import (from gossip, sync or the HTTP API).
+//! - Every attestation and aggregate supplied for verification.
+//! - Every run of fork choice and every per-slot tick.
+//!
+//! Each time the node starts, the recording is prefixed with the head it started from, since the
+//! inputs can only be replayed onto the same state.
+//!
+//! The entries are written by a dedicated thread, so recording never blocks the `BeaconChain` on
+//! the file system.
+//!
+//! The recording may then be replayed into a fresh chain (see `replay`), with the slot clock set
+//! to the recorded time before each input. This reproduces the sequence of head changes of the
+//! original node exactly, which is useful for debugging head-selection issues reported by users.
+//!
+//! The file contains one JSON-encoded `RecordedEntry` per line.
+
+use crate::embedded::EmbeddedChain;
+use crate::migrate::{Migrate, NullMigrator};
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use slog::{error, Logger};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use store::Store;
+use types::{Attestation, EthSpec, Hash256, SignedAggregateAndProof, SignedBeaconBlock, Slot};
+
+/// A consensus-relevant input to the `BeaconChain`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    bound = "E: EthSpec",
+    rename_all = "snake_case",
+    tag = "kind",
+    content = "data"
+)]
+pub enum RecordedInput<E: EthSpec> {
+    /// The head of the `BeaconChain` when it started recording.
+    Start {
+        slot: Slot,
+        block_root: Hash256,
+        state_root: Hash256,
+    },
+    /// A block supplied to `BeaconChain::process_block`.
+    Block(Box<SignedBeaconBlock<E>>),
+    /// An attestation supplied to `BeaconChain::verify_unaggregated_attestation_for_gossip`.
+    Attestation(Box<Attestation<E>>),
+    /// An aggregate supplied to `BeaconChain::verify_aggregated_attestation_for_gossip`.
+    AggregateAndProof(Box<SignedAggregateAndProof<E>>),
+    /// A call to `BeaconChain::fork_choice`.
+    ForkChoice,
+    /// A call to `BeaconChain::per_slot_task`.
+    Tick,
+}
+
+/// A `RecordedInput` and the time at which it was supplied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct RecordedEntry<E: EthSpec> {
+    /// Milliseconds since the UNIX epoch, according to the slot clock of the recording node.
+    pub time_ms: u64,
+    pub input: RecordedInput<E>,
+}
+
+/// Appends `RecordedInput`s to a file.
+///
+/// Entries are queued for a background thread which serializes and writes them. Dropping the
+/// recorder waits for the queued entries to be written.
+pub struct InputRecorder<E: EthSpec> {
+    sender: Option<Mutex<Sender<RecordedEntry<E>>>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl<E: EthSpec> InputRecorder<E> {
+    /// Opens the recording at `path` for appending, creating it if it does not exist, and spawns
+    /// the thread which writes to it.
+    pub fn open(path: &Path, log: Logger) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Unable to open input recording {:?}: {:?}", path, e))?;
+
+        let (sender, receiver) = channel();
+        let writer = thread::Builder::new()
+            .name("input_recorder".to_string())
+            .spawn(move || write_entries(receiver, file, &log))
+            .map_err(|e| format!("Unable to spawn input recorder thread: {:?}", e))?;
+
+        Ok(Self {
+            sender: Some(Mutex::new(sender)),
+            writer: Some(writer),
+        })
+    }
+
+    /// Queues `input` to be appended to the recording, stating that it was supplied at `time`
+    /// since the UNIX epoch.
+    pub fn record(&self, time: Duration, input: RecordedInput<E>) -> Result<(), String> {
+        let entry = RecordedEntry {
+            time_ms: time.as_millis() as u64,
+            input,
+        };
+
+        self.sender
+            .as_ref()
+            .ok_or_else(|| "Input recorder is closed".to_string())?
+            .lock()
+            .send(entry)
+            .map_err(|_| "Input recorder thread has stopped".to_string())
+    }
+}
+
+impl<E: EthSpec> Drop for InputRecorder<E> {
+    fn drop(&mut self) {
+        // Dropping the sender ends the writer thread once it has written every queued entry.
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Writes each entry from `receiver` to `file` until all senders are dropped, flushing whenever
+/// the queue is empty.
+fn write_entries<E: EthSpec>(receiver: Receiver<RecordedEntry<E>>, file: File, log: &Logger) {
+    let mut writer = BufWriter::new(file);
+
+    while let Ok(entry) = receiver.recv() {
+        let write_result = std::iter::once(entry)
+            .chain(receiver.try_iter())
+            .try_for_each(|entry| {
+                serde_json::to_writer(&mut writer, &entry)
+                    .map_err(|e| format!("Unable to serialize input: {:?}", e))?;
+                writer
+                    .write_all(b"\n")
+                    .map_err(|e| format!("Unable to write input recording: {:?}", e))
+            })
+            .and_then(|()| {
+                writer
+                    .flush()
+                    .map_err(|e| format!("Unable to flush input recording: {:?}", e))
+            });
+
+        if let Err(e) = write_result {
+            error!(
+                log,
+                "Stopped recording chain inputs";
+                "error" => e
+            );
+            return;
+        }
+    }
+}
+
+/// Reads all of the entries from the recording at `path`.
+///
+/// A partially-written final entry (e.g., if the recording node crashed) is ignored.
+pub fn read_recording<E: EthSpec>(path: &Path) -> Result<Vec<RecordedEntry<E>>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Unable to open input recording {:?}: {:?}", path, e))?;
+
+    let lines = BufReader::new(file)
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Unable to read input recording {:?}: {:?}", path, e))?;
+    let num_lines = lines.len();
+
+    let mut entries = Vec::with_capacity(num_lines);
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if i + 1 == num_lines => break,
+            Err(e) => return Err(format!("Invalid entry on line {}: {:?}", i + 1, e)),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// A summary of the outcomes of replaying a recording.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReplaySummary {
+    pub blocks_imported: usize,
+    pub blocks_rejected: usize,
+    pub attestations_applied: usize,
+    pub attestations_rejected: usize,
+    pub fork_choice_errors: usize,
+}
+
+/// Supplies each of the `entries` to `chain` in order, setting the slot clock to the recorded
+/// time beforehand.
+///
+/// Returns an error without supplying any input unless the recording starts from the head of
+/// `chain`, i.e., the `chain` must be started from the same genesis (or resumed from the same
+/// database) as the recording node. The head each subsequent restart of the recording node
+/// resumed from is not checked.
+pub fn replay<S, E>(
+    chain: &EmbeddedChain<S, E>,
+    entries: Vec<RecordedEntry<E>>,
+) -> Result<ReplaySummary, String>
+where
+    S: Store<E> + 'static,
+    E: EthSpec,
+    NullMigrator: Migrate<S, E>,
+{
+    let head = chain
+        .head()
+        .map_err(|e| format!("Unable to read head: {:?}", e))?;
+    match entries.first().map(|entry| &entry.input) {
+        Some(RecordedInput::Start { state_root, .. }) if *state_root == head.state_root => {}
+        Some(RecordedInput::Start {
+            slot, state_root, ..
+        }) => {
+            return Err(format!(
+                "The recording starts from state {:?} at slot {}, but the chain is at state {:?} \
+                 at slot {}",
+                state_root, slot, head.state_root, head.slot
+            ))
+        }
+        _ => return Err("The recording does not state its starting head".to_string()),
+    }
+
+    let mut summary = ReplaySummary::default();
+
+    for entry in entries {
+        chain.set_time(Duration::from_millis(entry.time_ms));
+        let chain = chain.chain();
+
+        match entry.input {
+            RecordedInput::Start { .. } => {}
+            RecordedInput::Block(block) => match chain.process_block(*block) {
+                Ok(_) => summary.blocks_imported += 1,
+                Err(_) => summary.blocks_rejected += 1,
+            },
+            RecordedInput::Attestation(attestation) => {
+                let applied = chain
+                    .verify_unaggregated_attestation_for_gossip(*attestation)
+                    .and_then(|verified| {
+                        chain.apply_attestation_to_fork_choice(&verified)?;
+                        Ok(())
+                    });
+                match applied {
+                    Ok(()) => summary.attestations_applied += 1,
+                    Err(_) => summary.attestations_rejected += 1,
+                }
+            }
+            RecordedInput::AggregateAndProof(aggregate) => {
+                let applied = chain
+                    .verify_aggregated_attestation_for_gossip(*aggregate)
+                    .and_then(|verified| {
+                        chain.apply_attestation_to_fork_choice(&verified)?;
+                        Ok(())
+                    });
+                match applied {
+                    Ok(()) => summary.attestations_applied += 1,
+                    Err(_) => summary.attestations_rejected += 1,
+                }
+            }
+            RecordedInput::ForkChoice => {
+                if chain.fork_choice().is_err() {
+                    summary.fork_choice_errors += 1;
+                }
+            }
+            RecordedInput::Tick => chain.per_slot_task(),
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use tempfile::tempdir;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn open(path: &Path) -> InputRecorder<E> {
+        let log = NullLoggerBuilder.build().expect("logger should build");
+        InputRecorder::open(path, log).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("inputs.json");
+
+        let recorder = open(&path);
+        recorder
+            .record(Duration::from_millis(1_500), RecordedInput::Tick)
+            .unwrap();
+        recorder
+            .record(Duration::from_millis(2_000), RecordedInput::ForkChoice)
+            .unwrap();
+        drop(recorder);
+
+        // Re-opening should append rather than truncate.
+        let recorder = open(&path);
+        recorder
+            .record(Duration::from_millis(3_000), RecordedInput::Tick)
+            .unwrap();
+        drop(recorder);
+
+        let entries = read_recording::<E>(&path).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                RecordedEntry {
+                    time_ms: 1_500,
+                    input: RecordedInput::Tick
+                },
+                RecordedEntry {
+                    time_ms: 2_000,
+                    input: RecordedInput::ForkChoice
+                },
+                RecordedEntry {
+                    time_ms: 3_000,
+                    input: RecordedInput::Tick
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_partial_final_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("inputs.json");
+
+        let recorder = open(&path);
+        recorder
+            .record(Duration::from_millis(1), RecordedInput::Tick)
+            .unwrap();
+        drop(recorder);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"time_ms\":2,\"inp").unwrap();
+
+        assert_eq!(read_recording::<E>(&path).unwrap().len(), 1);
+
+        // A corrupt entry which is not the last is an error.
+        file.write_all(b"\n{}\n").unwrap();
+        assert!(read_recording::<E>(&path).is_err());
+    }
+}
@@ -2,23 +2,31 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod attestation_self_check;
 pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_snapshot;
 mod block_verification;
 pub mod builder;
+pub mod differential;
+pub mod embedded;
 mod errors;
 pub mod eth1_chain;
+pub mod event_journal;
 pub mod events;
 mod fork_choice;
 mod head_tracker;
+pub mod input_recorder;
 mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
 mod observed_attestations;
 mod observed_attesters;
 mod observed_block_producers;
+pub mod own_publications;
 mod persisted_beacon_chain;
+mod persisted_observed_caches;
+mod proposer_cache;
 mod shuffling_cache;
 mod snapshot_cache;
 pub mod test_utils;
@@ -26,18 +34,22 @@ mod timeout_rw_lock;
 mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
-    AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    StateSkipConfig,
+    AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult, HeadInfo,
+    StateSkipConfig, BEACON_CHAIN_DB_KEY, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use attestation_verification::Error as AttestationError;
 pub use block_verification::{BlockError, BlockProcessingOutcome, GossipVerifiedBlock};
+pub use embedded::EmbeddedChain;
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
-pub use events::EventHandler;
+pub use events::{EventHandler, EventHandlerList};
 pub use fork_choice::ForkChoice;
 pub use metrics::scrape_for_metrics;
+pub use operation_pool::AttestationInclusionStrategy;
 pub use parking_lot;
+pub use persisted_beacon_chain::PersistedBeaconChain;
+pub use proposer_cache::ProposerShuffling;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
@@ -41,6 +41,22 @@ lazy_static! {
         "beacon_block_processing_core_seconds",
         "Time spent doing the core per_block_processing state processing."
     );
+    pub static ref BLOCK_PROCESSING_ARITH_ERRORS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_arith_errors_total",
+        "Count of blocks for which state processing failed due to an overflow or division by zero"
+    );
+    pub static ref DIFFERENTIAL_TEST_AGREEMENTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_differential_test_agreements_total",
+        "Count of blocks for which the reference implementation computed the same state root"
+    );
+    pub static ref DIFFERENTIAL_TEST_DIVERGENCES: Result<IntCounter> = try_create_int_counter(
+        "beacon_differential_test_divergences_total",
+        "Count of blocks for which the reference implementation did not compute the same state root"
+    );
+    pub static ref DIFFERENTIAL_TEST_SKIPPED: Result<IntCounter> = try_create_int_counter(
+        "beacon_differential_test_skipped_total",
+        "Count of blocks not given to the reference implementation because its queue was full"
+    );
     pub static ref BLOCK_PROCESSING_STATE_ROOT: Result<Histogram> = try_create_histogram(
         "beacon_block_processing_state_root_seconds",
         "Time spent calculating the state root when processing a block."
@@ -112,6 +128,35 @@ lazy_static! {
         "Full runtime of aggregated attestation gossip verification"
     );
 
+    pub static ref ATTESTATION_PROCESSING_REJECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_attestation_processing_rejections_total",
+        "Count of attestations rejected, per stage (unaggregated, aggregated or fork_choice) and reason",
+        &["stage", "reason"]
+    );
+    pub static ref MAX_SKIP_SLOTS_REJECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_max_skip_slots_rejections_total",
+        "Count of blocks and attestations rejected for skipping more than the max-skip-slots from their parent or head block",
+        &["type"]
+    );
+    pub static ref OWN_PUBLICATIONS_REFUSED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_own_publications_refused_total",
+        "Count of blocks and attestations from the API which were not published because a conflicting message was already published for the same validator",
+        &["type"]
+    );
+    pub static ref ATTESTATION_SELF_CHECK_COMPARISONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_self_check_comparisons_total",
+        "Count of gossip aggregates compared with the attestation data produced for their slot"
+    );
+    pub static ref ATTESTATION_SELF_CHECK_DIVERGENCES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_attestation_self_check_divergences_total",
+        "Count of gossip aggregates which differed from the attestation data produced for their slot, per field (head, target or source)",
+        &["field"]
+    );
+    pub static ref ATTESTATION_SELF_CHECK_DIVERGENCE_RATE: Result<Gauge> = try_create_float_gauge(
+        "beacon_attestation_self_check_divergence_rate",
+        "Fraction of recent gossip aggregates which differed from the attestation data produced for their slot"
+    );
+
     /*
      * General Attestation Processing
      */
@@ -188,6 +233,14 @@ lazy_static! {
     pub static ref SHUFFLING_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_misses_total", "Count of times shuffling cache fulfils request");
 
+    /*
+     * Proposer cache
+     */
+    pub static ref PROPOSER_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_proposer_cache_hits_total", "Count of times proposer cache fulfils request");
+    pub static ref PROPOSER_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_proposer_cache_misses_total", "Count of times proposer cache fails to fulfil request");
+
     /*
      * Attestation Production
      */
@@ -240,9 +293,35 @@ lazy_static! {
     );
     pub static ref BALANCES_CACHE_HITS: Result<IntCounter> =
         try_create_int_counter("beacon_balances_cache_hits_total", "Count of times balances cache fulfils request");
+    pub static ref BALANCES_CACHE_PINNED_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_balances_cache_pinned_hits_total", "Count of times the pinned justified balances fulfil request");
     pub static ref BALANCES_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_balances_cache_misses_total", "Count of times balances cache fulfils request");
 
+    /*
+     * Pruning of abandoned forks
+     */
+    pub static ref PRUNE_ABANDONED_FORKS_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_store_prune_abandoned_forks_seconds",
+        "Time taken to prune the blocks and states of forks abandoned by finalization"
+    );
+    pub static ref PRUNED_ABANDONED_HEADS: Result<IntCounter> = try_create_int_counter(
+        "beacon_store_pruned_heads_total",
+        "Count of heads of abandoned forks which have been pruned"
+    );
+    pub static ref PRUNED_ABANDONED_BLOCKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_store_pruned_blocks_total",
+        "Count of blocks of abandoned forks which have been pruned"
+    );
+    pub static ref PRUNED_ABANDONED_STATES: Result<IntCounter> = try_create_int_counter(
+        "beacon_store_pruned_states_total",
+        "Count of states of abandoned forks which have been pruned"
+    );
+    pub static ref PRUNED_ABANDONED_BYTES: Result<IntCounter> = try_create_int_counter(
+        "beacon_store_pruned_bytes_total",
+        "Total size of the blocks and states of abandoned forks which have been pruned"
+    );
+
     /*
      * Persisting BeaconChain components to disk
      */
@@ -254,18 +333,26 @@ lazy_static! {
         try_create_histogram("beacon_persist_eth1_cache", "Time taken to persist the eth1 caches");
     pub static ref PERSIST_FORK_CHOICE: Result<Histogram> =
         try_create_histogram("beacon_persist_fork_choice", "Time taken to persist the fork choice struct");
+    pub static ref PERSIST_OBSERVED_CACHES: Result<Histogram> =
+        try_create_histogram("beacon_persist_observed_caches", "Time taken to persist the caches of observed gossip messages");
 
     /*
      * Eth1
      */
     pub static ref DEFAULT_ETH1_VOTES: Result<IntCounter> =
         try_create_int_counter("beacon_eth1_default_votes", "Count of times we have voted default value for eth1 data");
+    pub static ref ETH1_DEPOSIT_ROOT_MISMATCHES: Result<IntCounter> = try_create_int_counter(
+        "beacon_eth1_deposit_root_mismatches_total",
+        "Count of times the deposit cache root did not match the eth1 data of the finalized state"
+    );
 
     /*
      * Chain Head
      */
     pub static ref UPDATE_HEAD_TIMES: Result<Histogram> =
         try_create_histogram("beacon_update_head_seconds", "Time taken to update the canonical head");
+    pub static ref HEAD_SLOT_DISTANCE: Result<IntGauge> =
+        try_create_int_gauge("beacon_head_slot_distance", "Number of slots between the head block and the current slot");
     pub static ref HEAD_STATE_SLOT: Result<IntGauge> =
         try_create_int_gauge("beacon_head_state_slot", "Slot of the block at the head of the chain");
     pub static ref HEAD_STATE_ROOT: Result<IntGauge> =
@@ -349,6 +436,8 @@ pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
         scrape_attestation_observation(slot, beacon_chain);
     }
 
+    scrape_head_slot_distance(beacon_chain);
+
     set_gauge_by_usize(
         &OP_POOL_NUM_ATTESTATIONS,
         beacon_chain.op_pool.num_attestations(),
@@ -439,6 +528,19 @@ fn scrape_attestation_observation<T: BeaconChainTypes>(slot_now: Slot, chain: &B
     }
 }
 
+/// Update the distance between the head block and the wall-clock slot.
+///
+/// This is called after each run of fork choice as well as during scraping, since the distance
+/// grows whilst the head is unchanged.
+pub fn scrape_head_slot_distance<T: BeaconChainTypes>(chain: &BeaconChain<T>) {
+    if let (Some(slot_now), Ok(head)) = (chain.slot_clock.now(), chain.head_info()) {
+        set_gauge_by_u64(
+            &HEAD_SLOT_DISTANCE,
+            slot_now.as_u64().saturating_sub(head.slot.as_u64()),
+        );
+    }
+}
+
 fn set_gauge_by_slot(gauge: &Result<IntGauge>, value: Slot) {
     set_gauge(gauge, value.as_u64() as i64);
 }
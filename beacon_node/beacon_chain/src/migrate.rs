@@ -1,18 +1,32 @@
 use crate::errors::BeaconChainError;
 use crate::head_tracker::HeadTracker;
+use crate::metrics;
 use parking_lot::Mutex;
-use slog::{debug, warn, Logger};
+use slog::{debug, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
 use store::iter::{ParentRootBlockIterator, RootsIterator};
-use store::{hot_cold_store::HotColdDBError, Error, SimpleDiskStore, Store, StoreOp};
+use store::{hot_cold_store::HotColdDBError, DBColumn, Error, SimpleDiskStore, Store, StoreOp};
 pub use store::{DiskStore, MemoryStore};
 use types::*;
 use types::{BeaconState, EthSpec, Hash256, Slot};
 
+/// The result of pruning the blocks and states of abandoned forks.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PruningOutcome {
+    /// The number of blocks deleted.
+    pub blocks: usize,
+    /// The number of states (i.e., state summaries and any full states) deleted.
+    pub states: usize,
+    /// The number of heads removed from the head tracker.
+    pub heads: usize,
+    /// The total size of the deleted values, excluding the overhead of the database itself.
+    pub bytes: u64,
+}
+
 /// Trait for migration processes that update the database upon finalization.
 pub trait Migrate<S: Store<E>, E: EthSpec>: Send + Sync + 'static {
     fn new(db: Arc<S>, log: Logger) -> Self;
@@ -32,6 +46,9 @@ pub trait Migrate<S: Store<E>, E: EthSpec>: Send + Sync + 'static {
     /// upon because finalization would prohibit it.  This is a optimisation intended to save disk
     /// space.
     ///
+    /// Returns the number of blocks, states and heads that were pruned, along with the number of
+    /// bytes reclaimed, which are also recorded in the `beacon_store_pruned_*` metrics.
+    ///
     /// Assumptions:
     ///  * It is called after every finalization.
     fn prune_abandoned_forks(
@@ -40,7 +57,9 @@ pub trait Migrate<S: Store<E>, E: EthSpec>: Send + Sync + 'static {
         old_finalized_block_hash: SignedBeaconBlockHash,
         new_finalized_block_hash: SignedBeaconBlockHash,
         new_finalized_slot: Slot,
-    ) -> Result<(), BeaconChainError> {
+    ) -> Result<PruningOutcome, BeaconChainError> {
+        let _timer = metrics::start_timer(&metrics::PRUNE_ABANDONED_FORKS_TIMES);
+
         let old_finalized_slot = store
             .get_block(&old_finalized_block_hash.into())?
             .ok_or_else(|| BeaconChainError::MissingBeaconBlock(old_finalized_block_hash.into()))?
@@ -143,6 +162,13 @@ pub trait Migrate<S: Store<E>, E: EthSpec>: Send + Sync + 'static {
             }
         }
 
+        let mut outcome = PruningOutcome {
+            blocks: abandoned_blocks.len(),
+            states: abandoned_states.len(),
+            heads: abandoned_heads.len(),
+            bytes: 0,
+        };
+
         let batch: Vec<StoreOp> = abandoned_blocks
             .into_iter()
             .map(|block_hash| StoreOp::DeleteBlock(block_hash))
@@ -152,12 +178,46 @@ pub trait Migrate<S: Store<E>, E: EthSpec>: Send + Sync + 'static {
                     .map(|(slot, state_hash)| StoreOp::DeleteState(state_hash, slot)),
             )
             .collect();
+
+        // Measure the values before they're deleted. Reading the full states is not free, but it
+        // happens off the hot path and only for epoch-boundary states of abandoned forks.
+        for op in &batch {
+            outcome.bytes += stored_bytes::<S, E>(&store, op)?;
+        }
+
         store.do_atomically(&batch)?;
         for head_hash in abandoned_heads.into_iter() {
             head_tracker.remove_head(head_hash);
         }
 
-        Ok(())
+        metrics::inc_counter_by(&metrics::PRUNED_ABANDONED_BLOCKS, outcome.blocks as i64);
+        metrics::inc_counter_by(&metrics::PRUNED_ABANDONED_STATES, outcome.states as i64);
+        metrics::inc_counter_by(&metrics::PRUNED_ABANDONED_HEADS, outcome.heads as i64);
+        metrics::inc_counter_by(&metrics::PRUNED_ABANDONED_BYTES, outcome.bytes as i64);
+
+        Ok(outcome)
+    }
+}
+
+/// Returns the number of bytes which `op` will delete from `store`.
+fn stored_bytes<S: Store<E>, E: EthSpec>(store: &S, op: &StoreOp) -> Result<u64, Error> {
+    let len = |column: DBColumn, hash: Hash256| -> Result<u64, Error> {
+        Ok(store
+            .get_bytes(column.into(), hash.as_bytes())?
+            .map_or(0, |bytes| bytes.len() as u64))
+    };
+
+    match op {
+        StoreOp::DeleteBlock(block_hash) => len(DBColumn::BeaconBlock, (*block_hash).into()),
+        StoreOp::DeleteState(state_hash, slot) => {
+            let state_hash: Hash256 = (*state_hash).into();
+            let mut bytes = len(DBColumn::BeaconStateSummary, state_hash)?;
+            // Mirrors `do_atomically`, which only deletes the full states at epoch boundaries.
+            if *slot % E::slots_per_epoch() == 0 {
+                bytes += len(DBColumn::BeaconState, state_hash)?;
+            }
+            Ok(bytes)
+        }
     }
 }
 
@@ -342,7 +402,15 @@ impl<E: EthSpec> BackgroundMigrator<E> {
                     new_finalized_block_hash,
                     new_finalized_slot,
                 ) {
-                    Ok(()) => {}
+                    Ok(outcome) if outcome == PruningOutcome::default() => {}
+                    Ok(outcome) => info!(
+                        log,
+                        "Pruned abandoned forks";
+                        "heads" => outcome.heads,
+                        "blocks" => outcome.blocks,
+                        "states" => outcome.states,
+                        "reclaimed_bytes" => outcome.bytes
+                    ),
                     Err(e) => warn!(log, "Block pruning failed: {:?}", e),
                 }
             }
@@ -2,6 +2,7 @@
 //! we've already seen the aggregated attestation.
 
 use parking_lot::RwLock;
+use ssz_derive::{Decode, Encode};
 use std::collections::HashSet;
 use std::marker::PhantomData;
 use tree_hash::TreeHash;
@@ -43,6 +44,11 @@ pub enum Error {
         expected: Slot,
         attestation: Slot,
     },
+    /// The `SszObservedAttestations` does not have an entry of roots for each slot.
+    MismatchingLengths {
+        slots_len: usize,
+        roots_len: usize,
+    },
 }
 
 /// A `HashSet` that contains entries related to some `Slot`.
@@ -170,6 +176,61 @@ impl<E: EthSpec> ObservedAttestations<E> {
         E::slots_per_epoch() + 2
     }
 
+    /// Returns a `SszObservedAttestations`, which contains all necessary information to restore
+    /// the state of `Self` at some later point.
+    pub fn to_ssz_container(&self) -> SszObservedAttestations {
+        let (slots, roots) = self
+            .sets
+            .read()
+            .iter()
+            .map(|set| (set.slot, set.set.iter().copied().collect::<Vec<_>>()))
+            .unzip();
+
+        SszObservedAttestations {
+            lowest_permissible_slot: *self.lowest_permissible_slot.read(),
+            slots,
+            roots,
+        }
+    }
+
+    /// Creates a new `Self` from the given `SszObservedAttestations`, restoring `Self` to the same
+    /// state of the `Self` that created the `SszObservedAttestations`.
+    pub fn from_ssz_container(ssz_container: &SszObservedAttestations) -> Result<Self, Error> {
+        let slots_len = ssz_container.slots.len();
+        let roots_len = ssz_container.roots.len();
+
+        if slots_len != roots_len {
+            return Err(Error::MismatchingLengths {
+                slots_len,
+                roots_len,
+            });
+        }
+
+        let sets = ssz_container
+            .slots
+            .iter()
+            .zip(ssz_container.roots.iter())
+            .map(|(slot, roots)| {
+                if roots.len() > MAX_OBSERVATIONS_PER_SLOT {
+                    Err(Error::ReachedMaxObservationsPerSlot(
+                        MAX_OBSERVATIONS_PER_SLOT,
+                    ))
+                } else {
+                    Ok(SlotHashSet {
+                        set: roots.iter().copied().collect(),
+                        slot: *slot,
+                    })
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            lowest_permissible_slot: RwLock::new(ssz_container.lowest_permissible_slot),
+            sets: RwLock::new(sets),
+            _phantom: PhantomData,
+        })
+    }
+
     /// Removes any attestations with a slot lower than `current_slot` and bars any future
     /// attestations with a slot lower than `current_slot - SLOTS_RETAINED`.
     pub fn prune(&self, current_slot: Slot) {
@@ -241,10 +302,22 @@ impl<E: EthSpec> ObservedAttestations<E> {
     }
 }
 
+/// Helper struct that is used to encode/decode the state of the `ObservedAttestations` as SSZ
+/// bytes.
+///
+/// This is used when persisting the state of the `BeaconChain` to disk.
+#[derive(Encode, Decode, Clone)]
+pub struct SszObservedAttestations {
+    lowest_permissible_slot: Slot,
+    slots: Vec<Slot>,
+    roots: Vec<Vec<Hash256>>,
+}
+
 #[cfg(test)]
 #[cfg(not(debug_assertions))]
 mod tests {
     use super::*;
+    use ssz::{Decode, Encode};
     use tree_hash::TreeHash;
     use types::{test_utils::test_random_instance, Hash256};
 
@@ -309,6 +382,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ssz_round_trip() {
+        let store = ObservedAttestations::default();
+
+        single_slot_test(&store, Slot::new(0));
+        single_slot_test(&store, Slot::new(1));
+        store.prune(Slot::new(store.max_capacity()));
+
+        let bytes = store.to_ssz_container().as_ssz_bytes();
+        let restored = ObservedAttestations::<E>::from_ssz_container(
+            &SszObservedAttestations::from_ssz_bytes(&bytes).expect("should decode"),
+        )
+        .expect("should restore");
+
+        assert_eq!(
+            *restored.lowest_permissible_slot.read(),
+            Slot::new(1),
+            "should restore the lowest permissible slot"
+        );
+        assert_eq!(restored.sets.read().len(), 1, "should restore one set");
+        assert_eq!(restored.sets.read()[0].slot, Slot::new(1));
+        assert_eq!(
+            restored.sets.read()[0].set,
+            store.sets.read()[0].set,
+            "should restore the observed roots"
+        );
+    }
+
     #[test]
     fn mulitple_contiguous_slots() {
         let store = ObservedAttestations::default();
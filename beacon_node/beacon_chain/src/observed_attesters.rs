@@ -8,6 +8,7 @@
 
 use bitvec::vec::BitVec;
 use parking_lot::RwLock;
+use ssz_derive::{Decode, Encode};
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use types::{Attestation, Epoch, EthSpec, Unsigned};
@@ -26,6 +27,11 @@ pub enum Error {
     ReachedMaxObservationsPerSlot(usize),
     /// The function to obtain a set index failed, this is an internal error.
     ValidatorIndexTooHigh(usize),
+    /// The `SszAutoPruningContainer` does not have an entry of validator indices for each epoch.
+    MismatchingLengths {
+        epochs_len: usize,
+        validator_indices_len: usize,
+    },
 }
 
 /// Implemented on an item in an `AutoPruningContainer`.
@@ -47,6 +53,9 @@ pub trait Item {
 
     /// Returns `true` if `validator_index` has been stored in `self`.
     fn contains(&self, validator_index: usize) -> bool;
+
+    /// Returns the validator indices that have been stored in `self`, in ascending order.
+    fn validator_indices(&self) -> Vec<usize>;
 }
 
 /// Stores a `BitVec` that represents which validator indices have attested during an epoch.
@@ -98,6 +107,15 @@ impl Item for EpochBitfield {
     fn contains(&self, validator_index: usize) -> bool {
         self.bitfield.get(validator_index).map_or(false, |bit| *bit)
     }
+
+    fn validator_indices(&self) -> Vec<usize> {
+        self.bitfield
+            .iter()
+            .enumerate()
+            .filter(|(_i, bit)| **bit)
+            .map(|(i, _bit)| i)
+            .collect()
+    }
 }
 
 /// Stores a `HashSet` of which validator indices have created an aggregate attestation during an
@@ -137,6 +155,12 @@ impl Item for EpochHashSet {
     fn contains(&self, validator_index: usize) -> bool {
         self.set.contains(&validator_index)
     }
+
+    fn validator_indices(&self) -> Vec<usize> {
+        let mut indices = self.set.iter().copied().collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices
+    }
 }
 
 /// A container that stores some number of `T` items.
@@ -265,6 +289,67 @@ impl<T: Item, E: EthSpec> AutoPruningContainer<T, E> {
         2
     }
 
+    /// Returns a `SszAutoPruningContainer`, which contains all necessary information to restore
+    /// the state of `Self` at some later point.
+    pub fn to_ssz_container(&self) -> SszAutoPruningContainer {
+        let (epochs, validator_indices) = self
+            .items
+            .read()
+            .iter()
+            .map(|(epoch, item)| {
+                let indices = item
+                    .validator_indices()
+                    .into_iter()
+                    .map(|i| i as u64)
+                    .collect::<Vec<_>>();
+                (*epoch, indices)
+            })
+            .unzip();
+
+        SszAutoPruningContainer {
+            lowest_permissible_epoch: *self.lowest_permissible_epoch.read(),
+            epochs,
+            validator_indices,
+        }
+    }
+
+    /// Creates a new `Self` from the given `SszAutoPruningContainer`, restoring `Self` to the same
+    /// state of the `Self` that created the `SszAutoPruningContainer`.
+    pub fn from_ssz_container(ssz_container: &SszAutoPruningContainer) -> Result<Self, Error> {
+        let epochs_len = ssz_container.epochs.len();
+        let validator_indices_len = ssz_container.validator_indices.len();
+
+        if epochs_len != validator_indices_len {
+            return Err(Error::MismatchingLengths {
+                epochs_len,
+                validator_indices_len,
+            });
+        }
+
+        let mut items = HashMap::with_capacity(epochs_len);
+        for (epoch, indices) in ssz_container
+            .epochs
+            .iter()
+            .zip(ssz_container.validator_indices.iter())
+        {
+            let mut item = T::with_capacity(indices.len());
+            for &validator_index in indices {
+                let validator_index = validator_index as usize;
+                if validator_index > E::ValidatorRegistryLimit::to_usize() {
+                    return Err(Error::ValidatorIndexTooHigh(validator_index));
+                }
+                item.insert(validator_index);
+            }
+            items.insert(*epoch, item);
+        }
+
+        Ok(Self {
+            lowest_permissible_epoch: RwLock::new(ssz_container.lowest_permissible_epoch),
+            items: RwLock::new(items),
+            _phantom: PhantomData,
+        })
+    }
+
     /// Updates `self` with the current epoch, removing all attestations that become expired
     /// relative to `Self::max_capacity`.
     ///
@@ -282,6 +367,17 @@ impl<T: Item, E: EthSpec> AutoPruningContainer<T, E> {
     }
 }
 
+/// Helper struct that is used to encode/decode the state of an `AutoPruningContainer` as SSZ
+/// bytes.
+///
+/// This is used when persisting the state of the `BeaconChain` to disk.
+#[derive(Encode, Decode, Clone)]
+pub struct SszAutoPruningContainer {
+    lowest_permissible_epoch: Epoch,
+    epochs: Vec<Epoch>,
+    validator_indices: Vec<Vec<u64>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +387,7 @@ mod tests {
             #[cfg(test)]
             mod $mod_name {
                 use super::*;
+                use ssz::{Decode, Encode};
                 use types::test_utils::test_random_instance;
 
                 type E = types::MainnetEthSpec;
@@ -345,6 +442,40 @@ mod tests {
                     );
                 }
 
+                #[test]
+                fn ssz_round_trip() {
+                    let store = $type::default();
+
+                    single_epoch_test(&store, Epoch::new(3));
+                    single_epoch_test(&store, Epoch::new(4));
+
+                    let bytes = store.to_ssz_container().as_ssz_bytes();
+                    let restored = $type::<E>::from_ssz_container(
+                        &SszAutoPruningContainer::from_ssz_bytes(&bytes).expect("should decode"),
+                    )
+                    .expect("should restore");
+
+                    assert_eq!(
+                        *restored.lowest_permissible_epoch.read(),
+                        Epoch::new(3),
+                        "should restore the lowest permissible epoch"
+                    );
+                    for epoch in &[Epoch::new(3), Epoch::new(4)] {
+                        assert_eq!(
+                            restored.items.read()[epoch].validator_indices(),
+                            store.items.read()[epoch].validator_indices(),
+                            "should restore the observed validators"
+                        );
+                    }
+
+                    let a = &get_attestation(Epoch::new(4));
+                    assert_eq!(
+                        restored.observe_validator(a, 22),
+                        Ok(true),
+                        "should acknowledge an attestation observed before the restore"
+                    );
+                }
+
                 #[test]
                 fn mulitple_contiguous_epochs() {
                     let store = $type::default();
@@ -2,6 +2,7 @@
 //! validators that have already produced a block.
 
 use parking_lot::RwLock;
+use ssz_derive::{Decode, Encode};
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use types::{BeaconBlock, EthSpec, Slot, Unsigned};
@@ -13,6 +14,11 @@ pub enum Error {
     FinalizedBlock { slot: Slot, finalized_slot: Slot },
     /// The function to obtain a set index failed, this is an internal error.
     ValidatorIndexTooHigh(u64),
+    /// The `SszObservedBlockProducers` does not have an entry of proposer indices for each slot.
+    MismatchingLengths {
+        slots_len: usize,
+        proposer_indices_len: usize,
+    },
 }
 
 /// Maintains a cache of observed `(block.slot, block.proposer)`.
@@ -119,11 +125,79 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
             .write()
             .retain(|slot, _set| *slot > finalized_slot);
     }
+
+    /// Returns a `SszObservedBlockProducers`, which contains all necessary information to restore
+    /// the state of `Self` at some later point.
+    pub fn to_ssz_container(&self) -> SszObservedBlockProducers {
+        let (slots, proposer_indices) = self
+            .items
+            .read()
+            .iter()
+            .map(|(slot, set)| {
+                let mut indices = set.iter().copied().collect::<Vec<_>>();
+                indices.sort_unstable();
+                (*slot, indices)
+            })
+            .unzip();
+
+        SszObservedBlockProducers {
+            finalized_slot: *self.finalized_slot.read(),
+            slots,
+            proposer_indices,
+        }
+    }
+
+    /// Creates a new `Self` from the given `SszObservedBlockProducers`, restoring `Self` to the
+    /// same state of the `Self` that created the `SszObservedBlockProducers`.
+    pub fn from_ssz_container(ssz_container: &SszObservedBlockProducers) -> Result<Self, Error> {
+        let slots_len = ssz_container.slots.len();
+        let proposer_indices_len = ssz_container.proposer_indices.len();
+
+        if slots_len != proposer_indices_len {
+            return Err(Error::MismatchingLengths {
+                slots_len,
+                proposer_indices_len,
+            });
+        }
+
+        let mut items = HashMap::with_capacity(slots_len);
+        for (slot, indices) in ssz_container
+            .slots
+            .iter()
+            .zip(ssz_container.proposer_indices.iter())
+        {
+            if let Some(index) = indices
+                .iter()
+                .find(|index| **index > E::ValidatorRegistryLimit::to_u64())
+            {
+                return Err(Error::ValidatorIndexTooHigh(*index));
+            }
+            items.insert(*slot, indices.iter().copied().collect::<HashSet<_>>());
+        }
+
+        Ok(Self {
+            finalized_slot: RwLock::new(ssz_container.finalized_slot),
+            items: RwLock::new(items),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// Helper struct that is used to encode/decode the state of the `ObservedBlockProducers` as SSZ
+/// bytes.
+///
+/// This is used when persisting the state of the `BeaconChain` to disk.
+#[derive(Encode, Decode, Clone)]
+pub struct SszObservedBlockProducers {
+    finalized_slot: Slot,
+    slots: Vec<Slot>,
+    proposer_indices: Vec<Vec<u64>>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ssz::{Decode, Encode};
     use types::MainnetEthSpec;
 
     type E = MainnetEthSpec;
@@ -425,4 +499,39 @@ mod tests {
             "only one proposer should be present in slot 1"
         );
     }
+
+    #[test]
+    fn ssz_round_trip() {
+        let cache = ObservedBlockProducers::default();
+
+        cache.prune(E::slots_per_epoch().into());
+        for &(slot, proposer) in &[(33, 0), (33, 7), (34, 7)] {
+            assert_eq!(
+                cache.observe_proposer(&get_block(slot, proposer)),
+                Ok(false)
+            );
+        }
+
+        let bytes = cache.to_ssz_container().as_ssz_bytes();
+        let restored = ObservedBlockProducers::<E>::from_ssz_container(
+            &SszObservedBlockProducers::from_ssz_bytes(&bytes).expect("should decode"),
+        )
+        .expect("should restore");
+
+        assert_eq!(
+            *restored.finalized_slot.read(),
+            Slot::from(E::slots_per_epoch()),
+            "should restore the finalized slot"
+        );
+        assert_eq!(
+            *restored.items.read(),
+            *cache.items.read(),
+            "should restore the observed proposers"
+        );
+        assert_eq!(
+            restored.proposer_has_been_observed(&get_block(33, 7)),
+            Ok(true),
+            "should indicate a proposer observed before the restore is known"
+        );
+    }
 }
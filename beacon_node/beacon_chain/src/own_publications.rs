@@ -0,0 +1,329 @@
+//! Remembers the blocks and attestations published through this node's HTTP API, so that a second,
+//! different block or attestation for the same validator and slot (or target epoch) is refused
+//! rather than published.
+//!
+//! This is a last line of defence against a validator slashing itself, e.g., because its
+//! slashing protection database was lost or the same keys are in use by two validator clients
+//! which share this beacon node. It is not a replacement for the slashing protection of the
+//! validator client: only messages published through this node are known and they are forgotten
+//! after `OWN_PUBLICATIONS_RETAINED_EPOCHS`, or when the node restarts.
+//!
+//! Publishing the same message twice is not an equivocation and is permitted. For attestations,
+//! only double votes (i.e., two different attestations with the same target epoch) are detected,
+//! not surround votes.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use slog::{crit, Logger};
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use types::{Epoch, EthSpec, Hash256, Slot};
+
+/// The number of epochs for which published messages are remembered.
+pub const OWN_PUBLICATIONS_RETAINED_EPOCHS: u64 = 64;
+
+/// The maximum number of refused publications which are retained for `refused`.
+pub const MAX_REFUSED_PUBLICATIONS: usize = 256;
+
+/// The kind of a message published through this node.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicationKind {
+    Block,
+    Attestation,
+}
+
+impl PublicationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PublicationKind::Block => "block",
+            PublicationKind::Attestation => "attestation",
+        }
+    }
+}
+
+/// A message which was not published because it conflicts with one already published for the
+/// same validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefusedPublication {
+    pub kind: PublicationKind,
+    pub validator_index: u64,
+    /// The slot of the refused message.
+    pub slot: Slot,
+    /// The root of the block, or attestation data, which was previously published.
+    pub published_root: Hash256,
+    /// The root of the block, or attestation data, which was refused.
+    pub refused_root: Hash256,
+}
+
+struct Inner {
+    /// The root of the block published by each proposer in each slot.
+    blocks: HashMap<(u64, Slot), Hash256>,
+    /// The data root of the attestation published by each validator for each target epoch.
+    attestations: HashMap<(u64, Epoch), Hash256>,
+    /// The most recent refused publications, oldest first.
+    refused: VecDeque<RefusedPublication>,
+}
+
+/// Remembers published messages and refuses those which would equivocate.
+pub struct OwnPublications<E: EthSpec> {
+    inner: Mutex<Inner>,
+    log: Logger,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> OwnPublications<E> {
+    pub fn new(log: Logger) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                blocks: HashMap::new(),
+                attestations: HashMap::new(),
+                refused: VecDeque::new(),
+            }),
+            log,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns an error (after logging a critical alert) if a block other than `block_root` has
+    /// already been published by `proposer_index` at `slot`.
+    pub fn check_block(
+        &self,
+        proposer_index: u64,
+        slot: Slot,
+        block_root: Hash256,
+    ) -> Result<(), RefusedPublication> {
+        let mut inner = self.inner.lock();
+        let published_root = inner.blocks.get(&(proposer_index, slot)).copied();
+        self.check(
+            &mut inner,
+            PublicationKind::Block,
+            proposer_index,
+            slot,
+            published_root,
+            block_root,
+        )
+    }
+
+    /// As `check_block`, but also remembers the block so that it may be published.
+    pub fn observe_block(
+        &self,
+        proposer_index: u64,
+        slot: Slot,
+        block_root: Hash256,
+    ) -> Result<(), RefusedPublication> {
+        let mut inner = self.inner.lock();
+        let published_root = inner.blocks.get(&(proposer_index, slot)).copied();
+        self.check(
+            &mut inner,
+            PublicationKind::Block,
+            proposer_index,
+            slot,
+            published_root,
+            block_root,
+        )?;
+        inner.blocks.insert((proposer_index, slot), block_root);
+
+        let oldest = Self::oldest_retained_epoch(slot.epoch(E::slots_per_epoch()));
+        inner
+            .blocks
+            .retain(|(_, slot), _| slot.epoch(E::slots_per_epoch()) >= oldest);
+
+        Ok(())
+    }
+
+    /// Returns an error (after logging a critical alert) if an attestation other than the one
+    /// with `data_root` has already been published by `validator_index` for `target_epoch`.
+    pub fn check_attestation(
+        &self,
+        validator_index: u64,
+        slot: Slot,
+        target_epoch: Epoch,
+        data_root: Hash256,
+    ) -> Result<(), RefusedPublication> {
+        let mut inner = self.inner.lock();
+        let published_root = inner
+            .attestations
+            .get(&(validator_index, target_epoch))
+            .copied();
+        self.check(
+            &mut inner,
+            PublicationKind::Attestation,
+            validator_index,
+            slot,
+            published_root,
+            data_root,
+        )
+    }
+
+    /// As `check_attestation`, but also remembers the attestation so that it may be published.
+    pub fn observe_attestation(
+        &self,
+        validator_index: u64,
+        slot: Slot,
+        target_epoch: Epoch,
+        data_root: Hash256,
+    ) -> Result<(), RefusedPublication> {
+        let mut inner = self.inner.lock();
+        let published_root = inner
+            .attestations
+            .get(&(validator_index, target_epoch))
+            .copied();
+        self.check(
+            &mut inner,
+            PublicationKind::Attestation,
+            validator_index,
+            slot,
+            published_root,
+            data_root,
+        )?;
+        inner
+            .attestations
+            .insert((validator_index, target_epoch), data_root);
+
+        let oldest = Self::oldest_retained_epoch(target_epoch);
+        inner.attestations.retain(|(_, epoch), _| *epoch >= oldest);
+
+        Ok(())
+    }
+
+    /// Returns the most recent refused publications, oldest first.
+    pub fn refused(&self) -> Vec<RefusedPublication> {
+        self.inner.lock().refused.iter().cloned().collect()
+    }
+
+    fn oldest_retained_epoch(epoch: Epoch) -> Epoch {
+        Epoch::new(
+            epoch
+                .as_u64()
+                .saturating_sub(OWN_PUBLICATIONS_RETAINED_EPOCHS),
+        )
+    }
+
+    /// Returns an error, after alerting the operator and recording the refusal, if
+    /// `published_root` is that of some message other than `root`.
+    fn check(
+        &self,
+        inner: &mut Inner,
+        kind: PublicationKind,
+        validator_index: u64,
+        slot: Slot,
+        published_root: Option<Hash256>,
+        root: Hash256,
+    ) -> Result<(), RefusedPublication> {
+        let published_root = match published_root {
+            Some(published_root) if published_root != root => published_root,
+            _ => return Ok(()),
+        };
+        let refused = RefusedPublication {
+            kind,
+            validator_index,
+            slot,
+            published_root,
+            refused_root: root,
+        };
+
+        metrics::inc_counter_vec(&metrics::OWN_PUBLICATIONS_REFUSED, &[kind.as_str()]);
+        crit!(
+            self.log,
+            "Refused to publish a slashable message";
+            "msg" => "a conflicting message was already published for this validator, check \
+                      that its keys are not in use by more than one validator client",
+            "kind" => refused.kind.as_str(),
+            "validator_index" => refused.validator_index,
+            "slot" => refused.slot,
+            "published_root" => format!("{}", refused.published_root),
+            "refused_root" => format!("{}", refused.refused_root)
+        );
+
+        if inner.refused.len() == MAX_REFUSED_PUBLICATIONS {
+            inner.refused.pop_front();
+        }
+        inner.refused.push_back(refused.clone());
+
+        Err(refused)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn own_publications() -> OwnPublications<E> {
+        OwnPublications::new(NullLoggerBuilder.build().unwrap())
+    }
+
+    #[test]
+    fn refuses_conflicting_blocks() {
+        let own = own_publications();
+        let slot = Slot::new(3);
+
+        // Checking a block does not remember it.
+        assert_eq!(own.check_block(1, slot, Hash256::repeat_byte(2)), Ok(()));
+        assert_eq!(own.observe_block(1, slot, Hash256::repeat_byte(1)), Ok(()));
+        // The same block may be published again, as may blocks from other proposers or slots.
+        assert_eq!(own.observe_block(1, slot, Hash256::repeat_byte(1)), Ok(()));
+        assert_eq!(own.observe_block(2, slot, Hash256::repeat_byte(2)), Ok(()));
+        assert_eq!(
+            own.observe_block(1, slot + 1, Hash256::repeat_byte(2)),
+            Ok(())
+        );
+
+        let refused = RefusedPublication {
+            kind: PublicationKind::Block,
+            validator_index: 1,
+            slot,
+            published_root: Hash256::repeat_byte(1),
+            refused_root: Hash256::repeat_byte(2),
+        };
+        assert_eq!(
+            own.observe_block(1, slot, Hash256::repeat_byte(2)),
+            Err(refused.clone())
+        );
+        assert_eq!(
+            own.check_block(1, slot, Hash256::repeat_byte(2)),
+            Err(refused.clone())
+        );
+        assert_eq!(own.refused(), vec![refused.clone(), refused]);
+    }
+
+    #[test]
+    fn refuses_double_votes() {
+        let own = own_publications();
+        let epoch = Epoch::new(2);
+        let slot = epoch.start_slot(E::slots_per_epoch());
+
+        assert_eq!(
+            own.observe_attestation(1, slot, epoch, Hash256::repeat_byte(1)),
+            Ok(())
+        );
+        assert_eq!(
+            own.observe_attestation(1, slot, epoch, Hash256::repeat_byte(1)),
+            Ok(())
+        );
+        assert!(own
+            .observe_attestation(1, slot + 1, epoch, Hash256::repeat_byte(2))
+            .is_err());
+        assert_eq!(
+            own.observe_attestation(1, slot, epoch + 1, Hash256::repeat_byte(2)),
+            Ok(())
+        );
+
+        // Old attestations are forgotten.
+        let later = epoch + 1 + OWN_PUBLICATIONS_RETAINED_EPOCHS + 1;
+        assert_eq!(
+            own.observe_attestation(1, slot, later, Hash256::repeat_byte(3)),
+            Ok(())
+        );
+        assert_eq!(
+            own.observe_attestation(1, slot, epoch, Hash256::repeat_byte(2)),
+            Ok(())
+        );
+        assert_eq!(own.refused().len(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::observed_attestations::{ObservedAttestations, SszObservedAttestations};
+use crate::observed_attesters::{ObservedAggregators, ObservedAttesters, SszAutoPruningContainer};
+use crate::observed_block_producers::{ObservedBlockProducers, SszObservedBlockProducers};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, SimpleStoreItem};
+use types::EthSpec;
+
+/// The caches which the `BeaconChain` uses to reject gossip messages that it has already seen.
+///
+/// These are persisted across restarts so that a node restarted part-way through an epoch does not
+/// forget which validators have already attested, aggregated or proposed, and so re-process (and
+/// re-propagate) a second, equivocating message from them.
+pub struct ObservedCaches<E: EthSpec> {
+    pub observed_attestations: ObservedAttestations<E>,
+    pub observed_attesters: ObservedAttesters<E>,
+    pub observed_aggregators: ObservedAggregators<E>,
+    pub observed_block_producers: ObservedBlockProducers<E>,
+}
+
+impl<E: EthSpec> Default for ObservedCaches<E> {
+    fn default() -> Self {
+        Self {
+            observed_attestations: <_>::default(),
+            observed_attesters: <_>::default(),
+            observed_aggregators: <_>::default(),
+            observed_block_producers: <_>::default(),
+        }
+    }
+}
+
+/// The SSZ encoding of `ObservedCaches`.
+///
+/// Each cache is stored exactly as it is held in memory, so the persisted caches are bounded by the
+/// same pruning (e.g., two epochs of attesters) as the in-memory caches.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedObservedCaches {
+    pub observed_attestations: SszObservedAttestations,
+    pub observed_attesters: SszAutoPruningContainer,
+    pub observed_aggregators: SszAutoPruningContainer,
+    pub observed_block_producers: SszObservedBlockProducers,
+}
+
+impl PersistedObservedCaches {
+    /// Returns the SSZ encoding of each of the given caches.
+    pub fn from_observed_caches<E: EthSpec>(
+        observed_attestations: &ObservedAttestations<E>,
+        observed_attesters: &ObservedAttesters<E>,
+        observed_aggregators: &ObservedAggregators<E>,
+        observed_block_producers: &ObservedBlockProducers<E>,
+    ) -> Self {
+        Self {
+            observed_attestations: observed_attestations.to_ssz_container(),
+            observed_attesters: observed_attesters.to_ssz_container(),
+            observed_aggregators: observed_aggregators.to_ssz_container(),
+            observed_block_producers: observed_block_producers.to_ssz_container(),
+        }
+    }
+
+    /// Restores the caches from their SSZ encoding.
+    pub fn to_observed_caches<E: EthSpec>(&self) -> Result<ObservedCaches<E>, String> {
+        Ok(ObservedCaches {
+            observed_attestations: ObservedAttestations::from_ssz_container(
+                &self.observed_attestations,
+            )
+            .map_err(|e| format!("Invalid observed attestations: {:?}", e))?,
+            observed_attesters: ObservedAttesters::from_ssz_container(&self.observed_attesters)
+                .map_err(|e| format!("Invalid observed attesters: {:?}", e))?,
+            observed_aggregators: ObservedAggregators::from_ssz_container(
+                &self.observed_aggregators,
+            )
+            .map_err(|e| format!("Invalid observed aggregators: {:?}", e))?,
+            observed_block_producers: ObservedBlockProducers::from_ssz_container(
+                &self.observed_block_producers,
+            )
+            .map_err(|e| format!("Invalid observed block producers: {:?}", e))?,
+        })
+    }
+}
+
+impl SimpleStoreItem for PersistedObservedCaches {
+    fn db_column() -> DBColumn {
+        DBColumn::ObservedCaches
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
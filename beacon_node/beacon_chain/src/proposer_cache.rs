@@ -0,0 +1,66 @@
+use crate::metrics;
+use lru::LruCache;
+use types::{Epoch, Hash256, Slot};
+
+/// The size of the LRU cache that stores proposer shufflings.
+///
+/// Each entry is only `SLOTS_PER_EPOCH` indices, so this can be generous enough to cover several
+/// forks across the current and next epochs.
+const CACHE_SIZE: usize = 16;
+
+/// The block proposers for each slot of an epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposerShuffling {
+    pub epoch: Epoch,
+    /// The root of the block at the last slot of the epoch prior to `self.epoch` (or the genesis
+    /// block, for the genesis epoch). Any state which descends from this block has the same
+    /// proposers for `self.epoch`.
+    pub decision_root: Hash256,
+    /// The validator index of the proposer for each slot of `self.epoch`, in order.
+    pub proposers: Vec<usize>,
+}
+
+impl ProposerShuffling {
+    /// Returns the validator index of the proposer for `slot`, if it is in `self.epoch`.
+    pub fn proposer(&self, slot: Slot, slots_per_epoch: u64) -> Option<usize> {
+        if slot.epoch(slots_per_epoch) != self.epoch {
+            return None;
+        }
+
+        let i = (slot - self.epoch.start_slot(slots_per_epoch)).as_usize();
+        self.proposers.get(i).copied()
+    }
+}
+
+/// Provides an LRU cache for `ProposerShuffling`, keyed by epoch and decision root.
+pub struct ProposerCache {
+    cache: LruCache<(Epoch, Hash256), ProposerShuffling>,
+}
+
+impl ProposerCache {
+    pub fn new() -> Self {
+        Self {
+            cache: LruCache::new(CACHE_SIZE),
+        }
+    }
+
+    pub fn get(&mut self, epoch: Epoch, decision_root: Hash256) -> Option<&ProposerShuffling> {
+        let opt = self.cache.get(&(epoch, decision_root));
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::PROPOSER_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::PROPOSER_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    pub fn insert(&mut self, shuffling: ProposerShuffling) {
+        let key = (shuffling.epoch, shuffling.decision_root);
+
+        if !self.cache.contains(&key) {
+            self.cache.put(key, shuffling);
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::BeaconSnapshot;
 use std::cmp;
-use types::{Epoch, EthSpec, Hash256};
+use std::collections::HashMap;
+use types::{Epoch, EthSpec, Hash256, Slot};
 
 /// The default size of the cache.
 pub const DEFAULT_SNAPSHOT_CACHE_SIZE: usize = 4;
+/// The default number of epoch boundary snapshots which are pinned in the cache.
+pub const DEFAULT_PINNED_EPOCH_BOUNDARIES: usize = 2;
 
 /// Provides a cache of `BeaconSnapshot` that is intended primarily for block processing.
 ///
@@ -16,9 +19,19 @@ pub const DEFAULT_SNAPSHOT_CACHE_SIZE: usize = 4;
 /// existing snapshot to be ejected. The ejected snapshot will:
 ///
 /// - Never be the `head_block_root`.
+/// - Never be a pinned epoch boundary snapshot.
 /// - Be the snapshot with the lowest `state.slot` (ties broken arbitrarily).
+///
+/// ## Epoch Boundary Pinning
+///
+/// The latest snapshot in each epoch is the state from which the shuffling and duties of later
+/// epochs are computed, so recomputing it at an epoch boundary is expensive. The latest snapshot
+/// of each of the `pinned_epoch_boundaries` most recent epochs prior to the latest epoch in the
+/// cache is pinned: it is never ejected and does not count towards `max_len`. A snapshot is
+/// unpinned once newer epochs take its place, and can then be ejected as normal.
 pub struct SnapshotCache<T: EthSpec> {
     max_len: usize,
+    pinned_epoch_boundaries: usize,
     head_block_root: Hash256,
     snapshots: Vec<BeaconSnapshot<T>>,
 }
@@ -26,10 +39,12 @@ pub struct SnapshotCache<T: EthSpec> {
 impl<T: EthSpec> SnapshotCache<T> {
     /// Instantiate a new cache which contains the `head` snapshot.
     ///
-    /// Setting `max_len = 0` is equivalent to setting `max_len = 1`.
-    pub fn new(max_len: usize, head: BeaconSnapshot<T>) -> Self {
+    /// Setting `max_len = 0` is equivalent to setting `max_len = 1`. Setting
+    /// `pinned_epoch_boundaries = 0` disables pinning.
+    pub fn new(max_len: usize, pinned_epoch_boundaries: usize, head: BeaconSnapshot<T>) -> Self {
         Self {
             max_len: cmp::max(max_len, 1),
+            pinned_epoch_boundaries,
             head_block_root: head.beacon_block_root,
             snapshots: vec![head],
         }
@@ -38,7 +53,9 @@ impl<T: EthSpec> SnapshotCache<T> {
     /// Insert a snapshot, potentially removing an existing snapshot if `self` is at capacity (see
     /// struct-level documentation for more info).
     pub fn insert(&mut self, snapshot: BeaconSnapshot<T>) {
-        if self.snapshots.len() < self.max_len {
+        let pinned = self.pinned_indices();
+
+        if self.snapshots.len() - pinned.len() < self.max_len {
             self.snapshots.push(snapshot);
         } else {
             let insert_at = self
@@ -46,7 +63,7 @@ impl<T: EthSpec> SnapshotCache<T> {
                 .iter()
                 .enumerate()
                 .filter_map(|(i, snapshot)| {
-                    if snapshot.beacon_block_root != self.head_block_root {
+                    if snapshot.beacon_block_root != self.head_block_root && !pinned.contains(&i) {
                         Some((i, snapshot.beacon_state.slot))
                     } else {
                         None
@@ -61,6 +78,39 @@ impl<T: EthSpec> SnapshotCache<T> {
         }
     }
 
+    /// Returns the indices of the pinned epoch boundary snapshots (see struct-level documentation).
+    fn pinned_indices(&self) -> Vec<usize> {
+        let slots_per_epoch = T::slots_per_epoch();
+        let epoch_of =
+            |snapshot: &BeaconSnapshot<T>| snapshot.beacon_state.slot.epoch(slots_per_epoch);
+
+        let latest_epoch = match self.snapshots.iter().map(epoch_of).max() {
+            Some(epoch) => epoch,
+            None => return vec![],
+        };
+
+        // The latest snapshot in each epoch prior to the latest epoch.
+        let mut boundaries: HashMap<Epoch, (usize, Slot)> = HashMap::new();
+        for (i, snapshot) in self.snapshots.iter().enumerate() {
+            let epoch = epoch_of(snapshot);
+            let slot = snapshot.beacon_state.slot;
+            if epoch < latest_epoch {
+                let boundary = boundaries.entry(epoch).or_insert((i, slot));
+                if slot > boundary.1 {
+                    *boundary = (i, slot);
+                }
+            }
+        }
+
+        let mut boundaries = boundaries.into_iter().collect::<Vec<_>>();
+        boundaries.sort_by_key(|(epoch, _)| cmp::Reverse(*epoch));
+        boundaries
+            .into_iter()
+            .take(self.pinned_epoch_boundaries)
+            .map(|(_, (i, _))| i)
+            .collect()
+    }
+
     /// If there is a snapshot with `block_root`, remove and return it.
     pub fn try_remove(&mut self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
         self.snapshots
@@ -69,6 +119,31 @@ impl<T: EthSpec> SnapshotCache<T> {
             .map(|i| self.snapshots.remove(i))
     }
 
+    /// If there is a snapshot with `block_root`, return it for the processing of a child block at
+    /// `child_slot`.
+    ///
+    /// The snapshot is removed, unless pinning is enabled and the child is in a later epoch. In
+    /// that case the snapshot is about to become an epoch boundary, so it is kept in the cache and
+    /// a full clone (including all caches) is returned instead.
+    pub fn try_remove_for_child(
+        &mut self,
+        block_root: Hash256,
+        child_slot: Slot,
+    ) -> Option<BeaconSnapshot<T>> {
+        let slots_per_epoch = T::slots_per_epoch();
+        let i = self
+            .snapshots
+            .iter()
+            .position(|snapshot| snapshot.beacon_block_root == block_root)?;
+
+        let snapshot_epoch = self.snapshots[i].beacon_state.slot.epoch(slots_per_epoch);
+        if self.pinned_epoch_boundaries > 0 && child_slot.epoch(slots_per_epoch) > snapshot_epoch {
+            Some(self.snapshots[i].clone())
+        } else {
+            Some(self.snapshots.remove(i))
+        }
+    }
+
     /// If there is a snapshot with `block_root`, clone it (with only the committee caches) and
     /// return the clone.
     pub fn get_cloned(&self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
@@ -123,7 +198,7 @@ mod test {
 
     #[test]
     fn insert_get_prune_update() {
-        let mut cache = SnapshotCache::new(CACHE_SIZE, get_snapshot(0));
+        let mut cache = SnapshotCache::new(CACHE_SIZE, 0, get_snapshot(0));
 
         // Insert a bunch of entries in the cache. It should look like this:
         //
@@ -214,4 +289,52 @@ mod test {
             "try_remove should get the correct snapshot"
         );
     }
+
+    #[test]
+    fn pins_epoch_boundaries() {
+        let slots_per_epoch = MainnetEthSpec::slots_per_epoch();
+        let snapshot_at = |i: u64, slot: u64| {
+            let mut snapshot = get_snapshot(i);
+            snapshot.beacon_state.slot = Slot::new(slot);
+            snapshot
+        };
+
+        let mut cache = SnapshotCache::new(2, 1, snapshot_at(0, 0));
+        cache.update_head(Hash256::from_low_u64_be(100));
+
+        // The last snapshot of epoch 0 becomes a boundary once there is a snapshot in epoch 1.
+        cache.insert(snapshot_at(1, slots_per_epoch - 1));
+        cache.insert(snapshot_at(2, slots_per_epoch + 1));
+        assert_eq!(cache.pinned_indices(), vec![1]);
+
+        // The pinned snapshot does not count towards `max_len`, so nothing is ejected.
+        cache.insert(snapshot_at(3, slots_per_epoch + 2));
+        assert_eq!(cache.snapshots.len(), 3);
+
+        // At capacity the unpinned snapshots with the lowest slots are ejected instead.
+        cache.insert(snapshot_at(4, slots_per_epoch + 3));
+        cache.insert(snapshot_at(5, slots_per_epoch + 4));
+        assert!(cache.get_cloned(Hash256::from_low_u64_be(0)).is_none());
+        assert!(cache.get_cloned(Hash256::from_low_u64_be(1)).is_some());
+        assert!(cache.get_cloned(Hash256::from_low_u64_be(2)).is_none());
+
+        // A parent in an earlier epoch is kept for processing its child, so it can be pinned.
+        assert!(cache
+            .try_remove_for_child(Hash256::from_low_u64_be(5), Slot::new(2 * slots_per_epoch))
+            .is_some());
+        assert!(cache.get_cloned(Hash256::from_low_u64_be(5)).is_some());
+        assert!(cache
+            .try_remove_for_child(Hash256::from_low_u64_be(4), Slot::new(slots_per_epoch + 5))
+            .is_some());
+        assert!(cache.get_cloned(Hash256::from_low_u64_be(4)).is_none());
+
+        // Once a later epoch boundary exists, the old boundary is no longer pinned.
+        cache.insert(snapshot_at(6, 2 * slots_per_epoch));
+        let pinned = cache.pinned_indices();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(
+            cache.snapshots[pinned[0]].beacon_block_root,
+            Hash256::from_low_u64_be(5)
+        );
+    }
 }
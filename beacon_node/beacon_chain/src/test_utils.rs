@@ -10,6 +10,7 @@ use crate::{
     BeaconChain, BeaconChainTypes, StateSkipConfig,
 };
 use genesis::interop_genesis_state;
+use integer_sqrt::IntegerSquareRoot;
 use rayon::prelude::*;
 use sloggers::{null::NullLoggerBuilder, Build};
 use slot_clock::TestingSlotClock;
@@ -71,6 +72,181 @@ pub enum AttestationStrategy {
     SomeValidators(Vec<usize>),
 }
 
+/// How a single validator attested during the previous epoch of a `RewardsScenario`.
+///
+/// The source of the attestation is always correct.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExpectedVote {
+    /// Whether the target of the attestation was the block at the start of its epoch.
+    pub target: bool,
+    /// Whether the head of the attestation was the canonical block at its slot.
+    pub head: bool,
+    /// The number of slots between the attestation and the block which first included it.
+    pub inclusion_delay: u64,
+    /// The proposer of the block which first included the attestation.
+    pub proposer_index: usize,
+}
+
+/// Describes how each validator attested during the previous epoch of a state, so that the
+/// balances after its next epoch transition can be computed directly from the spec, rather than by
+/// `state_processing`.
+///
+/// Validators without a vote did not attest. Slashed validators are not modelled.
+#[derive(Clone, Debug, Default)]
+pub struct RewardsScenario {
+    pub votes: HashMap<usize, ExpectedVote>,
+}
+
+impl RewardsScenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the vote of `validator_index`.
+    pub fn vote(mut self, validator_index: usize, vote: ExpectedVote) -> Self {
+        self.votes.insert(validator_index, vote);
+        self
+    }
+
+    /// Returns the reward (or penalty, if negative) that each validator should receive when
+    /// `state`, which must be at the last slot of its epoch, transitions into the next epoch.
+    ///
+    /// The inactivity leak is determined by the finalized checkpoint of `state`, so a transition
+    /// which both finalizes an epoch and leaves an inactivity leak is not modelled.
+    pub fn expected_deltas<E: EthSpec>(
+        &self,
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Vec<i64> {
+        let mut deltas = vec![0; state.validators.len()];
+
+        let current_epoch = state.current_epoch();
+        if current_epoch == E::genesis_epoch() {
+            return deltas;
+        }
+        let previous_epoch = state.previous_epoch();
+
+        let total_balance = |indices: &mut dyn Iterator<Item = usize>| {
+            std::cmp::max(
+                spec.effective_balance_increment,
+                indices.map(|i| state.validators[i].effective_balance).sum(),
+            )
+        };
+        let total_active_balance = total_balance(
+            &mut (0..state.validators.len())
+                .filter(|&i| state.validators[i].is_active_at(current_epoch)),
+        );
+        let source_balance = total_balance(&mut self.votes.keys().copied());
+        let target_balance = total_balance(
+            &mut self
+                .votes
+                .iter()
+                .filter(|(_, vote)| vote.target)
+                .map(|(&i, _)| i),
+        );
+        let head_balance = total_balance(
+            &mut self
+                .votes
+                .iter()
+                .filter(|(_, vote)| vote.head)
+                .map(|(&i, _)| i),
+        );
+
+        let increment = spec.effective_balance_increment;
+        let finality_delay = (previous_epoch - state.finalized_checkpoint.epoch).as_u64();
+
+        for (index, validator) in state.validators.iter().enumerate() {
+            if !validator.is_active_at(previous_epoch) {
+                continue;
+            }
+
+            let base_reward = validator.effective_balance * spec.base_reward_factor
+                / total_active_balance.integer_sqrt()
+                / spec.base_rewards_per_epoch;
+            let reward = |attesting_balance: u64| {
+                (base_reward * (attesting_balance / increment) / (total_active_balance / increment))
+                    as i64
+            };
+            let vote = self.votes.get(&index);
+
+            if let Some(vote) = vote {
+                let proposer_reward = base_reward / spec.proposer_reward_quotient;
+                deltas[index] += reward(source_balance)
+                    + ((base_reward - proposer_reward) / vote.inclusion_delay) as i64;
+                deltas[vote.proposer_index] += proposer_reward as i64;
+            } else {
+                deltas[index] -= base_reward as i64;
+            }
+
+            let target = vote.map_or(false, |vote| vote.target);
+            if target {
+                deltas[index] += reward(target_balance);
+            } else {
+                deltas[index] -= base_reward as i64;
+            }
+
+            if vote.map_or(false, |vote| vote.head) {
+                deltas[index] += reward(head_balance);
+            } else {
+                deltas[index] -= base_reward as i64;
+            }
+
+            if finality_delay > spec.min_epochs_to_inactivity_penalty {
+                deltas[index] -= (spec.base_rewards_per_epoch * base_reward) as i64;
+                if !target {
+                    deltas[index] -= (validator.effective_balance * finality_delay
+                        / spec.inactivity_penalty_quotient)
+                        as i64;
+                }
+            }
+        }
+
+        deltas
+    }
+
+    /// Returns the balance that each validator should have once `state` has transitioned into the
+    /// next epoch (see `expected_deltas`).
+    pub fn expected_balances<E: EthSpec>(
+        &self,
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Vec<u64> {
+        state
+            .balances
+            .iter()
+            .zip(self.expected_deltas(state, spec))
+            .map(|(&balance, delta)| std::cmp::max(0, balance as i64 + delta) as u64)
+            .collect()
+    }
+}
+
+/// Panics, listing each validator whose balance differs, if the balances of `state` are not
+/// `expected`.
+pub fn assert_balances<E: EthSpec>(state: &BeaconState<E>, expected: &[u64]) {
+    assert_eq!(
+        state.balances.len(),
+        expected.len(),
+        "the number of balances should match"
+    );
+
+    let mismatches = state
+        .balances
+        .iter()
+        .zip(expected)
+        .enumerate()
+        .filter(|(_, (actual, expected))| actual != expected)
+        .map(|(i, (actual, expected))| {
+            format!("validator {}: {} != {} (expected)", i, actual, expected)
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        mismatches.is_empty(),
+        "balances should match:\n{}",
+        mismatches.join("\n")
+    );
+}
+
 /// A testing harness which can instantiate a `BeaconChain` and populate it with blocks and
 /// attestations.
 ///
@@ -234,6 +410,14 @@ where
         self.chain.slot_clock.advance_slot();
     }
 
+    /// Advance the clock of the `BeaconChain` by `duration`, which need not be a whole number of
+    /// slots (e.g., to test the clock disparity allowed for gossip).
+    ///
+    /// Does not produce blocks or attestations.
+    pub fn advance_clock(&self, duration: Duration) {
+        self.chain.slot_clock.advance(duration);
+    }
+
     /// Extend the `BeaconChain` with some blocks and attestations. Returns the root of the
     /// last-produced block (the head of the chain).
     ///
@@ -456,7 +640,7 @@ where
 
         let (block, state) = self
             .chain
-            .produce_block_on_state(state, slot, randao_reveal)
+            .produce_block_on_state(state, slot, randao_reveal, None)
             .expect("should produce block");
 
         let signed_block = block.sign(sk, &state.fork, state.genesis_validators_root, &self.spec);
@@ -4,9 +4,12 @@
 extern crate lazy_static;
 
 use beacon_chain::{
-    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType},
-    BeaconSnapshot, BlockError,
+    test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType, HARNESS_SLOT_TIME,
+    },
+    BeaconSnapshot, BlockError, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
+use state_processing::block_signature_verifier::SignatureSetKind;
 use types::{
     test_utils::generate_deterministic_keypair, AggregateSignature, AttestationData,
     AttesterSlashing, Checkpoint, Deposit, DepositData, Epoch, EthSpec, Hash256,
@@ -292,47 +295,52 @@ fn invalid_signatures() {
 
         // For the given snapshots, test the following:
         //
-        // - The `process_chain_segment` function returns `InvalidSignature`.
+        // - The `process_chain_segment` function returns `InvalidSignature`, identifying the
+        //    invalid signature.
         // - The `process_block` function returns `InvalidSignature` when importing the
         //    `SignedBeaconBlock` directly.
         // - The `verify_block_for_gossip` function does _not_ return an error.
         // - The `process_block` function returns `InvalidSignature` when verifying the
         //    `GossipVerifiedBlock`.
-        let assert_invalid_signature = |snapshots: &[BeaconSnapshot<E>], item: &str| {
-            let blocks = snapshots
-                .iter()
-                .map(|snapshot| snapshot.beacon_block.clone())
-                .collect();
-
-            // Ensure the block will be rejected if imported in a chain segment.
-            assert!(
-                matches!(
-                    harness.chain.process_chain_segment(blocks).to_block_error(),
-                    Err(BlockError::InvalidSignature)
-                ),
-                "should not import chain segment with an invalid {} signature",
-                item
-            );
-
-            // Ensure the block will be rejected if imported on its own (without gossip checking).
-            assert!(
-                matches!(
-                    harness
-                        .chain
-                        .process_block(snapshots[block_index].beacon_block.clone()),
-                    Err(BlockError::InvalidSignature)
-                ),
-                "should not import individual block with an invalid {} signature",
-                item
-            );
-
-            // NOTE: we choose not to check gossip verification here. It only checks one signature
-            // (proposal) and that is already tested elsewhere in this file.
-            //
-            // It's not trivial to just check gossip verification since it will start refusing
-            // blocks as soon as it has seen one valid proposal signature for a given (validator,
-            // slot) tuple.
-        };
+        let assert_invalid_signature =
+            |snapshots: &[BeaconSnapshot<E>], item: &str, kind: SignatureSetKind| {
+                let blocks = snapshots
+                    .iter()
+                    .map(|snapshot| snapshot.beacon_block.clone())
+                    .collect();
+                let block_root = snapshots[block_index].beacon_block.canonical_root();
+
+                // Ensure the block will be rejected if imported in a chain segment.
+                assert!(
+                    matches!(
+                        harness.chain.process_chain_segment(blocks).to_block_error(),
+                        Err(BlockError::InvalidSignature(Some(ref invalid)))
+                            if invalid.kind == kind && invalid.block_root == block_root
+                    ),
+                    "should not import chain segment with an invalid {} signature",
+                    item
+                );
+
+                // Ensure the block will be rejected if imported on its own (without gossip
+                // checking).
+                assert!(
+                    matches!(
+                        harness
+                            .chain
+                            .process_block(snapshots[block_index].beacon_block.clone()),
+                        Err(BlockError::InvalidSignature(Some(ref invalid))) if invalid.kind == kind
+                    ),
+                    "should not import individual block with an invalid {} signature",
+                    item
+                );
+
+                // NOTE: we choose not to check gossip verification here. It only checks one signature
+                // (proposal) and that is already tested elsewhere in this file.
+                //
+                // It's not trivial to just check gossip verification since it will start refusing
+                // blocks as soon as it has seen one valid proposal signature for a given (validator,
+                // slot) tuple.
+            };
 
         /*
          * Block proposal
@@ -347,7 +355,8 @@ fn invalid_signatures() {
         assert!(
             matches!(
                 harness.chain.process_chain_segment(blocks).to_block_error(),
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature(Some(ref invalid)))
+                    if invalid.kind == SignatureSetKind::BlockProposal
             ),
             "should not import chain segment with an invalid gossip signature",
         );
@@ -357,7 +366,7 @@ fn invalid_signatures() {
                 harness
                     .chain
                     .process_block(snapshots[block_index].beacon_block.clone()),
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature(_))
             ),
             "should not import individual block with an invalid gossip signature",
         );
@@ -373,7 +382,7 @@ fn invalid_signatures() {
             .randao_reveal = junk_signature();
         update_parent_roots(&mut snapshots);
         update_proposal_signatures(&mut snapshots, &harness);
-        assert_invalid_signature(&snapshots, "randao");
+        assert_invalid_signature(&snapshots, "randao", SignatureSetKind::RandaoReveal);
 
         /*
          * Proposer slashing
@@ -398,7 +407,14 @@ fn invalid_signatures() {
             .expect("should update proposer slashing");
         update_parent_roots(&mut snapshots);
         update_proposal_signatures(&mut snapshots, &harness);
-        assert_invalid_signature(&snapshots, "proposer slashing");
+        assert_invalid_signature(
+            &snapshots,
+            "proposer slashing",
+            SignatureSetKind::ProposerSlashing {
+                index: 0,
+                header: 1,
+            },
+        );
 
         /*
          * Attester slashing
@@ -434,7 +450,14 @@ fn invalid_signatures() {
             .expect("should update attester slashing");
         update_parent_roots(&mut snapshots);
         update_proposal_signatures(&mut snapshots, &harness);
-        assert_invalid_signature(&snapshots, "attester slashing");
+        assert_invalid_signature(
+            &snapshots,
+            "attester slashing",
+            SignatureSetKind::AttesterSlashing {
+                index: 0,
+                attestation: 1,
+            },
+        );
 
         /*
          * Attestation
@@ -450,7 +473,11 @@ fn invalid_signatures() {
             attestation.signature = junk_aggregate_signature();
             update_parent_roots(&mut snapshots);
             update_proposal_signatures(&mut snapshots, &harness);
-            assert_invalid_signature(&snapshots, "attestation");
+            assert_invalid_signature(
+                &snapshots,
+                "attestation",
+                SignatureSetKind::Attestation { index: 0 },
+            );
             checked_attestation = true;
         }
 
@@ -485,7 +512,7 @@ fn invalid_signatures() {
         assert!(
             !matches!(
                 harness.chain.process_chain_segment(blocks).to_block_error(),
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature(_))
             ),
             "should not throw an invalid signature error for a bad deposit signature"
         );
@@ -510,7 +537,11 @@ fn invalid_signatures() {
             .expect("should update deposit");
         update_parent_roots(&mut snapshots);
         update_proposal_signatures(&mut snapshots, &harness);
-        assert_invalid_signature(&snapshots, "voluntary exit");
+        assert_invalid_signature(
+            &snapshots,
+            "voluntary exit",
+            SignatureSetKind::VoluntaryExit { index: 0 },
+        );
     }
 
     assert!(
@@ -707,3 +738,109 @@ fn block_gossip_verification() {
         "the second proposal by this validator should be rejected"
     );
 }
+
+#[test]
+fn block_gossip_verification_clock_disparity() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let block = CHAIN_SEGMENT[0].beacon_block.clone();
+    let block_slot = block.slot();
+
+    // Start the clock at the start of the slot prior to the block.
+    harness.chain.slot_clock.set_slot(block_slot.as_u64() - 1);
+
+    // More than `MAXIMUM_GOSSIP_CLOCK_DISPARITY` before the block's slot.
+    harness.advance_clock(HARNESS_SLOT_TIME - MAXIMUM_GOSSIP_CLOCK_DISPARITY * 2);
+    assert!(
+        matches!(
+            unwrap_err(harness.chain.verify_block_for_gossip(block.clone())),
+            BlockError::FutureSlot {
+                present_slot,
+                block_slot: future_slot,
+            }
+            if present_slot == block_slot - 1 && future_slot == block_slot
+        ),
+        "should not import a block beyond the clock disparity"
+    );
+
+    // Within `MAXIMUM_GOSSIP_CLOCK_DISPARITY` of the block's slot, which has not yet started.
+    harness.advance_clock(MAXIMUM_GOSSIP_CLOCK_DISPARITY * 3 / 2);
+    assert_eq!(
+        harness.chain.slot().expect("should read slot"),
+        block_slot - 1
+    );
+    harness
+        .chain
+        .verify_block_for_gossip(block)
+        .expect("should import a block within the clock disparity");
+}
+
+#[test]
+fn max_skip_slots() {
+    const SKIPPED_SLOTS: u64 = 8;
+
+    // Build a block which skips several slots after genesis.
+    let source = get_harness(VALIDATOR_COUNT);
+    for _ in 0..SKIPPED_SLOTS {
+        source.advance_slot();
+    }
+    source.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+    let block = source.chain.head().expect("should get head").beacon_block;
+
+    let mut harness = get_harness(VALIDATOR_COUNT);
+    for _ in 0..SKIPPED_SLOTS {
+        harness.advance_slot();
+    }
+
+    harness.chain.max_skip_slots = Some(SKIPPED_SLOTS - 1);
+    assert!(
+        matches!(
+            harness.chain.process_block(block.clone()),
+            Err(BlockError::TooManySkippedSlots { parent_slot, block_slot })
+            if parent_slot == Slot::new(0) && block_slot == block.slot()
+        ),
+        "a block skipping more than the maximum should be rejected"
+    );
+
+    harness.chain.max_skip_slots = Some(SKIPPED_SLOTS + 1);
+    assert!(
+        harness.chain.process_block(block).is_ok(),
+        "a block skipping fewer than the maximum should be imported"
+    );
+}
+
+#[test]
+fn max_skip_slots_dense_chain_segment() {
+    let mut harness = get_harness(VALIDATOR_COUNT);
+    let blocks = chain_segment_blocks();
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    // The segment is much longer than the maximum, but does not skip any slots.
+    harness.chain.max_skip_slots = Some(1);
+
+    harness
+        .chain
+        .process_chain_segment(blocks.clone())
+        .to_block_error()
+        .expect("should import a dense chain segment longer than the maximum skip slots");
+
+    harness.chain.fork_choice().expect("should run fork choice");
+
+    assert_eq!(
+        harness
+            .chain
+            .head_info()
+            .expect("should get head info")
+            .block_root,
+        blocks.last().unwrap().canonical_root(),
+        "harness should have last block as head"
+    );
+}
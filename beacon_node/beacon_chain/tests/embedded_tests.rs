@@ -0,0 +1,74 @@
+#![cfg(not(debug_assertions))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use beacon_chain::{
+    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, HARNESS_GENESIS_TIME},
+    EmbeddedChain,
+};
+use genesis::interop_genesis_state;
+use sloggers::{null::NullLoggerBuilder, Build};
+use std::sync::Arc;
+use store::MemoryStore;
+use tempfile::tempdir;
+use types::{EthSpec, Keypair, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+pub const VALIDATOR_COUNT: usize = 16;
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> = types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+#[test]
+fn replays_chain_to_same_head_and_finality() {
+    let harness = BeaconChainHarness::new(MinimalEthSpec, KEYPAIRS.to_vec());
+    harness.advance_slot();
+    harness.extend_chain(
+        E::slots_per_epoch() as usize * 5,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let data_dir = tempdir().unwrap();
+    let log = NullLoggerBuilder.build().expect("logger should build");
+    let genesis_state = interop_genesis_state::<E>(&KEYPAIRS, HARNESS_GENESIS_TIME, &harness.spec)
+        .expect("should generate interop state");
+
+    let embedded = EmbeddedChain::from_genesis(
+        Arc::new(MemoryStore::<E>::open()),
+        harness.spec.clone(),
+        genesis_state,
+        log,
+        data_dir.path().to_path_buf(),
+    )
+    .expect("should build embedded chain");
+
+    let blocks = harness
+        .chain
+        .chain_dump()
+        .expect("should dump chain")
+        .into_iter()
+        .skip(1) // Skip the genesis block.
+        .map(|snapshot| snapshot.beacon_block);
+
+    for block in blocks {
+        embedded.process_block(block).expect("should import block");
+    }
+
+    let expected = harness.chain.head_info().unwrap();
+    let head = embedded.head().unwrap();
+    assert_eq!(head.block_root, expected.block_root);
+    assert_eq!(head.slot, expected.slot);
+
+    let finalized = embedded.finalized_checkpoint().unwrap();
+    assert_eq!(finalized, expected.finalized_checkpoint);
+    assert!(finalized.epoch > 0, "chain should have finalized");
+    assert_eq!(
+        embedded.justified_checkpoint().unwrap(),
+        expected.current_justified_checkpoint
+    );
+}
@@ -15,8 +15,9 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 use store::{
+    hot_cold_store::StateAvailability,
     iter::{BlockRootsIterator, StateRootsIterator},
-    DiskStore, Store, StoreConfig,
+    DiskStore, HotStateSummary, Store, StoreConfig, TemporaryState,
 };
 use tempfile::{tempdir, TempDir};
 use tree_hash::TreeHash;
@@ -36,10 +37,16 @@ type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<DiskHarnessType<E>>;
 
 fn get_store(db_path: &TempDir) -> Arc<DiskStore<E>> {
-    let spec = MinimalEthSpec::default_spec();
+    get_store_with(
+        db_path,
+        StoreConfig::default(),
+        MinimalEthSpec::default_spec(),
+    )
+}
+
+fn get_store_with(db_path: &TempDir, config: StoreConfig, spec: ChainSpec) -> Arc<DiskStore<E>> {
     let hot_path = db_path.path().join("hot_db");
     let cold_path = db_path.path().join("cold_db");
-    let config = StoreConfig::default();
     let log = NullLoggerBuilder.build().expect("logger should build");
     Arc::new(
         DiskStore::open(&hot_path, &cold_path, config, spec, log)
@@ -250,6 +257,60 @@ fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+#[test]
+fn temporary_states_deleted_on_restart() {
+    let db_path = tempdir().unwrap();
+
+    let (orphan_root, imported_root) = {
+        let store = get_store(&db_path);
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness.extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+
+        let head = harness.chain.head().unwrap();
+        // No markers remain once the blocks have been imported.
+        assert_eq!(store.delete_temporary_states().unwrap(), 0);
+
+        // A state whose block was never stored, as if its import was interrupted.
+        let mut orphan_state = head.beacon_state.clone();
+        orphan_state.slot += 1;
+        let orphan_root = Hash256::random();
+        store.put_state(&orphan_root, &orphan_state).unwrap();
+        store
+            .put(
+                &orphan_root,
+                &TemporaryState {
+                    block_root: Hash256::random(),
+                },
+            )
+            .unwrap();
+
+        // A state whose block was stored before its marker was removed.
+        let imported_root = head.beacon_state_root;
+        store
+            .put(
+                &imported_root,
+                &TemporaryState {
+                    block_root: head.beacon_block_root,
+                },
+            )
+            .unwrap();
+
+        (orphan_root, imported_root)
+    };
+
+    let store = get_store(&db_path);
+    assert!(!store.exists::<HotStateSummary>(&orphan_root).unwrap());
+    assert!(store.exists::<HotStateSummary>(&imported_root).unwrap());
+    assert!(!store.exists::<TemporaryState>(&orphan_root).unwrap());
+    assert!(!store.exists::<TemporaryState>(&imported_root).unwrap());
+    assert_eq!(store.delete_temporary_states().unwrap(), 0);
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -1277,6 +1338,98 @@ fn prunes_skipped_slots_states() {
     }
 }
 
+#[test]
+fn prune_historic_blocks_and_states() {
+    let num_blocks_produced = E::slots_per_epoch() * 10;
+    let db_path = tempdir().unwrap();
+
+    // Retain three epochs of history before the split slot.
+    let mut spec = MinimalEthSpec::default_spec();
+    spec.min_validator_withdrawability_delay = Epoch::new(1);
+    spec.churn_limit_quotient = 4;
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let config = StoreConfig {
+        slots_per_restore_point,
+        prune_historic: true,
+        ..StoreConfig::default()
+    };
+    let store = get_store_with(&db_path, config, spec);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    check_finalization(&harness, num_blocks_produced);
+    check_split_slot(&harness, store.clone());
+
+    let retained_slots = store.historic_epochs_retained() * E::slots_per_epoch();
+    let pruned_slot = (store.get_split_slot() - retained_slots) / slots_per_restore_point
+        * slots_per_restore_point;
+    assert!(pruned_slot > 0);
+    assert_eq!(store.get_historic_pruned_slot().unwrap(), Some(pruned_slot));
+
+    // Only the genesis block and retained blocks remain.
+    for (block_root, slot) in harness
+        .chain
+        .forwards_iter_block_roots(Slot::new(0))
+        .unwrap()
+    {
+        assert_eq!(
+            store.get_block(&block_root).unwrap().is_some(),
+            slot == 0 || slot >= pruned_slot,
+            "block at slot {}",
+            slot
+        );
+    }
+
+    // Pruned states cannot be loaded, unlike the genesis state and the retained states.
+    assert!(store
+        .load_cold_state_by_slot(pruned_slot - slots_per_restore_point)
+        .is_err());
+    assert_eq!(
+        store.load_cold_state_by_slot(Slot::new(0)).unwrap().slot,
+        Slot::new(0)
+    );
+    assert_eq!(
+        store.load_cold_state_by_slot(pruned_slot + 1).unwrap().slot,
+        pruned_slot + 1
+    );
+
+    // The availability of each state reflects the split and the pruning.
+    let head_state = harness.chain.head().expect("should get head").beacon_state;
+    let split_slot = store.get_split_slot();
+    let oldest_slot = head_state.slot - E::slots_per_historical_root() as u64;
+    for slot in (oldest_slot.as_u64()..head_state.slot.as_u64()).map(Slot::new) {
+        let state_root = *head_state.get_state_root(slot).unwrap();
+        let expected = if slot >= split_slot {
+            if slot % E::slots_per_epoch() == 0 {
+                StateAvailability::Hot
+            } else {
+                StateAvailability::HotSummary {
+                    replay_slots: slot.as_u64() % E::slots_per_epoch(),
+                }
+            }
+        } else if slot < pruned_slot {
+            StateAvailability::Missing
+        } else if slot % slots_per_restore_point == 0 {
+            StateAvailability::RestorePoint
+        } else {
+            StateAvailability::ColdIntermediate {
+                replay_slots: slot.as_u64() % slots_per_restore_point,
+            }
+        };
+        assert_eq!(
+            store.state_availability(&state_root, slot).unwrap(),
+            expected,
+            "state at slot {}",
+            slot
+        );
+    }
+}
+
 /// Check that the head state's slot matches `expected_slot`.
 fn check_slot(harness: &TestHarness, expected_slot: u64) {
     let state = &harness.chain.head().expect("should get head").beacon_state;
@@ -6,8 +6,10 @@ extern crate lazy_static;
 use beacon_chain::{
     attestation_verification::Error as AttnError,
     test_utils::{
-        AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType, OP_POOL_DB_KEY,
+        assert_balances, AttestationStrategy, BeaconChainHarness, BlockStrategy, ExpectedVote,
+        HarnessType, RewardsScenario, OP_POOL_DB_KEY,
     },
+    BeaconChainError,
 };
 use operation_pool::PersistedOperationPool;
 use state_processing::{
@@ -131,6 +133,57 @@ fn iterators() {
     );
 }
 
+#[test]
+fn canonical_block_root_callbacks() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    // Produce blocks at slots 1..=8 and 10..=13, skipping slot 9.
+    harness.extend_chain(
+        8,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+    harness.advance_slot();
+    harness.extend_chain(
+        4,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+
+    let mut expected: Vec<(Hash256, Slot)> = harness
+        .chain
+        .rev_iter_block_roots()
+        .expect("should get iter")
+        .filter(|(_root, slot)| *slot >= 2 && *slot < 12)
+        .collect();
+    expected.reverse();
+
+    let mut block_roots = vec![];
+    harness
+        .chain
+        .with_canonical_block_roots(Slot::new(2), Slot::new(12), |root, slot| {
+            block_roots.push((root, slot));
+            Ok::<_, BeaconChainError>(())
+        })
+        .expect("should visit block roots");
+    assert_eq!(block_roots, expected);
+    assert_eq!(
+        block_roots[7].0, block_roots[6].0,
+        "slot 9 should be skipped"
+    );
+
+    // The block at slot 8 is not visited from slot 9, since it is prior to the range.
+    let mut block_slots = vec![];
+    harness
+        .chain
+        .with_canonical_blocks(Slot::new(9), Slot::new(12), |_root, block| {
+            block_slots.push(block.slot());
+            Ok::<_, BeaconChainError>(())
+        })
+        .expect("should visit blocks");
+    assert_eq!(block_slots, vec![Slot::new(10), Slot::new(11)]);
+}
+
 #[test]
 fn chooses_fork() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -258,6 +311,80 @@ fn finalizes_with_two_thirds_participation() {
     );
 }
 
+#[test]
+fn attestation_rewards_match_expected() {
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+    let spec = &MinimalEthSpec::default_spec();
+
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let two_thirds = (VALIDATOR_COUNT / 3) * 2;
+    let attesters: Vec<usize> = (0..two_thirds).collect();
+
+    // Stop at the last slot of the third epoch, so that the next block transitions into the
+    // fourth epoch and rewards the votes of the second.
+    harness.extend_chain(
+        (slots_per_epoch * 3 - 1) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(attesters.clone()),
+    );
+
+    let mut state = harness.chain.head().expect("should get head").beacon_state;
+    state
+        .build_committee_cache(RelativeEpoch::Previous, spec)
+        .expect("should build committee cache");
+
+    // Every attestation is included in the block at the next slot.
+    let mut scenario = RewardsScenario::new();
+    for slot in state.previous_epoch().slot_iter(slots_per_epoch) {
+        let proposer_index = harness
+            .chain
+            .block_at_slot(slot + 1)
+            .expect("should read block")
+            .expect("should have block at every slot")
+            .message
+            .proposer_index as usize;
+
+        for committee in state
+            .get_beacon_committees_at_slot(slot)
+            .expect("should get committees")
+        {
+            for &validator_index in committee.committee {
+                if attesters.contains(&validator_index) {
+                    scenario = scenario.vote(
+                        validator_index,
+                        ExpectedVote {
+                            target: true,
+                            head: true,
+                            inclusion_delay: 1,
+                            proposer_index,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    assert_eq!(scenario.votes.len(), attesters.len());
+
+    let deltas = scenario.expected_deltas(&state, spec);
+    assert!(attesters.iter().all(|&i| deltas[i] > 0));
+    assert!((two_thirds..VALIDATOR_COUNT).all(|i| deltas[i] < 0));
+
+    let expected = scenario.expected_balances(&state, spec);
+
+    harness.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(attesters),
+    );
+
+    assert_balances(
+        &harness.chain.head().expect("should get head").beacon_state,
+        &expected,
+    );
+}
+
 #[test]
 fn does_not_finalize_with_less_than_two_thirds_participation() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
@@ -6,7 +6,10 @@ use beacon_chain::{
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     migrate::{BackgroundMigrator, Migrate, NullMigrator},
     slot_clock::{SlotClock, SystemTimeSlotClock},
-    store::{DiskStore, MemoryStore, SimpleDiskStore, Store, StoreConfig},
+    store::{
+        hot_cold_store::HotColdDBError, DiskStore, Error as StoreError, MemoryStore,
+        SimpleDiskStore, Store, StoreConfig,
+    },
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, EventHandler,
 };
 use environment::RuntimeContext;
@@ -15,6 +18,7 @@ use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
+use rest_api::LocalRequestSender;
 use slog::info;
 use ssz::Decode;
 use std::net::SocketAddr;
@@ -55,6 +59,7 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     network_send: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     http_listen_addr: Option<SocketAddr>,
+    local_api: Option<LocalRequestSender>,
     websocket_listen_addr: Option<SocketAddr>,
     eth_spec_instance: T::EthSpec,
 }
@@ -89,6 +94,7 @@ where
             network_globals: None,
             network_send: None,
             http_listen_addr: None,
+            local_api: None,
             websocket_listen_addr: None,
             eth_spec_instance,
         }
@@ -137,7 +143,38 @@ where
             .store_migrator(store_migrator)
             .data_dir(data_dir)
             .custom_spec(spec.clone())
-            .disabled_forks(disabled_forks);
+            .disabled_forks(disabled_forks)
+            .attestation_inclusion_strategy(config.attestation_inclusion_strategy);
+
+        let builder = if let Some(path) = config.record_inputs.clone() {
+            builder.record_inputs(path)
+        } else {
+            builder
+        };
+
+        let builder = if let Some(command) = config.differential_test_command.clone() {
+            builder.differential_test(command)
+        } else {
+            builder
+        };
+
+        let builder = if let Some(minutes) = config.event_journal_minutes {
+            builder.event_journal(Duration::from_secs(minutes * 60))
+        } else {
+            builder
+        };
+
+        let builder = if config.attestation_self_check {
+            builder.attestation_self_check()
+        } else {
+            builder
+        };
+
+        let builder = if let Some(max_skip_slots) = config.max_skip_slots {
+            builder.max_skip_slots(max_skip_slots)
+        } else {
+            builder
+        };
 
         let chain_exists = builder
             .store_contains_beacon_chain()
@@ -304,8 +341,9 @@ where
                     .map_err(|_| "unable to read freezer DB dir")?,
                 eth2_config.clone(),
                 log,
+                context.log_control.clone(),
             )
-            .map_err(|e| format!("Failed to start HTTP API: {:?}", e))
+            .map_err(|e| format!("Failed to start HTTP API: {}", e))
         })?;
 
         self.exit_channels.push(exit_channel);
@@ -314,6 +352,63 @@ where
         Ok(self)
     }
 
+    /// Immediately starts serving the HTTP API to requests from within this process (e.g., from a
+    /// validator client), which are sent on the channel returned by `Client::local_api`.
+    pub fn local_api(
+        mut self,
+        client_config: &ClientConfig,
+        eth2_config: &Eth2Config,
+    ) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "local_api requires a beacon chain")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "local_api requires a runtime_context")?
+            .service_context("local_api".into());
+        let network_globals = self
+            .network_globals
+            .clone()
+            .ok_or_else(|| "local_api requires a libp2p network")?;
+        let network_send = self
+            .network_send
+            .clone()
+            .ok_or_else(|| "local_api requires a libp2p network sender")?;
+
+        let network_info = rest_api::NetworkInfo {
+            network_globals,
+            network_chan: network_send,
+        };
+        let db_path = client_config
+            .create_db_path()
+            .map_err(|_| "unable to read data dir")?;
+        let freezer_db_path = client_config
+            .create_freezer_db_path()
+            .map_err(|_| "unable to read freezer DB dir")?;
+
+        let (sender, receiver) = rest_api::local_request_channel();
+        let exit_channel = context.runtime_handle.enter(|| {
+            rest_api::serve_local_requests(
+                &client_config.rest_api,
+                beacon_chain,
+                network_info,
+                db_path,
+                freezer_db_path,
+                eth2_config.clone(),
+                context.log.clone(),
+                context.log_control.clone(),
+                receiver,
+            )
+        });
+
+        self.exit_channels.push(exit_channel);
+        self.local_api = Some(sender);
+
+        Ok(self)
+    }
+
     /// Immediately starts the service that periodically logs information each slot.
     pub fn notifier(mut self) -> Result<Self, String> {
         let context = self
@@ -343,6 +438,7 @@ where
                     network_globals,
                     milliseconds_per_slot,
                     context.log.clone(),
+                    context.shutdown_sender.clone(),
                 )
             })
             .map_err(|e| format!("Unable to start slot notifier: {}", e))?;
@@ -364,6 +460,7 @@ where
             beacon_chain: self.beacon_chain,
             network_globals: self.network_globals,
             http_listen_addr: self.http_listen_addr,
+            local_api: self.local_api,
             websocket_listen_addr: self.websocket_listen_addr,
             _exit_channels: self.exit_channels,
         }
@@ -494,8 +591,19 @@ where
             .clone()
             .ok_or_else(|| "disk_store requires a chain spec".to_string())?;
 
-        let store = DiskStore::open(hot_path, cold_path, config, spec, context.log)
-            .map_err(|e| format!("Unable to open database: {:?}", e))?;
+        let store = match DiskStore::open(hot_path, cold_path, config, spec, context.log) {
+            Ok(store) => store,
+            Err(StoreError::HotColdDBError(HotColdDBError::MismatchedFreezerDatabase {
+                ..
+            })) => {
+                return Err(format!(
+                    "The freezer database at {:?} does not belong to the database at {:?}. \
+                     Check that --freezer-dir is correct and that its disk is mounted.",
+                    cold_path, hot_path
+                ))
+            }
+            Err(e) => return Err(format!("Unable to open database: {:?}", e)),
+        };
         self.store = Some(Arc::new(store));
         Ok(self)
     }
@@ -521,8 +629,8 @@ where
 {
     /// Specifies that the `Client` should use a `DiskStore` database.
     pub fn simple_disk_store(mut self, path: &Path) -> Result<Self, String> {
-        let store =
-            SimpleDiskStore::open(path).map_err(|e| format!("Unable to open database: {:?}", e))?;
+        let store = SimpleDiskStore::open(path, false)
+            .map_err(|e| format!("Unable to open database: {:?}", e))?;
         self.store = Some(Arc::new(store));
         Ok(self)
     }
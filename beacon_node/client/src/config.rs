@@ -1,3 +1,4 @@
+use beacon_chain::AttestationInclusionStrategy;
 use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
@@ -55,6 +56,19 @@ pub struct Config {
     pub sync_eth1_chain: bool,
     /// A list of hard-coded forks that will be disabled.
     pub disabled_forks: Vec<String>,
+    /// If set, the consensus-relevant inputs to the beacon chain are appended to this file.
+    pub record_inputs: Option<PathBuf>,
+    /// If set, the state transition of each imported block is compared against the reference
+    /// implementation run by this command.
+    pub differential_test_command: Option<PathBuf>,
+    /// If `true`, produced attestation data is compared with the aggregates seen on gossip.
+    pub attestation_self_check: bool,
+    /// If set, blocks and attestations which skip more than this many slots are rejected.
+    pub max_skip_slots: Option<u64>,
+    /// If set, head and finalization events from this many minutes are retained in a journal.
+    pub event_journal_minutes: Option<u64>,
+    /// Determines whether attestations more than one epoch old are included in produced blocks.
+    pub attestation_inclusion_strategy: AttestationInclusionStrategy,
     #[serde(skip)]
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
@@ -84,6 +98,12 @@ impl Default for Config {
             sync_eth1_chain: false,
             eth1: <_>::default(),
             disabled_forks: Vec::new(),
+            record_inputs: None,
+            differential_test_command: None,
+            attestation_self_check: false,
+            max_skip_slots: None,
+            event_journal_minutes: None,
+            attestation_inclusion_strategy: AttestationInclusionStrategy::default(),
         }
     }
 }
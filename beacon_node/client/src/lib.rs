@@ -9,6 +9,7 @@ pub mod error;
 
 use beacon_chain::BeaconChain;
 use eth2_libp2p::{Enr, Multiaddr, NetworkGlobals};
+use rest_api::LocalRequestSender;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -24,6 +25,7 @@ pub struct Client<T: BeaconChainTypes> {
     beacon_chain: Option<Arc<BeaconChain<T>>>,
     network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     http_listen_addr: Option<SocketAddr>,
+    local_api: Option<LocalRequestSender>,
     websocket_listen_addr: Option<SocketAddr>,
     /// Exit channels will complete/error when dropped, causing each service to exit gracefully.
     _exit_channels: Vec<tokio::sync::oneshot::Sender<()>>,
@@ -40,6 +42,12 @@ impl<T: BeaconChainTypes> Client<T> {
         self.http_listen_addr
     }
 
+    /// Returns a channel on which requests may be sent to the client's HTTP API from within this
+    /// process, if it is serving them.
+    pub fn local_api(&self) -> Option<LocalRequestSender> {
+        self.local_api.clone()
+    }
+
     /// Returns the address of the client's WebSocket API server, if it was started.
     pub fn websocket_listen_addr(&self) -> Option<SocketAddr> {
         self.websocket_listen_addr
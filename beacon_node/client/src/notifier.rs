@@ -1,9 +1,10 @@
 use crate::metrics;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use environment::{ShutdownReason, ShutdownSender};
 use eth2_libp2p::NetworkGlobals;
 use futures::prelude::*;
 use parking_lot::Mutex;
-use slog::{debug, error, info, warn};
+use slog::{crit, debug, error, info, warn};
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -22,11 +23,15 @@ const MINUTES_PER_HOUR: i64 = 60;
 const SPEEDO_OBSERVATIONS: usize = 4;
 
 /// Spawns a notifier service which periodically logs information about the node.
+///
+/// The notifier also checks that the head block can be read from the database, requesting a
+/// shutdown via `shutdown_sender` if it cannot (e.g., because the database is corrupt).
 pub fn spawn_notifier<T: BeaconChainTypes>(
     beacon_chain: Arc<BeaconChain<T>>,
     network: Arc<NetworkGlobals<T::EthSpec>>,
     milliseconds_per_slot: u64,
     log: slog::Logger,
+    shutdown_sender: ShutdownSender,
 ) -> Result<tokio::sync::oneshot::Sender<()>, String> {
     let slot_duration = Duration::from_millis(milliseconds_per_slot);
     let duration_to_next_slot = beacon_chain
@@ -75,6 +80,26 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
                 )
             })?;
 
+            // The head block is always in the database, so failing to read it indicates that the
+            // database is corrupt and the node cannot continue.
+            let head_block = beacon_chain
+                .get_block(&head_info.block_root)
+                .map_err(|e| format!("{:?}", e))
+                .and_then(|block| block.ok_or_else(|| "Head block not found".to_string()));
+            if let Err(e) = head_block {
+                crit!(
+                    log,
+                    "Unable to read head block from database";
+                    "head_block" => format!("{}", head_info.block_root),
+                    "error" => &e
+                );
+                let _ = shutdown_sender.unbounded_send(ShutdownReason::Fatal(format!(
+                    "Unable to read head block {} from database: {}",
+                    head_info.block_root, e
+                )));
+                return Err(());
+            }
+
             let head_slot = head_info.slot;
             let current_slot = beacon_chain.slot().map_err(|e| {
                 error!(
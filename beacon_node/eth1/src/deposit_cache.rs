@@ -1,3 +1,4 @@
+use crate::metrics;
 use crate::DepositLog;
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
@@ -69,10 +70,31 @@ impl SszDepositCache {
             deposit_contract_deploy_block: self.deposit_contract_deploy_block,
             deposit_tree,
             deposit_roots: self.deposit_roots.clone(),
+            precomputed: None,
         })
     }
 }
 
+/// The result of a call to `DepositCache::get_deposits` which was prepared in advance by
+/// `DepositCache::precompute_deposits`.
+struct PrecomputedDeposits {
+    start: u64,
+    end: u64,
+    deposit_count: u64,
+    tree_depth: usize,
+    deposit_root: Hash256,
+    deposits: Vec<Deposit>,
+}
+
+impl PrecomputedDeposits {
+    fn matches(&self, start: u64, end: u64, deposit_count: u64, tree_depth: usize) -> bool {
+        self.start == start
+            && self.end == end
+            && self.deposit_count == deposit_count
+            && self.tree_depth == tree_depth
+    }
+}
+
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
@@ -86,6 +108,8 @@ pub struct DepositCache {
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
     /// `deposit_index` `i`.
     deposit_roots: Vec<Hash256>,
+    /// The most recently precomputed deposits, if any.
+    precomputed: Option<PrecomputedDeposits>,
 }
 
 impl Default for DepositCache {
@@ -98,6 +122,7 @@ impl Default for DepositCache {
             deposit_contract_deploy_block: 1,
             deposit_tree,
             deposit_roots,
+            precomputed: None,
         }
     }
 }
@@ -189,6 +214,64 @@ impl DepositCache {
         end: u64,
         deposit_count: u64,
         tree_depth: usize,
+    ) -> Result<(Hash256, Vec<Deposit>), Error> {
+        match &self.precomputed {
+            Some(precomputed) if precomputed.matches(start, end, deposit_count, tree_depth) => {
+                metrics::inc_counter(&metrics::PRECOMPUTED_DEPOSITS_HITS);
+                Ok((precomputed.deposit_root, precomputed.deposits.clone()))
+            }
+            _ => {
+                if start < end {
+                    metrics::inc_counter(&metrics::PRECOMPUTED_DEPOSITS_MISSES);
+                }
+                self.generate_deposits(start, end, deposit_count, tree_depth)
+            }
+        }
+    }
+
+    /// Generates the deposits returned by `get_deposits` for the same arguments and retains them,
+    /// so that they are returned by `get_deposits` without rebuilding the deposit tree (which is
+    /// costly when there are many deposits). Only the most recently precomputed deposits are
+    /// retained.
+    ///
+    /// Since logs are never removed or replaced once inserted, precomputed deposits remain valid
+    /// as further logs are inserted.
+    ///
+    /// Returns `true` if the deposits were generated, or `false` if they were already retained.
+    pub fn precompute_deposits(
+        &mut self,
+        start: u64,
+        end: u64,
+        deposit_count: u64,
+        tree_depth: usize,
+    ) -> Result<bool, Error> {
+        if let Some(precomputed) = &self.precomputed {
+            if precomputed.matches(start, end, deposit_count, tree_depth) {
+                return Ok(false);
+            }
+        }
+
+        let (deposit_root, deposits) =
+            self.generate_deposits(start, end, deposit_count, tree_depth)?;
+        self.precomputed = Some(PrecomputedDeposits {
+            start,
+            end,
+            deposit_count,
+            tree_depth,
+            deposit_root,
+            deposits,
+        });
+
+        Ok(true)
+    }
+
+    /// Generates the deposits for `get_deposits`, ignoring any precomputed deposits.
+    fn generate_deposits(
+        &self,
+        start: u64,
+        end: u64,
+        deposit_count: u64,
+        tree_depth: usize,
     ) -> Result<(Hash256, Vec<Deposit>), Error> {
         if deposit_count < end {
             // It's invalid to ask for more deposits than should exist.
@@ -276,6 +359,13 @@ impl DepositCache {
         }
     }
 
+    /// Gets the deposit root after the first `deposit_count` deposits were added to the tree.
+    ///
+    /// Returns `None` if the cache contains fewer than `deposit_count` deposits.
+    pub fn get_deposit_root_by_count(&self, deposit_count: u64) -> Option<Hash256> {
+        self.deposit_roots.get(deposit_count as usize).copied()
+    }
+
     /// Gets the deposit root at block height = block_number.
     ///
     /// Fetches the `deposit_count` on or just before the queried `block_number`
@@ -439,4 +529,46 @@ pub mod tests {
         // Range higher than count.
         assert!(tree.get_deposits(0, 4, 2, TREE_DEPTH).is_err());
     }
+
+    #[test]
+    fn precompute_deposits() {
+        let n = 16;
+        let mut tree = DepositCache::default();
+
+        for i in 0..n / 2 {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        // The deposits are not yet known.
+        assert!(tree.precompute_deposits(2, 6, n, TREE_DEPTH).is_err());
+        assert_eq!(tree.precompute_deposits(2, 6, n / 2, TREE_DEPTH), Ok(true));
+        assert_eq!(tree.precompute_deposits(2, 6, n / 2, TREE_DEPTH), Ok(false));
+
+        for i in n / 2..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        // The precomputed deposits are unaffected by the later logs.
+        let precomputed = tree
+            .get_deposits(2, 6, n / 2, TREE_DEPTH)
+            .expect("should get precomputed deposits");
+        assert_eq!(
+            Ok(precomputed),
+            tree.generate_deposits(2, 6, n / 2, TREE_DEPTH)
+        );
+
+        // Other requests generate their deposits.
+        let (_, deposits) = tree
+            .get_deposits(2, 6, n, TREE_DEPTH)
+            .expect("should get deposits");
+        assert_eq!(deposits.len(), 4);
+    }
 }
@@ -7,10 +7,21 @@ use parking_lot::RwLock;
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 
+/// A range of deposits, and the deposit count to prove them against, which is expected to be
+/// requested for inclusion in the next block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpectedDeposits {
+    pub start: u64,
+    pub end: u64,
+    pub deposit_count: u64,
+}
+
 #[derive(Default)]
 pub struct DepositUpdater {
     pub cache: DepositCache,
     pub last_processed_block: Option<u64>,
+    /// The deposits to precompute each time the cache is updated.
+    pub expected_deposits: Option<ExpectedDeposits>,
 }
 
 impl DepositUpdater {
@@ -19,6 +30,7 @@ impl DepositUpdater {
         DepositUpdater {
             cache,
             last_processed_block: None,
+            expected_deposits: None,
         }
     }
 }
@@ -78,6 +90,7 @@ impl SszEth1Cache {
             deposit_cache: RwLock::new(DepositUpdater {
                 cache: self.deposit_cache.to_deposit_cache()?,
                 last_processed_block: self.last_processed_block,
+                expected_deposits: None,
             }),
             config: RwLock::new(config),
         })
@@ -12,5 +12,5 @@ mod service;
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::DepositCache;
 pub use deposit_log::DepositLog;
-pub use inner::SszEth1Cache;
+pub use inner::{ExpectedDeposits, SszEth1Cache};
 pub use service::{BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Service};
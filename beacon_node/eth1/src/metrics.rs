@@ -16,4 +16,8 @@ lazy_static! {
         try_create_int_gauge("eth1_deposit_cache_len", "Number of deposits in the eth1 cache");
     pub static ref HIGHEST_PROCESSED_DEPOSIT_BLOCK: Result<IntGauge> =
         try_create_int_gauge("eth1_highest_processed_deposit_block", "Number of the last block checked for deposits");
+    pub static ref PRECOMPUTED_DEPOSITS_HITS: Result<IntCounter> =
+        try_create_int_counter("eth1_precomputed_deposits_hits_total", "Count of requests for deposits which were served by the precomputed deposits");
+    pub static ref PRECOMPUTED_DEPOSITS_MISSES: Result<IntCounter> =
+        try_create_int_counter("eth1_precomputed_deposits_misses_total", "Count of requests for deposits which required their proofs to be generated");
 }
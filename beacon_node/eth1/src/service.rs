@@ -3,7 +3,7 @@ use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
     deposit_cache::Error as DepositCacheError,
     http::{get_block, get_block_number, get_deposit_logs_in_range, Log},
-    inner::{DepositUpdater, Inner},
+    inner::{DepositUpdater, ExpectedDeposits, Inner},
     DepositLog,
 };
 use futures::{future::TryFutureExt, stream, stream::TryStreamExt, StreamExt};
@@ -14,6 +14,7 @@ use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{interval_at, Duration, Instant};
+use types::DEPOSIT_TREE_DEPTH;
 
 const STANDARD_TIMEOUT_MILLIS: u64 = 15_000;
 
@@ -204,6 +205,47 @@ impl Service {
         self.deposits().read().cache.len()
     }
 
+    /// Sets the deposits which are expected to be requested for the next block, so that their
+    /// proofs are generated each time the deposit cache is updated rather than during block
+    /// production.
+    pub fn set_expected_deposits(&self, expected_deposits: ExpectedDeposits) {
+        self.deposits().write().expected_deposits = Some(expected_deposits);
+    }
+
+    /// Precomputes the expected deposits, if any, unless they are already precomputed.
+    fn precompute_expected_deposits(&self) {
+        let mut deposits = self.deposits().write();
+        let expected = match deposits.expected_deposits {
+            Some(expected) => expected,
+            None => return,
+        };
+
+        match deposits.cache.precompute_deposits(
+            expected.start,
+            expected.end,
+            expected.deposit_count,
+            DEPOSIT_TREE_DEPTH,
+        ) {
+            Ok(true) => debug!(
+                self.log,
+                "Precomputed deposits for block production";
+                "start" => expected.start,
+                "end" => expected.end,
+                "deposit_count" => expected.deposit_count
+            ),
+            Ok(false) => (),
+            // The cache may not yet contain the expected deposits.
+            Err(e) => debug!(
+                self.log,
+                "Unable to precompute deposits";
+                "error" => format!("{:?}", e),
+                "start" => expected.start,
+                "end" => expected.end,
+                "deposit_count" => expected.deposit_count
+            ),
+        }
+    }
+
     /// Read the service's configuration.
     pub fn config(&self) -> RwLockReadGuard<Config> {
         self.inner.config.read()
@@ -459,6 +501,8 @@ impl Service {
             );
         }
 
+        service.precompute_expected_deposits();
+
         Ok(DepositCacheUpdateOutcome { logs_imported })
     }
 
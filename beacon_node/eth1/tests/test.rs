@@ -758,3 +758,115 @@ mod persist {
         );
     }
 }
+
+/// Tests against the in-process mock eth1 node, which do not require `ganache-cli`.
+mod mock_eth1 {
+    use super::*;
+    use eth1_test_rig::MockEth1Instance;
+
+    fn mock_service(eth1: &MockEth1Instance, follow_distance: u64) -> Service {
+        Service::new(
+            Config {
+                endpoint: eth1.endpoint(),
+                deposit_contract_address: eth1.deposit_contract_address(),
+                deposit_contract_deploy_block: 0,
+                lowest_cached_block_number: 0,
+                follow_distance,
+                ..Config::default()
+            },
+            null_logger(),
+        )
+    }
+
+    async fn update_caches(service: &Service) {
+        Service::update_deposit_cache(service.clone())
+            .await
+            .expect("should update deposit cache");
+        Service::update_block_cache(service.clone())
+            .await
+            .expect("should update block cache");
+    }
+
+    #[tokio::test]
+    async fn deposits() {
+        let eth1 = MockEth1Instance::new()
+            .await
+            .expect("should start mock eth1 node");
+        let service = mock_service(&eth1, 0);
+
+        let deposits: Vec<_> = (0..4).map(|_| random_deposit_data()).collect();
+        eth1.deposit(deposits[..1].to_vec());
+        eth1.mine();
+        let head = eth1.deposit(deposits[1..].to_vec());
+
+        update_caches(&service).await;
+
+        assert_eq!(service.deposit_cache_len(), deposits.len());
+        assert_eq!(service.block_cache_len(), head.number as usize + 1);
+
+        let (root, local_deposits) = service
+            .deposits()
+            .read()
+            .cache
+            .get_deposits(0, 4, 4, DEPOSIT_CONTRACT_TREE_DEPTH)
+            .expect("should get deposits");
+        assert_eq!(
+            local_deposits
+                .iter()
+                .map(|d| d.data.clone())
+                .collect::<Vec<_>>(),
+            deposits,
+            "obtained deposits should match those submitted"
+        );
+        assert_eq!(root, head.deposit_root, "deposit roots should match");
+
+        let endpoint = eth1.endpoint();
+        let address = eth1.deposit_contract_address();
+        assert_eq!(
+            get_deposit_root(&endpoint, &address, head.number, timeout())
+                .await
+                .expect("should get deposit root"),
+            Some(head.deposit_root)
+        );
+        assert_eq!(
+            get_deposit_count(&endpoint, &address, head.number, timeout())
+                .await
+                .expect("should get deposit count"),
+            Some(deposits.len() as u64)
+        );
+    }
+
+    #[tokio::test]
+    async fn reorg_within_follow_distance() {
+        let eth1 = MockEth1Instance::new()
+            .await
+            .expect("should start mock eth1 node");
+        let follow_distance = 4;
+        let service = mock_service(&eth1, follow_distance);
+
+        for _ in 0..8 {
+            eth1.mine();
+        }
+        update_caches(&service).await;
+
+        eth1.reorg(follow_distance);
+        for _ in 0..4 {
+            eth1.mine();
+        }
+        update_caches(&service).await;
+
+        // Blocks within the follow distance are never imported, so the reorg is not observed.
+        assert_eq!(
+            service.blocks().read().highest_block_number(),
+            Some(eth1.block_number() - follow_distance)
+        );
+        for block in service.blocks().read().iter() {
+            assert_eq!(
+                eth1.block(block.number).map(|b| b.hash),
+                Some(block.hash),
+                "cached block {} should be in the canonical chain",
+                block.number
+            );
+        }
+    }
+}
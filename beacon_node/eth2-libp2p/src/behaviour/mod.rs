@@ -1,7 +1,8 @@
 use crate::discovery::{enr::Eth2Enr, Discovery};
+use crate::metrics;
 use crate::peer_manager::{PeerManager, PeerManagerEvent};
 use crate::rpc::*;
-use crate::types::{GossipEncoding, GossipKind, GossipTopic};
+use crate::types::{Direction, GossipEncoding, GossipKind, GossipTopic};
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
 use discv5::Discv5Event;
 use futures::prelude::*;
@@ -25,6 +26,7 @@ use std::{
     marker::PhantomData,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use types::{EnrForkId, EthSpec, SubnetId};
 
@@ -53,10 +55,12 @@ pub struct Behaviour<TSpec: EthSpec> {
     // TODO: add events to send to the handler
     /// The current meta data of the node, so respond to pings and get metadata
     meta_data: MetaData<TSpec>,
-    /// A cache of recently seen gossip messages. This is used to filter out any possible
-    /// duplicates that may still be seen over gossipsub.
+    /// A cache of recently seen gossip messages, along with the time they were first seen. This is
+    /// used to filter out any possible duplicates that may still be seen over gossipsub.
     // TODO: Remove this
-    seen_gossip_messages: LruCache<MessageId, ()>,
+    seen_gossip_messages: LruCache<MessageId, Instant>,
+    /// If set, entries in `seen_gossip_messages` older than this are no longer considered seen.
+    seen_gossip_ttl: Option<Duration>,
     /// A collections of variables accessible outside the network service.
     network_globals: Arc<NetworkGlobals<TSpec>>,
     /// Keeps track of the current EnrForkId for upgrading gossipsub topics.
@@ -284,7 +288,8 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             identify,
             peer_manager: PeerManager::new(network_globals.clone(), log),
             events: Vec::new(),
-            seen_gossip_messages: LruCache::new(100_000),
+            seen_gossip_messages: LruCache::new(std::cmp::max(net_conf.seen_gossip_cache_size, 1)),
+            seen_gossip_ttl: net_conf.seen_gossip_cache_ttl,
             meta_data,
             network_globals,
             enr_fork_id,
@@ -376,6 +381,12 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 match message.encode(GossipEncoding::default()) {
                     Ok(message_data) => {
+                        self.record_gossip_traffic(
+                            &topic.kind().to_string(),
+                            None,
+                            Direction::Outbound,
+                            message_data.len(),
+                        );
                         self.gossipsub.publish(&topic.into(), message_data);
                     }
                     Err(e) => crit!(self.log, "Could not publish message"; "error" => e),
@@ -395,6 +406,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
     /// Sends an RPC Request/Response via the RPC protocol.
     pub fn send_rpc(&mut self, peer_id: PeerId, rpc_event: RPCEvent<TSpec>) {
+        self.record_rpc_traffic(&peer_id, &rpc_event, Direction::Outbound);
         self.eth2_rpc.send_rpc(peer_id, rpc_event);
     }
 
@@ -415,6 +427,11 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.discovery.enr_entries()
     }
 
+    /// Returns the ENR of a known peer, if it is cached by the discovery mechanism.
+    pub fn enr_of_peer(&mut self, peer_id: &PeerId) -> Option<Enr> {
+        self.discovery.enr_of_peer(peer_id)
+    }
+
     /// Add an ENR to the routing table of the discovery mechanism.
     pub fn add_enr(&mut self, enr: Enr) {
         self.discovery.add_enr(enr);
@@ -431,6 +448,11 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.update_metadata();
     }
 
+    /// Restarts the discovery of new peers.
+    pub fn discover_peers(&mut self) {
+        self.discovery.discover_peers();
+    }
+
     /// A request to search for peers connected to a long-lived subnet.
     pub fn peers_request(&mut self, subnet_id: SubnetId) {
         self.discovery.peers_request(subnet_id);
@@ -548,12 +570,98 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
     }
     */
 
+    /// Records the payload bytes of a gossip message on `topic`, along with the peer it was
+    /// received from, if any.
+    fn record_gossip_traffic(
+        &self,
+        topic: &str,
+        peer_id: Option<&PeerId>,
+        direction: Direction,
+        bytes: usize,
+    ) {
+        metrics::inc_counter_vec_by(
+            &metrics::GOSSIP_BYTES,
+            &[topic, direction.as_str()],
+            bytes as i64,
+        );
+        self.network_globals
+            .traffic
+            .add_gossip(topic, direction, bytes);
+        if let Some(peer_id) = peer_id {
+            self.network_globals
+                .peers
+                .read()
+                .add_traffic(peer_id, direction, bytes);
+        }
+    }
+
+    /// Records the payload bytes of an RPC event sent to or received from `peer_id`.
+    fn record_rpc_traffic(&self, peer_id: &PeerId, event: &RPCEvent<TSpec>, direction: Direction) {
+        let (protocol, bytes) = match event {
+            RPCEvent::Request(_, request) => (request.protocol(), request.ssz_bytes_len()),
+            RPCEvent::Response(_, RPCCodedResponse::Success(response)) => {
+                (response.protocol(), response.ssz_bytes_len())
+            }
+            // errors and stream terminations carry a negligible amount of data
+            _ => return,
+        };
+        let protocol = protocol.to_string();
+
+        metrics::inc_counter_vec_by(
+            &metrics::RPC_BYTES,
+            &[protocol.as_str(), direction.as_str()],
+            bytes as i64,
+        );
+        self.network_globals
+            .traffic
+            .add_rpc(&protocol, direction, bytes);
+        self.network_globals
+            .peers
+            .read()
+            .add_traffic(peer_id, direction, bytes);
+    }
+
+    /// Records `id` as seen, returning `true` if it had already been seen within the TTL.
+    fn is_duplicate_gossip(&mut self, id: &MessageId) -> bool {
+        let now = Instant::now();
+        let ttl = self.seen_gossip_ttl;
+        let seen = self
+            .seen_gossip_messages
+            .get(id)
+            .map_or(false, |first_seen| {
+                ttl.map_or(true, |ttl| now.duration_since(*first_seen) < ttl)
+            });
+
+        if !seen {
+            self.seen_gossip_messages.put(id.clone(), now);
+        }
+        metrics::set_gauge(
+            &metrics::GOSSIP_SEEN_CACHE_SIZE,
+            self.seen_gossip_messages.len() as i64,
+        );
+
+        seen
+    }
+
     fn on_gossip_event(&mut self, event: GossipsubEvent) {
         match event {
             GossipsubEvent::Message(propagation_source, id, gs_msg) => {
+                // duplicates are included, since they still consume bandwidth
+                let topic = gs_msg
+                    .topics
+                    .first()
+                    .and_then(|topic| GossipTopic::decode(topic.as_str()).ok())
+                    .map_or_else(|| "unknown".to_string(), |topic| topic.kind().to_string());
+                self.record_gossip_traffic(
+                    &topic,
+                    Some(&propagation_source),
+                    Direction::Inbound,
+                    gs_msg.data.len(),
+                );
+
                 // Note: We are keeping track here of the peer that sent us the message, not the
                 // peer that originally published the message.
-                if self.seen_gossip_messages.put(id.clone(), ()).is_none() {
+                if !self.is_duplicate_gossip(&id) {
                     match PubsubMessage::decode(&gs_msg.topics, &gs_msg.data) {
                         Err(e) => {
                             debug!(self.log, "Could not decode gossipsub message"; "error" => format!("{}", e))
@@ -574,6 +682,11 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                             debug!(self.log, "Could not decode gossipsub message"; "error" => format!("{}", e))
                         }
                         Ok(msg) => {
+                            let kind = msg.kind().to_string();
+                            metrics::inc_counter_vec(
+                                &metrics::GOSSIP_DUPLICATE_MESSAGES,
+                                &[kind.as_str()],
+                            );
                             debug!(self.log, "A duplicate gossipsub message was received"; "message_source" => format!("{}", gs_msg.source), "propagated_peer" => format!("{}",propagation_source), "message" => format!("{}", msg));
                         }
                     }
@@ -589,6 +702,8 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
     fn on_rpc_event(&mut self, message: RPCMessage<TSpec>) {
         let peer_id = message.peer_id;
+        self.record_rpc_traffic(&peer_id, &message.event, Direction::Inbound);
+
         // The METADATA and PING RPC responses are handled within the behaviour and not
         // propagated
         // TODO: Improve the RPC types to better handle this logic discrepancy
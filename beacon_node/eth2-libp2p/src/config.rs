@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 pub const GOSSIP_MAX_SIZE: usize = 1_048_576;
+/// The default number of gossip message ids remembered in order to suppress duplicates.
+pub const DEFAULT_SEEN_GOSSIP_CACHE_SIZE: usize = 100_000;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -17,9 +19,14 @@ pub struct Config {
     /// Data directory where node's keyfile is stored
     pub network_dir: PathBuf,
 
-    /// IP address to listen on.
+    /// IP address to listen on. This may be an IPv4 or IPv6 address and determines the address
+    /// family used for discovery.
     pub listen_address: std::net::IpAddr,
 
+    /// An additional IPv6 address to listen on for libp2p connections, allowing dual-stack
+    /// operation when `listen_address` is an IPv4 address.
+    pub listen_address_v6: Option<std::net::Ipv6Addr>,
+
     /// The TCP port that libp2p listens on.
     pub libp2p_port: u16,
 
@@ -36,6 +43,15 @@ pub struct Config {
     /// The tcp port to broadcast to peers in order to reach back for libp2p services.
     pub enr_tcp_port: Option<u16>,
 
+    /// The IPv6 address to broadcast to peers when listening on `listen_address_v6`.
+    pub enr_address_v6: Option<std::net::Ipv6Addr>,
+
+    /// The tcp port to broadcast to peers for libp2p services over `listen_address_v6`.
+    pub enr_tcp6_port: Option<u16>,
+
+    /// When a peer is reachable over both IPv4 and IPv6, dial its IPv6 address first.
+    pub prefer_ipv6: bool,
+
     /// Target number of connected peers.
     pub max_peers: usize,
 
@@ -69,6 +85,18 @@ pub struct Config {
     /// testing purposes and will likely be removed in future versions.
     // TODO: Remove this functionality for mainnet
     pub propagation_percentage: Option<u8>,
+
+    /// The maximum number of recently seen gossip message ids to remember. Messages with an id in
+    /// this cache are treated as duplicates and are not passed to the application.
+    pub seen_gossip_cache_size: usize,
+
+    /// If set, a seen gossip message id is forgotten after this duration, even if the cache is not
+    /// full.
+    pub seen_gossip_cache_ttl: Option<Duration>,
+
+    /// The number of slots, in addition to a fraction of a slot, before a validator's duty that
+    /// the node subscribes to the duty's attestation subnet.
+    pub subnet_subscription_lookahead_slots: u64,
 }
 
 impl Default for Config {
@@ -124,11 +152,15 @@ impl Default for Config {
         Config {
             network_dir,
             listen_address: "0.0.0.0".parse().expect("valid ip address"),
+            listen_address_v6: None,
             libp2p_port: 9000,
             discovery_port: 9000,
             enr_address: None,
             enr_udp_port: None,
             enr_tcp_port: None,
+            enr_address_v6: None,
+            enr_tcp6_port: None,
+            prefer_ipv6: false,
             max_peers: 50,
             secret_key_hex: None,
             gs_config,
@@ -138,6 +170,9 @@ impl Default for Config {
             client_version: version::version(),
             topics,
             propagation_percentage: None,
+            seen_gossip_cache_size: DEFAULT_SEEN_GOSSIP_CACHE_SIZE,
+            seen_gossip_cache_ttl: None,
+            subnet_subscription_lookahead_slots: 0,
         }
     }
 }
@@ -112,13 +112,39 @@ pub fn build_enr<T: EthSpec>(
     if let Some(enr_address) = config.enr_address {
         builder.ip(enr_address);
     }
-    if let Some(udp_port) = config.enr_udp_port {
-        builder.udp(udp_port);
-    }
+
     // we always give it our listening tcp port
     // TODO: Add uPnP support to map udp and tcp ports
     let tcp_port = config.enr_tcp_port.unwrap_or_else(|| config.libp2p_port);
-    builder.tcp(tcp_port);
+
+    // The primary ports are advertised under the keys of the address family we listen on (or
+    // advertise, if set), since this is also the family used by discovery.
+    let is_ipv6 = config
+        .enr_address
+        .map_or_else(|| config.listen_address.is_ipv6(), |ip| ip.is_ipv6());
+    if !is_ipv6 {
+        if let Some(udp_port) = config.enr_udp_port {
+            builder.udp(udp_port);
+        }
+        builder.tcp(tcp_port);
+    } else {
+        if let Some(udp_port) = config.enr_udp_port {
+            builder.udp6(udp_port);
+        }
+        builder.tcp6(tcp_port);
+    }
+
+    // advertise the additional IPv6 listener of a dual-stack node, as long as there is an address
+    // to reach it on (a `tcp6` port is meaningless without an `ip6` address)
+    if let Some(listen_address_v6) = config.listen_address_v6 {
+        let enr_address_v6 = config
+            .enr_address_v6
+            .or_else(|| Some(listen_address_v6).filter(|ip| !ip.is_unspecified()));
+        if let Some(enr_address_v6) = enr_address_v6 {
+            builder.ip(enr_address_v6.into());
+            builder.tcp6(config.enr_tcp6_port.unwrap_or_else(|| config.libp2p_port));
+        }
+    }
 
     // set the `eth2` field on our ENR
     builder.add_value(ETH2_ENR_KEY.into(), enr_fork_id.as_ssz_bytes());
@@ -129,7 +155,6 @@ pub fn build_enr<T: EthSpec>(
     builder.add_value(BITFIELD_ENR_KEY.into(), bitfield.as_ssz_bytes());
 
     builder
-        .tcp(config.libp2p_port)
         .build(enr_key)
         .map_err(|e| format!("Could not build Local ENR: {:?}", e))
 }
@@ -139,12 +164,15 @@ pub fn build_enr<T: EthSpec>(
 fn compare_enr(local_enr: &Enr, disk_enr: &Enr) -> bool {
     // take preference over disk_enr address if one is not specified
     (local_enr.ip().is_none() || local_enr.ip() == disk_enr.ip())
+        && (local_enr.ip6().is_none() || local_enr.ip6() == disk_enr.ip6())
         // tcp ports must match
         && local_enr.tcp() == disk_enr.tcp()
+        && local_enr.tcp6() == disk_enr.tcp6()
         // must match on the same fork
         && local_enr.get(ETH2_ENR_KEY) == disk_enr.get(ETH2_ENR_KEY)
         // take preference over disk udp port if one is not specified
         && (local_enr.udp().is_none() || local_enr.udp() == disk_enr.udp())
+        && (local_enr.udp6().is_none() || local_enr.udp6() == disk_enr.udp6())
         // we need the BITFIELD_ENR_KEY key to match, otherwise we use a new ENR. This will likely only
         // be true for non-validating nodes
         && local_enr.get(BITFIELD_ENR_KEY) == disk_enr.get(BITFIELD_ENR_KEY)
@@ -167,3 +195,40 @@ pub fn save_enr_to_disk(dir: &Path, enr: &Enr, log: &slog::Logger) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv6Addr;
+    use types::MinimalEthSpec;
+
+    fn build_dual_stack_enr(listen_address_v6: Ipv6Addr, enr_address_v6: Option<Ipv6Addr>) -> Enr {
+        let enr_key = CombinedKey::generate_secp256k1();
+        let config = NetworkConfig {
+            listen_address_v6: Some(listen_address_v6),
+            enr_address_v6,
+            ..NetworkConfig::default()
+        };
+        build_enr::<MinimalEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap()
+    }
+
+    #[test]
+    fn dual_stack_enr_addresses() {
+        let address: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let other_address: Ipv6Addr = "2001:db8::2".parse().unwrap();
+
+        // Without an address to advertise, the IPv6 listener is not advertised at all.
+        let enr = build_dual_stack_enr(Ipv6Addr::UNSPECIFIED, None);
+        assert_eq!(enr.ip6(), None);
+        assert_eq!(enr.tcp6(), None);
+
+        // A specific listen address is advertised if no other address is given.
+        let enr = build_dual_stack_enr(address, None);
+        assert_eq!(enr.ip6(), Some(address));
+        assert!(enr.tcp6().is_some());
+
+        let enr = build_dual_stack_enr(Ipv6Addr::UNSPECIFIED, Some(other_address));
+        assert_eq!(enr.ip6(), Some(other_address));
+        assert!(enr.tcp6().is_some());
+    }
+}
@@ -12,6 +12,11 @@ pub trait EnrExt {
     /// Returns a list of multiaddrs if the ENR has an `ip` and either a `tcp` or `udp` key **or** an `ip6` and either a `tcp6` or `udp6`.
     /// The vector remains empty if these fields are not defined.
     fn multiaddr(&self) -> Vec<Multiaddr>;
+
+    /// Returns the TCP multiaddrs of the ENR, in the order they should be dialed.
+    ///
+    /// IPv4 addresses are listed before IPv6 addresses, unless `prefer_ipv6` is `true`.
+    fn multiaddr_tcp(&self, prefer_ipv6: bool) -> Vec<Multiaddr>;
 }
 
 /// Extend ENR CombinedPublicKey for libp2p types.
@@ -66,6 +71,32 @@ impl EnrExt for Enr {
         }
         multiaddrs
     }
+
+    /// Returns the TCP multiaddrs of the ENR, in the order they should be dialed.
+    ///
+    /// IPv4 addresses are listed before IPv6 addresses, unless `prefer_ipv6` is `true`.
+    fn multiaddr_tcp(&self, prefer_ipv6: bool) -> Vec<Multiaddr> {
+        let mut multiaddrs: Vec<Multiaddr> = Vec::new();
+        if let Some(ip) = self.ip() {
+            if let Some(tcp) = self.tcp() {
+                let mut multiaddr: Multiaddr = ip.into();
+                multiaddr.push(Protocol::Tcp(tcp));
+                multiaddrs.push(multiaddr);
+            }
+        }
+        if let Some(ip6) = self.ip6() {
+            if let Some(tcp6) = self.tcp6() {
+                let mut multiaddr: Multiaddr = ip6.into();
+                multiaddr.push(Protocol::Tcp(tcp6));
+                if prefer_ipv6 {
+                    multiaddrs.insert(0, multiaddr);
+                } else {
+                    multiaddrs.push(multiaddr);
+                }
+            }
+        }
+        multiaddrs
+    }
 }
 
 impl CombinedKeyPublicExt for CombinedPublicKey {
@@ -187,4 +218,39 @@ mod tests {
 
         assert_eq!(enr.node_id(), node_id);
     }
+
+    #[test]
+    fn test_multiaddr_tcp_preference() {
+        let secret_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+        let enr = discv5::enr::EnrBuilder::new("v4")
+            .ip("10.0.0.1".parse().unwrap())
+            .udp(9000)
+            .tcp(9000)
+            .ip("2001:db8::1".parse().unwrap())
+            .udp6(9001)
+            .tcp6(9001)
+            .build(&secret_key)
+            .unwrap();
+
+        let v4: Multiaddr = "/ip4/10.0.0.1/tcp/9000".parse().unwrap();
+        let v6: Multiaddr = "/ip6/2001:db8::1/tcp/9001".parse().unwrap();
+
+        assert_eq!(enr.multiaddr_tcp(false), vec![v4.clone(), v6.clone()]);
+        assert_eq!(enr.multiaddr_tcp(true), vec![v6, v4]);
+    }
+
+    #[test]
+    fn test_multiaddr_tcp_ipv6_only() {
+        let secret_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+        let enr = discv5::enr::EnrBuilder::new("v4")
+            .ip("2001:db8::1".parse().unwrap())
+            .udp6(9000)
+            .tcp6(9000)
+            .build(&secret_key)
+            .unwrap();
+
+        let v6: Multiaddr = "/ip6/2001:db8::1/tcp/9000".parse().unwrap();
+
+        assert_eq!(enr.multiaddr_tcp(false), vec![v6]);
+    }
 }
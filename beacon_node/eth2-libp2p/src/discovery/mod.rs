@@ -22,7 +22,7 @@ use slog::{crit, debug, info, warn};
 use ssz::{Decode, Encode};
 use ssz_types::BitVector;
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     net::SocketAddr,
     path::Path,
     sync::Arc,
@@ -42,6 +42,11 @@ const MINIMUM_PEERS_BEFORE_DELAY_INCREASE: usize = 5;
 pub const ENR_FILENAME: &str = "enr.dat";
 /// Number of peers we'd like to have connected to a given long-lived subnet.
 const TARGET_SUBNET_PEERS: u64 = 3;
+/// The duration after a subnet query is started during which peers discovered on that subnet may
+/// be dialed, even if we are at our peer limit.
+const SUBNET_QUERY_DURATION: Duration = Duration::from_secs(120);
+/// The number of peers beyond `max_peers` that may be dialed to satisfy subnet queries.
+const MAX_SUBNET_PEERS_EXCESS: usize = 6;
 
 /// Lighthouse discovery behaviour. This provides peer management and discovery using the Discv5
 /// libp2p protocol.
@@ -52,6 +57,10 @@ pub struct Discovery<TSpec: EthSpec> {
     /// A collection of seen live ENRs for quick lookup and to map peer-id's to ENRs.
     cached_enrs: LruCache<PeerId, Enr>,
 
+    /// The subnets with outstanding peer requests (e.g., for upcoming validator duties), mapped
+    /// to the time after which the request lapses.
+    subnet_queries: HashMap<SubnetId, Instant>,
+
     /// The currently banned peers.
     banned_peers: HashSet<PeerId>,
 
@@ -73,6 +82,9 @@ pub struct Discovery<TSpec: EthSpec> {
     //TODO: Improve NAT handling limit the above restriction
     tcp_port: u16,
 
+    /// Whether to dial the IPv6 address of peers which advertise both IPv4 and IPv6 addresses.
+    prefer_ipv6: bool,
+
     /// The discovery behaviour used to discover new peers.
     discovery: Discv5,
 
@@ -99,7 +111,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         let local_enr = network_globals.local_enr.read().clone();
 
-        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()));
+        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()), "ip6" => format!("{:?}", local_enr.ip6()), "udp6" => format!("{:?}", local_enr.udp6()), "tcp6" => format!("{:?}", local_enr.tcp6()));
 
         let listen_socket = SocketAddr::new(config.listen_address, config.discovery_port);
 
@@ -123,8 +135,24 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                 "peer_id" => format!("{}", bootnode_enr.peer_id()),
                 "ip" => format!("{:?}", bootnode_enr.ip()),
                 "udp" => format!("{:?}", bootnode_enr.udp()),
-                "tcp" => format!("{:?}", bootnode_enr.tcp())
+                "tcp" => format!("{:?}", bootnode_enr.tcp()),
+                "ip6" => format!("{:?}", bootnode_enr.ip6()),
+                "udp6" => format!("{:?}", bootnode_enr.udp6())
             );
+            // discv5 only uses a single socket, so it can only reach nodes of the same family
+            let reachable = if listen_socket.is_ipv4() {
+                bootnode_enr.ip().is_some() && bootnode_enr.udp().is_some()
+            } else {
+                bootnode_enr.ip6().is_some() && bootnode_enr.udp6().is_some()
+            };
+            if !reachable {
+                warn!(
+                    log,
+                    "Boot node has no discovery address of the listening address family";
+                    "peer_id" => format!("{}", bootnode_enr.peer_id()),
+                    "listen_address" => format!("{}", listen_socket.ip())
+                );
+            }
             let _ = discovery.add_enr(bootnode_enr).map_err(|e| {
                 warn!(
                     log,
@@ -137,11 +165,13 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Ok(Self {
             events: VecDeque::with_capacity(16),
             cached_enrs: LruCache::new(50),
+            subnet_queries: HashMap::new(),
             banned_peers: HashSet::new(),
             max_peers: config.max_peers,
             peer_discovery_delay: delay_until(Instant::now()),
             past_discovery_delay: INITIAL_SEARCH_DELAY,
             tcp_port: config.libp2p_port,
+            prefer_ipv6: config.prefer_ipv6,
             discovery,
             network_globals,
             log,
@@ -322,6 +352,11 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                 false
             };
 
+            // peers found by this query may be dialed beyond the peer limit until it lapses
+            self.subnet_queries
+                .insert(subnet_id, Instant::now() + SUBNET_QUERY_DURATION);
+            metrics::inc_counter(&metrics::DISCOVERY_SUBNET_QUERIES);
+
             // start the query
             self.start_query(subnet_predicate, target_peers as usize);
         } else {
@@ -335,6 +370,21 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
     /* Internal Functions */
 
+    /// Returns `true` if the `enr` advertises a subnet which has an outstanding peer request and
+    /// still lacks `TARGET_SUBNET_PEERS` connected peers.
+    fn on_wanted_subnet(&self, enr: &Enr) -> bool {
+        let bitfield = match enr.bitfield::<TSpec>() {
+            Ok(bitfield) => bitfield,
+            Err(_) => return false,
+        };
+        let peers = self.network_globals.peers.read();
+
+        self.subnet_queries.keys().any(|subnet_id| {
+            bitfield.get(**subnet_id as usize).unwrap_or(false)
+                && (peers.peers_on_subnet(*subnet_id).count() as u64) < TARGET_SUBNET_PEERS
+        })
+    }
+
     /// Run a standard query to search for more peers.
     ///
     /// This searches for the standard kademlia bucket size (16) peers.
@@ -385,20 +435,10 @@ impl<TSpec: EthSpec> NetworkBehaviour for Discovery<TSpec> {
 
     fn addresses_of_peer(&mut self, peer_id: &PeerId) -> Vec<Multiaddr> {
         if let Some(enr) = self.enr_of_peer(peer_id) {
-            // ENR's may have multiple Multiaddrs. The multi-addr associated with the UDP
-            // port is removed, which is assumed to be associated with the discv5 protocol (and
-            // therefore irrelevant for other libp2p components).
-            let mut out_list = enr.multiaddr();
-            out_list.retain(|addr| {
-                addr.iter()
-                    .find(|v| match v {
-                        Protocol::Udp(_) => true,
-                        _ => false,
-                    })
-                    .is_none()
-            });
-
-            out_list
+            // ENR's may have multiple Multiaddrs. Only the TCP multiaddrs are returned, since the
+            // UDP ports are assumed to be associated with the discv5 protocol (and therefore
+            // irrelevant for other libp2p components). These are ordered by the IP preference.
+            enr.multiaddr_tcp(self.prefer_ipv6)
         } else {
             // PeerId is not known
             Vec::new()
@@ -493,14 +533,24 @@ impl<TSpec: EthSpec> NetworkBehaviour for Discovery<TSpec> {
                             self.peer_discovery_delay
                                 .reset(Instant::now() + Duration::from_secs(delay));
 
+                            let now = Instant::now();
+                            self.subnet_queries.retain(|_, deadline| *deadline > now);
+
                             for enr in closer_peers {
+                                // peers on a subnet we are short of are worth exceeding the peer
+                                // limit for
+                                let peer_limit = if self.on_wanted_subnet(&enr) {
+                                    self.max_peers + MAX_SUBNET_PEERS_EXCESS
+                                } else {
+                                    self.max_peers
+                                };
+
                                 // cache known peers
                                 let peer_id = enr.peer_id();
                                 self.cached_enrs.put(enr.peer_id(), enr);
 
                                 // if we need more peers, attempt a connection
-                                if self.network_globals.connected_or_dialing_peers()
-                                    < self.max_peers
+                                if self.network_globals.connected_or_dialing_peers() < peer_limit
                                     && !self
                                         .network_globals
                                         .peers
@@ -17,4 +17,27 @@ lazy_static! {
         "libp2p_peer_disconnect_event_total",
         "Count of libp2p peer disconnect events"
     );
+    pub static ref DISCOVERY_SUBNET_QUERIES: Result<IntCounter> = try_create_int_counter(
+        "libp2p_discovery_subnet_queries_total",
+        "Count of discovery queries for peers on a subnet which lacks connected peers"
+    );
+    pub static ref GOSSIP_DUPLICATE_MESSAGES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_gossip_duplicate_messages_total",
+        "Count of gossip messages ignored because they were already seen, per topic",
+        &["topic"]
+    );
+    pub static ref GOSSIP_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_gossip_bytes_total",
+        "Bytes of gossip message payloads sent and received, per topic and direction",
+        &["topic", "direction"]
+    );
+    pub static ref RPC_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_rpc_bytes_total",
+        "Bytes of RPC request and response payloads sent and received, per protocol and direction",
+        &["protocol", "direction"]
+    );
+    pub static ref GOSSIP_SEEN_CACHE_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "libp2p_gossip_seen_cache_size",
+        "Number of gossip message ids in the duplicate suppression cache"
+    );
 }
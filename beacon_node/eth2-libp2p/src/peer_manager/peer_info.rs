@@ -2,6 +2,7 @@ use super::client::Client;
 use super::peerdb::{Rep, DEFAULT_REPUTATION};
 use super::PeerSyncStatus;
 use crate::rpc::MetaData;
+use crate::types::AtomicByteCount;
 use crate::Multiaddr;
 use serde::{
     ser::{SerializeStructVariant, Serializer},
@@ -31,6 +32,8 @@ pub struct PeerInfo<T: EthSpec> {
     /// The ENR subnet bitfield of the peer. This may be determined after it's initial
     /// connection.
     pub meta_data: Option<MetaData<T>>,
+    /// The bytes transferred with this peer over gossipsub and the RPC.
+    pub traffic: AtomicByteCount,
 }
 
 impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
@@ -43,6 +46,7 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             listening_addresses: vec![],
             sync_status: PeerSyncStatus::Unknown,
             meta_data: None,
+            traffic: AtomicByteCount::default(),
         }
     }
 }
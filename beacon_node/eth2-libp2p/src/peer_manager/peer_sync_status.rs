@@ -12,6 +12,8 @@ pub enum PeerSyncStatus {
     Advanced { info: SyncInfo },
     /// Is behind our current head and not useful for block downloads.
     Behind { info: SyncInfo },
+    /// Has finalized a block which conflicts with our chain and is not useful for block downloads.
+    WrongChain { info: SyncInfo },
     /// Not currently known as a STATUS handshake has not occurred.
     Unknown,
 }
@@ -51,6 +53,14 @@ impl PeerSyncStatus {
         }
     }
 
+    /// Returns true if the peer has finalized a chain which conflicts with ours.
+    pub fn is_wrong_chain(&self) -> bool {
+        match self {
+            PeerSyncStatus::WrongChain { .. } => true,
+            _ => false,
+        }
+    }
+
     /// Updates the sync state given a fully synced peer.
     /// Returns true if the state has changed.
     pub fn update_synced(&mut self, info: SyncInfo) -> bool {
@@ -101,4 +111,21 @@ impl PeerSyncStatus {
             }
         }
     }
+
+    /// Updates the sync state given a peer that has finalized a conflicting chain.
+    /// Returns true if the state has changed.
+    pub fn update_wrong_chain(&mut self, info: SyncInfo) -> bool {
+        let new_state = PeerSyncStatus::WrongChain { info };
+
+        match self {
+            PeerSyncStatus::WrongChain { .. } | PeerSyncStatus::Unknown => {
+                *self = new_state;
+                false // state was not updated
+            }
+            _ => {
+                *self = new_state;
+                true
+            }
+        }
+    }
 }
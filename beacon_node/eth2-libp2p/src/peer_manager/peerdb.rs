@@ -1,6 +1,7 @@
 use super::peer_info::{PeerConnectionStatus, PeerInfo};
 use super::peer_sync_status::PeerSyncStatus;
 use crate::rpc::methods::MetaData;
+use crate::types::Direction;
 use crate::PeerId;
 use slog::{crit, debug, warn};
 use std::collections::{hash_map::Entry, HashMap};
@@ -315,6 +316,16 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
+    /// Records `bytes` transferred with a peer. Bytes transferred with unknown peers are ignored.
+    ///
+    /// Only a shared reference is required, so the `PeerDB` need not be locked exclusively for
+    /// every message.
+    pub fn add_traffic(&self, peer_id: &PeerId, direction: Direction, bytes: usize) {
+        if let Some(peer_info) = self.peers.get(peer_id) {
+            peer_info.traffic.add(direction, bytes);
+        }
+    }
+
     /// Sets the reputation of peer.
     #[allow(dead_code)]
     pub(super) fn set_reputation(&mut self, peer_id: &PeerId, rep: Rep) {
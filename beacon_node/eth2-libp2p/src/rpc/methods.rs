@@ -1,5 +1,6 @@
 //! Available RPC methods types and ids.
 
+use super::protocol::Protocol;
 use crate::types::EnrBitfield;
 use serde::Serialize;
 use ssz_derive::{Decode, Encode};
@@ -201,6 +202,30 @@ pub enum RPCResponseErrorCode {
     Unknown,
 }
 
+impl<T: EthSpec> RPCResponse<T> {
+    /// The protocol over which this response is sent.
+    pub fn protocol(&self) -> Protocol {
+        match self {
+            RPCResponse::Status(_) => Protocol::Status,
+            RPCResponse::BlocksByRange(_) => Protocol::BlocksByRange,
+            RPCResponse::BlocksByRoot(_) => Protocol::BlocksByRoot,
+            RPCResponse::Pong(_) => Protocol::Ping,
+            RPCResponse::MetaData(_) => Protocol::MetaData,
+        }
+    }
+
+    /// Returns the length of the SSZ-encoded response, as sent over the wire before compression.
+    pub fn ssz_bytes_len(&self) -> usize {
+        match self {
+            RPCResponse::Status(res) => ssz::Encode::ssz_bytes_len(res),
+            RPCResponse::BlocksByRange(res) => ssz::Encode::ssz_bytes_len(res.as_ref()),
+            RPCResponse::BlocksByRoot(res) => ssz::Encode::ssz_bytes_len(res.as_ref()),
+            RPCResponse::Pong(res) => ssz::Encode::ssz_bytes_len(&res.data),
+            RPCResponse::MetaData(res) => ssz::Encode::ssz_bytes_len(res),
+        }
+    }
+}
+
 impl<T: EthSpec> RPCCodedResponse<T> {
     /// Used to encode the response in the codec.
     pub fn as_u8(&self) -> Option<u8> {
@@ -14,6 +14,7 @@ use futures::future::Ready;
 use futures::prelude::*;
 use futures::prelude::{AsyncRead, AsyncWrite};
 use libp2p::core::{InboundUpgrade, OutboundUpgrade, ProtocolName, UpgradeInfo};
+use ssz::Encode;
 use std::io;
 use std::marker::PhantomData;
 use std::pin::Pin;
@@ -327,6 +328,18 @@ impl<TSpec: EthSpec> RPCRequest<TSpec> {
         }
     }
 
+    /// Returns the length of the SSZ-encoded request, as sent over the wire before compression.
+    pub fn ssz_bytes_len(&self) -> usize {
+        match self {
+            RPCRequest::Status(req) => req.ssz_bytes_len(),
+            RPCRequest::Goodbye(req) => req.ssz_bytes_len(),
+            RPCRequest::BlocksByRange(req) => req.ssz_bytes_len(),
+            RPCRequest::BlocksByRoot(req) => req.block_roots.ssz_bytes_len(),
+            RPCRequest::Ping(req) => req.ssz_bytes_len(),
+            RPCRequest::MetaData(_) => 0,
+        }
+    }
+
     /// Returns the `ResponseTermination` type associated with the request if a stream gets
     /// terminated.
     pub fn stream_termination(&self) -> ResponseTermination {
@@ -1,9 +1,10 @@
 use crate::behaviour::{Behaviour, BehaviourEvent};
 use crate::discovery::enr;
 use crate::multiaddr::Protocol;
+use crate::peer_manager::DEFAULT_REPUTATION;
 use crate::types::{error, GossipKind};
 use crate::EnrExt;
-use crate::{NetworkConfig, NetworkGlobals};
+use crate::{Enr, NetworkConfig, NetworkGlobals};
 use futures::prelude::*;
 use libp2p::core::{
     identity::Keypair,
@@ -19,6 +20,7 @@ use libp2p::{
     PeerId, Swarm, Transport,
 };
 use slog::{crit, debug, info, o, trace, warn};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::{Error, ErrorKind};
@@ -78,6 +80,9 @@ pub struct Service<TSpec: EthSpec> {
     /// A list of timeouts after which peers become unbanned.
     peer_ban_timeout: DelayQueue<PeerId>,
 
+    /// Whether to dial the IPv6 address of peers which advertise both IPv4 and IPv6 addresses.
+    prefer_ipv6: bool,
+
     /// The libp2p logger handle.
     pub log: slog::Logger,
 }
@@ -112,7 +117,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
         ));
 
         info!(log, "Libp2p Service"; "peer_id" => format!("{:?}", enr.peer_id()));
-        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "tcp_port" => config.libp2p_port, "udp_port" => config.discovery_port);
+        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "address_v6" => format!("{:?}", config.listen_address_v6), "tcp_port" => config.libp2p_port, "udp_port" => config.discovery_port);
 
         let mut swarm = {
             // Set up the transport - tcp/ws with noise/secio and mplex/yamux
@@ -134,29 +139,32 @@ impl<TSpec: EthSpec> Service<TSpec> {
                 .build()
         };
 
-        // listen on the specified address
-        let listen_multiaddr = {
-            let mut m = Multiaddr::from(config.listen_address);
-            m.push(Protocol::Tcp(config.libp2p_port));
-            m
-        };
+        // listen on the specified address, as well as the IPv6 address of a dual-stack node
+        let mut listen_multiaddrs = vec![Multiaddr::from(config.listen_address)];
+        if let Some(listen_address_v6) = config.listen_address_v6 {
+            listen_multiaddrs.push(Multiaddr::from(listen_address_v6));
+        }
 
-        match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
-            Ok(_) => {
-                let mut log_address = listen_multiaddr;
-                log_address.push(Protocol::P2p(local_peer_id.clone().into()));
-                info!(log, "Listening established"; "address" => format!("{}", log_address));
-            }
-            Err(err) => {
-                crit!(
-                    log,
-                    "Unable to listen on libp2p address";
-                    "error" => format!("{:?}", err),
-                    "listen_multiaddr" => format!("{}", listen_multiaddr),
-                );
-                return Err("Libp2p was unable to listen on the given listen address.".into());
-            }
-        };
+        for mut listen_multiaddr in listen_multiaddrs {
+            listen_multiaddr.push(Protocol::Tcp(config.libp2p_port));
+
+            match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
+                Ok(_) => {
+                    let mut log_address = listen_multiaddr;
+                    log_address.push(Protocol::P2p(local_peer_id.clone().into()));
+                    info!(log, "Listening established"; "address" => format!("{}", log_address));
+                }
+                Err(err) => {
+                    crit!(
+                        log,
+                        "Unable to listen on libp2p address";
+                        "error" => format!("{:?}", err),
+                        "listen_multiaddr" => format!("{}", listen_multiaddr),
+                    );
+                    return Err("Libp2p was unable to listen on the given listen address.".into());
+                }
+            };
+        }
 
         // helper closure for dialing peers
         let mut dial_addr = |multiaddr: &Multiaddr| {
@@ -177,15 +185,10 @@ impl<TSpec: EthSpec> Service<TSpec> {
         // attempt to connect to any specified boot-nodes
         let mut boot_nodes = config.boot_nodes.clone();
         boot_nodes.dedup();
+        *network_globals.boot_nodes.write() = boot_nodes.clone();
 
         for bootnode_enr in boot_nodes {
-            for multiaddr in &bootnode_enr.multiaddr() {
-                // ignore udp multiaddr if it exists
-                let components = multiaddr.iter().collect::<Vec<_>>();
-                if let Protocol::Udp(_) = components[1] {
-                    continue;
-                }
-
+            for multiaddr in &bootnode_enr.multiaddr_tcp(config.prefer_ipv6) {
                 if !network_globals
                     .peers
                     .read()
@@ -212,6 +215,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
             network_globals: network_globals.clone(),
             peers_to_ban: DelayQueue::new(),
             peer_ban_timeout: DelayQueue::new(),
+            prefer_ipv6: config.prefer_ipv6,
             log,
         };
 
@@ -219,7 +223,13 @@ impl<TSpec: EthSpec> Service<TSpec> {
     }
 
     /// Adds a peer to be banned for a period of time, specified by a timeout.
+    ///
+    /// Trusted peers are never banned.
     pub fn disconnect_and_ban_peer(&mut self, peer_id: PeerId, timeout: Duration) {
+        if self.network_globals.is_trusted_peer(&peer_id) {
+            debug!(self.log, "Not banning trusted peer"; "peer_id" => peer_id.to_string());
+            return;
+        }
         warn!(self.log, "Disconnecting and banning peer"; "peer_id" => peer_id.to_string(), "timeout" => format!("{:?}", timeout));
         self.peers_to_ban.insert(
             peer_id.clone(),
@@ -228,6 +238,126 @@ impl<TSpec: EthSpec> Service<TSpec> {
         self.peer_ban_timeout.insert(peer_id, timeout);
     }
 
+    /// Adds the `enr` to the discovery routing table and dials its TCP addresses, unless the peer
+    /// is already connected or being dialed.
+    pub fn dial_enr(&mut self, enr: &Enr) {
+        self.swarm.add_enr(enr.clone());
+
+        if self
+            .network_globals
+            .peers
+            .read()
+            .is_connected_or_dialing(&enr.peer_id())
+        {
+            return;
+        }
+
+        for multiaddr in enr.multiaddr_tcp(self.prefer_ipv6) {
+            match Swarm::dial_addr(&mut self.swarm, multiaddr.clone()) {
+                Ok(()) => {
+                    debug!(self.log, "Dialing libp2p peer"; "address" => format!("{}", multiaddr))
+                }
+                Err(err) => debug!(
+                    self.log,
+                    "Could not connect to peer"; "address" => format!("{}", multiaddr), "error" => format!("{:?}", err)
+                ),
+            };
+        }
+    }
+
+    /// Returns the ENRs of at most `max` connected peers whose reputation is no lower than that of
+    /// a new peer, from highest to lowest reputation.
+    ///
+    /// Peers for which no ENR is known are skipped.
+    pub fn good_peer_enrs(&mut self, max: usize) -> Vec<Enr> {
+        let good_peers = self
+            .network_globals
+            .peers
+            .read()
+            .best_peers_by_status(|status| status.is_connected())
+            .into_iter()
+            .filter(|(_, info)| info.reputation >= DEFAULT_REPUTATION)
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect::<Vec<_>>();
+
+        let mut routing_table = self
+            .swarm
+            .enr_entries()
+            .map(|enr| (enr.peer_id(), enr.clone()))
+            .collect::<HashMap<_, _>>();
+
+        good_peers
+            .iter()
+            .filter_map(|peer_id| {
+                routing_table
+                    .remove(peer_id)
+                    .or_else(|| self.swarm.enr_of_peer(peer_id))
+            })
+            .take(max)
+            .collect()
+    }
+
+    /// Adds a boot node and dials it.
+    pub fn add_boot_node(&mut self, enr: Enr) {
+        info!(self.log, "Adding boot node"; "peer_id" => enr.peer_id().to_string());
+        self.dial_enr(&enr);
+
+        let mut boot_nodes = self.network_globals.boot_nodes.write();
+        if !boot_nodes.contains(&enr) {
+            boot_nodes.push(enr);
+        }
+    }
+
+    /// Removes a boot node, so that it is no longer dialed when a re-discovery is requested.
+    ///
+    /// The node is not removed from the discovery routing table, nor is it disconnected.
+    pub fn remove_boot_node(&mut self, peer_id: &PeerId) {
+        info!(self.log, "Removing boot node"; "peer_id" => peer_id.to_string());
+        self.network_globals
+            .boot_nodes
+            .write()
+            .retain(|enr| enr.peer_id() != *peer_id);
+    }
+
+    /// Adds a trusted peer, which is never banned, and dials it.
+    pub fn add_trusted_peer(&mut self, enr: Enr) {
+        info!(self.log, "Adding trusted peer"; "peer_id" => enr.peer_id().to_string());
+        self.dial_enr(&enr);
+
+        let mut trusted_peers = self.network_globals.trusted_peers.write();
+        trusted_peers.retain(|trusted| trusted.peer_id() != enr.peer_id());
+        trusted_peers.push(enr);
+    }
+
+    /// Removes a trusted peer, which remains connected but may be banned.
+    pub fn remove_trusted_peer(&mut self, peer_id: &PeerId) {
+        info!(self.log, "Removing trusted peer"; "peer_id" => peer_id.to_string());
+        self.network_globals
+            .trusted_peers
+            .write()
+            .retain(|enr| enr.peer_id() != *peer_id);
+    }
+
+    /// Restarts peer discovery and re-dials all boot nodes and trusted peers.
+    pub fn rediscover(&mut self) {
+        info!(self.log, "Restarting peer discovery");
+
+        let enrs = {
+            let boot_nodes = self.network_globals.boot_nodes.read();
+            let trusted_peers = self.network_globals.trusted_peers.read();
+            boot_nodes
+                .iter()
+                .chain(trusted_peers.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        for enr in &enrs {
+            self.dial_enr(enr);
+        }
+
+        self.swarm.discover_peers();
+    }
+
     pub async fn next_event(&mut self) -> Libp2pEvent<TSpec> {
         loop {
             tokio::select! {
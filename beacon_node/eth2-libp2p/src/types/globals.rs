@@ -1,7 +1,7 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::PeerDB;
 use crate::rpc::methods::MetaData;
-use crate::types::SyncState;
+use crate::types::{SyncState, TrafficCounters};
 use crate::Client;
 use crate::EnrExt;
 use crate::{discovery::enr::Eth2Enr, Enr, GossipTopic, Multiaddr, PeerId};
@@ -29,6 +29,12 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// The boot nodes, which are dialed on startup and whenever a re-discovery is requested.
+    pub boot_nodes: RwLock<Vec<Enr>>,
+    /// Peers which are never banned and are re-dialed whenever a re-discovery is requested.
+    pub trusted_peers: RwLock<Vec<Enr>>,
+    /// The bytes transferred per gossip topic and RPC protocol.
+    pub traffic: TrafficCounters,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -51,6 +57,9 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             peers: RwLock::new(PeerDB::new(log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            boot_nodes: RwLock::new(Vec::new()),
+            trusted_peers: RwLock::new(Vec::new()),
+            traffic: TrafficCounters::default(),
         }
     }
 
@@ -60,6 +69,14 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.local_enr.read().clone()
     }
 
+    /// Returns `true` if the peer has been added as a trusted peer.
+    pub fn is_trusted_peer(&self, peer_id: &PeerId) -> bool {
+        self.trusted_peers
+            .read()
+            .iter()
+            .any(|enr| enr.peer_id() == *peer_id)
+    }
+
     /// Returns the local libp2p PeerID.
     pub fn local_peer_id(&self) -> PeerId {
         self.peer_id.read().clone()
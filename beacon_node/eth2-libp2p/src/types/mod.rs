@@ -3,6 +3,7 @@ mod globals;
 mod pubsub;
 mod sync_state;
 mod topics;
+mod traffic;
 
 use types::{BitVector, EthSpec};
 
@@ -15,3 +16,4 @@ pub use globals::NetworkGlobals;
 pub use pubsub::PubsubMessage;
 pub use sync_state::SyncState;
 pub use topics::{GossipEncoding, GossipKind, GossipTopic};
+pub use traffic::{AtomicByteCount, ByteCount, Direction, TrafficCounters, TrafficStats};
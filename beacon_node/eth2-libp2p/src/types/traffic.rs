@@ -0,0 +1,180 @@
+//! Accounting of the bytes sent and received over gossipsub and the RPC.
+//!
+//! Only message payloads are counted (i.e., the SSZ-encoded message before any compression),
+//! excluding the overhead of the underlying protocols and transports. Gossip messages are
+//! counted once per message, regardless of the number of peers they are sent to.
+//!
+//! The counts are updated for every message, so they are kept in atomics which only need a shared
+//! lock on the map which contains them.
+use parking_lot::RwLock;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The direction in which bytes were transferred.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Direction {
+    /// The label used for this direction in metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+        }
+    }
+}
+
+/// A count of bytes transferred in each direction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct ByteCount {
+    pub inbound: u64,
+    pub outbound: u64,
+}
+
+/// A `ByteCount` which may be added to concurrently.
+#[derive(Debug, Default)]
+pub struct AtomicByteCount {
+    inbound: AtomicU64,
+    outbound: AtomicU64,
+}
+
+impl AtomicByteCount {
+    /// Adds `bytes` to the count for `direction`.
+    pub fn add(&self, direction: Direction, bytes: usize) {
+        let count = match direction {
+            Direction::Inbound => &self.inbound,
+            Direction::Outbound => &self.outbound,
+        };
+        count.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the current counts.
+    pub fn load(&self) -> ByteCount {
+        ByteCount {
+            inbound: self.inbound.load(Ordering::Relaxed),
+            outbound: self.outbound.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Clone for AtomicByteCount {
+    fn clone(&self) -> Self {
+        let count = self.load();
+        Self {
+            inbound: AtomicU64::new(count.inbound),
+            outbound: AtomicU64::new(count.outbound),
+        }
+    }
+}
+
+impl Serialize for AtomicByteCount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.load().serialize(serializer)
+    }
+}
+
+/// The bytes transferred per gossip topic and per RPC protocol since the node started, as
+/// returned by `TrafficCounters::stats`.
+///
+/// The bytes transferred per peer are stored alongside the rest of the peer's information in the
+/// `PeerDB`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct TrafficStats {
+    /// Keyed by the kind of gossip topic (e.g., `beacon_block`).
+    pub gossip_topics: HashMap<String, ByteCount>,
+    /// Keyed by the RPC protocol name (e.g., `beacon_blocks_by_range`).
+    pub rpc_protocols: HashMap<String, ByteCount>,
+}
+
+/// Counts the bytes transferred per gossip topic and per RPC protocol.
+///
+/// The set of topics and protocols is small, so the exclusive lock on each map is only taken the
+/// first time a topic or protocol is seen.
+#[derive(Debug, Default)]
+pub struct TrafficCounters {
+    gossip_topics: RwLock<HashMap<String, AtomicByteCount>>,
+    rpc_protocols: RwLock<HashMap<String, AtomicByteCount>>,
+}
+
+impl TrafficCounters {
+    /// Records `bytes` transferred over the gossip `topic`.
+    pub fn add_gossip(&self, topic: &str, direction: Direction, bytes: usize) {
+        add(&self.gossip_topics, topic, direction, bytes)
+    }
+
+    /// Records `bytes` transferred over the RPC `protocol`.
+    pub fn add_rpc(&self, protocol: &str, direction: Direction, bytes: usize) {
+        add(&self.rpc_protocols, protocol, direction, bytes)
+    }
+
+    /// Returns the counts for every topic and protocol.
+    pub fn stats(&self) -> TrafficStats {
+        let load = |counts: &RwLock<HashMap<String, AtomicByteCount>>| {
+            counts
+                .read()
+                .iter()
+                .map(|(key, count)| (key.clone(), count.load()))
+                .collect()
+        };
+
+        TrafficStats {
+            gossip_topics: load(&self.gossip_topics),
+            rpc_protocols: load(&self.rpc_protocols),
+        }
+    }
+}
+
+/// Adds `bytes` to the count for `key` in `counts`, inserting it if it is not yet known.
+fn add(
+    counts: &RwLock<HashMap<String, AtomicByteCount>>,
+    key: &str,
+    direction: Direction,
+    bytes: usize,
+) {
+    if let Some(count) = counts.read().get(key) {
+        count.add(direction, bytes);
+        return;
+    }
+
+    counts
+        .write()
+        .entry(key.to_string())
+        .or_default()
+        .add(direction, bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_each_direction() {
+        let counters = TrafficCounters::default();
+        counters.add_gossip("beacon_block", Direction::Inbound, 100);
+        counters.add_gossip("beacon_block", Direction::Inbound, 50);
+        counters.add_gossip("beacon_block", Direction::Outbound, 10);
+        counters.add_rpc("status", Direction::Outbound, 84);
+
+        let stats = counters.stats();
+
+        assert_eq!(
+            stats.gossip_topics["beacon_block"],
+            ByteCount {
+                inbound: 150,
+                outbound: 10
+            }
+        );
+        assert_eq!(
+            stats.rpc_protocols["status"],
+            ByteCount {
+                inbound: 0,
+                outbound: 84
+            }
+        );
+        assert!(stats.gossip_topics.get("voluntary_exit").is_none());
+    }
+}
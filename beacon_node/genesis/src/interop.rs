@@ -14,6 +14,31 @@ pub fn interop_genesis_state<T: EthSpec>(
     genesis_time: u64,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, String> {
+    let withdrawal_pubkeys = keypairs
+        .iter()
+        .map(|keypair| keypair.pk.clone())
+        .collect::<Vec<_>>();
+
+    genesis_state_with_withdrawal_keys(keypairs, &withdrawal_pubkeys, genesis_time, spec)
+}
+
+/// Builds a genesis state in the same manner as `interop_genesis_state`, except that the
+/// withdrawal credentials of each of the `keypairs` are derived from the corresponding
+/// `withdrawal_pubkeys`, instead of the voting public key.
+pub fn genesis_state_with_withdrawal_keys<T: EthSpec>(
+    keypairs: &[Keypair],
+    withdrawal_pubkeys: &[PublicKey],
+    genesis_time: u64,
+    spec: &ChainSpec,
+) -> Result<BeaconState<T>, String> {
+    if keypairs.len() != withdrawal_pubkeys.len() {
+        return Err(format!(
+            "Got {} keypairs but {} withdrawal pubkeys",
+            keypairs.len(),
+            withdrawal_pubkeys.len()
+        ));
+    }
+
     let eth1_block_hash = Hash256::from_slice(&[0x42; 32]);
     let eth1_timestamp = 2_u64.pow(40);
     let amount = spec.max_effective_balance;
@@ -26,9 +51,10 @@ pub fn interop_genesis_state<T: EthSpec>(
 
     let datas = keypairs
         .into_par_iter()
-        .map(|keypair| {
+        .zip(withdrawal_pubkeys.into_par_iter())
+        .map(|(keypair, withdrawal_pubkey)| {
             let mut data = DepositData {
-                withdrawal_credentials: withdrawal_credentials(&keypair.pk),
+                withdrawal_credentials: withdrawal_credentials(withdrawal_pubkey),
                 pubkey: keypair.pk.clone().into(),
                 amount,
                 signature: Signature::empty_signature().into(),
@@ -117,4 +143,44 @@ mod test {
             "validator count should be correct"
         );
     }
+
+    #[test]
+    fn state_with_withdrawal_keys() {
+        let validator_count = 4;
+        let spec = &TestEthSpec::default_spec();
+
+        let keypairs = generate_deterministic_keypairs(validator_count * 2);
+        let (voting_keypairs, withdrawal_keypairs) = keypairs.split_at(validator_count);
+        let withdrawal_pubkeys = withdrawal_keypairs
+            .iter()
+            .map(|keypair| keypair.pk.clone())
+            .collect::<Vec<_>>();
+
+        let state = genesis_state_with_withdrawal_keys::<TestEthSpec>(
+            voting_keypairs,
+            &withdrawal_pubkeys,
+            42,
+            spec,
+        )
+        .expect("should build state");
+
+        for (v, withdrawal_pubkey) in state.validators.iter().zip(withdrawal_pubkeys.iter()) {
+            assert_eq!(
+                &v.withdrawal_credentials.as_bytes()[1..],
+                &hash(&withdrawal_pubkey.as_ssz_bytes())[1..],
+                "withdrawal creds should be withdrawal pubkey hash"
+            )
+        }
+
+        assert!(
+            genesis_state_with_withdrawal_keys::<TestEthSpec>(
+                voting_keypairs,
+                &withdrawal_pubkeys[1..],
+                42,
+                spec
+            )
+            .is_err(),
+            "should reject mismatched lengths"
+        );
+    }
 }
@@ -4,5 +4,5 @@ mod interop;
 
 pub use eth1::Config as Eth1Config;
 pub use eth1_genesis_service::{Eth1GenesisService, Statistics};
-pub use interop::interop_genesis_state;
+pub use interop::{genesis_state_with_withdrawal_keys, interop_genesis_state};
 pub use types::test_utils::generate_deterministic_keypairs;
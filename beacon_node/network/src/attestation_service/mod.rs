@@ -3,7 +3,7 @@
 //! determines whether attestations should be aggregated and/or passed to the beacon node.
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2_libp2p::{types::GossipKind, MessageId, NetworkGlobals, PeerId};
+use eth2_libp2p::{types::GossipKind, MessageId, NetworkConfig, NetworkGlobals, PeerId};
 use futures::prelude::*;
 use hashset_delay::HashSetDelay;
 use rand::seq::SliceRandom;
@@ -90,6 +90,10 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// This is a set of validator indices.
     known_validators: HashSetDelay<u64>,
 
+    /// The additional time before a duty's slot (beyond `ADVANCE_SUBSCRIBE_TIME`) that its subnet
+    /// is subscribed to.
+    subscription_lookahead: Duration,
+
     /// The logger for the attestation service.
     log: slog::Logger,
 }
@@ -100,6 +104,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     pub fn new(
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+        config: &NetworkConfig,
         log: &slog::Logger,
     ) -> Self {
         let log = log.new(o!("service" => "attestation_service"));
@@ -111,6 +116,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             .epochs_per_random_subnet_subscription
             .saturating_mul(T::EthSpec::slots_per_epoch())
             .saturating_mul(slot_duration.as_millis() as u64);
+        let subscription_lookahead_millis = config
+            .subnet_subscription_lookahead_slots
+            .saturating_mul(slot_duration.as_millis() as u64);
 
         // Panics on overflow. Ensure LAST_SEEN_VALIDATOR_TIMEOUT is not too large.
         let last_seen_val_timeout = slot_duration
@@ -130,6 +138,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             unsubscriptions: HashSetDelay::new(default_timeout),
             aggregate_validators_on_subnet: HashSetDelay::new(default_timeout),
             known_validators: HashSetDelay::new(last_seen_val_timeout),
+            subscription_lookahead: Duration::from_millis(subscription_lookahead_millis),
             log,
         }
     }
@@ -325,18 +334,22 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                     .checked_div(ADVANCE_SUBSCRIBE_TIME)
                     .expect("ADVANCE_SUBSCRIPTION_TIME cannot be too large");
 
-                // calculate the time to subscribe to the subnet
-                let duration_to_subscribe = self
+                // calculate the time to subscribe to the subnet, both with and without the
+                // configured lookahead
+                let duration_to_advance_subscription = self
                     .beacon_chain
                     .slot_clock
                     .duration_to_slot(exact_subnet.slot)
                     .ok_or_else(|| "Unable to determine duration to subscription slot")?
                     .checked_sub(advance_subscription_duration)
                     .unwrap_or_else(|| Duration::from_secs(0));
+                let duration_to_subscribe = duration_to_advance_subscription
+                    .checked_sub(self.subscription_lookahead)
+                    .unwrap_or_else(|| Duration::from_secs(0));
 
                 // the duration until we no longer need this subscription. We assume a single slot is
                 // sufficient.
-                let expected_end_subscription_duration = duration_to_subscribe
+                let expected_end_subscription_duration = duration_to_advance_subscription
                     + slot_duration
                     + std::cmp::min(advance_subscription_duration, duration_to_next_slot);
 
@@ -481,7 +494,8 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                 .expect("ADVANCE_SUBSCRIPTION_TIME cannot be too large");
             // we require the subnet subscription for at least a slot on top of the initial
             // subscription time
-            let expected_end_subscription_duration = slot_duration + advance_subscription_duration;
+            let expected_end_subscription_duration =
+                self.subscription_lookahead + slot_duration + advance_subscription_duration;
 
             if expiry < &(Instant::now() + expected_end_subscription_duration) {
                 self.random_subnets
@@ -90,16 +90,21 @@ mod tests {
     }
 
     fn get_attestation_service() -> AttestationService<TestBeaconChainType> {
+        get_attestation_service_with(NetworkConfig::default())
+    }
+
+    fn get_attestation_service_with(
+        config: NetworkConfig,
+    ) -> AttestationService<TestBeaconChainType> {
         let log = get_logger();
 
         let beacon_chain = CHAIN.chain.clone();
 
-        let config = NetworkConfig::default();
         let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
         let enr = build_enr::<MinimalEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap();
 
         let network_globals: NetworkGlobals<MinimalEthSpec> = NetworkGlobals::new(enr, 0, 0, &log);
-        AttestationService::new(beacon_chain, Arc::new(network_globals), &log)
+        AttestationService::new(beacon_chain, Arc::new(network_globals), &config, &log)
     }
 
     fn get_subscription(
@@ -298,6 +303,56 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn subscribe_five_slots_ahead_with_lookahead() {
+        // subscription config
+        let validator_index = 1;
+        let committee_index = 1;
+        let subscription_slot = 5;
+        let no_events_expected = 5;
+
+        // create the attestation service, subscribing to subnets more than five slots ahead
+        let mut config = NetworkConfig::default();
+        config.subnet_subscription_lookahead_slots = 6;
+        let mut attestation_service = get_attestation_service_with(config);
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        let subscriptions = vec![get_subscription(
+            validator_index,
+            committee_index,
+            current_slot + Slot::new(subscription_slot),
+        )];
+
+        // submit the subscriptions
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        // discover peers and subscribe immediately, rather than waiting for the slot
+        let expected = vec![
+            AttServiceMessage::DiscoverPeers(SubnetId::new(validator_index)),
+            AttServiceMessage::Subscribe(SubnetId::new(validator_index)),
+        ];
+
+        let events = get_events(attestation_service, no_events_expected, 1).await;
+        assert_matches!(
+            events[..3],
+            [
+                AttServiceMessage::DiscoverPeers(_any1),
+                AttServiceMessage::Subscribe(_any2),
+                AttServiceMessage::EnrAdd(_any3)
+            ]
+        );
+        // if there are fewer events than expected, there's been a collision
+        if events.len() == no_events_expected {
+            assert_eq!(expected[..], events[3..]);
+        }
+    }
+
     #[tokio::test]
     async fn subscribe_five_slots_ahead_wait_five_slots() {
         // subscription config
@@ -7,9 +7,36 @@ use types::{EthSpec, Hash256};
 /// 32-byte key for accessing the `DhtEnrs`.
 pub const DHT_DB_KEY: &str = "PERSISTEDDHTPERSISTEDDHTPERSISTE";
 
+/// 32-byte key for accessing the ENRs of well-behaved peers from the previous run.
+pub const GOOD_PEERS_DB_KEY: &str = "PERSISTEDPEERSPERSISTEDPEERSPERS";
+
 pub fn load_dht<T: Store<E>, E: EthSpec>(store: Arc<T>) -> Vec<Enr> {
-    // Load DHT from store
-    let key = Hash256::from_slice(&DHT_DB_KEY.as_bytes());
+    load_enrs(store, DHT_DB_KEY)
+}
+
+/// Attempt to persist the ENR's in the DHT to `self.store`.
+pub fn persist_dht<T: Store<E>, E: EthSpec>(
+    store: Arc<T>,
+    enrs: Vec<Enr>,
+) -> Result<(), store::Error> {
+    persist_enrs(store, DHT_DB_KEY, enrs)
+}
+
+/// Load the ENRs of the well-behaved peers which were connected when the node last shut down.
+pub fn load_good_peers<T: Store<E>, E: EthSpec>(store: Arc<T>) -> Vec<Enr> {
+    load_enrs(store, GOOD_PEERS_DB_KEY)
+}
+
+/// Attempt to persist the ENRs of well-behaved peers, so they may be dialed at the next startup.
+pub fn persist_good_peers<T: Store<E>, E: EthSpec>(
+    store: Arc<T>,
+    enrs: Vec<Enr>,
+) -> Result<(), store::Error> {
+    persist_enrs(store, GOOD_PEERS_DB_KEY, enrs)
+}
+
+fn load_enrs<T: Store<E>, E: EthSpec>(store: Arc<T>, db_key: &str) -> Vec<Enr> {
+    let key = Hash256::from_slice(&db_key.as_bytes());
     match store.get(&key) {
         Ok(Some(p)) => {
             let p: PersistedDht = p;
@@ -19,12 +46,12 @@ pub fn load_dht<T: Store<E>, E: EthSpec>(store: Arc<T>) -> Vec<Enr> {
     }
 }
 
-/// Attempt to persist the ENR's in the DHT to `self.store`.
-pub fn persist_dht<T: Store<E>, E: EthSpec>(
+fn persist_enrs<T: Store<E>, E: EthSpec>(
     store: Arc<T>,
+    db_key: &str,
     enrs: Vec<Enr>,
 ) -> Result<(), store::Error> {
-    let key = Hash256::from_slice(&DHT_DB_KEY.as_bytes());
+    let key = Hash256::from_slice(&db_key.as_bytes());
     store.put(&key, &PersistedDht { enrs })?;
     Ok(())
 }
@@ -72,4 +99,16 @@ mod tests {
         let dht: PersistedDht = store.get(&key).unwrap().unwrap();
         assert_eq!(dht.enrs, enrs);
     }
+
+    #[test]
+    fn test_persisted_good_peers() {
+        let store = Arc::new(MemoryStore::<MinimalEthSpec>::open());
+        let enrs = vec![Enr::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").unwrap()];
+        assert!(load_good_peers(store.clone()).is_empty());
+
+        persist_good_peers(store.clone(), enrs.clone()).unwrap();
+        assert_eq!(load_good_peers(store.clone()), enrs);
+        // The good peers are stored separately to the DHT.
+        assert!(load_dht(store).is_empty());
+    }
 }
@@ -227,6 +227,9 @@ impl<T: BeaconChainTypes> Processor<T> {
                 "peer" => format!("{:?}", peer_id),
                 "reason" => "different finalized chain"
             );
+            // Record the peer's view of the chain before it is disconnected, so it is visible in
+            // the HTTP API.
+            self.send_to_sync(SyncMessage::WrongChainPeer(peer_id.clone(), remote));
             self.network
                 .disconnect(peer_id, GoodbyeReason::IrrelevantNetwork);
         } else if remote.finalized_epoch < local.finalized_epoch {
@@ -249,6 +252,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                 "peer" => format!("{:?}", peer_id),
                 "reason" => "lower finalized epoch"
             );
+            self.send_to_sync(SyncMessage::BehindPeer(peer_id, remote));
         } else if self
             .chain
             .store
@@ -690,6 +694,24 @@ impl<T: BeaconChainTypes> Processor<T> {
                 // we don't know the block, get the sync manager to handle the block lookup
                 self.send_to_sync(SyncMessage::UnknownBlockHash(peer_id, beacon_block_root));
             }
+            AttnError::TooManySkippedSlots {
+                head_block_slot,
+                attestation_slot,
+            } => {
+                /*
+                 * The attestation skips more slots from its head block than the user is willing
+                 * to compute a state transition across.
+                 *
+                 * It's not clear if the message is invalid/malicious.
+                 */
+                debug!(
+                    self.log,
+                    "Rejected attestation with too many skipped slots";
+                    "peer_id" => format!("{:?}", peer_id),
+                    "head_block_slot" => head_block_slot,
+                    "attestation_slot" => attestation_slot
+                );
+            }
             AttnError::UnknownTargetRoot(_) => {
                 /*
                  * The block indicated by the target root is not known to us.
@@ -1,4 +1,4 @@
-use crate::persisted_dht::{load_dht, persist_dht};
+use crate::persisted_dht::{load_dht, load_good_peers, persist_dht, persist_good_peers};
 use crate::router::{Router, RouterMessage};
 use crate::{
     attestation_service::{AttServiceMessage, AttestationService},
@@ -23,6 +23,8 @@ mod tests;
 
 /// The time in seconds that a peer will be banned and prevented from reconnecting.
 const BAN_PEER_TIMEOUT: u64 = 30;
+/// The maximum number of well-behaved peers to persist on shutdown and dial on startup.
+const MAX_PERSISTED_GOOD_PEERS: usize = 50;
 
 /// Service that handles communication between internal services and the `eth2_libp2p` network service.
 pub struct NetworkService<T: BeaconChainTypes> {
@@ -77,6 +79,20 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         let (network_globals, mut libp2p) =
             runtime_handle.enter(|| LibP2PService::new(config, enr_fork_id, &network_log))?;
 
+        // dial the well-behaved peers from the previous run first, before falling back to
+        // discovery
+        let good_peers = load_good_peers::<T::Store, T::EthSpec>(store.clone());
+        if !good_peers.is_empty() {
+            info!(
+                network_log,
+                "Dialing persisted peers";
+                "count" => good_peers.len(),
+            );
+        }
+        for enr in &good_peers {
+            libp2p.dial_enr(enr);
+        }
+
         for enr in load_dht::<T::Store, T::EthSpec>(store.clone()) {
             libp2p.swarm.add_enr(enr);
         }
@@ -93,8 +109,12 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         )?;
 
         // attestation service
-        let attestation_service =
-            AttestationService::new(beacon_chain.clone(), network_globals.clone(), &network_log);
+        let attestation_service = AttestationService::new(
+            beacon_chain.clone(),
+            network_globals.clone(),
+            config,
+            &network_log,
+        );
 
         // create the network service and spawn the task
         let network_log = network_log.new(o!("service"=> "network"));
@@ -149,6 +169,21 @@ fn spawn_service<T: BeaconChainTypes>(
                         ),
                     }
 
+                    let good_peers = service.libp2p.good_peer_enrs(MAX_PERSISTED_GOOD_PEERS);
+                    debug!(
+                        service.log,
+                        "Persisting good peers to store";
+                        "Number of peers" => format!("{}", good_peers.len()),
+                    );
+
+                    if let Err(e) = persist_good_peers::<T::Store, T::EthSpec>(service.store.clone(), good_peers) {
+                        error!(
+                            service.log,
+                            "Failed to persist good peers on drop";
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+
                     info!(service.log, "Network service shutdown");
                     return;
             }
@@ -224,6 +259,29 @@ fn spawn_service<T: BeaconChainTypes>(
                                 std::time::Duration::from_secs(BAN_PEER_TIMEOUT),
                             );
                         }
+                        NetworkMessage::UpdatePeering {
+                            add_boot_nodes,
+                            remove_boot_nodes,
+                            add_trusted_peers,
+                            remove_trusted_peers,
+                            rediscover,
+                        } => {
+                            for peer_id in &remove_boot_nodes {
+                                service.libp2p.remove_boot_node(peer_id);
+                            }
+                            for enr in add_boot_nodes {
+                                service.libp2p.add_boot_node(enr);
+                            }
+                            for peer_id in &remove_trusted_peers {
+                                service.libp2p.remove_trusted_peer(peer_id);
+                            }
+                            for enr in add_trusted_peers {
+                                service.libp2p.add_trusted_peer(enr);
+                            }
+                            if rediscover {
+                                service.libp2p.rediscover();
+                            }
+                        }
                         NetworkMessage::Subscribe { subscriptions } => {
                             // the result is dropped as it used solely for ergonomics
                             let _ = service
@@ -396,6 +454,14 @@ pub enum NetworkMessage<T: EthSpec> {
     },
     /// Disconnect and bans a peer id.
     Disconnect { peer_id: PeerId },
+    /// Updates the boot nodes and trusted peers, optionally restarting peer discovery.
+    UpdatePeering {
+        add_boot_nodes: Vec<Enr>,
+        remove_boot_nodes: Vec<PeerId>,
+        add_trusted_peers: Vec<Enr>,
+        remove_trusted_peers: Vec<PeerId>,
+        rediscover: bool,
+    },
 }
 
 /// Inspects the `messages` that were being sent to the network and updates Prometheus metrics.
@@ -48,6 +48,7 @@ use smallvec::SmallVec;
 use std::boxed::Box;
 use std::ops::Sub;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -61,6 +62,8 @@ const PARENT_FAIL_TOLERANCE: usize = 5;
 /// canonical chain to its head once the peer connects. A chain should not appear where it's depth
 /// is further back than the most recent head slot.
 const PARENT_DEPTH_TOLERANCE: usize = SLOT_IMPORT_TOLERANCE * 2;
+/// How often range sync checks for chains which have stopped making progress.
+const SYNC_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug)]
 /// A message than can be sent to the sync manager thread.
@@ -68,6 +71,12 @@ pub enum SyncMessage<T: EthSpec> {
     /// A useful peer has been discovered.
     AddPeer(PeerId, PeerSyncInfo),
 
+    /// A peer with a lower finalized epoch than ours, which has nothing for us to download.
+    BehindPeer(PeerId, PeerSyncInfo),
+
+    /// A peer which has finalized a block that conflicts with our chain.
+    WrongChainPeer(PeerId, PeerSyncInfo),
+
     /// A `BlocksByRange` response has been received.
     BlocksByRangeResponse {
         peer_id: PeerId,
@@ -105,6 +114,9 @@ pub enum SyncMessage<T: EthSpec> {
 
     /// A parent lookup has failed for a block given by this `peer_id`.
     ParentLookupFailed(PeerId),
+
+    /// A periodic prompt to switch away from sync chains which have stalled.
+    Heartbeat,
 }
 
 /// Maintains a sequential list of parents to lookup and the lookup's current state.
@@ -219,6 +231,18 @@ pub fn spawn<T: BeaconChainTypes>(
         futures::future::select(Box::pin(sync_manager.main()), exit_rx).await;
         info!(log.clone(), "Sync Manager shutdown");
     });
+
+    // prompt range sync to check for stalled chains, until the sync manager is dropped
+    let heartbeat_send = sync_send.clone();
+    runtime_handle.spawn(async move {
+        let mut interval = tokio::time::interval(SYNC_HEARTBEAT_INTERVAL);
+        loop {
+            interval.tick().await;
+            if heartbeat_send.send(SyncMessage::Heartbeat).is_err() {
+                break;
+            }
+        }
+    });
     (sync_send, sync_exit)
 }
 
@@ -579,6 +603,18 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         self.update_sync_state();
     }
 
+    /// Updates the syncing state of a peer to be on a conflicting chain.
+    fn wrong_chain_peer(&mut self, peer_id: &PeerId, sync_info: PeerSyncInfo) {
+        if let Some(peer_info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            if peer_info.sync_status.update_wrong_chain(sync_info.into()) {
+                debug!(self.log, "Peer transitioned sync state"; "new_state" => "wrong_chain", "peer_id" => format!("{}", peer_id), "finalized_root" => format!("{}", sync_info.finalized_root), "finalized_epoch" => sync_info.finalized_epoch);
+            }
+        } else {
+            crit!(self.log, "Status'd peer is unknown"; "peer_id" => format!("{}", peer_id));
+        }
+        self.update_sync_state();
+    }
+
     /// Updates the global sync state and logs any changes.
     fn update_sync_state(&mut self) {
         if let Some((old_state, new_state)) = self.network_globals.update_sync_state() {
@@ -737,6 +773,12 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     SyncMessage::AddPeer(peer_id, info) => {
                         self.add_peer(peer_id, info);
                     }
+                    SyncMessage::BehindPeer(peer_id, info) => {
+                        self.behind_peer(&peer_id, info);
+                    }
+                    SyncMessage::WrongChainPeer(peer_id, info) => {
+                        self.wrong_chain_peer(&peer_id, info);
+                    }
                     SyncMessage::BlocksByRangeResponse {
                         peer_id,
                         request_id,
@@ -785,6 +827,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     SyncMessage::ParentLookupFailed(peer_id) => {
                         self.network.downvote_peer(peer_id);
                     }
+                    SyncMessage::Heartbeat => {
+                        self.range_sync.heartbeat(&mut self.network);
+                    }
                 }
             }
         }
@@ -9,6 +9,7 @@ use rand::prelude::*;
 use slog::{crit, debug, warn};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -31,6 +32,11 @@ const BATCH_BUFFER_SIZE: u8 = 5;
 /// be downvoted.
 const INVALID_BATCH_LOOKUP_ATTEMPTS: u8 = 3;
 
+/// If a syncing chain has not successfully processed a batch for this long, it is considered
+/// stalled (e.g., because its peers have stopped responding or have moved to another chain) and
+/// sync may switch to another chain.
+pub const CHAIN_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
 #[derive(PartialEq)]
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
@@ -85,6 +91,10 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// The current processing batch, if any.
     current_processing_batch: Option<Batch<T::EthSpec>>,
 
+    /// The time at which the chain last started syncing or successfully processed a batch, or
+    /// `None` if the chain is not syncing and did not stall.
+    last_progress: Option<Instant>,
+
     /// A send channel to the sync manager. This is given to the batch processor thread to report
     /// back once batch processing has completed.
     sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
@@ -131,6 +141,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             to_be_processed_id: BatchId(1),
             state: ChainSyncingState::Stopped,
             current_processing_batch: None,
+            last_progress: None,
             sync_send,
             chain,
             log,
@@ -321,6 +332,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let res = match result {
             BatchProcessResult::Success => {
                 *self.to_be_processed_id += 1;
+                self.last_progress = Some(Instant::now());
 
                 // If the processed batch was not empty, we can validate previous invalidated
                 // blocks
@@ -535,9 +547,21 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     }
 
     pub fn stop_syncing(&mut self) {
+        // A stalled chain remains so until it is restarted, so that it is not immediately chosen
+        // to sync again.
+        if !self.is_stalled() {
+            self.last_progress = None;
+        }
         self.state = ChainSyncingState::Stopped;
     }
 
+    /// Returns `true` if the chain has not made progress within `CHAIN_STALL_TIMEOUT` of starting
+    /// to sync or processing its last batch.
+    pub fn is_stalled(&self) -> bool {
+        self.last_progress
+            .map_or(false, |last| last.elapsed() > CHAIN_STALL_TIMEOUT)
+    }
+
     // Either a new chain, or an old one with a peer list
     /// This chain has been requested to start syncing.
     ///
@@ -577,6 +601,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
 
         self.state = ChainSyncingState::Syncing;
+        self.last_progress = Some(Instant::now());
 
         // start processing batches if needed
         self.process_completed_batches();
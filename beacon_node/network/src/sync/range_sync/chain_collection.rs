@@ -229,18 +229,24 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         if let Some(index) = self.finalized_syncing_index() {
             // There is a current finalized chain syncing
             let syncing_chain_peer_count = self.finalized_chains[index].peer_pool.len();
+            let syncing_chain_stalled = self.finalized_chains[index].is_stalled();
 
-            // search for a chain with more peers
-            if let Some((new_index, chain)) =
-                self.finalized_chains
-                    .iter_mut()
-                    .enumerate()
-                    .find(|(iter_index, chain)| {
-                        *iter_index != index && chain.peer_pool.len() > syncing_chain_peer_count
-                    })
+            // search for a chain with more peers or, if the syncing chain has stalled, any other
+            // chain. Chains which have stalled themselves are only chosen if there are no others.
+            if let Some((new_index, chain)) = self
+                .finalized_chains
+                .iter_mut()
+                .enumerate()
+                .filter(|(iter_index, chain)| {
+                    *iter_index != index
+                        && (syncing_chain_stalled
+                            || (!chain.is_stalled()
+                                && chain.peer_pool.len() > syncing_chain_peer_count))
+                })
+                .max_by_key(|(_, chain)| (!chain.is_stalled(), chain.peer_pool.len()))
             {
-                // A chain has more peers. Swap the syncing chain
-                debug!(self.log, "Switching finalized chains to sync"; "new_target_root" => format!("{}", chain.target_head_root), "new_end_slot" => chain.target_head_slot, "new_start_epoch"=> local_epoch);
+                // A chain has more peers or the syncing chain has stalled. Swap the syncing chain
+                debug!(self.log, "Switching finalized chains to sync"; "new_target_root" => format!("{}", chain.target_head_root), "new_end_slot" => chain.target_head_slot, "new_start_epoch"=> local_epoch, "previous_chain_stalled" => syncing_chain_stalled);
 
                 // update the state to a new finalized state
                 let state = RangeSyncState::Finalized {
@@ -258,7 +264,7 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         } else if let Some(chain) = self
             .finalized_chains
             .iter_mut()
-            .max_by_key(|chain| chain.peer_pool.len())
+            .max_by_key(|chain| (!chain.is_stalled(), chain.peer_pool.len()))
         {
             // There is no currently syncing finalization chain, starting the one with the most peers
             debug!(self.log, "New finalized chain started syncing"; "new_target_root" => format!("{}", chain.target_head_root), "new_end_slot" => chain.target_head_slot, "new_start_epoch"=> chain.start_epoch);
@@ -295,6 +301,34 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         }
     }
 
+    /// Handles chains which have stopped making progress, without restarting the rest of sync.
+    ///
+    /// The peers of a stalled chain are re-status'd, so that any peer whose head has moved is
+    /// added to a chain matching its new head. Stalled head chains are removed. If the syncing
+    /// finalized chain has stalled, another finalized chain is synced instead, if there is one.
+    pub fn handle_stalled_chains(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        let log_ref = &self.log;
+        self.head_chains.retain(|chain| {
+            if chain.is_stalled() {
+                debug!(log_ref, "Removing stalled head chain"; "start_epoch" => chain.start_epoch, "end_slot" => chain.target_head_slot, "peers" => chain.peer_pool.len());
+                chain.status_peers(network);
+                false
+            } else {
+                true
+            }
+        });
+
+        if let Some(index) = self.finalized_syncing_index() {
+            let chain = &self.finalized_chains[index];
+            if chain.is_stalled() {
+                debug!(self.log, "Syncing finalized chain has stalled"; "start_epoch" => chain.start_epoch, "end_slot" => chain.target_head_slot, "peers" => chain.peer_pool.len());
+                chain.status_peers(network);
+            }
+        }
+
+        self.update_finalized(network);
+    }
+
     /// Add a new finalized chain to the collection.
     pub fn new_finalized_chain(
         &mut self,
@@ -389,6 +423,14 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         ChainCollection::request_function(self.finalized_chains.iter_mut(), func)
     }
 
+    /// Runs a function on all finalized chains and collects all `Some` results from `F`.
+    pub fn finalized_request_all<F, U>(&mut self, func: F) -> Vec<(usize, U)>
+    where
+        F: FnMut(&mut SyncingChain<T>) -> Option<U>,
+    {
+        ChainCollection::request_function_all(self.finalized_chains.iter_mut(), func)
+    }
+
     /// Runs a function on head chains until we get the first `Some` result from `F`.
     pub fn head_request<F, U>(&mut self, func: F) -> Option<(usize, U)>
     where
@@ -23,6 +23,9 @@
 //!  - The finalized chain with the largest peer pool takes priority.
 //!  - As one finalized chain completes, others are checked to see if we they can be continued,
 //!  otherwise they are removed.
+//!  - A chain which has not processed a batch for `CHAIN_STALL_TIMEOUT` is considered stalled.
+//!  The syncing chain is switched away from as soon as it stalls (its peers are re-status'd in
+//!  case their view of the chain has changed) and stalled head chains are removed.
 //!
 //!  ## Head Chain Sync
 //!
@@ -39,7 +42,7 @@
 //!  Each chain is downloaded in batches of blocks. The batched blocks are processed sequentially
 //!  and further batches are requested as current blocks are being processed.
 
-use super::chain::{ChainId, ProcessingResult};
+use super::chain::{ChainId, ProcessingResult, SyncingChain};
 use super::chain_collection::{ChainCollection, RangeSyncState};
 use super::sync_type::RangeSyncType;
 use super::BatchId;
@@ -139,7 +142,20 @@ impl<T: BeaconChainTypes> RangeSync<T> {
             .finalized_epoch
             .start_slot(T::EthSpec::slots_per_epoch());
 
-        // NOTE: A peer that has been re-status'd may now exist in multiple finalized chains.
+        // A peer that has been re-status'd may have moved to a new finalized checkpoint. A peer
+        // which has finalized beyond the target of a finalized chain can still serve it, but one
+        // which has finalized a different block at or before the target conflicts with the chain.
+        // Remove it from such chains, so that a chain whose peers have all moved away is purged.
+        let results = self.chains.finalized_request_all(|chain| {
+            if remote_finalized_slot <= chain.target_head_slot
+                && remote_info.finalized_root != chain.target_head_root
+            {
+                Self::remove_peer_from_chain(network, chain, &peer_id)
+            } else {
+                None
+            }
+        });
+        self.remove_failed_chains(network, results);
 
         // remove any out-of-date chains
         self.chains.purge_outdated_chains(network);
@@ -366,25 +382,51 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         self.chains.update_sync_state();
     }
 
+    /// Called periodically to switch away from chains which have stopped making progress.
+    pub fn heartbeat(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        self.chains.handle_stalled_chains(network);
+        self.chains.update_sync_state();
+    }
+
     /// When a peer gets removed, both the head and finalized chains need to be searched to check which pool the peer is in. The chain may also have a batch or batches awaiting
     /// for this peer. If so we mark the batch as failed. The batch may then hit it's maximum
     /// retries. In this case, we need to remove the chain and re-status all the peers.
     fn remove_peer(&mut self, network: &mut SyncNetworkContext<T::EthSpec>, peer_id: &PeerId) {
-        for (index, result) in self.chains.head_finalized_request_all(|chain| {
-            if chain.peer_pool.remove(peer_id) {
-                // this chain contained the peer
-                while let Some(batch) = chain.pending_batches.remove_batch_by_peer(peer_id) {
-                    if let ProcessingResult::RemoveChain = chain.failed_batch(network, batch) {
-                        // a single batch failed, remove the chain
-                        return Some(ProcessingResult::RemoveChain);
-                    }
+        let results = self.chains.head_finalized_request_all(|chain| {
+            Self::remove_peer_from_chain(network, chain, peer_id)
+        });
+        self.remove_failed_chains(network, results);
+    }
+
+    /// Removes `peer_id` from `chain`, re-requesting any of its batches from the other peers of the
+    /// chain. Returns `None` if the peer was not in the chain's peer pool.
+    fn remove_peer_from_chain(
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        chain: &mut SyncingChain<T>,
+        peer_id: &PeerId,
+    ) -> Option<ProcessingResult> {
+        if chain.peer_pool.remove(peer_id) {
+            // this chain contained the peer
+            while let Some(batch) = chain.pending_batches.remove_batch_by_peer(peer_id) {
+                if let ProcessingResult::RemoveChain = chain.failed_batch(network, batch) {
+                    // a single batch failed, remove the chain
+                    return Some(ProcessingResult::RemoveChain);
                 }
-                // peer removed from chain, no batch failed
-                Some(ProcessingResult::KeepChain)
-            } else {
-                None
             }
-        }) {
+            // peer removed from chain, no batch failed
+            Some(ProcessingResult::KeepChain)
+        } else {
+            None
+        }
+    }
+
+    /// Removes the chains whose result is `ProcessingResult::RemoveChain`.
+    fn remove_failed_chains(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        results: Vec<(usize, ProcessingResult)>,
+    ) {
+        for (index, result) in results {
             if result == ProcessingResult::RemoveChain {
                 // the chain needed to be removed
                 debug!(self.log, "Chain being removed due to failed batch");
@@ -7,8 +7,9 @@ pub use persistence::PersistedOperationPool;
 
 use attestation::AttMaxCover;
 use attestation_id::AttestationId;
-use max_cover::maximum_cover;
+use max_cover::{maximum_cover, MaxCover};
 use parking_lot::RwLock;
+use serde_derive::{Deserialize, Serialize};
 use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, ExitValidationError,
     ProposerSlashingValidationError,
@@ -38,6 +39,36 @@ pub struct OperationPool<T: EthSpec + Default> {
     _phantom: PhantomData<T>,
 }
 
+/// Determines how attestations more than one epoch older than the block (i.e., "historic"
+/// attestations) are included in blocks.
+///
+/// Attestations are classified by their age as per the inclusion window of the spec, so an
+/// attestation is current whilst `state.slot <= data.slot + SLOTS_PER_EPOCH`. Since phase 0 also
+/// requires this window for inclusion, historic attestations only have an effect on networks and
+/// spec phases which permit later inclusion.
+///
+/// Historic attestations are often worth less to the network than those from the current epoch,
+/// since their attesters have a greater inclusion delay, and their value changes between networks
+/// and spec phases.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AttestationInclusionStrategy {
+    /// Historic attestations compete with current attestations for space in the block, purely on
+    /// the proposer reward for including them.
+    Compete,
+    /// Current attestations are included first, then historic attestations fill any space that
+    /// remains, as long as the estimated proposer reward for including each is at least
+    /// `min_reward` Gwei.
+    WhenSpaceRemains { min_reward: u64 },
+    /// Historic attestations are never included.
+    Never,
+}
+
+impl Default for AttestationInclusionStrategy {
+    fn default() -> Self {
+        AttestationInclusionStrategy::Compete
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum OpPoolError {
     GetAttestationsTotalBalanceError(BeaconStateError),
@@ -51,6 +82,9 @@ impl<T: EthSpec> OperationPool<T> {
 
     /// Insert an attestation into the pool, aggregating it with existing attestations if possible.
     ///
+    /// Attestations whose signers are all already included in an existing attestation for the
+    /// same data add nothing to the pool, and are dropped.
+    ///
     /// ## Note
     ///
     /// This function assumes the given `attestation` is valid.
@@ -74,13 +108,18 @@ impl<T: EthSpec> OperationPool<T> {
             hash_map::Entry::Occupied(entry) => entry.into_mut(),
         };
 
+        if existing_attestations
+            .iter()
+            .any(|existing_attestation| attestation.signers_subset_of(existing_attestation))
+        {
+            return Ok(());
+        }
+
         let mut aggregated = false;
         for existing_attestation in existing_attestations.iter_mut() {
             if existing_attestation.signers_disjoint_from(&attestation) {
                 existing_attestation.aggregate(&attestation);
                 aggregated = true;
-            } else if *existing_attestation == attestation {
-                aggregated = true;
             }
         }
 
@@ -102,11 +141,30 @@ impl<T: EthSpec> OperationPool<T> {
     /// before an approximately optimal bundle is constructed. We use it to provide access
     /// to the fork choice data from the `BeaconChain` struct that doesn't logically belong
     /// in the operation pool.
+    ///
+    /// Historic attestations are included as per the default `AttestationInclusionStrategy`.
     pub fn get_attestations(
         &self,
         state: &BeaconState<T>,
         validity_filter: impl FnMut(&&Attestation<T>) -> bool,
         spec: &ChainSpec,
+    ) -> Result<Vec<Attestation<T>>, OpPoolError> {
+        self.get_attestations_with_strategy(
+            state,
+            validity_filter,
+            AttestationInclusionStrategy::default(),
+            spec,
+        )
+    }
+
+    /// As per `Self::get_attestations`, but including historic attestations as per the given
+    /// `strategy`.
+    pub fn get_attestations_with_strategy(
+        &self,
+        state: &BeaconState<T>,
+        validity_filter: impl FnMut(&&Attestation<T>) -> bool,
+        strategy: AttestationInclusionStrategy,
+        spec: &ChainSpec,
     ) -> Result<Vec<Attestation<T>>, OpPoolError> {
         // Attestations for the current fork, which may be from the current or previous epoch.
         let prev_epoch = state.previous_epoch();
@@ -130,7 +188,7 @@ impl<T: EthSpec> OperationPool<T> {
         let total_active_balance = state
             .get_total_balance(&active_indices, spec)
             .map_err(OpPoolError::GetAttestationsTotalBalanceError)?;
-        let valid_attestations = reader
+        let (current_attestations, historic_attestations): (Vec<_>, Vec<_>) = reader
             .iter()
             .filter(|(key, _)| {
                 key.domain_bytes_match(&prev_domain_bytes)
@@ -148,12 +206,37 @@ impl<T: EthSpec> OperationPool<T> {
                 .is_ok()
             })
             .filter(validity_filter)
-            .flat_map(|att| AttMaxCover::new(att, state, total_active_balance, spec));
+            .partition(|attestation| state.slot <= attestation.data.slot + T::slots_per_epoch());
+
+        let max_cover_items = |attestations: Vec<_>| {
+            attestations
+                .into_iter()
+                .flat_map(|att| AttMaxCover::new(att, state, total_active_balance, spec))
+        };
+        let max_attestations = T::MaxAttestations::to_usize();
 
-        Ok(maximum_cover(
-            valid_attestations,
-            T::MaxAttestations::to_usize(),
-        ))
+        Ok(match strategy {
+            AttestationInclusionStrategy::Compete => maximum_cover(
+                max_cover_items(current_attestations).chain(max_cover_items(historic_attestations)),
+                max_attestations,
+            ),
+            AttestationInclusionStrategy::WhenSpaceRemains { min_reward } => {
+                let mut attestations =
+                    maximum_cover(max_cover_items(current_attestations), max_attestations);
+                let space_remaining = max_attestations - attestations.len();
+
+                // The score of each item is an estimate of the proposer reward for including it,
+                // since it may cover some of the same validators as another historic attestation.
+                let profitable = max_cover_items(historic_attestations)
+                    .filter(|item| item.score() as u64 >= min_reward);
+                attestations.extend(maximum_cover(profitable, space_remaining));
+
+                attestations
+            }
+            AttestationInclusionStrategy::Never => {
+                maximum_cover(max_cover_items(current_attestations), max_attestations)
+            }
+        })
     }
 
     /// Remove attestations which are too old to be included in a block.
@@ -473,6 +556,14 @@ mod release_tests {
     /// Test state for attestation-related tests.
     fn attestation_test_state<E: EthSpec>(
         num_committees: usize,
+    ) -> (BeaconState<E>, Vec<Keypair>, ChainSpec) {
+        attestation_test_state_at(num_committees, E::slots_per_epoch() / 2)
+    }
+
+    /// As per `attestation_test_state`, but `slot_in_epoch` slots into the epoch.
+    fn attestation_test_state_at<E: EthSpec>(
+        num_committees: usize,
+        slot_in_epoch: u64,
     ) -> (BeaconState<E>, Vec<Keypair>, ChainSpec) {
         let spec = E::default_spec();
 
@@ -480,7 +571,7 @@ mod release_tests {
             num_committees * E::slots_per_epoch() as usize * spec.target_committee_size;
         let mut state_builder =
             TestingBeaconStateBuilder::from_deterministic_keypairs(num_validators, &spec);
-        let slot_offset = 1000 * E::slots_per_epoch() + E::slots_per_epoch() / 2;
+        let slot_offset = 1000 * E::slots_per_epoch() + slot_in_epoch;
         let slot = spec.genesis_slot + slot_offset;
         state_builder.teleport_to_slot(slot);
         state_builder.build_caches(&spec).unwrap();
@@ -662,6 +753,54 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), committees.len());
     }
 
+    /// Attestations signed only by validators which have already signed an attestation in the
+    /// pool should be dropped rather than stored alongside it.
+    #[test]
+    fn attestation_subset_dropped() {
+        let (ref mut state, ref keypairs, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let op_pool = OperationPool::new();
+
+        let slot = state.slot - 1;
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for bc in &committees {
+            let insert = |range: std::ops::Range<usize>| {
+                let att = signed_attestation(
+                    &bc.committee,
+                    bc.index,
+                    keypairs,
+                    range,
+                    slot,
+                    state,
+                    spec,
+                    None,
+                );
+                op_pool
+                    .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                    .unwrap();
+            };
+
+            insert(0..4);
+            // Both are subsets of the first attestation.
+            insert(1..3);
+            insert(0..4);
+            // Overlaps the first attestation, so it cannot be aggregated or dropped.
+            insert(2..6);
+        }
+
+        assert_eq!(op_pool.num_attestations(), 2 * committees.len());
+        for attestations in op_pool.attestations.read().values() {
+            assert_eq!(attestations[0].aggregation_bits.num_set_bits(), 4);
+            assert_eq!(attestations[1].aggregation_bits.num_set_bits(), 4);
+        }
+    }
+
     /// Adding lots of attestations that only intersect pairwise should lead to two aggregate
     /// attestations.
     #[test]
@@ -891,6 +1030,121 @@ mod release_tests {
         }
     }
 
+    /// Check that attestations within the inclusion window are current as per each
+    /// `AttestationInclusionStrategy`, and that those outside it are never included.
+    #[test]
+    fn attestation_inclusion_strategy() {
+        let (ref mut state, ref keypairs, ref spec) =
+            attestation_test_state_at::<MainnetEthSpec>(1, MainnetEthSpec::slots_per_epoch() - 1);
+
+        let op_pool = OperationPool::new();
+
+        let current_slot = state.slot - 1;
+        let window_end_slot = state.slot - MainnetEthSpec::slots_per_epoch();
+        let expired_slot = window_end_slot - 1;
+        assert_eq!(
+            current_slot.epoch(MainnetEthSpec::slots_per_epoch()),
+            state.current_epoch()
+        );
+        assert_eq!(
+            expired_slot.epoch(MainnetEthSpec::slots_per_epoch()),
+            state.previous_epoch()
+        );
+
+        for &slot in &[current_slot, window_end_slot, expired_slot] {
+            for bc in state.get_beacon_committees_at_slot(slot).unwrap() {
+                let att = signed_attestation(
+                    bc.committee,
+                    bc.index,
+                    keypairs,
+                    ..,
+                    slot,
+                    state,
+                    spec,
+                    None,
+                );
+                op_pool
+                    .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                    .unwrap();
+            }
+        }
+
+        let get_slots = |strategy| {
+            op_pool
+                .get_attestations_with_strategy(state, |_| true, strategy, spec)
+                .expect("should get attestations")
+                .into_iter()
+                .map(|att| att.data.slot)
+                .collect::<BTreeSet<_>>()
+        };
+        let current = BTreeSet::from_iter(vec![current_slot, window_end_slot]);
+
+        assert_eq!(get_slots(AttestationInclusionStrategy::Compete), current);
+        assert_eq!(
+            get_slots(AttestationInclusionStrategy::WhenSpaceRemains { min_reward: 0 }),
+            current
+        );
+        assert_eq!(
+            get_slots(AttestationInclusionStrategy::WhenSpaceRemains {
+                min_reward: u64::max_value()
+            }),
+            current
+        );
+        assert_eq!(get_slots(AttestationInclusionStrategy::Never), current);
+    }
+
+    /// Check that attestations from the end of the previous epoch are not historic at the start of
+    /// the next epoch.
+    #[test]
+    fn attestation_inclusion_strategy_at_epoch_boundary() {
+        let (ref mut state, ref keypairs, ref spec) =
+            attestation_test_state_at::<MainnetEthSpec>(1, 2);
+
+        let op_pool = OperationPool::new();
+
+        let recent_slot = state.slot - 3;
+        let expired_slot = state.slot - MainnetEthSpec::slots_per_epoch() - 1;
+        assert_eq!(
+            recent_slot.epoch(MainnetEthSpec::slots_per_epoch()),
+            state.previous_epoch()
+        );
+        assert_eq!(
+            expired_slot.epoch(MainnetEthSpec::slots_per_epoch()),
+            state.previous_epoch()
+        );
+
+        for &slot in &[recent_slot, expired_slot] {
+            for bc in state.get_beacon_committees_at_slot(slot).unwrap() {
+                let att = signed_attestation(
+                    bc.committee,
+                    bc.index,
+                    keypairs,
+                    ..,
+                    slot,
+                    state,
+                    spec,
+                    None,
+                );
+                op_pool
+                    .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                    .unwrap();
+            }
+        }
+
+        let slots = op_pool
+            .get_attestations_with_strategy(
+                state,
+                |_| true,
+                AttestationInclusionStrategy::Never,
+                spec,
+            )
+            .expect("should get attestations")
+            .into_iter()
+            .map(|att| att.data.slot)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(slots, BTreeSet::from_iter(vec![recent_slot]));
+    }
+
     /// Insert two slashings for the same proposer and ensure only one is returned.
     #[test]
     fn duplicate_proposer_slashing() {
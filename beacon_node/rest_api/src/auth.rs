@@ -0,0 +1,169 @@
+//! Authenticates requests to the `/validator` endpoints, which affect the duties of validators,
+//! and the other requests which change the state of the beacon node (e.g., `POST /lighthouse/..`).
+//!
+//! When enabled, the beacon node writes a random token to a file (or reuses the token already in
+//! that file) and requires each of these requests to present it in an
+//! `Authorization: Bearer <token>` header. The validator client reads the same file, so the
+//! file system is the only channel over which the token is shared.
+
+use crate::ApiError;
+use hyper::header;
+use hyper::{Body, Method, Request};
+use rand::Rng;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+/// The name of the token file within the beacon node data directory.
+pub const API_TOKEN_FILENAME: &str = "api-token.txt";
+
+/// The number of random bytes in a generated token.
+const TOKEN_BYTES: usize = 32;
+
+/// The paths which require an `ApiToken`.
+const AUTHENTICATED_PATH_PREFIX: &str = "/validator/";
+/// The paths which require an `ApiToken`, unless the request is a `GET`.
+const AUTHENTICATED_UPDATE_PATH_PREFIX: &str = "/lighthouse/";
+
+/// A secret shared between the beacon node and its validator clients.
+#[derive(Clone)]
+pub struct ApiToken(String);
+
+impl ApiToken {
+    /// Reads the token in the file at `path`, generating a new token and writing it to `path` if
+    /// the file does not exist.
+    ///
+    /// New token files are readable and writable only by their owner.
+    pub fn load_or_generate(path: &Path) -> Result<Self, String> {
+        if path.exists() {
+            let token = fs::read_to_string(path)
+                .map_err(|e| format!("Unable to read API token file {:?}: {:?}", path, e))?
+                .trim()
+                .to_string();
+
+            if token.is_empty() {
+                return Err(format!("API token file {:?} is empty", path));
+            }
+
+            return Ok(Self(token));
+        }
+
+        let token = hex::encode(rand::thread_rng().gen::<[u8; TOKEN_BYTES]>());
+
+        let mut file = File::create(path)
+            .map_err(|e| format!("Unable to create API token file {:?}: {:?}", path, e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mut perm = file
+                .metadata()
+                .map_err(|e| format!("Unable to read API token file {:?}: {:?}", path, e))?
+                .permissions();
+            perm.set_mode(0o600);
+            file.set_permissions(perm).map_err(|e| {
+                format!(
+                    "Unable to set API token file permissions {:?}: {:?}",
+                    path, e
+                )
+            })?;
+        }
+
+        file.write_all(token.as_bytes())
+            .map_err(|e| format!("Unable to write API token file {:?}: {:?}", path, e))?;
+
+        Ok(Self(token))
+    }
+
+    /// Returns `true` if `method` requests to `path` must present the token.
+    pub fn is_required_for(method: &Method, path: &str) -> bool {
+        path.starts_with(AUTHENTICATED_PATH_PREFIX)
+            || (method != Method::GET && path.starts_with(AUTHENTICATED_UPDATE_PATH_PREFIX))
+    }
+
+    /// Returns an error unless `req` presents this token in its `Authorization` header.
+    pub fn authorize(&self, req: &Request<Body>) -> Result<(), ApiError> {
+        let presented = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                if value.starts_with("Bearer ") {
+                    Some(&value["Bearer ".len()..])
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| {
+                ApiError::Unauthorized("Missing Authorization: Bearer header.".to_string())
+            })?;
+
+        if constant_time_eq(presented.as_bytes(), self.0.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ApiError::Unauthorized("Invalid API token.".to_string()))
+        }
+    }
+}
+
+/// Compares `a` and `b` in a time which depends only upon their lengths, so the token cannot be
+/// discovered one byte at a time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn request(authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/validator/duties");
+        if let Some(value) = authorization {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn token_is_reused() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(API_TOKEN_FILENAME);
+
+        let token = ApiToken::load_or_generate(&path).unwrap();
+        assert_eq!(token.0.len(), TOKEN_BYTES * 2);
+        assert_eq!(ApiToken::load_or_generate(&path).unwrap().0, token.0);
+
+        fs::write(&path, "").unwrap();
+        assert!(ApiToken::load_or_generate(&path).is_err());
+    }
+
+    #[test]
+    fn authorize() {
+        let token = ApiToken("secret".to_string());
+
+        assert!(token.authorize(&request(Some("Bearer secret"))).is_ok());
+        assert!(token.authorize(&request(Some("Bearer secreT"))).is_err());
+        assert!(token.authorize(&request(Some("Bearer secret2"))).is_err());
+        assert!(token.authorize(&request(Some("secret"))).is_err());
+        assert!(token.authorize(&request(None)).is_err());
+
+        assert!(ApiToken::is_required_for(&Method::GET, "/validator/duties"));
+        assert!(ApiToken::is_required_for(&Method::POST, "/validator/block"));
+        assert!(!ApiToken::is_required_for(&Method::GET, "/node/version"));
+        assert!(!ApiToken::is_required_for(
+            &Method::GET,
+            "/lighthouse/peering"
+        ));
+        for path in &[
+            "/lighthouse/beacon/blocks",
+            "/lighthouse/peering",
+            "/lighthouse/logs/levels",
+            "/lighthouse/logs/capture",
+            "/lighthouse/malloc/purge",
+        ] {
+            assert!(ApiToken::is_required_for(&Method::POST, path), "{}", path);
+        }
+    }
+}
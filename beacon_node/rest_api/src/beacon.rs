@@ -6,15 +6,20 @@ use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
 use hyper::{Body, Request};
 use rest_types::{
     BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
-    ValidatorRequest, ValidatorResponse,
+    ValidatorRequest, ValidatorResponse, ValidatorStatus, ValidatorStatusRequest,
+    ValidatorStatusResponse,
 };
 use std::sync::Arc;
 use store::Store;
 use types::{
-    AttesterSlashing, BeaconState, EthSpec, Hash256, ProposerSlashing, PublicKeyBytes,
+    AttesterSlashing, BeaconState, ChainSpec, EthSpec, Hash256, ProposerSlashing, PublicKeyBytes,
     RelativeEpoch, Slot,
 };
 
+/// The path prefix of the endpoints that are relative to the state identified by a `state_id`,
+/// e.g., `/eth/v1/beacon/states/{state_id}/committees`.
+pub const STATES_PATH: &str = "/eth/v1/beacon/states/";
+
 /// HTTP handler to return a `BeaconBlock` at a given `root` or `slot`.
 pub fn get_head<T: BeaconChainTypes>(
     req: Request<Body>,
@@ -130,14 +135,36 @@ pub fn get_fork<T: BeaconChainTypes>(
     ResponseBuilder::new(&req)?.body(&beacon_chain.head()?.beacon_state.fork)
 }
 
+/// HTTP handler to return the `AttestationData` that this node would sign for the given `slot` and
+/// `committee_index`, computed from the canonical head.
+///
+/// Unlike `/validator/attestation`, this is intended for observers (e.g., to compare the votes of
+/// several nodes) rather than for validators.
+pub fn get_attestation_data<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let query = UrlQuery::from_request(&req)?;
+
+    let slot = query.slot()?;
+    let index = query.committee_index()?;
+
+    let attestation = beacon_chain
+        .produce_unaggregated_attestation(slot, index)
+        .map_err(|e| ApiError::BadRequest(format!("Unable to produce attestation: {:?}", e)))?;
+
+    ResponseBuilder::new(&req)?.body(&attestation.data)
+}
+
 /// HTTP handler to which accepts a query string of a list of validator pubkeys and maps it to a
 /// `ValidatorResponse`.
 ///
-/// This method is limited to as many `pubkeys` that can fit in a URL. See `post_validators` for
-/// doing bulk requests.
+/// This method is limited to as many `pubkeys` that can fit in a URL (and at most `max_results`).
+/// See `post_validators` for doing bulk requests.
 pub fn get_validators<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
 
@@ -146,6 +173,7 @@ pub fn get_validators<T: BeaconChainTypes>(
         .iter()
         .map(|validator_pubkey_str| parse_pubkey_bytes(validator_pubkey_str))
         .collect::<Result<Vec<_>, _>>()?;
+    check_request_size(validator_pubkeys.len(), max_results)?;
 
     let state_root_opt = if let Some((_key, value)) = query.first_of_opt(&["state_root"]) {
         Some(parse_root(&value)?)
@@ -160,11 +188,15 @@ pub fn get_validators<T: BeaconChainTypes>(
 }
 
 /// HTTP handler to return all validators, each as a `ValidatorResponse`.
+///
+/// The validators are paginated with the `continuation_token` and `limit` query parameters.
 pub fn get_all_validators<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
+    let pagination = query.pagination(max_results)?;
 
     let state_root_opt = if let Some((_key, value)) = query.first_of_opt(&["state_root"]) {
         Some(parse_root(&value)?)
@@ -172,24 +204,31 @@ pub fn get_all_validators<T: BeaconChainTypes>(
         None
     };
 
-    let mut state = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
+    let (state_root, mut state) = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
     state.update_pubkey_cache()?;
 
-    let validators = state
-        .validators
-        .iter()
+    let (page, continuation_token) = pagination.paginate(state_root, state.validators.iter())?;
+
+    let validators = page
+        .into_iter()
         .map(|validator| validator_response_by_pubkey(&state, validator.pubkey.clone()))
         .collect::<Result<Vec<_>, _>>()?;
 
-    ResponseBuilder::new(&req)?.body(&validators)
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body(&validators)
 }
 
 /// HTTP handler to return all active validators, each as a `ValidatorResponse`.
+///
+/// The validators are paginated with the `continuation_token` and `limit` query parameters.
 pub fn get_active_validators<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
+    let pagination = query.pagination(max_results)?;
 
     let state_root_opt = if let Some((_key, value)) = query.first_of_opt(&["state_root"]) {
         Some(parse_root(&value)?)
@@ -197,27 +236,37 @@ pub fn get_active_validators<T: BeaconChainTypes>(
         None
     };
 
-    let mut state = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
+    let (state_root, mut state) = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
     state.update_pubkey_cache()?;
 
-    let validators = state
-        .validators
-        .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
+    let (page, continuation_token) = pagination.paginate(
+        state_root,
+        state
+            .validators
+            .iter()
+            .filter(|validator| validator.is_active_at(state.current_epoch())),
+    )?;
+
+    let validators = page
+        .into_iter()
         .map(|validator| validator_response_by_pubkey(&state, validator.pubkey.clone()))
         .collect::<Result<Vec<_>, _>>()?;
 
-    ResponseBuilder::new(&req)?.body(&validators)
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body(&validators)
 }
 
 /// HTTP handler to which accepts a `ValidatorRequest` and returns a `ValidatorResponse` for
 /// each of the given `pubkeys`. When `state_root` is `None`, the canonical head is used.
 ///
 /// This method allows for a basically unbounded list of `pubkeys`, where as the `get_validators`
-/// request is limited by the max number of pubkeys you can fit in a URL.
+/// request is limited by the max number of pubkeys you can fit in a URL. At most `max_results`
+/// `pubkeys` may be requested at once.
 pub async fn post_validators<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let response_builder = ResponseBuilder::new(&req);
 
@@ -233,6 +282,7 @@ pub async fn post_validators<T: BeaconChainTypes>(
             ))
         })
         .and_then(|bulk_request| {
+            check_request_size(bulk_request.pubkeys.len(), max_results)?;
             validator_responses_by_pubkey(
                 beacon_chain,
                 bulk_request.state_root,
@@ -242,14 +292,97 @@ pub async fn post_validators<T: BeaconChainTypes>(
         .and_then(|validators| response_builder?.body(&validators))
 }
 
+/// HTTP handler which accepts a `ValidatorStatusRequest` and returns a `ValidatorStatusResponse`
+/// for each of the given `pubkeys` and `indices` (in that order), using the state identified by the
+/// `state_id` in the path `/eth/v1/beacon/states/{state_id}/validators`.
+///
+/// At most `max_results` validators may be requested at once.
+pub async fn post_state_validators<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let state_id = match path_params(req.uri().path(), STATES_PATH)?.as_slice() {
+        [state_id, "validators"] => state_id.to_string(),
+        _ => {
+            return Err(ApiError::NotFound(
+                "Request path and/or method not found.".to_owned(),
+            ))
+        }
+    };
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    let request = serde_json::from_slice::<ValidatorStatusRequest>(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into ValidatorStatusRequest: {:?}",
+            e
+        ))
+    })?;
+
+    check_request_size(request.pubkeys.len() + request.indices.len(), max_results)?;
+
+    let (_root, mut state) = state_from_state_id(&beacon_chain, &state_id)?;
+    state.update_pubkey_cache()?;
+
+    let pubkey_indices = request
+        .pubkeys
+        .iter()
+        .map(|pubkey| {
+            state
+                .get_validator_index(pubkey)
+                .map_err(|e| ApiError::ServerError(format!("Unable to read pubkey cache: {:?}", e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let validators = pubkey_indices
+        .into_iter()
+        .chain(request.indices.into_iter().map(|i| Some(i as usize)))
+        .map(|validator_index| {
+            validator_status_by_index(&state, validator_index, &beacon_chain.spec)
+        })
+        .collect::<Vec<_>>();
+
+    response_builder?.body_no_ssz(&validators)
+}
+
+/// Returns the `ValidatorStatusResponse` for the validator with `validator_index` in `state`.
+///
+/// Returns an `Unknown` status if `validator_index` is `None` or is not in `state`.
+fn validator_status_by_index<E: EthSpec>(
+    state: &BeaconState<E>,
+    validator_index: Option<usize>,
+    spec: &ChainSpec,
+) -> ValidatorStatusResponse {
+    let validator = validator_index.and_then(|i| state.validators.get(i));
+    let status =
+        ValidatorStatus::from_validator(validator, state.current_epoch(), spec.far_future_epoch);
+
+    ValidatorStatusResponse {
+        pubkey: validator.map(|v| v.pubkey.clone()),
+        validator_index: validator.and(validator_index).map(|i| i as u64),
+        balance: validator
+            .and(validator_index)
+            .and_then(|i| state.balances.get(i).copied()),
+        status,
+        validator: validator.cloned(),
+    }
+}
+
 /// Returns either the state given by `state_root_opt`, or the canonical head state if it is
-/// `None`.
+/// `None`, along with its root.
 fn get_state_from_root_opt<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     state_root_opt: Option<Hash256>,
-) -> Result<BeaconState<T::EthSpec>, ApiError> {
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
     if let Some(state_root) = state_root_opt {
-        beacon_chain
+        let state = beacon_chain
             .get_state(&state_root, None)
             .map_err(|e| {
                 ApiError::ServerError(format!(
@@ -257,9 +390,13 @@ fn get_state_from_root_opt<T: BeaconChainTypes>(
                     state_root, e
                 ))
             })?
-            .ok_or_else(|| ApiError::NotFound(format!("No state exists with root: {}", state_root)))
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("No state exists with root: {}", state_root))
+            })?;
+        Ok((state_root, state))
     } else {
-        Ok(beacon_chain.head()?.beacon_state)
+        let head = beacon_chain.head()?;
+        Ok((head.beacon_state_root, head.beacon_state))
     }
 }
 
@@ -270,7 +407,7 @@ fn validator_responses_by_pubkey<T: BeaconChainTypes>(
     state_root_opt: Option<Hash256>,
     validator_pubkeys: Vec<PublicKeyBytes>,
 ) -> Result<Vec<ValidatorResponse>, ApiError> {
-    let mut state = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
+    let (_state_root, mut state) = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
     state.update_pubkey_cache()?;
 
     validator_pubkeys
@@ -319,16 +456,21 @@ fn validator_response_by_pubkey<E: EthSpec>(
     }
 }
 
-/// HTTP handler
+/// HTTP handler to return the committees at the given `epoch`.
+///
+/// The committees are paginated with the `continuation_token` and `limit` query parameters.
 pub fn get_committees<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
 
     let epoch = query.epoch()?;
+    let pagination = query.pagination(max_results)?;
 
-    let mut state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    let (state_root, mut state) =
+        get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
 
     let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(|e| {
         ApiError::ServerError(format!("Failed to get state suitable for epoch: {:?}", e))
@@ -338,9 +480,85 @@ pub fn get_committees<T: BeaconChainTypes>(
         .build_committee_cache(relative_epoch, &beacon_chain.spec)
         .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
 
-    let committees = state
-        .get_beacon_committees_at_epoch(relative_epoch)
-        .map_err(|e| ApiError::ServerError(format!("Unable to get all committees: {:?}", e)))?
+    let (page, continuation_token) = pagination.paginate(
+        state_root,
+        state
+            .get_beacon_committees_at_epoch(relative_epoch)
+            .map_err(|e| ApiError::ServerError(format!("Unable to get all committees: {:?}", e)))?
+            .into_iter(),
+    )?;
+
+    let committees = page
+        .into_iter()
+        .map(|c| Committee {
+            slot: c.slot,
+            index: c.index,
+            committee: c.committee.to_vec(),
+        })
+        .collect::<Vec<_>>();
+
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body(&committees)
+}
+
+/// HTTP handler to return the committees of the state identified by the `state_id` in the path
+/// `/eth/v1/beacon/states/{state_id}/committees`.
+///
+/// The committees are read from the shuffling cache, where possible. They may be filtered with
+/// the `epoch` (defaults to the current epoch of the state), `index` and `slot` query parameters
+/// and paginated with the `continuation_token` and `limit` query parameters.
+pub fn get_state_committees<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
+) -> ApiResult {
+    let (state_root, mut state) = match path_params(req.uri().path(), STATES_PATH)?.as_slice() {
+        [state_id, "committees"] => state_from_state_id(&beacon_chain, state_id)?,
+        _ => {
+            return Err(ApiError::NotFound(
+                "Request path and/or method not found.".to_owned(),
+            ))
+        }
+    };
+
+    let query = UrlQuery::from_request(&req)?;
+
+    let epoch = match query.first_of_opt(&["epoch"]) {
+        Some((_key, value)) => parse_epoch(&value)?,
+        None => state.current_epoch(),
+    };
+    let index = query
+        .first_of_opt(&["index"])
+        .map(|(_key, value)| parse_committee_index(&value))
+        .transpose()?;
+    let slot = query
+        .first_of_opt(&["slot"])
+        .map(|(_key, value)| parse_slot(&value))
+        .transpose()?;
+    let pagination = query.pagination(max_results)?;
+
+    if RelativeEpoch::from_epoch(state.current_epoch(), epoch).is_err() {
+        return Err(ApiError::BadRequest(format!(
+            "Epoch {} is not the previous, current or next epoch of the state (epoch {})",
+            epoch,
+            state.current_epoch()
+        )));
+    }
+
+    let committee_cache = beacon_chain.committee_cache_for_state(&mut state, epoch)?;
+
+    let (page, continuation_token) = pagination.paginate(
+        state_root,
+        committee_cache
+            .get_all_beacon_committees()
+            .map_err(|e| ApiError::ServerError(format!("Unable to get all committees: {:?}", e)))?
+            .into_iter()
+            .filter(|c| slot.map_or(true, |slot| c.slot == slot))
+            .filter(|c| index.map_or(true, |index| c.index == index)),
+    )?;
+
+    let committees = page
         .into_iter()
         .map(|c| Committee {
             slot: c.slot,
@@ -349,7 +567,9 @@ pub fn get_committees<T: BeaconChainTypes>(
         })
         .collect::<Vec<_>>();
 
-    ResponseBuilder::new(&req)?.body(&committees)
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body(&committees)
 }
 
 /// HTTP handler to return a `BeaconState` at a given `root` or `slot`.
@@ -0,0 +1,145 @@
+//! Serves the latest finalized block and state, so that this node may act as a checkpoint sync
+//! source for other nodes.
+//!
+//! The finalized block and state are only read from the database once per finalized checkpoint
+//! and requests are rate limited, since each response is the size of an entire `BeaconState`.
+
+use crate::response_builder::ResponseBuilder;
+use crate::{ApiError, ApiResult};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use hyper::{Body, Request};
+use parking_lot::{Mutex, RwLock};
+use rest_types::CheckpointResponse;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use types::{EthSpec, Hash256};
+
+/// The period over which `CheckpointServer::requests_per_minute` is enforced.
+pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Caches the latest finalized block and state and limits the rate at which they are served.
+pub struct CheckpointServer<E: EthSpec> {
+    cached: RwLock<Option<Arc<CheckpointResponse<E>>>>,
+    recent_requests: Mutex<VecDeque<Instant>>,
+    requests_per_minute: usize,
+}
+
+impl<E: EthSpec> CheckpointServer<E> {
+    /// Creates a server which serves at most `requests_per_minute` checkpoints per minute. No
+    /// checkpoints are served if `requests_per_minute` is zero.
+    pub fn new(requests_per_minute: usize) -> Self {
+        Self {
+            cached: RwLock::new(None),
+            recent_requests: Mutex::new(VecDeque::with_capacity(requests_per_minute)),
+            requests_per_minute,
+        }
+    }
+
+    /// Records a request at `now`, returning `false` if it exceeds the rate limit.
+    fn try_acquire(&self, now: Instant) -> bool {
+        let mut recent_requests = self.recent_requests.lock();
+
+        while recent_requests
+            .front()
+            .map_or(false, |time| now.duration_since(*time) >= RATE_LIMIT_WINDOW)
+        {
+            recent_requests.pop_front();
+        }
+
+        if recent_requests.len() < self.requests_per_minute {
+            recent_requests.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the checkpoint for the finalized block at `block_root`, reading it from the
+    /// database if it is not already cached.
+    fn get_or_load<T: BeaconChainTypes<EthSpec = E>>(
+        &self,
+        beacon_chain: &BeaconChain<T>,
+        block_root: Hash256,
+    ) -> Result<Arc<CheckpointResponse<E>>, ApiError> {
+        if let Some(cached) = self.cached.read().as_ref() {
+            if cached.block_root == block_root {
+                return Ok(cached.clone());
+            }
+        }
+
+        let beacon_block = beacon_chain.store.get_block(&block_root)?.ok_or_else(|| {
+            ApiError::ServerError(format!("Missing finalized block {:?}", block_root))
+        })?;
+        let state_root = beacon_block.state_root();
+        let beacon_state = beacon_chain
+            .store
+            .get_state(&state_root, Some(beacon_block.slot()))?
+            .ok_or_else(|| {
+                ApiError::ServerError(format!("Missing finalized state {:?}", state_root))
+            })?;
+
+        let checkpoint = Arc::new(CheckpointResponse {
+            block_root,
+            beacon_block,
+            state_root,
+            beacon_state,
+        });
+        *self.cached.write() = Some(checkpoint.clone());
+
+        Ok(checkpoint)
+    }
+}
+
+/// HTTP handler to return the latest finalized block and its post-state.
+pub fn get_checkpoint<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    checkpoint_server: Arc<CheckpointServer<T::EthSpec>>,
+) -> ApiResult {
+    if !checkpoint_server.try_acquire(Instant::now()) {
+        return Err(ApiError::TooManyRequests(format!(
+            "Checkpoints are limited to {} requests per minute",
+            checkpoint_server.requests_per_minute
+        )));
+    }
+
+    // The finalized root is zero until the first finalization, in which case genesis is the
+    // latest finalized block.
+    let finalized_root = beacon_chain.head_info()?.finalized_checkpoint.root;
+    let block_root = if finalized_root == Hash256::zero() {
+        beacon_chain.genesis_block_root
+    } else {
+        finalized_root
+    };
+
+    let checkpoint = checkpoint_server.get_or_load(&beacon_chain, block_root)?;
+
+    ResponseBuilder::new(&req)?.body(checkpoint.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::MinimalEthSpec;
+
+    #[test]
+    fn rate_limit() {
+        let server = CheckpointServer::<MinimalEthSpec>::new(2);
+        let start = Instant::now();
+
+        assert!(server.try_acquire(start));
+        assert!(server.try_acquire(start + Duration::from_secs(1)));
+        assert!(!server.try_acquire(start + Duration::from_secs(2)));
+
+        // The first request leaves the window, freeing a single slot.
+        assert!(server.try_acquire(start + RATE_LIMIT_WINDOW));
+        assert!(!server.try_acquire(start + RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn zero_rate_limit_disables() {
+        let server = CheckpointServer::<MinimalEthSpec>::new(0);
+        assert!(!server.try_acquire(Instant::now()));
+    }
+}
@@ -0,0 +1,173 @@
+//! Provides transparent gzip/deflate compression of HTTP responses, as negotiated by the
+//! `Accept-Encoding` header of the request.
+
+use crate::ApiError;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Response};
+use std::io::Write;
+
+/// Responses smaller than this are not worth the CPU time or the extra headers required to
+/// compress them.
+pub const MIN_COMPRESSION_SIZE_BYTES: usize = 1_024;
+
+/// The compression schemes supported by the HTTP server.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentEncoding {
+    Gzip,
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Returns the encoding to use for a response to a request with the given `Accept-Encoding`
+    /// header, if any.
+    ///
+    /// Gzip is preferred over deflate when the client accepts both, regardless of the quality
+    /// values. Encodings with a quality value of zero are considered unacceptable, and `*` only
+    /// accepts the encodings which are not listed explicitly.
+    pub fn from_accept_encoding(accept_encoding: &str) -> Option<Self> {
+        // Each coding, with `true` if it is acceptable.
+        let codings = accept_encoding
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let coding = parts.next().filter(|coding| !coding.is_empty())?;
+                let rejected = parts
+                    .filter(|param| param.starts_with("q="))
+                    .any(|param| param[2..].parse::<f32>().map_or(false, |q| q == 0.0));
+
+                Some((coding.to_ascii_lowercase(), !rejected))
+            })
+            .collect::<Vec<_>>();
+
+        let is_accepted = |encoding: ContentEncoding| {
+            let find = |name: &str| {
+                codings
+                    .iter()
+                    .find(|(coding, _)| coding == name)
+                    .map(|(_, accepted)| *accepted)
+            };
+            find(encoding.as_str())
+                .or_else(|| find("*"))
+                .unwrap_or(false)
+        };
+
+        [ContentEncoding::Gzip, ContentEncoding::Deflate]
+            .iter()
+            .copied()
+            .find(|encoding| is_accepted(*encoding))
+    }
+
+    /// Returns the value of the `Content-Encoding` header for this encoding.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Deflate => "deflate",
+        }
+    }
+
+    /// Compresses `bytes` with this encoding.
+    pub fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        match self {
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(vec![], Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            ContentEncoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Compresses the body of `response` with `encoding`, setting the appropriate headers.
+///
+/// The response is returned unchanged if it is already encoded, its body is streamed (i.e., its
+/// size is not known up front, so compressing it would buffer the whole body) or its body is
+/// smaller than `MIN_COMPRESSION_SIZE_BYTES`.
+pub async fn compress_response(
+    response: Response<Body>,
+    encoding: ContentEncoding,
+) -> Result<Response<Body>, ApiError> {
+    if response.headers().contains_key(header::CONTENT_ENCODING)
+        || response.body().size_hint().exact().is_none()
+    {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to read response body: {:?}", e)))?;
+
+    if bytes.len() < MIN_COMPRESSION_SIZE_BYTES {
+        return Ok(Response::from_parts(parts, Body::from(bytes)));
+    }
+
+    let compressed = encoding
+        .encode(&bytes)
+        .map_err(|e| ApiError::ServerError(format!("Unable to compress response: {:?}", e)))?;
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    Ok(Response::from_parts(parts, Body::from(compressed)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    #[test]
+    fn from_accept_encoding() {
+        let get = ContentEncoding::from_accept_encoding;
+
+        assert_eq!(get("gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("deflate"), Some(ContentEncoding::Deflate));
+        assert_eq!(get("deflate, gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("deflate, gzip;q=0.5"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("deflate, gzip;q=0"), Some(ContentEncoding::Deflate));
+        assert_eq!(get("GZIP"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("*"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("gzip;q=0, *"), Some(ContentEncoding::Deflate));
+        assert_eq!(get("*, gzip;q=0"), Some(ContentEncoding::Deflate));
+        assert_eq!(get("gzip;q=0, deflate;q=0, *"), None);
+        assert_eq!(get("gzip, *;q=0"), Some(ContentEncoding::Gzip));
+        assert_eq!(get("*;q=0"), None);
+        assert_eq!(get("br"), None);
+        assert_eq!(get("identity"), None);
+        assert_eq!(get(""), None);
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let bytes = vec![42; MIN_COMPRESSION_SIZE_BYTES * 4];
+
+        let gzip = ContentEncoding::Gzip.encode(&bytes).unwrap();
+        assert!(gzip.len() < bytes.len());
+        let mut decoded = vec![];
+        GzDecoder::new(&gzip[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, bytes);
+
+        let deflate = ContentEncoding::Deflate.encode(&bytes).unwrap();
+        assert!(deflate.len() < bytes.len());
+        let mut decoded = vec![];
+        ZlibDecoder::new(&deflate[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, bytes);
+    }
+}
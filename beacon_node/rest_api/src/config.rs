@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::path::PathBuf;
 
 /// Defines the encoding for the API.
 #[derive(Clone, Serialize, Deserialize, Copy)]
@@ -38,6 +39,18 @@ pub struct Config {
     pub listen_address: Ipv4Addr,
     /// The port the REST API HTTP server will listen on.
     pub port: u16,
+    /// The maximum number of items returned in a single response (e.g., validators or
+    /// committees), or accepted in a single bulk request.
+    pub max_results: usize,
+    /// The maximum number of checkpoints (finalized block and state) served per minute. Zero
+    /// disables the checkpoint endpoint.
+    pub checkpoint_requests_per_minute: usize,
+    /// If set, requests to the `/validator` endpoints must present the token stored in this file,
+    /// which is generated if it does not exist.
+    pub auth_token_path: Option<PathBuf>,
+    /// Serve the API to a validator client in the same process, over a channel rather than a
+    /// socket. Independent of `enabled`.
+    pub local_api: bool,
 }
 
 impl Default for Config {
@@ -46,6 +59,10 @@ impl Default for Config {
             enabled: false,
             listen_address: Ipv4Addr::new(127, 0, 0, 1),
             port: 5052,
+            max_results: 16_384,
+            checkpoint_requests_per_minute: 10,
+            auth_token_path: None,
+            local_api: false,
         }
     }
 }
@@ -1,4 +1,6 @@
 use hyper::{Body, Response, StatusCode};
+use rest_types::{ErrorMessage, PublishRejection};
+use serde::Serialize;
 use std::error::Error as StdError;
 
 #[derive(PartialEq, Debug, Clone)]
@@ -9,8 +11,22 @@ pub enum ApiError {
     BadRequest(String),
     NotFound(String),
     UnsupportedType(String),
-    ImATeapot(String),       // Just in case.
+    PayloadTooLarge(String),
+    TooManyRequests(String),
+    Unauthorized(String),
+    ImATeapot(String),          // Just in case.
     ProcessingError(String), // A 202 error, for when a block/attestation cannot be processed, but still transmitted.
+    Rejected(PublishRejection), // A 202 error with a JSON body, for when a block/attestation is invalid.
+}
+
+/// The body of an `ApiError::Rejected` response, which is an `ErrorMessage` that also contains the
+/// fields of the `PublishRejection`.
+#[derive(Serialize)]
+struct RejectionMessage {
+    #[serde(flatten)]
+    error: ErrorMessage,
+    reason: String,
+    index: Option<usize>,
 }
 
 pub type ApiResult = Result<Response<Body>, ApiError>;
@@ -24,19 +40,70 @@ impl ApiError {
             ApiError::BadRequest(desc) => (StatusCode::BAD_REQUEST, desc),
             ApiError::NotFound(desc) => (StatusCode::NOT_FOUND, desc),
             ApiError::UnsupportedType(desc) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, desc),
+            ApiError::PayloadTooLarge(desc) => (StatusCode::PAYLOAD_TOO_LARGE, desc),
+            ApiError::TooManyRequests(desc) => (StatusCode::TOO_MANY_REQUESTS, desc),
+            ApiError::Unauthorized(desc) => (StatusCode::UNAUTHORIZED, desc),
             ApiError::ImATeapot(desc) => (StatusCode::IM_A_TEAPOT, desc),
             ApiError::ProcessingError(desc) => (StatusCode::ACCEPTED, desc),
+            ApiError::Rejected(rejection) => (StatusCode::ACCEPTED, rejection.message),
+        }
+    }
+
+    /// Returns the stable identifier of the class of failure, which is the `kind` of the
+    /// `ErrorMessage`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::MethodNotAllowed(_) => "method_not_allowed",
+            ApiError::ServerError(_) => "server_error",
+            ApiError::NotImplemented(_) => "not_implemented",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::UnsupportedType(_) => "unsupported_type",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::TooManyRequests(_) => "too_many_requests",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::ImATeapot(_) => "im_a_teapot",
+            ApiError::ProcessingError(_) => "processing_error",
+            ApiError::Rejected(_) => "rejected",
+        }
+    }
+
+    /// Returns the status code and the JSON-encoded `ErrorMessage` of the response.
+    pub fn json_body(self) -> (StatusCode, String) {
+        let rejection = match &self {
+            ApiError::Rejected(rejection) => Some((rejection.reason.clone(), rejection.index)),
+            _ => None,
+        };
+        let kind = self.kind().to_string();
+        let (status_code, message) = self.status_code();
+        let error = ErrorMessage {
+            code: status_code.as_u16(),
+            kind,
+            message,
+            stacktraces: vec![],
+        };
+        let body = match rejection {
+            Some((reason, index)) => serde_json::to_string(&RejectionMessage {
+                error,
+                reason,
+                index,
+            }),
+            None => serde_json::to_string(&error),
         }
+        .expect("Error messages should always serialize.");
+
+        (status_code, body)
     }
 }
 
+/// Responds with an `ErrorMessage` as JSON.
 impl Into<Response<Body>> for ApiError {
     fn into(self) -> Response<Body> {
-        let (status_code, desc) = self.status_code();
+        let (status_code, body) = self.json_body();
         Response::builder()
             .status(status_code)
-            .header("content-type", "text/plain; charset=utf-8")
-            .body(Body::from(desc))
+            .header("content-type", "application/json")
+            .body(Body::from(body))
             .expect("Response should always be created.")
     }
 }
@@ -83,3 +150,72 @@ impl std::fmt::Display for ApiError {
         write!(f, "{:?}: {:?}", status.0, status.1)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn parse(error: ApiError) -> (StatusCode, Value) {
+        let (status_code, body) = error.json_body();
+        (status_code, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn json_body() {
+        assert_eq!(
+            parse(ApiError::NotFound("unknown block".to_string())),
+            (
+                StatusCode::NOT_FOUND,
+                json!({
+                    "code": 404,
+                    "kind": "not_found",
+                    "message": "unknown block",
+                    "stacktraces": []
+                })
+            )
+        );
+
+        let rejection = PublishRejection {
+            reason: "past_slot".to_string(),
+            index: Some(3),
+            message: "too old".to_string(),
+        };
+        let (status_code, body) = ApiError::Rejected(rejection.clone()).json_body();
+        assert_eq!(status_code, StatusCode::ACCEPTED);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            json!({
+                "code": 202,
+                "kind": "rejected",
+                "message": "too old",
+                "stacktraces": [],
+                "reason": "past_slot",
+                "index": 3
+            })
+        );
+        // The body is both a `PublishRejection` and an `ErrorMessage`.
+        assert_eq!(
+            serde_json::from_str::<PublishRejection>(&body).unwrap(),
+            rejection
+        );
+        assert_eq!(
+            serde_json::from_str::<ErrorMessage>(&body).unwrap().message,
+            "too old"
+        );
+
+        // A processing error shares the status code of a rejection, but not the kind.
+        assert_eq!(
+            parse(ApiError::ProcessingError("not processed".to_string())),
+            (
+                StatusCode::ACCEPTED,
+                json!({
+                    "code": 202,
+                    "kind": "processing_error",
+                    "message": "not processed",
+                    "stacktraces": []
+                })
+            )
+        );
+    }
+}
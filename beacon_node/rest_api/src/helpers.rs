@@ -41,6 +41,37 @@ pub fn parse_committee_index(string: &str) -> Result<CommitteeIndex, ApiError> {
         .map_err(|e| ApiError::BadRequest(format!("Unable to parse committee index: {:?}", e)))
 }
 
+/// Returns the `/` separated segments of the `path` that follow `prefix`.
+///
+/// E.g., a `prefix` of `"/eth/v1/validator/duties/proposer/"` and a `path` of
+/// `"/eth/v1/validator/duties/proposer/42"` returns `["42"]`.
+pub fn path_params<'a>(path: &'a str, prefix: &str) -> Result<Vec<&'a str>, ApiError> {
+    if path.starts_with(prefix) {
+        Ok(path[prefix.len()..]
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect())
+    } else {
+        Err(ApiError::ServerError(format!(
+            "Path {} does not start with {}",
+            path, prefix
+        )))
+    }
+}
+
+/// Returns an error if a bulk request for `request_size` items exceeds the `max_results` allowed
+/// in a single request.
+pub fn check_request_size(request_size: usize, max_results: usize) -> Result<(), ApiError> {
+    if request_size > max_results {
+        Err(ApiError::BadRequest(format!(
+            "Requested {} items, the maximum is {}",
+            request_size, max_results
+        )))
+    } else {
+        Ok(())
+    }
+}
+
 /// Checks the provided request to ensure that the `content-type` header.
 ///
 /// The content-type header should either be omitted, in which case JSON is assumed, or it should
@@ -150,6 +181,99 @@ pub fn state_at_slot<T: BeaconChainTypes>(
     }
 }
 
+/// Returns a `BeaconState` and it's root from the `state_id` parameter of a request path.
+///
+/// The `state_id` may be `head`, `genesis`, `finalized`, `justified`, a slot (e.g., `"42"`) or a
+/// `0x` prefixed state root.
+pub fn state_from_state_id<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state_id: &str,
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
+    match state_id {
+        "head" => {
+            let head = beacon_chain.head()?;
+            Ok((head.beacon_state_root, head.beacon_state))
+        }
+        "genesis" => state_at_slot(beacon_chain, Slot::new(0)),
+        "finalized" | "justified" => {
+            let head_info = beacon_chain.head_info()?;
+            let checkpoint = if state_id == "finalized" {
+                head_info.finalized_checkpoint
+            } else {
+                head_info.current_justified_checkpoint
+            };
+
+            let block = beacon_chain.get_block(&checkpoint.root)?.ok_or_else(|| {
+                ApiError::NotFound(format!("Unable to find block {}", checkpoint.root))
+            })?;
+            let state_root = block.state_root();
+            let state = beacon_chain
+                .get_state(&state_root, Some(block.slot()))?
+                .ok_or_else(|| {
+                    ApiError::NotFound(format!("Unable to find state at root {}", state_root))
+                })?;
+
+            Ok((state_root, state))
+        }
+        root if root.starts_with("0x") => {
+            let root = parse_root(root)?;
+            let state = beacon_chain
+                .get_state(&root, None)?
+                .ok_or_else(|| ApiError::NotFound(format!("No state for root: {:?}", root)))?;
+
+            Ok((root, state))
+        }
+        slot => state_at_slot(beacon_chain, parse_slot(slot)?),
+    }
+}
+
+/// Returns the root of a `SignedBeaconBlock` from the `block_id` parameter of a request path.
+///
+/// The `block_id` may be `head`, `genesis`, `finalized`, `justified`, a slot (e.g., `"42"`) or a
+/// `0x` prefixed block root. Slots without a block are not found.
+pub fn block_root_from_block_id<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: &str,
+) -> Result<Hash256, ApiError> {
+    // Checkpoint roots are zero prior to the first justification or finalization.
+    let checkpoint_root = |root: Hash256| {
+        if root == Hash256::zero() {
+            beacon_chain.genesis_block_root
+        } else {
+            root
+        }
+    };
+
+    match block_id {
+        "head" => Ok(beacon_chain.head_info()?.block_root),
+        "genesis" => Ok(beacon_chain.genesis_block_root),
+        "finalized" => Ok(checkpoint_root(
+            beacon_chain.head_info()?.finalized_checkpoint.root,
+        )),
+        "justified" => Ok(checkpoint_root(
+            beacon_chain.head_info()?.current_justified_checkpoint.root,
+        )),
+        root if root.starts_with("0x") => parse_root(root),
+        slot => {
+            let slot = parse_slot(slot)?;
+            let root = block_root_at_slot(beacon_chain, slot)?
+                .ok_or_else(|| ApiError::NotFound(format!("No block at slot {}", slot)))?;
+
+            // A skip slot shares the root of the prior block.
+            let block_slot = beacon_chain
+                .store
+                .get_block(&root)?
+                .map(|block| block.slot())
+                .ok_or_else(|| ApiError::NotFound(format!("Unable to find block {}", root)))?;
+            if block_slot == slot {
+                Ok(root)
+            } else {
+                Err(ApiError::NotFound(format!("No block at slot {}", slot)))
+            }
+        }
+    }
+}
+
 /// Returns the root of the `BeaconState` in the canonical chain of `beacon_chain` at the given
 /// `slot`, if possible.
 ///
@@ -274,4 +398,30 @@ mod test {
         assert_eq!(parse_slot("10000000"), Ok(Slot::new(10_000_000)));
         assert!(parse_slot("cats").is_err());
     }
+
+    #[test]
+    fn path_params_works() {
+        let prefix = "/eth/v1/validator/duties/proposer/";
+
+        assert_eq!(
+            path_params("/eth/v1/validator/duties/proposer/42", prefix),
+            Ok(vec!["42"])
+        );
+        assert_eq!(
+            path_params("/eth/v1/validator/duties/proposer/42/", prefix),
+            Ok(vec!["42"])
+        );
+        assert_eq!(
+            path_params("/eth/v1/validator/duties/proposer/", prefix),
+            Ok(vec![])
+        );
+        assert!(path_params("/validator/duties", prefix).is_err());
+    }
+
+    #[test]
+    fn check_request_size_works() {
+        assert_eq!(check_request_size(0, 10), Ok(()));
+        assert_eq!(check_request_size(10, 10), Ok(()));
+        assert!(check_request_size(11, 10).is_err());
+    }
 }
@@ -5,7 +5,10 @@ extern crate lazy_static;
 extern crate network as client_network;
 
 mod advanced;
+mod auth;
 mod beacon;
+mod checkpoint;
+mod compression;
 pub mod config;
 mod consensus;
 mod error;
@@ -20,16 +23,21 @@ mod spec;
 mod url_query;
 mod validator;
 
+use auth::ApiToken;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use checkpoint::CheckpointServer;
 use client_network::NetworkMessage;
 pub use config::ApiEncodingFormat;
 use error::{ApiError, ApiResult};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
-use futures::future::TryFutureExt;
+use futures::future::{self, FutureExt, TryFutureExt};
+use futures::StreamExt;
 use hyper::server::conn::AddrStream;
 use hyper::service::{make_service_fn, service_fn};
-use hyper::{Body, Request, Server};
+use hyper::{Body, Request, Response, Server};
+use logging::LogControl;
+use rest_types::LocalRequest;
 use slog::{info, warn};
 use std::net::SocketAddr;
 use std::ops::Deref;
@@ -38,8 +46,10 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot};
 use url_query::UrlQuery;
 
+pub use crate::auth::API_TOKEN_FILENAME;
 pub use crate::helpers::parse_pubkey_bytes;
 pub use config::Config;
+pub use rest_types::{local_request_channel, LocalRequestReceiver, LocalRequestSender};
 
 pub type NetworkChannel<T> = mpsc::UnboundedSender<NetworkMessage<T>>;
 
@@ -58,9 +68,25 @@ pub fn start_server<T: BeaconChainTypes>(
     freezer_db_path: PathBuf,
     eth2_config: Eth2Config,
     log: slog::Logger,
-) -> Result<(oneshot::Sender<()>, SocketAddr), hyper::Error> {
+    log_control: LogControl,
+) -> Result<(oneshot::Sender<()>, SocketAddr), String> {
     let inner_log = log.clone();
     let eth2_config = Arc::new(eth2_config);
+    let max_results = config.max_results;
+    let checkpoint_server = Arc::new(CheckpointServer::new(config.checkpoint_requests_per_minute));
+    let auth_token = config
+        .auth_token_path
+        .as_ref()
+        .map(|path| {
+            let token = ApiToken::load_or_generate(path)?;
+            info!(
+                log,
+                "HTTP API authentication enabled";
+                "token_path" => format!("{:?}", path),
+            );
+            Ok::<_, String>(Arc::new(token))
+        })
+        .transpose()?;
 
     // Define the function that will build the request handler.
     let make_service = make_service_fn(move |_socket: &AddrStream| {
@@ -71,6 +97,9 @@ pub fn start_server<T: BeaconChainTypes>(
         let network_channel = network_info.network_chan.clone();
         let db_path = db_path.clone();
         let freezer_db_path = freezer_db_path.clone();
+        let checkpoint_server = checkpoint_server.clone();
+        let auth_token = auth_token.clone();
+        let log_control = log_control.clone();
 
         async move {
             Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
@@ -83,6 +112,10 @@ pub fn start_server<T: BeaconChainTypes>(
                     log.clone(),
                     db_path.clone(),
                     freezer_db_path.clone(),
+                    max_results,
+                    checkpoint_server.clone(),
+                    auth_token.clone(),
+                    log_control.clone(),
                 )
             }))
         }
@@ -130,6 +163,82 @@ pub fn start_server<T: BeaconChainTypes>(
     Ok((exit_signal, actual_listen_addr))
 }
 
+/// Serves the API to the requests received on `requests` (e.g., from a validator client in the
+/// same process), until the returned channel is triggered or dropped.
+///
+/// These requests do not need to present the authentication token of `config`.
+// Allowing more than 7 arguments.
+#[allow(clippy::too_many_arguments)]
+pub fn serve_local_requests<T: BeaconChainTypes>(
+    config: &Config,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_info: NetworkInfo<T>,
+    db_path: PathBuf,
+    freezer_db_path: PathBuf,
+    eth2_config: Eth2Config,
+    log: slog::Logger,
+    log_control: LogControl,
+    requests: LocalRequestReceiver,
+) -> oneshot::Sender<()> {
+    let inner_log = log.clone();
+    let eth2_config = Arc::new(eth2_config);
+    let max_results = config.max_results;
+    let checkpoint_server = Arc::new(CheckpointServer::new(config.checkpoint_requests_per_minute));
+
+    let serve = requests.for_each_concurrent(None, move |local_request: LocalRequest| {
+        let LocalRequest { request, response } = local_request;
+        let (parts, body) = request.into_parts();
+        let route = router::route(
+            Request::from_parts(parts, Body::from(body)),
+            beacon_chain.clone(),
+            network_info.network_globals.clone(),
+            network_info.network_chan.clone(),
+            eth2_config.clone(),
+            inner_log.clone(),
+            db_path.clone(),
+            freezer_db_path.clone(),
+            max_results,
+            checkpoint_server.clone(),
+            None,
+            log_control.clone(),
+        );
+        let log = inner_log.clone();
+
+        async move {
+            let result = match route.await {
+                Ok(routed) => {
+                    let (parts, body) = routed.into_parts();
+                    hyper::body::to_bytes(body)
+                        .await
+                        .map(|bytes| Response::from_parts(parts, bytes.to_vec()))
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                // The requester may have given up waiting for the response.
+                Ok(routed) => drop(response.send(routed)),
+                Err(e) => warn!(
+                    log,
+                    "Unable to serve in-process API request";
+                    "error" => format!("{:?}", e)
+                ),
+            }
+        }
+    });
+
+    // Build a channel to stop serving requests.
+    let (exit_signal, exit) = oneshot::channel::<()>();
+    let inner_log = log.clone();
+    tokio::spawn(future::select(Box::pin(serve), exit).map(move |_| {
+        info!(inner_log, "In-process HTTP API shutdown");
+    }));
+
+    info!(log, "In-process HTTP API started");
+
+    exit_signal
+}
+
 #[derive(Clone)]
 pub struct DBPath(PathBuf);
 
@@ -1,12 +1,46 @@
 //! This contains a collection of lighthouse specific HTTP endpoints.
 
+use crate::helpers::{block_root_from_block_id, check_content_type_for_json, path_params};
 use crate::response_builder::ResponseBuilder;
-use crate::ApiResult;
-use eth2_libp2p::{NetworkGlobals, PeerInfo};
-use hyper::{Body, Request};
+use crate::{ApiError, ApiResult, NetworkChannel, UrlQuery};
+use beacon_chain::{BeaconChain, BeaconChainTypes, ChainSegmentResult};
+use eth2_libp2p::types::{ByteCount, TrafficStats};
+use eth2_libp2p::{Enr, EnrExt, NetworkGlobals, PeerId, PeerInfo};
+use hyper::body::{Bytes, HttpBody, Sender};
+use hyper::{header, Body, Request};
+use integer_sqrt::IntegerSquareRoot;
+use logging::{level_name, parse_level, LogControl};
+use network::NetworkMessage;
+use rest_types::{
+    ImportBlocksResponse, PeeringNode, PeeringResponse, PeeringUpdateRequest, StakingEstimate,
+};
 use serde::Serialize;
+use slog::{error, info, Logger};
+use ssz::{Decode, Encode};
+use state_processing::per_epoch_processing::ValidatorStatuses;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
 use std::sync::Arc;
-use types::EthSpec;
+use std::time::Duration;
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Unsigned};
+
+/// The prefix of the path `/lighthouse/beacon/blocks/{block_id}/ssz`.
+pub const BLOCKS_PATH: &str = "/lighthouse/beacon/blocks/";
+
+/// The largest request body accepted by `post_blocks_ssz`, which bounds the memory used by each
+/// import.
+pub const MAX_IMPORT_BLOCKS_BYTES: usize = 256 * 1024 * 1024;
+
+/// The longest time for which `post_log_capture` collects logs.
+pub const MAX_LOG_CAPTURE_SECONDS: u64 = 300;
+
+/// The number of milliseconds in a year of 365.25 days.
+const MILLISECONDS_PER_YEAR: u64 = 31_557_600_000;
+
+/// The number of epochs, with ideal finality, from a deposit being processed until the epoch in
+/// which the validator became eligible for activation is finalized.
+const ELIGIBILITY_FINALIZATION_EPOCHS: u64 = 3;
 
 /// The syncing state of the beacon node.
 pub fn syncing<T: EthSpec>(
@@ -47,6 +81,158 @@ pub fn connected_peers<T: EthSpec>(
     ResponseBuilder::new(&req)?.body_no_ssz(&peers)
 }
 
+/// Returns the bytes transferred per gossip topic, RPC protocol and known peer.
+pub fn traffic<T: EthSpec>(
+    req: Request<Body>,
+    network_globals: Arc<NetworkGlobals<T>>,
+) -> ApiResult {
+    let peers = network_globals
+        .peers
+        .read()
+        .peers()
+        .map(|(peer_id, peer_info)| (peer_id.to_string(), peer_info.traffic.load()))
+        .collect();
+    let traffic = Traffic {
+        totals: network_globals.traffic.stats(),
+        peers,
+    };
+    ResponseBuilder::new(&req)?.body_no_ssz(&traffic)
+}
+
+/// Returns the journaled head and finalization events after the `since` cursor, or all journaled
+/// events if `since` is not supplied.
+pub fn get_events<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let journal = beacon_chain.event_journal.as_ref().ok_or_else(|| {
+        ApiError::NotFound("The event journal is not enabled on this node.".to_string())
+    })?;
+
+    let since = UrlQuery::from_request(&req)?
+        .first_of_opt(&["since"])
+        .map(|(_key, value)| {
+            value
+                .parse::<u64>()
+                .map_err(|e| ApiError::BadRequest(format!("Invalid since: {:?}", e)))
+        })
+        .transpose()?;
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&journal.events_since(since))
+}
+
+/// Returns the most recent blocks and attestations which were not published because a conflicting
+/// message had already been published through this node for the same validator.
+pub fn get_refused_publications<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    ResponseBuilder::new(&req)?.body_no_ssz(&beacon_chain.own_publications.refused())
+}
+
+/// Returns unused memory held by the allocator to the operating system, so that whether memory
+/// growth is caused by the allocator may be observed.
+pub fn post_malloc_purge(req: Request<Body>) -> ApiResult {
+    malloc_utils::purge().map_err(ApiError::ServerError)?;
+    ResponseBuilder::new(&req)?.body_no_ssz(&())
+}
+
+/// Information returned by `get_log_levels`.
+#[derive(Clone, Debug, Serialize)]
+struct LogLevels {
+    /// The level of modules without an override.
+    default: &'static str,
+    /// The level of each module with an override, keyed by module path prefix.
+    modules: BTreeMap<String, &'static str>,
+}
+
+/// Returns the current log levels.
+pub fn get_log_levels(req: Request<Body>, log_control: LogControl) -> ApiResult {
+    let levels = LogLevels {
+        default: level_name(log_control.default_level()),
+        modules: log_control
+            .module_levels()
+            .into_iter()
+            .map(|(module, level)| (module, level_name(level)))
+            .collect(),
+    };
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&levels)
+}
+
+/// Sets the log level of `module` (or of all modules without an override, if `module` is absent)
+/// to `level`, returning the new log levels. A `level` of `default` removes the override of
+/// `module`.
+pub fn post_log_levels(req: Request<Body>, log_control: LogControl, log: Logger) -> ApiResult {
+    let (_, level) = UrlQuery::from_request(&req)?.first_of(&["level"])?;
+    let module = UrlQuery::from_request(&req)?
+        .first_of_opt(&["module"])
+        .map(|(_, module)| module);
+
+    let parse = |level: &str| parse_level(level).map_err(ApiError::BadRequest);
+    match (&module, level.as_str()) {
+        (Some(module), "default") => log_control.set_module_level(module.clone(), None),
+        (Some(module), level) => log_control.set_module_level(module.clone(), Some(parse(level)?)),
+        (None, "default") => {
+            return Err(ApiError::BadRequest(
+                "The default level requires a module".to_string(),
+            ))
+        }
+        (None, level) => log_control.set_default_level(parse(level)?),
+    }
+
+    info!(
+        log,
+        "Log level changed";
+        "module" => module.as_deref().unwrap_or("all"),
+        "level" => level,
+    );
+
+    get_log_levels(req, log_control)
+}
+
+/// Collects the logs of `module` at `level` (`debug` by default) or above for `seconds`, then
+/// returns them as text, one record per line. What is logged elsewhere is unaffected.
+pub async fn post_log_capture(req: Request<Body>, log_control: LogControl) -> ApiResult {
+    let (_, module) = UrlQuery::from_request(&req)?.first_of(&["module"])?;
+    let (_, seconds) = UrlQuery::from_request(&req)?.first_of(&["seconds"])?;
+    let level = UrlQuery::from_request(&req)?
+        .first_of_opt(&["level"])
+        .map_or(Ok(slog::Level::Debug), |(_, level)| parse_level(&level))
+        .map_err(ApiError::BadRequest)?;
+
+    let seconds = seconds
+        .parse::<u64>()
+        .ok()
+        .filter(|seconds| (1..=MAX_LOG_CAPTURE_SECONDS).contains(seconds))
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "seconds must be between 1 and {}",
+                MAX_LOG_CAPTURE_SECONDS
+            ))
+        })?;
+
+    let response_builder = ResponseBuilder::new(&req)?;
+
+    let capture = log_control.start_capture(module, level);
+    tokio::time::delay_for(Duration::from_secs(seconds)).await;
+
+    let mut text = capture.lines().join("\n");
+    text.push('\n');
+
+    response_builder.body_text(text)
+}
+
+/// Information returned by `traffic`.
+#[derive(Clone, Debug, Serialize)]
+struct Traffic {
+    /// The bytes transferred per gossip topic and RPC protocol.
+    #[serde(flatten)]
+    totals: TrafficStats,
+    /// The bytes transferred with each known peer, keyed by `PeerId`.
+    peers: HashMap<String, ByteCount>,
+}
+
 /// Information returned by `peers` and `connected_peers`.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -56,3 +242,457 @@ struct Peer<T: EthSpec> {
     /// The PeerInfo associated with the peer.
     peer_info: PeerInfo<T>,
 }
+
+/// Returns the boot nodes and trusted peers of the node.
+pub fn get_peering<T: EthSpec>(
+    req: Request<Body>,
+    network_globals: Arc<NetworkGlobals<T>>,
+) -> ApiResult {
+    let to_nodes = |enrs: &[Enr]| {
+        enrs.iter()
+            .map(|enr| PeeringNode {
+                peer_id: enr.peer_id().to_string(),
+                enr: enr.to_base64(),
+            })
+            .collect()
+    };
+
+    let response = PeeringResponse {
+        boot_nodes: to_nodes(&network_globals.boot_nodes.read()),
+        trusted_peers: to_nodes(&network_globals.trusted_peers.read()),
+    };
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&response)
+}
+
+/// Adds or removes boot nodes and trusted peers at runtime, optionally restarting peer discovery.
+///
+/// The request is validated in full before any change is made.
+pub async fn post_peering<T: EthSpec>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    let request: PeeringUpdateRequest = serde_json::from_slice(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into PeeringUpdateRequest: {:?}",
+            e
+        ))
+    })?;
+
+    let parse_enrs = |enrs: &[String]| {
+        enrs.iter()
+            .map(|enr| {
+                Enr::from_str(enr)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid ENR {}: {:?}", enr, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let parse_peer_ids = |peer_ids: &[String]| {
+        peer_ids
+            .iter()
+            .map(|peer_id| {
+                PeerId::from_str(peer_id).map_err(|e| {
+                    ApiError::BadRequest(format!("Invalid peer id {}: {:?}", peer_id, e))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let message = NetworkMessage::UpdatePeering {
+        add_boot_nodes: parse_enrs(&request.add_boot_nodes)?,
+        remove_boot_nodes: parse_peer_ids(&request.remove_boot_nodes)?,
+        add_trusted_peers: parse_enrs(&request.add_trusted_peers)?,
+        remove_trusted_peers: parse_peer_ids(&request.remove_trusted_peers)?,
+        rediscover: request.rediscover,
+    };
+
+    network_chan.send(message).map_err(|e| {
+        ApiError::ServerError(format!("Unable to send peering update to network: {:?}", e))
+    })?;
+
+    response_builder?.body_no_ssz(&())
+}
+
+/// HTTP handler to return the raw SSZ bytes of the `SignedBeaconBlock` identified by the path
+/// `/lighthouse/beacon/blocks/{block_id}/ssz`.
+///
+/// If the `count` query parameter is supplied, a tar archive is returned instead, containing one
+/// SSZ file for each canonical block in the `count` slots starting at the identified block.
+///
+/// The archive is streamed, one block at a time, from the blocking thread pool of the runtime, so
+/// the number of concurrent archives is bounded by the size of the pool. Since the response
+/// headers have already been sent, an error while reading the blocks is logged and aborts the
+/// response, instead of returning an error response.
+pub fn get_block_ssz<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
+    log: Logger,
+) -> ApiResult {
+    let block_root = match path_params(req.uri().path(), BLOCKS_PATH)?.as_slice() {
+        [block_id, "ssz"] => block_root_from_block_id(&beacon_chain, block_id)?,
+        _ => {
+            return Err(ApiError::NotFound(
+                "Request path and/or method not found.".to_owned(),
+            ))
+        }
+    };
+
+    let block = get_block(&beacon_chain, &block_root)?;
+
+    let count = UrlQuery::from_request(&req)?
+        .first_of_opt(&["count"])
+        .map(|(_key, value)| {
+            value
+                .parse::<usize>()
+                .map_err(|e| ApiError::BadRequest(format!("Invalid count: {:?}", e)))
+        })
+        .transpose()?;
+
+    if let Some(count) = count {
+        if count == 0 || count > max_results {
+            return Err(ApiError::BadRequest(format!(
+                "The count must be between 1 and {}",
+                max_results
+            )));
+        }
+
+        let start_slot = block.slot();
+        let end_slot = start_slot + count as u64;
+
+        let response_builder = ResponseBuilder::new(&req)?;
+        let (sender, body) = Body::channel();
+
+        tokio::task::spawn_blocking(move || {
+            let mut archive = tar::Builder::new(BodyWriter(Some(sender)));
+
+            let result = beacon_chain
+                .with_canonical_blocks(start_slot, end_slot, |root, block| {
+                    let bytes = block.as_ssz_bytes();
+                    let mut header = tar::Header::new_ustar();
+                    header.set_size(bytes.len() as u64);
+                    header.set_mode(0o644);
+                    header.set_cksum();
+                    archive
+                        .append_data(
+                            &mut header,
+                            format!("{:010}_{:?}.ssz", block.slot().as_u64(), root),
+                            bytes.as_slice(),
+                        )
+                        .map_err(|e| {
+                            ApiError::ServerError(format!("Unable to write archive: {:?}", e))
+                        })
+                })
+                .and_then(|()| {
+                    archive.finish().map_err(|e| {
+                        ApiError::ServerError(format!("Unable to write archive: {:?}", e))
+                    })
+                });
+
+            if let Err(e) = result {
+                error!(
+                    log,
+                    "Unable to stream block archive";
+                    "error" => format!("{:?}", e),
+                    "start_slot" => start_slot.as_u64(),
+                );
+                archive.get_mut().abort();
+            }
+        });
+
+        response_builder.body_stream("application/x-tar", body)
+    } else {
+        ResponseBuilder::new(&req)?.body_bytes("application/ssz", block.as_ssz_bytes())
+    }
+}
+
+/// Writes to a streamed response body, blocking until the client has received the previous chunk.
+struct BodyWriter(Option<Sender>);
+
+impl BodyWriter {
+    /// Ends the response with an error, so the client does not mistake it for a complete one.
+    fn abort(&mut self) {
+        if let Some(sender) = self.0.take() {
+            sender.abort();
+        }
+    }
+}
+
+impl Write for BodyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let sender = self
+            .0
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "The response was aborted"))?;
+
+        futures::executor::block_on(sender.send_data(Bytes::copy_from_slice(buf)))
+            .map(|()| buf.len())
+            .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// HTTP handler to import blocks into the chain from their SSZ bytes, with full verification.
+///
+/// Useful for recovering a node which has no peers (e.g., across an airgap) from a trusted archive,
+/// such as one produced by `get_block_ssz`. The body is either a single `SignedBeaconBlock` with
+/// the content type `application/ssz`, or a tar archive of them with the content type
+/// `application/x-tar`. The blocks are imported in ascending slot order and must form a chain
+/// which descends from a known block.
+///
+/// Bodies larger than `MAX_IMPORT_BLOCKS_BYTES` are rejected.
+pub async fn post_blocks_ssz<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    log: Logger,
+) -> ApiResult {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.parse::<usize>().ok());
+    let response_builder = ResponseBuilder::new(&req);
+
+    let too_large = || {
+        ApiError::PayloadTooLarge(format!(
+            "The request body exceeds the limit of {} bytes",
+            MAX_IMPORT_BLOCKS_BYTES
+        ))
+    };
+
+    // Reject an oversized body before reading any of it, or as soon as it exceeds the limit if
+    // its length is not declared up-front.
+    if content_length.map_or(false, |len| len > MAX_IMPORT_BLOCKS_BYTES) {
+        return Err(too_large());
+    }
+
+    let mut body = req.into_body();
+    let mut chunks = Vec::with_capacity(content_length.unwrap_or(0));
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk
+            .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+        if chunks.len() + chunk.len() > MAX_IMPORT_BLOCKS_BYTES {
+            return Err(too_large());
+        }
+        chunks.extend_from_slice(&chunk);
+    }
+
+    // Decoding and importing the blocks is expensive, so it is done on the blocking thread pool
+    // rather than on the thread which is serving requests.
+    let (num_blocks, response) =
+        tokio::task::spawn_blocking(move || import_blocks_ssz(&beacon_chain, content_type, chunks))
+            .await
+            .map_err(|e| ApiError::ServerError(format!("Unable to import blocks: {:?}", e)))??;
+
+    info!(
+        log,
+        "Imported blocks via HTTP API";
+        "supplied" => num_blocks,
+        "imported" => response.imported_blocks,
+        "error" => response.error.as_ref().map(String::as_str).unwrap_or("none"),
+        "head_block" => format!("{}", response.head_block_root),
+    );
+
+    response_builder?.body_no_ssz(&response)
+}
+
+/// Decodes the blocks in `body` as per `post_blocks_ssz` and imports them into `beacon_chain`,
+/// returning the number of blocks supplied along with the outcome of the import.
+fn import_blocks_ssz<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+) -> Result<(usize, ImportBlocksResponse), ApiError> {
+    let decode_block = |bytes: &[u8]| {
+        SignedBeaconBlock::from_ssz_bytes(bytes)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid SignedBeaconBlock: {:?}", e)))
+    };
+
+    let mut blocks = match content_type.as_deref() {
+        Some("application/ssz") => vec![decode_block(&body)?],
+        Some("application/x-tar") => {
+            let mut archive = tar::Archive::new(body.as_slice());
+            let entries = archive
+                .entries()
+                .map_err(|e| ApiError::BadRequest(format!("Invalid archive: {:?}", e)))?;
+
+            let mut blocks = vec![];
+            for entry in entries {
+                let mut bytes = vec![];
+                entry
+                    .and_then(|mut entry| entry.read_to_end(&mut bytes))
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid archive: {:?}", e)))?;
+                blocks.push(decode_block(&bytes)?);
+            }
+            blocks
+        }
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "The provided content-type {:?} is not available, this endpoint only supports \
+                 application/ssz and application/x-tar.",
+                other
+            )))
+        }
+    };
+
+    blocks.sort_by_key(|block| block.slot());
+    let num_blocks = blocks.len();
+
+    let (imported_blocks, error) = match beacon_chain.process_chain_segment(blocks) {
+        ChainSegmentResult::Successful { imported_blocks } => (imported_blocks, None),
+        ChainSegmentResult::Failed {
+            imported_blocks,
+            error,
+        } => (imported_blocks, Some(format!("{:?}", error))),
+    };
+
+    beacon_chain
+        .fork_choice()
+        .map_err(|e| ApiError::ServerError(format!("Unable to run fork choice: {:?}", e)))?;
+
+    let head_block_root = beacon_chain.head_info()?.block_root;
+
+    Ok((
+        num_blocks,
+        ImportBlocksResponse {
+            imported_blocks,
+            error,
+            head_block_root,
+        },
+    ))
+}
+
+/// HTTP handler to estimate the rewards and activation delay of a new validator from the head
+/// state.
+///
+/// If the `cost_gwei` query parameter is supplied (e.g., the cost of the deposit transaction and
+/// of running the validator), the time taken for the rewards to exceed it is also estimated.
+pub fn get_staking_estimate<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let cost_gwei = UrlQuery::from_request(&req)?
+        .first_of_opt(&["cost_gwei"])
+        .map(|(_key, value)| {
+            value
+                .parse::<u64>()
+                .map_err(|e| ApiError::BadRequest(format!("Invalid cost_gwei: {:?}", e)))
+        })
+        .transpose()?;
+
+    let state = beacon_chain.head()?.beacon_state;
+    let estimate = staking_estimate(&state, &beacon_chain.spec, cost_gwei)?;
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&estimate)
+}
+
+/// Estimates the rewards of a validator with the maximum effective balance which attests
+/// perfectly, assuming the active balance and the participation of other validators in the
+/// previous epoch of `state` remain the same.
+fn staking_estimate<E: EthSpec>(
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+    cost_gwei: Option<u64>,
+) -> Result<StakingEstimate, ApiError> {
+    let mut validator_statuses = ValidatorStatuses::new(state, spec)?;
+    validator_statuses.process_attestations(state, spec)?;
+    let balances = &validator_statuses.total_balances;
+
+    let total_active_balance = balances.current_epoch();
+    let participation_rate = if balances.previous_epoch() > 0 {
+        balances.previous_epoch_target_attesters() as f64 / balances.previous_epoch() as f64
+    } else {
+        0.
+    };
+
+    // The source, target and head rewards are proportional to participation, whilst the
+    // inclusion reward is not (assuming inclusion in the next slot). Each validator also
+    // receives, on average, an equal share of the proposer rewards.
+    let base_reward = spec.max_effective_balance * spec.base_reward_factor
+        / std::cmp::max(total_active_balance.integer_sqrt(), 1)
+        / spec.base_rewards_per_epoch;
+    let proposer_reward = base_reward / spec.proposer_reward_quotient;
+    let epoch_reward = 3. * base_reward as f64 * participation_rate
+        + (base_reward - proposer_reward) as f64
+        + proposer_reward as f64 * participation_rate;
+
+    let epoch_millis = spec.milliseconds_per_slot * E::slots_per_epoch();
+    let annual_reward = epoch_reward * MILLISECONDS_PER_YEAR as f64 / epoch_millis as f64;
+
+    let activation_queue_length = state
+        .validators
+        .iter()
+        .filter(|validator| {
+            validator.activation_epoch == spec.far_future_epoch
+                && (validator.activation_eligibility_epoch != spec.far_future_epoch
+                    || validator.is_eligible_for_activation_queue(spec))
+        })
+        .count() as u64;
+    let churn_limit = state.get_churn_limit(spec)?;
+
+    // The deposit must be followed by the eth1 chain and voted in, then wait behind any deposits
+    // which are yet to be included in blocks.
+    let deposit_inclusion_millis = spec.eth1_follow_distance * spec.seconds_per_eth1_block * 1000
+        + E::slots_per_eth1_voting_period() as u64 * spec.milliseconds_per_slot
+        + state.get_outstanding_deposit_len()? / E::MaxDeposits::to_u64()
+            * spec.milliseconds_per_slot;
+    let activation_epochs = ELIGIBILITY_FINALIZATION_EPOCHS
+        + activation_queue_length / std::cmp::max(churn_limit, 1)
+        + 1
+        + spec.max_seed_lookahead.as_u64();
+    let activation_delay_millis = deposit_inclusion_millis + activation_epochs * epoch_millis;
+
+    let break_even_seconds = cost_gwei.and_then(|cost| {
+        if epoch_reward > 0. {
+            let earning_epochs = (cost as f64 / epoch_reward).ceil() as u64;
+            Some((activation_delay_millis + earning_epochs * epoch_millis) / 1000)
+        } else {
+            None
+        }
+    });
+
+    Ok(StakingEstimate {
+        epoch: state.current_epoch(),
+        active_validator_count: validator_statuses
+            .statuses
+            .iter()
+            .filter(|status| status.is_active_in_current_epoch)
+            .count(),
+        total_active_balance_gwei: total_active_balance,
+        participation_rate,
+        deposit_gwei: spec.max_effective_balance,
+        epoch_reward_gwei: epoch_reward as u64,
+        annual_reward_gwei: annual_reward as u64,
+        annual_reward_rate: annual_reward / spec.max_effective_balance as f64,
+        activation_queue_length,
+        churn_limit,
+        activation_delay_seconds: activation_delay_millis / 1000,
+        break_even_seconds,
+    })
+}
+
+fn get_block<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: &Hash256,
+) -> Result<SignedBeaconBlock<T::EthSpec>, ApiError> {
+    beacon_chain
+        .store
+        .get_block(root)?
+        .ok_or_else(|| ApiError::NotFound(format!("Unable to find SignedBeaconBlock {:?}", root)))
+}
@@ -104,6 +104,7 @@ pub fn get_prometheus<T: BeaconChainTypes>(
     slot_clock::scrape_for_metrics::<T::EthSpec, T::SlotClock>(&beacon_chain.slot_clock);
     store::scrape_for_metrics(&db_path, &freezer_db_path);
     beacon_chain::scrape_for_metrics(&beacon_chain);
+    malloc_utils::scrape_allocator_metrics();
 
     if let Ok(health) = Health::observe() {
         set_gauge(&PROCESS_NUM_THREADS, health.pid_num_threads as i64);
@@ -2,11 +2,13 @@ use super::{ApiError, ApiResult};
 use crate::config::ApiEncodingFormat;
 use hyper::header;
 use hyper::{Body, Request, Response, StatusCode};
+use rest_types::CONTINUATION_TOKEN_HEADER;
 use serde::Serialize;
 use ssz::Encode;
 
 pub struct ResponseBuilder {
     encoding: ApiEncodingFormat,
+    continuation_token: Option<String>,
 }
 
 impl ResponseBuilder {
@@ -25,13 +27,32 @@ impl ResponseBuilder {
 
         // JSON is our default encoding, unless something else is requested.
         let encoding = ApiEncodingFormat::from(accept_header.as_str());
-        Ok(Self { encoding })
+        Ok(Self {
+            encoding,
+            continuation_token: None,
+        })
+    }
+
+    /// Adds the `CONTINUATION_TOKEN_HEADER` to the response, if `token` is `Some`.
+    pub fn continuation_token(mut self, token: Option<String>) -> Self {
+        self.continuation_token = token;
+        self
+    }
+
+    /// Returns a response builder with the headers common to all successful responses.
+    fn builder(&self) -> http::response::Builder {
+        let builder = Response::builder().status(StatusCode::OK);
+
+        match &self.continuation_token {
+            Some(token) => builder.header(CONTINUATION_TOKEN_HEADER, token.as_str()),
+            None => builder,
+        }
     }
 
     pub fn body<T: Serialize + Encode>(self, item: &T) -> ApiResult {
         match self.encoding {
-            ApiEncodingFormat::SSZ => Response::builder()
-                .status(StatusCode::OK)
+            ApiEncodingFormat::SSZ => self
+                .builder()
                 .header("content-type", "application/ssz")
                 .body(Body::from(item.as_ssz_bytes()))
                 .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e))),
@@ -66,8 +87,24 @@ impl ResponseBuilder {
             ),
         };
 
-        Response::builder()
-            .status(StatusCode::OK)
+        self.builder()
+            .header("content-type", content_type)
+            .body(body)
+            .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+    }
+
+    /// Returns `bytes` verbatim, regardless of the encoding requested by the client.
+    pub fn body_bytes(self, content_type: &str, bytes: Vec<u8>) -> ApiResult {
+        self.builder()
+            .header("content-type", content_type)
+            .body(Body::from(bytes))
+            .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+    }
+
+    /// Returns `body`, which is sent to the client as it is produced, regardless of the encoding
+    /// requested by the client.
+    pub fn body_stream(self, content_type: &str, body: Body) -> ApiResult {
+        self.builder()
             .header("content-type", content_type)
             .body(body)
             .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
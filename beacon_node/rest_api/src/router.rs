@@ -1,3 +1,6 @@
+use crate::auth::ApiToken;
+use crate::checkpoint::{self, CheckpointServer};
+use crate::compression::{compress_response, ContentEncoding};
 use crate::{
     advanced, beacon, consensus, error::ApiError, helpers, lighthouse, metrics, network, node,
     spec, validator, NetworkChannel,
@@ -5,7 +8,8 @@ use crate::{
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
-use hyper::{Body, Error, Method, Request, Response};
+use hyper::{header, Body, Error, Method, Request, Response};
+use logging::LogControl;
 use slog::debug;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -23,12 +27,34 @@ pub async fn route<T: BeaconChainTypes>(
     local_log: slog::Logger,
     db_path: PathBuf,
     freezer_db_path: PathBuf,
+    max_results: usize,
+    checkpoint_server: Arc<CheckpointServer<T::EthSpec>>,
+    auth_token: Option<Arc<ApiToken>>,
+    log_control: LogControl,
 ) -> Result<Response<Body>, Error> {
     metrics::inc_counter(&metrics::REQUEST_COUNT);
     let timer = metrics::start_timer(&metrics::REQUEST_RESPONSE_TIME);
     let received_instant = Instant::now();
 
     let path = req.uri().path().to_string();
+    let content_encoding = req
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|h| h.to_str().ok())
+        .and_then(ContentEncoding::from_accept_encoding);
+
+    if let Some(token) = auth_token.filter(|_| ApiToken::is_required_for(req.method(), &path)) {
+        if let Err(e) = token.authorize(&req) {
+            debug!(
+                local_log,
+                "HTTP API request unauthorized";
+                "path" => path,
+            );
+            metrics::stop_timer(timer);
+
+            return Ok(e.into());
+        }
+    }
 
     let log = local_log.clone();
     let request_result = match (req.method(), path.as_ref()) {
@@ -67,22 +93,38 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/beacon/genesis_validators_root") => {
             beacon::get_genesis_validators_root::<T>(req, beacon_chain)
         }
-        (&Method::GET, "/beacon/validators") => beacon::get_validators::<T>(req, beacon_chain),
+        (&Method::GET, "/beacon/validators") => {
+            beacon::get_validators::<T>(req, beacon_chain, max_results)
+        }
         (&Method::POST, "/beacon/validators") => {
-            beacon::post_validators::<T>(req, beacon_chain).await
+            beacon::post_validators::<T>(req, beacon_chain, max_results).await
         }
         (&Method::GET, "/beacon/validators/all") => {
-            beacon::get_all_validators::<T>(req, beacon_chain)
+            beacon::get_all_validators::<T>(req, beacon_chain, max_results)
         }
         (&Method::GET, "/beacon/validators/active") => {
-            beacon::get_active_validators::<T>(req, beacon_chain)
+            beacon::get_active_validators::<T>(req, beacon_chain, max_results)
         }
         (&Method::GET, "/beacon/state") => beacon::get_state::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/state_root") => beacon::get_state_root::<T>(req, beacon_chain),
+        (&Method::GET, "/beacon/checkpoint") => {
+            checkpoint::get_checkpoint::<T>(req, beacon_chain, checkpoint_server)
+        }
         (&Method::GET, "/beacon/state/genesis") => {
             beacon::get_genesis_state::<T>(req, beacon_chain)
         }
-        (&Method::GET, "/beacon/committees") => beacon::get_committees::<T>(req, beacon_chain),
+        (&Method::GET, "/beacon/attestation_data") => {
+            beacon::get_attestation_data::<T>(req, beacon_chain)
+        }
+        (&Method::GET, "/beacon/committees") => {
+            beacon::get_committees::<T>(req, beacon_chain, max_results)
+        }
+        (&Method::GET, path) if path.starts_with(beacon::STATES_PATH) => {
+            beacon::get_state_committees::<T>(req, beacon_chain, max_results)
+        }
+        (&Method::POST, path) if path.starts_with(beacon::STATES_PATH) => {
+            beacon::post_state_validators::<T>(req, beacon_chain, max_results).await
+        }
         (&Method::POST, "/beacon/proposer_slashing") => {
             beacon::proposer_slashing::<T>(req, beacon_chain).await
         }
@@ -93,18 +135,24 @@ pub async fn route<T: BeaconChainTypes>(
         // Methods for Validator
         (&Method::POST, "/validator/duties") => {
             let timer = metrics::start_timer(&metrics::VALIDATOR_GET_DUTIES_REQUEST_RESPONSE_TIME);
-            let response = validator::post_validator_duties::<T>(req, beacon_chain);
+            let response = validator::post_validator_duties::<T>(req, beacon_chain, max_results);
             drop(timer);
             response.await
         }
+        (&Method::POST, "/validator/duties/history") => {
+            validator::post_historical_validator_duties::<T>(req, beacon_chain, max_results).await
+        }
         (&Method::POST, "/validator/subscribe") => {
             validator::post_validator_subscriptions::<T>(req, network_channel).await
         }
         (&Method::GET, "/validator/duties/all") => {
-            validator::get_all_validator_duties::<T>(req, beacon_chain)
+            validator::get_all_validator_duties::<T>(req, beacon_chain, max_results)
         }
         (&Method::GET, "/validator/duties/active") => {
-            validator::get_active_validator_duties::<T>(req, beacon_chain)
+            validator::get_active_validator_duties::<T>(req, beacon_chain, max_results)
+        }
+        (&Method::GET, path) if path.starts_with(validator::PROPOSER_DUTIES_PATH) => {
+            validator::get_proposer_duties::<T>(req, beacon_chain)
         }
         (&Method::GET, "/validator/block") => {
             let timer = metrics::start_timer(&metrics::VALIDATOR_GET_BLOCK_REQUEST_RESPONSE_TIME);
@@ -171,11 +219,47 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/lighthouse/connected_peers") => {
             lighthouse::connected_peers::<T::EthSpec>(req, network_globals)
         }
+        (&Method::GET, "/lighthouse/traffic") => {
+            lighthouse::traffic::<T::EthSpec>(req, network_globals)
+        }
+        (&Method::GET, "/lighthouse/peering") => {
+            lighthouse::get_peering::<T::EthSpec>(req, network_globals)
+        }
+        (&Method::POST, "/lighthouse/peering") => {
+            lighthouse::post_peering::<T::EthSpec>(req, network_channel).await
+        }
+        (&Method::GET, "/lighthouse/events") => lighthouse::get_events::<T>(req, beacon_chain),
+        (&Method::GET, "/lighthouse/refused_publications") => {
+            lighthouse::get_refused_publications::<T>(req, beacon_chain)
+        }
+        (&Method::POST, "/lighthouse/malloc/purge") => lighthouse::post_malloc_purge(req),
+        (&Method::GET, "/lighthouse/staking/estimate") => {
+            lighthouse::get_staking_estimate::<T>(req, beacon_chain)
+        }
+        (&Method::GET, "/lighthouse/logs/levels") => lighthouse::get_log_levels(req, log_control),
+        (&Method::POST, "/lighthouse/logs/levels") => {
+            lighthouse::post_log_levels(req, log_control, log)
+        }
+        (&Method::POST, "/lighthouse/logs/capture") => {
+            lighthouse::post_log_capture(req, log_control).await
+        }
+        (&Method::POST, "/lighthouse/beacon/blocks") => {
+            lighthouse::post_blocks_ssz::<T>(req, beacon_chain, log).await
+        }
+        (&Method::GET, path) if path.starts_with(lighthouse::BLOCKS_PATH) => {
+            lighthouse::get_block_ssz::<T>(req, beacon_chain, max_results, log)
+        }
         _ => Err(ApiError::NotFound(
             "Request path and/or method not found.".to_owned(),
         )),
     };
 
+    // Compress the response if the client supports it.
+    let request_result = match (request_result, content_encoding) {
+        (Ok(response), Some(encoding)) => compress_response(response, encoding).await,
+        (result, _) => result,
+    };
+
     // Map the Rust-friendly `Result` in to a http-friendly response. In effect, this ensures that
     // any `Err` returned from our response handlers becomes a valid http response to the client
     // (e.g., a response with a 404 or 500 status).
@@ -1,7 +1,12 @@
-use crate::helpers::{parse_committee_index, parse_epoch, parse_hex_ssz_bytes, parse_slot};
+use crate::helpers::{
+    parse_committee_index, parse_epoch, parse_hex_ssz_bytes, parse_root, parse_slot,
+};
 use crate::ApiError;
 use hyper::Request;
-use types::{AttestationData, CommitteeIndex, Epoch, Signature, Slot};
+use types::{AttestationData, CommitteeIndex, Epoch, Hash256, Signature, Slot};
+
+/// Separates the offset from the state root in a continuation token.
+const CONTINUATION_TOKEN_SEPARATOR: char = '_';
 
 /// Provides handy functions for parsing the query parameters of a URL.
 
@@ -109,6 +114,57 @@ impl<'a> UrlQuery<'a> {
             .and_then(|(_key, value)| parse_hex_ssz_bytes(&value))
     }
 
+    /// Returns the value of the first occurrence of the `graffiti` key, if any.
+    pub fn graffiti(self) -> Result<Option<[u8; 32]>, ApiError> {
+        self.first_of_opt(&["graffiti"])
+            .map(|(_key, value)| parse_hex_ssz_bytes(&value))
+            .transpose()
+    }
+
+    /// Returns the page of results requested by the first occurrences of the `continuation_token`
+    /// and `limit` keys.
+    ///
+    /// The page starts at the beginning of the results if there is no `continuation_token` and
+    /// contains at most `max_results` items, regardless of the `limit`.
+    pub fn pagination(self, max_results: usize) -> Result<Pagination, ApiError> {
+        let parse_usize = |key: &str, value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|e| ApiError::BadRequest(format!("Unable to parse {}: {:?}", key, e)))
+        };
+
+        let (offset, state_root) = match self.first_of_opt(&["continuation_token"]) {
+            Some((key, value)) => {
+                let mut parts = value.splitn(2, CONTINUATION_TOKEN_SEPARATOR);
+                let offset = parse_usize(&key, parts.next().unwrap_or(""))?;
+                let state_root = parts.next().map(parse_root).transpose()?.ok_or_else(|| {
+                    ApiError::BadRequest(format!("The {} has no state root", key))
+                })?;
+                (offset, Some(state_root))
+            }
+            None => (0, None),
+        };
+        let limit = match self
+            .first_of_opt(&["limit"])
+            .map(|(key, value)| parse_usize(&key, &value))
+            .transpose()?
+        {
+            Some(0) => {
+                return Err(ApiError::BadRequest(
+                    "The limit must be greater than zero".to_string(),
+                ))
+            }
+            Some(limit) => std::cmp::min(limit, max_results),
+            None => max_results,
+        };
+
+        Ok(Pagination {
+            offset,
+            limit,
+            state_root,
+        })
+    }
+
     /// Returns the value of the first occurrence of the `attestation_data` key.
     pub fn attestation_data(self) -> Result<AttestationData, ApiError> {
         self.first_of(&["attestation_data"])
@@ -116,6 +172,59 @@ impl<'a> UrlQuery<'a> {
     }
 }
 
+/// A single page of a list of results, as requested with `UrlQuery::pagination`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pagination {
+    /// The number of results to skip.
+    pub offset: usize,
+    /// The maximum number of results in the page.
+    pub limit: usize,
+    /// The root of the state from which the previous pages were read, if this is not the first
+    /// page.
+    pub state_root: Option<Hash256>,
+}
+
+impl Pagination {
+    /// Collects the page of `items`, read from the state with `state_root`, described by `self`.
+    ///
+    /// Also returns the continuation token which will fetch the next page, if any items remain.
+    /// Returns an error if the previous pages were read from another state (e.g., because the head
+    /// has changed), since the offset of the page may refer to different items.
+    pub fn paginate<I: Iterator>(
+        &self,
+        state_root: Hash256,
+        items: I,
+    ) -> Result<(Vec<I::Item>, Option<String>), ApiError> {
+        if let Some(expected) = self.state_root {
+            if expected != state_root {
+                return Err(ApiError::BadRequest(format!(
+                    "The continuation_token is for state {:?}, not {:?}. Request the first page \
+                     again.",
+                    expected, state_root
+                )));
+            }
+        }
+
+        let mut page = items
+            .skip(self.offset)
+            .take(self.limit.saturating_add(1))
+            .collect::<Vec<_>>();
+
+        if page.len() > self.limit {
+            page.truncate(self.limit);
+            let token = format!(
+                "{}{}{:?}",
+                self.offset + self.limit,
+                CONTINUATION_TOKEN_SEPARATOR,
+                state_root
+            );
+            Ok((page, Some(token)))
+        } else {
+            Ok((page, None))
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -163,4 +272,96 @@ mod test {
         );
         assert!(get_query().first_of(&["nothing"]).is_err());
     }
+
+    #[test]
+    fn pagination() {
+        let get_result = |addr: &str| -> Result<Pagination, ApiError> {
+            UrlQuery(url::Url::parse(addr).unwrap().query_pairs()).pagination(100)
+        };
+        let root = Hash256::repeat_byte(42);
+        let token = |offset| format!("{}_{:?}", offset, root);
+        let page = |offset, limit, state_root| {
+            Ok(Pagination {
+                offset,
+                limit,
+                state_root,
+            })
+        };
+
+        assert_eq!(get_result("http://cat.io/"), page(0, 100, None));
+        assert_eq!(
+            get_result(&format!("http://cat.io/?continuation_token={}", token(2))),
+            page(2, 100, Some(root))
+        );
+        assert_eq!(get_result("http://cat.io/?limit=5"), page(0, 5, None));
+        assert_eq!(get_result("http://cat.io/?limit=500"), page(0, 100, None));
+        assert_eq!(
+            get_result(&format!(
+                "http://cat.io/?limit=5&continuation_token={}",
+                token(10)
+            )),
+            page(10, 5, Some(root))
+        );
+        assert!(get_result("http://cat.io/?continuation_token=cats").is_err());
+        assert!(get_result("http://cat.io/?continuation_token=2").is_err());
+        assert!(get_result("http://cat.io/?continuation_token=2_cats").is_err());
+        assert!(get_result("http://cat.io/?limit=0").is_err());
+    }
+
+    #[test]
+    fn paginate() {
+        let root = Hash256::repeat_byte(42);
+        let other_root = Hash256::repeat_byte(7);
+        let token = |offset| Some(format!("{}_{:?}", offset, root));
+        let pagination = |offset, limit, state_root| Pagination {
+            offset,
+            limit,
+            state_root,
+        };
+
+        assert_eq!(
+            pagination(0, 2, None).paginate(root, 0..5),
+            Ok((vec![0, 1], token(2)))
+        );
+        assert_eq!(
+            pagination(2, 2, Some(root)).paginate(root, 0..5),
+            Ok((vec![2, 3], token(4)))
+        );
+        assert_eq!(
+            pagination(4, 2, Some(root)).paginate(root, 0..5),
+            Ok((vec![4], None))
+        );
+        assert_eq!(
+            pagination(3, 2, Some(root)).paginate(root, 0..5),
+            Ok((vec![3, 4], None))
+        );
+        assert_eq!(
+            pagination(8, 2, Some(root)).paginate(root, 0..5),
+            Ok((vec![], None))
+        );
+        assert!(
+            pagination(2, 2, Some(root))
+                .paginate(other_root, 0..5)
+                .is_err(),
+            "should reject a token for another state"
+        );
+
+        // The token of each page fetches the next.
+        let mut pages = vec![];
+        let mut pagination = pagination(0, 2, None);
+        loop {
+            let (page, token) = pagination.paginate(root, 0..5).unwrap();
+            pages.push(page);
+            match token {
+                Some(token) => {
+                    let url = format!("http://cat.io/?continuation_token={}", token);
+                    pagination = UrlQuery(url::Url::parse(&url).unwrap().query_pairs())
+                        .pagination(2)
+                        .unwrap();
+                }
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    }
 }
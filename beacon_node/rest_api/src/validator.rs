@@ -1,30 +1,41 @@
-use crate::helpers::{check_content_type_for_json, publish_beacon_block_to_network};
+use crate::helpers::{
+    check_content_type_for_json, check_request_size, parse_epoch, path_params,
+    publish_beacon_block_to_network, state_at_slot,
+};
 use crate::response_builder::ResponseBuilder;
 use crate::{ApiError, ApiResult, NetworkChannel, UrlQuery};
 use beacon_chain::{
-    attestation_verification::Error as AttnError, BeaconChain, BeaconChainTypes, BlockError,
-    StateSkipConfig,
+    attestation_verification::Error as AttnError, own_publications::RefusedPublication,
+    BeaconChain, BeaconChainTypes, BlockError, StateSkipConfig,
 };
 use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
 use hyper::{Body, Request};
 use network::NetworkMessage;
 use rayon::prelude::*;
-use rest_types::{ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorSubscription};
+use rest_types::{
+    HistoricalDuties, HistoricalDutiesRequest, ProposerDutiesResponse, ProposerDuty,
+    PublishRejection, ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorSubscription,
+};
 use slog::{error, info, trace, warn, Logger};
 use std::sync::Arc;
+use tree_hash::TreeHash;
 use types::beacon_state::EthSpec;
 use types::{
-    Attestation, AttestationData, BeaconState, Epoch, RelativeEpoch, SelectionProof,
+    Attestation, AttestationData, BeaconState, Epoch, Hash256, RelativeEpoch, SelectionProof,
     SignedAggregateAndProof, SignedBeaconBlock, Slot,
 };
 
+/// The path prefix of the proposer duties endpoint, which is followed by the epoch.
+pub const PROPOSER_DUTIES_PATH: &str = "/eth/v1/validator/duties/proposer/";
+
 /// HTTP Handler to retrieve the duties for a set of validators during a particular epoch. This
 /// method allows for collecting bulk sets of validator duties without risking exceeding the max
-/// URL length with query pairs.
+/// URL length with query pairs. At most `max_results` validators may be requested at once.
 pub async fn post_validator_duties<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let response_builder = ResponseBuilder::new(&req);
 
@@ -41,6 +52,7 @@ pub async fn post_validator_duties<T: BeaconChainTypes>(
             ))
         })
         .and_then(|bulk_request| {
+            check_request_size(bulk_request.pubkeys.len(), max_results)?;
             return_validator_duties(
                 beacon_chain,
                 bulk_request.epoch,
@@ -50,6 +62,69 @@ pub async fn post_validator_duties<T: BeaconChainTypes>(
         .and_then(|duties| response_builder?.body_no_ssz(&duties))
 }
 
+/// HTTP Handler to retrieve the duties for a set of validators in every epoch of a finalized range,
+/// so that their expected and actual performance may be compared. At most `max_results` duties
+/// (i.e., validators multiplied by epochs, counting at least one validator per epoch) may be
+/// requested at once.
+pub async fn post_historical_validator_duties<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
+) -> ApiResult {
+    let response_builder = ResponseBuilder::new(&req);
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    serde_json::from_slice::<HistoricalDutiesRequest>(&chunks)
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to parse JSON into HistoricalDutiesRequest: {:?}",
+                e
+            ))
+        })
+        .and_then(|request| {
+            if request.start_epoch > request.end_epoch {
+                return Err(ApiError::BadRequest(format!(
+                    "start_epoch {} is later than end_epoch {}",
+                    request.start_epoch, request.end_epoch
+                )));
+            }
+
+            // A state is loaded for every epoch in the range, even if no pubkeys are requested.
+            let num_epochs = (request.end_epoch - request.start_epoch).as_u64() as usize + 1;
+            check_request_size(
+                std::cmp::max(1, request.pubkeys.len()).saturating_mul(num_epochs),
+                max_results,
+            )?;
+
+            let finalized_epoch = beacon_chain.head_info()?.finalized_checkpoint.epoch;
+            if request.end_epoch > finalized_epoch {
+                return Err(ApiError::BadRequest(format!(
+                    "end_epoch {} is not finalized, the finalized epoch is {}",
+                    request.end_epoch, finalized_epoch
+                )));
+            }
+
+            (request.start_epoch.as_u64()..=request.end_epoch.as_u64())
+                .map(Epoch::new)
+                .map(|epoch| {
+                    Ok(HistoricalDuties {
+                        epoch,
+                        duties: return_historical_validator_duties(
+                            &beacon_chain,
+                            epoch,
+                            request.pubkeys.clone(),
+                        )?,
+                    })
+                })
+                .collect::<Result<Vec<_>, ApiError>>()
+        })
+        .and_then(|duties| response_builder?.body_no_ssz(&duties))
+}
+
 /// HTTP Handler to retrieve subscriptions for a set of validators. This allows the node to
 /// organise peer discovery and topic subscription for known validators.
 pub async fn post_validator_subscriptions<T: BeaconChainTypes>(
@@ -86,61 +161,135 @@ pub async fn post_validator_subscriptions<T: BeaconChainTypes>(
 }
 
 /// HTTP Handler to retrieve all validator duties for the given epoch.
+///
+/// The duties are paginated with the `continuation_token` and `limit` query parameters.
 pub fn get_all_validator_duties<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
 
     let epoch = query.epoch()?;
+    let pagination = query.pagination(max_results)?;
 
-    let state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    let (state_root, state) =
+        get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
 
-    let validator_pubkeys = state
-        .validators
-        .iter()
-        .map(|validator| validator.pubkey.clone())
-        .collect();
+    let (validator_pubkeys, continuation_token) = pagination.paginate(
+        state_root,
+        state
+            .validators
+            .iter()
+            .map(|validator| validator.pubkey.clone()),
+    )?;
 
-    let duties = return_validator_duties(beacon_chain, epoch, validator_pubkeys)?;
+    let duties = validator_duties_for_epoch(&beacon_chain, epoch, state, validator_pubkeys)?;
 
-    ResponseBuilder::new(&req)?.body_no_ssz(&duties)
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body_no_ssz(&duties)
 }
 
 /// HTTP Handler to retrieve all active validator duties for the given epoch.
+///
+/// The duties are paginated with the `continuation_token` and `limit` query parameters.
 pub fn get_active_validator_duties<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
+    max_results: usize,
 ) -> ApiResult {
     let query = UrlQuery::from_request(&req)?;
 
     let epoch = query.epoch()?;
+    let pagination = query.pagination(max_results)?;
 
-    let state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    let (state_root, state) =
+        get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
 
-    let validator_pubkeys = state
-        .validators
-        .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
-        .map(|validator| validator.pubkey.clone())
-        .collect();
+    let (validator_pubkeys, continuation_token) = pagination.paginate(
+        state_root,
+        state
+            .validators
+            .iter()
+            .filter(|validator| validator.is_active_at(state.current_epoch()))
+            .map(|validator| validator.pubkey.clone()),
+    )?;
 
-    let duties = return_validator_duties(beacon_chain, epoch, validator_pubkeys)?;
+    let duties = validator_duties_for_epoch(&beacon_chain, epoch, state, validator_pubkeys)?;
 
-    ResponseBuilder::new(&req)?.body_no_ssz(&duties)
+    ResponseBuilder::new(&req)?
+        .continuation_token(continuation_token)
+        .body_no_ssz(&duties)
+}
+
+/// HTTP Handler to retrieve the block proposer for every slot of the `epoch` given in the path.
+///
+/// The returned `dependent_root` is the block root at the last slot of the prior epoch, which is
+/// the block that determines the proposer shuffling for `epoch`.
+pub fn get_proposer_duties<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let epoch = match path_params(req.uri().path(), PROPOSER_DUTIES_PATH)?.as_slice() {
+        [epoch] => parse_epoch(epoch)?,
+        _ => {
+            return Err(ApiError::BadRequest(format!(
+                "Path must be {}{{epoch}}",
+                PROPOSER_DUTIES_PATH
+            )))
+        }
+    };
+
+    let shuffling = beacon_chain
+        .proposer_shuffling_for_epoch(epoch)
+        .map_err(|e| {
+            ApiError::ServerError(format!(
+                "Unable to get proposer shuffling for epoch {}: {:?}",
+                epoch, e
+            ))
+        })?;
+
+    let data = epoch
+        .slot_iter(T::EthSpec::slots_per_epoch())
+        .zip(shuffling.proposers.iter())
+        .map(|(slot, &validator_index)| {
+            let pubkey = beacon_chain
+                .validator_pubkey(validator_index)?
+                .map(PublicKeyBytes::from)
+                .ok_or_else(|| {
+                    ApiError::ServerError(format!("Invalid validator index: {}", validator_index))
+                })?;
+
+            Ok(ProposerDuty {
+                pubkey,
+                validator_index: validator_index as u64,
+                slot,
+            })
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    ResponseBuilder::new(&req)?.body(&ProposerDutiesResponse {
+        dependent_root: shuffling.decision_root,
+        data,
+    })
 }
 
 /// Helper function to return the state that can be used to determine the duties for some `epoch`.
+///
+/// Also returns a root which identifies the state: its own root, or the root of the head state if
+/// it was advanced from the head (in which case the state root is not computed).
 pub fn get_state_for_epoch<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     epoch: Epoch,
     config: StateSkipConfig,
-) -> Result<BeaconState<T::EthSpec>, ApiError> {
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
     let slots_per_epoch = T::EthSpec::slots_per_epoch();
-    let head_epoch = beacon_chain.head()?.beacon_state.current_epoch();
+    let head = beacon_chain.head()?;
+    let head_epoch = head.beacon_state.current_epoch();
 
     if RelativeEpoch::from_epoch(head_epoch, epoch).is_ok() {
-        Ok(beacon_chain.head()?.beacon_state)
+        Ok((head.beacon_state_root, head.beacon_state))
     } else {
         let slot = if epoch > head_epoch {
             // Move to the first slot of the epoch prior to the request.
@@ -154,9 +303,14 @@ pub fn get_state_for_epoch<T: BeaconChainTypes>(
             (epoch + 2).start_slot(slots_per_epoch) - 1
         };
 
-        beacon_chain.state_at_slot(slot, config).map_err(|e| {
-            ApiError::ServerError(format!("Unable to load state for epoch {}: {:?}", epoch, e))
-        })
+        if slot > head.beacon_state.slot {
+            let state = beacon_chain.state_at_slot(slot, config).map_err(|e| {
+                ApiError::ServerError(format!("Unable to load state for epoch {}: {:?}", epoch, e))
+            })?;
+            Ok((head.beacon_state_root, state))
+        } else {
+            state_at_slot(beacon_chain, slot)
+        }
     }
 }
 
@@ -166,37 +320,102 @@ fn return_validator_duties<T: BeaconChainTypes>(
     epoch: Epoch,
     validator_pubkeys: Vec<PublicKeyBytes>,
 ) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
-    let mut state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    let (_state_root, state) =
+        get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+
+    validator_duties_for_epoch(&beacon_chain, epoch, state, validator_pubkeys)
+}
 
+/// As per `return_validator_duties`, but reading the duties from `state`, which must have been
+/// loaded with `get_state_for_epoch`.
+fn validator_duties_for_epoch<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+    mut state: BeaconState<T::EthSpec>,
+    validator_pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
     let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
         .map_err(|_| ApiError::ServerError(String::from("Loaded state is in the wrong epoch")))?;
 
-    state.update_pubkey_cache()?;
-    state
-        .build_committee_cache(relative_epoch, &beacon_chain.spec)
-        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
-    state
-        .update_pubkey_cache()
-        .map_err(|e| ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e)))?;
-
     // Get a list of all validators for this epoch.
     //
     // Used for quickly determining the slot for a proposer.
-    let validator_proposers: Vec<(usize, Slot)> = epoch
-        .slot_iter(T::EthSpec::slots_per_epoch())
+    let validator_proposers: Vec<(usize, Slot)> = beacon_chain
+        .proposer_shuffling_for_epoch(epoch)
+        .map_err(|e| ApiError::ServerError(format!("Unable to get proposer shuffling: {:?}", e)))?
+        .proposers
+        .into_iter()
+        .zip(epoch.slot_iter(T::EthSpec::slots_per_epoch()))
+        .collect();
+
+    validator_duties_from_state(
+        beacon_chain,
+        &mut state,
+        relative_epoch,
+        &validator_proposers,
+        validator_pubkeys,
+    )
+}
+
+/// Helper function to get the duties for some `validator_pubkeys` in some past `epoch`.
+///
+/// Unlike `return_validator_duties`, the proposers are read from the same state as the committees
+/// rather than the proposer cache, so only one historical state is loaded per epoch.
+fn return_historical_validator_duties<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+    validator_pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+
+    let mut state = beacon_chain
+        .state_at_slot(
+            epoch.start_slot(slots_per_epoch),
+            StateSkipConfig::WithoutStateRoots,
+        )
+        .map_err(|e| {
+            ApiError::ServerError(format!("Unable to load state for epoch {}: {:?}", epoch, e))
+        })?;
+
+    state
+        .build_committee_cache(RelativeEpoch::Current, &beacon_chain.spec)
+        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
+
+    let validator_proposers = epoch
+        .slot_iter(slots_per_epoch)
         .map(|slot| {
             state
                 .get_beacon_proposer_index(slot, &beacon_chain.spec)
-                .map(|i| (i, slot))
-                .map_err(|e| {
-                    ApiError::ServerError(format!(
-                        "Unable to get proposer index for validator: {:?}",
-                        e
-                    ))
-                })
+                .map(|validator_index| (validator_index, slot))
         })
         .collect::<Result<Vec<_>, _>>()?;
 
+    validator_duties_from_state(
+        beacon_chain,
+        &mut state,
+        RelativeEpoch::Current,
+        &validator_proposers,
+        validator_pubkeys,
+    )
+}
+
+/// Helper function to get the duties for some `validator_pubkeys` from a `state` in the epoch
+/// given by `relative_epoch`, where `validator_proposers` are the proposers of that epoch.
+fn validator_duties_from_state<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state: &mut BeaconState<T::EthSpec>,
+    relative_epoch: RelativeEpoch,
+    validator_proposers: &[(usize, Slot)],
+    validator_pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<ValidatorDutyBytes>, ApiError> {
+    state.update_pubkey_cache()?;
+    state
+        .build_committee_cache(relative_epoch, &beacon_chain.spec)
+        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
+    state
+        .update_pubkey_cache()
+        .map_err(|e| ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e)))?;
+
     validator_pubkeys
         .into_iter()
         .map(|validator_pubkey| {
@@ -267,9 +486,10 @@ pub fn get_new_beacon_block<T: BeaconChainTypes>(
 
     let slot = query.slot()?;
     let randao_reveal = query.randao_reveal()?;
+    let graffiti = query.graffiti()?;
 
     let (new_block, _state) = beacon_chain
-        .produce_block(randao_reveal, slot)
+        .produce_block(randao_reveal, slot, graffiti)
         .map_err(|e| {
             error!(
                 log,
@@ -301,82 +521,123 @@ pub async fn publish_beacon_block<T: BeaconChainTypes>(
         .await
         .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
 
-    serde_json::from_slice(&chunks).map_err(|e| {
-                    ApiError::BadRequest(format!("Unable to parse JSON into SignedBeaconBlock: {:?}", e))
-                })
-            .and_then(move |block: SignedBeaconBlock<T::EthSpec>| {
-                let slot = block.slot();
-                match beacon_chain.process_block(block.clone()) {
-                    Ok(block_root) => {
-                        // Block was processed, publish via gossipsub
-                        info!(
-                            log,
-                            "Block from local validator";
-                            "block_root" => format!("{}", block_root),
-                            "block_slot" => slot,
-                        );
-
-                        publish_beacon_block_to_network::<T>(network_chan, block)?;
-
-                        // Run the fork choice algorithm and enshrine a new canonical head, if
-                        // found.
-                        //
-                        // The new head may or may not be the block we just received.
-                        if let Err(e) = beacon_chain.fork_choice() {
-                            error!(
-                                log,
-                                "Failed to find beacon chain head";
-                                "error" => format!("{:?}", e)
-                            );
-                        } else {
-                            // In the best case, validators should produce blocks that become the
-                            // head.
-                            //
-                            // Potential reasons this may not be the case:
-                            //
-                            // - A quick re-org between block produce and publish.
-                            // - Excessive time between block produce and publish.
-                            // - A validator is using another beacon node to produce blocks and
-                            // submitting them here.
-                            if beacon_chain.head()?.beacon_block_root != block_root {
-                                warn!(
-                                    log,
-                                    "Block from validator is not head";
-                                    "desc" => "potential re-org",
-                                );
-
-                            }
-                        }
-
-                        Ok(())
-                    }
-                    Err(BlockError::BeaconChainError(e)) => {
-                        error!(
-                            log,
-                            "Error whilst processing block";
-                            "error" => format!("{:?}", e)
-                        );
-
-                        Err(ApiError::ServerError(format!(
-                            "Error while processing block: {:?}",
-                            e
-                        )))
-                    }
-                    Err(other) => {
-                        warn!(
-                            log,
-                            "Invalid block from local validator";
-                            "outcome" => format!("{:?}", other)
-                        );
-
-                        Err(ApiError::ProcessingError(format!(
-                            "The SignedBeaconBlock could not be processed and has not been published: {:?}",
-                            other
-                        )))
-                    }
-                }
-        })
-        .and_then(|_| response_builder?.body_no_ssz(&()))
+    let block: SignedBeaconBlock<T::EthSpec> = serde_json::from_slice(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into SignedBeaconBlock: {:?}",
+            e
+        ))
+    })?;
+    let slot = block.slot();
+
+    // Refuse to publish a block which conflicts with one already published through this node,
+    // before gossip verification rejects it as a repeat proposal without alerting the operator.
+    beacon_chain
+        .own_publications
+        .check_block(block.message.proposer_index, slot, block.canonical_root())
+        .map_err(|refused| own_equivocation_rejection(refused, None))?;
+
+    // Verify the block exactly as if it had been received on gossip (including the checks for a
+    // repeat proposal), so a block from the API cannot be published if it would not have been
+    // propagated by this node had it arrived from a peer.
+    let verified_block = beacon_chain
+        .verify_block_for_gossip(block.clone())
+        .map_err(|e| block_rejection(&log, e, "has not been published"))?;
+    let block_root = verified_block.block_root;
+
+    beacon_chain
+        .own_publications
+        .observe_block(block.message.proposer_index, slot, block_root)
+        .map_err(|refused| own_equivocation_rejection(refused, None))?;
+
+    // Publish the block as soon as it is valid for gossip, as the router does for blocks from
+    // peers, rather than delaying propagation until it has been imported.
+    publish_beacon_block_to_network::<T>(network_chan, block)?;
+
+    beacon_chain
+        .process_block(verified_block)
+        .map_err(|e| block_rejection(&log, e, "has been published but could not be imported"))?;
+
+    info!(
+        log,
+        "Block from local validator";
+        "block_root" => format!("{}", block_root),
+        "block_slot" => slot,
+    );
+
+    // Run the fork choice algorithm and enshrine a new canonical head, if
+    // found.
+    //
+    // The new head may or may not be the block we just received.
+    if let Err(e) = beacon_chain.fork_choice() {
+        error!(
+            log,
+            "Failed to find beacon chain head";
+            "error" => format!("{:?}", e)
+        );
+    } else {
+        // In the best case, validators should produce blocks that become the
+        // head.
+        //
+        // Potential reasons this may not be the case:
+        //
+        // - A quick re-org between block produce and publish.
+        // - Excessive time between block produce and publish.
+        // - A validator is using another beacon node to produce blocks and
+        // submitting them here.
+        if beacon_chain.head()?.beacon_block_root != block_root {
+            warn!(
+                log,
+                "Block from validator is not head";
+                "desc" => "potential re-org",
+            );
+        }
+    }
+
+    response_builder?.body_no_ssz(&())
+}
+
+/// Converts a refusal to publish a conflicting block or attestation into a response.
+fn own_equivocation_rejection(refused: RefusedPublication, index: Option<usize>) -> ApiError {
+    ApiError::Rejected(PublishRejection {
+        reason: "own_equivocation".to_string(),
+        index,
+        message: format!(
+            "A conflicting {} was already published through this beacon node, so this one has not been published: {:?}",
+            refused.kind.as_str(), refused
+        ),
+    })
+}
+
+/// Converts an error from verifying or importing a block from the API into a response, where
+/// `outcome` describes whether the block was published.
+fn block_rejection(log: &Logger, error: BlockError, outcome: &str) -> ApiError {
+    match error {
+        BlockError::BeaconChainError(e) => {
+            error!(
+                log,
+                "Error whilst processing block";
+                "error" => format!("{:?}", e)
+            );
+
+            ApiError::ServerError(format!("Error while processing block: {:?}", e))
+        }
+        other => {
+            warn!(
+                log,
+                "Invalid block from local validator";
+                "outcome" => format!("{:?}", other)
+            );
+
+            ApiError::Rejected(PublishRejection {
+                reason: other.as_str().to_string(),
+                index: None,
+                message: format!(
+                    "The SignedBeaconBlock could not be processed and {}: {:?}",
+                    outcome, other
+                ),
+            })
+        }
+    }
 }
 
 /// HTTP Handler to produce a new Attestation from the current state, ready to be signed by a validator.
@@ -480,14 +741,53 @@ fn process_unaggregated_attestation<T: BeaconChainTypes>(
     let verified_attestation = beacon_chain
         .verify_unaggregated_attestation_for_gossip(attestation.clone())
         .map_err(|e| {
+            // The attestation is a repeat. Alert the operator if it conflicts with the one
+            // already published through this node.
+            if let AttnError::PriorAttestationKnown {
+                validator_index,
+                epoch,
+            } = e
+            {
+                if let Err(refused) = beacon_chain.own_publications.check_attestation(
+                    validator_index,
+                    data.slot,
+                    epoch,
+                    data.tree_hash_root(),
+                ) {
+                    return own_equivocation_rejection(refused, Some(i));
+                }
+            }
+
             handle_attestation_error(
                 e,
+                i,
                 &format!("unaggregated attestation {} failed gossip verification", i),
                 data,
                 log,
             )
         })?;
 
+    // Refuse to publish an attestation which conflicts with one already published through this
+    // node (e.g., if verification did not catch it because the earlier attestation was not
+    // observed on gossip).
+    let validator_index = verified_attestation
+        .indexed_attestation()
+        .attesting_indices
+        .first()
+        .copied()
+        .ok_or_else(|| {
+            ApiError::ServerError(format!("Unaggregated attestation {} has no attester", i))
+        })?;
+    beacon_chain
+        .own_publications
+        .observe_attestation(
+            validator_index,
+            data.slot,
+            data.target.epoch,
+            data.tree_hash_root(),
+        )
+        .map_err(|refused| own_equivocation_rejection(refused, Some(i)))?;
+
     // Publish the attestation to the network
     if let Err(e) = network_chan.send(NetworkMessage::Publish {
         messages: vec![PubsubMessage::Attestation(Box::new((
@@ -508,6 +808,7 @@ fn process_unaggregated_attestation<T: BeaconChainTypes>(
         .map_err(|e| {
             handle_attestation_error(
                 e,
+                i,
                 &format!(
                     "unaggregated attestation {} was unable to be added to fork choice",
                     i
@@ -522,6 +823,7 @@ fn process_unaggregated_attestation<T: BeaconChainTypes>(
         .map_err(|e| {
             handle_attestation_error(
                 e,
+                i,
                 &format!(
                     "unaggregated attestation {} was unable to be added to aggregation pool",
                     i
@@ -623,6 +925,7 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
             Err(e) => {
                 return Err(handle_attestation_error(
                     e,
+                    i,
                     &format!("aggregated attestation {} failed gossip verification", i),
                     data,
                     log,
@@ -647,6 +950,7 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
         .map_err(|e| {
             handle_attestation_error(
                 e,
+                i,
                 &format!(
                     "aggregated attestation {} was unable to be added to fork choice",
                     i
@@ -661,6 +965,7 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
         .map_err(|e| {
             handle_attestation_error(
                 e,
+                i,
                 &format!(
                     "aggregated attestation {} was unable to be added to op pool",
                     i
@@ -673,9 +978,11 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
     Ok(())
 }
 
-/// Common handler for `AttnError` during attestation verification.
+/// Common handler for `AttnError` during attestation verification of the attestation with index
+/// `i` in the published list.
 fn handle_attestation_error(
     e: AttnError,
+    i: usize,
     detail: &str,
     data: &AttestationData,
     log: &Logger,
@@ -710,10 +1017,14 @@ fn handle_attestation_error(
                 "slot" => data.slot,
             );
 
-            ApiError::ProcessingError(format!(
-                "Invalid local attestation. Error: {:?} Detail: {}",
-                e, detail
-            ))
+            ApiError::Rejected(PublishRejection {
+                reason: e.as_str().to_string(),
+                index: Some(i),
+                message: format!(
+                    "Invalid local attestation. Error: {:?} Detail: {}",
+                    e, detail
+                ),
+            })
         }
     }
 }
@@ -9,19 +9,23 @@ use node_test_rig::{
     testing_client_config, ClientConfig, ClientGenesis, LocalBeaconNode,
 };
 use remote_beacon_node::{
-    Committee, HeadBeaconBlock, PersistedOperationPool, PublishStatus, ValidatorResponse,
+    Committee, HeadBeaconBlock, ImportBlocksResponse, PeeringResponse, PeeringUpdateRequest,
+    PersistedOperationPool, PublishStatus, RemoteBeaconNode, ValidatorResponse, ValidatorStatus,
 };
 use rest_types::ValidatorDutyBytes;
+use ssz::{Decode, Encode};
 use std::convert::TryInto;
+use std::io::Read;
 use std::sync::Arc;
+use std::time::Duration;
 use types::{
     test_utils::{
         build_double_vote_attester_slashing, build_proposer_slashing,
         generate_deterministic_keypair, AttesterSlashingTestTask, ProposerSlashingTestTask,
     },
-    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, MinimalEthSpec, PublicKey,
-    RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
-    Validator,
+    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, Hash256, MinimalEthSpec,
+    PublicKey, PublicKeyBytes, RelativeEpoch, Signature, SignedAggregateAndProof,
+    SignedBeaconBlock, SignedRoot, Slot, Validator,
 };
 use version;
 
@@ -314,6 +318,329 @@ fn validator_duties() {
     // chain, yet.
 }
 
+#[test]
+fn validator_historical_duties() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let validators = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .validators
+        .iter()
+        .map(|v| (&v.pubkey).try_into().expect("pubkey should be valid"))
+        .collect::<Vec<_>>();
+
+    // Nothing has been finalized, so only the genesis epoch may be requested.
+    let epoch = Epoch::new(0);
+    let mut history = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .get_historical_duties(epoch, epoch, &validators),
+        )
+        .expect("should fetch historical duties from http api");
+
+    assert_eq!(history.len(), 1, "should have duties for one epoch");
+    let duties = history.remove(0);
+    assert_eq!(duties.epoch, epoch);
+    check_duties(duties.duties, epoch, validators.clone(), beacon_chain, spec);
+
+    assert!(
+        env.runtime()
+            .block_on(remote_node.http.validator().get_historical_duties(
+                epoch,
+                epoch + 1,
+                &validators
+            ))
+            .is_err(),
+        "should not return duties for an epoch that is not finalized"
+    );
+}
+
+#[test]
+fn validator_historical_duties_without_pubkeys() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.max_results = 3;
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let epoch = Epoch::new(0);
+    let history = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .get_historical_duties(epoch, epoch, &[]),
+        )
+        .expect("should fetch historical duties without pubkeys");
+    assert_eq!(history.len(), 1, "should have duties for one epoch");
+    assert!(history[0].duties.is_empty(), "should have no duties");
+
+    // Each epoch counts towards the max results, even without any pubkeys.
+    match env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .get_historical_duties(epoch, epoch + 1_000_000, &[]),
+        ) {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, body }) => {
+            assert_eq!(status, 400, "should reject a long range");
+            assert!(
+                body.contains("the maximum is 3"),
+                "should reject a long range for its size, got {}",
+                body
+            );
+        }
+        other => panic!("should reject a long range, got {:?}", other),
+    }
+}
+
+#[test]
+fn validator_auth_token() {
+    let mut env = build_env();
+
+    let token_dir = tempfile::tempdir().expect("should create temp dir");
+    let token_path = token_dir.path().join(rest_api::API_TOKEN_FILENAME);
+
+    let mut config = testing_client_config();
+    config.rest_api.auth_token_path = Some(token_path.clone());
+    let node = build_node(&mut env, config);
+
+    let socket_addr = node
+        .client
+        .http_listen_addr()
+        .expect("should have http server");
+    let url = format!("http://{}:{}", socket_addr.ip(), socket_addr.port());
+    let token = std::fs::read_to_string(&token_path).expect("should write token file");
+
+    let validators = vec![generate_deterministic_keypair(0).pk];
+    let epoch = Epoch::new(0);
+
+    // Requests which are not to the `/validator` endpoints do not require the token, unless they
+    // change the state of the node.
+    let unauthenticated = node.remote_node().expect("should produce remote node");
+    env.runtime()
+        .block_on(unauthenticated.http.node().get_version())
+        .expect("should get version without token");
+    env.runtime()
+        .block_on(unauthenticated.http.lighthouse().get_peering())
+        .expect("should get peering without token");
+
+    match env.runtime().block_on(
+        unauthenticated
+            .http
+            .lighthouse()
+            .update_peering(PeeringUpdateRequest::default()),
+    ) {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+            assert_eq!(status, 401, "should reject update without token")
+        }
+        other => panic!("should reject update without token, got {:?}", other),
+    }
+
+    match env.runtime().block_on(
+        unauthenticated
+            .http
+            .validator()
+            .get_duties(epoch, &validators),
+    ) {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+            assert_eq!(status, 401, "should reject request without token")
+        }
+        other => panic!("should reject request without token, got {:?}", other),
+    }
+
+    let wrong_token = RemoteBeaconNode::new_with_auth_token(
+        url.clone(),
+        Duration::from_secs(15),
+        Some("not the token".to_string()),
+    )
+    .expect("should produce remote node");
+    assert!(
+        env.runtime()
+            .block_on(wrong_token.http.validator().get_duties(epoch, &validators))
+            .is_err(),
+        "should reject request with wrong token"
+    );
+
+    let authenticated =
+        RemoteBeaconNode::new_with_auth_token(url, Duration::from_secs(15), Some(token))
+            .expect("should produce remote node");
+    let duties = env
+        .runtime()
+        .block_on(
+            authenticated
+                .http
+                .validator()
+                .get_duties(epoch, &validators),
+        )
+        .expect("should accept request with token");
+    assert_eq!(duties.len(), 1);
+    env.runtime()
+        .block_on(
+            authenticated
+                .http
+                .lighthouse()
+                .update_peering(PeeringUpdateRequest::default()),
+        )
+        .expect("should accept update with token");
+}
+
+#[test]
+fn in_process_api() {
+    let mut env = build_env();
+
+    let token_dir = tempfile::tempdir().expect("should create temp dir");
+
+    let mut config = testing_client_config();
+    config.rest_api.local_api = true;
+    config.rest_api.auth_token_path = Some(token_dir.path().join(rest_api::API_TOKEN_FILENAME));
+    let node = build_node(&mut env, config);
+
+    let local = RemoteBeaconNode::<E>::new_local(
+        node.client
+            .local_api()
+            .expect("should serve in-process requests"),
+        Duration::from_secs(15),
+    );
+    let remote = node.remote_node().expect("should produce remote node");
+    assert_eq!(local.http.endpoint(), "in-process");
+
+    let version = env
+        .runtime()
+        .block_on(local.http.node().get_version())
+        .expect("should get version");
+    assert_eq!(version, version::version());
+
+    let head = env
+        .runtime()
+        .block_on(local.http.beacon().get_head())
+        .expect("should get head");
+    assert_eq!(
+        head,
+        env.runtime()
+            .block_on(remote.http.beacon().get_head())
+            .expect("should get head over http")
+    );
+
+    // In-process requests are not authenticated.
+    let duties = env
+        .runtime()
+        .block_on(
+            local
+                .http
+                .validator()
+                .get_duties(Epoch::new(0), &[generate_deterministic_keypair(0).pk]),
+        )
+        .expect("should get duties without token");
+    assert_eq!(duties.len(), 1);
+
+    // Errors are returned as they would be over HTTP.
+    match env
+        .runtime()
+        .block_on(local.http.beacon().get_block_by_slot(Slot::new(1_000)))
+    {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+            assert_eq!(status, 404, "should not find block")
+        }
+        other => panic!("should not find block, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn in_process_api_timeout() {
+    let mut env = build_env();
+
+    // The receiver is kept, but never responds to a request.
+    let (sender, _receiver) = futures::channel::mpsc::unbounded();
+    let local = RemoteBeaconNode::<E>::new_local(sender, Duration::from_millis(100));
+
+    match env.runtime().block_on(local.http.node().get_version()) {
+        Err(remote_beacon_node::Error::LocalNodeTimeout) => {}
+        other => panic!("should time out, got {:?}", other),
+    }
+}
+
+#[test]
+fn validator_proposer_duties() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let genesis_block_root = beacon_chain
+        .head()
+        .expect("should get head")
+        .beacon_block_root;
+
+    for epoch in &[Epoch::new(0), Epoch::new(4)] {
+        let duties = env
+            .runtime()
+            .block_on(remote_node.http.validator().get_proposer_duties(*epoch))
+            .expect("should fetch proposer duties from http api");
+
+        // No blocks have been produced, so every epoch depends upon the genesis block.
+        assert_eq!(
+            duties.dependent_root, genesis_block_root,
+            "dependent root should be genesis block root"
+        );
+
+        let mut state = beacon_chain
+            .state_at_slot(
+                epoch.start_slot(E::slots_per_epoch()),
+                StateSkipConfig::WithStateRoots,
+            )
+            .expect("should get state at slot");
+
+        state.build_all_caches(spec).expect("should build caches");
+
+        let slots: Vec<Slot> = duties.data.iter().map(|duty| duty.slot).collect();
+        let all_slots: Vec<Slot> = epoch.slot_iter(E::slots_per_epoch()).collect();
+        assert_eq!(slots, all_slots, "should have a duty for every slot");
+
+        for duty in &duties.data {
+            let expected_proposer = state
+                .get_beacon_proposer_index(duty.slot, spec)
+                .expect("should know proposer");
+
+            assert_eq!(
+                duty.validator_index as usize, expected_proposer,
+                "should have correct proposer index"
+            );
+            assert_eq!(
+                duty.pubkey, state.validators[expected_proposer].pubkey,
+                "should have correct proposer pubkey"
+            );
+        }
+    }
+}
+
 fn check_duties<T: BeaconChainTypes>(
     duties: Vec<ValidatorDutyBytes>,
     epoch: Epoch,
@@ -433,7 +760,7 @@ fn validator_block_post() {
             remote_node
                 .http
                 .validator()
-                .produce_block(slot, randao_reveal),
+                .produce_block(slot, randao_reveal, None),
         )
         .expect("should fetch block from http api");
 
@@ -451,14 +778,27 @@ fn validator_block_post() {
             !publish_status.is_valid(),
             "the unsigned published block should not be valid"
         );
+        assert!(
+            matches!(
+                publish_status,
+                PublishStatus::Rejected(ref rejection)
+                    if rejection.reason == "proposal_signature_invalid" && rejection.index.is_none()
+            ),
+            "the unsigned published block should be rejected with a reason"
+        );
     }
 
-    let signed_block = sign_block(beacon_chain.clone(), block, spec);
+    let signed_block = sign_block(beacon_chain.clone(), block.clone(), spec);
     let block_root = signed_block.canonical_root();
 
     let publish_status = env
         .runtime()
-        .block_on(remote_node.http.validator().publish_block(signed_block))
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_block(signed_block.clone()),
+        )
         .expect("should publish block");
 
     if cfg!(not(feature = "fake_crypto")) {
@@ -469,6 +809,46 @@ fn validator_block_post() {
         );
     }
 
+    // Blocks from the API are verified as if they were from gossip, so a second block from the
+    // same proposer and slot is not published.
+    let publish_status = env
+        .runtime()
+        .block_on(remote_node.http.validator().publish_block(signed_block))
+        .expect("should publish block");
+    assert!(
+        matches!(
+            publish_status,
+            PublishStatus::Rejected(ref rejection) if rejection.reason == "repeat_proposal"
+        ),
+        "the repeated block should be rejected"
+    );
+
+    // A different block from the same proposer and slot would be slashable, so is refused.
+    let mut conflicting_block = block;
+    conflicting_block.body.graffiti = [42; 32];
+    let conflicting_block = sign_block(beacon_chain.clone(), conflicting_block, spec);
+    let publish_status = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_block(conflicting_block),
+        )
+        .expect("should publish block");
+    assert!(
+        matches!(
+            publish_status,
+            PublishStatus::Rejected(ref rejection) if rejection.reason == "own_equivocation"
+        ),
+        "the conflicting block should be refused"
+    );
+    assert_eq!(
+        beacon_chain.own_publications.refused().len(),
+        1,
+        "the refusal should be recorded"
+    );
+
     let head = env
         .runtime()
         .block_on(remote_node.http.beacon().get_head())
@@ -512,7 +892,7 @@ fn validator_block_get() {
         .expect("client should have beacon chain");
 
     let slot = Slot::new(1);
-    let randao_reveal = get_randao_reveal(beacon_chain, slot, spec);
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
 
     let block = env
         .runtime()
@@ -520,21 +900,33 @@ fn validator_block_get() {
             remote_node
                 .http
                 .validator()
-                .produce_block(slot, randao_reveal.clone()),
+                .produce_block(slot, randao_reveal.clone(), None),
         )
         .expect("should fetch block from http api");
 
-    let (expected_block, _state) = node
-        .client
-        .beacon_chain()
-        .expect("client should have beacon chain")
-        .produce_block(randao_reveal, slot)
+    let (expected_block, _state) = beacon_chain
+        .produce_block(randao_reveal.clone(), slot, None)
         .expect("should produce block");
 
     assert_eq!(
         block, expected_block,
         "the block returned from the API should be as expected"
     );
+
+    let graffiti = [42; 32];
+    let block =
+        env.runtime()
+            .block_on(remote_node.http.validator().produce_block(
+                slot,
+                randao_reveal,
+                Some(graffiti),
+            ))
+            .expect("should fetch block with graffiti from http api");
+
+    assert_eq!(
+        block.body.graffiti, graffiti,
+        "the block should contain the requested graffiti"
+    );
 }
 
 #[test]
@@ -700,6 +1092,34 @@ fn fork() {
     );
 }
 
+#[test]
+fn attestation_data() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let slot = beacon_chain.slot().expect("should get slot");
+
+    let attestation_data = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_attestation_data(slot, 0))
+        .expect("should fetch from http api");
+
+    assert_eq!(
+        attestation_data,
+        beacon_chain
+            .produce_unaggregated_attestation(slot, 0)
+            .expect("should produce attestation")
+            .data,
+        "should match attestation produced from the head"
+    );
+}
+
 #[test]
 fn eth2_config() {
     let mut env = build_env();
@@ -766,6 +1186,253 @@ fn get_genesis_state_root() {
     assert_eq!(result, expected, "result should be as expected");
 }
 
+#[test]
+fn get_checkpoint() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain");
+
+    let checkpoint = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_checkpoint())
+        .expect("should fetch from http api");
+
+    // Nothing has been finalized, so the checkpoint is genesis.
+    assert_eq!(checkpoint.block_root, beacon_chain.genesis_block_root);
+    assert_eq!(checkpoint.beacon_block.slot(), Slot::new(0));
+    assert_eq!(
+        checkpoint.state_root,
+        checkpoint.beacon_block.state_root(),
+        "state should be the post-state of the block"
+    );
+    assert_eq!(checkpoint.beacon_state.slot, Slot::new(0));
+}
+
+#[test]
+fn get_block_ssz() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain");
+
+    let genesis_block = beacon_chain
+        .get_block(&beacon_chain.genesis_block_root)
+        .expect("should read db")
+        .expect("should have genesis block");
+
+    for block_id in &["genesis", "head", "finalized", "0"] {
+        let block = env
+            .runtime()
+            .block_on(remote_node.http.lighthouse().get_block_ssz(block_id))
+            .expect("should fetch from http api");
+
+        assert_eq!(
+            block, genesis_block,
+            "block_id {} should be genesis",
+            block_id
+        );
+    }
+
+    let root = format!("{:?}", beacon_chain.genesis_block_root);
+    let block = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_block_ssz(&root))
+        .expect("should fetch by root");
+    assert_eq!(block, genesis_block);
+}
+
+#[test]
+fn get_blocks_archive() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: 13_371_337,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain");
+
+    let mut blocks = vec![beacon_chain
+        .get_block(&beacon_chain.genesis_block_root)
+        .expect("should read db")
+        .expect("should have genesis block")];
+
+    for slot in 1..4 {
+        let slot = Slot::new(slot);
+        let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+        let block = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .validator()
+                    .produce_block(slot, randao_reveal, None),
+            )
+            .expect("should fetch block from http api");
+        let signed_block = sign_block(beacon_chain.clone(), block, spec);
+
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .lighthouse()
+                    .import_blocks_ssz("application/ssz", signed_block.as_ssz_bytes()),
+            )
+            .expect("should import block");
+        blocks.push(signed_block);
+    }
+
+    let unpack = |archive: Vec<u8>| {
+        tar::Archive::new(archive.as_slice())
+            .entries()
+            .expect("should read archive")
+            .map(|entry| {
+                let mut entry = entry.expect("should read entry");
+                let name = entry
+                    .path()
+                    .expect("should have path")
+                    .to_string_lossy()
+                    .into_owned();
+                let mut bytes = vec![];
+                entry.read_to_end(&mut bytes).expect("should read block");
+                let block =
+                    SignedBeaconBlock::<E>::from_ssz_bytes(&bytes).expect("should decode block");
+                (name, block)
+            })
+            .collect::<Vec<_>>()
+    };
+    let expected = |blocks: &[SignedBeaconBlock<E>]| {
+        blocks
+            .iter()
+            .map(|block| {
+                let name = format!(
+                    "{:010}_{:?}.ssz",
+                    block.slot().as_u64(),
+                    block.canonical_root()
+                );
+                (name, block.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let archive = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .get_blocks_archive("genesis", 4),
+        )
+        .expect("should fetch the whole chain");
+    assert_eq!(unpack(archive), expected(&blocks));
+
+    let archive = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_blocks_archive("2", 8))
+        .expect("should fetch the end of the chain");
+    assert_eq!(
+        unpack(archive),
+        expected(&blocks[2..]),
+        "should stop at the head"
+    );
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_blocks_archive("head", 0));
+    assert!(result.is_err(), "should reject a count of zero");
+}
+
+#[test]
+fn import_blocks_ssz() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: 13_371_337,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("should have beacon chain");
+
+    let slot = Slot::new(1);
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+    let block = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .produce_block(slot, randao_reveal, None),
+        )
+        .expect("should fetch block from http api");
+    let signed_block = sign_block(beacon_chain.clone(), block, spec);
+    let block_root = signed_block.canonical_root();
+
+    let response = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .import_blocks_ssz("application/ssz", signed_block.as_ssz_bytes()),
+        )
+        .expect("should import block");
+
+    assert_eq!(
+        response,
+        ImportBlocksResponse {
+            imported_blocks: 1,
+            error: None,
+            head_block_root: block_root,
+        },
+        "the imported block should become the head block"
+    );
+
+    // Importing the same block again is not an error.
+    let response = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .import_blocks_ssz("application/ssz", signed_block.as_ssz_bytes()),
+        )
+        .expect("should import known block");
+    assert_eq!(response.imported_blocks, 0, "should skip the known block");
+
+    let invalid = env.runtime().block_on(
+        remote_node
+            .http
+            .lighthouse()
+            .import_blocks_ssz("application/ssz", vec![42]),
+    );
+    assert!(invalid.is_err(), "should reject invalid ssz");
+}
+
 #[test]
 fn get_genesis_block_root() {
     let mut env = build_env();
@@ -845,6 +1512,56 @@ fn get_all_validators() {
         .for_each(|(response, validator)| compare_validator_response(state, response, validator));
 }
 
+#[test]
+fn get_all_validators_paginated() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.max_results = 3;
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    assert!(
+        state.validators.len() > 3,
+        "test requires more validators than the max results"
+    );
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_all_validators(None))
+        .expect("should fetch all pages from http api");
+
+    assert_eq!(
+        result.len(),
+        state.validators.len(),
+        "should return every validator"
+    );
+    result
+        .iter()
+        .zip(state.validators.iter())
+        .for_each(|(response, validator)| compare_validator_response(state, response, validator));
+
+    let pubkeys = state
+        .validators
+        .iter()
+        .take(4)
+        .map(|v| (&v.pubkey).try_into().expect("should decode pubkey bytes"))
+        .collect::<Vec<_>>();
+
+    assert!(
+        env.runtime()
+            .block_on(remote_node.http.beacon().get_validators(pubkeys, None))
+            .is_err(),
+        "should reject a request for more than the max results"
+    );
+}
+
 #[test]
 fn get_active_validators() {
     let mut env = build_env();
@@ -881,6 +1598,72 @@ fn get_active_validators() {
         .for_each(|(response, validator)| compare_validator_response(state, response, validator));
 }
 
+#[test]
+fn get_validator_statuses() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+
+    let state = &chain.head().expect("should get head").beacon_state;
+    let unknown_pubkey: PublicKeyBytes = generate_deterministic_keypair(state.validators.len() + 1)
+        .pk
+        .into();
+
+    let pubkeys = vec![
+        state.validators[0].pubkey.clone(),
+        unknown_pubkey.clone(),
+        state.validators[2].pubkey.clone(),
+    ];
+    let indices = vec![1, state.validators.len() as u64];
+
+    let results = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .get_validator_statuses("head", pubkeys, indices),
+        )
+        .expect("should fetch from http api");
+
+    assert_eq!(results.len(), 5, "should return a result for each request");
+
+    for (result, expected_index) in results.iter().zip(&[Some(0), None, Some(2), Some(1), None]) {
+        if let Some(i) = expected_index {
+            assert_eq!(
+                result.validator_index,
+                Some(*i as u64),
+                "index should match"
+            );
+            assert_eq!(
+                result.pubkey,
+                Some(state.validators[*i].pubkey.clone()),
+                "pubkey should match"
+            );
+            assert_eq!(
+                result.balance,
+                Some(state.balances[*i]),
+                "balance should match"
+            );
+            assert_eq!(
+                result.status,
+                ValidatorStatus::ActiveOngoing,
+                "genesis validator should be active"
+            );
+        } else {
+            assert_eq!(result.validator_index, None, "should have no index");
+            assert_eq!(result.pubkey, None, "should have no pubkey");
+            assert_eq!(result.balance, None, "should have no balance");
+            assert_eq!(result.status, ValidatorStatus::Unknown, "should be unknown");
+        }
+    }
+}
+
 #[test]
 fn get_committees() {
     let mut env = build_env();
@@ -916,6 +1699,97 @@ fn get_committees() {
     assert_eq!(result, expected, "result should be as expected");
 }
 
+#[test]
+fn get_state_committees() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+
+    let expected = chain
+        .head()
+        .expect("should get head")
+        .beacon_state
+        .get_beacon_committees_at_epoch(RelativeEpoch::Current)
+        .expect("should get committees")
+        .iter()
+        .map(|c| Committee {
+            slot: c.slot,
+            index: c.index,
+            committee: c.committee.to_vec(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut get = |query_params: Vec<(&str, String)>| {
+        env.runtime()
+            .block_on(
+                remote_node.http.beacon().get_state_committees(
+                    "head",
+                    query_params
+                        .into_iter()
+                        .map(|(key, value)| (key.to_string(), value))
+                        .collect(),
+                ),
+            )
+            .expect("should fetch from http api")
+    };
+
+    assert_eq!(get(vec![]), expected, "should return all committees");
+    assert_eq!(
+        get(vec![("epoch", "0".to_string())]),
+        expected,
+        "should return all committees for the epoch"
+    );
+
+    let slot = Slot::new(1);
+    assert_eq!(
+        get(vec![("slot", slot.to_string())]),
+        expected
+            .iter()
+            .filter(|c| c.slot == slot)
+            .cloned()
+            .collect::<Vec<_>>(),
+        "should filter by slot"
+    );
+    assert_eq!(
+        get(vec![("index", "0".to_string())]),
+        expected
+            .iter()
+            .filter(|c| c.index == 0)
+            .cloned()
+            .collect::<Vec<_>>(),
+        "should filter by index"
+    );
+    let head_state_root = chain.head().expect("should get head").beacon_state_root;
+    assert_eq!(
+        get(vec![
+            ("continuation_token", format!("2_{:?}", head_state_root)),
+            ("limit", "3".to_string())
+        ]),
+        expected.iter().skip(2).take(3).cloned().collect::<Vec<_>>(),
+        "should paginate"
+    );
+
+    match env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_state_committees(
+            "head",
+            vec![(
+                "continuation_token".to_string(),
+                format!("2_{:?}", Hash256::repeat_byte(42)),
+            )],
+        )) {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+            assert_eq!(status, 400, "should reject a token for another state")
+        }
+        other => panic!("should reject a token for another state, got {:?}", other),
+    }
+}
+
 #[test]
 fn get_fork_choice() {
     let mut env = build_env();
@@ -1253,6 +2127,48 @@ mod validator_attestation {
     }
 }
 
+#[test]
+fn get_staking_estimate() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let spec = &E::default_spec();
+
+    let estimate = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_staking_estimate(None))
+        .expect("should fetch from http api");
+
+    assert_eq!(estimate.active_validator_count, 8);
+    assert_eq!(
+        estimate.total_active_balance_gwei,
+        8 * spec.max_effective_balance
+    );
+    assert_eq!(estimate.deposit_gwei, spec.max_effective_balance);
+    assert!(estimate.participation_rate >= 0. && estimate.participation_rate <= 1.);
+    assert!(estimate.epoch_reward_gwei > 0);
+    assert!(estimate.annual_reward_gwei > estimate.epoch_reward_gwei);
+    assert!(estimate.activation_delay_seconds > 0);
+    assert_eq!(estimate.break_even_seconds, None);
+
+    let with_cost = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .lighthouse()
+                .get_staking_estimate(Some(estimate.epoch_reward_gwei * 10)),
+        )
+        .expect("should fetch from http api");
+    assert!(
+        with_cost
+            .break_even_seconds
+            .expect("should estimate break even")
+            > with_cost.activation_delay_seconds
+    );
+}
+
 #[test]
 fn get_health() {
     let mut env = build_env();
@@ -1264,3 +2180,84 @@ fn get_health() {
         .block_on(remote_node.http.node().get_health())
         .unwrap();
 }
+
+#[test]
+fn update_peering() {
+    const ENR: &str = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let peering = env
+        .runtime()
+        .block_on(remote_node.http.lighthouse().get_peering())
+        .expect("should fetch from http api");
+    assert!(peering.trusted_peers.is_empty());
+
+    let invalid = PeeringUpdateRequest {
+        add_trusted_peers: vec!["not an enr".to_string()],
+        ..PeeringUpdateRequest::default()
+    };
+    assert!(
+        env.runtime()
+            .block_on(remote_node.http.lighthouse().update_peering(invalid))
+            .is_err(),
+        "should reject an invalid enr"
+    );
+
+    let add = PeeringUpdateRequest {
+        add_trusted_peers: vec![ENR.to_string()],
+        rediscover: true,
+        ..PeeringUpdateRequest::default()
+    };
+    env.runtime()
+        .block_on(remote_node.http.lighthouse().update_peering(add))
+        .expect("should update peering");
+
+    // The update is applied by the network service, after the response.
+    let peering = wait_for_peering(&mut env, &remote_node, |peering| {
+        !peering.trusted_peers.is_empty()
+    });
+    assert_eq!(peering.trusted_peers.len(), 1);
+    assert!(peering.trusted_peers[0]
+        .enr
+        .ends_with(ENR.trim_start_matches("enr:")));
+
+    let remove = PeeringUpdateRequest {
+        remove_trusted_peers: vec![peering.trusted_peers[0].peer_id.clone()],
+        ..PeeringUpdateRequest::default()
+    };
+    env.runtime()
+        .block_on(remote_node.http.lighthouse().update_peering(remove))
+        .expect("should update peering");
+
+    wait_for_peering(&mut env, &remote_node, |peering| {
+        peering.trusted_peers.is_empty()
+    });
+}
+
+/// Polls the peering of `remote_node` until `done` returns `true` for it, returning the final
+/// response. Panics if `done` is not satisfied within a few seconds.
+fn wait_for_peering<F: Fn(&PeeringResponse) -> bool>(
+    env: &mut Environment<E>,
+    remote_node: &RemoteBeaconNode<E>,
+    done: F,
+) -> PeeringResponse {
+    env.runtime().block_on(async {
+        for _ in 0..100 {
+            let peering = remote_node
+                .http
+                .lighthouse()
+                .get_peering()
+                .await
+                .expect("should fetch from http api");
+            if done(&peering) {
+                return peering;
+            }
+            tokio::time::delay_for(Duration::from_millis(50)).await;
+        }
+        panic!("peering was not updated in time");
+    })
+}
@@ -23,7 +23,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("freezer-dir")
                 .long("freezer-dir")
                 .value_name("DIR")
-                .help("Data directory for the freezer database.")
+                .help("Data directory for the freezer database. May be on a different disk to \
+                       the rest of the database, which remains in the beacon node dir. Defaults \
+                       to freezer_db/ inside the beacon node dir.")
                 .takes_value(true)
         )
         /*
@@ -41,10 +43,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("listen-address")
                 .long("listen-address")
                 .value_name("ADDRESS")
-                .help("The address lighthouse will listen for UDP and TCP connections.")
+                .help("The address lighthouse will listen for UDP and TCP connections. This may \
+                       be an IPv4 or IPv6 address, which also determines the address family used \
+                       for discovery.")
                 .default_value("0.0.0.0")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("listen-address-v6")
+                .long("listen-address-v6")
+                .value_name("ADDRESS")
+                .help("An additional IPv6 address lighthouse will listen for TCP connections on, \
+                       allowing dual-stack operation. Requires --listen-address to be an IPv4 \
+                       address. Discovery continues to use --listen-address.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("prefer-ipv6")
+                .long("prefer-ipv6")
+                .help("When a peer advertises both IPv4 and IPv6 addresses, dial the IPv6 address \
+                       first. By default IPv4 addresses are dialed first.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("port")
                 .long("port")
@@ -102,6 +122,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("enr-udp-port")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("enr-address-v6")
+                .long("enr-address-v6")
+                .value_name("ADDRESS")
+                .help("The IPv6 address to broadcast to other peers on how to reach this node \
+                       over --listen-address-v6.")
+                .requires("listen-address-v6")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("enr-tcp6-port")
+                .long("enr-tcp6-port")
+                .value_name("PORT")
+                .help("The TCP port to broadcast to other peers on how to reach this node over \
+                       --listen-address-v6. The --port flag is used if this is not set.")
+                .requires("listen-address-v6")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enr-match")
                 .short("e")
@@ -143,6 +181,34 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        attestations. This should only be used for testing networking elements. The \
                        value must like in the range 1-100. Default is 100.")
         )
+        .arg(
+            Arg::with_name("gossip-seen-cache-size")
+                .long("gossip-seen-cache-size")
+                .value_name("INTEGER")
+                .takes_value(true)
+                .help("The number of recently seen gossip message ids to remember, so that \
+                       duplicate messages are not re-validated. Default is 100000.")
+        )
+        .arg(
+            Arg::with_name("gossip-seen-ttl")
+                .long("gossip-seen-ttl")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .help("If set, a seen gossip message id is forgotten after this many seconds, \
+                       even if the seen cache is not full. By default ids are only forgotten \
+                       when the cache is full.")
+        )
+        .arg(
+            Arg::with_name("subnet-subscription-lookahead")
+                .long("subnet-subscription-lookahead")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .help("The number of additional slots before a validator's attestation duty that \
+                       the node subscribes to the duty's subnet, giving its mesh longer to form \
+                       (e.g., SLOTS_PER_EPOCH to subscribe an epoch ahead). Subscriptions cannot \
+                       begin before validator clients announce their duties. By default the node \
+                       subscribes a third of a slot ahead.")
+        )
         /* REST API related arguments */
         .arg(
             Arg::with_name("http")
@@ -166,6 +232,37 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("5052")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-max-results")
+                .long("http-max-results")
+                .value_name("INTEGER")
+                .help("Set the maximum number of items (e.g., validators or committees) returned \
+                       in a single response, or accepted in a single bulk request, by the \
+                       RESTful HTTP API server. Larger lists of results are paginated.")
+                .default_value("16384")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-checkpoint-rate-limit")
+                .long("http-checkpoint-rate-limit")
+                .value_name("INTEGER")
+                .help("Set the maximum number of requests per minute served by the \
+                       /beacon/checkpoint endpoint of the RESTful HTTP API server. Each \
+                       response contains an entire BeaconState. Set to zero to disable the \
+                       endpoint.")
+                .default_value("10")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-require-auth")
+                .long("http-require-auth")
+                .help("Require requests to the /validator endpoints of the RESTful HTTP API \
+                       server, and requests other than GET to the /lighthouse endpoints, \
+                       to present the token in the api-token.txt file in the datadir, \
+                       which is generated if it does not exist. Supply this file to the \
+                       validator client with --server-auth-token.")
+                .takes_value(false),
+        )
         /* Websocket related arguments */
         .arg(
             Arg::with_name("ws")
@@ -226,8 +323,110 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("block-cache-size")
                 .long("block-cache-size")
                 .value_name("SIZE")
-                .help("Specifies how many blocks the database should cache in memory [default: 5]")
+                .help("Specifies how many blocks the database should cache in memory [default: 64]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("db-checksums")
+                .long("db-checksums")
+                .help("Append a checksum to each value written to a new database and verify it \
+                       whenever the value is read, so that corruption on disk is detected rather \
+                       than decoded. Has no effect on an existing database, which keeps the \
+                       setting it was created with.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("prune-historic")
+                .long("prune-historic")
+                .help("Delete blocks and finalized states once they are older than the history \
+                       which must be served to peers (at least the weak subjectivity period), \
+                       rather than keeping them forever. Reduces disk usage for nodes which only \
+                       perform validator duties. Deleted history is never restored.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("record-inputs")
+                .long("record-inputs")
+                .value_name("PATH")
+                .help("If present, appends every block, attestation and fork choice run supplied \
+                       to the chain to the given file, so they may later be replayed with \
+                       `lcli replay-inputs`.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("differential-test-command")
+                .long("differential-test-command")
+                .value_name("PATH")
+                .help("If present, runs the given reference implementation with the paths of the \
+                       SSZ pre-state and block of each imported block, expecting it to print the \
+                       0x-prefixed post-state root. The pre-state is the post-state of the parent \
+                       block, so the command must process the slots up to the block as well as \
+                       the block itself. Any divergence is logged and its inputs are \
+                       kept in the divergences directory of the datadir. Intended for canary \
+                       nodes.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("attestation-self-check")
+                .long("attestation-self-check")
+                .help("If present, compares the attestation data produced for the validator \
+                       client with the aggregates seen on gossip for the same slots, reporting the \
+                       divergence rate as a metric and warning if this node consistently \
+                       disagrees with the network.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("max-skip-slots")
+                .long("max-skip-slots")
+                .value_name("SLOTS")
+                .help("If present, refuses to process blocks and attestations which skip more \
+                       than this many slots from their parent or head block, rather than \
+                       computing the state transition across them. Protects against messages \
+                       crafted to waste CPU time, but may prevent a node from following a chain \
+                       which genuinely had no blocks for longer.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("event-journal-minutes")
+                .long("event-journal-minutes")
+                .value_name("MINUTES")
+                .help("If present, retains the head and finalization events of the given number \
+                       of minutes in a journal in the datadir, so that websocket consumers may \
+                       recover the events they missed from /lighthouse/events.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("historic-attestations")
+                .long("historic-attestations")
+                .value_name("STRATEGY")
+                .help("Determines whether attestations more than one epoch older than the \
+                       block are included in produced blocks. With \"compete\" they compete for space with current \
+                       attestations, with \"when-space-remains\" they only fill space left by \
+                       current attestations and with \"never\" they are never included.")
                 .takes_value(true)
+                .possible_values(&["compete", "when-space-remains", "never"])
+                .default_value("compete")
+        )
+        .arg(
+            Arg::with_name("historic-attestation-min-reward")
+                .long("historic-attestation-min-reward")
+                .value_name("GWEI")
+                .help("The minimum estimated proposer reward for including an attestation more \
+                       than one epoch old, when using --historic-attestations \
+                       when-space-remains.")
+                .takes_value(true)
+                .default_value("0")
+        )
+
+        /*
+         * Dry run.
+         */
+        .arg(
+            Arg::with_name("dump-config")
+                .long("dump-config")
+                .help("If present, prints the effective configuration (resolved from the CLI \
+                       flags, the config file in the datadir and the testnet config) as YAML and \
+                       exits without starting the node. The datadir is read but not modified.")
         )
 
         /*
@@ -236,6 +435,6 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("purge-db")
                 .long("purge-db")
-                .help("If present, the chain database will be deleted. Use with caution.")
+                .help("If present, the chain database (including the freezer database, which may be in --freezer-dir) will be deleted. Use with caution.")
         )
 }
@@ -1,18 +1,19 @@
-use beacon_chain::builder::PUBKEY_CACHE_FILENAME;
+use beacon_chain::{builder::PUBKEY_CACHE_FILENAME, AttestationInclusionStrategy};
 use clap::ArgMatches;
-use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{config::DEFAULT_DATADIR, ClientConfig, ClientGenesis};
 use eth2_libp2p::{Enr, Multiaddr};
 use eth2_testnet_config::Eth2TestnetConfig;
+use serde_derive::Serialize;
 use slog::{crit, info, Logger};
 use ssz::Encode;
 use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
-use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
 use std::path::PathBuf;
-use types::{ChainSpec, EthSpec};
+use std::time::Duration;
+use types::{ChainSpec, EthSpec, YamlConfig};
 
 pub const CLIENT_CONFIG_FILENAME: &str = "beacon-node.toml";
 pub const BEACON_NODE_DIR: &str = "beacon";
@@ -36,8 +37,16 @@ pub fn get_config<E: EthSpec>(
 
     client_config.data_dir = get_data_dir(cli_args);
 
+    // With `--dump-config` the configuration is only printed, so the datadir must not be changed.
+    let dry_run = cli_args.is_present("dump-config");
+
+    // Parsed before purging, so that a configured freezer DB is removed along with the hot DB.
+    if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
+        client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
+    }
+
     // If necessary, remove any existing database and configuration
-    if client_config.data_dir.exists() && cli_args.is_present("purge-db") {
+    if client_config.data_dir.exists() && cli_args.is_present("purge-db") && !dry_run {
         // Remove the chain_db.
         fs::remove_dir_all(
             client_config
@@ -46,13 +55,14 @@ pub fn get_config<E: EthSpec>(
         )
         .map_err(|err| format!("Failed to remove chain_db: {}", err))?;
 
-        // Remove the freezer db.
-        fs::remove_dir_all(
-            client_config
-                .get_freezer_db_path()
-                .ok_or("Failed to get freezer db path".to_string())?,
-        )
-        .map_err(|err| format!("Failed to remove chain_db: {}", err))?;
+        // Remove the freezer db, which may be outside the datadir and not yet exist.
+        let freezer_db_path = client_config
+            .get_freezer_db_path()
+            .ok_or("Failed to get freezer db path".to_string())?;
+        if freezer_db_path.exists() {
+            fs::remove_dir_all(freezer_db_path)
+                .map_err(|err| format!("Failed to remove freezer_db: {}", err))?;
+        }
 
         // Remove the pubkey cache file if it exists
         let pubkey_cache_file = client_config.data_dir.join(PUBKEY_CACHE_FILENAME);
@@ -63,8 +73,10 @@ pub fn get_config<E: EthSpec>(
     }
 
     // Create `datadir` and any non-existing parent directories.
-    fs::create_dir_all(&client_config.data_dir)
-        .map_err(|e| format!("Failed to create data dir: {}", e))?;
+    if !dry_run {
+        fs::create_dir_all(&client_config.data_dir)
+            .map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
 
     // logs the chosen data directory
     let mut log_dir = client_config.data_dir.clone();
@@ -102,6 +114,22 @@ pub fn get_config<E: EthSpec>(
         client_config.network.listen_address = listen_address;
     }
 
+    if let Some(listen_address_str) = cli_args.value_of("listen-address-v6") {
+        if client_config.network.listen_address.is_ipv6() {
+            return Err(
+                "--listen-address-v6 requires --listen-address to be an IPv4 address".to_string(),
+            );
+        }
+        let listen_address = listen_address_str
+            .parse::<Ipv6Addr>()
+            .map_err(|_| format!("Invalid IPv6 listen address: {:?}", listen_address_str))?;
+        client_config.network.listen_address_v6 = Some(listen_address);
+    }
+
+    if cli_args.is_present("prefer-ipv6") {
+        client_config.network.prefer_ipv6 = true;
+    }
+
     if let Some(max_peers_str) = cli_args.value_of("maxpeers") {
         client_config.network.max_peers = max_peers_str
             .parse::<usize>()
@@ -157,6 +185,14 @@ pub fn get_config<E: EthSpec>(
         );
     }
 
+    if let Some(enr_tcp6_port_str) = cli_args.value_of("enr-tcp6-port") {
+        client_config.network.enr_tcp6_port = Some(
+            enr_tcp6_port_str
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid ENR TCP6 port: {}", enr_tcp6_port_str))?,
+        );
+    }
+
     if cli_args.is_present("enr-match") {
         // set the enr address to localhost if the address is 0.0.0.0
         if client_config.network.listen_address
@@ -164,10 +200,28 @@ pub fn get_config<E: EthSpec>(
         {
             client_config.network.enr_address =
                 Some("127.0.0.1".parse::<IpAddr>().expect("valid ip addr"));
+        } else if client_config.network.listen_address == IpAddr::V6(Ipv6Addr::UNSPECIFIED) {
+            client_config.network.enr_address = Some(IpAddr::V6(Ipv6Addr::LOCALHOST));
         } else {
             client_config.network.enr_address = Some(client_config.network.listen_address);
         }
         client_config.network.enr_udp_port = Some(client_config.network.discovery_port);
+
+        if let Some(listen_address_v6) = client_config.network.listen_address_v6 {
+            client_config.network.enr_address_v6 = if listen_address_v6.is_unspecified() {
+                Some(Ipv6Addr::LOCALHOST)
+            } else {
+                Some(listen_address_v6)
+            };
+        }
+    }
+
+    if let Some(enr_address_str) = cli_args.value_of("enr-address-v6") {
+        client_config.network.enr_address_v6 = Some(
+            enr_address_str
+                .parse::<Ipv6Addr>()
+                .map_err(|_| format!("Invalid ENR IPv6 address: {:?}", enr_address_str))?,
+        );
     }
 
     if let Some(enr_address) = cli_args.value_of("enr-address") {
@@ -226,6 +280,28 @@ pub fn get_config<E: EthSpec>(
         client_config.network.propagation_percentage = Some(percentage);
     }
 
+    if let Some(cache_size_str) = cli_args.value_of("gossip-seen-cache-size") {
+        let cache_size = cache_size_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid gossip seen cache size: {}", cache_size_str))?;
+        if cache_size == 0 {
+            return Err("Gossip seen cache size must be greater than zero".to_string());
+        }
+        client_config.network.seen_gossip_cache_size = cache_size;
+    }
+
+    if let Some(ttl_str) = cli_args.value_of("gossip-seen-ttl") {
+        let ttl = ttl_str
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid gossip seen TTL: {}", ttl_str))?;
+        client_config.network.seen_gossip_cache_ttl = Some(Duration::from_secs(ttl));
+    }
+
+    if let Some(lookahead) = clap_utils::parse_optional(cli_args, "subnet-subscription-lookahead")?
+    {
+        client_config.network.subnet_subscription_lookahead_slots = lookahead;
+    }
+
     /*
      * Http server
      */
@@ -246,6 +322,32 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "http-port is not a valid u16.")?;
     }
 
+    if let Some(max_results) = cli_args.value_of("http-max-results") {
+        client_config.rest_api.max_results = max_results
+            .parse::<usize>()
+            .map_err(|_| "http-max-results is not a valid usize.")?;
+        if client_config.rest_api.max_results == 0 {
+            return Err("http-max-results must be greater than zero.".into());
+        }
+    }
+
+    if let Some(rate_limit) = cli_args.value_of("http-checkpoint-rate-limit") {
+        client_config.rest_api.checkpoint_requests_per_minute = rate_limit
+            .parse::<usize>()
+            .map_err(|_| "http-checkpoint-rate-limit is not a valid usize.")?;
+    }
+
+    if cli_args.is_present("http-require-auth") {
+        client_config.rest_api.auth_token_path =
+            Some(client_config.data_dir.join(rest_api::API_TOKEN_FILENAME));
+    }
+
+    // A validator client given as a subcommand runs in this process, and reaches the API over a
+    // channel rather than a socket.
+    if cli_args.subcommand_matches("validator_client").is_some() {
+        client_config.rest_api.local_api = true;
+    }
+
     /*
      * Websocket server
      */
@@ -290,10 +392,6 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.endpoint = val.to_string();
     }
 
-    if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
-        client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
-    }
-
     if let Some(slots_per_restore_point) = cli_args.value_of("slots-per-restore-point") {
         client_config.store.slots_per_restore_point = slots_per_restore_point
             .parse()
@@ -311,6 +409,39 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    client_config.store.checksums = cli_args.is_present("db-checksums");
+    client_config.store.prune_historic = cli_args.is_present("prune-historic");
+
+    if let Some(path) = cli_args.value_of("record-inputs") {
+        client_config.record_inputs = Some(PathBuf::from(path));
+    }
+
+    if let Some(command) = cli_args.value_of("differential-test-command") {
+        client_config.differential_test_command = Some(PathBuf::from(command));
+    }
+
+    client_config.attestation_self_check = cli_args.is_present("attestation-self-check");
+
+    if let Some(max_skip_slots) = cli_args.value_of("max-skip-slots") {
+        client_config.max_skip_slots = Some(
+            max_skip_slots
+                .parse()
+                .map_err(|_| "max-skip-slots is not a valid integer".to_string())?,
+        );
+    }
+
+    client_config.event_journal_minutes =
+        clap_utils::parse_optional(cli_args, "event-journal-minutes")?;
+
+    client_config.attestation_inclusion_strategy = match cli_args.value_of("historic-attestations")
+    {
+        Some("when-space-remains") => AttestationInclusionStrategy::WhenSpaceRemains {
+            min_reward: clap_utils::parse_required(cli_args, "historic-attestation-min-reward")?,
+        },
+        Some("never") => AttestationInclusionStrategy::Never,
+        _ => AttestationInclusionStrategy::Compete,
+    };
+
     if spec_constants != client_config.spec_constants {
         crit!(log, "Specification constants do not match.";
               "client_config" => client_config.spec_constants.to_string(),
@@ -343,8 +474,7 @@ pub fn get_config<E: EthSpec>(
     /*
      * Load the eth2 testnet dir to obtain some additional config values.
      */
-    let eth2_testnet_config: Eth2TestnetConfig<E> =
-        get_eth2_testnet_config(&client_config.testnet_dir)?;
+    let eth2_testnet_config: Eth2TestnetConfig<E> = get_eth2_testnet_config(cli_args)?;
 
     client_config.eth1.deposit_contract_address =
         format!("{:?}", eth2_testnet_config.deposit_contract_address()?);
@@ -369,13 +499,53 @@ pub fn get_config<E: EthSpec>(
         client_config.genesis = ClientGenesis::DepositContract;
     }
 
-    if !config_file_existed {
+    if !config_file_existed && !dry_run {
         write_to_file(config_file_path, &client_config)?;
     }
 
     Ok(client_config)
 }
 
+/// The configuration with which a beacon node would start, as printed by `--dump-config`.
+#[derive(Serialize)]
+pub struct EffectiveConfig<'a> {
+    pub client: &'a ClientConfig,
+    /// A description of `client.genesis`, which is not otherwise serialized.
+    pub genesis: String,
+    pub chain_spec: YamlConfig,
+}
+
+/// Returns the configuration with which a beacon node would start, as a YAML document.
+pub fn get_effective_config<E: EthSpec>(
+    client_config: &ClientConfig,
+    spec: &ChainSpec,
+) -> Result<String, String> {
+    let genesis = match &client_config.genesis {
+        ClientGenesis::Interop {
+            validator_count,
+            genesis_time,
+        } => format!(
+            "interop with {} validators at genesis time {}",
+            validator_count, genesis_time
+        ),
+        ClientGenesis::FromStore => "from store".to_string(),
+        ClientGenesis::DepositContract => "from deposit contract".to_string(),
+        ClientGenesis::SszBytes {
+            genesis_state_bytes,
+        } => format!(
+            "from testnet genesis state ({} bytes)",
+            genesis_state_bytes.len()
+        ),
+    };
+
+    serde_yaml::to_string(&EffectiveConfig {
+        client: client_config,
+        genesis,
+        chain_spec: YamlConfig::from_spec::<E>(spec),
+    })
+    .map_err(|e| format!("Unable to serialize configuration: {:?}", e))
+}
+
 /// Gets the datadir which should be used.
 pub fn get_data_dir(cli_args: &ArgMatches) -> PathBuf {
     // Read the `--datadir` flag.
@@ -399,18 +569,12 @@ pub fn get_testnet_dir(cli_args: &ArgMatches) -> Option<PathBuf> {
     }
 }
 
-/// If `testnet_dir` is `Some`, returns the `Eth2TestnetConfig` at that path or returns an error.
-/// If it is `None`, returns the "hard coded" config.
+/// Returns the `Eth2TestnetConfig` of the network named by the `--network` flag, or at the path of
+/// the `--testnet-dir` flag. If neither is present, returns the "hard coded" config.
 pub fn get_eth2_testnet_config<E: EthSpec>(
-    testnet_dir: &Option<PathBuf>,
+    cli_args: &ArgMatches,
 ) -> Result<Eth2TestnetConfig<E>, String> {
-    Ok(if let Some(testnet_dir) = testnet_dir {
-        Eth2TestnetConfig::load(testnet_dir.clone())
-            .map_err(|e| format!("Unable to open testnet dir at {:?}: {}", testnet_dir, e))?
-    } else {
-        Eth2TestnetConfig::hard_coded()
-            .map_err(|e| format!("{} Error : {}", BAD_TESTNET_DIR_MESSAGE, e))?
-    })
+    clap_utils::parse_testnet_config_with_hardcoded_default(cli_args, "network", "testnet-dir")
 }
 
 /// A bit of hack to find an unused port.
@@ -16,7 +16,7 @@ use beacon_chain::{
     slot_clock::SystemTimeSlotClock,
 };
 use clap::ArgMatches;
-use config::get_config;
+use config::{get_config, get_effective_config};
 use environment::RuntimeContext;
 use slog::{info, warn};
 use std::ops::{Deref, DerefMut};
@@ -63,6 +63,21 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         Self::new(context, client_config).await
     }
 
+    /// Returns the configuration with which `Self::new_from_cli` would start a beacon node, as a
+    /// YAML document, without starting it.
+    pub fn effective_config_from_cli<'b>(
+        context: &RuntimeContext<E>,
+        matches: &ArgMatches<'b>,
+    ) -> Result<String, String> {
+        let client_config = get_config::<E>(
+            matches,
+            &context.eth2_config.spec_constants,
+            &context.eth2_config().spec,
+            context.log.clone(),
+        )?;
+        get_effective_config::<E>(&client_config, &context.eth2_config().spec)
+    }
+
     /// Starts a new beacon node `Client` in the given `environment`.
     ///
     /// Client behaviour is defined by the given `client_config`.
@@ -126,6 +141,12 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             builder
         };
 
+        let builder = if client_config.rest_api.local_api {
+            builder.local_api(&client_config, &http_eth2_config)?
+        } else {
+            builder
+        };
+
         Ok(Self(builder.build()))
     }
 
@@ -2,7 +2,7 @@ use serde_derive::{Deserialize, Serialize};
 use types::{EthSpec, MinimalEthSpec};
 
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
-pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 5;
+pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 64;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -11,6 +11,16 @@ pub struct StoreConfig {
     pub slots_per_restore_point: u64,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
+    /// Whether to append a checksum to each value and verify it when the value is read.
+    ///
+    /// Only takes effect when a database is created, since an existing database keeps the setting
+    /// it was created with.
+    pub checksums: bool,
+    /// Whether to delete blocks and states once they are older than the history which must be
+    /// served to peers, rather than retaining them indefinitely.
+    ///
+    /// Deleted history is never restored, even if this is later disabled.
+    pub prune_historic: bool,
 }
 
 impl Default for StoreConfig {
@@ -19,6 +29,8 @@ impl Default for StoreConfig {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            checksums: false,
+            prune_historic: false,
         }
     }
 }
@@ -13,6 +13,7 @@ pub enum Error {
     DBError { message: String },
     RlpError(String),
     BlockNotFound(Hash256),
+    ChecksumMismatch { column: String, key: Vec<u8> },
 }
 
 impl From<DecodeError> for Error {
@@ -1,17 +1,21 @@
+use crate::chunked_iter::ChunkedVectorIter;
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, RandaoMixes, StateRoots,
 };
 use crate::config::StoreConfig;
-use crate::forwards_iter::HybridForwardsBlockRootsIterator;
+use crate::forwards_iter::{FrozenForwardsBlockRootsIterator, HybridForwardsBlockRootsIterator};
 use crate::impls::beacon_state::store_full_state;
 use crate::iter::{ParentRootBlockIterator, StateRootsIterator};
+use crate::metadata::{
+    database_id_key, schema_version_key, DatabaseId, SchemaVersion, CURRENT_SCHEMA_VERSION,
+};
 use crate::metrics;
 use crate::{
     leveldb_store::LevelDB, DBColumn, Error, PartialBeaconState, SimpleStoreItem, Store, StoreOp,
 };
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
-use slog::{debug, trace, warn, Logger};
+use slog::{debug, info, trace, warn, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use state_processing::{
@@ -26,6 +30,8 @@ use types::*;
 
 /// 32-byte key for accessing the `split` of the freezer DB.
 pub const SPLIT_DB_KEY: &str = "FREEZERDBSPLITFREEZERDBSPLITFREE";
+/// 32-byte key for accessing the slot below which historic blocks and states have been pruned.
+pub const HISTORIC_PRUNED_SLOT_KEY: &str = "HISTORICPRUNEDSLOTHISTORICPRUNED";
 
 /// On-disk database that stores finalized states efficiently.
 ///
@@ -45,6 +51,10 @@ pub struct HotColdDB<E: EthSpec> {
     /// The hot database also contains all blocks.
     pub(crate) hot_db: LevelDB<E>,
     /// LRU cache of deserialized blocks. Updated whenever a block is loaded.
+    ///
+    /// Blocks are stored and cached independently of states, so serving a block (e.g., to a
+    /// `BlocksByRoot` request or the HTTP API) never loads a state and is unaffected by the
+    /// number of states held in memory elsewhere.
     block_cache: Mutex<LruCache<Hash256, SignedBeaconBlock<E>>>,
     /// Chain spec.
     spec: ChainSpec,
@@ -54,6 +64,46 @@ pub struct HotColdDB<E: EthSpec> {
     _phantom: PhantomData<E>,
 }
 
+/// Where a state is stored, as returned by `HotColdDB::state_availability`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateAvailability {
+    /// A full state in the hot database.
+    Hot,
+    /// A summary in the hot database, loaded by replaying `replay_slots` slots on top of the full
+    /// state at the start of its epoch.
+    HotSummary { replay_slots: u64 },
+    /// A restore point in the freezer database.
+    RestorePoint,
+    /// A frozen state between restore points, loaded by replaying `replay_slots` slots on top of
+    /// the previous restore point.
+    ColdIntermediate { replay_slots: u64 },
+    /// Not stored, e.g., because it was pruned.
+    Missing,
+}
+
+impl StateAvailability {
+    /// The database which holds the state, if any.
+    pub fn location(&self) -> &'static str {
+        match self {
+            StateAvailability::Hot | StateAvailability::HotSummary { .. } => "hot",
+            StateAvailability::RestorePoint | StateAvailability::ColdIntermediate { .. } => {
+                "freezer"
+            }
+            StateAvailability::Missing => "missing",
+        }
+    }
+
+    /// The number of slots that must be replayed to load the state, if it is stored.
+    pub fn replay_slots(&self) -> Option<u64> {
+        match self {
+            StateAvailability::Hot | StateAvailability::RestorePoint => Some(0),
+            StateAvailability::HotSummary { replay_slots }
+            | StateAvailability::ColdIntermediate { replay_slots } => Some(*replay_slots),
+            StateAvailability::Missing => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum HotColdDBError {
     /// Recoverable error indicating that the database freeze point couldn't be updated
@@ -81,6 +131,34 @@ pub enum HotColdDBError {
         slots_per_epoch: u64,
     },
     RestorePointBlockHashError(BeaconStateError),
+    /// The database was written by a later release, which must be used to migrate it to
+    /// `CURRENT_SCHEMA_VERSION` first.
+    SchemaVersionTooNew {
+        on_disk: SchemaVersion,
+        current: SchemaVersion,
+    },
+    UnsupportedSchemaMigration {
+        from: SchemaVersion,
+        to: SchemaVersion,
+    },
+    /// The database was opened without migration, but its schema is not the current one.
+    SchemaVersionMismatch {
+        on_disk: SchemaVersion,
+        current: SchemaVersion,
+    },
+    /// The database cannot be migrated down to `to`, since it holds data which releases using
+    /// that version would misinterpret.
+    SchemaDowngradeRefused {
+        from: SchemaVersion,
+        to: SchemaVersion,
+        reason: &'static str,
+    },
+    /// The hot and cold databases were not created together, e.g., because the freezer directory
+    /// was changed or the disk containing it is not mounted.
+    MismatchedFreezerDatabase {
+        hot_id: Option<DatabaseId>,
+        cold_id: Option<DatabaseId>,
+    },
 }
 
 impl<E: EthSpec> Store<E> for HotColdDB<E> {
@@ -284,6 +362,11 @@ impl<E: EthSpec> Store<E> for HotColdDB<E> {
             store.delete_state(&state_root, slot)?;
         }
 
+        // 4. Delete history which is no longer retained, if configured to.
+        if store.config.prune_historic {
+            Self::prune_historic(store.clone())?;
+        }
+
         debug!(
             store.log,
             "Freezer migration complete";
@@ -351,8 +434,8 @@ impl<E: EthSpec> HotColdDB<E> {
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            cold_db: LevelDB::open(cold_path, config.checksums)?,
+            hot_db: LevelDB::open(hot_path, config.checksums)?,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
             config,
             spec,
@@ -360,14 +443,277 @@ impl<E: EthSpec> HotColdDB<E> {
             _phantom: PhantomData,
         };
 
+        db.check_database_id()?;
+
         // Load the previous split slot from the database (if any). This ensures we can
         // stop and restart correctly.
         if let Some(split) = db.load_split()? {
             *db.split.write() = split;
         }
+
+        if !db.config.prune_historic {
+            if let Some(slot) = db.get_historic_pruned_slot()? {
+                warn!(
+                    db.log,
+                    "Database history has been pruned";
+                    "msg" => "blocks and states before this slot were deleted and will not be \
+                              restored",
+                    "slot" => slot
+                );
+            }
+        }
+
+        if db.hot_db.checksums() != db.config.checksums {
+            warn!(
+                db.log,
+                "Ignoring database checksum setting";
+                "reason" => "the setting of an existing database cannot be changed",
+                "checksums" => db.hot_db.checksums()
+            );
+        }
+
+        // Upgrade the schema of a database written by an earlier release, or refuse to open one
+        // written by a later release.
+        let schema_version = db.schema_version()?;
+        if schema_version > CURRENT_SCHEMA_VERSION {
+            return Err(HotColdDBError::SchemaVersionTooNew {
+                on_disk: schema_version,
+                current: CURRENT_SCHEMA_VERSION,
+            }
+            .into());
+        } else if schema_version < CURRENT_SCHEMA_VERSION {
+            db.migrate_schema(schema_version, CURRENT_SCHEMA_VERSION)?;
+        }
+
+        let deleted = db.delete_temporary_states()?;
+        if deleted > 0 {
+            info!(
+                db.log,
+                "Deleted temporary states";
+                "reason" => "the import of their block was interrupted",
+                "count" => deleted
+            );
+        }
+
+        Ok(db)
+    }
+
+    /// Open an existing database without migrating its schema or writing to it, e.g., to inspect
+    /// it whilst the beacon node is stopped.
+    ///
+    /// Fails if the schema version of the database is not `CURRENT_SCHEMA_VERSION`, since the
+    /// database could otherwise be misread.
+    pub fn open_read_only(
+        hot_path: &Path,
+        cold_path: &Path,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<Self, Error> {
+        Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+
+        let db = HotColdDB {
+            split: RwLock::new(Split::default()),
+            cold_db: LevelDB::open_read_only(cold_path)?,
+            hot_db: LevelDB::open_read_only(hot_path)?,
+            block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            config,
+            spec,
+            log,
+            _phantom: PhantomData,
+        };
+
+        let schema_version = db.schema_version()?;
+        if schema_version != CURRENT_SCHEMA_VERSION {
+            return Err(HotColdDBError::SchemaVersionMismatch {
+                on_disk: schema_version,
+                current: CURRENT_SCHEMA_VERSION,
+            }
+            .into());
+        }
+
+        if let Some(split) = db.load_split()? {
+            *db.split.write() = split;
+        }
+
         Ok(db)
     }
 
+    /// Returns the schema version of the existing database at `hot_path`, without migrating or
+    /// writing to it.
+    pub fn read_schema_version(hot_path: &Path) -> Result<SchemaVersion, Error> {
+        // The schema version is never checksummed, so it may be read from any database.
+        Ok(LevelDB::<E>::open_read_only(hot_path)?
+            .get(&schema_version_key())?
+            .unwrap_or(SchemaVersion(0)))
+    }
+
+    /// Deletes the states marked with a `TemporaryState` whose block was never stored, i.e., those
+    /// left behind by a block import which was interrupted (e.g., by a crash), and removes all
+    /// markers.
+    ///
+    /// Returns the number of states deleted.
+    pub fn delete_temporary_states(&self) -> Result<usize, Error> {
+        let mut deleted = 0;
+
+        for state_root in self.hot_db.keys_in_column(DBColumn::BeaconStateTemporary) {
+            let block_stored = match self.hot_db.get::<TemporaryState>(&state_root)? {
+                Some(temporary) => self
+                    .hot_db
+                    .exists::<SignedBeaconBlock<E>>(&temporary.block_root)?,
+                None => true,
+            };
+
+            if !block_stored {
+                self.hot_db
+                    .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())?;
+                self.hot_db
+                    .key_delete(DBColumn::BeaconState.into(), state_root.as_bytes())?;
+                deleted += 1;
+            }
+
+            self.hot_db.delete::<TemporaryState>(&state_root)?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Checks that the hot and cold databases were created together, or records a new
+    /// `DatabaseId` in both if neither has one (i.e., they are new or were created before IDs
+    /// were recorded).
+    ///
+    /// Databases without IDs are only accepted if the cold database holds the genesis restore
+    /// point exactly when the hot database has a split point (i.e., states have been migrated to
+    /// the cold database), so an empty freezer is not accepted in place of a populated one.
+    fn check_database_id(&self) -> Result<(), Error> {
+        let key = database_id_key();
+        let hot_id: Option<DatabaseId> = self.hot_db.get(&key)?;
+        let cold_id: Option<DatabaseId> = self.cold_db.get(&key)?;
+
+        match (hot_id, cold_id) {
+            (Some(hot_id), Some(cold_id)) if hot_id == cold_id => Ok(()),
+            (None, None) => {
+                let migrated = self.load_split()?.is_some();
+                let cold_populated = self
+                    .cold_db
+                    .exists::<RestorePointHash>(&Self::restore_point_key(0))?;
+                if migrated != cold_populated {
+                    return Err(HotColdDBError::MismatchedFreezerDatabase {
+                        hot_id: None,
+                        cold_id: None,
+                    }
+                    .into());
+                }
+
+                let id = DatabaseId::random();
+                self.hot_db.put(&key, &id)?;
+                self.cold_db.put(&key, &id)?;
+                Ok(())
+            }
+            (hot_id, cold_id) => {
+                Err(HotColdDBError::MismatchedFreezerDatabase { hot_id, cold_id }.into())
+            }
+        }
+    }
+
+    /// Returns the schema version of the database.
+    ///
+    /// A database without a recorded version (i.e., a new database or one created before schema
+    /// versions were recorded) has version `0`.
+    pub fn schema_version(&self) -> Result<SchemaVersion, Error> {
+        Ok(self
+            .hot_db
+            .get(&schema_version_key())?
+            .unwrap_or(SchemaVersion(0)))
+    }
+
+    /// Migrates the database from schema version `from` to `to`, which may be lower than `from`
+    /// to allow rolling back to an earlier release.
+    ///
+    /// Only migrations between adjacent versions are supported, so a migration across several
+    /// versions is performed one version at a time.
+    pub fn migrate_schema(&self, from: SchemaVersion, to: SchemaVersion) -> Result<(), Error> {
+        let mut current = from;
+
+        while current != to {
+            let next = if to > current {
+                SchemaVersion(current.as_u64() + 1)
+            } else {
+                SchemaVersion(current.as_u64() - 1)
+            };
+
+            match (current, next) {
+                // Versions 0 and 1 only differ by the presence of the version itself.
+                (SchemaVersion(0), SchemaVersion(1)) | (SchemaVersion(1), SchemaVersion(0)) => {}
+                // Databases from earlier releases are not checksummed.
+                (SchemaVersion(1), SchemaVersion(2)) => {}
+                (SchemaVersion(2), SchemaVersion(1)) => {
+                    self.hot_db.remove_checksums()?;
+                    self.cold_db.remove_checksums()?;
+                }
+                // Earlier releases expect the entire history to be present.
+                (SchemaVersion(2), SchemaVersion(3)) => {}
+                (SchemaVersion(3), SchemaVersion(2)) => {
+                    if self.get_historic_pruned_slot()?.is_some() {
+                        return Err(HotColdDBError::SchemaDowngradeRefused {
+                            from: current,
+                            to: next,
+                            reason: "historic blocks and states have been pruned",
+                        }
+                        .into());
+                    }
+                }
+                // Earlier releases do not persist the observed gossip caches, so the caches would
+                // be out of date if this release were used again.
+                (SchemaVersion(3), SchemaVersion(4)) => {}
+                (SchemaVersion(4), SchemaVersion(3)) => {
+                    for key in self.hot_db.keys_in_column(DBColumn::ObservedCaches) {
+                        self.hot_db
+                            .key_delete(DBColumn::ObservedCaches.into(), key.as_bytes())?;
+                    }
+                }
+                // The `DatabaseId`s are recorded when the database is opened (see
+                // `Self::check_database_id`). Earlier releases do not check them, so they would be
+                // stale if the freezer database were replaced while using such a release.
+                (SchemaVersion(4), SchemaVersion(5)) => {}
+                (SchemaVersion(5), SchemaVersion(4)) => {
+                    self.hot_db.delete::<DatabaseId>(&database_id_key())?;
+                    self.cold_db.delete::<DatabaseId>(&database_id_key())?;
+                }
+                // Earlier releases neither write nor remove `TemporaryState` markers, so any which
+                // remain would never be removed.
+                (SchemaVersion(5), SchemaVersion(6)) => {}
+                (SchemaVersion(6), SchemaVersion(5)) => {
+                    self.delete_temporary_states()?;
+                }
+                _ => {
+                    return Err(HotColdDBError::UnsupportedSchemaMigration {
+                        from: current,
+                        to: next,
+                    }
+                    .into())
+                }
+            }
+
+            if next == SchemaVersion(0) {
+                self.hot_db.delete::<SchemaVersion>(&schema_version_key())?;
+            } else {
+                self.hot_db.put(&schema_version_key(), &next)?;
+            }
+
+            debug!(
+                self.log,
+                "Migrated database schema";
+                "from" => current.as_u64(),
+                "to" => next.as_u64(),
+            );
+
+            current = next;
+        }
+
+        Ok(())
+    }
+
     /// Store a post-finalization state efficiently in the hot database.
     ///
     /// On an epoch boundary, store a full state. On an intermediate slot, store
@@ -664,6 +1010,113 @@ impl<E: EthSpec> HotColdDB<E> {
             * self.config.slots_per_restore_point
     }
 
+    /// Returns the number of epochs before the split slot for which blocks and states are retained
+    /// when `StoreConfig::prune_historic` is set.
+    ///
+    /// This is the number of epochs for which blocks must be served to peers, i.e.,
+    /// `MIN_VALIDATOR_WITHDRAWABILITY_DELAY + CHURN_LIMIT_QUOTIENT // 2`, which is at least the
+    /// weak subjectivity period.
+    pub fn historic_epochs_retained(&self) -> u64 {
+        self.spec.min_validator_withdrawability_delay.as_u64() + self.spec.churn_limit_quotient / 2
+    }
+
+    /// Returns the slot below which blocks and states have been deleted by pruning, if any.
+    pub fn get_historic_pruned_slot(&self) -> Result<Option<Slot>, Error> {
+        let key = Hash256::from_slice(HISTORIC_PRUNED_SLOT_KEY.as_bytes());
+        Ok(self
+            .hot_db
+            .get(&key)?
+            .map(|pruned: HistoricPrunedSlot| pruned.slot))
+    }
+
+    /// Deletes the blocks and frozen states older than `historic_epochs_retained` epochs before
+    /// the split slot, except those of genesis.
+    ///
+    /// The pruned slot is rounded down to a restore point, so every retained state may still be
+    /// reconstructed from a retained restore point and the blocks after it.
+    fn prune_historic(store: Arc<Self>) -> Result<(), Error> {
+        let slots_per_restore_point = store.config.slots_per_restore_point;
+        let retained_slots = store.historic_epochs_retained() * E::slots_per_epoch();
+        let prune_to = store
+            .get_split_slot()
+            .as_u64()
+            .saturating_sub(retained_slots)
+            / slots_per_restore_point
+            * slots_per_restore_point;
+        let pruned_to = store
+            .get_historic_pruned_slot()?
+            .map_or(0, |slot| slot.as_u64());
+
+        if prune_to <= pruned_to {
+            return Ok(());
+        }
+
+        let latest_restore_point_slot = store.get_latest_restore_point_slot();
+        let block_roots = |start_slot| {
+            FrozenForwardsBlockRootsIterator::new(
+                store.clone(),
+                Slot::new(start_slot),
+                latest_restore_point_slot,
+                &store.spec,
+            )
+        };
+
+        // 1. Delete the blocks, which are repeated in the block roots of skipped slots.
+        let genesis_block_root = block_roots(0).next().map(|(block_root, _)| block_root);
+        let mut previous_block_root = genesis_block_root;
+        let mut pruned_blocks = 0;
+        for (block_root, _) in
+            block_roots(pruned_to).take_while(|(_, slot)| slot.as_u64() < prune_to)
+        {
+            if Some(block_root) != previous_block_root {
+                store.delete_block(&block_root)?;
+                previous_block_root = Some(block_root);
+                pruned_blocks += 1;
+            }
+        }
+
+        // 2. Delete the summaries of the frozen states, so they are no longer found by root.
+        let state_roots: ChunkedVectorIter<StateRoots, E> = ChunkedVectorIter::new(
+            store.clone(),
+            std::cmp::max(pruned_to, 1) as usize,
+            latest_restore_point_slot,
+            &store.spec,
+        );
+        for (_, state_root) in state_roots.take_while(|(slot, _)| (*slot as u64) < prune_to) {
+            store.cold_db.delete::<ColdStateSummary>(&state_root)?;
+        }
+
+        // 3. Delete the restore points.
+        let mut pruned_restore_points = 0;
+        let first_restore_point = std::cmp::max(
+            1,
+            (pruned_to + slots_per_restore_point - 1) / slots_per_restore_point,
+        );
+        for restore_point_index in first_restore_point..prune_to / slots_per_restore_point {
+            let state_root = store.load_restore_point_hash(restore_point_index)?;
+            store.cold_db.delete::<PartialBeaconState<E>>(&state_root)?;
+            pruned_restore_points += 1;
+        }
+
+        let key = Hash256::from_slice(HISTORIC_PRUNED_SLOT_KEY.as_bytes());
+        store.hot_db.put(
+            &key,
+            &HistoricPrunedSlot {
+                slot: Slot::new(prune_to),
+            },
+        )?;
+
+        info!(
+            store.log,
+            "Pruned historic blocks and states";
+            "blocks" => pruned_blocks,
+            "restore_points" => pruned_restore_points,
+            "oldest_retained_slot" => prune_to
+        );
+
+        Ok(())
+    }
+
     /// Load the split point from disk.
     fn load_split(&self) -> Result<Option<Split>, Error> {
         let key = Hash256::from_slice(SPLIT_DB_KEY.as_bytes());
@@ -727,6 +1180,38 @@ impl<E: EthSpec> HotColdDB<E> {
         self.hot_db.get(state_root)
     }
 
+    /// Returns where the state with `state_root` at `slot` is stored and how much work it takes to
+    /// load, without loading it.
+    pub fn state_availability(
+        &self,
+        state_root: &Hash256,
+        slot: Slot,
+    ) -> Result<StateAvailability, Error> {
+        if slot >= self.get_split_slot() {
+            if self
+                .hot_db
+                .key_exists(DBColumn::BeaconState.into(), state_root.as_bytes())?
+            {
+                Ok(StateAvailability::Hot)
+            } else if self.load_hot_state_summary(state_root)?.is_some() {
+                Ok(StateAvailability::HotSummary {
+                    replay_slots: slot.as_u64() % E::slots_per_epoch(),
+                })
+            } else {
+                Ok(StateAvailability::Missing)
+            }
+        } else if self.load_cold_state_slot(state_root)?.is_some() {
+            let replay_slots = slot.as_u64() % self.config.slots_per_restore_point;
+            if replay_slots == 0 {
+                Ok(StateAvailability::RestorePoint)
+            } else {
+                Ok(StateAvailability::ColdIntermediate { replay_slots })
+            }
+        } else {
+            Ok(StateAvailability::Missing)
+        }
+    }
+
     /// Check that the restore point frequency is valid.
     ///
     /// Specifically, check that it is:
@@ -760,7 +1245,7 @@ impl<E: EthSpec> HotColdDB<E> {
 
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
-struct Split {
+pub(crate) struct Split {
     slot: Slot,
     state_root: Hash256,
 }
@@ -779,6 +1264,26 @@ impl SimpleStoreItem for Split {
     }
 }
 
+/// Struct for storing the slot below which historic blocks and states have been pruned.
+#[derive(Debug, Clone, Copy, Default, Encode, Decode)]
+pub(crate) struct HistoricPrunedSlot {
+    pub(crate) slot: Slot,
+}
+
+impl SimpleStoreItem for HistoricPrunedSlot {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 /// Struct for summarising a state in the hot database.
 ///
 /// Allows full reconstruction by replaying blocks.
@@ -826,6 +1331,27 @@ impl HotStateSummary {
     }
 }
 
+/// Marks a state in the hot database as temporary, i.e., written during the import of the block
+/// with `block_root` and deleted when the database is opened if that block was never stored.
+#[derive(Debug, Clone, Copy, Default, Encode, Decode)]
+pub struct TemporaryState {
+    pub block_root: Hash256,
+}
+
+impl SimpleStoreItem for TemporaryState {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateTemporary
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 /// Struct for summarising a state in the freezer database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 struct ColdStateSummary {
@@ -848,7 +1374,7 @@ impl SimpleStoreItem for ColdStateSummary {
 
 /// Struct for storing the state root of a restore point in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
-struct RestorePointHash {
+pub(crate) struct RestorePointHash {
     state_root: Hash256,
 }
 
@@ -1,37 +1,175 @@
 use super::*;
 use crate::forwards_iter::SimpleForwardsBlockRootsIterator;
 use crate::impls::beacon_state::{get_full_state, store_full_state};
+use crate::metadata::SCHEMA_VERSION_KEY;
 use crate::metrics;
 use db_key::Key;
 use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
+use leveldb::iterator::{Iterable, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
+use std::hash::Hasher;
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use twox_hash::XxHash64;
+
+/// 32-byte key (in the `BeaconMeta` column) recording whether values are checksummed.
+///
+/// The value is a single byte, `1` if checksums are enabled, and is itself never checksummed so
+/// that it may be read before the setting is known.
+pub const CHECKSUMS_KEY: &str = "CHECKSUMSCHECKSUMSCHECKSUMSCHECK";
+
+/// The length of the checksum appended to each value when checksums are enabled.
+pub const CHECKSUM_LEN: usize = 8;
+
+/// The number of values rewritten in each batch by `LevelDB::remove_checksums`.
+const REMOVE_CHECKSUMS_BATCH_SIZE: usize = 1_024;
 
 /// A wrapped leveldb database.
 pub struct LevelDB<E: EthSpec> {
     db: Database<BytesKey>,
+    /// If `true`, an xxHash64 of the key and value is appended to each value and verified when
+    /// it is read.
+    checksums: AtomicBool,
     _phantom: PhantomData<E>,
 }
 
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
-    pub fn open(path: &Path) -> Result<Self, Error> {
+    ///
+    /// Whether values are checksummed is decided by `checksums` when the database is created. An
+    /// existing database keeps the setting it was created with, since its values could not
+    /// otherwise be read.
+    pub fn open(path: &Path, checksums: bool) -> Result<Self, Error> {
         let mut options = Options::new();
 
         options.create_if_missing = true;
 
+        let mut db = Self {
+            db: Database::open(path, options)?,
+            checksums: AtomicBool::new(checksums),
+            _phantom: PhantomData,
+        };
+
+        let on_disk = match db.db.get(db.read_options(), Self::checksums_marker_key())? {
+            Some(marker) => Some(marker == [1]),
+            // Databases created before checksums existed have no marker and no checksums.
+            None if db.db.keys_iter(db.read_options()).next().is_some() => Some(false),
+            None => None,
+        };
+
+        match on_disk {
+            Some(on_disk) => db.checksums = AtomicBool::new(on_disk),
+            None => db.db.put(
+                db.write_options(),
+                Self::checksums_marker_key(),
+                &[checksums as u8],
+            )?,
+        }
+
+        Ok(db)
+    }
+
+    /// Open an existing database at `path` without writing any values to it (e.g., to inspect it
+    /// whilst the beacon node is stopped).
+    ///
+    /// LevelDB itself may still rewrite its log and manifest files when the database is opened.
+    pub fn open_read_only(path: &Path) -> Result<Self, Error> {
+        let mut options = Options::new();
+
+        options.create_if_missing = false;
+
         let db = Database::open(path, options)?;
+        let checksums = db.get(ReadOptions::new(), Self::checksums_marker_key())? == Some(vec![1]);
 
         Ok(Self {
             db,
+            checksums: AtomicBool::new(checksums),
             _phantom: PhantomData,
         })
     }
 
+    /// Returns the key of the marker recording whether values are checksummed.
+    fn checksums_marker_key() -> BytesKey {
+        Self::get_key_for_col(DBColumn::BeaconMeta.into(), CHECKSUMS_KEY.as_bytes())
+    }
+
+    /// Returns `true` if values are checksummed.
+    pub fn checksums(&self) -> bool {
+        self.checksums.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the value in `col` with `key` is checksummed.
+    ///
+    /// The schema version is never checksummed, so that releases which predate checksums may read
+    /// it and refuse to open the database, instead of failing to decode it.
+    fn is_checksummed(&self, col: &str, key: &[u8]) -> bool {
+        let meta: &str = DBColumn::BeaconMeta.into();
+        self.checksums() && !(col == meta && key == SCHEMA_VERSION_KEY.as_bytes())
+    }
+
+    /// Rewrites every value without its checksum and records that values are not checksummed,
+    /// so that the database may be read by releases which predate checksums.
+    pub fn remove_checksums(&self) -> Result<(), Error> {
+        if !self.checksums() {
+            return Ok(());
+        }
+
+        // All column names have the same length.
+        let meta: &str = DBColumn::BeaconMeta.into();
+        let column_len = meta.len();
+        let marker_key = Self::checksums_marker_key();
+
+        let mut batch = Writebatch::new();
+        let mut batch_len = 0;
+        for (column_key, bytes) in self.db.iter(self.read_options()) {
+            if column_key.key == marker_key.key || column_key.key.len() < column_len {
+                continue;
+            }
+
+            let (col, key) = column_key.key.split_at(column_len);
+            let col = std::str::from_utf8(col).map_err(|e| Error::DBError {
+                message: format!("Invalid column {:?}: {:?}", col, e),
+            })?;
+            if !self.is_checksummed(col, key) {
+                continue;
+            }
+
+            let value = self.strip_checksum(col, key, bytes)?;
+            batch.put(Self::get_key_for_col(col, key), &value);
+            batch_len += 1;
+
+            if batch_len == REMOVE_CHECKSUMS_BATCH_SIZE {
+                self.db.write(self.write_options(), &batch)?;
+                batch = Writebatch::new();
+                batch_len = 0;
+            }
+        }
+        self.db.write(self.write_options(), &batch)?;
+
+        self.db.put(self.write_options(), marker_key, &[0])?;
+        self.checksums.store(false, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Returns the keys of all items in `column`, where every key is a `Hash256`.
+    pub fn keys_in_column(&self, column: DBColumn) -> Vec<Hash256> {
+        let column: &str = column.into();
+        let start_key = Self::get_key_for_col(column, &[]);
+
+        self.db
+            .keys_iter(self.read_options())
+            .from(&start_key)
+            .take_while(|key| key.key.starts_with(column.as_bytes()))
+            .filter(|key| key.key.len() == column.len() + 32)
+            .map(|key| Hash256::from_slice(&key.key[column.len()..]))
+            .collect()
+    }
+
     fn read_options(&self) -> ReadOptions<BytesKey> {
         ReadOptions::new()
     }
@@ -45,6 +183,39 @@ impl<E: EthSpec> LevelDB<E> {
         col.append(&mut key.to_vec());
         BytesKey { key: col }
     }
+
+    /// Returns the checksum of `value`, stored in `col` with `key`.
+    fn checksum(col: &str, key: &[u8], value: &[u8]) -> [u8; CHECKSUM_LEN] {
+        let mut hasher = XxHash64::with_seed(0);
+        hasher.write(col.as_bytes());
+        hasher.write(key);
+        hasher.write(value);
+        hasher.finish().to_le_bytes()
+    }
+
+    /// Verifies and removes the checksum from `bytes`, if checksums are enabled.
+    fn strip_checksum(&self, col: &str, key: &[u8], mut bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if !self.is_checksummed(col, key) {
+            return Ok(bytes);
+        }
+
+        let value_len = bytes.len().checked_sub(CHECKSUM_LEN);
+        match value_len {
+            Some(value_len)
+                if bytes[value_len..] == Self::checksum(col, key, &bytes[..value_len]) =>
+            {
+                bytes.truncate(value_len);
+                Ok(bytes)
+            }
+            _ => {
+                metrics::inc_counter(&metrics::DISK_DB_CHECKSUM_FAILURES);
+                Err(Error::ChecksumMismatch {
+                    column: col.to_string(),
+                    key: key.to_vec(),
+                })
+            }
+        }
+    }
 }
 
 /// Used for keying leveldb.
@@ -75,12 +246,13 @@ impl<E: EthSpec> Store<E> for LevelDB<E> {
         self.db
             .get(self.read_options(), column_key)
             .map_err(Into::into)
-            .map(|opt| {
+            .and_then(|opt| {
                 opt.map(|bytes| {
                     metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as i64);
                     metrics::stop_timer(timer);
-                    bytes
+                    self.strip_checksum(col, key, bytes)
                 })
+                .transpose()
             })
     }
 
@@ -92,12 +264,18 @@ impl<E: EthSpec> Store<E> for LevelDB<E> {
         metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as i64);
         let timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
-        self.db
-            .put(self.write_options(), column_key, val)
-            .map_err(Into::into)
-            .map(|()| {
-                metrics::stop_timer(timer);
-            })
+        let result = if self.is_checksummed(col, key) {
+            let mut bytes = Vec::with_capacity(val.len() + CHECKSUM_LEN);
+            bytes.extend_from_slice(val);
+            bytes.extend_from_slice(&Self::checksum(col, key, val));
+            self.db.put(self.write_options(), column_key, &bytes)
+        } else {
+            self.db.put(self.write_options(), column_key, val)
+        };
+
+        result.map_err(Into::into).map(|()| {
+            metrics::stop_timer(timer);
+        })
     }
 
     /// Return `true` if `key` exists in `column`.
@@ -190,3 +368,99 @@ impl From<LevelDBError> for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn checksums_detect_corruption() {
+        let dir = tempdir().unwrap();
+        let store = LevelDB::<E>::open(dir.path(), true).unwrap();
+        let column = DBColumn::BeaconBlock.into();
+        let key = Hash256::random();
+
+        store.put_bytes(column, key.as_bytes(), &[1, 2, 3]).unwrap();
+        assert_eq!(
+            store.get_bytes(column, key.as_bytes()).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+
+        let column_key = || LevelDB::<E>::get_key_for_col(column, key.as_bytes());
+        let mut bytes = store
+            .db
+            .get(store.read_options(), column_key())
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes.len(), 3 + CHECKSUM_LEN);
+        bytes[0] ^= 1;
+        store
+            .db
+            .put(store.write_options(), column_key(), &bytes)
+            .unwrap();
+
+        match store.get_bytes(column, key.as_bytes()) {
+            Err(Error::ChecksumMismatch { .. }) => {}
+            other => panic!("expected checksum mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn checksum_setting_is_persisted() {
+        let enabled = tempdir().unwrap();
+        drop(LevelDB::<E>::open(enabled.path(), true).unwrap());
+        assert!(LevelDB::<E>::open(enabled.path(), false)
+            .unwrap()
+            .checksums());
+
+        // A database with values but no marker was created without checksums.
+        let legacy = tempdir().unwrap();
+        let store = LevelDB::<E>::open(legacy.path(), false).unwrap();
+        store
+            .db
+            .delete(
+                store.write_options(),
+                LevelDB::<E>::get_key_for_col(
+                    DBColumn::BeaconMeta.into(),
+                    CHECKSUMS_KEY.as_bytes(),
+                ),
+            )
+            .unwrap();
+        store
+            .put_bytes(DBColumn::BeaconBlock.into(), &[0; 32], &[1])
+            .unwrap();
+        drop(store);
+        assert!(!LevelDB::<E>::open(legacy.path(), true).unwrap().checksums());
+    }
+
+    #[test]
+    fn remove_checksums() {
+        let dir = tempdir().unwrap();
+        let store = LevelDB::<E>::open(dir.path(), true).unwrap();
+        let column = DBColumn::BeaconBlock.into();
+        let keys = (0..REMOVE_CHECKSUMS_BATCH_SIZE + 1)
+            .map(|_| Hash256::random())
+            .collect::<Vec<_>>();
+        for key in &keys {
+            store
+                .put_bytes(column, key.as_bytes(), key.as_bytes())
+                .unwrap();
+        }
+
+        store.remove_checksums().unwrap();
+        assert!(!store.checksums());
+        drop(store);
+
+        let store = LevelDB::<E>::open(dir.path(), true).unwrap();
+        assert!(!store.checksums(), "the setting should be persisted");
+        for key in &keys {
+            assert_eq!(
+                store.get_bytes(column, key.as_bytes()).unwrap(),
+                Some(key.as_bytes().to_vec())
+            );
+        }
+    }
+}
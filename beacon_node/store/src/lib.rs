@@ -19,6 +19,7 @@ pub mod hot_cold_store;
 mod impls;
 mod leveldb_store;
 mod memory_store;
+pub mod metadata;
 mod metrics;
 mod partial_beacon_state;
 mod state_batch;
@@ -28,7 +29,7 @@ pub mod iter;
 use std::sync::Arc;
 
 pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{HotColdDB as DiskStore, HotStateSummary};
+pub use self::hot_cold_store::{HotColdDB as DiskStore, HotStateSummary, TemporaryState};
 pub use self::leveldb_store::LevelDB as SimpleDiskStore;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
@@ -218,10 +219,14 @@ pub enum DBColumn {
     OpPool,
     Eth1Cache,
     ForkChoice,
+    /// For the caches of gossip messages which have already been observed.
+    ObservedCaches,
     /// For the table mapping restore point numbers to state roots.
     BeaconRestorePoint,
     /// For the mapping from state roots to their slots or summaries.
     BeaconStateSummary,
+    /// For marking states which were written during a block import that has yet to complete.
+    BeaconStateTemporary,
     BeaconBlockRoots,
     BeaconStateRoots,
     BeaconHistoricalRoots,
@@ -240,8 +245,10 @@ impl Into<&'static str> for DBColumn {
             DBColumn::OpPool => "opo",
             DBColumn::Eth1Cache => "etc",
             DBColumn::ForkChoice => "frk",
+            DBColumn::ObservedCaches => "obc",
             DBColumn::BeaconRestorePoint => "brp",
             DBColumn::BeaconStateSummary => "bss",
+            DBColumn::BeaconStateTemporary => "bst",
             DBColumn::BeaconBlockRoots => "bbr",
             DBColumn::BeaconStateRoots => "bsr",
             DBColumn::BeaconHistoricalRoots => "bhr",
@@ -332,11 +339,311 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn schema_migration() {
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let open = || {
+            DiskStore::<MinimalEthSpec>::open(
+                &hot_dir.path(),
+                &cold_dir.path(),
+                StoreConfig::default(),
+                MinimalEthSpec::default_spec(),
+                NullLoggerBuilder.build().unwrap(),
+            )
+        };
+        let version_key = Hash256::from_slice(SCHEMA_VERSION_KEY.as_bytes());
+
+        // A new database is given the current version.
+        let store = open().unwrap();
+        assert_eq!(store.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // Downgrading and re-opening upgrades it again.
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(0))
+            .unwrap();
+        assert_eq!(store.schema_version().unwrap(), SchemaVersion(0));
+        drop(store);
+
+        let store = open().unwrap();
+        assert_eq!(store.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // Migrating to an unknown version fails.
+        let future = SchemaVersion(CURRENT_SCHEMA_VERSION.as_u64() + 1);
+        assert!(store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, future)
+            .is_err());
+
+        // A database from a later release is not opened.
+        store.put(&version_key, &future).unwrap();
+        drop(store);
+        assert!(open().is_err());
+    }
+
+    #[test]
+    fn open_read_only() {
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let open = |read_only: bool| {
+            let open = if read_only {
+                DiskStore::<MinimalEthSpec>::open_read_only
+            } else {
+                DiskStore::<MinimalEthSpec>::open
+            };
+            open(
+                &hot_dir.path(),
+                &cold_dir.path(),
+                StoreConfig::default(),
+                MinimalEthSpec::default_spec(),
+                NullLoggerBuilder.build().unwrap(),
+            )
+        };
+
+        // A database which does not exist is not created.
+        assert!(open(true).is_err());
+
+        let store = open(false).unwrap();
+        let state_root = Hash256::random();
+        store
+            .put(
+                &state_root,
+                &TemporaryState {
+                    block_root: Hash256::random(),
+                },
+            )
+            .unwrap();
+        drop(store);
+
+        // The temporary state is not deleted.
+        let store = open(true).unwrap();
+        assert!(store.exists::<TemporaryState>(&state_root).unwrap());
+        drop(store);
+
+        // The schema is neither migrated nor required to be migrated to read its version.
+        let store = open(false).unwrap();
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(0))
+            .unwrap();
+        drop(store);
+        assert_eq!(
+            DiskStore::<MinimalEthSpec>::read_schema_version(hot_dir.path()).unwrap(),
+            SchemaVersion(0)
+        );
+        assert!(open(true).is_err(), "should refuse an earlier schema");
+        assert_eq!(
+            DiskStore::<MinimalEthSpec>::read_schema_version(hot_dir.path()).unwrap(),
+            SchemaVersion(0)
+        );
+    }
+
+    #[test]
+    fn schema_downgrade_deletes_temporary_states() {
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let store = DiskStore::<MinimalEthSpec>::open(
+            &hot_dir.path(),
+            &cold_dir.path(),
+            StoreConfig::default(),
+            MinimalEthSpec::default_spec(),
+            NullLoggerBuilder.build().unwrap(),
+        )
+        .unwrap();
+
+        let state_root = Hash256::random();
+        store
+            .put(
+                &state_root,
+                &TemporaryState {
+                    block_root: Hash256::random(),
+                },
+            )
+            .unwrap();
+
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(5))
+            .unwrap();
+        assert!(!store.exists::<TemporaryState>(&state_root).unwrap());
+    }
+
+    #[test]
+    fn schema_downgrade_deletes_observed_caches() {
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let store = DiskStore::<MinimalEthSpec>::open(
+            &hot_dir.path(),
+            &cold_dir.path(),
+            StoreConfig::default(),
+            MinimalEthSpec::default_spec(),
+            NullLoggerBuilder.build().unwrap(),
+        )
+        .unwrap();
+
+        let key = Hash256::zero();
+        let column = DBColumn::ObservedCaches.into();
+        store.put_bytes(column, key.as_bytes(), &[42]).unwrap();
+
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(3))
+            .unwrap();
+        assert!(!store.key_exists(column, key.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn schema_downgrade_refused_after_pruning() {
+        use hot_cold_store::{HistoricPrunedSlot, HISTORIC_PRUNED_SLOT_KEY};
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let store = DiskStore::<MinimalEthSpec>::open(
+            &hot_dir.path(),
+            &cold_dir.path(),
+            StoreConfig::default(),
+            MinimalEthSpec::default_spec(),
+            NullLoggerBuilder.build().unwrap(),
+        )
+        .unwrap();
+
+        store
+            .hot_db
+            .put(
+                &Hash256::from_slice(HISTORIC_PRUNED_SLOT_KEY.as_bytes()),
+                &HistoricPrunedSlot {
+                    slot: Slot::new(64),
+                },
+            )
+            .unwrap();
+
+        // Releases using version 2 would expect the pruned history to be present.
+        assert!(store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(2))
+            .is_err());
+        assert_eq!(store.schema_version().unwrap(), SchemaVersion(3));
+    }
+
+    #[test]
+    fn schema_downgrade_removes_checksums() {
+        use metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let open = |checksums| {
+            DiskStore::<MinimalEthSpec>::open(
+                &hot_dir.path(),
+                &cold_dir.path(),
+                StoreConfig {
+                    checksums,
+                    ..StoreConfig::default()
+                },
+                MinimalEthSpec::default_spec(),
+                NullLoggerBuilder.build().unwrap(),
+            )
+        };
+
+        let store = open(true).unwrap();
+        assert!(store.hot_db.checksums());
+        let key = Hash256::random();
+        let item = StorableThing { a: 1, b: 42 };
+        store.put(&key, &item).unwrap();
+
+        // Version 1 predates checksums.
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(1))
+            .unwrap();
+        assert!(!store.hot_db.checksums());
+        assert!(!store.cold_db.checksums());
+        drop(store);
+
+        let store = open(true).unwrap();
+        assert!(!store.hot_db.checksums(), "should keep the setting on disk");
+        assert_eq!(store.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+        assert_eq!(store.get::<StorableThing>(&key).unwrap(), Some(item));
+    }
+
+    #[test]
+    fn mismatched_freezer() {
+        use hot_cold_store::{RestorePointHash, Split, SPLIT_DB_KEY};
+        use metadata::{database_id_key, DatabaseId, SchemaVersion, CURRENT_SCHEMA_VERSION};
+        use sloggers::{null::NullLoggerBuilder, Build};
+
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let other_cold_dir = tempdir().unwrap();
+        let open = |hot_dir: &tempfile::TempDir, cold_dir: &tempfile::TempDir| {
+            DiskStore::<MinimalEthSpec>::open(
+                &hot_dir.path(),
+                &cold_dir.path(),
+                StoreConfig::default(),
+                MinimalEthSpec::default_spec(),
+                NullLoggerBuilder.build().unwrap(),
+            )
+        };
+
+        drop(open(&hot_dir, &cold_dir).unwrap());
+        // The freezer the database was created with may be re-opened.
+        drop(open(&hot_dir, &cold_dir).unwrap());
+        // An empty freezer, or that of another database, is refused.
+        assert!(open(&hot_dir, &other_cold_dir).is_err());
+        drop(open(&hot_dir, &cold_dir).unwrap());
+
+        // Create a database which has migrated states to its freezer, and migrate it to the
+        // schema of a release which did not record IDs.
+        let legacy_hot_dir = tempdir().unwrap();
+        let legacy_cold_dir = tempdir().unwrap();
+        let empty_cold_dir = tempdir().unwrap();
+
+        let store = open(&legacy_hot_dir, &legacy_cold_dir).unwrap();
+        store
+            .hot_db
+            .put(
+                &Hash256::from_slice(SPLIT_DB_KEY.as_bytes()),
+                &Split::default(),
+            )
+            .unwrap();
+        store
+            .cold_db
+            .put(&Hash256::from_low_u64_be(0), &RestorePointHash::default())
+            .unwrap();
+        store
+            .migrate_schema(CURRENT_SCHEMA_VERSION, SchemaVersion(4))
+            .unwrap();
+        assert!(!store
+            .hot_db
+            .exists::<DatabaseId>(&database_id_key())
+            .unwrap());
+        assert!(!store
+            .cold_db
+            .exists::<DatabaseId>(&database_id_key())
+            .unwrap());
+        drop(store);
+
+        // An empty freezer is refused, even though neither database has an ID.
+        assert!(open(&legacy_hot_dir, &empty_cold_dir).is_err());
+        // The populated freezer is accepted, and both databases are given an ID.
+        drop(open(&legacy_hot_dir, &legacy_cold_dir).unwrap());
+        assert!(open(&legacy_hot_dir, &empty_cold_dir).is_err());
+        drop(open(&legacy_hot_dir, &legacy_cold_dir).unwrap());
+    }
+
     #[test]
     fn simplediskdb() {
         let dir = tempdir().unwrap();
         let path = dir.path();
-        let store = SimpleDiskStore::open(&path).unwrap();
+        let store = SimpleDiskStore::open(&path, false).unwrap();
 
         test_impl(store);
     }
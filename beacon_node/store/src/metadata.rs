@@ -0,0 +1,99 @@
+//! Versioning of the on-disk database schema, and identification of the hot and cold databases.
+//!
+//! The schema version is stored in the hot database. Each release knows how to migrate between
+//! its own `CURRENT_SCHEMA_VERSION` and at least the version before it, in both directions, so a
+//! release may be rolled back without re-syncing (see `HotColdDB::migrate_schema`).
+//!
+//! The same random `DatabaseId` is stored in both the hot and cold databases when they are
+//! created, so that a hot database is never opened with the cold database of another (e.g., when
+//! the disk containing the cold database is not mounted).
+
+use crate::{DBColumn, Error, SimpleStoreItem};
+use ssz::{Decode, Encode};
+use std::fmt;
+use types::Hash256;
+
+/// The schema version written by this release.
+///
+/// ## History
+///
+/// - `0`: databases created before schema versions were recorded. The layout is identical to
+///   version `1`.
+/// - `1`: the schema version is recorded.
+/// - `2`: values may be checksummed (see `StoreConfig::checksums`). The schema version itself is
+///   never checksummed, so that earlier releases may read it.
+/// - `3`: historic blocks and states may have been pruned (see `StoreConfig::prune_historic`).
+/// - `4`: the caches of observed gossip messages are persisted (`DBColumn::ObservedCaches`).
+/// - `5`: the hot and cold databases record a shared `DatabaseId`.
+/// - `6`: states written during a block import are marked as temporary until the import completes
+///   (`DBColumn::BeaconStateTemporary`).
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(6);
+
+/// 32-byte key for accessing the `SchemaVersion` of the hot DB.
+pub const SCHEMA_VERSION_KEY: &str = "SCHEMAVERSIONSCHEMAVERSIONSCHEMA";
+
+/// 32-byte key for accessing the `DatabaseId` of both the hot and cold DBs.
+pub const DATABASE_ID_KEY: &str = "DATABASEIDDATABASEIDDATABASEIDDA";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaVersion(pub u64);
+
+impl SchemaVersion {
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl SimpleStoreItem for SchemaVersion {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(SchemaVersion(u64::from_ssz_bytes(bytes)?))
+    }
+}
+
+/// Returns the key at which the `SchemaVersion` is stored.
+pub(crate) fn schema_version_key() -> Hash256 {
+    Hash256::from_slice(SCHEMA_VERSION_KEY.as_bytes())
+}
+
+/// A random identifier shared by a hot database and the cold database created with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatabaseId(pub Hash256);
+
+impl DatabaseId {
+    pub fn random() -> Self {
+        DatabaseId(Hash256::random())
+    }
+}
+
+impl SimpleStoreItem for DatabaseId {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(DatabaseId(Hash256::from_ssz_bytes(bytes)?))
+    }
+}
+
+/// Returns the key at which the `DatabaseId` is stored.
+pub(crate) fn database_id_key() -> Hash256 {
+    Hash256::from_slice(DATABASE_ID_KEY.as_bytes())
+}
@@ -43,6 +43,10 @@ lazy_static! {
         "store_disk_db_delete_count_total",
         "Total number of deletions from the hot on-disk DB"
     );
+    pub static ref DISK_DB_CHECKSUM_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "store_disk_db_checksum_failures_total",
+        "Number of values read from the on-disk DBs with an incorrect checksum"
+    );
     /*
      * Beacon State
      */
@@ -33,6 +33,13 @@ impl<E: EthSpec> StateBatch<E> {
         Ok(())
     }
 
+    /// Returns the roots of the staged states.
+    pub fn state_roots(&self) -> impl Iterator<Item = Hash256> + '_ {
+        self.items.iter().map(|item| match item {
+            BatchItem::Full(state_root, _) | BatchItem::Summary(state_root, _) => *state_root,
+        })
+    }
+
     /// Write the batch to the database.
     ///
     /// May fail to write the full batch if any of the items error (i.e. not atomic!)
@@ -1,15 +1,78 @@
+use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use types::EthSpec;
-use ws::{Sender, WebSocket};
+use ws::{CloseCode, Handler, Request, Response, Sender, WebSocket};
 
 mod config;
+mod topic;
 
 pub use config::Config;
+pub use topic::{topics_from_resource, Topic, TOPICS_QUERY_PARAM};
+
+/// A connected client and the topics to which it is subscribed.
+struct Subscriber {
+    sender: Sender,
+    /// `None` if the client is subscribed to every topic.
+    topics: Option<HashSet<Topic>>,
+}
+
+impl Subscriber {
+    fn is_subscribed(&self, topics: &[Topic]) -> bool {
+        self.topics.as_ref().map_or(true, |subscribed| {
+            topics.iter().any(|t| subscribed.contains(t))
+        })
+    }
+}
+
+/// The connected clients, keyed by connection id.
+type Subscribers = Arc<Mutex<HashMap<u32, Subscriber>>>;
+
+/// Registers each client with the topics of its request and forgets it once it disconnects.
+struct Connection {
+    sender: Sender,
+    subscribers: Subscribers,
+    log: Logger,
+}
+
+impl Handler for Connection {
+    fn on_request(&mut self, req: &Request) -> ws::Result<Response> {
+        match topics_from_resource(req.resource()) {
+            Ok(topics) => {
+                self.subscribers.lock().insert(
+                    self.sender.connection_id(),
+                    Subscriber {
+                        sender: self.sender.clone(),
+                        topics,
+                    },
+                );
+                Response::from_request(req)
+            }
+            Err(e) => {
+                debug!(
+                    self.log,
+                    "Rejected websocket client";
+                    "error" => &e
+                );
+                Ok(Response::new(400, "Bad Request", e.into_bytes()))
+            }
+        }
+    }
+
+    fn on_close(&mut self, _code: CloseCode, _reason: &str) {
+        self.subscribers.lock().remove(&self.sender.connection_id());
+    }
+
+    fn on_error(&mut self, _err: ws::Error) {
+        self.subscribers.lock().remove(&self.sender.connection_id());
+    }
+}
 
 pub struct WebSocketSender<T: EthSpec> {
-    sender: Option<Sender>,
+    subscribers: Option<Subscribers>,
     _phantom: PhantomData<T>,
 }
 
@@ -17,16 +80,30 @@ impl<T: EthSpec> WebSocketSender<T> {
     /// Creates a dummy websocket server that never starts and where all future calls are no-ops.
     pub fn dummy() -> Self {
         Self {
-            sender: None,
+            subscribers: None,
             _phantom: PhantomData,
         }
     }
 
-    pub fn send_string(&self, string: String) -> Result<(), String> {
-        if let Some(sender) = &self.sender {
-            sender
-                .send(string)
-                .map_err(|e| format!("Unable to broadcast to websocket clients: {:?}", e))
+    /// Sends `string` to each client which is subscribed to any of `topics`.
+    pub fn send_string(&self, topics: &[Topic], string: String) -> Result<(), String> {
+        if let Some(subscribers) = &self.subscribers {
+            let errors = subscribers
+                .lock()
+                .values()
+                .filter(|subscriber| subscriber.is_subscribed(topics))
+                .filter_map(|subscriber| subscriber.sender.send(string.clone()).err())
+                .map(|e| format!("{:?}", e))
+                .collect::<Vec<_>>();
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Unable to send to websocket clients: {}",
+                    errors.join(", ")
+                ))
+            }
         } else {
             Ok(())
         }
@@ -46,8 +123,17 @@ pub fn start_server<T: EthSpec>(
 > {
     let server_string = format!("{}:{}", config.listen_address, config.port);
 
-    // Create a server that simply ignores any incoming messages.
-    let server = WebSocket::new(|_| |_| Ok(()))
+    let subscribers: Subscribers = <_>::default();
+
+    // Create a server that ignores any incoming messages, other than the topics of each request.
+    let subscribers_inner = subscribers.clone();
+    let log_inner = log.clone();
+    let factory = move |sender: Sender| Connection {
+        sender,
+        subscribers: subscribers_inner.clone(),
+        log: log_inner.clone(),
+    };
+    let server = WebSocket::new(factory)
         .map_err(|e| format!("Failed to initialize websocket server: {:?}", e))?
         .bind(server_string.clone())
         .map_err(|e| {
@@ -64,8 +150,6 @@ pub fn start_server<T: EthSpec>(
         )
     })?;
 
-    let broadcaster = server.broadcaster();
-
     // Produce a signal/channel that can gracefully shutdown the websocket server.
     let exit_channel = {
         let (exit_channel, exit) = tokio::sync::oneshot::channel();
@@ -119,7 +203,7 @@ pub fn start_server<T: EthSpec>(
 
     Ok((
         WebSocketSender {
-            sender: Some(broadcaster),
+            subscribers: Some(subscribers),
             _phantom: PhantomData,
         },
         exit_channel,
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// The name of the query parameter with which clients choose their topics, e.g.
+/// `ws://localhost:5053/?topics=head,finalized_checkpoint`.
+pub const TOPICS_QUERY_PARAM: &str = "topics";
+
+/// A class of events to which a websocket client may subscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// Changes to the canonical head.
+    Head,
+    /// Changes to the finalized checkpoint.
+    FinalizedCheckpoint,
+    /// Attestations which were imported or rejected.
+    Attestation,
+    /// Blocks which were imported or rejected.
+    Block,
+    /// Changes to the canonical head which are re-orgs.
+    Reorg,
+    /// Voluntary exits which were imported.
+    Exit,
+}
+
+impl Topic {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Topic::Head => "head",
+            Topic::FinalizedCheckpoint => "finalized_checkpoint",
+            Topic::Attestation => "attestation",
+            Topic::Block => "block",
+            Topic::Reorg => "reorg",
+            Topic::Exit => "exit",
+        }
+    }
+}
+
+impl FromStr for Topic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "head" => Ok(Topic::Head),
+            "finalized_checkpoint" => Ok(Topic::FinalizedCheckpoint),
+            "attestation" => Ok(Topic::Attestation),
+            "block" => Ok(Topic::Block),
+            "reorg" => Ok(Topic::Reorg),
+            "exit" => Ok(Topic::Exit),
+            other => Err(format!("Unknown topic \"{}\"", other)),
+        }
+    }
+}
+
+/// Parses the comma-separated topics of the `topics` query parameter of the request `resource`
+/// (i.e., its path and query).
+///
+/// Returns `None` if there is no such parameter, in which case the client is subscribed to every
+/// topic.
+pub fn topics_from_resource(resource: &str) -> Result<Option<HashSet<Topic>>, String> {
+    let query = match resource.splitn(2, '?').nth(1) {
+        Some(query) => query,
+        None => return Ok(None),
+    };
+
+    let mut topics: Option<HashSet<Topic>> = None;
+    for pair in query.split('&') {
+        let mut parts = pair.splitn(2, '=');
+        if parts.next() != Some(TOPICS_QUERY_PARAM) {
+            continue;
+        }
+
+        let value = parts
+            .next()
+            .unwrap_or("")
+            .replace("%2C", ",")
+            .replace("%2c", ",");
+        let set = topics.get_or_insert_with(HashSet::new);
+        for topic in value.split(',').filter(|topic| !topic.is_empty()) {
+            set.insert(topic.parse()?);
+        }
+    }
+
+    match topics {
+        Some(ref set) if set.is_empty() => Err(format!(
+            "The {} parameter has no topics",
+            TOPICS_QUERY_PARAM
+        )),
+        topics => Ok(topics),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_topics() {
+        assert_eq!(topics_from_resource("/"), Ok(None));
+        assert_eq!(topics_from_resource("/?other=1"), Ok(None));
+        assert_eq!(
+            topics_from_resource("/?topics=head,reorg&topics=exit"),
+            Ok(Some(
+                vec![Topic::Head, Topic::Reorg, Topic::Exit]
+                    .into_iter()
+                    .collect()
+            ))
+        );
+        assert_eq!(
+            topics_from_resource("/?topics=block%2Cfinalized_checkpoint"),
+            Ok(Some(
+                vec![Topic::Block, Topic::FinalizedCheckpoint]
+                    .into_iter()
+                    .collect()
+            ))
+        );
+        assert!(topics_from_resource("/?topics=head,sideways").is_err());
+        assert!(topics_from_resource("/?topics=").is_err());
+
+        for topic in &[
+            Topic::Head,
+            Topic::FinalizedCheckpoint,
+            Topic::Attestation,
+            Topic::Block,
+            Topic::Reorg,
+            Topic::Exit,
+        ] {
+            assert_eq!(topic.as_str().parse::<Topic>(), Ok(*topic));
+        }
+    }
+}
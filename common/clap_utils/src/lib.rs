@@ -1,7 +1,7 @@
 //! A helper library for parsing values from `clap::ArgMatches`.
 
 use clap::ArgMatches;
-use eth2_testnet_config::Eth2TestnetConfig;
+use eth2_testnet_config::{hardcoded_net_names, Eth2TestnetConfig};
 use hex;
 use ssz::Decode;
 use std::path::PathBuf;
@@ -13,15 +13,30 @@ pub const BAD_TESTNET_DIR_MESSAGE: &str = "The hard-coded testnet directory was
                                         or when there is no default public network to connect to. \
                                         During these times you must specify a --testnet-dir.";
 
-/// Attempts to load the testnet dir at the path if `name` is in `matches`, returning an error if
-/// the path cannot be found or the testnet dir is invalid.
+/// Attempts to load the testnet included in the binary with the name given by the `network_name`
+/// flag, returning an error if there is no such testnet.
 ///
-/// If `name` is not in `matches`, attempts to return the "hard coded" testnet dir.
-pub fn parse_testnet_dir_with_hardcoded_default<E: EthSpec>(
+/// If `network_name` is not in `matches`, attempts to load the testnet dir at the path given by
+/// the `testnet_dir_name` flag, returning an error if the path cannot be found or the testnet dir
+/// is invalid.
+///
+/// If neither flag is in `matches`, attempts to return the "hard coded" testnet dir.
+pub fn parse_testnet_config_with_hardcoded_default<E: EthSpec>(
     matches: &ArgMatches,
-    name: &'static str,
+    network_name: &'static str,
+    testnet_dir_name: &'static str,
 ) -> Result<Eth2TestnetConfig<E>, String> {
-    if let Some(path) = parse_optional::<PathBuf>(matches, name)? {
+    if let Some(network) = matches.value_of(network_name) {
+        Eth2TestnetConfig::constant(network)
+            .map_err(|e| format!("{} Error : {}", BAD_TESTNET_DIR_MESSAGE, e))?
+            .ok_or_else(|| {
+                format!(
+                    "Unknown network: {}. Known networks are: {}",
+                    network,
+                    hardcoded_net_names().join(", ")
+                )
+            })
+    } else if let Some(path) = parse_optional::<PathBuf>(matches, testnet_dir_name)? {
         Eth2TestnetConfig::load(path.clone())
             .map_err(|e| format!("Unable to open testnet dir at {:?}: {}", path, e))
     } else {
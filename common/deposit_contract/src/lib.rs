@@ -27,6 +27,29 @@ pub const ABI: &[u8] = include_bytes!("../contracts/v0.11.1_validator_registrati
 pub const BYTECODE: &[u8] = include_bytes!("../contracts/v0.11.1_validator_registration.bytecode");
 pub const DEPOSIT_DATA_LEN: usize = 420; // lol
 
+/// The ABI of a batch deposit contract, which forwards each of the concatenated deposits to the
+/// deposit contract with an equal share of the `msg.value`.
+///
+/// There is no canonical batch deposit contract; this is the interface shared by those commonly
+/// used by staking providers.
+pub const BATCH_DEPOSIT_ABI: &[u8] = br#"[{
+    "name": "batchDeposit",
+    "type": "function",
+    "constant": false,
+    "payable": true,
+    "stateMutability": "payable",
+    "inputs": [
+        { "name": "pubkeys", "type": "bytes" },
+        { "name": "withdrawal_credentials", "type": "bytes" },
+        { "name": "signatures", "type": "bytes" },
+        { "name": "deposit_data_roots", "type": "bytes32[]" }
+    ],
+    "outputs": []
+}]"#;
+
+/// The gas required by a batch deposit transaction, in addition to `DEPOSIT_GAS` per deposit.
+pub const BATCH_DEPOSIT_BASE_GAS: usize = 50_000;
+
 pub mod testnet {
     pub const ABI: &[u8] =
         include_bytes!("../contracts/v0.11.1_testnet_validator_registration.json");
@@ -49,6 +72,44 @@ pub fn encode_eth1_tx_data(deposit_data: &DepositData) -> Result<Vec<u8>, Error>
     function.encode_input(&params)
 }
 
+/// Returns the call data for a batch deposit contract (see `BATCH_DEPOSIT_ABI`) which submits all
+/// of the `deposit_datas` in a single transaction.
+///
+/// The batch deposit contract divides the value of the transaction equally between the deposits,
+/// so all of the `deposit_datas` should have the same `amount`.
+pub fn encode_batch_eth1_tx_data(deposit_datas: &[DepositData]) -> Result<Vec<u8>, Error> {
+    let mut pubkeys = vec![];
+    let mut withdrawal_credentials = vec![];
+    let mut signatures = vec![];
+    let mut roots = vec![];
+
+    for deposit_data in deposit_datas {
+        pubkeys.extend_from_slice(&deposit_data.pubkey.as_ssz_bytes());
+        withdrawal_credentials
+            .extend_from_slice(&deposit_data.withdrawal_credentials.as_ssz_bytes());
+        signatures.extend_from_slice(&deposit_data.signature.as_ssz_bytes());
+        roots.push(Token::FixedBytes(
+            deposit_data.tree_hash_root().as_ssz_bytes(),
+        ));
+    }
+
+    let params = vec![
+        Token::Bytes(pubkeys),
+        Token::Bytes(withdrawal_credentials),
+        Token::Bytes(signatures),
+        Token::Array(roots),
+    ];
+
+    let abi = Contract::load(BATCH_DEPOSIT_ABI)?;
+    let function = abi.function("batchDeposit")?;
+    function.encode_input(&params)
+}
+
+/// Returns the gas limit for a batch deposit transaction containing `deposit_count` deposits.
+pub fn batch_deposit_gas(deposit_count: usize) -> usize {
+    BATCH_DEPOSIT_BASE_GAS + DEPOSIT_GAS * deposit_count
+}
+
 pub fn decode_eth1_tx_data(
     bytes: &[u8],
     amount: u64,
@@ -128,4 +189,41 @@ mod tests {
             "decode root should match original root"
         );
     }
+
+    #[test]
+    fn batch_concatenates_deposits() {
+        let spec = &E::default_spec();
+
+        let deposits = (0..3)
+            .map(|i| get_deposit(generate_deterministic_keypair(i), spec))
+            .collect::<Vec<_>>();
+
+        let data = encode_batch_eth1_tx_data(&deposits).expect("should produce tx data");
+
+        let abi = Contract::load(BATCH_DEPOSIT_ABI).unwrap();
+        let tokens = abi
+            .function("batchDeposit")
+            .unwrap()
+            .decode_input(&data[4..])
+            .expect("should decode");
+
+        let pubkeys = tokens[0].clone().to_bytes().unwrap();
+        let signatures = tokens[2].clone().to_bytes().unwrap();
+        let roots = tokens[3].clone().to_array().unwrap();
+
+        assert_eq!(pubkeys.len(), 48 * deposits.len());
+        assert_eq!(signatures.len(), 96 * deposits.len());
+        assert_eq!(roots.len(), deposits.len());
+
+        for (i, deposit) in deposits.iter().enumerate() {
+            assert_eq!(
+                &pubkeys[i * 48..(i + 1) * 48],
+                &deposit.pubkey.as_ssz_bytes()[..]
+            );
+            assert_eq!(
+                roots[i].clone().to_fixed_bytes().unwrap(),
+                deposit.tree_hash_root().as_ssz_bytes()
+            );
+        }
+    }
 }
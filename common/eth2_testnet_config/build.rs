@@ -1,4 +1,4 @@
-//! Downloads a testnet configuration from Github.
+//! Downloads the configurations of the testnets embedded in the binary from Github.
 
 use reqwest;
 use std::env;
@@ -6,43 +6,50 @@ use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
 
-const TESTNET_ID: &str = "witti-v0-11-3";
+/// The directory of each embedded testnet, with the URL from which its files are downloaded.
+///
+/// Each directory must also be listed in `HARDCODED_NETS` in `src/lib.rs`.
+const TESTNETS: &[(&str, &str)] = &[(
+    "witti-v0-11-3",
+    "https://raw.githubusercontent.com/sigp/witti/6d079b0f10f6bed75cd003e5f0ea5ecbe2044455/lighthouse",
+)];
 
 fn main() {
-    if !base_dir().exists() {
-        std::fs::create_dir_all(base_dir()).expect(&format!("Unable to create {:?}", base_dir()));
+    for (testnet_id, base_url) in TESTNETS {
+        let base_dir = base_dir(testnet_id);
 
-        match get_all_files() {
-            Ok(()) => (),
-            Err(e) => {
-                std::fs::remove_dir_all(base_dir()).expect(&format!(
-                    "{}. Failed to remove {:?}, please remove the directory manually because it may contains incomplete testnet data.",
-                    e,
-                    base_dir(),
-                ));
-                panic!(e);
+        if !base_dir.exists() {
+            std::fs::create_dir_all(&base_dir).expect(&format!("Unable to create {:?}", base_dir));
+
+            match get_all_files(&base_dir, base_url) {
+                Ok(()) => (),
+                Err(e) => {
+                    std::fs::remove_dir_all(&base_dir).expect(&format!(
+                        "{}. Failed to remove {:?}, please remove the directory manually because it may contains incomplete testnet data.",
+                        e,
+                        base_dir,
+                    ));
+                    panic!(e);
+                }
             }
         }
     }
 }
 
-pub fn get_all_files() -> Result<(), String> {
-    get_file("boot_enr.yaml")?;
-    get_file("config.yaml")?;
-    get_file("deploy_block.txt")?;
-    get_file("deposit_contract.txt")?;
-    get_file("genesis.ssz")?;
+pub fn get_all_files(base_dir: &PathBuf, base_url: &str) -> Result<(), String> {
+    get_file(base_dir, base_url, "boot_enr.yaml")?;
+    get_file(base_dir, base_url, "config.yaml")?;
+    get_file(base_dir, base_url, "deploy_block.txt")?;
+    get_file(base_dir, base_url, "deposit_contract.txt")?;
+    get_file(base_dir, base_url, "genesis.ssz")?;
 
     Ok(())
 }
 
-pub fn get_file(filename: &str) -> Result<(), String> {
-    let url = format!(
-        "https://raw.githubusercontent.com/sigp/witti/6d079b0f10f6bed75cd003e5f0ea5ecbe2044455/lighthouse/{}",
-        filename
-    );
+pub fn get_file(base_dir: &PathBuf, base_url: &str, filename: &str) -> Result<(), String> {
+    let url = format!("{}/{}", base_url, filename);
 
-    let path = base_dir().join(filename);
+    let path = base_dir.join(filename);
     let mut file =
         File::create(path).map_err(|e| format!("Failed to create {}: {:?}", filename, e))?;
 
@@ -66,10 +73,10 @@ pub fn get_file(filename: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn base_dir() -> PathBuf {
+fn base_dir(testnet_id: &str) -> PathBuf {
     env::var("CARGO_MANIFEST_DIR")
         .expect("should know manifest dir")
         .parse::<PathBuf>()
         .expect("should parse manifest dir as path")
-        .join(TESTNET_ID)
+        .join(testnet_id)
 }
@@ -20,14 +20,49 @@ pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
 pub const GENESIS_STATE_FILE: &str = "genesis.ssz";
 pub const YAML_CONFIG_FILE: &str = "config.yaml";
 
-pub const HARDCODED_TESTNET: &str = "witti-v0-11-3";
+/// The name of the network used when none is specified.
+pub const HARDCODED_TESTNET: &str = "witti";
+
+/// The files of a testnet which was included in the binary at compile time (see `build.rs`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HardcodedNet {
+    /// The name by which the testnet is selected, e.g. with `--network`.
+    pub name: &'static str,
+    pub yaml_config: &'static [u8],
+    pub deploy_block: &'static [u8],
+    pub deposit_contract_address: &'static [u8],
+    pub genesis_state: &'static [u8],
+    pub boot_enr: &'static [u8],
+}
 
-pub const HARDCODED_YAML_CONFIG: &[u8] = include_bytes!("../witti-v0-11-3/config.yaml");
-pub const HARDCODED_DEPLOY_BLOCK: &[u8] = include_bytes!("../witti-v0-11-3/deploy_block.txt");
-pub const HARDCODED_DEPOSIT_CONTRACT: &[u8] =
-    include_bytes!("../witti-v0-11-3/deposit_contract.txt");
-pub const HARDCODED_GENESIS_STATE: &[u8] = include_bytes!("../witti-v0-11-3/genesis.ssz");
-pub const HARDCODED_BOOT_ENR: &[u8] = include_bytes!("../witti-v0-11-3/boot_enr.yaml");
+/// Includes the files of the testnet in the directory `$dir` of this crate.
+macro_rules! define_net {
+    ($name: expr, $dir: tt) => {
+        HardcodedNet {
+            name: $name,
+            yaml_config: include_bytes!(concat!("../", $dir, "/config.yaml")),
+            deploy_block: include_bytes!(concat!("../", $dir, "/deploy_block.txt")),
+            deposit_contract_address: include_bytes!(concat!("../", $dir, "/deposit_contract.txt")),
+            genesis_state: include_bytes!(concat!("../", $dir, "/genesis.ssz")),
+            boot_enr: include_bytes!(concat!("../", $dir, "/boot_enr.yaml")),
+        }
+    };
+}
+
+pub const WITTI: HardcodedNet = define_net!("witti", "witti-v0-11-3");
+
+/// All of the testnets included in the binary.
+///
+/// Only Witti is included, since it is the only public network which follows the v0.11.3 spec
+/// implemented by this release. Neither a mainnet genesis state nor the configurations of the
+/// other public testnets (e.g., Altona, which follows v0.12) can be embedded until they match this
+/// spec. Other networks are joined with `--testnet-dir`.
+pub const HARDCODED_NETS: &[HardcodedNet] = &[WITTI];
+
+/// Returns the names of all the testnets included in the binary.
+pub fn hardcoded_net_names() -> Vec<&'static str> {
+    HARDCODED_NETS.iter().map(|net| net.name).collect()
+}
 
 /// Specifies an Eth2 testnet.
 ///
@@ -42,27 +77,44 @@ pub struct Eth2TestnetConfig<E: EthSpec> {
 }
 
 impl<E: EthSpec> Eth2TestnetConfig<E> {
-    // Creates the `Eth2TestnetConfig` that was included in the binary at compile time. This can be
-    // considered the default Lighthouse testnet.
+    // Creates the `Eth2TestnetConfig` of the `HARDCODED_TESTNET`. This can be considered the
+    // default Lighthouse testnet.
     //
     // Returns an error if those included bytes are invalid (this is unlikely).
     pub fn hard_coded() -> Result<Self, String> {
+        Self::constant(HARDCODED_TESTNET)?
+            .ok_or_else(|| format!("No hard-coded testnet named {}", HARDCODED_TESTNET))
+    }
+
+    // Creates the `Eth2TestnetConfig` of the testnet named `name` which was included in the binary
+    // at compile time, returning `None` if there is no such testnet.
+    //
+    // Returns an error if those included bytes are invalid (this is unlikely).
+    pub fn constant(name: &str) -> Result<Option<Self>, String> {
+        HARDCODED_NETS
+            .iter()
+            .find(|net| net.name == name)
+            .map(Self::from_hardcoded_net)
+            .transpose()
+    }
+
+    fn from_hardcoded_net(net: &HardcodedNet) -> Result<Self, String> {
         Ok(Self {
-            deposit_contract_address: serde_yaml::from_reader(HARDCODED_DEPOSIT_CONTRACT)
+            deposit_contract_address: serde_yaml::from_reader(net.deposit_contract_address)
                 .map_err(|e| format!("Unable to parse contract address: {:?}", e))?,
-            deposit_contract_deploy_block: serde_yaml::from_reader(HARDCODED_DEPLOY_BLOCK)
+            deposit_contract_deploy_block: serde_yaml::from_reader(net.deploy_block)
                 .map_err(|e| format!("Unable to parse deploy block: {:?}", e))?,
             boot_enr: Some(
-                serde_yaml::from_reader(HARDCODED_BOOT_ENR)
+                serde_yaml::from_reader(net.boot_enr)
                     .map_err(|e| format!("Unable to parse boot enr: {:?}", e))?,
             ),
             genesis_state: Some(
-                BeaconState::from_ssz_bytes(HARDCODED_GENESIS_STATE)
+                BeaconState::from_ssz_bytes(net.genesis_state)
                     .map_err(|e| format!("Unable to parse genesis state: {:?}", e))?,
             ),
             yaml_config: Some(
-                serde_yaml::from_reader(HARDCODED_YAML_CONFIG)
-                    .map_err(|e| format!("Unable to parse genesis state: {:?}", e))?,
+                serde_yaml::from_reader(net.yaml_config)
+                    .map_err(|e| format!("Unable to parse yaml config: {:?}", e))?,
             ),
         })
     }
@@ -219,6 +271,16 @@ mod tests {
     }
     */
 
+    #[test]
+    fn hard_coded_names() {
+        assert!(hardcoded_net_names().contains(&HARDCODED_TESTNET));
+        assert_eq!(
+            Eth2TestnetConfig::<E>::constant("unknown-network"),
+            Ok(None),
+            "should not find a network which is not included"
+        );
+    }
+
     #[test]
     fn round_trip() {
         let spec = &E::default_spec();
@@ -56,7 +56,9 @@
 
 use prometheus::{HistogramOpts, HistogramTimer, Opts};
 
-pub use prometheus::{Encoder, Gauge, Histogram, IntCounter, IntGauge, Result, TextEncoder};
+pub use prometheus::{
+    Encoder, Gauge, Histogram, IntCounter, IntCounterVec, IntGauge, Result, TextEncoder,
+};
 
 /// Collect all the metrics for reporting.
 pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
@@ -72,6 +74,19 @@ pub fn try_create_int_counter(name: &str, help: &str) -> Result<IntCounter> {
     Ok(counter)
 }
 
+/// Attempts to crate an `IntCounterVec`, returning `Err` if the registry does not accept the
+/// counter (potentially due to naming conflict).
+pub fn try_create_int_counter_vec(
+    name: &str,
+    help: &str,
+    label_names: &[&str],
+) -> Result<IntCounterVec> {
+    let opts = Opts::new(name, help);
+    let counter_vec = IntCounterVec::new(opts, label_names)?;
+    prometheus::register(Box::new(counter_vec.clone()))?;
+    Ok(counter_vec)
+}
+
 /// Attempts to crate an `IntGauge`, returning `Err` if the registry does not accept the counter
 /// (potentially due to naming conflict).
 pub fn try_create_int_gauge(name: &str, help: &str) -> Result<IntGauge> {
@@ -127,6 +142,28 @@ pub fn inc_counter_by(counter: &Result<IntCounter>, value: i64) {
     }
 }
 
+/// Increments the counter in `counter_vec` with the given `label_values`.
+///
+/// Does nothing if the number of `label_values` does not match the labels of `counter_vec`.
+pub fn inc_counter_vec(counter_vec: &Result<IntCounterVec>, label_values: &[&str]) {
+    if let Ok(counter_vec) = counter_vec {
+        if let Ok(counter) = counter_vec.get_metric_with_label_values(label_values) {
+            counter.inc();
+        }
+    }
+}
+
+/// Increments the counter in `counter_vec` with the given `label_values` by `value`.
+///
+/// Does nothing if the number of `label_values` does not match the labels of `counter_vec`.
+pub fn inc_counter_vec_by(counter_vec: &Result<IntCounterVec>, label_values: &[&str], value: i64) {
+    if let Ok(counter_vec) = counter_vec {
+        if let Ok(counter) = counter_vec.get_metric_with_label_values(label_values) {
+            counter.inc_by(value);
+        }
+    }
+}
+
 pub fn set_gauge(gauge: &Result<IntGauge>, value: i64) {
     if let Ok(gauge) = gauge {
         gauge.set(value);
@@ -1,6 +1,12 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod log_control;
+
+pub use log_control::{
+    level_name, parse_level, CaptureHandle, ControlledDrain, LogControl, MAX_CAPTURE_LINES,
+};
+
 use lighthouse_metrics::{
     inc_counter, try_create_int_counter, IntCounter, Result as MetricsResult,
 };
@@ -0,0 +1,307 @@
+//! Allows the log level of each module to be changed whilst Lighthouse is running, and the logs of
+//! a single module to be captured at a more verbose level for a short time without changing what
+//! is logged elsewhere.
+//!
+//! A module is identified by a prefix of its module path (e.g., `beacon_chain` or
+//! `network::sync`). When several overrides apply to a record the longest (i.e., most specific)
+//! one is used.
+
+use parking_lot::{Mutex, RwLock};
+use slog::{Drain, Level, OwnedKVList, Record, Serializer, KV};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as FmtWrite};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The maximum number of lines retained by a single capture, to bound its memory usage.
+pub const MAX_CAPTURE_LINES: usize = 100_000;
+
+/// Parses a level as given to `--debug-level`.
+pub fn parse_level(name: &str) -> Result<Level, String> {
+    match name {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown debug-level: {}", unknown)),
+    }
+}
+
+/// Returns the name of `level`, as accepted by `parse_level`.
+pub fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+        Level::Warning => "warn",
+        Level::Error => "error",
+        Level::Critical => "crit",
+    }
+}
+
+/// Returns `true` if `module` is `prefix` or one of its sub-modules.
+fn module_matches(module: &str, prefix: &str) -> bool {
+    module == prefix || (module.starts_with(prefix) && module[prefix.len()..].starts_with("::"))
+}
+
+/// The records of a single module which are being collected, regardless of the log level.
+struct Capture {
+    module: String,
+    level: Level,
+    lines: Mutex<Vec<String>>,
+}
+
+struct Inner {
+    default_level: Level,
+    module_levels: BTreeMap<String, Level>,
+    captures: Vec<Arc<Capture>>,
+}
+
+impl Inner {
+    fn level_for(&self, module: &str) -> Level {
+        self.module_levels
+            .iter()
+            .filter(|(prefix, _)| module_matches(module, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default_level, |(_, level)| *level)
+    }
+}
+
+/// A handle to the log levels and captures of a logger built with `LogControl::drain`.
+///
+/// Cloning returns a handle to the same levels.
+#[derive(Clone)]
+pub struct LogControl {
+    inner: Arc<RwLock<Inner>>,
+}
+
+impl Default for LogControl {
+    fn default() -> Self {
+        Self::new(Level::Info)
+    }
+}
+
+impl LogControl {
+    /// Creates a control which logs records at `default_level` or above from all modules.
+    pub fn new(default_level: Level) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                default_level,
+                module_levels: BTreeMap::new(),
+                captures: vec![],
+            })),
+        }
+    }
+
+    /// Wraps `drain` so it only receives the records permitted by `self`.
+    pub fn drain<D: Drain>(&self, drain: D) -> ControlledDrain<D> {
+        ControlledDrain {
+            drain,
+            control: self.clone(),
+        }
+    }
+
+    /// Returns the level of modules without an override.
+    pub fn default_level(&self) -> Level {
+        self.inner.read().default_level
+    }
+
+    /// Sets the level of modules without an override.
+    pub fn set_default_level(&self, level: Level) {
+        self.inner.write().default_level = level;
+    }
+
+    /// Returns the modules whose level overrides the default level.
+    pub fn module_levels(&self) -> Vec<(String, Level)> {
+        self.inner
+            .read()
+            .module_levels
+            .iter()
+            .map(|(module, level)| (module.clone(), *level))
+            .collect()
+    }
+
+    /// Sets the level of `module` and its sub-modules, or reverts them to the default level if
+    /// `level` is `None`.
+    pub fn set_module_level(&self, module: String, level: Option<Level>) {
+        let mut inner = self.inner.write();
+        match level {
+            Some(level) => inner.module_levels.insert(module, level),
+            None => inner.module_levels.remove(&module),
+        };
+    }
+
+    /// Returns `true` if a record from `module` at `level` is logged.
+    pub fn is_enabled(&self, module: &str, level: Level) -> bool {
+        level.is_at_least(self.inner.read().level_for(module))
+    }
+
+    /// Starts collecting the records at `level` or above from `module` and its sub-modules, until
+    /// the returned handle is dropped.
+    pub fn start_capture(&self, module: String, level: Level) -> CaptureHandle {
+        let capture = Arc::new(Capture {
+            module,
+            level,
+            lines: Mutex::new(vec![]),
+        });
+        self.inner.write().captures.push(capture.clone());
+
+        CaptureHandle {
+            control: self.clone(),
+            capture,
+        }
+    }
+}
+
+/// Collects records for `LogControl::start_capture` until dropped.
+pub struct CaptureHandle {
+    control: LogControl,
+    capture: Arc<Capture>,
+}
+
+impl CaptureHandle {
+    /// Returns the lines collected so far, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        self.capture.lines.lock().clone()
+    }
+}
+
+impl Drop for CaptureHandle {
+    fn drop(&mut self) {
+        let capture = &self.capture;
+        self.control
+            .inner
+            .write()
+            .captures
+            .retain(|other| !Arc::ptr_eq(other, capture));
+    }
+}
+
+/// A `slog::Drain` which filters records according to a `LogControl`, passing them to any
+/// matching capture first.
+pub struct ControlledDrain<D> {
+    drain: D,
+    control: LogControl,
+}
+
+impl<D: Drain> Drain for ControlledDrain<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        let enabled = {
+            let inner = self.control.inner.read();
+
+            for capture in &inner.captures {
+                if record.level().is_at_least(capture.level)
+                    && module_matches(record.module(), &capture.module)
+                {
+                    let mut lines = capture.lines.lock();
+                    if lines.len() < MAX_CAPTURE_LINES {
+                        lines.push(format_line(record, values));
+                    }
+                }
+            }
+
+            record.level().is_at_least(inner.level_for(record.module()))
+        };
+
+        if enabled {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Formats `record` as a single line, including its module and key-value pairs.
+fn format_line(record: &Record, values: &OwnedKVList) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+
+    let mut line = format!(
+        "{} {} {} {}",
+        millis,
+        record.level().as_short_str(),
+        record.module(),
+        record.msg()
+    );
+
+    let mut serializer = LineSerializer(&mut line);
+    let _ = record.kv().serialize(record, &mut serializer);
+    let _ = values.serialize(record, &mut serializer);
+
+    line
+}
+
+/// Appends each key-value pair of a log record to a line.
+struct LineSerializer<'a>(&'a mut String);
+
+impl<'a> Serializer for LineSerializer<'a> {
+    fn emit_arguments(&mut self, key: slog::Key, val: &fmt::Arguments) -> slog::Result {
+        let _ = write!(self.0, ", {}: {}", key, val);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::{debug, info, o, Logger};
+
+    /// A drain which counts the records it receives.
+    #[derive(Clone, Default)]
+    struct Counter(Arc<Mutex<usize>>);
+
+    impl Drain for Counter {
+        type Ok = ();
+        type Err = slog::Never;
+
+        fn log(&self, _: &Record, _: &OwnedKVList) -> Result<(), slog::Never> {
+            *self.0.lock() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn module_levels() {
+        let control = LogControl::new(Level::Info);
+        control.set_module_level("beacon_chain".to_string(), Some(Level::Debug));
+        control.set_module_level("beacon_chain::fork_choice".to_string(), Some(Level::Error));
+
+        assert!(control.is_enabled("beacon_chain", Level::Debug));
+        assert!(control.is_enabled("beacon_chain::block_verification", Level::Debug));
+        assert!(!control.is_enabled("beacon_chain::fork_choice", Level::Info));
+        assert!(!control.is_enabled("beacon_chain_other", Level::Debug));
+        assert!(control.is_enabled("network", Level::Info));
+
+        control.set_module_level("beacon_chain".to_string(), None);
+        assert!(!control.is_enabled("beacon_chain", Level::Debug));
+        assert_eq!(control.module_levels().len(), 1);
+    }
+
+    #[test]
+    fn captures_without_logging() {
+        let control = LogControl::new(Level::Info);
+        let counter = Counter::default();
+        let log = Logger::root(control.drain(counter.clone()).fuse(), o!());
+
+        let capture = control.start_capture(module_path!().to_string(), Level::Debug);
+        debug!(log, "Captured"; "key" => 42);
+        info!(log, "Captured and logged");
+
+        let lines = capture.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("Captured, key: 42"));
+        assert_eq!(*counter.0.lock(), 1);
+
+        // Records are no longer captured once the handle is dropped.
+        drop(capture);
+        debug!(log, "Not captured");
+        assert!(control.inner.read().captures.is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! Provides statistics about, and control of, the memory allocator.
+//!
+//! When built with the `jemalloc` feature, jemalloc becomes the global allocator and its
+//! statistics are exposed as Prometheus metrics by `scrape_allocator_metrics`. Otherwise, the
+//! system allocator is used and these functions do nothing (or return an error).
+//!
+//! The statistics distinguish memory allocated by Lighthouse from memory which is held by the
+//! allocator but not in use (fragmentation), which is otherwise indistinguishable in the resident
+//! set size of the process.
+
+#[macro_use]
+extern crate lazy_static;
+
+use lighthouse_metrics::*;
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+
+lazy_static! {
+    pub static ref ALLOCATED: Result<IntGauge> = try_create_int_gauge(
+        "malloc_allocated_bytes",
+        "Bytes allocated by the application"
+    );
+    pub static ref ACTIVE: Result<IntGauge> = try_create_int_gauge(
+        "malloc_active_bytes",
+        "Bytes in pages which contain allocations (a multiple of the page size)"
+    );
+    pub static ref RESIDENT: Result<IntGauge> = try_create_int_gauge(
+        "malloc_resident_bytes",
+        "Bytes in physically resident pages mapped by the allocator"
+    );
+    pub static ref MAPPED: Result<IntGauge> = try_create_int_gauge(
+        "malloc_mapped_bytes",
+        "Bytes in chunks mapped by the allocator"
+    );
+    pub static ref RETAINED: Result<IntGauge> = try_create_int_gauge(
+        "malloc_retained_bytes",
+        "Bytes in virtual memory retained by the allocator rather than returned to the OS"
+    );
+    pub static ref FRAGMENTATION: Result<Gauge> = try_create_float_gauge(
+        "malloc_fragmentation_ratio",
+        "The fraction of active bytes which are not allocated"
+    );
+}
+
+/// Returns the name of the global allocator.
+pub fn allocator_name() -> &'static str {
+    if cfg!(feature = "jemalloc") {
+        "jemalloc"
+    } else {
+        "system"
+    }
+}
+
+/// Updates the allocator metrics. Does nothing unless built with the `jemalloc` feature.
+pub fn scrape_allocator_metrics() {
+    #[cfg(feature = "jemalloc")]
+    jemalloc::scrape_allocator_metrics();
+}
+
+/// Returns unused memory held by the allocator to the operating system.
+#[cfg(feature = "jemalloc")]
+pub fn purge() -> Result<(), String> {
+    jemalloc::purge()
+}
+
+/// Returns an error, since the system allocator cannot be purged.
+#[cfg(not(feature = "jemalloc"))]
+pub fn purge() -> Result<(), String> {
+    Err("Lighthouse was not built with the jemalloc feature".to_string())
+}
+
+#[cfg(feature = "jemalloc")]
+mod jemalloc {
+    use super::*;
+    use jemalloc_ctl::{epoch, stats};
+    use std::ptr;
+
+    /// Addresses every arena when used as the index in an `arena.<i>.*` control name.
+    const MALLCTL_ARENAS_ALL: usize = 4096;
+
+    pub fn scrape_allocator_metrics() {
+        // The statistics are a snapshot, taken when the epoch is advanced.
+        if epoch::advance().is_err() {
+            return;
+        }
+
+        let allocated = stats::allocated::read().ok();
+        let active = stats::active::read().ok();
+
+        maybe_set_gauge(&ALLOCATED, allocated.map(|bytes| bytes as i64));
+        maybe_set_gauge(&ACTIVE, active.map(|bytes| bytes as i64));
+        maybe_set_gauge(&RESIDENT, stats::resident::read().ok().map(|b| b as i64));
+        maybe_set_gauge(&MAPPED, stats::mapped::read().ok().map(|b| b as i64));
+        maybe_set_gauge(&RETAINED, stats::retained::read().ok().map(|b| b as i64));
+
+        if let (Some(allocated), Some(active)) = (allocated, active) {
+            if active > 0 {
+                set_float_gauge(&FRAGMENTATION, 1.0 - allocated as f64 / active as f64);
+            }
+        }
+    }
+
+    pub fn purge() -> Result<(), String> {
+        let name = format!("arena.{}.purge\0", MALLCTL_ARENAS_ALL);
+
+        // Safe since the name is nul-terminated and `purge` neither reads nor writes a value.
+        let ret = unsafe {
+            jemalloc_sys::mallctl(
+                name.as_ptr() as *const _,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(format!("jemalloc purge failed with error code {}", ret))
+        }
+    }
+}
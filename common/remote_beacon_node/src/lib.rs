@@ -1,12 +1,15 @@
 //! Provides a `RemoteBeaconNode` which interacts with a HTTP API on another Lighthouse (or
-//! compatible) instance.
+//! compatible) instance, or with the API of a beacon node in the same process.
 //!
 //! Presently, this is only used for testing but it _could_ become a user-facing library.
 
 use eth2_config::Eth2Config;
-use reqwest::{Client, ClientBuilder, Response, StatusCode};
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    Client, ClientBuilder, Method, Response, StatusCode,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use ssz::Encode;
+use ssz::{Decode, Encode};
 use std::marker::PhantomData;
 use std::time::Duration;
 use types::{
@@ -19,9 +22,13 @@ use url::Url;
 pub use operation_pool::PersistedOperationPool;
 pub use proto_array_fork_choice::core::ProtoArray;
 pub use rest_types::{
-    CanonicalHeadResponse, Committee, HeadBeaconBlock, Health, IndividualVotesRequest,
-    IndividualVotesResponse, SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes,
-    ValidatorRequest, ValidatorResponse, ValidatorSubscription,
+    CanonicalHeadResponse, CheckpointResponse, Committee, ErrorMessage, HeadBeaconBlock, Health,
+    HistoricalDuties, HistoricalDutiesRequest, ImportBlocksResponse, IndividualVotesRequest,
+    IndividualVotesResponse, LocalRequest, LocalRequestSender, PeeringResponse,
+    PeeringUpdateRequest, ProposerDutiesResponse, ProposerDuty, PublishRejection, StakingEstimate,
+    SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorRequest,
+    ValidatorResponse, ValidatorStatus, ValidatorStatusRequest, ValidatorStatusResponse,
+    ValidatorSubscription, CONTINUATION_TOKEN_HEADER,
 };
 
 // Setting a long timeout for debug ensures that crypto-heavy operations can still succeed.
@@ -31,6 +38,10 @@ pub const REQUEST_TIMEOUT_SECONDS: u64 = 15;
 #[cfg(not(debug_assertions))]
 pub const REQUEST_TIMEOUT_SECONDS: u64 = 5;
 
+/// The base URL of requests to a beacon node in the same process. Only the path and query of each
+/// request are used.
+const LOCAL_URL: &str = "http://in-process/";
+
 #[derive(Clone)]
 /// Connects to a remote Lighthouse (or compatible) node via HTTP.
 pub struct RemoteBeaconNode<E: EthSpec> {
@@ -44,11 +55,29 @@ impl<E: EthSpec> RemoteBeaconNode<E> {
     }
 
     pub fn new_with_timeout(http_endpoint: String, timeout: Duration) -> Result<Self, String> {
+        Self::new_with_auth_token(http_endpoint, timeout, None)
+    }
+
+    /// Presents `auth_token` (e.g., as written by a beacon node started with `--http-require-auth`)
+    /// with every request, if it is supplied.
+    pub fn new_with_auth_token(
+        http_endpoint: String,
+        timeout: Duration,
+        auth_token: Option<String>,
+    ) -> Result<Self, String> {
         Ok(Self {
-            http: HttpClient::new(http_endpoint, timeout)
+            http: HttpClient::new(http_endpoint, timeout, auth_token)
                 .map_err(|e| format!("Unable to create http client: {:?}", e))?,
         })
     }
+
+    /// Sends requests to the beacon node in the same process which serves `requests`, rather than
+    /// over HTTP, giving up on each request after `timeout`.
+    pub fn new_local(requests: LocalRequestSender, timeout: Duration) -> Self {
+        Self {
+            http: HttpClient::new_local(requests, timeout),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -60,14 +89,32 @@ pub enum Error {
     /// There was an error when encoding/decoding an object using serde.
     SerdeJsonError(serde_json::Error),
     /// The server responded to the request, however it did not return a 200-type success code.
+    ///
+    /// The `body` is the `message` of the `ErrorMessage` returned by the server, or the entire
+    /// body if the server did not return an `ErrorMessage`.
     DidNotSucceed { status: StatusCode, body: String },
     /// The request input was invalid.
     InvalidInput,
+    /// The authentication token contains characters which are not valid in a HTTP header.
+    InvalidAuthToken,
+    /// The server responded with bytes that could not be decoded as SSZ.
+    SszDecodeError(ssz::DecodeError),
+    /// The beacon node in the same process has shut down, or was unable to serve the request.
+    LocalNodeUnavailable,
+    /// The beacon node in the same process did not respond within the timeout.
+    LocalNodeTimeout,
+}
+
+/// How requests reach the node.
+#[derive(Clone)]
+enum Transport {
+    Http(Client),
+    Local(LocalRequestSender),
 }
 
 #[derive(Clone)]
 pub struct HttpClient<E> {
-    client: Client,
+    transport: Transport,
     url: Url,
     timeout: Duration,
     _phantom: PhantomData<E>,
@@ -75,18 +122,56 @@ pub struct HttpClient<E> {
 
 impl<E: EthSpec> HttpClient<E> {
     /// Creates a new instance (without connecting to the node).
-    pub fn new(server_url: String, timeout: Duration) -> Result<Self, Error> {
+    ///
+    /// If `auth_token` is supplied, it is presented in the `Authorization` header of every request.
+    pub fn new(
+        server_url: String,
+        timeout: Duration,
+        auth_token: Option<String>,
+    ) -> Result<Self, Error> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = auth_token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| Error::InvalidAuthToken)?;
+            value.set_sensitive(true);
+            headers.insert(header::AUTHORIZATION, value);
+        }
+
         Ok(Self {
-            client: ClientBuilder::new()
-                .timeout(timeout)
-                .build()
-                .expect("should build from static configuration"),
+            transport: Transport::Http(
+                ClientBuilder::new()
+                    .timeout(timeout)
+                    .default_headers(headers)
+                    .build()
+                    .expect("should build from static configuration"),
+            ),
             url: Url::parse(&server_url)?,
-            timeout: Duration::from_secs(15),
+            timeout,
             _phantom: PhantomData,
         })
     }
 
+    /// Creates a new instance which sends each request to the beacon node in the same process
+    /// which serves `requests`.
+    ///
+    /// The beacon node does not require authentication of these requests.
+    pub fn new_local(requests: LocalRequestSender, timeout: Duration) -> Self {
+        Self {
+            transport: Transport::Local(requests),
+            url: Url::parse(LOCAL_URL).expect("should parse static URL"),
+            timeout,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the URL of the node, or `"in-process"` if it is in the same process.
+    pub fn endpoint(&self) -> String {
+        match self.transport {
+            Transport::Http(_) => self.url.to_string(),
+            Transport::Local(_) => "in-process".to_string(),
+        }
+    }
+
     pub fn beacon(&self) -> Beacon<E> {
         Beacon(self.clone())
     }
@@ -111,17 +196,58 @@ impl<E: EthSpec> HttpClient<E> {
         Consensus(self.clone())
     }
 
+    pub fn lighthouse(&self) -> Lighthouse<E> {
+        Lighthouse(self.clone())
+    }
+
     fn url(&self, path: &str) -> Result<Url, Error> {
         self.url.join(path).map_err(|e| e.into())
     }
 
+    /// Sends a request to the node, with a body of `content_type` if one is supplied.
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<(&str, Vec<u8>)>,
+    ) -> Result<Response, Error> {
+        match &self.transport {
+            Transport::Http(client) => {
+                let mut request = client.request(method, &url.to_string());
+                if let Some((content_type, body)) = body {
+                    request = request
+                        .header(header::CONTENT_TYPE, content_type)
+                        .body(body);
+                }
+                request.send().await.map_err(Error::from)
+            }
+            Transport::Local(requests) => {
+                let mut request = http::Request::builder().method(method).uri(url.as_str());
+                let body = if let Some((content_type, body)) = body {
+                    request = request.header(header::CONTENT_TYPE, content_type);
+                    body
+                } else {
+                    vec![]
+                };
+                let request = request.body(body).map_err(|_| Error::InvalidInput)?;
+
+                let (response, receiver) = futures::channel::oneshot::channel();
+                requests
+                    .unbounded_send(LocalRequest { request, response })
+                    .map_err(|_| Error::LocalNodeUnavailable)?;
+                tokio::time::timeout(self.timeout, receiver)
+                    .await
+                    .map_err(|_| Error::LocalNodeTimeout)?
+                    .map(Response::from)
+                    .map_err(|_| Error::LocalNodeUnavailable)
+            }
+        }
+    }
+
     pub async fn json_post<T: Serialize>(&self, url: Url, body: T) -> Result<Response, Error> {
-        self.client
-            .post(&url.to_string())
-            .json(&body)
-            .send()
+        let body = serde_json::to_vec(&body)?;
+        self.send(Method::POST, url, Some(("application/json", body)))
             .await
-            .map_err(Error::from)
     }
 
     pub async fn json_get<T: DeserializeOwned>(
@@ -133,16 +259,54 @@ impl<E: EthSpec> HttpClient<E> {
             url.query_pairs_mut().append_pair(&key, &param);
         });
 
-        let response = self
-            .client
-            .get(&url.to_string())
-            .send()
-            .await
-            .map_err(Error::from)?;
+        let response = self.send(Method::GET, url, None).await?;
 
         let success = error_for_status(response).await.map_err(Error::from)?;
         success.json::<T>().await.map_err(Error::from)
     }
+
+    /// Performs `json_get` for each page of a paginated list of results, following the
+    /// continuation token returned by the server until all pages have been collected.
+    pub async fn json_get_paginated<T: DeserializeOwned>(
+        &self,
+        url: Url,
+        query_pairs: Vec<(String, String)>,
+    ) -> Result<Vec<T>, Error> {
+        let mut results = vec![];
+        let mut continuation_token = None;
+
+        loop {
+            let mut url = url.clone();
+            query_pairs.iter().for_each(|(key, param)| {
+                url.query_pairs_mut().append_pair(key, param);
+            });
+            if let Some(token) = &continuation_token {
+                url.query_pairs_mut()
+                    .append_pair("continuation_token", token);
+            }
+
+            let response = self.send(Method::GET, url, None).await?;
+
+            let success = error_for_status(response).await.map_err(Error::from)?;
+            continuation_token = success
+                .headers()
+                .get(CONTINUATION_TOKEN_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+            results.append(&mut success.json::<Vec<T>>().await.map_err(Error::from)?);
+
+            if continuation_token.is_none() {
+                return Ok(results);
+            }
+        }
+    }
+}
+
+/// Returns the `message` of the `ErrorMessage` in `body`, or `body` if it is not an `ErrorMessage`.
+fn error_message(body: String) -> String {
+    serde_json::from_str::<ErrorMessage>(&body)
+        .map(|error| error.message)
+        .unwrap_or(body)
 }
 
 /// Returns an `Error` (with a description) if the `response` was not a 200-type success response.
@@ -158,7 +322,10 @@ async fn error_for_status(response: Response) -> Result<Response, Error> {
         let text_result = response.text().await;
         match text_result {
             Err(e) => Err(Error::ReqwestError(e)),
-            Ok(body) => Err(Error::DidNotSucceed { status, body }),
+            Ok(body) => Err(Error::DidNotSucceed {
+                status,
+                body: error_message(body),
+            }),
         }
     }
 }
@@ -169,6 +336,8 @@ pub enum PublishStatus {
     Valid,
     /// The object was not valid and may or may not have been published to the network.
     Invalid(String),
+    /// The object was not valid and has not been published to the network, for the given reason.
+    Rejected(PublishRejection),
     /// The server responded with an unknown status code. The object may or may not have been
     /// published to the network.
     Unknown,
@@ -179,6 +348,14 @@ impl PublishStatus {
     pub fn is_valid(&self) -> bool {
         *self == PublishStatus::Valid
     }
+
+    /// Interprets the body of a `202 Accepted` response, which is a `PublishRejection` if the
+    /// server provided one.
+    fn from_accepted_body(body: String) -> Self {
+        serde_json::from_str(&body)
+            .map(PublishStatus::Rejected)
+            .unwrap_or_else(|_| PublishStatus::Invalid(error_message(body)))
+    }
 }
 
 /// Provides the functions on the `/validator` endpoint of the node.
@@ -235,7 +412,7 @@ impl<E: EthSpec> Validator<E> {
 
         match response.status() {
             StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+            StatusCode::ACCEPTED => Ok(PublishStatus::from_accepted_body(
                 response.text().await.map_err(Error::from)?,
             )),
             _ => response
@@ -258,7 +435,7 @@ impl<E: EthSpec> Validator<E> {
 
         match response.status() {
             StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+            StatusCode::ACCEPTED => Ok(PublishStatus::from_accepted_body(
                 response.text().await.map_err(Error::from)?,
             )),
             _ => response
@@ -290,6 +467,41 @@ impl<E: EthSpec> Validator<E> {
         success.json().await.map_err(Error::from)
     }
 
+    /// Returns the duties of the given validator pubkeys in each epoch from `start_epoch` to
+    /// `end_epoch` (inclusive), all of which must be finalized.
+    pub async fn get_historical_duties(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        validator_pubkeys: &[PublicKey],
+    ) -> Result<Vec<HistoricalDuties>, Error> {
+        let client = self.0.clone();
+
+        let request = HistoricalDutiesRequest {
+            start_epoch,
+            end_epoch,
+            pubkeys: validator_pubkeys
+                .iter()
+                .map(|pubkey| pubkey.clone().into())
+                .collect(),
+        };
+
+        let url = self.url("duties/history")?;
+        let response = client.json_post::<_>(url, request).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Returns the block proposer for each slot in the given epoch.
+    pub async fn get_proposer_duties(&self, epoch: Epoch) -> Result<ProposerDutiesResponse, Error> {
+        let client = self.0.clone();
+        let url = self.0.url(&format!(
+            "eth/v1/validator/duties/proposer/{}",
+            epoch.as_u64()
+        ))?;
+        client.json_get(url, vec![]).await
+    }
+
     /// Posts a block to the beacon node, expecting it to verify it and publish it to the network.
     pub async fn publish_block(&self, block: SignedBeaconBlock<E>) -> Result<PublishStatus, Error> {
         let client = self.0.clone();
@@ -298,7 +510,7 @@ impl<E: EthSpec> Validator<E> {
 
         match response.status() {
             StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+            StatusCode::ACCEPTED => Ok(PublishStatus::from_accepted_body(
                 response.text().await.map_err(Error::from)?,
             )),
             _ => response
@@ -309,22 +521,26 @@ impl<E: EthSpec> Validator<E> {
     }
 
     /// Requests a new (unsigned) block from the beacon node.
+    ///
+    /// The block contains `graffiti`, if supplied, otherwise the beacon node's default graffiti.
     pub async fn produce_block(
         &self,
         slot: Slot,
         randao_reveal: Signature,
+        graffiti: Option<[u8; 32]>,
     ) -> Result<BeaconBlock<E>, Error> {
         let client = self.0.clone();
         let url = self.url("block")?;
-        client
-            .json_get::<BeaconBlock<E>>(
-                url,
-                vec![
-                    ("slot".into(), format!("{}", slot.as_u64())),
-                    ("randao_reveal".into(), as_ssz_hex_string(&randao_reveal)),
-                ],
-            )
-            .await
+
+        let mut query_params = vec![
+            ("slot".into(), format!("{}", slot.as_u64())),
+            ("randao_reveal".into(), as_ssz_hex_string(&randao_reveal)),
+        ];
+        if let Some(graffiti) = graffiti {
+            query_params.push(("graffiti".into(), as_ssz_hex_string(&graffiti)));
+        }
+
+        client.json_get::<BeaconBlock<E>>(url, query_params).await
     }
 
     /// Subscribes a list of validators to particular slots for attestation production/publication.
@@ -338,7 +554,7 @@ impl<E: EthSpec> Validator<E> {
 
         match response.status() {
             StatusCode::OK => Ok(PublishStatus::Valid),
-            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+            StatusCode::ACCEPTED => Ok(PublishStatus::from_accepted_body(
                 response.text().await.map_err(Error::from)?,
             )),
             _ => response
@@ -382,6 +598,23 @@ impl<E: EthSpec> Beacon<E> {
         client.json_get(url, vec![]).await
     }
 
+    /// Returns the `AttestationData` the node would attest to for the given `slot` and
+    /// `committee_index`.
+    pub async fn get_attestation_data(
+        &self,
+        slot: Slot,
+        committee_index: CommitteeIndex,
+    ) -> Result<AttestationData, Error> {
+        let query_params = vec![
+            ("slot".into(), format!("{}", slot)),
+            ("committee_index".into(), format!("{}", committee_index)),
+        ];
+
+        let client = self.0.clone();
+        let url = self.url("attestation_data")?;
+        client.json_get(url, query_params).await
+    }
+
     /// Returns info about the head of the canonical beacon chain.
     pub async fn get_head(&self) -> Result<CanonicalHeadResponse, Error> {
         let client = self.0.clone();
@@ -443,6 +676,14 @@ impl<E: EthSpec> Beacon<E> {
             .await
     }
 
+    /// Returns the latest finalized block and its post-state, for use as a checkpoint sync
+    /// source.
+    pub async fn get_checkpoint(&self) -> Result<CheckpointResponse<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url("checkpoint")?;
+        client.json_get(url, vec![]).await
+    }
+
     /// Returns the root of the state at the given slot.
     pub async fn get_state_root(&self, slot: Slot) -> Result<Hash256, Error> {
         let client = self.0.clone();
@@ -517,7 +758,7 @@ impl<E: EthSpec> Beacon<E> {
         };
 
         let url = self.url("validators/all")?;
-        client.json_get(url, query_params).await
+        client.json_get_paginated(url, query_params).await
     }
 
     /// Returns the active validators.
@@ -537,7 +778,7 @@ impl<E: EthSpec> Beacon<E> {
         };
 
         let url = self.url("validators/active")?;
-        client.json_get(url, query_params).await
+        client.json_get_paginated(url, query_params).await
     }
 
     /// Returns committees at the given epoch.
@@ -546,10 +787,49 @@ impl<E: EthSpec> Beacon<E> {
 
         let url = self.url("committees")?;
         client
-            .json_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
+            .json_get_paginated(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
             .await
     }
 
+    /// Returns the committees of the state identified by `state_id` (e.g., `"head"` or a slot).
+    ///
+    /// The `query_params` may be used to filter (`epoch`, `index`, `slot`) and paginate
+    /// (`continuation_token`, `limit`) the committees. Only a single page of committees is
+    /// returned.
+    pub async fn get_state_committees(
+        &self,
+        state_id: &str,
+        query_params: Vec<(String, String)>,
+    ) -> Result<Vec<Committee>, Error> {
+        let client = self.0.clone();
+
+        let url = self
+            .0
+            .url(&format!("eth/v1/beacon/states/{}/committees", state_id))?;
+        client.json_get(url, query_params).await
+    }
+
+    /// Returns the status of each of the given validators in the state identified by `state_id`.
+    ///
+    /// The results for `pubkeys` are returned first, followed by the results for `indices`.
+    pub async fn get_validator_statuses(
+        &self,
+        state_id: &str,
+        pubkeys: Vec<PublicKeyBytes>,
+        indices: Vec<u64>,
+    ) -> Result<Vec<ValidatorStatusResponse>, Error> {
+        let client = self.0.clone();
+
+        let bulk_request = ValidatorStatusRequest { pubkeys, indices };
+
+        let url = self
+            .0
+            .url(&format!("eth/v1/beacon/states/{}/validators", state_id))?;
+        let response = client.json_post::<_>(url, bulk_request).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
     pub async fn proposer_slashing(
         &self,
         proposer_slashing: ProposerSlashing,
@@ -669,7 +949,7 @@ impl<E: EthSpec> Consensus<E> {
         &self,
         epoch: Epoch,
         pubkeys: Vec<PublicKeyBytes>,
-    ) -> Result<IndividualVotesResponse, Error> {
+    ) -> Result<Vec<IndividualVotesResponse>, Error> {
         let client = self.0.clone();
         let req_body = IndividualVotesRequest { epoch, pubkeys };
 
@@ -688,6 +968,103 @@ impl<E: EthSpec> Consensus<E> {
     }
 }
 
+/// Provides the functions on the `/lighthouse` endpoint of the node.
+#[derive(Clone)]
+pub struct Lighthouse<E>(HttpClient<E>);
+
+impl<E: EthSpec> Lighthouse<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("lighthouse/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Returns the block identified by `block_id` (e.g., `"head"`, a slot or a `0x` prefixed
+    /// root), transferred as raw SSZ.
+    pub async fn get_block_ssz(&self, block_id: &str) -> Result<SignedBeaconBlock<E>, Error> {
+        let url = self.url(&format!("beacon/blocks/{}/ssz", block_id))?;
+        let response = self.0.send(Method::GET, url, None).await?;
+        let bytes = error_for_status(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(Error::from)?;
+
+        SignedBeaconBlock::from_ssz_bytes(&bytes).map_err(Error::SszDecodeError)
+    }
+
+    /// Returns a tar archive of the canonical blocks in the `count` slots starting at the block
+    /// identified by `block_id`, with one SSZ file per block.
+    pub async fn get_blocks_archive(&self, block_id: &str, count: usize) -> Result<Vec<u8>, Error> {
+        let mut url = self.url(&format!("beacon/blocks/{}/ssz", block_id))?;
+        url.query_pairs_mut()
+            .append_pair("count", &format!("{}", count));
+
+        let response = self.0.send(Method::GET, url, None).await?;
+        let bytes = error_for_status(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(Error::from)?;
+
+        Ok(bytes.to_vec())
+    }
+
+    /// Imports the blocks in `body` into the node's chain, with full verification.
+    ///
+    /// The `content_type` must be `application/ssz`, in which case `body` is a single SSZ-encoded
+    /// `SignedBeaconBlock`, or `application/x-tar`, in which case `body` is a tar archive of them
+    /// (e.g., as returned by the `/lighthouse/beacon/blocks/{block_id}/ssz?count` endpoint).
+    pub async fn import_blocks_ssz(
+        &self,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<ImportBlocksResponse, Error> {
+        let url = self.url("beacon/blocks")?;
+        let response = self
+            .0
+            .send(Method::POST, url, Some((content_type, body)))
+            .await?;
+
+        error_for_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the boot nodes and trusted peers of the node.
+    pub async fn get_peering(&self) -> Result<PeeringResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("peering")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Adds or removes boot nodes and trusted peers, without restarting the node.
+    pub async fn update_peering(&self, request: PeeringUpdateRequest) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url("peering")?;
+        let response = client.json_post::<_>(url, request).await?;
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// Returns the expected rewards and activation delay of a new validator, including the time
+    /// taken to earn `cost_gwei`, if any.
+    pub async fn get_staking_estimate(
+        &self,
+        cost_gwei: Option<u64>,
+    ) -> Result<StakingEstimate, Error> {
+        let client = self.0.clone();
+        let url = self.url("staking/estimate")?;
+        let query_pairs = cost_gwei
+            .map(|cost| vec![("cost_gwei".into(), format!("{}", cost))])
+            .unwrap_or_default();
+        client.json_get(url, query_pairs).await
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(bound = "T: EthSpec")]
 pub struct BlockResponse<T: EthSpec> {
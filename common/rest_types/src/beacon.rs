@@ -4,7 +4,7 @@ use bls::PublicKeyBytes;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use types::beacon_state::EthSpec;
-use types::{BeaconState, CommitteeIndex, Hash256, SignedBeaconBlock, Slot, Validator};
+use types::{BeaconState, CommitteeIndex, Epoch, Hash256, SignedBeaconBlock, Slot, Validator};
 
 /// Information about a block that is at the head of a chain. May or may not represent the
 /// canonical head.
@@ -50,6 +50,100 @@ pub struct ValidatorRequest {
     pub pubkeys: Vec<PublicKeyBytes>,
 }
 
+/// A request for the status of many validators, identified by pubkey and/or index.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorStatusRequest {
+    #[serde(default)]
+    pub pubkeys: Vec<PublicKeyBytes>,
+    #[serde(default)]
+    pub indices: Vec<u64>,
+}
+
+/// The status of a validator at some epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    /// The validator is not known to the state.
+    Unknown,
+    /// The validator has deposited but is not yet eligible for activation.
+    PendingInitialized,
+    /// The validator is in the activation queue.
+    PendingQueued,
+    /// The validator is active and has not initiated an exit.
+    ActiveOngoing,
+    /// The validator is active and has initiated a voluntary exit.
+    ActiveExiting,
+    /// The validator is active and has been slashed.
+    ActiveSlashed,
+    /// The validator has exited without being slashed.
+    ExitedUnslashed,
+    /// The validator has exited after being slashed.
+    ExitedSlashed,
+    /// The validator's balance may be withdrawn.
+    WithdrawalPossible,
+}
+
+impl ValidatorStatus {
+    /// Determines the status of `validator` at the given `epoch`, where `None` indicates an
+    /// unknown validator.
+    pub fn from_validator(
+        validator: Option<&Validator>,
+        epoch: Epoch,
+        far_future_epoch: Epoch,
+    ) -> Self {
+        match validator {
+            None => ValidatorStatus::Unknown,
+            Some(validator) if validator.is_withdrawable_at(epoch) => {
+                ValidatorStatus::WithdrawalPossible
+            }
+            Some(validator) if validator.is_exited_at(epoch) => {
+                if validator.slashed {
+                    ValidatorStatus::ExitedSlashed
+                } else {
+                    ValidatorStatus::ExitedUnslashed
+                }
+            }
+            Some(validator) if validator.is_active_at(epoch) => {
+                if validator.slashed {
+                    ValidatorStatus::ActiveSlashed
+                } else if validator.exit_epoch != far_future_epoch {
+                    ValidatorStatus::ActiveExiting
+                } else {
+                    ValidatorStatus::ActiveOngoing
+                }
+            }
+            Some(validator) if validator.activation_eligibility_epoch == far_future_epoch => {
+                ValidatorStatus::PendingInitialized
+            }
+            Some(_) => ValidatorStatus::PendingQueued,
+        }
+    }
+
+    /// Returns `true` if the validator has exited or been slashed, in which case it will never be
+    /// required to perform any further duties.
+    pub fn is_exited_or_slashed(&self) -> bool {
+        match self {
+            ValidatorStatus::ActiveSlashed
+            | ValidatorStatus::ExitedUnslashed
+            | ValidatorStatus::ExitedSlashed
+            | ValidatorStatus::WithdrawalPossible => true,
+            _ => false,
+        }
+    }
+}
+
+/// The status and balance of a single validator, as returned from a `ValidatorStatusRequest`.
+///
+/// The `pubkey` and `validator_index` are `None` if the validator is not known to the state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorStatusResponse {
+    pub pubkey: Option<PublicKeyBytes>,
+    pub validator_index: Option<u64>,
+    pub balance: Option<u64>,
+    pub status: ValidatorStatus,
+    pub validator: Option<Validator>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct Committee {
     pub slot: Slot,
@@ -63,3 +157,13 @@ pub struct StateResponse<T: EthSpec> {
     pub root: Hash256,
     pub beacon_state: BeaconState<T>,
 }
+
+/// The latest finalized block and its post-state, suitable for checkpoint sync.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+#[serde(bound = "T: EthSpec")]
+pub struct CheckpointResponse<T: EthSpec> {
+    pub block_root: Hash256,
+    pub beacon_block: SignedBeaconBlock<T>,
+    pub state_root: Hash256,
+    pub beacon_state: BeaconState<T>,
+}
@@ -0,0 +1,22 @@
+//! The body of every unsuccessful response from the HTTP API.
+use serde::{Deserialize, Serialize};
+
+/// Returned by the beacon node, as JSON, whenever a request does not succeed.
+///
+/// This has the same shape as the errors of the standard Eth2 beacon node API.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ErrorMessage {
+    /// The HTTP status code of the response, which identifies the class of failure (e.g., `400`
+    /// for an invalid request or `404` for an unknown object).
+    pub code: u16,
+    /// A stable, machine-readable identifier of the class of failure (e.g., `bad_request`), which
+    /// also distinguishes failures that share a status code. Empty if the server did not provide
+    /// one.
+    #[serde(default)]
+    pub kind: String,
+    /// A human-readable description of the failure.
+    pub message: String,
+    /// Always empty, since Lighthouse does not return stack traces.
+    #[serde(default)]
+    pub stacktraces: Vec<String>,
+}
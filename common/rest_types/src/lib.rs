@@ -4,18 +4,36 @@
 
 mod beacon;
 mod consensus;
+mod error;
+mod lighthouse;
+mod local;
 mod node;
 mod validator;
 
+/// The response header which contains the token used to fetch the next page of a paginated list
+/// of results. The token should be provided as the `continuation_token` query parameter.
+pub const CONTINUATION_TOKEN_HEADER: &str = "x-continuation-token";
+
 pub use beacon::{
-    BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
-    ValidatorRequest, ValidatorResponse,
+    BlockResponse, CanonicalHeadResponse, CheckpointResponse, Committee, HeadBeaconBlock,
+    StateResponse, ValidatorRequest, ValidatorResponse, ValidatorStatus, ValidatorStatusRequest,
+    ValidatorStatusResponse,
 };
 
 pub use validator::{
-    ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
+    HistoricalDuties, HistoricalDutiesRequest, ProposerDutiesResponse, ProposerDuty,
+    PublishRejection, ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes,
+    ValidatorSubscription,
 };
 
+pub use error::ErrorMessage;
+
 pub use consensus::{IndividualVote, IndividualVotesRequest, IndividualVotesResponse};
 
+pub use lighthouse::{
+    ImportBlocksResponse, PeeringNode, PeeringResponse, PeeringUpdateRequest, StakingEstimate,
+};
+
+pub use local::{local_request_channel, LocalRequest, LocalRequestReceiver, LocalRequestSender};
+
 pub use node::{Health, SyncingResponse, SyncingStatus};
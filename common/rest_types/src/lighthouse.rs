@@ -0,0 +1,76 @@
+//! Collection of types for the /lighthouse HTTP
+use serde::{Deserialize, Serialize};
+use types::{Epoch, Hash256};
+
+/// A boot node or trusted peer of the node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeeringNode {
+    /// The libp2p `PeerId` of the node, as a base58 string.
+    pub peer_id: String,
+    /// The ENR of the node, as a base64 string.
+    pub enr: String,
+}
+
+/// The response for the /lighthouse/peering HTTP GET.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeeringResponse {
+    pub boot_nodes: Vec<PeeringNode>,
+    pub trusted_peers: Vec<PeeringNode>,
+}
+
+/// The request body for the /lighthouse/peering HTTP POST.
+///
+/// Nodes are added by ENR and removed by `PeerId`. All fields are optional.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeeringUpdateRequest {
+    pub add_boot_nodes: Vec<String>,
+    pub remove_boot_nodes: Vec<String>,
+    pub add_trusted_peers: Vec<String>,
+    pub remove_trusted_peers: Vec<String>,
+    /// Restart peer discovery and re-dial all boot nodes and trusted peers.
+    pub rediscover: bool,
+}
+
+/// The response for the /lighthouse/beacon/blocks HTTP POST.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportBlocksResponse {
+    /// The number of blocks imported, excluding any which were already known.
+    pub imported_blocks: usize,
+    /// The reason the import stopped before the last block, if it did.
+    pub error: Option<String>,
+    /// The root of the head block after the import.
+    pub head_block_root: Hash256,
+}
+
+/// The response for the /lighthouse/staking/estimate HTTP GET.
+///
+/// An estimate of the rewards of a new validator which attests perfectly, from the head state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StakingEstimate {
+    /// The epoch of the head state.
+    pub epoch: Epoch,
+    pub active_validator_count: usize,
+    pub total_active_balance_gwei: u64,
+    /// The fraction of the active balance which attested to the target in the previous epoch.
+    pub participation_rate: f64,
+    /// The balance which must be deposited to become a validator.
+    pub deposit_gwei: u64,
+    /// The expected reward of the validator in each epoch.
+    pub epoch_reward_gwei: u64,
+    /// The expected reward of the validator in a year, if the active balance and participation
+    /// remain the same.
+    pub annual_reward_gwei: u64,
+    /// `annual_reward_gwei` as a fraction of `deposit_gwei`.
+    pub annual_reward_rate: f64,
+    /// The number of validators waiting to be activated.
+    pub activation_queue_length: u64,
+    /// The number of validators activated each epoch.
+    pub churn_limit: u64,
+    /// The expected time from submitting a deposit until the validator is active, assuming
+    /// ideal finality.
+    pub activation_delay_seconds: u64,
+    /// The expected time from submitting a deposit until the rewards of the validator exceed the
+    /// `cost_gwei` given in the request, if any.
+    pub break_even_seconds: Option<u64>,
+}
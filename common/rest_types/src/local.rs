@@ -0,0 +1,21 @@
+//! Types used to make requests to the HTTP API of a beacon node running in the same process (e.g.,
+//! from an in-process validator client), which are passed over a channel rather than a socket.
+
+use futures::channel::{mpsc, oneshot};
+
+/// A request to the HTTP API of a beacon node in the same process.
+pub struct LocalRequest {
+    pub request: http::Request<Vec<u8>>,
+    /// Receives the response to `request`. Dropped without a response if the request could not
+    /// be served.
+    pub response: oneshot::Sender<http::Response<Vec<u8>>>,
+}
+
+pub type LocalRequestSender = mpsc::UnboundedSender<LocalRequest>;
+pub type LocalRequestReceiver = mpsc::UnboundedReceiver<LocalRequest>;
+
+/// Returns a channel on which requests may be sent to a `LocalRequestReceiver` served by the
+/// beacon node.
+pub fn local_request_channel() -> (LocalRequestSender, LocalRequestReceiver) {
+    mpsc::unbounded()
+}
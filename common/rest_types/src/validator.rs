@@ -3,7 +3,19 @@ use eth2_hashing::hash;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::convert::TryInto;
-use types::{CommitteeIndex, Epoch, Slot};
+use types::{CommitteeIndex, Epoch, Hash256, Slot};
+
+/// Returned by the beacon node when it refuses an object (e.g., an attestation) published by a
+/// validator.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct PublishRejection {
+    /// A short, stable identifier for the reason, such as `past_slot` or `unknown_head_block`.
+    pub reason: String,
+    /// The position of the refused object in the published list, if a list was published.
+    pub index: Option<usize>,
+    /// A human-readable description of the reason.
+    pub message: String,
+}
 
 /// A Validator duty with the validator public key represented a `PublicKeyBytes`.
 pub type ValidatorDutyBytes = ValidatorDutyBase<PublicKeyBytes>;
@@ -55,6 +67,44 @@ pub struct ValidatorDutiesRequest {
     pub pubkeys: Vec<PublicKeyBytes>,
 }
 
+/// Requests the duties of the `pubkeys` in every epoch from `start_epoch` to `end_epoch`
+/// (inclusive).
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct HistoricalDutiesRequest {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+/// The duties of some validators in a past `epoch`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct HistoricalDuties {
+    pub epoch: Epoch,
+    pub duties: Vec<ValidatorDutyBytes>,
+}
+
+/// The validator that must propose a block at some `slot`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct ProposerDuty {
+    /// The validator's BLS public key. _48-bytes, hex encoded with 0x prefix, case insensitive._
+    pub pubkey: PublicKeyBytes,
+    /// The validator's index in `state.validators`.
+    pub validator_index: u64,
+    /// The slot at which the validator must propose a block.
+    pub slot: Slot,
+}
+
+/// The block proposers for every slot in an epoch.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct ProposerDutiesResponse {
+    /// The block root at the last slot of the previous epoch. The proposer shuffling is
+    /// determined by this block, so the duties must be re-fetched if it changes (e.g., via a
+    /// re-org).
+    pub dependent_root: Hash256,
+    /// One duty for each slot in the epoch, in ascending slot order.
+    pub data: Vec<ProposerDuty>,
+}
+
 /// A validator subscription, created when a validator subscribes to a slot to perform optional aggregation
 /// duties.
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
@@ -41,6 +41,18 @@ impl ManualSlotClock {
         self.set_slot(self.now().unwrap().as_u64() + 1)
     }
 
+    /// Moves the clock forward by `duration`, which need not be a whole number of slots (e.g., to
+    /// simulate the attestation deadline part-way through a slot).
+    pub fn advance(&self, duration: Duration) {
+        let mut current_time = self.current_time.write();
+        *current_time += duration;
+    }
+
+    /// Sets the present time to `duration` since the UNIX epoch.
+    pub fn set_current_time(&self, duration: Duration) {
+        *self.current_time.write() = duration;
+    }
+
     /// Returns the duration between UNIX epoch and the start of `slot`.
     pub fn start_of(&self, slot: Slot) -> Option<Duration> {
         let slot = slot
@@ -139,6 +151,10 @@ impl SlotClock for ManualSlotClock {
         self.duration_to_slot(slot, *self.current_time.read())
     }
 
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        self.start_of(slot)
+    }
+
     fn genesis_slot(&self) -> Slot {
         self.genesis_slot
     }
@@ -160,6 +176,30 @@ mod tests {
         assert_eq!(clock.now(), Some(Slot::new(123)));
     }
 
+    #[test]
+    fn advance() {
+        let clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(10),
+            Duration::from_secs(12),
+        );
+        assert_eq!(clock.now(), Some(Slot::new(0)));
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(clock.now(), Some(Slot::new(0)));
+        assert_eq!(clock.duration_to_next_slot(), Some(Duration::from_secs(8)));
+
+        clock.advance(Duration::from_secs(8));
+        assert_eq!(clock.now(), Some(Slot::new(1)));
+
+        clock.advance_slot();
+        assert_eq!(clock.now(), Some(Slot::new(2)));
+        assert_eq!(clock.now_duration(), Some(Duration::from_secs(34)));
+
+        clock.set_current_time(Duration::from_secs(0));
+        assert_eq!(clock.now(), None, "should be prior to genesis");
+    }
+
     #[test]
     fn start_of() {
         // Genesis slot and genesis duration 0.
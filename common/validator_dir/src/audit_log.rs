@@ -0,0 +1,140 @@
+//! Provides an append-only log of the events which create, import, delete or export validator
+//! keys, so that operators can reconstruct who did what to their keys and when.
+//!
+//! The log is stored in the validators directory (i.e., alongside each `ValidatorDir`) and
+//! contains one JSON-encoded `AuditEntry` per line. Entries are only ever appended; the log is
+//! never rewritten or truncated by Lighthouse.
+
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The name of the audit log within the validators directory.
+pub const AUDIT_LOG_FILE: &str = "key-audit-log.jsonl";
+
+#[derive(Debug)]
+pub enum Error {
+    UnableToOpenLog(io::Error),
+    UnableToWriteLog(io::Error),
+    UnableToReadLog(io::Error),
+    UnableToEncodeEntry(serde_json::Error),
+    UnableToDecodeEntry(serde_json::Error),
+}
+
+/// The kind of action taken on a key.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyEvent {
+    /// A new key was generated.
+    Create,
+    /// An existing key was brought into the validators directory.
+    Import,
+    /// A key was removed from the validators directory.
+    Delete,
+    /// A key was copied out of the validators directory.
+    Export,
+    /// An existing key was re-derived from the mnemonic of its wallet.
+    Recover,
+}
+
+/// The interface through which the action was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interface {
+    Cli,
+    Api,
+}
+
+/// A single line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Milliseconds since the UNIX epoch.
+    pub time_ms: u64,
+    pub event: KeyEvent,
+    pub interface: Interface,
+    /// The `0x`-prefixed voting public key of the validator.
+    pub voting_pubkey: String,
+    /// Free-form context, e.g. the source of an import.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Appends entries to the audit log of a validators directory.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Returns the log of the validators directory `validators_dir`. The file is created when the
+    /// first entry is appended.
+    pub fn new<P: AsRef<Path>>(validators_dir: P) -> Self {
+        Self {
+            path: validators_dir.as_ref().join(AUDIT_LOG_FILE),
+        }
+    }
+
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry for `event` on the key `voting_pubkey`, timestamped with the current time.
+    ///
+    /// The entry is flushed to disk before returning, so an entry is never lost after the action
+    /// it records has been reported as successful.
+    pub fn record(
+        &self,
+        event: KeyEvent,
+        interface: Interface,
+        voting_pubkey: String,
+        detail: Option<String>,
+    ) -> Result<(), Error> {
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        self.append(&AuditEntry {
+            time_ms,
+            event,
+            interface,
+            voting_pubkey,
+            detail,
+        })
+    }
+
+    /// Appends `entry` to the log.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(entry).map_err(Error::UnableToEncodeEntry)?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(Error::UnableToOpenLog)?;
+
+        // The entry is written in a single call so that concurrent writers, which each open the
+        // file in append mode, do not interleave their lines.
+        file.write_all(&line).map_err(Error::UnableToWriteLog)?;
+        file.sync_data().map_err(Error::UnableToWriteLog)
+    }
+
+    /// Reads every entry in the log, oldest first. Returns no entries if the log does not exist.
+    pub fn entries(&self) -> Result<Vec<AuditEntry>, Error> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let file = File::open(&self.path).map_err(Error::UnableToOpenLog)?;
+        BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.map_err(Error::UnableToReadLog)?;
+                serde_json::from_str(&line).map_err(Error::UnableToDecodeEntry)
+            })
+            .collect()
+    }
+}
@@ -7,7 +7,7 @@ use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use types::{ChainSpec, DepositData, Hash256, Keypair, Signature};
+use types::{Address, ChainSpec, DepositData, Hash256, Keypair, Signature};
 
 /// The `Alphanumeric` crate only generates a-z, A-Z, 0-9, therefore it has a range of 62
 /// characters.
@@ -21,6 +21,9 @@ pub const WITHDRAWAL_KEYSTORE_FILE: &str = "withdrawal-keystore.json";
 pub const ETH1_DEPOSIT_DATA_FILE: &str = "eth1-deposit-data.rlp";
 pub const ETH1_DEPOSIT_AMOUNT_FILE: &str = "eth1-deposit-gwei.txt";
 
+/// The first byte of withdrawal credentials which commit to an eth1 address.
+pub const ETH1_ADDRESS_WITHDRAWAL_PREFIX: u8 = 0x01;
+
 #[derive(Debug)]
 pub enum Error {
     DirectoryAlreadyExists(PathBuf),
@@ -38,6 +41,11 @@ pub enum Error {
     UnableToOpenDir(DirError),
     UninitializedVotingKeystore,
     UninitializedWithdrawalKeystore,
+    /// The withdrawal credentials are not an `ETH1_ADDRESS_WITHDRAWAL_PREFIX` byte, 11 zero bytes
+    /// and a 20 byte address.
+    InvalidEth1WithdrawalCredentials(Hash256),
+    /// Both a withdrawal keystore and withdrawal credentials were supplied.
+    WithdrawalKeystoreAndCredentials,
     #[cfg(feature = "insecure_keys")]
     InsecureKeysError(String),
 }
@@ -55,7 +63,9 @@ pub struct Builder<'a> {
     pub(crate) voting_keystore: Option<(Keystore, PlainText)>,
     pub(crate) withdrawal_keystore: Option<(Keystore, PlainText)>,
     store_withdrawal_keystore: bool,
+    withdrawal_credentials: Option<Hash256>,
     deposit_info: Option<(u64, &'a ChainSpec)>,
+    deposit_fork_version: Option<[u8; 4]>,
 }
 
 impl<'a> Builder<'a> {
@@ -67,7 +77,9 @@ impl<'a> Builder<'a> {
             voting_keystore: None,
             withdrawal_keystore: None,
             store_withdrawal_keystore: true,
+            withdrawal_credentials: None,
             deposit_info: None,
+            deposit_fork_version: None,
         }
     }
 
@@ -102,6 +114,40 @@ impl<'a> Builder<'a> {
         Ok(self.store_withdrawal_keystore(true))
     }
 
+    /// Build the `ValidatorDir` without a withdrawal keystore, depositing with the given eth1-style
+    /// `withdrawal_credentials` instead (i.e., `ETH1_ADDRESS_WITHDRAWAL_PREFIX`, 11 zero bytes
+    /// and a 20 byte address).
+    ///
+    /// No withdrawal key is generated or stored, so calls to `Self::store_withdrawal_keystore`
+    /// are ignored. Returns an error if `withdrawal_credentials` is not eth1-style.
+    pub fn eth1_withdrawal_credentials(
+        mut self,
+        withdrawal_credentials: Hash256,
+    ) -> Result<Self, Error> {
+        let bytes = withdrawal_credentials.as_bytes();
+        if bytes[0] != ETH1_ADDRESS_WITHDRAWAL_PREFIX || bytes[1..12].iter().any(|b| *b != 0) {
+            return Err(Error::InvalidEth1WithdrawalCredentials(
+                withdrawal_credentials,
+            ));
+        }
+
+        self.withdrawal_credentials = Some(withdrawal_credentials);
+        Ok(self)
+    }
+
+    /// Build the `ValidatorDir` without a withdrawal keystore, depositing with withdrawal
+    /// credentials which commit to the eth1 `address`.
+    ///
+    /// See `Self::eth1_withdrawal_credentials`.
+    pub fn eth1_withdrawal_address(mut self, address: Address) -> Self {
+        let mut bytes = [0; 32];
+        bytes[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+        bytes[12..].copy_from_slice(address.as_bytes());
+
+        self.withdrawal_credentials = Some(Hash256::from(bytes));
+        self
+    }
+
     /// Upon build, create files in the `ValidatorDir` which will permit the submission of a
     /// deposit to the eth1 deposit contract with the given `deposit_amount`.
     pub fn create_eth1_tx_data(mut self, deposit_amount: u64, spec: &'a ChainSpec) -> Self {
@@ -109,6 +155,20 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Sign the deposit created by `Self::create_eth1_tx_data` for the network with the given
+    /// genesis `fork_version`, instead of the `genesis_fork_version` of its `ChainSpec`.
+    ///
+    /// ## Notes
+    ///
+    /// A deposit signed for the wrong fork version is accepted by the deposit contract but
+    /// ignored by the beacon chain, so the deposited ether is lost. Only use this to prepare
+    /// deposits for another network when it is certain that `fork_version` is its genesis fork
+    /// version.
+    pub fn deposit_fork_version(mut self, fork_version: [u8; 4]) -> Self {
+        self.deposit_fork_version = Some(fork_version);
+        self
+    }
+
     /// If `should_store == true`, the validator keystore will be saved in the `ValidatorDir` (and
     /// the password to it stored in the `password_dir`). If `should_store == false`, the
     /// withdrawal keystore will be dropped after `Self::build`.
@@ -133,6 +193,10 @@ impl<'a> Builder<'a> {
             .voting_keystore
             .ok_or_else(|| Error::UninitializedVotingKeystore)?;
 
+        if self.withdrawal_credentials.is_some() && self.withdrawal_keystore.is_some() {
+            return Err(Error::WithdrawalKeystoreAndCredentials);
+        }
+
         let dir = self
             .base_validators_dir
             .join(format!("0x{}", voting_keystore.pubkey()));
@@ -143,78 +207,96 @@ impl<'a> Builder<'a> {
             create_dir_all(&dir).map_err(Error::UnableToCreateDir)?;
         }
 
-        // The withdrawal keystore must be initialized in order to store it or create an eth1
-        // deposit.
-        if (self.store_withdrawal_keystore || self.deposit_info.is_some())
+        // Unless eth1 withdrawal credentials were supplied, the withdrawal keystore must be
+        // initialized in order to store it or create an eth1 deposit.
+        if self.withdrawal_credentials.is_none()
+            && (self.store_withdrawal_keystore || self.deposit_info.is_some())
             && self.withdrawal_keystore.is_none()
         {
             return Err(Error::UninitializedWithdrawalKeystore);
         };
 
-        if let Some((withdrawal_keystore, withdrawal_password)) = self.withdrawal_keystore {
+        let withdrawal_keypair = self
+            .withdrawal_keystore
+            .as_ref()
+            .map(|(keystore, password)| keystore.decrypt_keypair(password.as_bytes()))
+            .transpose()?;
+
+        // If a deposit amount was specified, create a deposit.
+        if let Some((amount, spec)) = self.deposit_info {
             // Attempt to decrypt the voting keypair.
             let voting_keypair = voting_keystore.decrypt_keypair(voting_password.as_bytes())?;
 
-            // Attempt to decrypt the withdrawal keypair.
-            let withdrawal_keypair =
-                withdrawal_keystore.decrypt_keypair(withdrawal_password.as_bytes())?;
-
-            // If a deposit amount was specified, create a deposit.
-            if let Some((amount, spec)) = self.deposit_info {
-                let withdrawal_credentials = Hash256::from_slice(&get_withdrawal_credentials(
-                    &withdrawal_keypair.pk,
-                    spec.bls_withdrawal_prefix_byte,
-                ));
-
-                let mut deposit_data = DepositData {
-                    pubkey: voting_keypair.pk.clone().into(),
-                    withdrawal_credentials,
-                    amount,
-                    signature: Signature::empty_signature().into(),
-                };
-
-                deposit_data.signature = deposit_data.create_signature(&voting_keypair.sk, &spec);
-
-                let deposit_data =
-                    encode_eth1_tx_data(&deposit_data).map_err(Error::UnableToEncodeDeposit)?;
-
-                // Save `ETH1_DEPOSIT_DATA_FILE` to file.
-                //
-                // This allows us to know the RLP data for the eth1 transaction without needing to know
-                // the withdrawal/voting keypairs again at a later date.
-                let path = dir.clone().join(ETH1_DEPOSIT_DATA_FILE);
-                if path.exists() {
-                    return Err(Error::DepositDataAlreadyExists(path));
-                } else {
-                    let hex = format!("0x{}", hex::encode(&deposit_data));
-                    OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create(true)
-                        .open(path.clone())
-                        .map_err(Error::UnableToSaveDepositData)?
-                        .write_all(hex.as_bytes())
-                        .map_err(Error::UnableToSaveDepositData)?
+            let withdrawal_credentials = match (self.withdrawal_credentials, &withdrawal_keypair) {
+                (Some(withdrawal_credentials), _) => withdrawal_credentials,
+                (None, Some(withdrawal_keypair)) => {
+                    Hash256::from_slice(&get_withdrawal_credentials(
+                        &withdrawal_keypair.pk,
+                        spec.bls_withdrawal_prefix_byte,
+                    ))
                 }
+                (None, None) => return Err(Error::UninitializedWithdrawalKeystore),
+            };
+
+            let mut deposit_data = DepositData {
+                pubkey: voting_keypair.pk.clone().into(),
+                withdrawal_credentials,
+                amount,
+                signature: Signature::empty_signature().into(),
+            };
+
+            let fork_version = self
+                .deposit_fork_version
+                .unwrap_or(spec.genesis_fork_version);
+            deposit_data.signature = deposit_data.create_signature_with_fork_version(
+                &voting_keypair.sk,
+                fork_version,
+                &spec,
+            );
+
+            let deposit_data =
+                encode_eth1_tx_data(&deposit_data).map_err(Error::UnableToEncodeDeposit)?;
+
+            // Save `ETH1_DEPOSIT_DATA_FILE` to file.
+            //
+            // This allows us to know the RLP data for the eth1 transaction without needing to know
+            // the withdrawal/voting keypairs again at a later date.
+            let path = dir.clone().join(ETH1_DEPOSIT_DATA_FILE);
+            if path.exists() {
+                return Err(Error::DepositDataAlreadyExists(path));
+            } else {
+                let hex = format!("0x{}", hex::encode(&deposit_data));
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create(true)
+                    .open(path.clone())
+                    .map_err(Error::UnableToSaveDepositData)?
+                    .write_all(hex.as_bytes())
+                    .map_err(Error::UnableToSaveDepositData)?
+            }
 
-                // Save `ETH1_DEPOSIT_AMOUNT_FILE` to file.
-                //
-                // This allows us to know the intended deposit amount at a later date.
-                let path = dir.clone().join(ETH1_DEPOSIT_AMOUNT_FILE);
-                if path.exists() {
-                    return Err(Error::DepositAmountAlreadyExists(path));
-                } else {
-                    OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create(true)
-                        .open(path.clone())
-                        .map_err(Error::UnableToSaveDepositAmount)?
-                        .write_all(format!("{}", amount).as_bytes())
-                        .map_err(Error::UnableToSaveDepositAmount)?
-                }
+            // Save `ETH1_DEPOSIT_AMOUNT_FILE` to file.
+            //
+            // This allows us to know the intended deposit amount at a later date.
+            let path = dir.clone().join(ETH1_DEPOSIT_AMOUNT_FILE);
+            if path.exists() {
+                return Err(Error::DepositAmountAlreadyExists(path));
+            } else {
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create(true)
+                    .open(path.clone())
+                    .map_err(Error::UnableToSaveDepositAmount)?
+                    .write_all(format!("{}", amount).as_bytes())
+                    .map_err(Error::UnableToSaveDepositAmount)?
             }
+        }
 
+        if let (Some((withdrawal_keystore, withdrawal_password)), Some(withdrawal_keypair)) =
+            (self.withdrawal_keystore, withdrawal_keypair)
+        {
             // Only the withdrawal keystore if explicitly required.
             if self.store_withdrawal_keystore {
                 // Write the withdrawal password to file.
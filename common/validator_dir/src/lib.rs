@@ -3,19 +3,27 @@
 //! - `ValidatorDir`: manages a directory containing validator keypairs, deposit info and other
 //! things.
 //! - `Manager`: manages a directory that contains multiple `ValidatorDir`.
+//! - `AuditLog`: records the creation, import, deletion and export of keys in such a directory.
 //!
 //! This crate is intended to be used by the account manager to create validators and the validator
 //! client to load those validators.
 
+pub mod audit_log;
 mod builder;
 pub mod insecure_keys;
 mod manager;
 pub mod unencrypted_keys;
 mod validator_dir;
 
-pub use crate::validator_dir::{Error, Eth1DepositData, ValidatorDir, ETH1_DEPOSIT_TX_HASH_FILE};
+pub use crate::validator_dir::{
+    Error, Eth1DepositData, KeystoreKind, ValidatorDir, ETH1_DEPOSIT_TX_HASH_FILE,
+    SIGNING_DISABLED_FILE,
+};
+pub use audit_log::{
+    AuditEntry, AuditLog, Error as AuditLogError, Interface, KeyEvent, AUDIT_LOG_FILE,
+};
 pub use builder::{
-    Builder, Error as BuilderError, ETH1_DEPOSIT_DATA_FILE, VOTING_KEYSTORE_FILE,
-    WITHDRAWAL_KEYSTORE_FILE,
+    Builder, Error as BuilderError, ETH1_ADDRESS_WITHDRAWAL_PREFIX, ETH1_DEPOSIT_DATA_FILE,
+    VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
 };
 pub use manager::{Error as ManagerError, Manager};
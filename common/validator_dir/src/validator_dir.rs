@@ -3,9 +3,11 @@ use crate::builder::{
     WITHDRAWAL_KEYSTORE_FILE,
 };
 use deposit_contract::decode_eth1_tx_data;
-use eth2_keystore::{Error as KeystoreError, Keystore, PlainText};
-use std::fs::{read, remove_file, write, OpenOptions};
-use std::io;
+use eth2_keystore::{
+    json_keystore::Kdf, Error as KeystoreError, Keystore, KeystoreBuilder, PlainText,
+};
+use std::fs::{read, remove_file, rename, write, OpenOptions};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use tree_hash::TreeHash;
 use types::{DepositData, Hash256, Keypair};
@@ -16,6 +18,10 @@ const LOCK_FILE: &str = ".lock";
 /// The file used to save the Eth1 transaction hash from a deposit.
 pub const ETH1_DEPOSIT_TX_HASH_FILE: &str = "eth1-deposit-tx-hash.txt";
 
+/// The file used to indicate that the validator client must not sign any messages with this
+/// validator. It contains a human-readable reason.
+pub const SIGNING_DISABLED_FILE: &str = "signing-disabled.txt";
+
 #[derive(Debug)]
 pub enum Error {
     DirectoryDoesNotExist(PathBuf),
@@ -37,6 +43,10 @@ pub enum Error {
     UnableToParseDepositData(deposit_contract::DecodeError),
     Eth1TxHashExists(PathBuf),
     UnableToWriteEth1TxHash(io::Error),
+    UnableToWriteSigningDisabled(io::Error),
+    UnableToEncryptKeystore(KeystoreError),
+    UnableToWriteKeystore(io::Error),
+    UnableToWritePassword(io::Error),
     /// The deposit root in the deposit data file does not match the one generated locally. This is
     /// generally caused by supplying an `amount` at deposit-time that is different to the one used
     /// at generation-time.
@@ -45,6 +55,23 @@ pub enum Error {
     SszKeypairError(String),
 }
 
+/// Identifies one of the keystores which may be stored in a `ValidatorDir`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeystoreKind {
+    Voting,
+    Withdrawal,
+}
+
+impl KeystoreKind {
+    /// The name of the file in a `ValidatorDir` which contains this keystore.
+    pub fn filename(&self) -> &'static str {
+        match self {
+            KeystoreKind::Voting => VOTING_KEYSTORE_FILE,
+            KeystoreKind::Withdrawal => WITHDRAWAL_KEYSTORE_FILE,
+        }
+    }
+}
+
 /// Information required to submit a deposit to the Eth1 deposit contract.
 #[derive(Debug, PartialEq)]
 pub struct Eth1DepositData {
@@ -150,6 +177,72 @@ impl ValidatorDir {
         write(path, tx_hash.as_bytes()).map_err(Error::UnableToWriteEth1TxHash)
     }
 
+    /// Returns `true` if signing has been disabled for this validator via `Self::disable_signing`.
+    ///
+    /// Signing may be re-enabled by deleting the `SIGNING_DISABLED_FILE` in `self.dir`.
+    pub fn signing_disabled(&self) -> bool {
+        self.dir.join(SIGNING_DISABLED_FILE).exists()
+    }
+
+    /// Persists that signing has been disabled for this validator, recording the `reason` in a
+    /// file in `self.dir`. Artificially requires `mut self` to prevent concurrent calls.
+    ///
+    /// ## Errors
+    ///
+    /// If there is a file-system error.
+    pub fn disable_signing(&mut self, reason: &str) -> Result<(), Error> {
+        write(self.dir.join(SIGNING_DISABLED_FILE), reason.as_bytes())
+            .map_err(Error::UnableToWriteSigningDisabled)
+    }
+
+    /// Decrypts the `kind` keystore in `self.dir` using its password file in `password_dir`, then
+    /// re-encrypts it with `new_password` and replaces the password file. The keystore is
+    /// encrypted with `kdf` if it is supplied, otherwise with `eth2_keystore::default_kdf`. A new
+    /// salt, IV and UUID are always generated. Artificially requires `mut self` to prevent
+    /// concurrent calls.
+    ///
+    /// The new keystore and password are each written to a temporary file and then renamed over
+    /// the originals, keystore first. Should the process be interrupted between the two renames,
+    /// the new password is left in the password file path with a `.tmp` extension.
+    ///
+    /// ## Errors
+    ///
+    /// If there is a file-system error, the password is missing or incorrect, or the `kdf`
+    /// parameters are invalid.
+    pub fn change_password<P: AsRef<Path>>(
+        &mut self,
+        kind: KeystoreKind,
+        password_dir: P,
+        new_password: &[u8],
+        kdf: Option<Kdf>,
+    ) -> Result<(), Error> {
+        let keystore_path = self.dir.join(kind.filename());
+        let keystore = read_keystore(&keystore_path)?;
+        let password_path = password_dir
+            .as_ref()
+            .join(format!("0x{}", keystore.pubkey()));
+        let keypair = decrypt_keystore(&keystore, &password_path)?;
+
+        let mut builder = KeystoreBuilder::new(&keypair, new_password, keystore.path().into())
+            .map_err(Error::UnableToEncryptKeystore)?;
+        if let Some(kdf) = kdf {
+            builder = builder.kdf(kdf);
+        }
+        let new_keystore = builder.build().map_err(Error::UnableToEncryptKeystore)?;
+        let keystore_json = new_keystore
+            .to_json_string()
+            .map_err(Error::UnableToEncryptKeystore)?;
+
+        let keystore_tmp = tmp_path(&keystore_path);
+        let password_tmp = tmp_path(&password_path);
+        write_synced(&password_tmp, new_password).map_err(Error::UnableToWritePassword)?;
+        write_synced(&keystore_tmp, keystore_json.as_bytes())
+            .map_err(Error::UnableToWriteKeystore)?;
+
+        rename(&keystore_tmp, &keystore_path).map_err(Error::UnableToWriteKeystore)?;
+        rename(&password_tmp, &password_path).map_err(Error::UnableToWritePassword)
+    }
+
     /// Attempts to read files in `self.dir` and return an `Eth1DepositData` that can be used for
     /// submitting an Eth1 deposit.
     ///
@@ -220,19 +313,29 @@ fn unlock_keypair<P: AsRef<Path>>(
     filename: &str,
     password_dir: P,
 ) -> Result<Keypair, Error> {
-    let keystore = Keystore::from_json_reader(
+    let keystore = read_keystore(&keystore_dir.clone().join(filename))?;
+
+    let password_path = password_dir
+        .as_ref()
+        .join(format!("0x{}", keystore.pubkey()));
+    decrypt_keystore(&keystore, &password_path)
+}
+
+/// Reads the keystore at `path`.
+fn read_keystore(path: &Path) -> Result<Keystore, Error> {
+    Keystore::from_json_reader(
         &mut OpenOptions::new()
             .read(true)
             .create(false)
-            .open(keystore_dir.clone().join(filename))
+            .open(path)
             .map_err(Error::UnableToOpenKeystore)?,
     )
-    .map_err(Error::UnableToReadKeystore)?;
+    .map_err(Error::UnableToReadKeystore)
+}
 
-    let password_path = password_dir
-        .as_ref()
-        .join(format!("0x{}", keystore.pubkey()));
-    let password: PlainText = read(&password_path)
+/// Decrypts `keystore` using the password in the file at `password_path`.
+fn decrypt_keystore(keystore: &Keystore, password_path: &Path) -> Result<Keypair, Error> {
+    let password: PlainText = read(password_path)
         .map_err(|_| Error::UnableToReadPassword(password_path.into()))?
         .into();
 
@@ -240,3 +343,21 @@ fn unlock_keypair<P: AsRef<Path>>(
         .decrypt_keypair(password.as_bytes())
         .map_err(Error::UnableToDecryptKeypair)
 }
+
+/// Returns `path` with `.tmp` appended to the file name.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Writes `bytes` to a new file at `path`, flushing them to disk before returning.
+fn write_synced(path: &Path, bytes: &[u8]) -> Result<(), io::Error> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
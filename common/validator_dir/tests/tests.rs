@@ -1,13 +1,20 @@
 #![cfg(not(debug_assertions))]
 
-use eth2_keystore::{Keystore, KeystoreBuilder, PlainText};
+use eth2_keystore::{
+    json_keystore::{Kdf, Pbkdf2, Prf},
+    Keystore, KeystoreBuilder, PlainText, DKLEN,
+};
 use std::fs::{self, File};
 use std::path::Path;
 use tempfile::{tempdir, TempDir};
-use types::{test_utils::generate_deterministic_keypair, EthSpec, Keypair, MainnetEthSpec};
+use types::{
+    test_utils::generate_deterministic_keypair, Address, EthSpec, Hash256, Keypair, MainnetEthSpec,
+};
 use validator_dir::{
-    Builder, BuilderError, ValidatorDir, ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE,
-    VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
+    AuditLog, Builder, BuilderError, Interface, KeyEvent, KeystoreKind, ValidatorDir,
+    AUDIT_LOG_FILE, ETH1_ADDRESS_WITHDRAWAL_PREFIX, ETH1_DEPOSIT_DATA_FILE,
+    ETH1_DEPOSIT_TX_HASH_FILE, SIGNING_DISABLED_FILE, VOTING_KEYSTORE_FILE,
+    WITHDRAWAL_KEYSTORE_FILE,
 };
 
 /// A very weak password with which to encrypt the keystores.
@@ -203,6 +210,69 @@ fn concurrency() {
     ValidatorDir::open(&path).unwrap();
 }
 
+#[test]
+fn disable_signing() {
+    let harness = Harness::new();
+
+    let mut val_dir = harness.create_and_test(&BuildConfig::default());
+    let path = val_dir.dir().clone();
+    assert!(!val_dir.signing_disabled());
+
+    val_dir.disable_signing("slashed").unwrap();
+    assert!(val_dir.signing_disabled());
+    assert_eq!(
+        fs::read_to_string(path.join(SIGNING_DISABLED_FILE)).unwrap(),
+        "slashed"
+    );
+
+    // The disabled state should persist across re-opening the directory.
+    drop(val_dir);
+    assert!(ValidatorDir::open(&path).unwrap().signing_disabled());
+}
+
+#[test]
+fn change_password() {
+    let harness = Harness::new();
+
+    let mut val_dir = harness.create_and_test(&BuildConfig::default());
+    let password_dir = harness.password_dir.path();
+    let original = val_dir.voting_keypair(password_dir).unwrap().pk;
+
+    let kdf = Kdf::Pbkdf2(Pbkdf2 {
+        dklen: DKLEN,
+        c: 2,
+        prf: Prf::HmacSha256,
+        salt: vec![42].into(),
+    });
+    val_dir
+        .change_password(
+            KeystoreKind::Voting,
+            password_dir,
+            b"a new password",
+            Some(kdf.clone()),
+        )
+        .unwrap();
+
+    let keystore_path = val_dir.dir().join(VOTING_KEYSTORE_FILE);
+    let keystore = Keystore::from_json_reader(File::open(&keystore_path).unwrap()).unwrap();
+    assert_eq!(keystore.kdf(), &kdf);
+    assert_eq!(
+        keystore.decrypt_keypair(b"a new password").unwrap().pk,
+        original
+    );
+    assert_eq!(val_dir.voting_keypair(password_dir).unwrap().pk, original);
+
+    // No temporary files should remain.
+    assert!(!val_dir
+        .dir()
+        .join(format!("{}.tmp", VOTING_KEYSTORE_FILE))
+        .exists());
+    assert_eq!(fs::read_dir(password_dir).unwrap().count(), 2);
+
+    // The withdrawal keystore should be unaffected.
+    val_dir.withdrawal_keypair(password_dir).unwrap();
+}
+
 #[test]
 fn without_voting_keystore() {
     let harness = Harness::new();
@@ -266,6 +336,101 @@ fn without_withdrawal_keystore() {
     );
 }
 
+#[test]
+fn eth1_withdrawal_credentials() {
+    let harness = Harness::new();
+    let spec = &MainnetEthSpec::default_spec();
+    let address = Address::repeat_byte(0x42);
+
+    let mut expected = [0; 32];
+    expected[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+    expected[12..].copy_from_slice(address.as_bytes());
+
+    // No withdrawal keystore is required or stored, even though storing is the default.
+    let validator = Builder::new(
+        harness.validators_dir.path().into(),
+        harness.password_dir.path().into(),
+    )
+    .random_voting_keystore()
+    .unwrap()
+    .eth1_withdrawal_address(address)
+    .create_eth1_tx_data(42, spec)
+    .build()
+    .unwrap();
+
+    assert!(!validator.dir().join(WITHDRAWAL_KEYSTORE_FILE).exists());
+    assert_eq!(
+        fs::read_dir(harness.password_dir.path()).unwrap().count(),
+        1
+    );
+
+    let data = validator.eth1_deposit_data().unwrap().unwrap();
+    assert_eq!(
+        data.deposit_data.withdrawal_credentials,
+        Hash256::from(expected)
+    );
+    assert_eq!(data.deposit_data.amount, 42);
+
+    // BLS-style withdrawal credentials are rejected.
+    let builder = Builder::new(
+        harness.validators_dir.path().into(),
+        harness.password_dir.path().into(),
+    );
+    assert!(matches!(
+        builder.eth1_withdrawal_credentials(Hash256::repeat_byte(0)),
+        Err(BuilderError::InvalidEth1WithdrawalCredentials(_))
+    ));
+
+    // Credentials cannot be combined with a withdrawal keystore.
+    assert!(matches!(
+        Builder::new(
+            harness.validators_dir.path().into(),
+            harness.password_dir.path().into(),
+        )
+        .random_voting_keystore()
+        .unwrap()
+        .random_withdrawal_keystore()
+        .unwrap()
+        .eth1_withdrawal_credentials(Hash256::from(expected))
+        .unwrap()
+        .build(),
+        Err(BuilderError::WithdrawalKeystoreAndCredentials)
+    ));
+}
+
+#[test]
+fn deposit_fork_version() {
+    let harness = Harness::new();
+    let spec = &MainnetEthSpec::default_spec();
+    let fork_version = [0x00, 0x00, 0x00, 0x42];
+    assert_ne!(fork_version, spec.genesis_fork_version);
+
+    let (voting_keystore, password) = generate_deterministic_keystore(0).unwrap();
+    let validator = Builder::new(
+        harness.validators_dir.path().into(),
+        harness.password_dir.path().into(),
+    )
+    .voting_keystore(voting_keystore, password.as_bytes())
+    .random_withdrawal_keystore()
+    .unwrap()
+    .create_eth1_tx_data(42, spec)
+    .deposit_fork_version(fork_version)
+    .build()
+    .unwrap();
+
+    let deposit_data = validator.eth1_deposit_data().unwrap().unwrap().deposit_data;
+    let voting_keypair = generate_deterministic_keypair(0);
+
+    assert_eq!(
+        deposit_data.signature,
+        deposit_data.create_signature_with_fork_version(&voting_keypair.sk, fork_version, spec)
+    );
+    assert_ne!(
+        deposit_data.signature,
+        deposit_data.create_signature(&voting_keypair.sk, spec)
+    );
+}
+
 #[test]
 fn deterministic_voting_keystore() {
     let harness = Harness::new();
@@ -385,3 +550,38 @@ fn store_withdrawal_keystore_with_eth1_data() {
 
     harness.create_and_test(&config);
 }
+
+#[test]
+fn audit_log() {
+    let temp_dir = tempdir().unwrap();
+    let audit_log = AuditLog::new(temp_dir.path());
+    assert!(audit_log.entries().unwrap().is_empty());
+
+    audit_log
+        .record(KeyEvent::Create, Interface::Cli, "0xaa".to_string(), None)
+        .unwrap();
+    AuditLog::new(temp_dir.path())
+        .record(
+            KeyEvent::Import,
+            Interface::Api,
+            "0xbb".to_string(),
+            Some("teku".to_string()),
+        )
+        .unwrap();
+
+    let entries = audit_log.entries().unwrap();
+    assert_eq!(
+        entries.len(),
+        2,
+        "entries should be appended, not overwritten"
+    );
+    assert_eq!(entries[0].event, KeyEvent::Create);
+    assert_eq!(entries[0].interface, Interface::Cli);
+    assert_eq!(entries[1].voting_pubkey, "0xbb");
+    assert_eq!(entries[1].detail, Some("teku".to_string()));
+    assert!(entries[0].time_ms <= entries[1].time_ms);
+
+    let contents = fs::read_to_string(temp_dir.path().join(AUDIT_LOG_FILE)).unwrap();
+    assert!(contents.starts_with("{\"time_ms\":"));
+    assert!(contents.contains("\"event\":\"import\",\"interface\":\"api\""));
+}
@@ -220,6 +220,53 @@ impl<'a> SszDecoderBuilder<'a> {
 
         Ok(SszDecoder { items: self.items })
     }
+
+    /// Finalizes the builder, returning a `SszContainerView` which provides the bytes of each
+    /// registered item without decoding them.
+    pub fn build_view(mut self) -> Result<SszContainerView<'a>, DecodeError> {
+        self.finalize()?;
+
+        Ok(SszContainerView { items: self.items })
+    }
+}
+
+/// A borrowed view of the SSZ encoding of a container, split into the bytes of each of its fields.
+/// Should be instantiated using
+/// [`SszDecoderBuilder::build_view`](struct.SszDecoderBuilder.html#method.build_view).
+///
+/// Unlike `SszDecoder`, the fields may be decoded in any order, any number of times, or not at all.
+/// No field is copied or decoded until requested, so reading a few small fields of a large
+/// container is cheap. However, the bytes of a field are only checked when it is decoded.
+pub struct SszContainerView<'a> {
+    items: SmallVec8<&'a [u8]>,
+}
+
+impl<'a> SszContainerView<'a> {
+    /// Returns the number of fields in the container.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the container has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the SSZ bytes of the `index`'th field.
+    pub fn field_bytes(&self, index: usize) -> Result<&'a [u8], DecodeError> {
+        self.items.get(index).copied().ok_or_else(|| {
+            DecodeError::BytesInvalid(format!(
+                "Field index {} out of bounds for container with {} fields",
+                index,
+                self.items.len()
+            ))
+        })
+    }
+
+    /// Decodes the `index`'th field.
+    pub fn decode_field<T: Decode>(&self, index: usize) -> Result<T, DecodeError> {
+        T::from_ssz_bytes(self.field_bytes(index)?)
+    }
 }
 
 /// Decodes some slices of SSZ into object instances. Should be instantiated using
@@ -1,5 +1,5 @@
 use ethereum_types::H256;
-use ssz::{Decode, DecodeError, Encode};
+use ssz::{Decode, DecodeError, Encode, SszDecoderBuilder};
 use ssz_derive::{Decode, Encode};
 
 #[allow(clippy::zero_prefixed_literal)]
@@ -172,6 +172,41 @@ mod round_trip {
         assert!(VariableLen::from_ssz_bytes(&bytes).is_err());
     }
 
+    #[test]
+    fn container_view() {
+        let variable = VariableLen {
+            a: 1,
+            b: vec![2, 3],
+            c: 4,
+        };
+        let bytes = variable.as_ssz_bytes();
+
+        let mut builder = SszDecoderBuilder::new(&bytes);
+        builder.register_type::<u16>().unwrap();
+        builder.register_type::<Vec<u16>>().unwrap();
+        builder.register_type::<u32>().unwrap();
+        let view = builder.build_view().unwrap();
+
+        assert_eq!(view.len(), 3);
+        // Fields may be decoded in any order.
+        assert_eq!(view.decode_field::<u32>(2), Ok(4));
+        assert_eq!(view.decode_field::<Vec<u16>>(1), Ok(vec![2, 3]));
+        assert_eq!(view.field_bytes(0), Ok(&bytes[0..2]));
+        assert!(view.field_bytes(3).is_err());
+
+        // Invalid offsets are detected when the view is built.
+        let mut bytes = bytes;
+        bytes[2] = 0;
+        let mut builder = SszDecoderBuilder::new(&bytes);
+        builder.register_type::<u16>().unwrap();
+        builder.register_type::<Vec<u16>>().unwrap();
+        builder.register_type::<u32>().unwrap();
+        assert!(matches!(
+            builder.build_view(),
+            Err(DecodeError::OffsetIntoFixedPortion(0))
+        ));
+    }
+
     #[test]
     fn first_offset_skips_byte() {
         let bytes = vec![
@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate lazy_static;
+
+use libfuzzer_sys::fuzz_target;
+use state_processing::fuzz::FuzzHarness;
+use types::{EthSpec, MinimalEthSpec};
+
+lazy_static! {
+    static ref HARNESS: FuzzHarness<MinimalEthSpec> =
+        FuzzHarness::new(MinimalEthSpec::default_spec());
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = HARNESS.block_arbitrary(data);
+});
@@ -0,0 +1,179 @@
+//! Deterministic entry points for fuzzing block and attestation processing.
+//!
+//! Each entry point decodes its input as either an `arbitrary`-generated or SSZ-encoded object and
+//! processes it against a clone of a state which is seeded from deterministic keypairs, so a crash
+//! found by a fuzzer can always be reproduced from its input alone. Errors are expected and
+//! ignored by the fuzz targets; only panics (including overflows) are bugs.
+//!
+//! The fuzz targets in `state_processing/fuzz` also enable the `fake_crypto` feature, so that
+//! arbitrary signatures are valid and inputs reach the processing which follows each signature
+//! check.
+
+use crate::per_block_processing::{
+    errors::BlockProcessingError, per_block_processing, process_attestations,
+    BlockSignatureStrategy, VerifySignatures,
+};
+use arbitrary::{Arbitrary, Unstructured};
+use ssz::{Decode, DecodeError};
+use types::test_utils::TestingBeaconStateBuilder;
+use types::{Attestation, BeaconState, ChainSpec, Epoch, EthSpec, SignedBeaconBlock};
+
+/// The number of validators in the seeded state.
+pub const FUZZ_VALIDATOR_COUNT: usize = 64;
+
+/// The epoch of the seeded state, which is in the second slot of this epoch. Late enough that
+/// attestations from the previous epoch and justified checkpoints may be processed.
+pub const FUZZ_STATE_EPOCH: u64 = 4;
+
+/// The reasons an input was not fully processed.
+#[derive(Debug)]
+pub enum FuzzError {
+    /// The input could not be decoded as an `arbitrary` object.
+    Arbitrary(arbitrary::Error),
+    /// The input could not be decoded as an SSZ object.
+    Ssz(DecodeError),
+    /// The object was decoded, but is invalid.
+    Processing(BlockProcessingError),
+}
+
+impl From<BlockProcessingError> for FuzzError {
+    fn from(e: BlockProcessingError) -> Self {
+        FuzzError::Processing(e)
+    }
+}
+
+/// Holds the seeded state against which inputs are processed.
+pub struct FuzzHarness<E: EthSpec> {
+    state: BeaconState<E>,
+    spec: ChainSpec,
+}
+
+impl<E: EthSpec> FuzzHarness<E> {
+    /// Builds the seeded state, with all caches built. Intended to be called once per fuzzing
+    /// process, rather than once per input.
+    pub fn new(spec: ChainSpec) -> Self {
+        let mut builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(FUZZ_VALIDATOR_COUNT, &spec);
+        builder.teleport_to_slot(Epoch::new(FUZZ_STATE_EPOCH).start_slot(E::slots_per_epoch()) + 1);
+        builder
+            .build_caches(&spec)
+            .expect("should build caches for seeded state");
+        let (state, _keypairs) = builder.build();
+
+        Self { state, spec }
+    }
+
+    /// Returns the seeded state.
+    pub fn state(&self) -> &BeaconState<E> {
+        &self.state
+    }
+
+    /// Processes a `SignedBeaconBlock` generated from `data` by `arbitrary`.
+    pub fn block_arbitrary(&self, data: &[u8]) -> Result<(), FuzzError> {
+        let block = SignedBeaconBlock::arbitrary(&mut Unstructured::new(data))
+            .map_err(FuzzError::Arbitrary)?;
+        self.block(block)
+    }
+
+    /// Processes an SSZ-encoded `SignedBeaconBlock`.
+    pub fn block_ssz(&self, data: &[u8]) -> Result<(), FuzzError> {
+        let block = SignedBeaconBlock::from_ssz_bytes(data).map_err(FuzzError::Ssz)?;
+        self.block(block)
+    }
+
+    /// Processes an `Attestation` generated from `data` by `arbitrary`.
+    pub fn attestation_arbitrary(&self, data: &[u8]) -> Result<(), FuzzError> {
+        let attestation =
+            Attestation::arbitrary(&mut Unstructured::new(data)).map_err(FuzzError::Arbitrary)?;
+        self.attestation(attestation)
+    }
+
+    /// Processes an SSZ-encoded `Attestation`.
+    pub fn attestation_ssz(&self, data: &[u8]) -> Result<(), FuzzError> {
+        let attestation = Attestation::from_ssz_bytes(data).map_err(FuzzError::Ssz)?;
+        self.attestation(attestation)
+    }
+
+    /// Processes `block` against a clone of the seeded state.
+    ///
+    /// The slot, proposer index and parent root of the block are replaced with those expected by
+    /// the state, since almost no inputs would otherwise pass the block header checks and reach
+    /// the processing of the block's operations.
+    fn block(&self, mut block: SignedBeaconBlock<E>) -> Result<(), FuzzError> {
+        let mut state = self.state.clone();
+
+        block.message.slot = state.slot;
+        block.message.proposer_index = state
+            .get_beacon_proposer_index(state.slot, &self.spec)
+            .map_err(BlockProcessingError::from)? as u64;
+        block.message.parent_root = state.latest_block_header.canonical_root();
+
+        per_block_processing(
+            &mut state,
+            &block,
+            None,
+            BlockSignatureStrategy::VerifyIndividual,
+            &self.spec,
+        )?;
+
+        Ok(())
+    }
+
+    /// Processes `attestation` against a clone of the seeded state, as if it were included in a
+    /// block at the slot of the state.
+    fn attestation(&self, attestation: Attestation<E>) -> Result<(), FuzzError> {
+        let mut state = self.state.clone();
+
+        process_attestations(
+            &mut state,
+            &[attestation],
+            VerifySignatures::True,
+            &self.spec,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::BlockBuilder;
+    use ssz::Encode;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn entry_points_do_not_panic() {
+        let harness = FuzzHarness::<E>::new(E::default_spec());
+        let inputs: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![0; 32],
+            vec![0xff; 512],
+            (0..4096).map(|i| i as u8).collect(),
+        ];
+
+        for data in &inputs {
+            let _ = harness.block_arbitrary(data);
+            let _ = harness.block_ssz(data);
+            let _ = harness.attestation_arbitrary(data);
+            let _ = harness.attestation_ssz(data);
+        }
+    }
+
+    #[test]
+    fn processes_valid_block() {
+        let spec = E::default_spec();
+        let harness = FuzzHarness::<E>::new(spec.clone());
+
+        let mut builder = BlockBuilder::<E>::new(FUZZ_VALIDATOR_COUNT, &spec);
+        builder.set_slot(harness.state().slot);
+        builder.build_caches(&spec);
+        let (block, _state) = builder.build(&spec);
+
+        harness
+            .block_ssz(&block.as_ssz_bytes())
+            .expect("should process a valid block");
+    }
+}
@@ -82,8 +82,15 @@ pub fn process_activations<T: EthSpec>(
 ///
 /// Does _not_ ensure that the time is greater than `MIN_GENESIS_TIME`.
 ///
+/// A `MIN_GENESIS_DELAY` of zero (e.g., for a local devnet) results in the `eth1_timestamp`,
+/// rather than a division by zero.
+///
 /// Spec v0.11.1
 pub fn eth2_genesis_time(eth1_timestamp: u64, spec: &ChainSpec) -> Result<u64, ArithError> {
+    if spec.min_genesis_delay == 0 {
+        return Ok(eth1_timestamp);
+    }
+
     eth1_timestamp
         .safe_sub(eth1_timestamp.safe_rem(spec.min_genesis_delay)?)?
         .safe_add(2.safe_mul(spec.min_genesis_delay)?)
@@ -4,6 +4,8 @@
 mod macros;
 
 pub mod common;
+#[cfg(feature = "arbitrary-fuzz")]
+pub mod fuzz;
 pub mod genesis;
 pub mod per_block_processing;
 pub mod per_epoch_processing;
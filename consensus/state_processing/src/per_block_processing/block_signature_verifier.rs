@@ -1,14 +1,14 @@
-#![allow(clippy::integer_arithmetic)]
-
 use super::signature_sets::{Error as SignatureSetError, Result as SignatureSetResult, *};
 use crate::common::get_indexed_attestation;
 use crate::per_block_processing::errors::{AttestationInvalid, BlockOperationError};
 use bls::{verify_signature_sets, PublicKey, SignatureSet};
 use rayon::prelude::*;
 use std::borrow::Cow;
+use std::fmt;
+use tree_hash::TreeHash;
 use types::{
     BeaconState, BeaconStateError, ChainSpec, EthSpec, Hash256, IndexedAttestation,
-    SignedBeaconBlock,
+    SignedBeaconBlock, Slot,
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -16,7 +16,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, PartialEq)]
 pub enum Error {
     /// All public keys were found but signature verification failed. The block is invalid.
-    SignatureInvalid,
+    ///
+    /// Contains the first signature which failed when each was re-verified individually. It is
+    /// only `None` if every signature was individually valid, which should not happen.
+    SignatureInvalid(Option<InvalidSignatureSet>),
     /// An attestation in the block was invalid. The block is invalid.
     AttestationValidationError(BlockOperationError<AttestationInvalid>),
     /// There was an error attempting to read from a `BeaconState`. Block
@@ -59,6 +62,61 @@ impl From<BlockOperationError<AttestationInvalid>> for Error {
     }
 }
 
+/// Identifies a signature within a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignatureSetKind {
+    BlockProposal,
+    RandaoReveal,
+    /// The `header` is either `1` or `2`, for `signed_header_1` or `signed_header_2`.
+    ProposerSlashing {
+        index: usize,
+        header: usize,
+    },
+    /// The `attestation` is either `1` or `2`, for `attestation_1` or `attestation_2`.
+    AttesterSlashing {
+        index: usize,
+        attestation: usize,
+    },
+    Attestation {
+        index: usize,
+    },
+    VoluntaryExit {
+        index: usize,
+    },
+}
+
+impl fmt::Display for SignatureSetKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignatureSetKind::BlockProposal => write!(f, "block_proposal"),
+            SignatureSetKind::RandaoReveal => write!(f, "randao_reveal"),
+            SignatureSetKind::ProposerSlashing { index, header } => {
+                write!(f, "proposer_slashings[{}].signed_header_{}", index, header)
+            }
+            SignatureSetKind::AttesterSlashing { index, attestation } => {
+                write!(
+                    f,
+                    "attester_slashings[{}].attestation_{}",
+                    index, attestation
+                )
+            }
+            SignatureSetKind::Attestation { index } => write!(f, "attestations[{}]", index),
+            SignatureSetKind::VoluntaryExit { index } => write!(f, "voluntary_exits[{}]", index),
+        }
+    }
+}
+
+/// Describes an invalid signature found by `BlockSignatureVerifier::verify`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidSignatureSet {
+    pub block_slot: Slot,
+    pub block_root: Hash256,
+    pub kind: SignatureSetKind,
+    /// The tree hash root of the signed object (e.g., the attestation). For the block proposal
+    /// and randao reveal this is the block root.
+    pub object_root: Hash256,
+}
+
 /// Reads the BLS signatures and keys from a `SignedBeaconBlock`, storing them as a `Vec<SignatureSet>`.
 ///
 /// This allows for optimizations related to batch BLS operations (see the
@@ -72,6 +130,8 @@ where
     state: &'a BeaconState<T>,
     spec: &'a ChainSpec,
     sets: Vec<SignatureSet>,
+    /// The block and kind of each item in `sets`, used to report an invalid signature.
+    origins: Vec<(&'a SignedBeaconBlock<T>, Option<Hash256>, SignatureSetKind)>,
 }
 
 impl<'a, T, F> BlockSignatureVerifier<'a, T, F>
@@ -87,6 +147,7 @@ where
             state,
             spec,
             sets: vec![],
+            origins: vec![],
         }
     }
 
@@ -118,27 +179,78 @@ where
     /// BLS signatures](https://ethresear.ch/t/fast-verification-of-multiple-bls-signatures/5407)
     /// optimization proposed by Vitalik Buterin.
     ///
-    /// It is not possible to know exactly _which_ signature is invalid here, just that
-    /// _at least one_ was invalid.
+    /// The batch method only determines that _at least one_ signature was invalid. In that case,
+    /// the signatures are re-verified individually to find the first which is invalid, so it may
+    /// be reported.
     ///
     /// Uses `rayon` to do a map-reduce of Vitalik's method across multiple cores.
     pub fn verify(self) -> Result<()> {
         let num_sets = self.sets.len();
-        let num_chunks = std::cmp::max(1, num_sets / rayon::current_num_threads());
+        let num_chunks = std::cmp::max(
+            1,
+            num_sets
+                .checked_div(rayon::current_num_threads())
+                .unwrap_or(num_sets),
+        );
         let result: bool = self
             .sets
-            .into_par_iter()
+            .par_iter()
             .chunks(num_chunks)
-            .map(|chunk| verify_signature_sets(chunk))
+            .map(verify_signature_sets)
             .reduce(|| true, |current, this| current && this);
 
         if result {
             Ok(())
         } else {
-            Err(Error::SignatureInvalid)
+            Err(Error::SignatureInvalid(self.find_invalid_set()))
         }
     }
 
+    /// Verifies each signature individually, returning a description of the first invalid one.
+    fn find_invalid_set(&self) -> Option<InvalidSignatureSet> {
+        let i = self.sets.par_iter().position_first(|set| !set.is_valid())?;
+        let (block, block_root, kind) = self.origins.get(i)?;
+        let block_root = block_root.unwrap_or_else(|| block.canonical_root());
+        let body = &block.message.body;
+
+        let object_root = match *kind {
+            SignatureSetKind::BlockProposal | SignatureSetKind::RandaoReveal => Some(block_root),
+            SignatureSetKind::ProposerSlashing { index, header } => {
+                body.proposer_slashings.get(index).map(|slashing| {
+                    if header == 1 {
+                        slashing.signed_header_1.tree_hash_root()
+                    } else {
+                        slashing.signed_header_2.tree_hash_root()
+                    }
+                })
+            }
+            SignatureSetKind::AttesterSlashing { index, attestation } => {
+                body.attester_slashings.get(index).map(|slashing| {
+                    if attestation == 1 {
+                        slashing.attestation_1.tree_hash_root()
+                    } else {
+                        slashing.attestation_2.tree_hash_root()
+                    }
+                })
+            }
+            SignatureSetKind::Attestation { index } => body
+                .attestations
+                .get(index)
+                .map(|attestation| attestation.tree_hash_root()),
+            SignatureSetKind::VoluntaryExit { index } => body
+                .voluntary_exits
+                .get(index)
+                .map(|exit| exit.tree_hash_root()),
+        }?;
+
+        Some(InvalidSignatureSet {
+            block_slot: block.slot(),
+            block_root,
+            kind: *kind,
+            object_root,
+        })
+    }
+
     /// Includes all signatures on the block (except the deposit signatures) for verification.
     pub fn include_all_signatures(
         &mut self,
@@ -186,6 +298,8 @@ where
             self.spec,
         )?;
         self.sets.push(set);
+        self.origins
+            .push((block, block_root, SignatureSetKind::BlockProposal));
         Ok(())
     }
 
@@ -198,6 +312,8 @@ where
             self.spec,
         )?;
         self.sets.push(set);
+        self.origins
+            .push((block, None, SignatureSetKind::RandaoReveal));
         Ok(())
     }
 
@@ -223,6 +339,15 @@ where
             .collect();
 
         self.sets.append(&mut sets);
+        for index in 0..block.message.body.proposer_slashings.len() {
+            for header in 1..=2 {
+                self.origins.push((
+                    block,
+                    None,
+                    SignatureSetKind::ProposerSlashing { index, header },
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -233,7 +358,8 @@ where
             .body
             .attester_slashings
             .iter()
-            .try_for_each(|attester_slashing| {
+            .enumerate()
+            .try_for_each(|(index, attester_slashing)| {
                 let (set_1, set_2) = attester_slashing_signature_sets(
                     &self.state,
                     self.get_pubkey.clone(),
@@ -243,6 +369,13 @@ where
 
                 self.sets.push(set_1);
                 self.sets.push(set_2);
+                for attestation in 1..=2 {
+                    self.origins.push((
+                        block,
+                        None,
+                        SignatureSetKind::AttesterSlashing { index, attestation },
+                    ));
+                }
 
                 Ok(())
             })
@@ -258,7 +391,8 @@ where
             .body
             .attestations
             .iter()
-            .map(|attestation| {
+            .enumerate()
+            .map(|(index, attestation)| {
                 let committee = self
                     .state
                     .get_beacon_committee(attestation.data.slot, attestation.data.index)?;
@@ -272,6 +406,8 @@ where
                     &indexed_attestation,
                     &self.spec,
                 )?);
+                self.origins
+                    .push((block, None, SignatureSetKind::Attestation { index }));
 
                 Ok(indexed_attestation)
             })
@@ -290,6 +426,10 @@ where
             .collect::<SignatureSetResult<_>>()?;
 
         self.sets.append(&mut sets);
+        for index in 0..block.message.body.voluntary_exits.len() {
+            self.origins
+                .push((block, None, SignatureSetKind::VoluntaryExit { index }));
+        }
 
         Ok(())
     }
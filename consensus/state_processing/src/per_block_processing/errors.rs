@@ -52,6 +52,20 @@ pub enum BlockProcessingError {
     ArithError(ArithError),
 }
 
+impl BlockProcessingError {
+    /// Returns `true` if processing failed due to an arithmetic overflow or division by zero.
+    ///
+    /// Such errors are expected only from blocks crafted to trigger them. Otherwise, they indicate
+    /// a bug which may cause this node to disagree with others on the validity of a block.
+    pub fn is_arith_error(&self) -> bool {
+        match self {
+            BlockProcessingError::ArithError(_)
+            | BlockProcessingError::BeaconStateError(BeaconStateError::ArithError(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 impl From<BeaconStateError> for BlockProcessingError {
     fn from(e: BeaconStateError) -> Self {
         BlockProcessingError::BeaconStateError(e)
@@ -21,6 +21,17 @@ pub enum EpochProcessingError {
     ArithError(safe_arith::ArithError),
 }
 
+impl EpochProcessingError {
+    /// Returns `true` if processing failed due to an arithmetic overflow or division by zero.
+    pub fn is_arith_error(&self) -> bool {
+        match self {
+            EpochProcessingError::ArithError(_)
+            | EpochProcessingError::BeaconStateError(BeaconStateError::ArithError(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 impl From<InclusionError> for EpochProcessingError {
     fn from(e: InclusionError) -> EpochProcessingError {
         EpochProcessingError::InclusionError(e)
@@ -7,6 +7,17 @@ pub enum Error {
     EpochProcessingError(EpochProcessingError),
 }
 
+impl Error {
+    /// Returns `true` if processing failed due to an arithmetic overflow or division by zero.
+    pub fn is_arith_error(&self) -> bool {
+        match self {
+            Error::BeaconStateError(BeaconStateError::ArithError(_)) => true,
+            Error::EpochProcessingError(e) => e.is_arith_error(),
+            _ => false,
+        }
+    }
+}
+
 /// Advances a state forward by one slot, performing per-epoch processing if required.
 ///
 /// If the root of the supplied `state` is known, then it can be passed as `state_root`. If
@@ -24,6 +24,7 @@ pub use self::committee_cache::CommitteeCache;
 pub use clone_config::CloneConfig;
 pub use eth_spec::*;
 pub use tree_hash_cache::BeaconTreeHashCache;
+pub use view::BeaconStateView;
 
 #[macro_use]
 mod committee_cache;
@@ -32,6 +33,7 @@ mod exit_cache;
 mod pubkey_cache;
 mod tests;
 mod tree_hash_cache;
+mod view;
 
 pub const CACHED_EPOCHS: usize = 3;
 const MAX_RANDOM_BYTE: u64 = (1 << 8) - 1;
@@ -213,6 +213,40 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn ssz_view() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use ssz::Encode;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+    let state: BeaconState<MinimalEthSpec> = BeaconState::random_for_test(&mut rng);
+    let bytes = state.as_ssz_bytes();
+
+    let view = BeaconStateView::<MinimalEthSpec>::from_ssz_bytes(&bytes).unwrap();
+    assert_eq!(view.slot(), Ok(state.slot));
+    assert_eq!(view.fork(), Ok(state.fork.clone()));
+    assert_eq!(view.balances(), Ok(state.balances.clone()));
+    assert_eq!(
+        view.finalized_checkpoint(),
+        Ok(state.finalized_checkpoint.clone())
+    );
+    assert_eq!(view.validator_count(), Ok(state.validators.len()));
+
+    let last = state.validators.len() - 1;
+    assert_eq!(
+        view.get_validator(last),
+        Ok(Some(state.validators[last].clone()))
+    );
+    assert_eq!(view.get_balance(last), Ok(Some(state.balances[last])));
+    assert_eq!(view.get_validator(last + 1), Ok(None));
+    assert_eq!(view.get_balance(usize::max_value()), Ok(None));
+
+    assert_eq!(view.to_state(), Ok(state));
+
+    // Truncated states are rejected when the view is built.
+    assert!(BeaconStateView::<MinimalEthSpec>::from_ssz_bytes(&bytes[..100]).is_err());
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
@@ -0,0 +1,168 @@
+use crate::*;
+use ssz::{Decode, DecodeError, SszContainerView, SszDecoderBuilder};
+use std::marker::PhantomData;
+
+/// Defines a method which decodes only the `$index`'th field of the state, named after the field.
+macro_rules! view_field {
+    ($name: ident, $index: expr, $type: ty) => {
+        pub fn $name(&self) -> Result<$type, DecodeError> {
+            self.fields.decode_field($index)
+        }
+    };
+}
+
+const VALIDATORS_INDEX: usize = 11;
+const BALANCES_INDEX: usize = 12;
+
+/// A borrowed view of the SSZ encoding of a `BeaconState`, which only decodes the fields that are
+/// requested.
+///
+/// Building the view checks the layout of the encoding (i.e., the length of the fixed portion and
+/// its offsets) but not the contents of each field, which are checked when the field is decoded.
+/// Decoding the whole view with `to_state` is exactly equivalent to `BeaconState::from_ssz_bytes`.
+pub struct BeaconStateView<'a, T: EthSpec> {
+    bytes: &'a [u8],
+    fields: SszContainerView<'a>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T: EthSpec> BeaconStateView<'a, T> {
+    /// Splits `bytes` into the fields of a `BeaconState`, without decoding them.
+    pub fn from_ssz_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut builder = SszDecoderBuilder::new(bytes);
+
+        builder.register_type::<u64>()?;
+        builder.register_type::<Hash256>()?;
+        builder.register_type::<Slot>()?;
+        builder.register_type::<Fork>()?;
+        builder.register_type::<BeaconBlockHeader>()?;
+        builder.register_type::<FixedVector<Hash256, T::SlotsPerHistoricalRoot>>()?;
+        builder.register_type::<FixedVector<Hash256, T::SlotsPerHistoricalRoot>>()?;
+        builder.register_type::<VariableList<Hash256, T::HistoricalRootsLimit>>()?;
+        builder.register_type::<Eth1Data>()?;
+        builder.register_type::<VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>>()?;
+        builder.register_type::<u64>()?;
+        builder.register_type::<VariableList<Validator, T::ValidatorRegistryLimit>>()?;
+        builder.register_type::<VariableList<u64, T::ValidatorRegistryLimit>>()?;
+        builder.register_type::<FixedVector<Hash256, T::EpochsPerHistoricalVector>>()?;
+        builder.register_type::<FixedVector<u64, T::EpochsPerSlashingsVector>>()?;
+        builder
+            .register_type::<VariableList<PendingAttestation<T>, T::MaxPendingAttestations>>()?;
+        builder
+            .register_type::<VariableList<PendingAttestation<T>, T::MaxPendingAttestations>>()?;
+        builder.register_type::<BitVector<T::JustificationBitsLength>>()?;
+        builder.register_type::<Checkpoint>()?;
+        builder.register_type::<Checkpoint>()?;
+        builder.register_type::<Checkpoint>()?;
+
+        Ok(Self {
+            bytes,
+            fields: builder.build_view()?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Decodes the entire state.
+    pub fn to_state(&self) -> Result<BeaconState<T>, DecodeError> {
+        BeaconState::from_ssz_bytes(self.bytes)
+    }
+
+    view_field!(genesis_time, 0, u64);
+    view_field!(genesis_validators_root, 1, Hash256);
+    view_field!(slot, 2, Slot);
+    view_field!(fork, 3, Fork);
+    view_field!(latest_block_header, 4, BeaconBlockHeader);
+    view_field!(
+        block_roots,
+        5,
+        FixedVector<Hash256, T::SlotsPerHistoricalRoot>
+    );
+    view_field!(
+        state_roots,
+        6,
+        FixedVector<Hash256, T::SlotsPerHistoricalRoot>
+    );
+    view_field!(
+        historical_roots,
+        7,
+        VariableList<Hash256, T::HistoricalRootsLimit>
+    );
+    view_field!(eth1_data, 8, Eth1Data);
+    view_field!(
+        eth1_data_votes,
+        9,
+        VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>
+    );
+    view_field!(eth1_deposit_index, 10, u64);
+    view_field!(
+        validators,
+        VALIDATORS_INDEX,
+        VariableList<Validator, T::ValidatorRegistryLimit>
+    );
+    view_field!(
+        balances,
+        BALANCES_INDEX,
+        VariableList<u64, T::ValidatorRegistryLimit>
+    );
+    view_field!(
+        randao_mixes,
+        13,
+        FixedVector<Hash256, T::EpochsPerHistoricalVector>
+    );
+    view_field!(
+        slashings,
+        14,
+        FixedVector<u64, T::EpochsPerSlashingsVector>
+    );
+    view_field!(
+        previous_epoch_attestations,
+        15,
+        VariableList<PendingAttestation<T>, T::MaxPendingAttestations>
+    );
+    view_field!(
+        current_epoch_attestations,
+        16,
+        VariableList<PendingAttestation<T>, T::MaxPendingAttestations>
+    );
+    view_field!(
+        justification_bits,
+        17,
+        BitVector<T::JustificationBitsLength>
+    );
+    view_field!(previous_justified_checkpoint, 18, Checkpoint);
+    view_field!(current_justified_checkpoint, 19, Checkpoint);
+    view_field!(finalized_checkpoint, 20, Checkpoint);
+
+    /// Returns the number of validators, without decoding them.
+    pub fn validator_count(&self) -> Result<usize, DecodeError> {
+        Ok(self.fields.field_bytes(VALIDATORS_INDEX)?.len()
+            / <Validator as Decode>::ssz_fixed_len())
+    }
+
+    /// Decodes only the validator at `index`, returning `None` if there is no such validator.
+    pub fn get_validator(&self, index: usize) -> Result<Option<Validator>, DecodeError> {
+        self.get_list_item(VALIDATORS_INDEX, index)
+    }
+
+    /// Decodes only the balance of the validator at `index`, returning `None` if there is no such
+    /// validator.
+    pub fn get_balance(&self, index: usize) -> Result<Option<u64>, DecodeError> {
+        self.get_list_item(BALANCES_INDEX, index)
+    }
+
+    /// Decodes the `index`'th item of the list of fixed-length items in the `field`'th field.
+    fn get_list_item<I: Decode>(
+        &self,
+        field: usize,
+        index: usize,
+    ) -> Result<Option<I>, DecodeError> {
+        let len = I::ssz_fixed_len();
+        let bytes = self.fields.field_bytes(field)?;
+
+        index
+            .checked_mul(len)
+            .and_then(|start| bytes.get(start..start.checked_add(len)?))
+            .map(I::from_ssz_bytes)
+            .transpose()
+    }
+}
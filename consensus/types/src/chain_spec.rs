@@ -72,6 +72,12 @@ pub struct ChainSpec {
     #[serde(deserialize_with = "u8_from_hex_str", serialize_with = "u8_to_hex_str")]
     pub bls_withdrawal_prefix_byte: u8,
 
+    /*
+     * Fork schedule
+     */
+    /// The next fork announced for this network, if any.
+    pub next_fork: Option<Fork>,
+
     /*
      * Time parameters
      */
@@ -139,17 +145,18 @@ impl ChainSpec {
                 self.genesis_fork_version,
                 genesis_validators_root,
             ),
-            next_fork_version: self.genesis_fork_version,
-            next_fork_epoch: self.far_future_epoch,
+            next_fork_version: self
+                .next_fork
+                .as_ref()
+                .map_or(self.genesis_fork_version, |fork| fork.current_version),
+            next_fork_epoch: self.next_fork_epoch().unwrap_or(self.far_future_epoch),
         }
     }
 
-    /// Returns the epoch of the next scheduled change in the `fork.current_version`.
-    ///
-    /// There are no future forks scheduled so this function always returns `None`. This may not
-    /// always be the case in the future, though.
+    /// Returns the epoch of the next scheduled change in the `fork.current_version`, if a fork has
+    /// been announced for this network.
     pub fn next_fork_epoch(&self) -> Option<Epoch> {
-        None
+        self.next_fork.as_ref().map(|fork| fork.epoch)
     }
 
     /// Get the domain number, unmodified by the fork.
@@ -188,7 +195,15 @@ impl ChainSpec {
     ///
     /// Spec v0.11.1
     pub fn get_deposit_domain(&self) -> Hash256 {
-        self.compute_domain(Domain::Deposit, self.genesis_fork_version, Hash256::zero())
+        self.get_deposit_domain_for_fork_version(self.genesis_fork_version)
+    }
+
+    /// Get the domain for a deposit signature on the network with the given genesis
+    /// `fork_version`, which need not be that of `self`.
+    ///
+    /// Intended for preparing deposits for a network other than the one described by `self`.
+    pub fn get_deposit_domain_for_fork_version(&self, fork_version: [u8; 4]) -> Hash256 {
+        self.compute_domain(Domain::Deposit, fork_version, Hash256::zero())
     }
 
     /// Return the 32-byte fork data root for the `current_version` and `genesis_validators_root`.
@@ -282,6 +297,11 @@ impl ChainSpec {
             genesis_fork_version: [0; 4],
             bls_withdrawal_prefix_byte: 0,
 
+            /*
+             * Fork schedule
+             */
+            next_fork: None,
+
             /*
              * Time parameters
              */
@@ -473,6 +493,13 @@ pub struct YamlConfig {
         deserialize_with = "fork_from_hex_str"
     )]
     genesis_fork_version: [u8; 4],
+    // The next fork, which has not been announced if `next_fork_epoch == far_future_epoch`.
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    next_fork_version: [u8; 4],
+    next_fork_epoch: u64,
     #[serde(deserialize_with = "u8_from_hex_str", serialize_with = "u8_to_hex_str")]
     bls_withdrawal_prefix: u8,
     seconds_per_slot: u64,
@@ -599,6 +626,14 @@ impl YamlConfig {
             inactivity_penalty_quotient: spec.inactivity_penalty_quotient,
             min_slashing_penalty_quotient: spec.min_slashing_penalty_quotient,
             genesis_fork_version: spec.genesis_fork_version,
+            next_fork_version: spec
+                .next_fork
+                .as_ref()
+                .map_or(spec.genesis_fork_version, |fork| fork.current_version),
+            next_fork_epoch: spec
+                .next_fork_epoch()
+                .unwrap_or(spec.far_future_epoch)
+                .into(),
             safe_slots_to_update_justified: spec.safe_slots_to_update_justified,
             domain_beacon_proposer: spec.domain_beacon_proposer,
             domain_beacon_attester: spec.domain_beacon_attester,
@@ -704,6 +739,15 @@ impl YamlConfig {
             domain_voluntary_exit: self.domain_voluntary_exit,
             boot_nodes: chain_spec.boot_nodes.clone(),
             genesis_fork_version: self.genesis_fork_version,
+            next_fork: if self.next_fork_epoch == self.far_future_epoch {
+                None
+            } else {
+                Some(Fork {
+                    previous_version: self.genesis_fork_version,
+                    current_version: self.next_fork_version,
+                    epoch: Epoch::from(self.next_fork_epoch),
+                })
+            },
             eth1_follow_distance: self.eth1_follow_distance,
             ..*chain_spec
         })
@@ -779,4 +823,37 @@ mod yaml_tests {
             .expect("should have applied spec");
         assert_eq!(new_spec, ChainSpec::minimal());
     }
+
+    #[test]
+    fn next_fork() {
+        let spec = ChainSpec::minimal();
+        let yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+        assert_eq!(yamlconfig.next_fork_epoch, u64::max_value());
+        assert_eq!(
+            yamlconfig.apply_to_chain_spec::<MinimalEthSpec>(&spec),
+            Some(spec.clone()),
+            "should not announce a fork by default"
+        );
+
+        let yaml = "NEXT_FORK_VERSION: 0x01000001\nNEXT_FORK_EPOCH: 100\n";
+        let yamlconfig = YamlConfig {
+            genesis_fork_version: spec.genesis_fork_version,
+            ..serde_yaml::from_str(yaml).expect("should parse yaml")
+        };
+        let new_spec = yamlconfig
+            .apply_to_chain_spec::<MainnetEthSpec>(&ChainSpec::mainnet())
+            .expect("should have applied spec");
+        let next_fork = Fork {
+            previous_version: spec.genesis_fork_version,
+            current_version: [1, 0, 0, 1],
+            epoch: Epoch::new(100),
+        };
+        assert_eq!(new_spec.next_fork, Some(next_fork.clone()));
+        assert_eq!(new_spec.next_fork_epoch(), Some(Epoch::new(100)));
+        assert_eq!(
+            YamlConfig::from_spec::<MainnetEthSpec>(&new_spec),
+            yamlconfig,
+            "should round trip"
+        );
+    }
 }
@@ -35,7 +35,18 @@ impl DepositData {
     ///
     /// Spec v0.11.1
     pub fn create_signature(&self, secret_key: &SecretKey, spec: &ChainSpec) -> SignatureBytes {
-        let domain = spec.get_deposit_domain();
+        self.create_signature_with_fork_version(secret_key, spec.genesis_fork_version, spec)
+    }
+
+    /// Generate the signature for a given DepositData details, for the network with the given
+    /// genesis `fork_version` rather than that of `spec`.
+    pub fn create_signature_with_fork_version(
+        &self,
+        secret_key: &SecretKey,
+        fork_version: [u8; 4],
+        spec: &ChainSpec,
+    ) -> SignatureBytes {
+        let domain = spec.get_deposit_domain_for_fork_version(fork_version);
         let msg = self.as_deposit_message().signing_root(domain);
 
         SignatureBytes::from(Signature::new(msg.as_bytes(), secret_key))
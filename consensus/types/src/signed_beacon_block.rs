@@ -1,11 +1,13 @@
 use crate::{
-    test_utils::TestRandom, BeaconBlock, ChainSpec, Domain, EthSpec, Fork, Hash256, PublicKey,
-    SignedRoot, SigningRoot, Slot,
+    test_utils::TestRandom, BeaconBlock, BeaconBlockBody, ChainSpec, Domain, EthSpec, Fork,
+    Hash256, PublicKey, SignedRoot, SigningRoot, Slot,
 };
 use bls::Signature;
 use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError, SszContainerView, SszDecoderBuilder};
 use ssz_derive::{Decode, Encode};
 use std::fmt;
+use std::marker::PhantomData;
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
 
@@ -104,10 +106,103 @@ impl<E: EthSpec> SignedBeaconBlock<E> {
     }
 }
 
+/// A borrowed view of the SSZ encoding of a `SignedBeaconBlock`, which only decodes the fields
+/// that are requested. In particular, the block body is not decoded unless `body` or `to_block` is
+/// called.
+///
+/// Building the view checks the layout of the encoding but not the contents of each field, which
+/// are checked when the field is decoded.
+pub struct SignedBeaconBlockView<'a, E: EthSpec> {
+    bytes: &'a [u8],
+    signed_block: SszContainerView<'a>,
+    message: SszContainerView<'a>,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E: EthSpec> SignedBeaconBlockView<'a, E> {
+    /// Splits `bytes` into the fields of a `SignedBeaconBlock`, without decoding them.
+    pub fn from_ssz_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut builder = SszDecoderBuilder::new(bytes);
+        builder.register_type::<BeaconBlock<E>>()?;
+        builder.register_type::<Signature>()?;
+        let signed_block = builder.build_view()?;
+
+        let mut builder = SszDecoderBuilder::new(signed_block.field_bytes(0)?);
+        builder.register_type::<Slot>()?;
+        builder.register_type::<u64>()?;
+        builder.register_type::<Hash256>()?;
+        builder.register_type::<Hash256>()?;
+        builder.register_type::<BeaconBlockBody<E>>()?;
+        let message = builder.build_view()?;
+
+        Ok(Self {
+            bytes,
+            signed_block,
+            message,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Decodes the entire block.
+    pub fn to_block(&self) -> Result<SignedBeaconBlock<E>, DecodeError> {
+        SignedBeaconBlock::from_ssz_bytes(self.bytes)
+    }
+
+    /// Decodes the block's slot.
+    pub fn slot(&self) -> Result<Slot, DecodeError> {
+        self.message.decode_field(0)
+    }
+
+    /// Decodes the block's proposer index.
+    pub fn proposer_index(&self) -> Result<u64, DecodeError> {
+        self.message.decode_field(1)
+    }
+
+    /// Decodes the block's parent root.
+    pub fn parent_root(&self) -> Result<Hash256, DecodeError> {
+        self.message.decode_field(2)
+    }
+
+    /// Decodes the block's state root.
+    pub fn state_root(&self) -> Result<Hash256, DecodeError> {
+        self.message.decode_field(3)
+    }
+
+    /// Decodes the block's body, which is by far its largest field.
+    pub fn body(&self) -> Result<BeaconBlockBody<E>, DecodeError> {
+        self.message.decode_field(4)
+    }
+
+    /// Decodes the proposer's signature.
+    pub fn signature(&self) -> Result<Signature, DecodeError> {
+        self.signed_block.decode_field(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
     use crate::MainnetEthSpec;
+    use ssz::Encode;
 
     ssz_tests!(SignedBeaconBlock<MainnetEthSpec>);
+
+    #[test]
+    fn ssz_view() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let block = SignedBeaconBlock::<MainnetEthSpec>::random_for_test(&mut rng);
+        let bytes = block.as_ssz_bytes();
+
+        let view = SignedBeaconBlockView::<MainnetEthSpec>::from_ssz_bytes(&bytes).unwrap();
+        assert_eq!(view.slot(), Ok(block.slot()));
+        assert_eq!(view.proposer_index(), Ok(block.message.proposer_index));
+        assert_eq!(view.parent_root(), Ok(block.parent_root()));
+        assert_eq!(view.state_root(), Ok(block.state_root()));
+        assert_eq!(view.body(), Ok(block.message.body.clone()));
+        assert_eq!(view.signature(), Ok(block.signature.clone()));
+        assert_eq!(view.to_block(), Ok(block));
+
+        assert!(SignedBeaconBlockView::<MainnetEthSpec>::from_ssz_bytes(&bytes[..50]).is_err());
+    }
 }
@@ -60,16 +60,16 @@ type VerifySet<'a> = (
 );
 
 #[cfg(not(feature = "fake_crypto"))]
-pub fn verify_signature_sets<'a>(sets: Vec<SignatureSet>) -> bool {
+pub fn verify_signature_sets<'a>(sets: impl IntoIterator<Item = &'a SignatureSet>) -> bool {
     let rng = &mut rand::thread_rng();
     let verify_set: Vec<VerifySet> = sets
-        .iter()
+        .into_iter()
         .map(|ss| (&ss.signature, &ss.signing_keys, ss.message.as_slice()))
         .collect();
     RawAggregateSignature::verify_multiple_aggregate_signatures(rng, verify_set.into_iter())
 }
 
 #[cfg(feature = "fake_crypto")]
-pub fn verify_signature_sets<'a>(_: Vec<SignatureSet>) -> bool {
+pub fn verify_signature_sets<'a>(_: impl IntoIterator<Item = &'a SignatureSet>) -> bool {
     true
 }
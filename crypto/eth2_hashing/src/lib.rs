@@ -3,6 +3,11 @@
 //! The purpose of this crate is to provide an abstraction to whatever hash function Ethereum
 //! 2.0 is using. The hash function has been subject to change during the specification process, so
 //! defining it once in this crate makes it easy to replace.
+//!
+//! `ring` detects the features of the CPU (e.g., SHA extensions, AVX2 or SSSE3 on x86_64 and the
+//! ARMv8 SHA2 instructions or NEON on aarch64) when the program starts and selects the fastest
+//! implementation available, so a binary built for a generic target is portable without giving up
+//! the optimized code paths.
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use ring::digest::{digest, Context, Digest, SHA256};
@@ -4,6 +4,7 @@ use std::iter::Iterator;
 pub const PURPOSE: u32 = 12381;
 pub const COIN_TYPE: u32 = 3600;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum KeyType {
     Voting,
     Withdrawal,
@@ -285,3 +285,20 @@ pub fn recover_validator_secret(
 
     Ok((destination.secret().to_vec().into(), path))
 }
+
+/// Returns `(secret, path)` for the `key_type` for the validator at `index`, derived directly from
+/// a BIP-39 `seed` rather than from an encrypted `Wallet`.
+///
+/// The caveats of `recover_validator_secret` also apply to this function.
+pub fn recover_validator_secret_from_seed(
+    seed: &[u8],
+    index: u32,
+    key_type: KeyType,
+) -> Result<(PlainText, ValidatorPath), Error> {
+    let path = ValidatorPath::new(index, key_type);
+    let master = DerivedKey::from_seed(seed).map_err(|()| Error::EmptySeed)?;
+
+    let destination = path.iter_nodes().fold(master, |dk, i| dk.child(*i));
+
+    Ok((destination.secret().to_vec().into(), path))
+}
@@ -2,7 +2,8 @@
 
 use eth2_wallet::{
     bip39::{Language, Mnemonic, Seed},
-    recover_validator_secret, DerivedKey, Error, KeyType, KeystoreError, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_from_seed, DerivedKey, Error, KeyType,
+    KeystoreError, Wallet, WalletBuilder,
 };
 use std::fs::OpenOptions;
 use tempfile::tempdir;
@@ -279,3 +280,28 @@ fn key_derivation_from_seed() {
         assert_eq!(wallet.nextaccount(), i + 1, "updated nextaccount");
     }
 }
+
+#[test]
+fn recover_from_seed() {
+    let wallet = wallet_from_seed();
+
+    for i in 0..3 {
+        let (voting, path) = recover_validator_secret_from_seed(SEED, i, KeyType::Voting)
+            .expect("should recover voting secret");
+        assert_eq!(format!("{}", path), format!("m/12381/3600/{}/0/0", i));
+        assert_eq!(voting.as_bytes(), &recovered_voting_key(&wallet, i)[..]);
+
+        let (withdrawal, path) = recover_validator_secret_from_seed(SEED, i, KeyType::Withdrawal)
+            .expect("should recover withdrawal secret");
+        assert_eq!(format!("{}", path), format!("m/12381/3600/{}/0", i));
+        assert_eq!(
+            withdrawal.as_bytes(),
+            &recovered_withdrawal_key(&wallet, i)[..]
+        );
+    }
+
+    assert_eq!(
+        recover_validator_secret_from_seed(&[], 0, KeyType::Voting).err(),
+        Some(Error::EmptySeed)
+    );
+}
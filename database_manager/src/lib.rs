@@ -0,0 +1,269 @@
+//! Provides the `lighthouse db` command, for inspecting and migrating the schema of the beacon
+//! node database whilst the beacon node is stopped, and for reporting which blocks and states it
+//! holds.
+
+use beacon_node::{
+    beacon_chain::{PersistedBeaconChain, BEACON_CHAIN_DB_KEY},
+    get_data_dir, ClientConfig,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use std::path::PathBuf;
+use std::sync::Arc;
+use store::{
+    iter::RootsIterator,
+    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
+    DiskStore, Store, StoreConfig,
+};
+use types::{EthSpec, Hash256, Slot};
+
+pub const CMD: &str = "database_manager";
+pub const VERSION_CMD: &str = "version";
+pub const MIGRATE_CMD: &str = "migrate";
+pub const PROBE_CMD: &str = "probe";
+pub const FREEZER_DIR_FLAG: &str = "freezer-dir";
+pub const TO_FLAG: &str = "to";
+pub const START_SLOT_FLAG: &str = "start-slot";
+pub const END_SLOT_FLAG: &str = "end-slot";
+pub const BLOCK_ROOT_FLAG: &str = "block-root";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["db"])
+        .about(
+            "Utilities for managing the beacon node database. The beacon node must not be \
+            running.",
+        )
+        .arg(
+            Arg::with_name(FREEZER_DIR_FLAG)
+                .long(FREEZER_DIR_FLAG)
+                .value_name("DIR")
+                .help("The freezer database directory, if not the default.")
+                .takes_value(true),
+        )
+        .subcommand(App::new(VERSION_CMD).about("Prints the schema version of the database."))
+        .subcommand(
+            App::new(MIGRATE_CMD)
+                .about(
+                    "Migrates the database to the given schema version, which may be lower than \
+                    the current version. Use this before rolling back to an earlier release.",
+                )
+                .arg(
+                    Arg::with_name(TO_FLAG)
+                        .long(TO_FLAG)
+                        .value_name("VERSION")
+                        .help("The schema version to migrate to.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new(PROBE_CMD)
+                .about(
+                    "Reports, for each slot in a range, whether the block and state are in the \
+                    hot database, the freezer database or missing, along with the number of \
+                    slots which must be replayed to load the state. States which must be \
+                    replayed are slow to serve from the HTTP API, and missing states cannot be \
+                    served at all.",
+                )
+                .arg(
+                    Arg::with_name(START_SLOT_FLAG)
+                        .long(START_SLOT_FLAG)
+                        .value_name("SLOT")
+                        .help("The first slot to report.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(END_SLOT_FLAG)
+                        .long(END_SLOT_FLAG)
+                        .value_name("SLOT")
+                        .help("The last slot to report. Defaults to --start-slot.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(BLOCK_ROOT_FLAG)
+                        .long(BLOCK_ROOT_FLAG)
+                        .value_name("ROOT")
+                        .help(
+                            "The 0x-prefixed root of a block whose ancestors are reported, e.g., \
+                            to probe a fork. Defaults to the canonical head.",
+                        )
+                        .takes_value(true),
+                ),
+        )
+}
+
+/// Run the database manager, returning an error if the operation did not succeed.
+pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, mut env: Environment<T>) -> Result<(), String> {
+    let (hot_path, cold_path) = db_paths(matches)?;
+    let context = env.core_context();
+
+    match matches.subcommand() {
+        (VERSION_CMD, Some(_)) => {
+            let version = DiskStore::<T>::read_schema_version(&hot_path)
+                .map_err(|e| format!("Unable to read schema version: {:?}", e))?;
+
+            println!("schema_version: {}", version);
+            println!("current_schema_version: {}", CURRENT_SCHEMA_VERSION);
+        }
+        (MIGRATE_CMD, Some(matches)) => {
+            let to = SchemaVersion(clap_utils::parse_required(matches, TO_FLAG)?);
+
+            // Opening the database upgrades its schema to `CURRENT_SCHEMA_VERSION`, if it is
+            // lower.
+            let db = DiskStore::<T>::open(
+                &hot_path,
+                &cold_path,
+                StoreConfig::default(),
+                context.eth2_config.spec.clone(),
+                context.log,
+            )
+            .map_err(|e| format!("Unable to open database: {:?}", e))?;
+            let version = db
+                .schema_version()
+                .map_err(|e| format!("Unable to read schema version: {:?}", e))?;
+
+            db.migrate_schema(version, to)
+                .map_err(|e| format!("Unable to migrate from {} to {}: {:?}", version, to, e))?;
+
+            println!(
+                "Migrated database from schema version {} to {}",
+                version, to
+            );
+        }
+        (PROBE_CMD, Some(matches)) => {
+            let start_slot = Slot::new(clap_utils::parse_required(matches, START_SLOT_FLAG)?);
+            let end_slot = clap_utils::parse_optional(matches, END_SLOT_FLAG)?
+                .map(Slot::new)
+                .unwrap_or(start_slot);
+            let block_root = clap_utils::parse_ssz_optional(matches, BLOCK_ROOT_FLAG)?;
+
+            if end_slot < start_slot {
+                return Err(format!(
+                    "--{} must not be less than --{}",
+                    END_SLOT_FLAG, START_SLOT_FLAG
+                ));
+            }
+
+            let db = DiskStore::<T>::open_read_only(
+                &hot_path,
+                &cold_path,
+                StoreConfig::default(),
+                context.eth2_config.spec.clone(),
+                context.log,
+            )
+            .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+            probe(Arc::new(db), start_slot, end_slot, block_root)?;
+        }
+        (unknown, _) => {
+            return Err(format!(
+                "{} is not a valid {} command. See --help.",
+                unknown, CMD
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the availability of the blocks and states from `start_slot` to `end_slot` (inclusive) on
+/// the chain which ends at `block_root`, or the canonical head.
+fn probe<E: EthSpec>(
+    db: Arc<DiskStore<E>>,
+    start_slot: Slot,
+    end_slot: Slot,
+    block_root: Option<Hash256>,
+) -> Result<(), String> {
+    let block_root = match block_root {
+        Some(block_root) => block_root,
+        None => {
+            db.get::<PersistedBeaconChain>(&Hash256::from_slice(&BEACON_CHAIN_DB_KEY))
+                .map_err(|e| format!("Unable to read the canonical head: {:?}", e))?
+                .ok_or_else(|| "The database has no canonical head".to_string())?
+                .canonical_head_block_root
+        }
+    };
+    let block = db
+        .get_block(&block_root)
+        .map_err(|e| format!("Unable to read block {:?}: {:?}", block_root, e))?
+        .ok_or_else(|| format!("Unknown block {:?}", block_root))?;
+
+    // Walk back from the block, including it since `RootsIterator` starts at the slot before.
+    let roots = std::iter::once((block_root, block.state_root(), block.slot())).chain(
+        RootsIterator::from_block(db.clone(), block_root)
+            .map_err(|e| format!("Unable to load the state of {:?}: {:?}", block_root, e))?,
+    );
+
+    let mut lines = vec![];
+    let mut oldest_slot = None;
+    for (block_root, state_root, slot) in roots.take_while(|(_, _, slot)| *slot >= start_slot) {
+        oldest_slot = Some(slot);
+        if slot > end_slot {
+            continue;
+        }
+
+        let block = match db.get_block(&block_root) {
+            Ok(Some(block)) if block.slot() == slot => "hot",
+            Ok(Some(_)) => "skipped",
+            Ok(None) => "missing",
+            Err(e) => return Err(format!("Unable to read block {:?}: {:?}", block_root, e)),
+        };
+        let state = db
+            .state_availability(&state_root, slot)
+            .map_err(|e| format!("Unable to read state {:?}: {:?}", state_root, e))?;
+        let replay_slots = state
+            .replay_slots()
+            .map_or_else(|| "-".to_string(), |slots| slots.to_string());
+
+        lines.push(format!(
+            "{}\t{:?}\t{}\t{:?}\t{}\t{}",
+            slot,
+            block_root,
+            block,
+            state_root,
+            state.location(),
+            replay_slots
+        ));
+    }
+
+    println!("slot\tblock_root\tblock\tstate_root\tstate\treplay_slots");
+    for line in lines.iter().rev() {
+        println!("{}", line);
+    }
+
+    // The walk stops early if it reaches history which has been pruned.
+    if let Some(oldest_slot) = oldest_slot.filter(|slot| *slot > start_slot) {
+        println!(
+            "Slots {} to {} are unknown, the history before slot {} is not available",
+            start_slot,
+            std::cmp::min(oldest_slot - 1, end_slot),
+            oldest_slot
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the paths of the hot and freezer databases, at the same locations as the beacon node
+/// would use.
+fn db_paths(matches: &ArgMatches<'_>) -> Result<(PathBuf, PathBuf), String> {
+    let mut client_config = ClientConfig::default();
+    client_config.data_dir = get_data_dir(matches);
+    client_config.freezer_db_path =
+        clap_utils::parse_optional::<PathBuf>(matches, FREEZER_DIR_FLAG)?;
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database".to_string())?;
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    Ok((hot_path, cold_path))
+}
@@ -0,0 +1,87 @@
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use remote_beacon_node::RemoteBeaconNode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::EthSpec;
+
+/// Imports the blocks in a SSZ file, a tar archive of SSZ files or a directory of SSZ files into a
+/// running beacon node via its HTTP API. The node fully verifies each block.
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let path: PathBuf = parse_required(matches, "blocks")?;
+    let server: String = parse_required(matches, "server")?;
+    let timeout_secs: u64 = parse_required(matches, "timeout")?;
+    let auth_token_path: Option<PathBuf> = parse_optional(matches, "server-auth-token")?;
+    let auth_token = auth_token_path
+        .map(|path| {
+            fs::read_to_string(&path)
+                .map(|token| token.trim().to_string())
+                .map_err(|e| format!("Unable to read --server-auth-token {:?}: {:?}", path, e))
+        })
+        .transpose()?;
+
+    let (content_type, body) = if path.is_dir() {
+        ("application/x-tar", archive_dir(&path)?)
+    } else {
+        let bytes = fs::read(&path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+
+        if path.extension().map_or(false, |ext| ext == "tar") {
+            ("application/x-tar", bytes)
+        } else {
+            ("application/ssz", bytes)
+        }
+    };
+
+    let node = RemoteBeaconNode::<T>::new_with_auth_token(
+        server,
+        Duration::from_secs(timeout_secs),
+        auth_token,
+    )?;
+    let response = env
+        .runtime()
+        .block_on(node.http.lighthouse().import_blocks_ssz(content_type, body))
+        .map_err(|e| format!("Failed to import blocks: {:?}", e))?;
+
+    println!("imported_blocks: {}", response.imported_blocks);
+    println!("head_block_root: {:?}", response.head_block_root);
+
+    if let Some(error) = response.error {
+        Err(format!("Import stopped early: {}", error))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns a tar archive of the `.ssz` files in `dir`.
+fn archive_dir(dir: &Path) -> Result<Vec<u8>, String> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "ssz"));
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(format!("No .ssz files found in {:?}", dir));
+    }
+
+    let mut archive = tar::Builder::new(vec![]);
+    for path in paths {
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid file name {:?}", path))?;
+        archive
+            .append_path_with_name(&path, name)
+            .map_err(|e| format!("Unable to archive {:?}: {:?}", path, e))?;
+    }
+
+    archive
+        .into_inner()
+        .map_err(|e| format!("Unable to build archive: {:?}", e))
+}
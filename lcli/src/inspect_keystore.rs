@@ -0,0 +1,46 @@
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use eth2_keystore::{json_keystore::Kdf, Keystore};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Prints the unencrypted fields of an EIP-2335 keystore. The keystore is never decrypted, so no
+/// password is required.
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let keystore_path: PathBuf = parse_required(matches, "keystore")?;
+
+    let file = File::open(&keystore_path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", keystore_path, e))?;
+    let keystore = Keystore::from_json_reader(file)
+        .map_err(|e| format!("Unable to parse keystore: {:?}", e))?;
+
+    println!("uuid: {}", keystore.uuid());
+    println!("pubkey: 0x{}", keystore.pubkey());
+    println!(
+        "path: {}",
+        if keystore.path().is_empty() {
+            "<none>"
+        } else {
+            keystore.path()
+        }
+    );
+
+    match keystore.kdf() {
+        Kdf::Scrypt(params) => {
+            println!("kdf: scrypt");
+            println!("  n: {}", params.n);
+            println!("  r: {}", params.r);
+            println!("  p: {}", params.p);
+            println!("  dklen: {}", params.dklen);
+            println!("  salt: 0x{}", hex::encode(params.salt.as_bytes()));
+        }
+        Kdf::Pbkdf2(params) => {
+            println!("kdf: pbkdf2");
+            println!("  c: {}", params.c);
+            println!("  dklen: {}", params.dklen);
+            println!("  salt: 0x{}", hex::encode(params.salt.as_bytes()));
+        }
+    }
+
+    Ok(())
+}
@@ -6,12 +6,17 @@ mod check_deposit_data;
 mod deploy_deposit_contract;
 mod eth1_genesis;
 mod generate_bootnode_enr;
+mod import_blocks;
 mod insecure_validators;
+mod inspect_keystore;
 mod interop_genesis;
+mod mnemonic_pubkeys;
 mod new_testnet;
 mod parse_hex;
 mod refund_deposit_contract;
+mod replay_inputs;
 mod skip_slots;
+mod step_block;
 mod transition_blocks;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
@@ -131,6 +136,45 @@ fn main() {
                         .help("Path to output a SSZ file."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("step-block")
+                .about(
+                    "Applies a block to a pre-state one operation at a time (each slot, the \
+                    header, randao, eth1 data and each slashing, attestation, deposit and exit), \
+                    printing the state root after each. Useful for finding the first operation \
+                    on which two implementations disagree.",
+                )
+                .arg(
+                    Arg::with_name("pre-state")
+                        .value_name("BEACON_STATE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a SSZ file of the pre-state."),
+                )
+                .arg(
+                    Arg::with_name("block")
+                        .value_name("BEACON_BLOCK")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a SSZ file of the block to apply to pre-state."),
+                )
+                .arg(
+                    Arg::with_name("compare")
+                        .long("compare")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help(
+                            "The output of a previous run (e.g., with another version) to \
+                            compare against. Exits with an error naming the first step whose \
+                            state root differs.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("no-signature-verification")
+                        .long("no-signature-verification")
+                        .help("Do not verify the signatures of the block and its operations."),
+                )
+        )
         .subcommand(
             SubCommand::with_name("pretty-hex")
                 .about("Parses SSZ encoded as ASCII 0x-prefixed hex")
@@ -379,6 +423,35 @@ fn main() {
                         .help("The block the deposit contract was deployed. Setting this is a huge
                               optimization for nodes, please do it."),
                 )
+                .arg(
+                    Arg::with_name("boot-enr")
+                        .long("boot-enr")
+                        .value_name("ENR")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .help("The ENR of a boot node for the testnet. May be supplied multiple
+                              times or as a comma-separated list."),
+                )
+                .arg(
+                    Arg::with_name("genesis-validator-count")
+                        .long("genesis-validator-count")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .help("If present, a genesis state with this many validators is created
+                              at --min-genesis-time (defaults to now). Otherwise, genesis is
+                              determined by deposits to the deposit contract."),
+                )
+                .arg(
+                    Arg::with_name("mnemonic-path")
+                        .long("mnemonic-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .requires("genesis-validator-count")
+                        .help("A file containing the BIP-39 mnemonic from which the genesis
+                              validator keys are derived (EIP-2334, starting at index 0). If
+                              omitted, the insecure interop keys are used."),
+                )
         )
         .subcommand(
             SubCommand::with_name("check-deposit-data")
@@ -468,6 +541,135 @@ fn main() {
                         .help("The directory for storing secrets."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("mnemonic-pubkeys")
+                .about(
+                    "Prints the EIP-2334 derivation path and public key of the validator keys \
+                    derived from a mnemonic, without creating any keystores.",
+                )
+                .arg(
+                    Arg::with_name("mnemonic-path")
+                        .long("mnemonic-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("A file containing the BIP-39 mnemonic."),
+                )
+                .arg(
+                    Arg::with_name("first-index")
+                        .long("first-index")
+                        .value_name("INDEX")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("The index of the first validator to derive."),
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .long("count")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("The number of consecutive validators to derive."),
+                )
+                .arg(
+                    Arg::with_name("key-type")
+                        .long("key-type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .possible_values(&["voting", "withdrawal"])
+                        .default_value("voting")
+                        .help("Whether to derive the voting or withdrawal keys."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("inspect-keystore")
+                .about(
+                    "Prints the public key, derivation path and KDF parameters of an EIP-2335 \
+                    keystore without decrypting it.",
+                )
+                .arg(
+                    Arg::with_name("keystore")
+                        .value_name("KEYSTORE_PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The path to the keystore JSON file."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("replay-inputs")
+                .about(
+                    "Replays the inputs recorded by a beacon node with --record-inputs into a \
+                    fresh chain and prints the resulting head and finality.",
+                )
+                .arg(
+                    Arg::with_name("genesis-state")
+                        .value_name("SSZ_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a SSZ file of the genesis state of the recording node."),
+                )
+                .arg(
+                    Arg::with_name("recording")
+                        .value_name("RECORDING_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the recording."),
+                )
+                .arg(
+                    Arg::with_name("datadir")
+                        .long("datadir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .default_value("./replay")
+                        .help("The directory in which to store any caches persisted by the chain."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("import-blocks")
+                .about(
+                    "Imports blocks from SSZ files into a running beacon node via its HTTP API, \
+                    with full verification. Useful for recovering a node which has no peers \
+                    from a trusted archive (e.g., one downloaded from \
+                    /lighthouse/beacon/blocks/{block_id}/ssz?count).",
+                )
+                .arg(
+                    Arg::with_name("blocks")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "Path to a SSZ file of a SignedBeaconBlock, a tar archive of such \
+                            files or a directory containing .ssz files.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("server")
+                        .long("server")
+                        .value_name("HTTP_ADDRESS")
+                        .takes_value(true)
+                        .default_value("http://localhost:5052")
+                        .help("The HTTP API of the beacon node."),
+                )
+                .arg(
+                    Arg::with_name("timeout")
+                        .long("timeout")
+                        .value_name("SECONDS")
+                        .takes_value(true)
+                        .default_value("600")
+                        .help("The time to wait for the beacon node to import the blocks."),
+                )
+                .arg(
+                    Arg::with_name("server-auth-token")
+                        .long("server-auth-token")
+                        .value_name("TOKEN_PATH")
+                        .takes_value(true)
+                        .help(
+                            "Path to the file containing the token with which to authenticate \
+                            to the beacon node. Required if the beacon node was started with \
+                            --http-require-auth.",
+                        ),
+                )
+        )
         .get_matches();
 
     macro_rules! run_with_spec {
@@ -546,6 +748,8 @@ fn run<T: EthSpec>(
         }
         ("transition-blocks", Some(matches)) => run_transition_blocks::<T>(matches)
             .map_err(|e| format!("Failed to transition blocks: {}", e)),
+        ("step-block", Some(matches)) => step_block::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to step through block: {}", e)),
         ("skip-slots", Some(matches)) => {
             skip_slots::run::<T>(matches).map_err(|e| format!("Failed to skip slots: {}", e))
         }
@@ -574,6 +778,14 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run generate-bootnode-enr command: {}", e)),
         ("insecure-validators", Some(matches)) => insecure_validators::run(matches)
             .map_err(|e| format!("Failed to run insecure-validators command: {}", e)),
+        ("mnemonic-pubkeys", Some(matches)) => mnemonic_pubkeys::run(matches)
+            .map_err(|e| format!("Failed to run mnemonic-pubkeys command: {}", e)),
+        ("inspect-keystore", Some(matches)) => inspect_keystore::run(matches)
+            .map_err(|e| format!("Failed to run inspect-keystore command: {}", e)),
+        ("replay-inputs", Some(matches)) => replay_inputs::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to run replay-inputs command: {}", e)),
+        ("import-blocks", Some(matches)) => import_blocks::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to run import-blocks command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
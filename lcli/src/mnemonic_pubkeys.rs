@@ -0,0 +1,48 @@
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use eth2_keystore::keypair_from_secret;
+use eth2_wallet::{
+    bip39::{Language, Mnemonic, Seed as Bip39Seed},
+    recover_validator_secret_from_seed, KeyType,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads a BIP-39 mnemonic from the file at `path` and returns its seed, using an empty
+/// passphrase.
+pub fn seed_from_mnemonic_file(path: &Path) -> Result<Bip39Seed, String> {
+    let phrase =
+        fs::read_to_string(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|e| format!("Unable to parse mnemonic: {:?}", e))?;
+    Ok(Bip39Seed::new(&mnemonic, ""))
+}
+
+/// Prints the EIP-2334 path and public key for each index in `first-index..first-index + count`.
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let mnemonic_path: PathBuf = parse_required(matches, "mnemonic-path")?;
+    let first_index: u32 = parse_required(matches, "first-index")?;
+    let count: u32 = parse_required(matches, "count")?;
+    let key_type = match matches.value_of("key-type") {
+        Some("withdrawal") => KeyType::Withdrawal,
+        Some("voting") | None => KeyType::Voting,
+        Some(other) => return Err(format!("Unknown key type: {}", other)),
+    };
+
+    let end_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| "first-index + count overflows".to_string())?;
+
+    let seed = seed_from_mnemonic_file(&mnemonic_path)?;
+
+    for index in first_index..end_index {
+        let (secret, path) = recover_validator_secret_from_seed(seed.as_bytes(), index, key_type)
+            .map_err(|e| format!("Unable to derive key {}: {:?}", index, e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to build keypair {}: {:?}", index, e))?;
+
+        println!("{}\t{}\t{}", index, path, keypair.pk.as_hex_string());
+    }
+
+    Ok(())
+}
@@ -1,10 +1,20 @@
+use crate::mnemonic_pubkeys::seed_from_mnemonic_file;
 use clap::ArgMatches;
 use clap_utils::{
     parse_optional, parse_path_with_default_in_home_dir, parse_required, parse_ssz_optional,
 };
+use eth2_keystore::keypair_from_secret;
+use eth2_libp2p::Enr;
 use eth2_testnet_config::Eth2TestnetConfig;
+use eth2_wallet::{recover_validator_secret_from_seed, KeyType};
+use genesis::{genesis_state_with_withdrawal_keys, interop_genesis_state};
 use std::path::PathBuf;
-use types::{Address, EthSpec, YamlConfig};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{
+    test_utils::generate_deterministic_keypairs, Address, BeaconState, ChainSpec, EthSpec, Keypair,
+    YamlConfig,
+};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let testnet_dir_path = parse_path_with_default_in_home_dir(
@@ -53,13 +63,85 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         spec.genesis_fork_version = v;
     }
 
+    let boot_enr = matches
+        .values_of("boot-enr")
+        .map(|values| {
+            values
+                .map(|enr| {
+                    Enr::from_str(enr).map_err(|e| format!("Unable to parse boot ENR: {:?}", e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_else(Vec::new);
+
+    // Without genesis validators, genesis is determined by deposits to the deposit contract.
+    let genesis_state = if let Some(validator_count) =
+        parse_optional::<usize>(matches, "genesis-validator-count")?
+    {
+        let genesis_time = match parse_optional(matches, "min-genesis-time")? {
+            Some(time) => time,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| format!("Unable to get time: {:?}", e))?
+                .as_secs(),
+        };
+
+        let mnemonic_path: Option<PathBuf> = parse_optional(matches, "mnemonic-path")?;
+        Some(genesis_state::<T>(
+            validator_count,
+            mnemonic_path,
+            genesis_time,
+            &spec,
+        )?)
+    } else {
+        None
+    };
+
     let testnet: Eth2TestnetConfig<T> = Eth2TestnetConfig {
         deposit_contract_address: format!("{:?}", deposit_contract_address),
         deposit_contract_deploy_block,
-        boot_enr: Some(vec![]),
-        genesis_state: None,
+        boot_enr: Some(boot_enr),
+        genesis_state,
         yaml_config: Some(YamlConfig::from_spec::<T>(&spec)),
     };
 
     testnet.write_to_file(testnet_dir_path, overwrite_files)
 }
+
+/// Returns a genesis state with `validator_count` validators.
+///
+/// The validator keys are derived from the mnemonic in the file at `mnemonic_path` (as per
+/// EIP-2334, starting at index 0), or are the insecure interop keys if `mnemonic_path` is `None`.
+fn genesis_state<T: EthSpec>(
+    validator_count: usize,
+    mnemonic_path: Option<PathBuf>,
+    genesis_time: u64,
+    spec: &ChainSpec,
+) -> Result<BeaconState<T>, String> {
+    let mnemonic_path = if let Some(path) = mnemonic_path {
+        path
+    } else {
+        let keypairs = generate_deterministic_keypairs(validator_count);
+        return interop_genesis_state(&keypairs, genesis_time, spec);
+    };
+
+    let seed = seed_from_mnemonic_file(&mnemonic_path)?;
+
+    let derive_keypair = |index: usize, key_type: KeyType| -> Result<Keypair, String> {
+        let (secret, _path) =
+            recover_validator_secret_from_seed(seed.as_bytes(), index as u32, key_type)
+                .map_err(|e| format!("Unable to derive key {}: {:?}", index, e))?;
+        keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to build keypair {}: {:?}", index, e))
+    };
+
+    let keypairs = (0..validator_count)
+        .map(|i| derive_keypair(i, KeyType::Voting))
+        .collect::<Result<Vec<_>, _>>()?;
+    let withdrawal_pubkeys = (0..validator_count)
+        .map(|i| derive_keypair(i, KeyType::Withdrawal).map(|keypair| keypair.pk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    genesis_state_with_withdrawal_keys(&keypairs, &withdrawal_pubkeys, genesis_time, spec)
+}
@@ -0,0 +1,63 @@
+use crate::transition_blocks::load_from_ssz;
+use beacon_chain::{
+    input_recorder::{read_recording, replay},
+    EmbeddedChain,
+};
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use environment::Environment;
+use std::path::PathBuf;
+use std::sync::Arc;
+use store::MemoryStore;
+use types::{BeaconState, EthSpec};
+
+/// Replays a recording made with the beacon node `--record-inputs` flag into a fresh, in-memory
+/// chain started from the given genesis state, then prints the resulting head and finality.
+///
+/// The recording must have been started from the same genesis state.
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let genesis_state_path: PathBuf = parse_required(matches, "genesis-state")?;
+    let recording_path: PathBuf = parse_required(matches, "recording")?;
+    let data_dir: PathBuf = parse_required(matches, "datadir")?;
+
+    let context = env.core_context();
+    let log = context.log.clone();
+    let spec = context.eth2_config.spec.clone();
+
+    let genesis_state: BeaconState<T> = load_from_ssz(genesis_state_path)?;
+    let entries = read_recording::<T>(&recording_path)?;
+
+    info!(
+        "Replaying {} inputs from {:?}",
+        entries.len(),
+        recording_path
+    );
+
+    let chain = EmbeddedChain::from_genesis(
+        Arc::new(MemoryStore::<T>::open()),
+        spec,
+        genesis_state,
+        log,
+        data_dir,
+    )?;
+
+    let summary = replay(&chain, entries)?;
+    let head = chain
+        .head()
+        .map_err(|e| format!("Unable to read head: {:?}", e))?;
+
+    println!("blocks_imported: {}", summary.blocks_imported);
+    println!("blocks_rejected: {}", summary.blocks_rejected);
+    println!("attestations_applied: {}", summary.attestations_applied);
+    println!("attestations_rejected: {}", summary.attestations_rejected);
+    println!("fork_choice_errors: {}", summary.fork_choice_errors);
+    println!("head_slot: {}", head.slot);
+    println!("head_block_root: {:?}", head.block_root);
+    println!(
+        "current_justified_epoch: {}",
+        head.current_justified_checkpoint.epoch
+    );
+    println!("finalized_epoch: {}", head.finalized_checkpoint.epoch);
+
+    Ok(())
+}
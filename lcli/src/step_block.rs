@@ -0,0 +1,237 @@
+//! Applies a block to a state one operation at a time, printing the state root after each, so
+//! that the first operation on which two implementations (or versions) disagree can be found.
+
+use crate::transition_blocks::load_from_ssz;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use state_processing::{
+    per_block_processing::{
+        process_attestations, process_attester_slashings, process_block_header, process_deposit,
+        process_eth1_data, process_exits, process_proposer_slashings, process_randao,
+        verify_block_signature,
+    },
+    per_slot_processing, BlockProcessingError, VerifySignatures,
+};
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, Unsigned};
+
+/// Prints the state root after each step, comparing it to the root of the same step in a previous
+/// output.
+struct Stepper<T: EthSpec> {
+    state: BeaconState<T>,
+    count: usize,
+    /// The steps of a previous output, if any, to compare against.
+    expected: Option<Vec<(String, Hash256)>>,
+    /// The index and name of the first step whose root differs from `expected`.
+    divergence: Option<(usize, String)>,
+}
+
+impl<T: EthSpec> Stepper<T> {
+    /// Applies `f` to the state as the step called `name`, then prints the state root.
+    fn step<F, E>(&mut self, name: String, f: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut BeaconState<T>) -> Result<(), E>,
+        E: Debug,
+    {
+        f(&mut self.state)
+            .map_err(|e| format!("Step {} ({}) failed: {:?}", self.count, name, e))?;
+        let root = self
+            .state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to hash state: {:?}", e))?;
+
+        println!("{}\t{:?}", name, root);
+
+        if self.divergence.is_none() {
+            let matches = self
+                .expected
+                .as_ref()
+                .and_then(|expected| expected.get(self.count))
+                .map(|(expected_name, expected_root)| {
+                    *expected_name == name && *expected_root == root
+                });
+            if matches == Some(false) {
+                self.divergence = Some((self.count, name));
+            }
+        }
+
+        self.count += 1;
+        Ok(())
+    }
+}
+
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let pre_state_path: PathBuf = parse_required(matches, "pre-state")?;
+    let block_path: PathBuf = parse_required(matches, "block")?;
+    let compare_path: Option<PathBuf> = parse_optional(matches, "compare")?;
+    let verify_signatures = if matches.is_present("no-signature-verification") {
+        VerifySignatures::False
+    } else {
+        VerifySignatures::True
+    };
+    let spec = env.core_context().eth2_config.spec;
+
+    let expected = compare_path
+        .as_ref()
+        .map(|path| read_steps(path))
+        .transpose()?;
+
+    let mut stepper = Stepper {
+        state: load_from_ssz(pre_state_path)?,
+        count: 0,
+        expected,
+        divergence: None,
+    };
+    let block: SignedBeaconBlock<T> = load_from_ssz(block_path)?;
+
+    let result = step_block(&mut stepper, &block, verify_signatures, &spec);
+
+    if let Some((index, name)) = &stepper.divergence {
+        return Err(format!(
+            "The state root first differs from {:?} after step {} ({})",
+            compare_path, index, name
+        ));
+    }
+    result?;
+
+    if let Some(expected) = &stepper.expected {
+        if expected.len() != stepper.count {
+            return Err(format!(
+                "{:?} has {} steps, but {} were applied",
+                compare_path,
+                expected.len(),
+                stepper.count
+            ));
+        }
+        info!("All {} state roots match {:?}", stepper.count, compare_path);
+    }
+
+    Ok(())
+}
+
+/// Applies the same state transition as `per_block_processing` (after advancing to the slot of
+/// `block`), as one step for each operation.
+fn step_block<T: EthSpec>(
+    stepper: &mut Stepper<T>,
+    block: &SignedBeaconBlock<T>,
+    verify_signatures: VerifySignatures,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    stepper
+        .state
+        .build_all_caches(spec)
+        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+
+    while stepper.state.slot < block.slot() {
+        let slot = stepper.state.slot + 1;
+        stepper.step(format!("slot {}", slot), |state| {
+            per_slot_processing(state, None, spec).map(|_| ())
+        })?;
+    }
+
+    let body = &block.message.body;
+
+    stepper.step(
+        "block_header".to_string(),
+        |state| -> Result<(), BlockProcessingError> {
+            process_block_header(state, &block.message, spec)?;
+            if verify_signatures.is_true() {
+                verify_block_signature(state, block, None, spec)?;
+            }
+            state.build_committee_cache(RelativeEpoch::Previous, spec)?;
+            state.build_committee_cache(RelativeEpoch::Current, spec)?;
+            Ok(())
+        },
+    )?;
+    stepper.step("randao".to_string(), |state| {
+        process_randao(state, &block.message, verify_signatures, spec)
+    })?;
+    stepper.step("eth1_data".to_string(), |state| {
+        process_eth1_data(state, &body.eth1_data)
+    })?;
+
+    for (i, slashing) in body.proposer_slashings.iter().enumerate() {
+        stepper.step(format!("proposer_slashing {}", i), |state| {
+            process_proposer_slashings(
+                state,
+                std::slice::from_ref(slashing),
+                verify_signatures,
+                spec,
+            )
+        })?;
+    }
+    for (i, slashing) in body.attester_slashings.iter().enumerate() {
+        stepper.step(format!("attester_slashing {}", i), |state| {
+            process_attester_slashings(
+                state,
+                std::slice::from_ref(slashing),
+                verify_signatures,
+                spec,
+            )
+        })?;
+    }
+    for (i, attestation) in body.attestations.iter().enumerate() {
+        stepper.step(format!("attestation {}", i), |state| {
+            process_attestations(
+                state,
+                std::slice::from_ref(attestation),
+                verify_signatures,
+                spec,
+            )
+        })?;
+    }
+
+    // The number of deposits is checked for the block as a whole.
+    let expected_deposits = std::cmp::min(
+        T::MaxDeposits::to_u64(),
+        stepper
+            .state
+            .get_outstanding_deposit_len()
+            .map_err(|e| format!("Unable to count outstanding deposits: {:?}", e))?,
+    );
+    if body.deposits.len() as u64 != expected_deposits {
+        return Err(format!(
+            "The block has {} deposits, but {} were expected",
+            body.deposits.len(),
+            expected_deposits
+        ));
+    }
+    for (i, deposit) in body.deposits.iter().enumerate() {
+        stepper.step(format!("deposit {}", i), |state| {
+            process_deposit(state, deposit, spec, true)
+        })?;
+    }
+
+    for (i, exit) in body.voluntary_exits.iter().enumerate() {
+        stepper.step(format!("voluntary_exit {}", i), |state| {
+            process_exits(state, std::slice::from_ref(exit), verify_signatures, spec)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Reads the steps printed by a previous run.
+fn read_steps(path: &PathBuf) -> Result<Vec<(String, Hash256)>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            let mut parts = line.splitn(2, '\t');
+            let name = parts.next().unwrap_or("");
+            let root = parts
+                .next()
+                .filter(|root| root.starts_with("0x"))
+                .and_then(|root| root[2..].parse::<Hash256>().ok())
+                .ok_or_else(|| format!("Invalid line {} of {:?}: {}", i + 1, path, line))?;
+            Ok((name.to_string(), root))
+        })
+        .collect()
+}
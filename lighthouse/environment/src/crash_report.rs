@@ -0,0 +1,197 @@
+//! Provides the crash report bundle written when a Lighthouse service suffers a fatal internal
+//! error (e.g., a panic or a corrupt database).
+//!
+//! The report is a plain-text file containing the reason for the crash, any sections registered
+//! by the running services (e.g., the head of the beacon chain) and the most recent log lines.
+
+use slog::{Drain, Never, OwnedKVList, Record, Serializer, KV};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as FmtWrite};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of log lines retained by `RecentLogs`.
+pub const RECENT_LOGS_CAPACITY: usize = 1_024;
+
+/// A `slog::Drain` which retains the most recent log lines in memory, so they may be included in a
+/// crash report.
+///
+/// Cloning returns a handle to the same buffer.
+#[derive(Clone)]
+pub struct RecentLogs {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Default for RecentLogs {
+    fn default() -> Self {
+        Self::new(RECENT_LOGS_CAPACITY)
+    }
+}
+
+impl RecentLogs {
+    /// Creates a new, empty buffer which retains at most `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns the retained lines, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn push(&self, line: String) {
+        // The buffer is only locked by this type and never whilst panicking, however a crash report
+        // is still preferable to a second panic.
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+}
+
+impl Drain for RecentLogs {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), Never> {
+        let mut line = format!(
+            "{} {} {}",
+            unix_seconds(),
+            record.level().as_short_str(),
+            record.msg()
+        );
+
+        let mut serializer = LineSerializer(&mut line);
+        let _ = record.kv().serialize(record, &mut serializer);
+        let _ = values.serialize(record, &mut serializer);
+
+        self.push(line);
+
+        Ok(())
+    }
+}
+
+/// Appends each key-value pair of a log record to a line.
+struct LineSerializer<'a>(&'a mut String);
+
+impl<'a> Serializer for LineSerializer<'a> {
+    fn emit_arguments(&mut self, key: slog::Key, val: &fmt::Arguments) -> slog::Result {
+        let _ = write!(self.0, ", {}: {}", key, val);
+        Ok(())
+    }
+}
+
+/// The contents of a crash report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    /// The time of the crash, as seconds since the UNIX epoch.
+    pub time: u64,
+    /// The reason given for the fatal error.
+    pub reason: String,
+    /// Named sections contributed by the running services.
+    pub sections: Vec<(String, String)>,
+    /// The most recent log lines, oldest first.
+    pub recent_logs: Vec<String>,
+}
+
+impl CrashReport {
+    /// Creates a report of a crash which happened now.
+    pub fn new(reason: String, sections: Vec<(String, String)>, recent_logs: Vec<String>) -> Self {
+        Self {
+            time: unix_seconds(),
+            reason,
+            sections,
+            recent_logs,
+        }
+    }
+
+    /// Writes the report to a new file in `dir`, creating `dir` if necessary. Returns the path of
+    /// the file.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<PathBuf, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Unable to create crash report directory: {:?}", e))?;
+
+        let path = dir.join(format!("crash-{}.txt", self.time));
+        fs::write(&path, self.to_string())
+            .map_err(|e| format!("Unable to write crash report {:?}: {:?}", path, e))?;
+
+        Ok(path)
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Lighthouse crash report")?;
+        writeln!(f, "time: {}", self.time)?;
+        writeln!(f, "reason: {}", self.reason)?;
+
+        for (name, contents) in &self.sections {
+            writeln!(f, "\n## {}\n{}", name, contents)?;
+        }
+
+        writeln!(f, "\n## recent logs")?;
+        for line in &self.recent_logs {
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::{info, o, Logger};
+
+    #[test]
+    fn recent_logs_are_bounded() {
+        let recent_logs = RecentLogs::new(2);
+        let log = Logger::root(recent_logs.clone().fuse(), o!("service" => "test"));
+
+        info!(log, "first");
+        info!(log, "second"; "slot" => 1);
+        info!(log, "third"; "slot" => 2);
+
+        let lines = recent_logs.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("INFO second, slot: 1, service: test"));
+        assert!(lines[1].ends_with("INFO third, slot: 2, service: test"));
+    }
+
+    #[test]
+    fn writes_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CrashReport::new(
+            "database corrupt".to_string(),
+            vec![("head".to_string(), "slot: 1".to_string())],
+            vec!["a log line".to_string()],
+        );
+
+        let path = report
+            .write_to_dir(&dir.path().join("crash_reports"))
+            .unwrap();
+        let contents = fs::read_to_string(path).unwrap();
+
+        assert!(contents.contains("reason: database corrupt"));
+        assert!(contents.contains("## head\nslot: 1"));
+        assert!(contents.ends_with("## recent logs\na log line\n"));
+    }
+}
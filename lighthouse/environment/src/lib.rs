@@ -7,21 +7,87 @@
 //! `Context` which can be handed to any service that wishes to start async tasks or perform
 //! logging.
 
+#[macro_use]
+extern crate lazy_static;
+
+mod crash_report;
+
 use eth2_config::Eth2Config;
 use eth2_testnet_config::Eth2TestnetConfig;
-use futures::channel::oneshot;
-use slog::{info, o, Drain, Level, Logger};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use logging::{parse_level, LogControl};
+use parking_lot::Mutex;
+use slog::{crit, info, o, Drain, Duplicate, Logger};
 use sloggers::{null::NullLoggerBuilder, Build};
-use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::fs::{rename as FsRename, OpenOptions};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::runtime::{Builder as RuntimeBuilder, Handle, Runtime};
-use types::{EthSpec, InteropEthSpec, MainnetEthSpec, MinimalEthSpec};
+use types::{ChainSpec, EthSpec, InteropEthSpec, MainnetEthSpec, MinimalEthSpec};
+
+pub use crash_report::{CrashReport, RecentLogs};
 
 pub const ETH2_CONFIG_FILENAME: &str = "eth2-spec.toml";
 
+lazy_static! {
+    /// The sender of the `Environment` which is currently blocking in
+    /// `Environment::block_until_shutdown_requested`.
+    ///
+    /// The `ctrlc` crate only permits a single handler per process, so the handler (and the panic
+    /// hook) are installed once and forward to whichever `Environment` is running.
+    static ref SHUTDOWN_SENDER: Mutex<Option<ShutdownSender>> = Mutex::new(None);
+}
+
+static INSTALL_SIGNAL_HANDLERS: Once = Once::new();
+
+/// The reason for which an `Environment` should stop its services.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShutdownReason {
+    /// The user pressed Ctrl+C or the process was terminated.
+    Interrupted,
+    /// A service suffered an internal error from which it cannot recover (e.g., a panic or a
+    /// corrupt database).
+    Fatal(String),
+}
+
+/// Requests that the `Environment` stops its services. Obtained from a `RuntimeContext`.
+pub type ShutdownSender = UnboundedSender<ShutdownReason>;
+
+/// A function returning a section to be included in a `CrashReport`.
+pub type CrashReportSection = Box<dyn Fn() -> String + Send>;
+
+/// Overrides for the genesis parameters of the eth2 spec.
+///
+/// Useful for launching a devnet on a single machine within seconds, rather than waiting for the
+/// genesis delay and minimum genesis time of the testnet it was derived from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenesisOverrides {
+    /// Overrides `MIN_GENESIS_DELAY`, in seconds.
+    pub genesis_delay: Option<u64>,
+    /// Overrides `MIN_GENESIS_TIME`, as seconds since the UNIX epoch.
+    pub min_genesis_time: Option<u64>,
+    /// Overrides `GENESIS_FORK_VERSION`.
+    pub genesis_fork_version: Option<[u8; 4]>,
+}
+
+impl GenesisOverrides {
+    /// Applies each of the overrides that are set to `spec`.
+    pub fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        if let Some(genesis_delay) = self.genesis_delay {
+            spec.min_genesis_delay = genesis_delay;
+        }
+        if let Some(min_genesis_time) = self.min_genesis_time {
+            spec.min_genesis_time = min_genesis_time;
+        }
+        if let Some(genesis_fork_version) = self.genesis_fork_version {
+            spec.genesis_fork_version = genesis_fork_version;
+        }
+    }
+}
+
 /// Builds an `Environment`.
 pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Runtime>,
@@ -29,6 +95,8 @@ pub struct EnvironmentBuilder<E: EthSpec> {
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     testnet: Option<Eth2TestnetConfig<E>>,
+    recent_logs: RecentLogs,
+    log_control: LogControl,
 }
 
 impl EnvironmentBuilder<MinimalEthSpec> {
@@ -40,6 +108,8 @@ impl EnvironmentBuilder<MinimalEthSpec> {
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             testnet: None,
+            recent_logs: RecentLogs::default(),
+            log_control: LogControl::default(),
         }
     }
 }
@@ -53,6 +123,8 @@ impl EnvironmentBuilder<MainnetEthSpec> {
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             testnet: None,
+            recent_logs: RecentLogs::default(),
+            log_control: LogControl::default(),
         }
     }
 }
@@ -66,6 +138,8 @@ impl EnvironmentBuilder<InteropEthSpec> {
             eth_spec_instance: InteropEthSpec,
             eth2_config: Eth2Config::interop(),
             testnet: None,
+            recent_logs: RecentLogs::default(),
+            log_control: LogControl::default(),
         }
     }
 }
@@ -133,15 +207,13 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             slog_async::Async::new(drain).build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        // Retain the most recent logs for crash reports.
+        let drain = Duplicate::new(drain, self.recent_logs.clone()).fuse();
+
+        // The level may be changed at runtime, e.g., from the HTTP API.
+        self.log_control
+            .set_default_level(parse_level(debug_level)?);
+        let drain = self.log_control.drain(drain);
 
         self.log = Some(Logger::root(drain.fuse(), o!()));
         Ok(self)
@@ -170,8 +242,18 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         Ok(self)
     }
 
+    /// Overrides the genesis parameters of the eth2 spec.
+    ///
+    /// Must be called after `Self::eth2_testnet_config`, which replaces the spec.
+    pub fn genesis_overrides(mut self, overrides: &GenesisOverrides) -> Self {
+        overrides.apply_to_chain_spec(&mut self.eth2_config.spec);
+        self
+    }
+
     /// Consumes the builder, returning an `Environment`.
     pub fn build(self) -> Result<Environment<E>, String> {
+        let (shutdown_sender, shutdown_receiver) = unbounded();
+
         Ok(Environment {
             runtime: self
                 .runtime
@@ -182,6 +264,11 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             testnet: self.testnet,
+            shutdown_sender,
+            shutdown_receiver,
+            recent_logs: self.recent_logs,
+            log_control: self.log_control,
+            crash_report_sections: vec![],
         })
     }
 }
@@ -196,6 +283,9 @@ pub struct RuntimeContext<E: EthSpec> {
     pub log: Logger,
     pub eth_spec_instance: E,
     pub eth2_config: Eth2Config,
+    pub shutdown_sender: ShutdownSender,
+    /// Controls the log level of each module of `log`.
+    pub log_control: LogControl,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -208,6 +298,8 @@ impl<E: EthSpec> RuntimeContext<E> {
             log: self.log.new(o!("service" => service_name)),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            shutdown_sender: self.shutdown_sender.clone(),
+            log_control: self.log_control.clone(),
         }
     }
 
@@ -215,6 +307,16 @@ impl<E: EthSpec> RuntimeContext<E> {
     pub fn eth2_config(&self) -> &Eth2Config {
         &self.eth2_config
     }
+
+    /// Reports an internal error from which this service cannot recover, causing the `Environment`
+    /// to write a crash report and stop all services.
+    pub fn fatal_error(&self, reason: String) {
+        crit!(self.log, "Fatal internal error"; "reason" => &reason);
+        // The environment may already be shutting down, in which case there is nothing to do.
+        let _ = self
+            .shutdown_sender
+            .unbounded_send(ShutdownReason::Fatal(reason));
+    }
 }
 
 /// An environment where Lighthouse services can run. Used to start a production beacon node or
@@ -225,6 +327,11 @@ pub struct Environment<E: EthSpec> {
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub testnet: Option<Eth2TestnetConfig<E>>,
+    shutdown_sender: ShutdownSender,
+    shutdown_receiver: UnboundedReceiver<ShutdownReason>,
+    recent_logs: RecentLogs,
+    log_control: LogControl,
+    crash_report_sections: Vec<(String, CrashReportSection)>,
 }
 
 impl<E: EthSpec> Environment<E> {
@@ -243,6 +350,8 @@ impl<E: EthSpec> Environment<E> {
             log: self.log.clone(),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            shutdown_sender: self.shutdown_sender.clone(),
+            log_control: self.log_control.clone(),
         }
     }
 
@@ -253,24 +362,65 @@ impl<E: EthSpec> Environment<E> {
             log: self.log.new(o!("service" => service_name)),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            shutdown_sender: self.shutdown_sender.clone(),
+            log_control: self.log_control.clone(),
         }
     }
 
-    /// Block the current thread until Ctrl+C is received.
-    pub fn block_until_ctrl_c(&mut self) -> Result<(), String> {
-        let (ctrlc_send, ctrlc_oneshot) = oneshot::channel();
-        let ctrlc_send_c = RefCell::new(Some(ctrlc_send));
-        ctrlc::set_handler(move || {
-            if let Some(ctrlc_send) = ctrlc_send_c.try_borrow_mut().unwrap().take() {
-                ctrlc_send.send(()).expect("Error sending ctrl-c message");
-            }
-        })
-        .map_err(|e| format!("Could not set ctrlc handler: {:?}", e))?;
+    /// Block the current thread until Ctrl+C is received, a service panics or a service reports a
+    /// fatal error via `RuntimeContext::fatal_error`.
+    pub fn block_until_shutdown_requested(&mut self) -> Result<ShutdownReason, String> {
+        *SHUTDOWN_SENDER.lock() = Some(self.shutdown_sender.clone());
 
-        // Block this thread until Crtl+C is pressed.
-        self.runtime()
-            .block_on(ctrlc_oneshot)
-            .map_err(|e| format!("Ctrlc oneshot failed: {:?}", e))
+        let mut result = Ok(());
+        INSTALL_SIGNAL_HANDLERS.call_once(|| {
+            result = ctrlc::set_handler(|| {
+                if let Some(sender) = SHUTDOWN_SENDER.lock().as_ref() {
+                    let _ = sender.unbounded_send(ShutdownReason::Interrupted);
+                }
+            })
+            .map_err(|e| format!("Could not set ctrlc handler: {:?}", e));
+
+            // A panic in a spawned task only terminates that task, possibly leaving locks poisoned
+            // or caches half-updated, so treat it as fatal to the whole process.
+            let default_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                default_hook(info);
+                if let Some(sender) = SHUTDOWN_SENDER.lock().as_ref() {
+                    let _ = sender.unbounded_send(ShutdownReason::Fatal(format!("{}", info)));
+                }
+            }));
+        });
+        result?;
+
+        // Block this thread until a shutdown is requested.
+        let shutdown_receiver = &mut self.shutdown_receiver;
+        let reason = self
+            .runtime
+            .block_on(shutdown_receiver.next())
+            .ok_or_else(|| "Shutdown channel closed".to_string());
+
+        *SHUTDOWN_SENDER.lock() = None;
+
+        reason
+    }
+
+    /// Adds a section to any crash report written by `Self::write_crash_report`. The `section`
+    /// function is called when the report is written.
+    pub fn add_crash_report_section(&mut self, name: String, section: CrashReportSection) {
+        self.crash_report_sections.push((name, section));
+    }
+
+    /// Writes a crash report for the fatal error described by `reason` to a new file in `dir`,
+    /// returning the path of the file.
+    pub fn write_crash_report(&self, dir: &Path, reason: &str) -> Result<PathBuf, String> {
+        let sections = self
+            .crash_report_sections
+            .iter()
+            .map(|(name, section)| (name.clone(), section()))
+            .collect();
+
+        CrashReport::new(reason.to_string(), sections, self.recent_logs.lines()).write_to_dir(dir)
     }
 
     /// Shutdown the `tokio` runtime when all tasks are idle.
@@ -320,15 +470,12 @@ impl<E: EthSpec> Environment<E> {
             _ => return Err("Logging format provided is not supported".to_string()),
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let drain = Duplicate::new(drain, self.recent_logs.clone()).fuse();
+
+        // The level may be changed at runtime, e.g., from the HTTP API.
+        self.log_control
+            .set_default_level(parse_level(debug_level)?);
+        let drain = self.log_control.drain(drain);
 
         self.log = Logger::root(drain.fuse(), o!());
 
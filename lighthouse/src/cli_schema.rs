@@ -0,0 +1,168 @@
+//! Describes the command line interface for tooling, either as shell completions or as a JSON
+//! schema of every subcommand and flag (with its default value), generated from the same `clap`
+//! definitions used to parse the arguments.
+
+use clap::{App, Arg, ArgMatches, ArgSettings, Shell, SubCommand};
+use serde_derive::Serialize;
+use std::io;
+
+pub const COMPLETIONS_CMD: &str = "completions";
+pub const SCHEMA_CMD: &str = "cli-schema";
+pub const SHELL_FLAG: &str = "shell";
+
+/// The name of the binary, as invoked by the completion scripts.
+const BIN_NAME: &str = "lighthouse";
+
+pub fn completions_cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(COMPLETIONS_CMD)
+        .about("Prints a shell completion script for all subcommands and flags.")
+        .arg(
+            Arg::with_name(SHELL_FLAG)
+                .long(SHELL_FLAG)
+                .value_name("SHELL")
+                .help("The shell to generate completions for.")
+                .possible_values(&Shell::variants())
+                .takes_value(true)
+                .required(true),
+        )
+}
+
+pub fn schema_cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(SCHEMA_CMD).about(
+        "Prints a JSON description of all subcommands and flags, including their default \
+            values, for use by configuration management tooling.",
+    )
+}
+
+/// A subcommand (or the binary itself) and its flags.
+#[derive(Debug, Serialize)]
+pub struct CommandSchema {
+    pub name: String,
+    pub version: Option<String>,
+    pub about: Option<String>,
+    pub args: Vec<ArgSchema>,
+    pub subcommands: Vec<CommandSchema>,
+}
+
+/// A flag or positional argument of a subcommand.
+#[derive(Debug, Serialize)]
+pub struct ArgSchema {
+    pub name: String,
+    /// `None` for positional arguments.
+    pub long: Option<String>,
+    pub short: Option<char>,
+    pub takes_value: bool,
+    pub value_name: Option<String>,
+    pub default_value: Option<String>,
+    /// `None` if any value is accepted.
+    pub possible_values: Option<Vec<String>>,
+    pub required: bool,
+    /// `true` if the flag may also be given to any subcommand.
+    pub global: bool,
+    pub help: Option<String>,
+}
+
+impl CommandSchema {
+    /// Describes `app` and its subcommands.
+    ///
+    /// `clap` has no public API for inspecting an `App`, so this reads its (hidden) parser. The
+    /// `clap` version is pinned in `Cargo.toml` for this reason.
+    pub fn from_app(app: &App) -> Self {
+        let parser = &app.p;
+        let required = |name: &str| parser.required.contains(&name);
+
+        let flags = parser.flags.iter().map(|flag| ArgSchema {
+            name: flag.b.name.to_string(),
+            long: flag.s.long.map(String::from),
+            short: flag.s.short,
+            takes_value: false,
+            value_name: None,
+            default_value: None,
+            possible_values: None,
+            required: required(flag.b.name),
+            global: flag.b.settings.is_set(ArgSettings::Global),
+            help: flag.b.help.map(String::from),
+        });
+        let opts = parser.opts.iter().map(|opt| ArgSchema {
+            name: opt.b.name.to_string(),
+            long: opt.s.long.map(String::from),
+            short: opt.s.short,
+            takes_value: true,
+            value_name: opt
+                .v
+                .val_names
+                .as_ref()
+                .and_then(|names| names.values().next())
+                .map(|name| name.to_string()),
+            default_value: opt
+                .v
+                .default_val
+                .map(|value| value.to_string_lossy().into_owned()),
+            possible_values: opt
+                .v
+                .possible_vals
+                .as_ref()
+                .map(|values| values.iter().map(|value| value.to_string()).collect()),
+            required: required(opt.b.name),
+            global: opt.b.settings.is_set(ArgSettings::Global),
+            help: opt.b.help.map(String::from),
+        });
+        let positionals = parser.positionals.values().map(|pos| ArgSchema {
+            name: pos.b.name.to_string(),
+            long: None,
+            short: None,
+            takes_value: true,
+            value_name: pos
+                .v
+                .val_names
+                .as_ref()
+                .and_then(|names| names.values().next())
+                .map(|name| name.to_string()),
+            default_value: pos
+                .v
+                .default_val
+                .map(|value| value.to_string_lossy().into_owned()),
+            possible_values: pos
+                .v
+                .possible_vals
+                .as_ref()
+                .map(|values| values.iter().map(|value| value.to_string()).collect()),
+            required: required(pos.b.name),
+            global: pos.b.settings.is_set(ArgSettings::Global),
+            help: pos.b.help.map(String::from),
+        });
+
+        let mut args: Vec<ArgSchema> = flags.chain(opts).chain(positionals).collect();
+        args.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            name: parser.meta.name.clone(),
+            version: parser.meta.version.map(String::from),
+            about: parser.meta.about.map(String::from),
+            args,
+            subcommands: parser
+                .subcommands
+                .iter()
+                .map(CommandSchema::from_app)
+                .collect(),
+        }
+    }
+}
+
+/// Prints the completion script for the shell in `matches` for all of `app`.
+pub fn print_completions(mut app: App, matches: &ArgMatches) -> Result<(), String> {
+    let shell = clap_utils::parse_required::<String>(matches, SHELL_FLAG)?
+        .parse::<Shell>()
+        .map_err(|e| format!("Invalid --{}: {}", SHELL_FLAG, e))?;
+
+    app.gen_completions_to(BIN_NAME, shell, &mut io::stdout());
+    Ok(())
+}
+
+/// Prints the JSON schema of `app`.
+pub fn print_schema(app: &App) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&CommandSchema::from_app(app))
+        .map_err(|e| format!("Unable to encode CLI schema: {:?}", e))?;
+    println!("{}", json);
+    Ok(())
+}
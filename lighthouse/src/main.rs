@@ -1,15 +1,18 @@
 #[macro_use]
 extern crate clap;
 
+mod cli_schema;
+
 use beacon_node::ProductionBeaconNode;
 use clap::{App, Arg, ArgMatches};
 use clap_utils;
 use env_logger::{Builder, Env};
-use environment::EnvironmentBuilder;
-use eth2_testnet_config::HARDCODED_TESTNET;
+use environment::{EnvironmentBuilder, GenesisOverrides, ShutdownReason};
+use eth2_testnet_config::{hardcoded_net_names, HARDCODED_TESTNET};
 use slog::{crit, info, warn};
 use std::path::PathBuf;
 use std::process::exit;
+use std::sync::Arc;
 use types::EthSpec;
 use validator_client::ProductionValidatorClient;
 
@@ -22,7 +25,84 @@ fn main() {
     Builder::from_env(Env::default()).init();
 
     // Parse the CLI parameters.
-    let matches = App::new("Lighthouse")
+    let matches = cli_app().get_matches();
+
+    // Describing the CLI does not require an environment.
+    let description =
+        if let Some(sub_matches) = matches.subcommand_matches(cli_schema::COMPLETIONS_CMD) {
+            Some(cli_schema::print_completions(cli_app(), sub_matches))
+        } else if matches.subcommand_matches(cli_schema::SCHEMA_CMD).is_some() {
+            Some(cli_schema::print_schema(&cli_app()))
+        } else {
+            None
+        };
+    if let Some(result) = description {
+        drop(matches);
+        match result {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1)
+            }
+        }
+    }
+
+    macro_rules! run_with_spec {
+        ($env_builder: expr) => {
+            run($env_builder, &matches)
+        };
+    }
+
+    let restart_on_fatal_error = matches.is_present("restart-on-fatal-error");
+
+    let result = clap_utils::parse_required(&matches, "max-restarts").and_then(|max_restarts| {
+        let mut restarts: usize = 0;
+
+        loop {
+            let result = match matches.value_of("spec") {
+                Some("minimal") => run_with_spec!(EnvironmentBuilder::minimal()),
+                Some("mainnet") => run_with_spec!(EnvironmentBuilder::mainnet()),
+                Some("interop") => run_with_spec!(EnvironmentBuilder::interop()),
+                spec => {
+                    // This path should be unreachable due to slog having a `default_value`
+                    unreachable!("Unknown spec configuration: {:?}", spec);
+                }
+            };
+
+            match result {
+                Ok(Some(reason)) if restart_on_fatal_error && restarts < max_restarts => {
+                    restarts += 1;
+                    eprintln!(
+                        "Restarting after fatal error ({}/{}): {}",
+                        restarts, max_restarts, reason
+                    );
+                }
+                Ok(Some(reason)) => return Err(format!("Fatal error: {}", reason)),
+                Ok(None) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    });
+
+    // `std::process::exit` does not run destructors so we drop manually.
+    drop(matches);
+
+    // Return the appropriate error code.
+    match result {
+        Ok(()) => exit(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            drop(e);
+            exit(1)
+        }
+    }
+}
+
+/// Returns the definition of every subcommand and flag.
+fn cli_app() -> App<'static, 'static> {
+    let network_names = hardcoded_net_names();
+
+    App::new("Lighthouse")
         .version(crate_version!())
         .author("Sigma Prime <contact@sigmaprime.io>")
         .about(
@@ -88,57 +168,111 @@ fn main() {
                 .takes_value(true)
                 .global(true),
         )
-        .subcommand(beacon_node::cli_app())
+        .arg(
+            Arg::with_name("network")
+                .long("network")
+                .value_name("NETWORK")
+                .help(
+                    "Name of an Eth2 network included in this binary, which sets the spec \
+                      config, genesis state, boot nodes and deposit contract. Defaults to the \
+                      hard-coded Lighthouse testnet. Only effective if there is no existing \
+                      database. Only the networks which follow the spec of this release are \
+                      included, use --testnet-dir for any other network.",
+                )
+                .possible_values(&network_names)
+                .conflicts_with("testnet-dir")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("genesis-delay")
+                .long("genesis-delay")
+                .value_name("SECONDS")
+                .help(
+                    "Overrides the MIN_GENESIS_DELAY of the testnet. Set to 0 for devnets which \
+                      should start as soon as enough validators have deposited. All nodes on the \
+                      network must use the same value.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("min-genesis-time")
+                .long("min-genesis-time")
+                .value_name("UNIX_SECONDS")
+                .help(
+                    "Overrides the MIN_GENESIS_TIME of the testnet. All nodes on the network must \
+                      use the same value.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("genesis-fork-version")
+                .long("genesis-fork-version")
+                .value_name("HEX")
+                .help(
+                    "Overrides the GENESIS_FORK_VERSION of the testnet, as 4 hex-encoded bytes \
+                      (e.g., 0x01020304). All nodes on the network must use the same value.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("restart-on-fatal-error")
+                .long("restart-on-fatal-error")
+                .help(
+                    "Restart all services after a fatal internal error (e.g., a panic or a \
+                      corrupt database), rather than exiting. A crash report is written to \
+                      <datadir>/crash_reports regardless.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("max-restarts")
+                .long("max-restarts")
+                .value_name("COUNT")
+                .help(
+                    "The number of times to restart after a fatal internal error before exiting. \
+                      Only effective with --restart-on-fatal-error.",
+                )
+                .takes_value(true)
+                .default_value("3"),
+        )
+        // A validator client given after the beacon node's flags runs in the same process.
+        .subcommand(beacon_node::cli_app().subcommand(validator_client::cli_app()))
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
-        .get_matches();
-
-    macro_rules! run_with_spec {
-        ($env_builder: expr) => {
-            run($env_builder, &matches)
-        };
-    }
-
-    let result = match matches.value_of("spec") {
-        Some("minimal") => run_with_spec!(EnvironmentBuilder::minimal()),
-        Some("mainnet") => run_with_spec!(EnvironmentBuilder::mainnet()),
-        Some("interop") => run_with_spec!(EnvironmentBuilder::interop()),
-        spec => {
-            // This path should be unreachable due to slog having a `default_value`
-            unreachable!("Unknown spec configuration: {:?}", spec);
-        }
-    };
-
-    // `std::process::exit` does not run destructors so we drop manually.
-    drop(matches);
-
-    // Return the appropriate error code.
-    match result {
-        Ok(()) => exit(0),
-        Err(e) => {
-            eprintln!("{}", e);
-            drop(e);
-            exit(1)
-        }
-    }
+        .subcommand(database_manager::cli_app())
+        .subcommand(cli_schema::completions_cli_app())
+        .subcommand(cli_schema::schema_cli_app())
 }
 
+/// Runs the services specified by `matches` until they are interrupted or suffer a fatal internal
+/// error, returning the reason for the latter.
 fn run<E: EthSpec>(
     environment_builder: EnvironmentBuilder<E>,
     matches: &ArgMatches,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     let debug_level = matches
         .value_of("debug-level")
         .ok_or_else(|| "Expected --debug-level flag".to_string())?;
 
     let log_format = matches.value_of("log-format");
     let eth2_testnet_config =
-        clap_utils::parse_testnet_dir_with_hardcoded_default(matches, "testnet-dir")?;
+        clap_utils::parse_testnet_config_with_hardcoded_default(matches, "network", "testnet-dir")?;
+
+    let genesis_overrides = GenesisOverrides {
+        genesis_delay: clap_utils::parse_optional(matches, "genesis-delay")?,
+        min_genesis_time: clap_utils::parse_optional(matches, "min-genesis-time")?,
+        genesis_fork_version: clap_utils::parse_ssz_optional(matches, "genesis-fork-version")?,
+    };
 
     let mut environment = environment_builder
         .async_logger(debug_level, log_format)?
         .multi_threaded_tokio_runtime()?
         .eth2_testnet_config(eth2_testnet_config)?
+        .genesis_overrides(&genesis_overrides)
         .build()?;
 
     let log = environment.core_context().log;
@@ -159,28 +293,39 @@ fn run<E: EthSpec>(
         return Err("Invalid CPU architecture".into());
     }
 
-    // Note: the current code technically allows for starting a beacon node _and_ a validator
-    // client at the same time.
-    //
-    // Whilst this is possible, the mutual-exclusivity of `clap` sub-commands prevents it from
-    // actually happening.
-    //
-    // Creating a command which can run both might be useful future works.
-
     if let Some(sub_matches) = matches.subcommand_matches("account_manager") {
         // Pass the entire `environment` to the account manager so it can run blocking operations.
         account_manager::run(sub_matches, environment)?;
 
         // Exit as soon as account manager returns control.
-        return Ok(());
+        return Ok(None);
     };
 
+    if let Some(sub_matches) = matches.subcommand_matches(database_manager::CMD) {
+        database_manager::run(sub_matches, environment)?;
+
+        return Ok(None);
+    };
+
+    let crash_report_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        "datadir",
+        PathBuf::new().join(DEFAULT_DATA_DIR),
+    )?
+    .join("crash_reports");
+
     warn!(
         log,
         "Ethereum 2.0 is pre-release. This software is experimental."
     );
 
-    if !matches.is_present("testnet-dir") {
+    if let Some(network) = matches.value_of("network") {
+        info!(
+            log,
+            "Using network";
+            "network" => network
+        )
+    } else if !matches.is_present("testnet-dir") {
         info!(
             log,
             "Using default testnet";
@@ -191,6 +336,14 @@ fn run<E: EthSpec>(
     let beacon_node = if let Some(sub_matches) = matches.subcommand_matches("beacon_node") {
         let runtime_context = environment.core_context();
 
+        if sub_matches.is_present("dump-config") {
+            let config =
+                ProductionBeaconNode::effective_config_from_cli(&runtime_context, sub_matches)?;
+            println!("{}", config);
+
+            return Ok(None);
+        }
+
         let beacon = environment
             .runtime()
             .block_on(ProductionBeaconNode::new_from_cli(
@@ -199,41 +352,99 @@ fn run<E: EthSpec>(
             ))
             .map_err(|e| format!("Failed to start beacon node: {}", e))?;
 
+        if let Some(beacon_chain) = beacon.beacon_chain() {
+            // Hold a weak reference so the crash report does not prevent the chain from dropping.
+            let beacon_chain = Arc::downgrade(&beacon_chain);
+            environment.add_crash_report_section(
+                "beacon chain head".to_string(),
+                Box::new(move || match beacon_chain.upgrade() {
+                    Some(beacon_chain) => beacon_chain
+                        .head_info()
+                        .map(|head| format!("{:?}", head))
+                        .unwrap_or_else(|e| format!("Unable to read head: {:?}", e)),
+                    None => "Beacon chain has shut down".to_string(),
+                }),
+            );
+        }
+
         Some(beacon)
     } else {
         None
     };
 
-    let validator_client = if let Some(sub_matches) = matches.subcommand_matches("validator_client")
-    {
-        let runtime_context = environment.core_context();
+    // A validator client is either run alone, or in the same process as the beacon node (e.g.,
+    // `lighthouse bn --http vc --auto-register`), in which case it uses the beacon node's API
+    // over a channel rather than HTTP.
+    let in_process_validator_client = matches
+        .subcommand_matches("beacon_node")
+        .and_then(|sub_matches| sub_matches.subcommand_matches("validator_client"));
 
-        let mut validator = environment
-            .runtime()
-            .block_on(ProductionValidatorClient::new_from_cli(
-                runtime_context,
-                sub_matches,
-            ))
-            .map_err(|e| format!("Failed to init validator client: {}", e))?;
+    let mut validator_client =
+        if let Some(sub_matches) = matches.subcommand_matches("validator_client") {
+            let runtime_context = environment.core_context();
+
+            let validator = environment
+                .runtime()
+                .block_on(ProductionValidatorClient::new_from_cli(
+                    runtime_context,
+                    sub_matches,
+                ))
+                .map_err(|e| format!("Failed to init validator client: {}", e))?;
+
+            Some(validator)
+        } else if let Some(sub_matches) = in_process_validator_client {
+            let runtime_context = environment.core_context();
+            let local_api = beacon_node
+                .as_ref()
+                .and_then(|beacon| beacon.local_api())
+                .ok_or_else(|| "The beacon node is not serving its API in-process".to_string())?;
 
+            let validator = environment
+                .runtime()
+                .block_on(ProductionValidatorClient::new_in_process_from_cli(
+                    runtime_context,
+                    sub_matches,
+                    local_api,
+                ))
+                .map_err(|e| format!("Failed to init validator client: {}", e))?;
+
+            Some(validator)
+        } else {
+            None
+        };
+
+    if let Some(validator) = validator_client.as_mut() {
         environment.core_context().runtime_handle.enter(|| {
             validator
                 .start_service()
                 .map_err(|e| format!("Failed to start validator client service: {}", e))
         })?;
-
-        Some(validator)
-    } else {
-        None
-    };
+    }
 
     if beacon_node.is_none() && validator_client.is_none() {
         crit!(log, "No subcommand supplied. See --help .");
         return Err("No subcommand supplied.".into());
     }
 
-    // Block this thread until Crtl+C is pressed.
-    environment.block_until_ctrl_c()?;
+    // Block this thread until Crtl+C is pressed or a service suffers a fatal error.
+    let fatal_error = match environment.block_until_shutdown_requested()? {
+        ShutdownReason::Interrupted => None,
+        ShutdownReason::Fatal(reason) => {
+            match environment.write_crash_report(&crash_report_dir, &reason) {
+                Ok(path) => crit!(
+                    log,
+                    "Wrote crash report";
+                    "path" => format!("{:?}", path)
+                ),
+                Err(e) => crit!(
+                    log,
+                    "Unable to write crash report";
+                    "error" => e
+                ),
+            }
+            Some(reason)
+        }
+    };
 
     info!(log, "Shutting down..");
 
@@ -241,5 +452,7 @@ fn run<E: EthSpec>(
     drop(validator_client);
 
     // Shutdown the environment once all tasks have completed.
-    Ok(environment.shutdown_on_idle())
+    environment.shutdown_on_idle();
+
+    Ok(fatal_error)
 }
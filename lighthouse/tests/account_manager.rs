@@ -2,7 +2,13 @@
 
 use account_manager::{
     upgrade_legacy_keypairs::{CMD as UPGRADE_CMD, *},
-    validator::{create::*, CMD as VALIDATOR_CMD},
+    validator::{
+        create::*,
+        deposit::{
+            CMD as DEPOSIT_CMD, ETH1_DEPOSIT_TX_JSON_FILE, UNSIGNED_TX_FLAG, VALIDATOR_FLAG,
+        },
+        CMD as VALIDATOR_CMD,
+    },
     wallet::{
         create::{CMD as CREATE_CMD, *},
         list::CMD as LIST_CMD,
@@ -19,9 +25,9 @@ use tempfile::{tempdir, TempDir};
 use types::Keypair;
 use validator_dir::ValidatorDir;
 
-// TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
-// access to an IPC endpoint during testing or adding support for deposit submission via HTTP and
-// using ganache-cli.
+// TODO: create tests for submitting deposits with the `lighthouse account validator deposit`
+// command. This involves getting access to an IPC endpoint during testing or adding support for
+// deposit submission via HTTP and using ganache-cli.
 
 /// Returns the `lighthouse account` command.
 fn account_cmd() -> Command {
@@ -418,3 +424,41 @@ fn upgrade_legacy_keypairs() {
         );
     }
 }
+
+#[test]
+fn validator_deposit_unsigned_tx() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let validator = TestValidator::new(validator_dir.path(), secrets_dir.path(), wallet);
+    let dirs = validator.create_expect_success(COUNT_FLAG, 2, false);
+
+    output_result(
+        validator_cmd()
+            .arg(DEPOSIT_CMD)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_FLAG))
+            .arg("all")
+            .arg(format!("--{}", UNSIGNED_TX_FLAG)),
+    )
+    .unwrap();
+
+    for dir in dirs {
+        let json = fs::read_to_string(dir.dir().join(ETH1_DEPOSIT_TX_JSON_FILE))
+            .expect("should save unsigned tx");
+        let tx: serde_json::Value = serde_json::from_str(&json).expect("should be valid json");
+
+        let rlp = dir.eth1_deposit_data().unwrap().unwrap().rlp;
+        assert_eq!(tx["data"], format!("0x{}", hex::encode(rlp)));
+        assert_eq!(tx["value"], "0x1bc16d674ec800000");
+        assert_eq!(tx["chainId"], 5);
+
+        // An unsigned transaction does not mean the deposit has been submitted.
+        assert!(!dir.eth1_deposit_tx_hash_exists());
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(not(debug_assertions))]
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::Command;
+use std::str::from_utf8;
+
+/// Runs `lighthouse` with `args`, returning its output if it succeeds.
+fn lighthouse(args: &[&str]) -> String {
+    let path = env!("CARGO_BIN_EXE_lighthouse")
+        .parse::<PathBuf>()
+        .expect("should parse CARGO_BIN_EXE_lighthouse");
+    let output = Command::new(path)
+        .args(args)
+        .output()
+        .expect("should run command");
+    assert!(
+        output.status.success(),
+        "command failed: {}",
+        from_utf8(&output.stderr).unwrap()
+    );
+    from_utf8(&output.stdout)
+        .expect("stdout is not utf8")
+        .to_string()
+}
+
+#[test]
+fn schema_describes_flags_and_defaults() {
+    let schema: Value = serde_json::from_str(&lighthouse(&["cli-schema"])).unwrap();
+
+    let find = |values: &Value, key: &str, name: &str| {
+        values[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|value| value["name"] == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("no {} named {}", key, name))
+    };
+
+    let spec = find(&schema, "args", "spec");
+    assert_eq!(spec["long"], "spec");
+    assert_eq!(spec["default_value"], "mainnet");
+    assert_eq!(spec["global"], true);
+    assert!(spec["possible_values"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("minimal")));
+
+    let beacon_node = find(&schema, "subcommands", "beacon_node");
+    let http = find(&beacon_node, "args", "http");
+    assert_eq!(http["takes_value"], false);
+    assert_eq!(http["default_value"], Value::Null);
+
+    // The validator client which runs in the beacon node's process is included.
+    find(&beacon_node, "subcommands", "validator_client");
+}
+
+#[test]
+fn completions_for_each_shell() {
+    for shell in &["bash", "zsh", "fish"] {
+        let script = lighthouse(&["completions", "--shell", shell]);
+        assert!(script.contains("beacon_node"), "{} completions", shell);
+    }
+}
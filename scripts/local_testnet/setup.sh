@@ -13,9 +13,10 @@ lcli \
 	--deposit-contract-address 0000000000000000000000000000000000000000 \
 	--testnet-dir $TESTNET_DIR \
 	--min-genesis-active-validator-count $VALIDATOR_COUNT \
+	--genesis-validator-count $VALIDATOR_COUNT \
 	--force
 
-echo Specification generated at $TESTNET_DIR.
+echo Specification and genesis state generated at $TESTNET_DIR.
 echo "Generating $VALIDATOR_COUNT validators concurrently... (this may take a while)"
 
 lcli \
@@ -25,12 +26,3 @@ lcli \
 	--secrets-dir $SECRETS_DIR
 
 echo Validators generated at $VALIDATORS_DIR with keystore passwords at $SECRETS_DIR.
-echo "Building genesis state... (this might take a while)"
-
-lcli \
-	--spec mainnet \
-	interop-genesis \
-	--testnet-dir $TESTNET_DIR \
-	$VALIDATOR_COUNT
-
-echo Created genesis state in $TESTNET_DIR
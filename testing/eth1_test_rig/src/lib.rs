@@ -1,11 +1,13 @@
 //! Provides utilities for deploying and manipulating the eth2 deposit contract on the eth1 chain.
 //!
 //! Presently used with [`ganache-cli`](https://github.com/trufflesuite/ganache-cli) to simulate
-//! the deposit contract for testing beacon node eth1 integration.
+//! the deposit contract for testing beacon node eth1 integration. `MockEth1Instance` provides an
+//! in-process alternative which does not require `ganache-cli`.
 //!
 //! Not tested to work with actual clients (e.g., geth). It should work fine, however there may be
 //! some initial issues.
 mod ganache;
+mod mock;
 
 use deposit_contract::{
     encode_eth1_tx_data, testnet, ABI, BYTECODE, CONTRACT_DEPLOY_GAS, DEPOSIT_GAS,
@@ -21,6 +23,8 @@ use web3::transports::Http;
 use web3::types::{Address, TransactionRequest, U256};
 use web3::Web3;
 
+pub use mock::{MockBlock, MockEth1Instance, MOCK_DEPOSIT_CONTRACT_ADDRESS};
+
 pub const DEPLOYER_ACCOUNTS_INDEX: usize = 0;
 pub const DEPOSIT_ACCOUNTS_INDEX: usize = 0;
 
@@ -0,0 +1,395 @@
+//! Provides an in-process eth1 node which serves the small subset of the JSON-RPC API used by the
+//! beacon node's eth1 service, so that tests need not start `ganache-cli`.
+//!
+//! The chain is controlled directly by the test: blocks are only produced by `mine` and
+//! `deposit`, and `reorg` replaces the most recent blocks. The deposit contract is emulated at
+//! `MOCK_DEPOSIT_CONTRACT_ADDRESS`.
+//!
+//! The supported methods are `eth_blockNumber`, `eth_getBlockByNumber`, `eth_getLogs` (deposit
+//! events only) and `eth_call` (`get_deposit_root()` and `get_deposit_count()` only).
+
+use futures::future::FutureExt;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use state_processing::common::DepositDataTree;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+use tree_hash::TreeHash;
+use types::{DepositData, Hash256, DEPOSIT_TREE_DEPTH};
+
+/// The address at which the deposit contract is emulated.
+pub const MOCK_DEPOSIT_CONTRACT_ADDRESS: &str = "0x8c5fecdc472e27bc447696f431e425d02dd46a8c";
+
+/// `keccak("DepositEvent(bytes,bytes,bytes,bytes,bytes)")`
+const DEPOSIT_EVENT_TOPIC: &str =
+    "0x649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5";
+/// `keccak("get_deposit_root()")[0..4]`
+const DEPOSIT_ROOT_FN_SIGNATURE: &str = "0xc5f2892f";
+/// `keccak("get_deposit_count()")[0..4]`
+const DEPOSIT_COUNT_FN_SIGNATURE: &str = "0x621fd130";
+
+/// The JSON-RPC error code used for every failed request.
+const SERVER_ERROR_CODE: i64 = -32000;
+
+/// A block of the mock chain.
+#[derive(Debug, Clone)]
+pub struct MockBlock {
+    pub number: u64,
+    pub hash: Hash256,
+    pub timestamp: u64,
+    /// The deposits included in this block, in order.
+    pub deposits: Vec<DepositData>,
+    /// The deposit contract root after this block.
+    pub deposit_root: Hash256,
+    /// The number of deposits in the deposit contract after this block.
+    pub deposit_count: u64,
+}
+
+/// The state of the mock chain, which is shared with the server.
+struct MockChain {
+    blocks: Vec<MockBlock>,
+    /// The number of blocks ever mined, used to give blocks which replace others in a reorg a
+    /// new hash.
+    mined: u64,
+}
+
+impl MockChain {
+    fn new() -> Self {
+        let mut chain = Self {
+            blocks: vec![],
+            mined: 0,
+        };
+        chain.mine(vec![]);
+        chain
+    }
+
+    fn head(&self) -> &MockBlock {
+        self.blocks
+            .last()
+            .expect("the mock chain always has a genesis block")
+    }
+
+    /// Appends a block containing `deposits` to the chain.
+    fn mine(&mut self, deposits: Vec<DepositData>) -> &MockBlock {
+        let (number, timestamp) = match self.blocks.last() {
+            Some(parent) => (parent.number + 1, std::cmp::max(now(), parent.timestamp)),
+            None => (0, now()),
+        };
+        let mut leaves = self.deposit_leaves();
+        leaves.extend(deposits.iter().map(|deposit| deposit.tree_hash_root()));
+
+        self.mined += 1;
+        self.blocks.push(MockBlock {
+            number,
+            hash: Hash256::from_low_u64_be(self.mined),
+            timestamp,
+            deposit_root: DepositDataTree::create(&leaves, leaves.len(), DEPOSIT_TREE_DEPTH).root(),
+            deposit_count: leaves.len() as u64,
+            deposits,
+        });
+        self.head()
+    }
+
+    /// Returns the deposit tree leaves of every deposit in the chain.
+    fn deposit_leaves(&self) -> Vec<Hash256> {
+        self.blocks
+            .iter()
+            .flat_map(|block| {
+                block
+                    .deposits
+                    .iter()
+                    .map(|deposit| deposit.tree_hash_root())
+            })
+            .collect()
+    }
+
+    fn block(&self, number: u64) -> Option<&MockBlock> {
+        self.blocks.get(number as usize)
+    }
+
+    /// Returns the result of the JSON-RPC `method`, or an error message.
+    fn respond(&self, method: &str, params: &Value) -> Result<Value, String> {
+        match method {
+            "eth_blockNumber" => Ok(json!(hex_u64(self.head().number))),
+            "eth_getBlockByNumber" => {
+                let number = self.block_number_param(param(params, 0)?)?;
+                Ok(self.block(number).map_or(Value::Null, |block| {
+                    json!({
+                        "number": hex_u64(block.number),
+                        "hash": hex_hash(block.hash),
+                        "timestamp": hex_u64(block.timestamp),
+                    })
+                }))
+            }
+            "eth_call" => {
+                let call = param(params, 0)?;
+                let block = self.existing_block(param(params, 1)?)?;
+
+                // A call to an address without code returns no bytes.
+                if !is_deposit_contract(call.get("to")) {
+                    return Ok(json!("0x"));
+                }
+
+                match call.get("data").and_then(Value::as_str) {
+                    Some(DEPOSIT_ROOT_FN_SIGNATURE) => Ok(json!(hex_hash(block.deposit_root))),
+                    Some(DEPOSIT_COUNT_FN_SIGNATURE) => Ok(json!(format!(
+                        "0x{}",
+                        hex::encode(abi_encode_bytes(&[&block.deposit_count.to_le_bytes()]))
+                    ))),
+                    other => Err(format!("Unsupported call data: {:?}", other)),
+                }
+            }
+            "eth_getLogs" => {
+                let filter = param(params, 0)?;
+                if !is_deposit_contract(filter.get("address")) {
+                    return Ok(json!([]));
+                }
+
+                let range_param = |key: &str| {
+                    filter
+                        .get(key)
+                        .ok_or_else(|| format!("Missing {}", key))
+                        .and_then(|value| self.block_number_param(value))
+                };
+                let from = range_param("fromBlock")?;
+                let to = range_param("toBlock")?;
+
+                let mut index = self
+                    .blocks
+                    .iter()
+                    .take_while(|block| block.number < from)
+                    .map(|block| block.deposits.len() as u64)
+                    .sum::<u64>();
+
+                let logs = self
+                    .blocks
+                    .iter()
+                    .filter(|block| block.number >= from && block.number <= to)
+                    .flat_map(|block| block.deposits.iter().map(move |deposit| (block, deposit)))
+                    .map(|(block, deposit)| {
+                        let data = deposit_log_data(deposit, index);
+                        index += 1;
+                        json!({
+                            "address": MOCK_DEPOSIT_CONTRACT_ADDRESS,
+                            "blockNumber": hex_u64(block.number),
+                            "blockHash": hex_hash(block.hash),
+                            "topics": [DEPOSIT_EVENT_TOPIC],
+                            "data": format!("0x{}", hex::encode(data)),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(json!(logs))
+            }
+            other => Err(format!("Unsupported method: {}", other)),
+        }
+    }
+
+    /// Parses a block number parameter, which is either `0x`-prefixed hex or `"latest"`.
+    fn block_number_param(&self, value: &Value) -> Result<u64, String> {
+        match value.as_str() {
+            Some("latest") => Ok(self.head().number),
+            Some(hex) if hex.starts_with("0x") => u64::from_str_radix(&hex[2..], 16)
+                .map_err(|e| format!("Invalid block number {}: {:?}", hex, e)),
+            _ => Err(format!("Invalid block number: {}", value)),
+        }
+    }
+
+    fn existing_block(&self, value: &Value) -> Result<&MockBlock, String> {
+        let number = self.block_number_param(value)?;
+        self.block(number)
+            .ok_or_else(|| format!("Unknown block: {}", number))
+    }
+}
+
+/// An in-process eth1 node with an emulated deposit contract.
+///
+/// The server is stopped when this is dropped.
+pub struct MockEth1Instance {
+    chain: Arc<RwLock<MockChain>>,
+    listen_addr: SocketAddr,
+    _exit: oneshot::Sender<()>,
+}
+
+impl MockEth1Instance {
+    /// Starts a server on an unused localhost port, with a chain containing only a genesis block
+    /// and no deposits.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn new() -> Result<Self, String> {
+        let chain = Arc::new(RwLock::new(MockChain::new()));
+
+        let inner_chain = chain.clone();
+        let make_service = make_service_fn(move |_| {
+            let chain = inner_chain.clone();
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |req| handle_request(req, chain.clone())))
+            }
+        });
+
+        let server = Server::try_bind(&([127, 0, 0, 1], 0).into())
+            .map_err(|e| format!("Unable to bind mock eth1 server: {:?}", e))?
+            .serve(make_service);
+        let listen_addr = server.local_addr();
+
+        let (exit, exit_rx) = oneshot::channel::<()>();
+        tokio::spawn(
+            server
+                .with_graceful_shutdown(async {
+                    let _ = exit_rx.await;
+                })
+                .map(|_| ()),
+        );
+
+        Ok(Self {
+            chain,
+            listen_addr,
+            _exit: exit,
+        })
+    }
+
+    /// The HTTP JSON-RPC endpoint, e.g., `http://127.0.0.1:8545`.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.listen_addr)
+    }
+
+    /// The address of the emulated deposit contract, as expected by the eth1 service config.
+    pub fn deposit_contract_address(&self) -> String {
+        MOCK_DEPOSIT_CONTRACT_ADDRESS.to_string()
+    }
+
+    /// Returns the number of the head block.
+    pub fn block_number(&self) -> u64 {
+        self.chain.read().head().number
+    }
+
+    /// Returns the block with the given `number`, if it is in the chain.
+    pub fn block(&self, number: u64) -> Option<MockBlock> {
+        self.chain.read().block(number).cloned()
+    }
+
+    /// Appends an empty block to the chain, returning it.
+    pub fn mine(&self) -> MockBlock {
+        self.chain.write().mine(vec![]).clone()
+    }
+
+    /// Appends a block containing each of `deposits` to the chain, returning it.
+    pub fn deposit(&self, deposits: Vec<DepositData>) -> MockBlock {
+        self.chain.write().mine(deposits).clone()
+    }
+
+    /// Replaces the `depth` most recent blocks (and the deposits they contain) with `depth` empty
+    /// blocks with different hashes. The genesis block is never replaced.
+    pub fn reorg(&self, depth: u64) {
+        let mut chain = self.chain.write();
+        let depth = std::cmp::min(depth as usize, chain.blocks.len() - 1);
+        let common_ancestor = chain.blocks.len() - depth;
+
+        chain.blocks.truncate(common_ancestor);
+        for _ in 0..depth {
+            chain.mine(vec![]);
+        }
+    }
+}
+
+/// Answers a single JSON-RPC request.
+async fn handle_request(
+    req: Request<Body>,
+    chain: Arc<RwLock<MockChain>>,
+) -> Result<Response<Body>, hyper::Error> {
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(request) => {
+            let id = request.get("id").cloned().unwrap_or(Value::Null);
+            let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+            let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+            match chain.read().respond(method, &params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(message) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": SERVER_ERROR_CODE, "message": message }
+                }),
+            }
+        }
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": Value::Null,
+            "error": { "code": SERVER_ERROR_CODE, "message": format!("Invalid request: {}", e) }
+        }),
+    };
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(response.to_string()))
+        .expect("response should be valid"))
+}
+
+fn param(params: &Value, index: usize) -> Result<&Value, String> {
+    params
+        .get(index)
+        .ok_or_else(|| format!("Missing parameter {}", index))
+}
+
+fn is_deposit_contract(address: Option<&Value>) -> bool {
+    address.and_then(Value::as_str).map_or(false, |address| {
+        address.eq_ignore_ascii_case(MOCK_DEPOSIT_CONTRACT_ADDRESS)
+    })
+}
+
+fn hex_u64(n: u64) -> String {
+    format!("0x{:x}", n)
+}
+
+fn hex_hash(hash: Hash256) -> String {
+    format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Returns the data of the `DepositEvent` emitted by the deposit contract for `deposit`, the
+/// `index`'th deposit.
+fn deposit_log_data(deposit: &DepositData, index: u64) -> Vec<u8> {
+    abi_encode_bytes(&[
+        deposit.pubkey.as_slice(),
+        deposit.withdrawal_credentials.as_bytes(),
+        &deposit.amount.to_le_bytes(),
+        deposit.signature.as_slice(),
+        &index.to_le_bytes(),
+    ])
+}
+
+/// ABI-encodes `values` as a tuple of `bytes`, i.e., a head of offsets followed by the length and
+/// zero-padded contents of each value.
+fn abi_encode_bytes(values: &[&[u8]]) -> Vec<u8> {
+    let word = |n: usize| {
+        let mut word = [0; 32];
+        word[24..].copy_from_slice(&(n as u64).to_be_bytes());
+        word
+    };
+    let padded_len = |len: usize| (len + 31) / 32 * 32;
+
+    let mut head = vec![];
+    let mut tail = vec![];
+    for value in values {
+        head.extend_from_slice(&word(values.len() * 32 + tail.len()));
+        tail.extend_from_slice(&word(value.len()));
+        tail.extend_from_slice(value);
+        tail.resize(tail.len() + padded_len(value.len()) - value.len(), 0);
+    }
+
+    head.extend_from_slice(&tail);
+    head
+}
@@ -14,6 +14,11 @@ use std::sync::Arc;
 use tokio::time::{delay_until, interval_at, Duration, Instant};
 use types::{Attestation, ChainSpec, CommitteeIndex, EthSpec, Slot};
 
+/// The default fraction of the way through each slot at which attestations are produced.
+pub const DEFAULT_ATTESTATION_SLOT_FRACTION: f64 = 1.0 / 3.0;
+/// The default fraction of the way through each slot at which aggregates are published.
+pub const DEFAULT_AGGREGATE_SLOT_FRACTION: f64 = 2.0 / 3.0;
+
 /// Builds an `AttestationService`.
 pub struct AttestationServiceBuilder<T, E: EthSpec> {
     duties_service: Option<DutiesService<T, E>>,
@@ -21,6 +26,8 @@ pub struct AttestationServiceBuilder<T, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_node: Option<RemoteBeaconNode<E>>,
     context: Option<RuntimeContext<E>>,
+    attestation_slot_fraction: f64,
+    aggregate_slot_fraction: f64,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -31,6 +38,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_node: None,
             context: None,
+            attestation_slot_fraction: DEFAULT_ATTESTATION_SLOT_FRACTION,
+            aggregate_slot_fraction: DEFAULT_AGGREGATE_SLOT_FRACTION,
         }
     }
 
@@ -59,6 +68,14 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    /// Sets the fractions of the way through each slot at which attestations are produced and
+    /// aggregates are published. The caller must ensure `0 < attestation < aggregate < 1`.
+    pub fn slot_fractions(mut self, attestation: f64, aggregate: f64) -> Self {
+        self.attestation_slot_fraction = attestation;
+        self.aggregate_slot_fraction = aggregate;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
         Ok(AttestationService {
             inner: Arc::new(Inner {
@@ -77,6 +94,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build AttestationService without runtime_context")?,
+                attestation_slot_fraction: self.attestation_slot_fraction,
+                aggregate_slot_fraction: self.aggregate_slot_fraction,
             }),
         })
     }
@@ -89,9 +108,12 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_node: RemoteBeaconNode<E>,
     context: RuntimeContext<E>,
+    attestation_slot_fraction: f64,
+    aggregate_slot_fraction: f64,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators part of the way through each slot
+/// (1/3rd by default).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -130,13 +152,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         info!(
             log,
             "Attestation production service started";
-            "next_update_millis" => duration_to_next_slot.as_millis()
+            "next_update_millis" => duration_to_next_slot.as_millis(),
+            "attestation_slot_fraction" => self.attestation_slot_fraction,
+            "aggregate_slot_fraction" => self.aggregate_slot_fraction
         );
 
         let mut interval = {
             // Note: `interval_at` panics if `slot_duration` is 0
             interval_at(
-                Instant::now() + duration_to_next_slot + slot_duration / 3,
+                Instant::now()
+                    + duration_to_next_slot
+                    + slot_duration.mul_f64(self.attestation_slot_fraction),
                 slot_duration,
             )
         };
@@ -185,11 +211,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .duration_to_next_slot()
             .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
 
-        // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
+        // If a validator needs to publish an aggregate attestation, they must do so part of the
+        // way through the slot (2/3 by default). This delay triggers at this time
         let aggregate_production_instant = Instant::now()
             + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
+                .checked_sub(slot_duration.mul_f64(1.0 - self.aggregate_slot_fraction))
                 .unwrap_or_else(|| Duration::from_secs(0));
 
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
@@ -413,6 +439,16 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         "slot" => slot.as_u64(),
                         "type" => "unaggregated",
                     ),
+                    PublishStatus::Rejected(rejection) => crit!(
+                        log,
+                        "Beacon node rejected attestation";
+                        "reason" => rejection.reason,
+                        "index" => format!("{:?}", rejection.index),
+                        "message" => rejection.message,
+                        "committee_index" => committee_index,
+                        "slot" => slot.as_u64(),
+                        "type" => "unaggregated",
+                    ),
                     PublishStatus::Unknown => {
                         crit!(log, "Unknown condition when publishing unagg. attestation")
                     }
@@ -528,6 +564,16 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     "slot" => attestation.data.slot.as_u64(),
                     "type" => "aggregated",
                 ),
+                PublishStatus::Rejected(rejection) => crit!(
+                    log,
+                    "Beacon node rejected attestation";
+                    "reason" => rejection.reason,
+                    "index" => format!("{:?}", rejection.index),
+                    "message" => rejection.message,
+                    "committee_index" => attestation.data.index,
+                    "slot" => attestation.data.slot.as_u64(),
+                    "type" => "aggregated",
+                ),
                 PublishStatus::Unknown => {
                     crit!(log, "Unknown condition when publishing agg. attestation")
                 }
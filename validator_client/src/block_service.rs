@@ -1,9 +1,11 @@
-use crate::{duties_service::DutiesService, validator_store::ValidatorStore};
+use crate::{
+    duties_service::DutiesService, graffiti_file::GraffitiFile, validator_store::ValidatorStore,
+};
 use environment::RuntimeContext;
 use exit_future::Signal;
 use futures::{FutureExt, StreamExt, TryFutureExt};
 use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
-use slog::{crit, error, info, trace};
+use slog::{crit, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -20,6 +22,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     slot_clock: Option<Arc<T>>,
     beacon_node: Option<RemoteBeaconNode<E>>,
     context: Option<RuntimeContext<E>>,
+    graffiti_file: Option<GraffitiFile>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -30,6 +33,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             slot_clock: None,
             beacon_node: None,
             context: None,
+            graffiti_file: None,
         }
     }
 
@@ -58,6 +62,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    /// Sets the file from which the graffiti of each proposal is read, if any.
+    pub fn graffiti_file(mut self, graffiti_file: Option<GraffitiFile>) -> Self {
+        self.graffiti_file = graffiti_file;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -76,6 +86,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build BlockService without runtime_context")?,
+                graffiti_file: self.graffiti_file,
             }),
         })
     }
@@ -88,6 +99,7 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: Arc<T>,
     beacon_node: RemoteBeaconNode<E>,
     context: RuntimeContext<E>,
+    graffiti_file: Option<GraffitiFile>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -220,11 +232,26 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .randao_reveal(&validator_pubkey, slot.epoch(E::slots_per_epoch()))
             .ok_or_else(|| "Unable to produce randao reveal".to_string())?;
 
+        // A problem with the graffiti file should not prevent the block from being proposed.
+        let graffiti = self.graffiti_file.as_ref().and_then(|graffiti_file| {
+            graffiti_file
+                .next_graffiti(&validator_pubkey)
+                .unwrap_or_else(|e| {
+                    warn!(
+                        log,
+                        "Unable to read graffiti file";
+                        "msg" => "using the default graffiti of the beacon node",
+                        "error" => e
+                    );
+                    None
+                })
+        });
+
         let block = self
             .beacon_node
             .http
             .validator()
-            .produce_block(slot, randao_reveal)
+            .produce_block(slot, randao_reveal, graffiti)
             .await
             .map_err(|e| format!("Error from beacon node when producing block: {:?}", e))?;
 
@@ -255,6 +282,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 "message" => msg,
                 "slot" => signed_block.slot().as_u64(),
             ),
+            PublishStatus::Rejected(rejection) => crit!(
+                log,
+                "Beacon node rejected block";
+                "reason" => rejection.reason,
+                "message" => rejection.message,
+                "slot" => signed_block.slot().as_u64(),
+            ),
             PublishStatus::Unknown => crit!(log, "Unknown condition when publishing block"),
         }
 
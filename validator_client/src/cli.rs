@@ -16,6 +16,29 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value(&DEFAULT_HTTP_SERVER)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("server-auth-token")
+                .long("server-auth-token")
+                .value_name("TOKEN_PATH")
+                .help(
+                    "Path to the file containing the token with which to authenticate to the \
+                    BeaconNode. Required if the BeaconNode was started with --http-require-auth, \
+                    in which case the token is in the api-token.txt file of its datadir.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("extra-validators-dirs")
+                .long("extra-validators-dirs")
+                .value_name("DIRECTORIES")
+                .help(
+                    "A comma-separated list of directories from which validators are loaded, in \
+                    addition to those in the datadir (e.g., a directory of imported keys on a \
+                    mounted volume). Their passwords must be in the --secrets-dir. The slashing \
+                    protection database remains in the datadir.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("secrets-dir")
                 .long("secrets-dir")
@@ -44,4 +67,72 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                       node is not synced.",
                 ),
         )
+        .arg(
+            Arg::with_name("slashing-protection-backups")
+                .long("slashing-protection-backups")
+                .value_name("COUNT")
+                .help(
+                    "The number of backups of the slashing protection database to keep. A \
+                    backup is created whenever the validator client starts with a different \
+                    version to its last run. Set to 0 to disable backups. Defaults to 5.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("attestation-slot-fraction")
+                .long("attestation-slot-fraction")
+                .value_name("FRACTION")
+                .help(
+                    "The fraction of the way through each slot at which attestations are \
+                    produced. Delaying attestation slightly may help validators on high-latency \
+                    links to observe the block of the slot first. Defaults to 0.333.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("aggregate-slot-fraction")
+                .long("aggregate-slot-fraction")
+                .value_name("FRACTION")
+                .help(
+                    "The fraction of the way through each slot at which aggregate attestations \
+                    are published. Must be greater than --attestation-slot-fraction. Defaults \
+                    to 0.667.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("graffiti-file")
+                .long("graffiti-file")
+                .value_name("FILE")
+                .help(
+                    "A file containing the graffiti to include in proposed blocks, one per line. \
+                    The file is re-read before each proposal, so it may be changed without a \
+                    restart. A line of the form `strategy: random` chooses a random graffiti for \
+                    each proposal, rather than using each in turn. A line of the form \
+                    `<0x-prefixed voting public key>: <graffiti>` gives a graffiti used only by \
+                    that validator. Lines starting with `#` are ignored.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("proposer-duties-file")
+                .long("proposer-duties-file")
+                .value_name("FILE")
+                .help(
+                    "A file to which the upcoming block proposals of the validators are written \
+                    whenever they change (e.g., once the duties of the next epoch are known), to \
+                    help schedule maintenance between them. The file is written in iCalendar \
+                    format if its name ends with `.ics`, or as CSV otherwise.",
+                )
+                .takes_value(true),
+        )
+        .arg(Arg::with_name("distributed").long("distributed").help(
+            "If present, the validator client will apply additional safeguards for \
+                    setups where several validator clients share the same keys (e.g., \
+                    active-passive failover). An existing slashing protection database is \
+                    required, --auto-register is not permitted and no messages will be signed \
+                    until the beacon node has been watched for a full epoch without observing \
+                    any attestations from these validators. Only one instance should ever be \
+                    active at a time.",
+        ))
 }
@@ -1,3 +1,7 @@
+use crate::attestation_service::{
+    DEFAULT_AGGREGATE_SLOT_FRACTION, DEFAULT_ATTESTATION_SLOT_FRACTION,
+};
+use crate::slashing_protection_backup::DEFAULT_BACKUP_RETENTION;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_path_with_default_in_home_dir};
 use serde_derive::{Deserialize, Serialize};
@@ -14,17 +18,37 @@ pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
 pub struct Config {
     /// The data directory, which stores all validator databases
     pub data_dir: PathBuf,
+    /// Directories, in addition to `data_dir`, which contain validators to be loaded at startup
+    /// (e.g., a directory of imported keys on a mounted volume).
+    pub extra_validators_dirs: Vec<PathBuf>,
     /// The directory containing the passwords to unlock validator keystores.
     pub secrets_dir: PathBuf,
     /// The http endpoint of the beacon node API.
     ///
     /// Should be similar to `http://localhost:8080`
     pub http_server: String,
+    /// The path to a file containing the token which authenticates this validator client to the
+    /// beacon node, if it requires one.
+    pub http_auth_token_path: Option<PathBuf>,
     /// If true, the validator client will still poll for duties and produce blocks even if the
     /// beacon node is not synced at startup.
     pub allow_unsynced_beacon_node: bool,
     /// If true, register new validator keys with the slashing protection database.
     pub auto_register: bool,
+    /// The number of slashing protection database backups to retain. A backup is made whenever
+    /// the version of the validator client changes. Zero disables backups.
+    pub slashing_protection_backups: usize,
+    /// If true, the validator client is one of several instances sharing the same keys (e.g., an
+    /// active-passive setup) and must apply additional safeguards before signing.
+    pub distributed: bool,
+    /// The fraction of the way through each slot at which attestations are produced.
+    pub attestation_slot_fraction: f64,
+    /// The fraction of the way through each slot at which aggregates are published.
+    pub aggregate_slot_fraction: f64,
+    /// A file from which the graffiti of each block proposal is read.
+    pub graffiti_file: Option<PathBuf>,
+    /// A file to which the upcoming block proposals of the validators are exported.
+    pub proposer_duties_file: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -38,10 +62,18 @@ impl Default for Config {
             .unwrap_or_else(|| PathBuf::from("."));
         Self {
             data_dir,
+            extra_validators_dirs: vec![],
             secrets_dir,
             http_server: DEFAULT_HTTP_SERVER.to_string(),
+            http_auth_token_path: None,
             allow_unsynced_beacon_node: false,
             auto_register: false,
+            slashing_protection_backups: DEFAULT_BACKUP_RETENTION,
+            distributed: false,
+            attestation_slot_fraction: DEFAULT_ATTESTATION_SLOT_FRACTION,
+            aggregate_slot_fraction: DEFAULT_AGGREGATE_SLOT_FRACTION,
+            graffiti_file: None,
+            proposer_duties_file: None,
         }
     }
 }
@@ -65,13 +97,82 @@ impl Config {
             ));
         }
 
+        if let Some(dirs) = cli_args.value_of("extra-validators-dirs") {
+            for dir in dirs.split(',').map(PathBuf::from) {
+                if !dir.exists() {
+                    return Err(format!(
+                        "The validators directory (--extra-validators-dirs) does not exist: {:?}",
+                        dir
+                    ));
+                }
+                if config.validators_dirs().any(|existing| *existing == dir) {
+                    return Err(format!(
+                        "The validators directory {:?} is given more than once",
+                        dir
+                    ));
+                }
+                config.extra_validators_dirs.push(dir);
+            }
+        }
+
         if let Some(server) = parse_optional(cli_args, "server")? {
             config.http_server = server;
         }
 
+        config.http_auth_token_path = parse_optional(cli_args, "server-auth-token")?;
+
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
         config.auto_register = cli_args.is_present("auto-register");
 
+        if let Some(backups) = parse_optional(cli_args, "slashing-protection-backups")? {
+            config.slashing_protection_backups = backups;
+        }
+
+        config.distributed = cli_args.is_present("distributed");
+
+        if config.distributed {
+            // Registering keys is how the same key ends up in the slashing protection database of
+            // multiple instances, each unaware of the others' messages.
+            if config.auto_register {
+                return Err(
+                    "The --auto-register flag cannot be used with --distributed. Import the \
+                     slashing protection database of the previous instance instead."
+                        .to_string(),
+                );
+            }
+
+            let slashing_db_path = config.data_dir.join(SLASHING_PROTECTION_FILENAME);
+            if !slashing_db_path.exists() {
+                return Err(format!(
+                    "A slashing protection database is required with --distributed, but none \
+                     exists at {:?}",
+                    slashing_db_path
+                ));
+            }
+        }
+
+        if let Some(fraction) = parse_optional(cli_args, "attestation-slot-fraction")? {
+            config.attestation_slot_fraction = fraction;
+        }
+
+        if let Some(fraction) = parse_optional(cli_args, "aggregate-slot-fraction")? {
+            config.aggregate_slot_fraction = fraction;
+        }
+
+        if !(0.0 < config.attestation_slot_fraction
+            && config.attestation_slot_fraction < config.aggregate_slot_fraction
+            && config.aggregate_slot_fraction < 1.0)
+        {
+            return Err(format!(
+                "--attestation-slot-fraction ({}) must be greater than 0 and less than \
+                 --aggregate-slot-fraction ({}), which must be less than 1",
+                config.attestation_slot_fraction, config.aggregate_slot_fraction
+            ));
+        }
+
+        config.graffiti_file = parse_optional(cli_args, "graffiti-file")?;
+        config.proposer_duties_file = parse_optional(cli_args, "proposer-duties-file")?;
+
         if let Some(secrets_dir) = parse_optional(cli_args, "secrets-dir")? {
             config.secrets_dir = secrets_dir;
         }
@@ -85,4 +186,10 @@ impl Config {
 
         Ok(config)
     }
+
+    /// Returns each directory from which validators are loaded: the `data_dir`, followed by the
+    /// `extra_validators_dirs`.
+    pub fn validators_dirs(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.data_dir).chain(self.extra_validators_dirs.iter())
+    }
 }
@@ -0,0 +1,120 @@
+//! Provides the "doppelganger" check which is mandatory when the validator client is run in
+//! distributed mode (i.e., `--distributed`).
+//!
+//! Before any messages are signed, the validator client watches the chain (via the beacon node)
+//! for attestations from its own validators. Since this validator client has not signed anything
+//! yet, any such attestation must have come from another instance using the same keys. Starting
+//! this instance as well would very likely lead to a slashing, so it refuses to start.
+//!
+//! The check is a heuristic: an attestation that is not included in the chain before the end of
+//! the detection period will not be detected.
+
+use remote_beacon_node::RemoteBeaconNode;
+use slog::{info, Logger};
+use slot_clock::SlotClock;
+use tokio::time::delay_for;
+use types::{Epoch, EthSpec, PublicKey, PublicKeyBytes};
+
+/// The number of full epochs of attestations to observe before signing any messages.
+///
+/// The epoch in which the validator client starts is not observed, since it may contain
+/// attestations from a previous run of this same validator client.
+pub const DOPPELGANGER_DETECTION_EPOCHS: u64 = 1;
+
+/// Waits until `DOPPELGANGER_DETECTION_EPOCHS` full epochs have passed, returning an error if
+/// any of the `voting_pubkeys` have attested during that time.
+///
+/// Also returns an error if the beacon node cannot be queried, since it is not safe to sign
+/// messages without completing the check.
+pub async fn wait_for_doppelgangers<T: SlotClock, E: EthSpec>(
+    beacon_node: &RemoteBeaconNode<E>,
+    slot_clock: &T,
+    voting_pubkeys: Vec<PublicKey>,
+    log: &Logger,
+) -> Result<(), String> {
+    let pubkeys = voting_pubkeys
+        .into_iter()
+        .map(PublicKeyBytes::from)
+        .collect::<Vec<_>>();
+
+    let start_epoch = if let Some(slot) = slot_clock.now() {
+        slot.epoch(E::slots_per_epoch())
+    } else {
+        // Nobody can have attested prior to genesis.
+        info!(log, "Skipping doppelganger detection prior to genesis");
+        return Ok(());
+    };
+    let end_epoch = start_epoch + DOPPELGANGER_DETECTION_EPOCHS + 1;
+
+    info!(
+        log,
+        "Waiting to detect other instances of these validators";
+        "start_epoch" => start_epoch,
+        "signing_epoch" => end_epoch,
+    );
+
+    loop {
+        let duration = slot_clock
+            .duration_to_next_epoch(E::slots_per_epoch())
+            .ok_or_else(|| "Unable to determine duration to next epoch".to_string())?;
+        delay_for(duration).await;
+
+        let current_epoch = slot_clock
+            .now()
+            .ok_or_else(|| "Unable to read slot clock".to_string())?
+            .epoch(E::slots_per_epoch());
+
+        // Check the epoch which has just completed, as well as the one prior.
+        let completed_epoch = current_epoch.saturating_sub(1u64);
+        let active_elsewhere =
+            active_pubkeys(beacon_node, completed_epoch, start_epoch, pubkeys.clone()).await?;
+
+        if !active_elsewhere.is_empty() {
+            return Err(format!(
+                "Refusing to start since the following validators are active elsewhere: {:?}. \
+                 Ensure no other validator client is using these keys before retrying.",
+                active_elsewhere
+            ));
+        }
+
+        if current_epoch >= end_epoch {
+            info!(
+                log,
+                "No other instances of these validators detected";
+                "epoch" => current_epoch,
+            );
+            return Ok(());
+        }
+    }
+}
+
+/// Returns the `pubkeys` with attestations included in the chain in `epoch` or the epoch prior,
+/// ignoring attestations in epochs which are not after `start_epoch`.
+async fn active_pubkeys<E: EthSpec>(
+    beacon_node: &RemoteBeaconNode<E>,
+    epoch: Epoch,
+    start_epoch: Epoch,
+    pubkeys: Vec<PublicKeyBytes>,
+) -> Result<Vec<PublicKeyBytes>, String> {
+    if epoch <= start_epoch {
+        return Ok(vec![]);
+    }
+
+    let votes = beacon_node
+        .http
+        .consensus()
+        .get_individual_votes(epoch, pubkeys)
+        .await
+        .map_err(|e| format!("Unable to check for other instances of validators: {:?}", e))?;
+
+    Ok(votes
+        .into_iter()
+        .filter(|response| {
+            response.vote.as_ref().map_or(false, |vote| {
+                vote.is_current_epoch_attester
+                    || (epoch - 1 > start_epoch && vote.is_previous_epoch_attester)
+            })
+        })
+        .map(|response| response.pubkey)
+        .collect())
+}
@@ -0,0 +1,247 @@
+//! Exports the upcoming block proposals of this validator client's validators to a file, so that
+//! operators can schedule maintenance (e.g., restarts or upgrades) around them.
+//!
+//! The file is re-written whenever the known proposals change, e.g., once the duties of the next
+//! epoch are known. It is written as an iCalendar (RFC 5545) file if its name ends with `.ics`,
+//! which most calendar applications can subscribe to, or as CSV otherwise.
+
+use chrono::{TimeZone, Utc};
+use parking_lot::Mutex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{Epoch, PublicKey, Slot};
+
+/// The maximum length of a line in an iCalendar file, in octets (excluding the line break).
+const ICS_MAX_LINE_LEN: usize = 75;
+
+/// The format of the exported file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Ics,
+}
+
+impl ExportFormat {
+    /// Chooses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("ics") => ExportFormat::Ics,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+/// An upcoming block proposal by one of our validators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposalDuty {
+    pub slot: Slot,
+    pub epoch: Epoch,
+    pub validator_pubkey: PublicKey,
+    /// The duration between the UNIX epoch and the start of `self.slot`.
+    pub start: Duration,
+}
+
+/// Writes upcoming proposals to a file.
+pub struct DutiesExport {
+    path: PathBuf,
+    format: ExportFormat,
+    slot_duration: Duration,
+    /// The proposals last written, to avoid re-writing an unchanged file.
+    written: Mutex<Option<Vec<ProposalDuty>>>,
+}
+
+impl DutiesExport {
+    pub fn new(path: PathBuf, slot_duration: Duration) -> Self {
+        Self {
+            format: ExportFormat::from_path(&path),
+            path,
+            slot_duration,
+            written: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes `proposals` (which are sorted by slot) to the file, unless they are the same as
+    /// those last written.
+    ///
+    /// The file is replaced atomically, so a reader never observes a partially written file.
+    pub fn write(&self, mut proposals: Vec<ProposalDuty>) -> Result<(), String> {
+        proposals.sort_by_key(|proposal| proposal.slot);
+
+        let mut written = self.written.lock();
+        if written.as_ref() == Some(&proposals) {
+            return Ok(());
+        }
+
+        let contents = match self.format {
+            ExportFormat::Csv => to_csv(&proposals),
+            ExportFormat::Ics => to_ics(&proposals, self.slot_duration),
+        };
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|()| fs::rename(&temp_path, &self.path))
+            .map_err(|e| format!("Unable to write {:?}: {:?}", self.path, e))?;
+
+        *written = Some(proposals);
+        Ok(())
+    }
+}
+
+/// Formats `time` (since the UNIX epoch) as an RFC 3339 UTC timestamp.
+fn rfc3339(time: Duration) -> String {
+    Utc.timestamp(time.as_secs() as i64, 0)
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+/// Formats `time` (since the UNIX epoch) as an iCalendar UTC date-time.
+fn ics_date_time(time: Duration) -> String {
+    Utc.timestamp(time.as_secs() as i64, 0)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn to_csv(proposals: &[ProposalDuty]) -> String {
+    let mut csv = "slot,epoch,validator_pubkey,start_time\n".to_string();
+    for proposal in proposals {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            proposal.slot,
+            proposal.epoch,
+            proposal.validator_pubkey.as_hex_string(),
+            rfc3339(proposal.start)
+        ));
+    }
+    csv
+}
+
+fn to_ics(proposals: &[ProposalDuty], slot_duration: Duration) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Sigma Prime//Lighthouse Validator Client//EN".to_string(),
+    ];
+
+    for proposal in proposals {
+        let pubkey = proposal.validator_pubkey.as_hex_string();
+        lines.extend(vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:proposal-{}-{}@lighthouse", proposal.slot, pubkey),
+            // The event is determined by the slot alone, so it is "created" when the slot starts.
+            // Unlike the current time, this keeps the file unchanged between updates.
+            format!("DTSTAMP:{}", ics_date_time(proposal.start)),
+            format!("DTSTART:{}", ics_date_time(proposal.start)),
+            format!("DTEND:{}", ics_date_time(proposal.start + slot_duration)),
+            format!("SUMMARY:Block proposal at slot {}", proposal.slot),
+            format!(
+                "DESCRIPTION:Validator {} proposes the block at slot {} (epoch {}).",
+                pubkey, proposal.slot, proposal.epoch
+            ),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+/// Folds `line` into lines of at most `ICS_MAX_LINE_LEN` octets, each ending with CRLF and all
+/// but the first starting with a space, as required by RFC 5545.
+///
+/// The `line` must be ASCII, so that it may be split at any octet.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut rest = line;
+    let mut max_len = ICS_MAX_LINE_LEN;
+
+    while rest.len() > max_len {
+        let (head, tail) = rest.split_at(max_len);
+        folded.push_str(head);
+        folded.push_str("\r\n ");
+        rest = tail;
+        // Subsequent lines start with a space.
+        max_len = ICS_MAX_LINE_LEN - 1;
+    }
+
+    folded.push_str(rest);
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempdir::TempDir;
+    use types::Keypair;
+
+    fn proposal(slot: u64, validator_pubkey: &PublicKey) -> ProposalDuty {
+        ProposalDuty {
+            slot: Slot::new(slot),
+            epoch: Slot::new(slot).epoch(32),
+            validator_pubkey: validator_pubkey.clone(),
+            start: Duration::from_secs(1_600_000_000 + slot * 12),
+        }
+    }
+
+    #[test]
+    fn exports_csv() {
+        let dir = TempDir::new("duties_export").unwrap();
+        let path = dir.path().join("proposals.csv");
+        let export = DutiesExport::new(path.clone(), Duration::from_secs(12));
+        let pubkey = Keypair::random().pk;
+
+        export
+            .write(vec![proposal(70, &pubkey), proposal(33, &pubkey)])
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "slot,epoch,validator_pubkey,start_time\n\
+                 33,1,{0},2020-09-13T12:33:16Z\n\
+                 70,2,{0},2020-09-13T12:40:40Z\n",
+                pubkey.as_hex_string()
+            )
+        );
+
+        // Unchanged proposals are not re-written.
+        fs::remove_file(&path).unwrap();
+        export
+            .write(vec![proposal(33, &pubkey), proposal(70, &pubkey)])
+            .unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn exports_ics() {
+        let dir = TempDir::new("duties_export").unwrap();
+        let path = dir.path().join("proposals.ics");
+        let export = DutiesExport::new(path.clone(), Duration::from_secs(12));
+        let pubkey = Keypair::random().pk;
+
+        export.write(vec![proposal(33, &pubkey)]).unwrap();
+        let ics = fs::read_to_string(&path).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20200913T123316Z\r\n"));
+        assert!(ics.contains("\r\nDTEND:20200913T123328Z\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= ICS_MAX_LINE_LEN));
+        // Unfolding the lines restores the description.
+        assert!(ics.replace("\r\n ", "").contains(&format!(
+            "DESCRIPTION:Validator {} proposes the block at slot 33 (epoch 1).",
+            pubkey.as_hex_string()
+        )));
+    }
+}
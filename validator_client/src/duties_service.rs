@@ -1,18 +1,24 @@
-use crate::{is_synced::is_synced, validator_store::ValidatorStore};
+use crate::{
+    duties_export::{DutiesExport, ProposalDuty},
+    is_synced::is_synced,
+    validator_store::ValidatorStore,
+};
 use environment::RuntimeContext;
 use exit_future::Signal;
 use futures::{FutureExt, StreamExt};
 use parking_lot::RwLock;
 use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
-use rest_types::{ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription};
-use slog::{debug, error, info, trace, warn};
+use rest_types::{ValidatorDuty, ValidatorDutyBytes, ValidatorStatus, ValidatorSubscription};
+use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::ops::Deref;
 use std::sync::Arc;
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, CommitteeIndex, Epoch, EthSpec, PublicKey, SelectionProof, Slot};
+use types::{
+    ChainSpec, CommitteeIndex, Epoch, EthSpec, PublicKey, PublicKeyBytes, SelectionProof, Slot,
+};
 
 /// Delay this period of time after the slot starts. This allows the node to process the new slot.
 const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
@@ -22,6 +28,9 @@ const PRUNE_DEPTH: u64 = 4;
 
 type BaseHashMap = HashMap<PublicKey, HashMap<Epoch, DutyAndProof>>;
 
+/// Selection proofs keyed by the validator and the slot they were signed for.
+type SelectionProofCache = RwLock<HashMap<(PublicKey, Slot), SelectionProof>>;
+
 #[derive(Debug, Clone)]
 pub struct DutyAndProof {
     /// The validator duty.
@@ -35,6 +44,10 @@ impl DutyAndProof {
     /// storing it in `self.selection_proof` _if_ the validator is an aggregator. If the validator
     /// is not an aggregator, `self.selection_proof` is set to `None`.
     ///
+    /// The selection proof is only signed if it is not already in `selection_proofs`, so that
+    /// duties which change (e.g., due to a re-org) without changing slot do not require another
+    /// signature.
+    ///
     /// ## Errors
     ///
     /// - `self.validator_pubkey` is not known in `validator_store`.
@@ -42,6 +55,7 @@ impl DutyAndProof {
     pub fn compute_selection_proof<T: SlotClock + 'static, E: EthSpec>(
         &mut self,
         validator_store: &ValidatorStore<T, E>,
+        selection_proofs: &SelectionProofCache,
     ) -> Result<(), String> {
         let (modulo, slot) = if let (Some(modulo), Some(slot)) =
             (self.duty.aggregator_modulo, self.duty.attestation_slot)
@@ -54,9 +68,19 @@ impl DutyAndProof {
             return Ok(());
         };
 
-        let selection_proof = validator_store
-            .produce_selection_proof(&self.duty.validator_pubkey, slot)
-            .ok_or_else(|| "Failed to produce selection proof".to_string())?;
+        let key = (self.duty.validator_pubkey.clone(), slot);
+        let cached = selection_proofs.read().get(&key).cloned();
+        let selection_proof = if let Some(selection_proof) = cached {
+            selection_proof
+        } else {
+            let selection_proof = validator_store
+                .produce_selection_proof(&self.duty.validator_pubkey, slot)
+                .ok_or_else(|| "Failed to produce selection proof".to_string())?;
+            selection_proofs
+                .write()
+                .insert(key, selection_proof.clone());
+            selection_proof
+        };
 
         self.selection_proof = selection_proof
             .is_aggregator_from_modulo(modulo)
@@ -159,6 +183,7 @@ impl InsertOutcome {
 #[derive(Default)]
 pub struct DutiesStore {
     store: RwLock<BaseHashMap>,
+    selection_proofs: SelectionProofCache,
 }
 
 impl DutiesStore {
@@ -210,6 +235,24 @@ impl DutiesStore {
             .collect()
     }
 
+    /// Returns the slot and validator of each known block proposal at or after `slot`.
+    fn proposals_from(&self, slot: Slot) -> Vec<(Slot, PublicKey)> {
+        self.store
+            .read()
+            .iter()
+            .flat_map(|(validator_pubkey, validator_map)| {
+                validator_map.values().flat_map(move |duties| {
+                    duties
+                        .duty
+                        .block_proposal_slots
+                        .iter()
+                        .filter(move |proposal_slot| **proposal_slot >= slot)
+                        .map(move |proposal_slot| (*proposal_slot, validator_pubkey.clone()))
+                })
+            })
+            .collect()
+    }
+
     fn attesters(&self, slot: Slot, slots_per_epoch: u64) -> Vec<DutyAndProof> {
         self.store
             .read()
@@ -263,7 +306,7 @@ impl DutiesStore {
                     Ok(InsertOutcome::Identical)
                 } else {
                     // Compute the selection proof.
-                    duties.compute_selection_proof(validator_store)?;
+                    duties.compute_selection_proof(validator_store, &self.selection_proofs)?;
 
                     // Determine if a re-subscription is required.
                     let should_resubscribe = duties.subscription_eq(known_duties);
@@ -275,7 +318,7 @@ impl DutiesStore {
                 }
             } else {
                 // Compute the selection proof.
-                duties.compute_selection_proof(validator_store)?;
+                duties.compute_selection_proof(validator_store, &self.selection_proofs)?;
 
                 validator_map.insert(epoch, duties);
 
@@ -283,7 +326,7 @@ impl DutiesStore {
             }
         } else {
             // Compute the selection proof.
-            duties.compute_selection_proof(validator_store)?;
+            duties.compute_selection_proof(validator_store, &self.selection_proofs)?;
 
             let validator_pubkey = duties.duty.validator_pubkey.clone();
 
@@ -296,13 +339,21 @@ impl DutiesStore {
         }
     }
 
-    fn prune(&self, prior_to: Epoch) {
+    /// Removes all duties for the given validator.
+    fn remove_validator(&self, validator_pubkey: &PublicKey) {
+        self.store.write().remove(validator_pubkey);
+    }
+
+    fn prune(&self, prior_to: Epoch, slots_per_epoch: u64) {
         self.store
             .write()
             .retain(|_validator_pubkey, validator_map| {
                 validator_map.retain(|epoch, _duties| *epoch >= prior_to);
                 !validator_map.is_empty()
             });
+        self.selection_proofs
+            .write()
+            .retain(|(_validator_pubkey, slot), _proof| slot.epoch(slots_per_epoch) >= prior_to);
     }
 }
 
@@ -312,6 +363,7 @@ pub struct DutiesServiceBuilder<T, E: EthSpec> {
     beacon_node: Option<RemoteBeaconNode<E>>,
     context: Option<RuntimeContext<E>>,
     allow_unsynced_beacon_node: bool,
+    duties_export: Option<DutiesExport>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
@@ -322,6 +374,7 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
             beacon_node: None,
             context: None,
             allow_unsynced_beacon_node: false,
+            duties_export: None,
         }
     }
 
@@ -351,10 +404,18 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
         self
     }
 
+    /// Set a file to which upcoming block proposals are exported after each update.
+    pub fn duties_export(mut self, duties_export: Option<DutiesExport>) -> Self {
+        self.duties_export = duties_export;
+        self
+    }
+
     pub fn build(self) -> Result<DutiesService<T, E>, String> {
         Ok(DutiesService {
             inner: Arc::new(Inner {
                 store: Arc::new(DutiesStore::default()),
+                exited_or_slashed: RwLock::new(HashSet::new()),
+                statuses_checked_epoch: RwLock::new(None),
                 validator_store: self
                     .validator_store
                     .ok_or_else(|| "Cannot build DutiesService without validator_store")?,
@@ -368,6 +429,7 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
                     .context
                     .ok_or_else(|| "Cannot build DutiesService without runtime_context")?,
                 allow_unsynced_beacon_node: self.allow_unsynced_beacon_node,
+                duties_export: self.duties_export,
             }),
         })
     }
@@ -376,6 +438,11 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
 /// Helper to minimise `Arc` usage.
 pub struct Inner<T, E: EthSpec> {
     store: Arc<DutiesStore>,
+    /// Validators which the beacon node reports as exited or slashed. Duties are no longer
+    /// requested for these validators.
+    exited_or_slashed: RwLock<HashSet<PublicKey>>,
+    /// The epoch in which the statuses of the validators were last checked.
+    statuses_checked_epoch: RwLock<Option<Epoch>>,
     validator_store: ValidatorStore<T, E>,
     pub(crate) slot_clock: T,
     pub(crate) beacon_node: RemoteBeaconNode<E>,
@@ -383,6 +450,8 @@ pub struct Inner<T, E: EthSpec> {
     /// If true, the duties service will poll for duties from the beacon node even if it is not
     /// synced.
     allow_unsynced_beacon_node: bool,
+    /// A file to which upcoming block proposals are exported.
+    duties_export: Option<DutiesExport>,
 }
 
 /// Maintains a store of the duties for all voting validators in the `validator_store`.
@@ -510,12 +579,20 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                         "current_epoch" => epoch.as_u64(),
                     );
 
-                    self.store.prune(prune_below);
+                    self.store.prune(prune_below, E::slots_per_epoch());
                 }
 
                 epoch
             })?;
 
+        if let Err(e) = self.update_validator_statuses(current_epoch).await {
+            warn!(
+                log,
+                "Failed to check validator statuses";
+                "error" => e
+            );
+        }
+
         let result = self.clone().update_epoch(current_epoch).await;
         if let Err(e) = result {
             error!(
@@ -525,23 +602,170 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             );
         }
 
-        self.clone()
-            .update_epoch(current_epoch + 1)
+        let result = self.clone().update_epoch(current_epoch + 1).await;
+
+        // Export whichever duties are known, even if the next epoch's could not be fetched.
+        if let Err(e) = self.export_proposals() {
+            warn!(
+                log,
+                "Failed to export proposer duties";
+                "error" => e
+            );
+        }
+
+        result.map_err(move |e| {
+            error!(
+                log,
+                "Failed to get next epoch duties";
+                "http_error" => format!("{:?}", e)
+            );
+        })?;
+
+        Ok(())
+    }
+
+    /// Writes the known block proposals from the current slot onwards to the `duties_export`
+    /// file, if any.
+    fn export_proposals(&self) -> Result<(), String> {
+        let duties_export = match &self.duties_export {
+            Some(duties_export) => duties_export,
+            None => return Ok(()),
+        };
+        let now = self
+            .slot_clock
+            .now()
+            .ok_or_else(|| "Unable to read slot clock".to_string())?;
+
+        let proposals =
+            self.store
+                .proposals_from(now)
+                .into_iter()
+                .map(|(slot, validator_pubkey)| {
+                    Ok(ProposalDuty {
+                        slot,
+                        epoch: slot.epoch(E::slots_per_epoch()),
+                        validator_pubkey,
+                        start: self.slot_clock.start_of(slot).ok_or_else(|| {
+                            format!("Unable to compute the start of slot {}", slot)
+                        })?,
+                    })
+                })
+                .collect::<Result<_, String>>()?;
+
+        duties_export.write(proposals)
+    }
+
+    /// Queries the beacon node for the status of all managed validators, at most once per epoch,
+    /// and stops performing duties for any validator which has exited or been slashed.
+    async fn update_validator_statuses(&self, current_epoch: Epoch) -> Result<(), String> {
+        if *self.statuses_checked_epoch.read() == Some(current_epoch) {
+            return Ok(());
+        }
+
+        let pubkeys = self.active_pubkeys();
+        if pubkeys.is_empty() {
+            return Ok(());
+        }
+
+        let statuses = self
+            .beacon_node
+            .http
+            .beacon()
+            .get_validator_statuses(
+                "head",
+                pubkeys.iter().cloned().map(PublicKeyBytes::from).collect(),
+                vec![],
+            )
             .await
-            .map_err(move |e| {
-                error!(
-                    log,
-                    "Failed to get next epoch duties";
-                    "http_error" => format!("{:?}", e)
-                );
-            })?;
+            .map_err(|e| format!("Failed to get validator statuses: {:?}", e))?;
+
+        // The responses are returned in the same order as the requested pubkeys.
+        for (pubkey, response) in pubkeys.into_iter().zip(statuses.into_iter()) {
+            let slashed = response
+                .validator
+                .as_ref()
+                .map_or(false, |validator| validator.slashed);
+
+            if slashed {
+                self.lock_down_slashed_validator(&pubkey);
+            }
+
+            if response.status.is_exited_or_slashed() {
+                self.stop_duties(pubkey, response.status);
+            }
+        }
+
+        *self.statuses_checked_epoch.write() = Some(current_epoch);
 
         Ok(())
     }
 
+    /// Disables signing for a validator which has been slashed, since any signing from this point
+    /// will only compound the penalties (e.g., if another instance is using the same keys).
+    fn lock_down_slashed_validator(&self, validator_pubkey: &PublicKey) {
+        let log = &self.context.log;
+
+        match self
+            .validator_store
+            .disable_signing(validator_pubkey, "The validator has been slashed.")
+        {
+            Ok(true) => crit!(
+                log,
+                "Validator has been slashed";
+                "action" => "signing disabled, investigate before re-enabling",
+                "validator" => format!("{:?}", validator_pubkey),
+            ),
+            Ok(false) => (),
+            Err(e) => crit!(
+                log,
+                "Validator has been slashed";
+                "action" => "signing disabled until restart, unable to persist",
+                "error" => e,
+                "validator" => format!("{:?}", validator_pubkey),
+            ),
+        }
+    }
+
+    /// Stops requesting and performing duties for the given validator.
+    fn stop_duties(&self, validator_pubkey: PublicKey, status: ValidatorStatus) {
+        self.store.remove_validator(&validator_pubkey);
+
+        if self
+            .exited_or_slashed
+            .write()
+            .insert(validator_pubkey.clone())
+        {
+            warn!(
+                self.context.log,
+                "Validator is no longer performing duties";
+                "status" => format!("{:?}", status),
+                "validator" => format!("{:?}", validator_pubkey),
+            );
+        }
+    }
+
+    /// Returns the pubkeys of the managed validators which have not exited or been slashed, and
+    /// for which signing has not been disabled.
+    fn active_pubkeys(&self) -> Vec<PublicKey> {
+        let exited_or_slashed = self.exited_or_slashed.read();
+        self.validator_store
+            .voting_pubkeys()
+            .into_iter()
+            .filter(|pubkey| {
+                !exited_or_slashed.contains(pubkey)
+                    && !self.validator_store.is_signing_disabled(pubkey)
+            })
+            .collect()
+    }
+
     /// Attempt to download the duties of all managed validators for the given `epoch`.
+    ///
+    /// Validators which have exited or been slashed are ignored.
     async fn update_epoch(self, epoch: Epoch) -> Result<(), String> {
-        let pubkeys = self.validator_store.voting_pubkeys();
+        let pubkeys = self.active_pubkeys();
+        if pubkeys.is_empty() {
+            return Ok(());
+        }
         let all_duties = self
             .beacon_node
             .http
@@ -682,6 +906,12 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                             "Failed to subscribe validator";
                             "error" => e
                         ),
+                        PublishStatus::Rejected(rejection) => error!(
+                            log,
+                            "Failed to subscribe validator";
+                            "reason" => rejection.reason,
+                            "error" => rejection.message
+                        ),
                     };
                 })
         }
@@ -1,18 +1,53 @@
+use crate::slashing_protection_backup::current_version;
 use environment::RuntimeContext;
 use exit_future::Signal;
 use futures::{FutureExt, StreamExt};
 use parking_lot::RwLock;
 use remote_beacon_node::RemoteBeaconNode;
-use slog::{debug, info, trace};
+use slog::{crit, debug, info, trace, warn};
 use slot_clock::SlotClock;
 use std::ops::Deref;
 use std::sync::Arc;
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, EthSpec, Fork};
+use types::{ChainSpec, Epoch, EthSpec, Fork};
 
 /// Delay this period of time after the slot starts. This allows the node to process the new slot.
 const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(80);
 
+/// Whether this validator client supports a fork announced or reported by the beacon node.
+#[derive(Debug, PartialEq)]
+enum ForkSupport {
+    Supported,
+    /// The fork is unsupported and occurs after the current epoch.
+    UnsupportedUpcoming {
+        epochs_remaining: u64,
+    },
+    /// The fork is unsupported and has already occurred.
+    Unsupported,
+}
+
+impl ForkSupport {
+    /// Returns the support for `fork` at `current_epoch` on the network of `spec`, where
+    /// `current_epoch` is `None` before genesis.
+    ///
+    /// There are no forks after genesis, so only the genesis fork version is supported.
+    fn new(fork: &Fork, current_epoch: Option<Epoch>, spec: &ChainSpec) -> Self {
+        if fork.current_version == spec.genesis_fork_version {
+            return ForkSupport::Supported;
+        }
+
+        // Before genesis, every fork is upcoming.
+        let current_epoch = current_epoch.unwrap_or_else(|| Epoch::new(0));
+        if current_epoch < fork.epoch {
+            ForkSupport::UnsupportedUpcoming {
+                epochs_remaining: (fork.epoch - current_epoch).as_u64(),
+            }
+        } else {
+            ForkSupport::Unsupported
+        }
+    }
+}
+
 /// Builds a `ForkService`.
 pub struct ForkServiceBuilder<T, E: EthSpec> {
     fork: Option<Fork>,
@@ -46,6 +81,13 @@ impl<T: SlotClock + 'static, E: EthSpec> ForkServiceBuilder<T, E> {
         self
     }
 
+    /// Sets the fork known to the service before its first update.
+    #[cfg(test)]
+    pub fn fork(mut self, fork: Fork) -> Self {
+        self.fork = Some(fork);
+        self
+    }
+
     pub fn build(self) -> Result<ForkService<T, E>, String> {
         Ok(ForkService {
             inner: Arc::new(Inner {
@@ -146,6 +188,12 @@ impl<T: SlotClock + 'static, E: EthSpec> ForkService<T, E> {
     async fn do_update(self) -> Result<(), ()> {
         let log = &self.context.log;
 
+        // The fork schedule is part of the spec, which is obtained from the beacon node at startup.
+        let next_fork = &self.context.eth2_config.spec.next_fork;
+        if let Some(next_fork) = next_fork {
+            self.check_fork_supported(next_fork);
+        }
+
         let fork = self
             .inner
             .beacon_node
@@ -161,6 +209,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ForkService<T, E> {
                 )
             })?;
 
+        // Also check the fork of the head, in case the beacon node does not announce its schedule.
+        if next_fork
+            .as_ref()
+            .map(|next_fork| next_fork.current_version)
+            != Some(fork.current_version)
+        {
+            self.check_fork_supported(&fork);
+        }
+
         if self.fork.read().as_ref() != Some(&fork) {
             *(self.fork.write()) = Some(fork);
         }
@@ -171,4 +228,75 @@ impl<T: SlotClock + 'static, E: EthSpec> ForkService<T, E> {
         // should not stop all future attempts.
         Ok(())
     }
+
+    /// Alerts the operator if `fork` is to a version which this validator client does not
+    /// support, since its validators will stop following the chain once the fork occurs.
+    fn check_fork_supported(&self, fork: &Fork) {
+        let log = &self.context.log;
+        let current_epoch = self
+            .slot_clock
+            .now()
+            .map(|slot| slot.epoch(E::slots_per_epoch()));
+
+        match ForkSupport::new(fork, current_epoch, &self.context.eth2_config.spec) {
+            ForkSupport::Supported => {}
+            ForkSupport::UnsupportedUpcoming { epochs_remaining } => warn!(
+                log,
+                "Upcoming fork is not supported";
+                "msg" => "update the validator client before the fork epoch or its validators \
+                          will stop following the chain",
+                "epochs_remaining" => epochs_remaining,
+                "fork_epoch" => fork.epoch,
+                "fork_version" => format!("{:?}", fork.current_version),
+                "validator_client_version" => current_version()
+            ),
+            ForkSupport::Unsupported => crit!(
+                log,
+                "Current fork is not supported";
+                "msg" => "update the validator client, its validators are not following the chain",
+                "fork_epoch" => fork.epoch,
+                "fork_version" => format!("{:?}", fork.current_version),
+                "validator_client_version" => current_version()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    #[test]
+    fn detects_unsupported_forks() {
+        let spec = MainnetEthSpec::default_spec();
+        let fork = |version, epoch| Fork {
+            previous_version: spec.genesis_fork_version,
+            current_version: version,
+            epoch: Epoch::new(epoch),
+        };
+        let support = |fork, epoch| ForkSupport::new(&fork, Some(Epoch::new(epoch)), &spec);
+
+        assert_eq!(
+            support(fork(spec.genesis_fork_version, 0), 5),
+            ForkSupport::Supported
+        );
+        assert_eq!(
+            support(fork([9, 9, 9, 9], 10), 4),
+            ForkSupport::UnsupportedUpcoming {
+                epochs_remaining: 6
+            }
+        );
+        assert_eq!(
+            support(fork([9, 9, 9, 9], 10), 10),
+            ForkSupport::Unsupported
+        );
+        assert_eq!(
+            ForkSupport::new(&fork([9, 9, 9, 9], 10), None, &spec),
+            ForkSupport::UnsupportedUpcoming {
+                epochs_remaining: 10
+            },
+            "should treat a fork as upcoming before genesis"
+        );
+    }
 }
@@ -0,0 +1,260 @@
+//! Reads the graffiti of each block proposal from a file, so that operators can change their
+//! graffiti (or rotate through several) without restarting the validator client.
+//!
+//! The file is re-read before every proposal. Each line which is neither empty nor starts with `#`
+//! is one of:
+//!
+//! - `strategy: ordered` or `strategy: random`, which chooses how proposals rotate through the
+//!   graffiti. Proposals use each graffiti in turn by default.
+//! - `<voting public key>: <graffiti>`, where the public key is `0x`-prefixed hex, which adds a
+//!   graffiti used only by that validator.
+//! - `<graffiti>`, which adds a graffiti used by every validator without a graffiti of its own.
+//!
+//! Each graffiti may be at most 32 bytes long.
+//!
+//! If the file cannot be read or is invalid when a block is proposed, the block contains the
+//! default graffiti of the beacon node.
+
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use types::{utils::GRAFFITI_BYTES_LEN, PublicKey};
+
+/// The length of a `0x`-prefixed, hex-encoded voting public key.
+const PUBKEY_HEX_LEN: usize = 2 + 2 * 48;
+
+/// How proposals choose between several graffiti.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    /// Each proposal uses the graffiti after that of the previous proposal.
+    Ordered,
+    /// Each proposal uses a graffiti chosen at random.
+    Random,
+}
+
+/// The parsed contents of a graffiti file.
+#[derive(Debug, PartialEq)]
+pub struct GraffitiList {
+    pub strategy: Strategy,
+    /// The graffiti of validators without a graffiti of their own.
+    pub default: Vec<[u8; GRAFFITI_BYTES_LEN]>,
+    /// The graffiti of specific validators, keyed by the `0x`-prefixed hex of their public key.
+    pub validators: HashMap<String, Vec<[u8; GRAFFITI_BYTES_LEN]>>,
+}
+
+impl GraffitiList {
+    /// Parses the contents of a graffiti file.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut strategy = None;
+        let mut default = vec![];
+        let mut validators: HashMap<String, Vec<_>> = HashMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |e: String| format!("Line {}: {}", i + 1, e);
+
+            if let Some(value) = strip_key(line, "strategy") {
+                if strategy.is_some() {
+                    return Err(error("strategy is given more than once".to_string()));
+                }
+                strategy = Some(match value {
+                    "ordered" => Strategy::Ordered,
+                    "random" => Strategy::Random,
+                    other => return Err(error(format!("unknown strategy \"{}\"", other))),
+                });
+            } else if let Some((pubkey, graffiti)) = split_pubkey(line) {
+                validators
+                    .entry(pubkey.to_lowercase())
+                    .or_default()
+                    .push(graffiti_bytes(graffiti).map_err(error)?);
+            } else {
+                default.push(graffiti_bytes(line).map_err(error)?);
+            }
+        }
+
+        Ok(Self {
+            strategy: strategy.unwrap_or(Strategy::Ordered),
+            default,
+            validators,
+        })
+    }
+
+    /// Returns the graffiti which may be used by the validator with `pubkey_hex`.
+    fn graffiti_for(&self, pubkey_hex: &str) -> &[[u8; GRAFFITI_BYTES_LEN]] {
+        self.validators
+            .get(pubkey_hex)
+            .map_or(&self.default, |graffiti| graffiti)
+    }
+}
+
+/// Chooses the graffiti of each proposal from a graffiti file.
+pub struct GraffitiFile {
+    path: PathBuf,
+    /// The number of proposals which have used each validator's graffiti (keyed by public key) or
+    /// the default graffiti (keyed by `None`), for the ordered strategy.
+    proposals: Mutex<HashMap<Option<String>, usize>>,
+}
+
+impl GraffitiFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            proposals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads and parses the file.
+    pub fn read(&self) -> Result<GraffitiList, String> {
+        let contents = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", self.path, e))?;
+        GraffitiList::parse(&contents).map_err(|e| format!("Invalid {:?}: {}", self.path, e))
+    }
+
+    /// Re-reads the file and returns the graffiti of the next proposal by `pubkey`, or `None` if
+    /// the file has no graffiti for that validator.
+    pub fn next_graffiti(
+        &self,
+        pubkey: &PublicKey,
+    ) -> Result<Option<[u8; GRAFFITI_BYTES_LEN]>, String> {
+        let list = self.read()?;
+        Ok(self.choose(&list, &pubkey.as_hex_string()))
+    }
+
+    fn choose(&self, list: &GraffitiList, pubkey_hex: &str) -> Option<[u8; GRAFFITI_BYTES_LEN]> {
+        let graffiti = list.graffiti_for(pubkey_hex);
+        if graffiti.is_empty() {
+            return None;
+        }
+
+        let index = match list.strategy {
+            Strategy::Ordered => {
+                let key = if list.validators.contains_key(pubkey_hex) {
+                    Some(pubkey_hex.to_string())
+                } else {
+                    None
+                };
+                let mut proposals = self.proposals.lock();
+                let count = proposals.entry(key).or_insert(0);
+                let index = *count % graffiti.len();
+                *count += 1;
+                index
+            }
+            Strategy::Random => rand::thread_rng().gen_range(0, graffiti.len()),
+        };
+
+        Some(graffiti[index])
+    }
+}
+
+/// Returns the trimmed value of `line` if it is of the form `<key>: <value>`.
+fn strip_key<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let mut parts = line.splitn(2, ':');
+    if parts.next()?.trim() == key {
+        parts.next().map(str::trim)
+    } else {
+        None
+    }
+}
+
+/// Splits `line` into a public key and graffiti if it is of the form `<0x-prefixed pubkey>:
+/// <graffiti>`.
+fn split_pubkey(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.splitn(2, ':');
+    let pubkey = parts.next()?.trim();
+    let graffiti = parts.next()?.trim();
+
+    if pubkey.len() == PUBKEY_HEX_LEN
+        && pubkey.starts_with("0x")
+        && pubkey[2..].chars().all(|c| c.is_ascii_hexdigit())
+    {
+        Some((pubkey, graffiti))
+    } else {
+        None
+    }
+}
+
+/// Returns `graffiti` as a graffiti, padded with zeros.
+fn graffiti_bytes(graffiti: &str) -> Result<[u8; GRAFFITI_BYTES_LEN], String> {
+    let bytes = graffiti.as_bytes();
+    if bytes.len() > GRAFFITI_BYTES_LEN {
+        return Err(format!(
+            "graffiti \"{}\" is longer than {} bytes",
+            graffiti, GRAFFITI_BYTES_LEN
+        ));
+    }
+
+    let mut array = [0; GRAFFITI_BYTES_LEN];
+    array[..bytes.len()].copy_from_slice(bytes);
+    Ok(array)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempdir::TempDir;
+    use types::Keypair;
+
+    #[test]
+    fn parses_graffiti_file() {
+        let pubkey = Keypair::random().pk.as_hex_string();
+        let contents = format!(
+            "# A comment\n\nstrategy: random\nfirst\n{}: mine\nsecond: with a colon\n",
+            pubkey.to_uppercase().replacen("0X", "0x", 1)
+        );
+
+        let list = GraffitiList::parse(&contents).unwrap();
+        assert_eq!(list.strategy, Strategy::Random);
+        assert_eq!(
+            list.default,
+            vec![
+                graffiti_bytes("first").unwrap(),
+                graffiti_bytes("second: with a colon").unwrap()
+            ]
+        );
+        assert_eq!(
+            list.graffiti_for(&pubkey),
+            &[graffiti_bytes("mine").unwrap()]
+        );
+
+        assert!(GraffitiList::parse("strategy: sideways").is_err());
+        assert!(GraffitiList::parse("strategy: ordered\nstrategy: random").is_err());
+        assert!(GraffitiList::parse(&"a".repeat(GRAFFITI_BYTES_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn rotates_and_rereads_graffiti() {
+        let dir = TempDir::new("graffiti_file").unwrap();
+        let path = dir.path().join("graffiti.txt");
+        let file = GraffitiFile::new(path.clone());
+        let keypair = Keypair::random();
+        let other = Keypair::random();
+
+        fs::write(&path, format!("a\nb\n{}: mine\n", other.pk.as_hex_string())).unwrap();
+
+        let next = |pubkey: &PublicKey| file.next_graffiti(pubkey).unwrap();
+        assert_eq!(next(&keypair.pk), Some(graffiti_bytes("a").unwrap()));
+        assert_eq!(next(&other.pk), Some(graffiti_bytes("mine").unwrap()));
+        assert_eq!(next(&keypair.pk), Some(graffiti_bytes("b").unwrap()));
+        assert_eq!(next(&keypair.pk), Some(graffiti_bytes("a").unwrap()));
+
+        // Changes to the file apply to the next proposal.
+        fs::write(&path, "strategy: random\nc\n").unwrap();
+        assert_eq!(next(&other.pk), Some(graffiti_bytes("c").unwrap()));
+
+        fs::write(&path, "# No graffiti\n").unwrap();
+        assert_eq!(next(&keypair.pk), None);
+
+        fs::remove_file(&path).unwrap();
+        assert!(file.next_graffiti(&keypair.pk).is_err());
+    }
+}
@@ -2,10 +2,16 @@ mod attestation_service;
 mod block_service;
 mod cli;
 mod config;
+mod doppelganger;
+mod duties_export;
 mod duties_service;
 mod fork_service;
+mod graffiti_file;
 mod is_synced;
 mod notifier;
+mod randao_cache;
+mod sealed_key;
+mod slashing_protection_backup;
 mod validator_store;
 
 pub use cli::cli_app;
@@ -15,15 +21,18 @@ use attestation_service::{AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
 use config::SLASHING_PROTECTION_FILENAME;
+use duties_export::DutiesExport;
 use duties_service::{DutiesService, DutiesServiceBuilder};
 use environment::RuntimeContext;
 use exit_future::Signal;
 use fork_service::{ForkService, ForkServiceBuilder};
+use graffiti_file::GraffitiFile;
 use notifier::spawn_notifier;
-use remote_beacon_node::RemoteBeaconNode;
+use remote_beacon_node::{LocalRequestSender, RemoteBeaconNode};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
+use std::fs;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{delay_for, Duration};
 use types::EthSpec;
@@ -57,9 +66,52 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         Self::new(context, config).await
     }
 
+    /// Instantiates the validator client, using the beacon node in the same process which serves
+    /// `local_api` rather than the one at `--server`, _without_ starting the timers to trigger
+    /// block and attestation production.
+    pub async fn new_in_process_from_cli(
+        context: RuntimeContext<T>,
+        cli_args: &ArgMatches<'_>,
+        local_api: LocalRequestSender,
+    ) -> Result<Self, String> {
+        let config = Config::from_cli(&cli_args)
+            .map_err(|e| format!("Unable to initialize config: {}", e))?;
+        Self::new_with_beacon_node(
+            context,
+            config,
+            RemoteBeaconNode::new_local(local_api, HTTP_TIMEOUT),
+        )
+        .await
+    }
+
     /// Instantiates the validator client, _without_ starting the timers to trigger block
     /// and attestation production.
-    pub async fn new(mut context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+    pub async fn new(context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+        let auth_token = config
+            .http_auth_token_path
+            .as_ref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|e| format!("Unable to read --server-auth-token {:?}: {:?}", path, e))
+            })
+            .transpose()?;
+
+        let beacon_node = RemoteBeaconNode::new_with_auth_token(
+            config.http_server.clone(),
+            HTTP_TIMEOUT,
+            auth_token,
+        )
+        .map_err(|e| format!("Unable to init beacon node http client: {}", e))?;
+
+        Self::new_with_beacon_node(context, config, beacon_node).await
+    }
+
+    async fn new_with_beacon_node(
+        mut context: RuntimeContext<T>,
+        config: Config,
+        beacon_node: RemoteBeaconNode<T>,
+    ) -> Result<Self, String> {
         let log_1 = context.log.clone();
         let log_2 = context.log.clone();
         let log_3 = context.log.clone();
@@ -68,8 +120,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         info!(
             log_1,
             "Starting validator client";
-            "beacon_node" => &config.http_server,
+            "beacon_node" => beacon_node.http.endpoint(),
             "datadir" => format!("{:?}", config.data_dir),
+            "extra_validators_dirs" => format!("{:?}", config.extra_validators_dirs),
         );
 
         if !config.data_dir.join(SLASHING_PROTECTION_FILENAME).exists() && !config.auto_register {
@@ -80,9 +133,19 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             );
         }
 
-        let beacon_node =
-            RemoteBeaconNode::new_with_timeout(config.http_server.clone(), HTTP_TIMEOUT)
-                .map_err(|e| format!("Unable to init beacon node http client: {}", e))?;
+        // Backup the slashing protection database before this version has a chance to modify it.
+        if let Some(backup_path) = slashing_protection_backup::backup_on_version_change(
+            &config.data_dir,
+            slashing_protection_backup::current_version(),
+            config.slashing_protection_backups,
+        )? {
+            info!(
+                log_1,
+                "Backed up slashing protection database";
+                "path" => format!("{:?}", backup_path),
+                "version" => slashing_protection_backup::current_version(),
+            );
+        }
 
         // TODO: check if all logs in wait_for_node are produed while awaiting
         let beacon_node = wait_for_node(beacon_node, log_2).await?;
@@ -187,20 +250,51 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             info!(log, "Validator auto-registration complete");
         }
 
+        if config.distributed {
+            doppelganger::wait_for_doppelgangers(
+                &beacon_node,
+                &slot_clock,
+                validator_store.voting_pubkeys(),
+                &log,
+            )
+            .await?;
+        }
+
         let duties_service = DutiesServiceBuilder::new()
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
             .beacon_node(beacon_node.clone())
             .runtime_context(context.service_context("duties".into()))
             .allow_unsynced_beacon_node(config.allow_unsynced_beacon_node)
+            .duties_export(
+                config
+                    .proposer_duties_file
+                    .clone()
+                    .map(|path| DutiesExport::new(path, slot_clock.slot_duration())),
+            )
             .build()?;
 
+        // The graffiti file is re-read before each proposal, but an invalid file is most likely a
+        // mistake which should be reported now rather than at the first proposal.
+        let graffiti_file = config.graffiti_file.clone().map(GraffitiFile::new);
+        if let Some(graffiti_file) = &graffiti_file {
+            let graffiti = graffiti_file.read()?;
+            info!(
+                log,
+                "Loaded graffiti file";
+                "path" => format!("{:?}", graffiti_file.path()),
+                "default_graffiti" => graffiti.default.len(),
+                "validators_with_graffiti" => graffiti.validators.len()
+            );
+        }
+
         let block_service = BlockServiceBuilder::new()
             .duties_service(duties_service.clone())
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
             .beacon_node(beacon_node.clone())
             .runtime_context(context.service_context("block".into()))
+            .graffiti_file(graffiti_file)
             .build()?;
 
         let attestation_service = AttestationServiceBuilder::new()
@@ -209,6 +303,10 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_store(validator_store)
             .beacon_node(beacon_node)
             .runtime_context(context.service_context("attestation".into()))
+            .slot_fractions(
+                config.attestation_slot_fraction,
+                config.aggregate_slot_fraction,
+            )
             .build()?;
 
         Ok(Self {
@@ -0,0 +1,170 @@
+//! Persists the RANDAO reveal signed by each validator for each epoch, so that a proposal which is
+//! interrupted (e.g., by a crash or restart between producing and publishing its block) re-uses the
+//! reveal it already signed, rather than signing another.
+//!
+//! The file is tied to a single network by its genesis validators root; the reveals of any other
+//! network are discarded.
+
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use types::{Epoch, Hash256, PublicKey, Signature};
+
+/// The name of the file within the datadir.
+pub const RANDAO_CACHE_FILENAME: &str = "randao_reveals.json";
+
+/// The number of epochs prior to the latest reveal for which reveals are retained.
+const RETAINED_EPOCHS: u64 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    genesis_validators_root: Hash256,
+    reveals: Vec<CachedReveal>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedReveal {
+    validator_pubkey: PublicKey,
+    epoch: Epoch,
+    reveal: Signature,
+}
+
+/// The RANDAO reveals of recent epochs, which are written to a file whenever one is added.
+pub struct RandaoCache {
+    path: PathBuf,
+    genesis_validators_root: Hash256,
+    reveals: Mutex<HashMap<(PublicKey, Epoch), Signature>>,
+}
+
+impl RandaoCache {
+    /// Returns an empty cache, which is written to `path` once a reveal is added.
+    pub fn new(path: PathBuf, genesis_validators_root: Hash256) -> Self {
+        Self {
+            path,
+            genesis_validators_root,
+            reveals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads the cache at `path`, or returns an empty cache if there is no such file.
+    pub fn open(path: PathBuf, genesis_validators_root: Hash256) -> Result<Self, String> {
+        let cache = Self::new(path, genesis_validators_root);
+        if !cache.path.exists() {
+            return Ok(cache);
+        }
+
+        let bytes = fs::read(&cache.path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", cache.path, e))?;
+        let file: CacheFile = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Invalid {:?}: {:?}", cache.path, e))?;
+
+        if file.genesis_validators_root == genesis_validators_root {
+            *cache.reveals.lock() = file
+                .reveals
+                .into_iter()
+                .map(|cached| ((cached.validator_pubkey, cached.epoch), cached.reveal))
+                .collect();
+        }
+
+        Ok(cache)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reveal of `validator_pubkey` for `epoch`, if one has been added.
+    pub fn get(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<Signature> {
+        self.reveals
+            .lock()
+            .get(&(validator_pubkey.clone(), epoch))
+            .cloned()
+    }
+
+    /// Adds the reveal of `validator_pubkey` for `epoch`, forgetting those of epochs more than
+    /// `RETAINED_EPOCHS` before it, and replaces the file atomically.
+    ///
+    /// The reveal is kept in memory even if the file cannot be written.
+    pub fn insert(
+        &self,
+        validator_pubkey: PublicKey,
+        epoch: Epoch,
+        reveal: Signature,
+    ) -> Result<(), String> {
+        let mut reveals = self.reveals.lock();
+        reveals.insert((validator_pubkey, epoch), reveal);
+
+        let oldest = Epoch::new(epoch.as_u64().saturating_sub(RETAINED_EPOCHS));
+        reveals.retain(|(_, reveal_epoch), _| *reveal_epoch >= oldest);
+
+        let file = CacheFile {
+            genesis_validators_root: self.genesis_validators_root,
+            reveals: reveals
+                .iter()
+                .map(|((validator_pubkey, epoch), reveal)| CachedReveal {
+                    validator_pubkey: validator_pubkey.clone(),
+                    epoch: *epoch,
+                    reveal: reveal.clone(),
+                })
+                .collect(),
+        };
+        let contents = serde_json::to_vec(&file)
+            .map_err(|e| format!("Unable to encode RANDAO reveals: {:?}", e))?;
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|()| fs::rename(&temp_path, &self.path))
+            .map_err(|e| format!("Unable to write {:?}: {:?}", self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempdir::TempDir;
+    use types::Keypair;
+
+    #[test]
+    fn persists_recent_reveals() {
+        let dir = TempDir::new("randao_cache").unwrap();
+        let path = dir.path().join(RANDAO_CACHE_FILENAME);
+        let root = Hash256::repeat_byte(1);
+        let keypair = Keypair::random();
+        let reveal = |epoch: u64| Signature::new(&epoch.to_le_bytes(), &keypair.sk);
+
+        let cache = RandaoCache::open(path.clone(), root).unwrap();
+        assert_eq!(cache.get(&keypair.pk, Epoch::new(1)), None);
+        cache
+            .insert(keypair.pk.clone(), Epoch::new(1), reveal(1))
+            .unwrap();
+        cache
+            .insert(keypair.pk.clone(), Epoch::new(2), reveal(2))
+            .unwrap();
+
+        let reopened = RandaoCache::open(path.clone(), root).unwrap();
+        assert_eq!(reopened.get(&keypair.pk, Epoch::new(1)), Some(reveal(1)));
+        assert_eq!(reopened.get(&keypair.pk, Epoch::new(2)), Some(reveal(2)));
+
+        // Reveals of old epochs are forgotten.
+        reopened
+            .insert(
+                keypair.pk.clone(),
+                Epoch::new(1 + RETAINED_EPOCHS + 1),
+                reveal(4),
+            )
+            .unwrap();
+        let reopened = RandaoCache::open(path.clone(), root).unwrap();
+        assert_eq!(reopened.get(&keypair.pk, Epoch::new(1)), None);
+        assert_eq!(reopened.get(&keypair.pk, Epoch::new(2)), Some(reveal(2)));
+
+        // Reveals of another network are discarded.
+        let other = RandaoCache::open(path.clone(), Hash256::repeat_byte(2)).unwrap();
+        assert_eq!(other.get(&keypair.pk, Epoch::new(2)), None);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(RandaoCache::open(path, root).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! Keeps the voting secret keys of local validators encrypted whilst they are held in memory.
+//!
+//! Each secret key is encrypted with aes-128-ctr under an ephemeral key which is generated when
+//! the validator client starts and never leaves the process. A secret key is only decrypted for the
+//! duration of a single signing operation, so the process does not hold it in the clear between
+//! signing operations.
+//!
+//! The decrypted bytes are zeroized once the operation completes, however the decrypted
+//! `bls::SecretKey` is not (the underlying BLS library does not zeroize its secret keys on drop).
+//! Its memory is released without being cleared, so a memory dump or core file may still contain
+//! the secret keys of recent signing operations until that memory is reused.
+//!
+//! This is a defence against casual inspection, not against an attacker who is able to read the
+//! memory of the running process (and therefore the ephemeral key) at will.
+
+use bls::{PlainText, SecretKey};
+use crypto::aes::{ctr, KeySize};
+use rand::Rng;
+
+/// The length of the ephemeral key, as required by aes-128.
+const KEY_SIZE: usize = 16;
+/// The length of the aes-128-ctr initialization vector.
+const IV_SIZE: usize = 16;
+
+/// A random key, unique to this process, with which to seal secret keys.
+pub struct EphemeralKey(PlainText);
+
+impl EphemeralKey {
+    /// Generates a new key using `rand::thread_rng`.
+    pub fn random() -> Self {
+        Self(rand::thread_rng().gen::<[u8; KEY_SIZE]>().to_vec().into())
+    }
+}
+
+/// A `SecretKey` encrypted with an `EphemeralKey`.
+pub struct SealedSecretKey {
+    iv: [u8; IV_SIZE],
+    cipher_text: Vec<u8>,
+}
+
+impl SealedSecretKey {
+    /// Encrypts `secret_key` with `key`, using a new random initialization vector.
+    pub fn seal(secret_key: &SecretKey, key: &EphemeralKey) -> Self {
+        let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>();
+        let plain_text = secret_key.as_bytes();
+
+        let mut cipher_text = vec![0; plain_text.len()];
+        ctr(KeySize::KeySize128, key.0.as_bytes(), &iv)
+            .process(plain_text.as_bytes(), &mut cipher_text);
+
+        Self { iv, cipher_text }
+    }
+
+    /// Decrypts the secret key with `key` and calls `func` with it, dropping the decrypted key
+    /// before returning. The decrypted bytes are zeroized, the `SecretKey` is not.
+    ///
+    /// Returns `None` if the decrypted bytes are not a valid secret key.
+    pub fn with_secret_key<F, R>(&self, key: &EphemeralKey, func: F) -> Option<R>
+    where
+        F: FnOnce(&SecretKey) -> R,
+    {
+        let mut plain_text = PlainText::zero(self.cipher_text.len());
+        ctr(KeySize::KeySize128, key.0.as_bytes(), &self.iv)
+            .process(&self.cipher_text, plain_text.as_mut_bytes());
+
+        let secret_key = SecretKey::from_bytes(plain_text.as_bytes()).ok()?;
+
+        Some(func(&secret_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::Keypair;
+
+    #[test]
+    fn seal_and_unseal() {
+        let keypair = Keypair::random();
+        let key = EphemeralKey::random();
+
+        let sealed = SealedSecretKey::seal(&keypair.sk, &key);
+        assert_ne!(sealed.cipher_text, keypair.sk.as_bytes().as_bytes());
+
+        // Sealing the same key twice does not produce the same cipher text.
+        let resealed = SealedSecretKey::seal(&keypair.sk, &key);
+        assert_ne!(sealed.cipher_text, resealed.cipher_text);
+
+        let unsealed = sealed
+            .with_secret_key(&key, |sk| sk.as_bytes())
+            .expect("should unseal with the same key");
+        assert!(unsealed == keypair.sk.as_bytes());
+    }
+}
@@ -0,0 +1,180 @@
+//! Creates a backup of the slashing protection database whenever the validator client starts with
+//! a different version to the previous run, so that a failed upgrade (e.g., a database migration
+//! gone wrong) never leaves the user without a recoverable record of their signed messages.
+
+use crate::config::SLASHING_PROTECTION_FILENAME;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The file in the datadir which records the version of the validator client that last used it.
+pub const VERSION_FILENAME: &str = "validator_client_version";
+/// The directory within the datadir where backups of the slashing protection database are kept.
+pub const BACKUP_DIR: &str = "slashing_protection_backups";
+/// The default number of backups to retain.
+pub const DEFAULT_BACKUP_RETENTION: usize = 5;
+
+/// The version of the validator client which is presently running.
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Backs up the slashing protection database in `data_dir` if the version recorded in the datadir
+/// differs from `current_version`, then records `current_version` in the datadir.
+///
+/// At most `retention` backups are kept, with the oldest being deleted first. No backup is made if
+/// `retention` is zero or there is no slashing protection database.
+///
+/// Returns the path to the new backup, if one was made.
+pub fn backup_on_version_change(
+    data_dir: &Path,
+    current_version: &str,
+    retention: usize,
+) -> Result<Option<PathBuf>, String> {
+    let version_path = data_dir.join(VERSION_FILENAME);
+    let previous_version = if version_path.exists() {
+        let version = fs::read_to_string(&version_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", version_path, e))?;
+        Some(version.trim().to_string())
+    } else {
+        None
+    };
+
+    if previous_version.as_ref().map(String::as_str) == Some(current_version) {
+        return Ok(None);
+    }
+
+    let db_path = data_dir.join(SLASHING_PROTECTION_FILENAME);
+    let backup_path = if retention > 0 && db_path.exists() {
+        let backup_dir = data_dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", backup_dir, e))?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs();
+        let backup_path = backup_dir.join(format!(
+            "slashing_protection_{:020}_v{}.sqlite",
+            timestamp,
+            previous_version.as_ref().map_or("unknown", String::as_str)
+        ));
+
+        // Copying preserves the restrictive permissions of the database file.
+        fs::copy(&db_path, &backup_path).map_err(|e| {
+            format!(
+                "Unable to backup slashing protection database to {:?}: {:?}",
+                backup_path, e
+            )
+        })?;
+
+        prune_backups(&backup_dir, retention)?;
+
+        Some(backup_path)
+    } else {
+        None
+    };
+
+    fs::write(&version_path, current_version)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", version_path, e))?;
+
+    Ok(backup_path)
+}
+
+/// Deletes the oldest backups in `backup_dir` until at most `retention` remain.
+fn prune_backups(backup_dir: &Path, retention: usize) -> Result<(), String> {
+    let mut backups = fs::read_dir(backup_dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", backup_dir, e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with("slashing_protection_"))
+        })
+        .collect::<Vec<_>>();
+
+    // The zero-padded timestamp in the file name means lexicographic order is chronological.
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(retention);
+    for path in backups.into_iter().take(excess) {
+        fs::remove_file(&path).map_err(|e| format!("Unable to delete {:?}: {:?}", path, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempdir::TempDir;
+
+    fn num_backups(data_dir: &Path) -> usize {
+        fs::read_dir(data_dir.join(BACKUP_DIR))
+            .map(|dir| dir.count())
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn backs_up_on_version_change() {
+        let dir = TempDir::new("slashing_protection_backup").unwrap();
+        let data_dir = dir.path();
+        let db_path = data_dir.join(SLASHING_PROTECTION_FILENAME);
+
+        // A fresh datadir has nothing to backup.
+        assert_eq!(backup_on_version_change(data_dir, "0.1.0", 2), Ok(None));
+
+        fs::write(&db_path, b"signed messages").unwrap();
+
+        // The same version does not trigger a backup.
+        assert_eq!(backup_on_version_change(data_dir, "0.1.0", 2), Ok(None));
+        assert_eq!(num_backups(data_dir), 0);
+
+        // A new version triggers a backup of the database.
+        let backup = backup_on_version_change(data_dir, "0.1.1", 2)
+            .unwrap()
+            .expect("should backup");
+        assert_eq!(fs::read(&backup).unwrap(), b"signed messages");
+        assert!(backup.to_str().unwrap().ends_with("_v0.1.0.sqlite"));
+        assert_eq!(
+            fs::read_to_string(data_dir.join(VERSION_FILENAME)).unwrap(),
+            "0.1.1"
+        );
+    }
+
+    #[test]
+    fn prunes_old_backups() {
+        let dir = TempDir::new("slashing_protection_backup").unwrap();
+        let data_dir = dir.path();
+        fs::write(data_dir.join(SLASHING_PROTECTION_FILENAME), b"db").unwrap();
+
+        let backup_dir = data_dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir).unwrap();
+        for i in 0..3 {
+            fs::write(
+                backup_dir.join(format!("slashing_protection_{:020}_v0.0.{}.sqlite", i, i)),
+                b"old",
+            )
+            .unwrap();
+        }
+
+        backup_on_version_change(data_dir, "0.1.0", 2)
+            .unwrap()
+            .expect("should backup");
+        assert_eq!(num_backups(data_dir), 2);
+        assert!(!backup_dir
+            .join(format!("slashing_protection_{:020}_v0.0.0.sqlite", 0))
+            .exists());
+    }
+
+    #[test]
+    fn zero_retention_disables_backups() {
+        let dir = TempDir::new("slashing_protection_backup").unwrap();
+        let data_dir = dir.path();
+        fs::write(data_dir.join(SLASHING_PROTECTION_FILENAME), b"db").unwrap();
+
+        assert_eq!(backup_on_version_change(data_dir, "0.1.0", 0), Ok(None));
+        assert_eq!(num_backups(data_dir), 0);
+    }
+}
@@ -1,23 +1,29 @@
 use crate::config::SLASHING_PROTECTION_FILENAME;
+use crate::randao_cache::{RandaoCache, RANDAO_CACHE_FILENAME};
+use crate::sealed_key::{EphemeralKey, SealedSecretKey};
 use crate::{config::Config, fork_service::ForkService};
 use parking_lot::RwLock;
 use slashing_protection::{NotSafe, Safe, SlashingDatabase};
 use slog::{crit, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
-use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tempdir::TempDir;
 use types::{
-    Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork, Hash256, Keypair, PublicKey,
-    SelectionProof, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
+    Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork, Hash256, PublicKey,
+    SecretKey, SelectionProof, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot,
+    Slot,
 };
-use validator_dir::{Manager as ValidatorManager, ValidatorDir};
+use validator_dir::{Manager as ValidatorManager, ValidatorDir, SIGNING_DISABLED_FILE};
 
 struct LocalValidator {
     validator_dir: ValidatorDir,
-    voting_keypair: Keypair,
+    voting_pubkey: PublicKey,
+    /// The voting secret key, sealed with `ValidatorStore::sealing_key`.
+    voting_secret_key: SealedSecretKey,
+    /// If `true`, no messages will be signed with `self.voting_secret_key`.
+    signing_disabled: bool,
 }
 
 /// We derive our own `PartialEq` to avoid doing equality checks between secret keys.
@@ -35,15 +41,18 @@ struct LocalValidator {
 /// It seems reasonable to make these two assumptions in order to avoid the equality checks.
 impl PartialEq for LocalValidator {
     fn eq(&self, other: &Self) -> bool {
-        self.validator_dir == other.validator_dir
-            && self.voting_keypair.pk == other.voting_keypair.pk
+        self.validator_dir == other.validator_dir && self.voting_pubkey == other.voting_pubkey
     }
 }
 
 #[derive(Clone)]
 pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<HashMap<PublicKey, LocalValidator>>>,
+    /// The key with which the secret keys in `self.validators` are sealed, unique to this process.
+    sealing_key: Arc<EphemeralKey>,
     slashing_protection: SlashingDatabase,
+    /// The RANDAO reveals already signed for recent epochs.
+    randao_cache: Arc<RandaoCache>,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
@@ -69,24 +78,71 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 )
             })?;
 
-        let validator_key_values = ValidatorManager::open(&config.data_dir)
-            .map_err(|e| format!("unable to read data_dir: {:?}", e))?
-            .decrypt_all_validators(config.secrets_dir.clone(), Some(&log))
-            .map_err(|e| format!("unable to decrypt all validator directories: {:?}", e))?
-            .into_iter()
-            .map(|(kp, dir)| {
-                (
+        let randao_cache_path = config.data_dir.join(RANDAO_CACHE_FILENAME);
+        let randao_cache = RandaoCache::open(randao_cache_path.clone(), genesis_validators_root)
+            .unwrap_or_else(|e| {
+                warn!(
+                    log,
+                    "Ignoring RANDAO reveal cache";
+                    "error" => e,
+                );
+                RandaoCache::new(randao_cache_path, genesis_validators_root)
+            });
+
+        let sealing_key = EphemeralKey::random();
+
+        // The validators of every directory are merged into a single view. A validator which is in
+        // more than one directory is an error, rather than one of its directories being ignored.
+        let mut validators: HashMap<PublicKey, LocalValidator> = HashMap::new();
+        for validators_dir in config.validators_dirs() {
+            let key_values = ValidatorManager::open(validators_dir)
+                .map_err(|e| format!("unable to read {:?}: {:?}", validators_dir, e))?
+                .decrypt_all_validators(config.secrets_dir.clone(), Some(&log))
+                .map_err(|e| {
+                    format!(
+                        "unable to decrypt all validator directories in {:?}: {:?}",
+                        validators_dir, e
+                    )
+                })?;
+
+            for (kp, dir) in key_values {
+                if let Some(existing) = validators.get(&kp.pk) {
+                    return Err(format!(
+                        "validator {} is in both {:?} and {:?}",
+                        kp.pk.as_hex_string(),
+                        existing.validator_dir.dir(),
+                        dir.dir()
+                    ));
+                }
+
+                let signing_disabled = dir.signing_disabled();
+                if signing_disabled {
+                    warn!(
+                        log,
+                        "Signing is disabled for validator";
+                        "info" => format!("delete {} to re-enable signing", SIGNING_DISABLED_FILE),
+                        "dir" => format!("{:?}", dir.dir()),
+                        "validator" => format!("{:?}", kp.pk),
+                    );
+                }
+
+                validators.insert(
                     kp.pk.clone(),
                     LocalValidator {
                         validator_dir: dir,
-                        voting_keypair: kp,
+                        voting_pubkey: kp.pk,
+                        voting_secret_key: SealedSecretKey::seal(&kp.sk, &sealing_key),
+                        signing_disabled,
                     },
-                )
-            });
+                );
+            }
+        }
 
         Ok(Self {
-            validators: Arc::new(RwLock::new(HashMap::from_iter(validator_key_values))),
+            validators: Arc::new(RwLock::new(validators)),
+            sealing_key: Arc::new(sealing_key),
             slashing_protection,
+            randao_cache: Arc::new(randao_cache),
             genesis_validators_root,
             spec: Arc::new(spec),
             log,
@@ -118,6 +174,87 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().len()
     }
 
+    /// Disables signing for `validator_pubkey` and persists the disabled state in its validator
+    /// directory, so that signing remains disabled after a restart.
+    ///
+    /// Signing is disabled in memory even if it cannot be persisted. Returns `Ok(true)` if signing
+    /// was previously enabled.
+    pub fn disable_signing(
+        &self,
+        validator_pubkey: &PublicKey,
+        reason: &str,
+    ) -> Result<bool, String> {
+        let mut validators = self.validators.write();
+        let validator = validators
+            .get_mut(validator_pubkey)
+            .ok_or_else(|| format!("Unknown validator {:?}", validator_pubkey))?;
+
+        if validator.signing_disabled {
+            return Ok(false);
+        }
+        validator.signing_disabled = true;
+
+        validator
+            .validator_dir
+            .disable_signing(reason)
+            .map_err(|e| format!("Unable to persist disabled signing: {:?}", e))?;
+
+        Ok(true)
+    }
+
+    /// Returns `true` if signing has been disabled for `validator_pubkey`.
+    pub fn is_signing_disabled(&self, validator_pubkey: &PublicKey) -> bool {
+        self.validators
+            .read()
+            .get(validator_pubkey)
+            .map_or(false, |validator| validator.signing_disabled)
+    }
+
+    /// Returns `true` if `validator_pubkey` is known and signing has not been disabled for it.
+    fn signing_enabled(&self, validator_pubkey: &PublicKey) -> bool {
+        let enabled = self
+            .validators
+            .read()
+            .get(validator_pubkey)
+            .map_or(false, |validator| !validator.signing_disabled);
+
+        if !enabled {
+            warn!(
+                self.log,
+                "Not signing for validator with signing disabled";
+                "public_key" => format!("{:?}", validator_pubkey)
+            );
+        }
+
+        enabled
+    }
+
+    /// Calls `func` with the unsealed voting secret key of `validator_pubkey`, if it is known.
+    ///
+    /// The decrypted copy of the secret key is dropped once `func` returns, see the `sealed_key`
+    /// module for the limits of this.
+    fn with_voting_secret_key<F, R>(&self, validator_pubkey: &PublicKey, func: F) -> Option<R>
+    where
+        F: FnOnce(&SecretKey) -> R,
+    {
+        let validators = self.validators.read();
+        let validator = validators.get(validator_pubkey)?;
+
+        let result = validator
+            .voting_secret_key
+            .with_secret_key(&self.sealing_key, func);
+
+        if result.is_none() {
+            error!(
+                self.log,
+                "Unable to unseal voting secret key";
+                "public_key" => format!("{:?}", validator_pubkey)
+            );
+        }
+
+        result
+    }
+
     fn fork(&self) -> Option<Fork> {
         if self.fork_service.fork().is_none() {
             error!(
@@ -128,23 +265,43 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.fork_service.fork()
     }
 
+    /// Returns the RANDAO reveal of `validator_pubkey` for `epoch`, re-using the reveal signed
+    /// previously (including before a restart) if there is one.
     pub fn randao_reveal(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<Signature> {
         // TODO: check this against the slot clock to make sure it's not an early reveal?
-        self.validators
-            .read()
-            .get(validator_pubkey)
-            .and_then(|local_validator| {
-                let voting_keypair = &local_validator.voting_keypair;
-                let domain = self.spec.get_domain(
-                    epoch,
-                    Domain::Randao,
-                    &self.fork()?,
-                    self.genesis_validators_root,
-                );
-                let message = epoch.signing_root(domain);
+        if !self.signing_enabled(validator_pubkey) {
+            return None;
+        }
+
+        if let Some(reveal) = self.randao_cache.get(validator_pubkey, epoch) {
+            return Some(reveal);
+        }
 
-                Some(Signature::new(message.as_bytes(), &voting_keypair.sk))
-            })
+        let domain = self.spec.get_domain(
+            epoch,
+            Domain::Randao,
+            &self.fork()?,
+            self.genesis_validators_root,
+        );
+        let message = epoch.signing_root(domain);
+
+        let reveal = self.with_voting_secret_key(validator_pubkey, |sk| {
+            Signature::new(message.as_bytes(), sk)
+        })?;
+
+        if let Err(e) = self
+            .randao_cache
+            .insert(validator_pubkey.clone(), epoch, reveal.clone())
+        {
+            warn!(
+                self.log,
+                "Unable to persist RANDAO reveal";
+                "error" => e,
+                "path" => format!("{:?}", self.randao_cache.path()),
+            );
+        }
+
+        Some(reveal)
     }
 
     pub fn sign_block(
@@ -164,6 +321,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return None;
         }
 
+        if !self.signing_enabled(validator_pubkey) {
+            return None;
+        }
+
         // Check for slashing conditions.
         let fork = self.fork()?;
         let domain = self.spec.get_domain(
@@ -181,18 +342,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         match slashing_status {
             // We can safely sign this block.
-            Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let validator = validators.get(validator_pubkey)?;
-                let voting_keypair = &validator.voting_keypair;
-
-                Some(block.sign(
-                    &voting_keypair.sk,
-                    &fork,
-                    self.genesis_validators_root,
-                    &self.spec,
-                ))
-            }
+            Ok(Safe::Valid) => self.with_voting_secret_key(validator_pubkey, |sk| {
+                block.sign(sk, &fork, self.genesis_validators_root, &self.spec)
+            }),
             Ok(Safe::SameData) => {
                 warn!(
                     self.log,
@@ -232,6 +384,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return None;
         }
 
+        if !self.signing_enabled(validator_pubkey) {
+            return None;
+        }
+
         // Checking for slashing conditions.
         let fork = self.fork()?;
 
@@ -250,26 +406,23 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         match slashing_status {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let validator = validators.get(validator_pubkey)?;
-                let voting_keypair = &validator.voting_keypair;
-
-                attestation
-                    .sign(
-                        &voting_keypair.sk,
+                self.with_voting_secret_key(validator_pubkey, |sk| {
+                    attestation.sign(
+                        sk,
                         validator_committee_position,
                         &fork,
                         self.genesis_validators_root,
                         &self.spec,
                     )
-                    .map_err(|e| {
-                        error!(
-                            self.log,
-                            "Error whilst signing attestation";
-                            "error" => format!("{:?}", e)
-                        )
-                    })
-                    .ok()?;
+                })?
+                .map_err(|e| {
+                    error!(
+                        self.log,
+                        "Error whilst signing attestation";
+                        "error" => format!("{:?}", e)
+                    )
+                })
+                .ok()?;
 
                 Some(())
             }
@@ -312,18 +465,23 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         aggregate: Attestation<E>,
         selection_proof: SelectionProof,
     ) -> Option<SignedAggregateAndProof<E>> {
-        let validators = self.validators.read();
-        let voting_keypair = &validators.get(validator_pubkey)?.voting_keypair;
+        if !self.signing_enabled(validator_pubkey) {
+            return None;
+        }
 
-        Some(SignedAggregateAndProof::from_aggregate(
-            validator_index,
-            aggregate,
-            Some(selection_proof),
-            &voting_keypair.sk,
-            &self.fork()?,
-            self.genesis_validators_root,
-            &self.spec,
-        ))
+        let fork = self.fork()?;
+
+        self.with_voting_secret_key(validator_pubkey, |sk| {
+            SignedAggregateAndProof::from_aggregate(
+                validator_index,
+                aggregate,
+                Some(selection_proof),
+                sk,
+                &fork,
+                self.genesis_validators_root,
+                &self.spec,
+            )
+        })
     }
 
     /// Produces a `SelectionProof` for the `slot`, signed by with corresponding secret key to
@@ -333,15 +491,127 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: &PublicKey,
         slot: Slot,
     ) -> Option<SelectionProof> {
-        let validators = self.validators.read();
-        let voting_keypair = &validators.get(validator_pubkey)?.voting_keypair;
+        if !self.signing_enabled(validator_pubkey) {
+            return None;
+        }
 
-        Some(SelectionProof::new::<E>(
-            slot,
-            &voting_keypair.sk,
-            &self.fork()?,
-            self.genesis_validators_root,
-            &self.spec,
-        ))
+        let fork = self.fork()?;
+
+        self.with_voting_secret_key(validator_pubkey, |sk| {
+            SelectionProof::new::<E>(slot, sk, &fork, self.genesis_validators_root, &self.spec)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fork_service::ForkServiceBuilder;
+    use environment::EnvironmentBuilder;
+    use remote_beacon_node::RemoteBeaconNode;
+    use slot_clock::TestingSlotClock;
+    use std::time::Duration;
+    use types::{test_utils::generate_deterministic_keypair, MinimalEthSpec};
+    use validator_dir::insecure_keys::build_deterministic_validator_dirs;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn signs_with_sealed_keys() {
+        let mut env = EnvironmentBuilder::minimal()
+            .null_logger()
+            .expect("should build null logger")
+            .single_thread_tokio_runtime()
+            .expect("should start tokio runtime")
+            .build()
+            .expect("should build environment");
+
+        let data_dir = TempDir::new("validator_store").expect("should create data dir");
+        let secrets_dir =
+            TempDir::new("validator_store_secrets").expect("should create secrets dir");
+        build_deterministic_validator_dirs(
+            data_dir.path().into(),
+            secrets_dir.path().into(),
+            &[0, 1],
+        )
+        .expect("should build validator dirs");
+
+        let config = Config {
+            data_dir: data_dir.path().into(),
+            secrets_dir: secrets_dir.path().into(),
+            ..Config::default()
+        };
+        let spec = E::default_spec();
+        let fork = Fork {
+            previous_version: spec.genesis_fork_version,
+            current_version: spec.genesis_fork_version,
+            epoch: Epoch::new(0),
+        };
+        let genesis_validators_root = Hash256::repeat_byte(42);
+
+        let context = env.core_context();
+        let fork_service = ForkServiceBuilder::new()
+            .fork(fork.clone())
+            .slot_clock(TestingSlotClock::new(
+                Slot::new(0),
+                Duration::from_secs(0),
+                Duration::from_secs(1),
+            ))
+            .beacon_node(
+                RemoteBeaconNode::new("http://localhost:5052".to_string())
+                    .expect("should create beacon node client"),
+            )
+            .runtime_context(context.clone())
+            .build()
+            .expect("should build fork service");
+
+        let store = ValidatorStore::<TestingSlotClock, E>::load_from_disk(
+            &config,
+            genesis_validators_root,
+            spec.clone(),
+            fork_service,
+            context.log,
+        )
+        .expect("should load validators");
+        store
+            .register_all_validators_for_slashing_protection()
+            .expect("should register validators");
+        assert_eq!(store.num_voting_validators(), 2);
+
+        for i in 0..2 {
+            let pubkey = &generate_deterministic_keypair(i).pk;
+            let epoch = Epoch::new(1);
+            let slot = epoch.start_slot(E::slots_per_epoch());
+
+            let reveal = store
+                .randao_reveal(pubkey, epoch)
+                .expect("should sign randao reveal");
+            let domain = spec.get_domain(epoch, Domain::Randao, &fork, genesis_validators_root);
+            assert!(
+                reveal.verify(epoch.signing_root(domain).as_bytes(), pubkey),
+                "randao reveal should be valid"
+            );
+
+            let block = BeaconBlock {
+                slot,
+                proposer_index: i as u64,
+                ..BeaconBlock::empty(&spec)
+            };
+            let signed_block = store
+                .sign_block(pubkey, block, slot)
+                .expect("should sign block");
+            assert!(
+                signed_block.verify_signature(None, pubkey, &fork, genesis_validators_root, &spec),
+                "block signature should be valid"
+            );
+
+            let selection_proof = store
+                .produce_selection_proof(pubkey, slot)
+                .expect("should sign selection proof");
+            assert!(
+                selection_proof.verify::<E>(slot, pubkey, &fork, genesis_validators_root, &spec),
+                "selection proof should be valid"
+            );
+        }
     }
 }